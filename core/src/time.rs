@@ -14,6 +14,28 @@ pub const MINUTES: u64 = SECONDS * 60;
 pub const HOURS: u64 = MINUTES * 60;
 pub const DAYS: u64 = HOURS * 24;
 
+/// Exponential backoff delay before the attempt `attempt` (starting from `1`):
+/// `delay * multiplier^(attempt-1)` capped at `max_delay`.  The delay is
+/// computed in `f64` and clamped before being converted to [`Duration`],
+/// so it does not overflow for any number of attempts.  A `multiplier`
+/// below `1.0` is treated as `1.0` (constant delay).
+pub fn backoff_delay(
+    delay: std::time::Duration,
+    multiplier: f64,
+    max_delay: std::time::Duration,
+    attempt: u32,
+) -> std::time::Duration {
+    let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+    let factor = multiplier.max(1.0).powi(exponent);
+    let secs = delay.as_secs_f64() * factor;
+    if secs.is_nan() {
+        // `0 * inf`: a zero delay remains zero
+        return std::time::Duration::ZERO;
+    }
+    let secs = secs.min(max_delay.as_secs_f64());
+    std::time::Duration::try_from_secs_f64(secs).unwrap_or(max_delay)
+}
+
 pub enum TimeFormat {
     Time24,
     Time12,
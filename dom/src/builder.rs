@@ -0,0 +1,206 @@
+//!
+//! Declarative DOM element builder
+//!
+//! [`ElementBuilder`] creates DOM elements with attributes, classes, text,
+//! child elements and event listeners.  Building an element produces a
+//! [`Component`] that retains the event listener callbacks; when the
+//! [`Component`] is dropped, listeners are removed from their elements
+//! and the underlying closures are released.
+//!
+//! ```ignore
+//! use workflow_dom::builder::ElementBuilder;
+//!
+//! let component = ElementBuilder::new("div")
+//!     .with_class("toolbar")
+//!     .with_child(
+//!         ElementBuilder::new("button")
+//!             .with_attribute("type", "button")
+//!             .with_text("Save")
+//!             .with_listener("click", move |_event| {
+//!                 log_info!("save clicked");
+//!             }),
+//!     )
+//!     .build()?;
+//!
+//! component.append_to(&body()?)?;
+//! ```
+//!
+
+use crate::result::Result;
+use crate::utils::*;
+use web_sys::Element;
+use workflow_wasm::callback::*;
+
+/// Callback receiving DOM events
+pub type EventCallback = Callback<CallbackClosureWithoutResult<web_sys::Event>>;
+
+enum Child {
+    Builder(ElementBuilder),
+    Element(Element),
+    Text(String),
+}
+
+/// Builder for DOM elements (see the [module](self) documentation)
+pub struct ElementBuilder {
+    tag: String,
+    attributes: Vec<(String, String)>,
+    classes: Vec<String>,
+    children: Vec<Child>,
+    listeners: Vec<(String, EventCallback)>,
+}
+
+impl ElementBuilder {
+    /// Create a builder for an element with the given tag name
+    pub fn new(tag: &str) -> Self {
+        ElementBuilder {
+            tag: tag.to_string(),
+            attributes: Vec::new(),
+            classes: Vec::new(),
+            children: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn with_id(self, id: &str) -> Self {
+        self.with_attribute("id", id)
+    }
+
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.classes.push(class.to_string());
+        self
+    }
+
+    /// Append a text node
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.children.push(Child::Text(text.to_string()));
+        self
+    }
+
+    /// Append a child element built by the supplied builder
+    pub fn with_child(mut self, child: ElementBuilder) -> Self {
+        self.children.push(Child::Builder(child));
+        self
+    }
+
+    /// Append an existing element
+    pub fn with_element(mut self, element: Element) -> Self {
+        self.children.push(Child::Element(element));
+        self
+    }
+
+    /// Register an event listener. The listener remains registered
+    /// while the resulting [`Component`] is alive.
+    pub fn with_listener<F>(mut self, event: &str, listener: F) -> Self
+    where
+        F: FnMut(web_sys::Event) + 'static,
+    {
+        self.listeners
+            .push((event.to_string(), Callback::create(listener)));
+        self
+    }
+
+    /// Create the element and its children
+    pub fn build(self) -> Result<Component> {
+        let mut listeners = Vec::new();
+        let callbacks = CallbackMap::new();
+        let element = self.build_element(&mut listeners, &callbacks)?;
+        Ok(Component {
+            element,
+            listeners,
+            callbacks,
+        })
+    }
+
+    fn build_element(
+        self,
+        listeners: &mut Vec<Listener>,
+        callbacks: &CallbackMap,
+    ) -> Result<Element> {
+        let document = document();
+        let element = document.create_element(&self.tag)?;
+        for (name, value) in self.attributes.iter() {
+            element.set_attribute(name, value)?;
+        }
+        if !self.classes.is_empty() {
+            element.set_class_name(&self.classes.join(" "));
+        }
+        for child in self.children {
+            match child {
+                Child::Builder(builder) => {
+                    let child = builder.build_element(listeners, callbacks)?;
+                    element.append_child(&child)?;
+                }
+                Child::Element(child) => {
+                    element.append_child(&child)?;
+                }
+                Child::Text(text) => {
+                    element.append_child(&document.create_text_node(&text))?;
+                }
+            }
+        }
+        for (event, callback) in self.listeners {
+            element.add_event_listener_with_callback(&event, callback.as_ref())?;
+            listeners.push(Listener {
+                element: element.clone(),
+                event,
+                id: callback.get_id(),
+            });
+            callbacks.retain(callback)?;
+        }
+        Ok(element)
+    }
+}
+
+struct Listener {
+    element: Element,
+    event: String,
+    id: CallbackId,
+}
+
+/// Element created by [`ElementBuilder`]. Event listeners registered
+/// by the builder are removed when the component is dropped.
+pub struct Component {
+    element: Element,
+    listeners: Vec<Listener>,
+    callbacks: CallbackMap,
+}
+
+impl Component {
+    /// The root element of the component
+    pub fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Append the component to the parent element
+    pub fn append_to(&self, parent: &Element) -> Result<()> {
+        parent.append_child(&self.element)?;
+        Ok(())
+    }
+
+    /// Remove the component from the DOM (listeners remain
+    /// registered until the component is dropped)
+    pub fn remove(&self) {
+        self.element.remove();
+    }
+}
+
+impl Drop for Component {
+    fn drop(&mut self) {
+        let callbacks = self.callbacks.inner();
+        for listener in self.listeners.iter() {
+            if let Some(callback) = callbacks.get(&listener.id) {
+                listener
+                    .element
+                    .remove_event_listener_with_callback(&listener.event, callback.get_fn())
+                    .ok();
+            }
+        }
+        drop(callbacks);
+        self.callbacks.clear();
+    }
+}
@@ -1,4 +1,28 @@
+//!
+//! Clipboard access using the async
+//! [Clipboard API](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard_API).
+//!
+//! The [`clipboard()`] function returns a [`Clipboard`] interface providing
+//! text and image access.  If the Clipboard API is not available (for example,
+//! in insecure contexts), text is written using the legacy
+//! `document.execCommand("copy")` fallback.  Reading the clipboard may require
+//! user permission, which can be checked using [`Clipboard::permission()`].
+//!
+//! ```ignore
+//! use workflow_dom::clipboard::clipboard;
+//!
+//! clipboard().write_text("hello").await?;
+//! let text = clipboard().read_text().await?;
+//! ```
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::utils::*;
+use js_sys::{Array, Object, Promise, Reflect};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, HtmlTextAreaElement};
 
 #[wasm_bindgen]
 extern "C" {
@@ -7,7 +31,157 @@ extern "C" {
     #[wasm_bindgen (js_namespace=["navigator", "clipboard"], js_name="read")]
     pub async fn read() -> JsValue;
     #[wasm_bindgen (catch, js_namespace=["navigator", "clipboard"], js_name="writeText")]
-    pub async fn write_text(text: &str) -> Result<(), JsValue>;
+    pub async fn write_text(text: &str) -> std::result::Result<(), JsValue>;
     #[wasm_bindgen (catch, js_namespace=["navigator", "clipboard"], js_name="write")]
-    pub async fn write(data: JsValue) -> Result<(), JsValue>;
+    pub async fn write(data: JsValue) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen (catch, js_namespace=["navigator", "clipboard"], js_name="readText")]
+    async fn try_read_text() -> std::result::Result<JsValue, JsValue>;
+    #[wasm_bindgen (catch, js_namespace=["navigator", "clipboard"], js_name="read")]
+    async fn try_read() -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = ClipboardItem)]
+    type ClipboardItem;
+    #[wasm_bindgen(catch, constructor, js_class = "ClipboardItem")]
+    fn new(items: &Object) -> std::result::Result<ClipboardItem, JsValue>;
+    #[wasm_bindgen(method, getter)]
+    fn types(this: &ClipboardItem) -> Array;
+    #[wasm_bindgen(method, js_name = getType)]
+    fn get_type(this: &ClipboardItem, mime: &str) -> Promise;
+
+    #[wasm_bindgen(catch, js_namespace=["navigator", "permissions"], js_name = query)]
+    fn query_permission(descriptor: &Object) -> std::result::Result<Promise, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = document, js_name = execCommand)]
+    fn exec_command(command: &str) -> std::result::Result<bool, JsValue>;
+}
+
+/// Clipboard access permission state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Granted,
+    Denied,
+    /// The user will be prompted when the clipboard is accessed
+    Prompt,
+    /// The permission can not be queried in this environment
+    Unknown,
+}
+
+/// Clipboard interface (see the [module](self) documentation)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clipboard;
+
+/// Obtain the [`Clipboard`] interface
+pub fn clipboard() -> Clipboard {
+    Clipboard
+}
+
+impl Clipboard {
+    /// Returns `true` if the async Clipboard API is available
+    pub fn is_supported(&self) -> bool {
+        Reflect::get(&window().navigator(), &"clipboard".into())
+            .map(|clipboard| !clipboard.is_undefined())
+            .unwrap_or(false)
+    }
+
+    /// Query clipboard permission; `write` selects the
+    /// `clipboard-write` permission instead of `clipboard-read`
+    pub async fn permission(&self, write: bool) -> Permission {
+        let name = if write {
+            "clipboard-write"
+        } else {
+            "clipboard-read"
+        };
+        let descriptor = Object::new();
+        Reflect::set(&descriptor, &"name".into(), &name.into()).ok();
+        // some browsers do not support clipboard permission names
+        let Ok(promise) = query_permission(&descriptor) else {
+            return Permission::Unknown;
+        };
+        let Ok(status) = JsFuture::from(promise).await else {
+            return Permission::Unknown;
+        };
+        let state = Reflect::get(&status, &"state".into())
+            .ok()
+            .and_then(|state| state.as_string());
+        match state.as_deref() {
+            Some("granted") => Permission::Granted,
+            Some("denied") => Permission::Denied,
+            Some("prompt") => Permission::Prompt,
+            _ => Permission::Unknown,
+        }
+    }
+
+    /// Read text from the clipboard
+    pub async fn read_text(&self) -> Result<String> {
+        if !self.is_supported() {
+            return Err(Error::String("Clipboard API is not available".into()));
+        }
+        try_read_text()
+            .await?
+            .as_string()
+            .ok_or_else(|| Error::String("Unable to read clipboard text".into()))
+    }
+
+    /// Write text to the clipboard, falling back to `document.execCommand("copy")`
+    /// if the Clipboard API is not available or fails
+    pub async fn write_text(&self, text: &str) -> Result<()> {
+        if self.is_supported() && write_text(text).await.is_ok() {
+            return Ok(());
+        }
+        write_text_with_exec_command(text)
+    }
+
+    /// Read an image from the clipboard, returns `None`
+    /// if the clipboard does not contain an image
+    pub async fn read_image(&self) -> Result<Option<Blob>> {
+        if !self.is_supported() {
+            return Err(Error::String("Clipboard API is not available".into()));
+        }
+        let items = try_read().await?;
+        for item in Array::from(&items).iter() {
+            let item = item.unchecked_into::<ClipboardItem>();
+            let mime = item
+                .types()
+                .iter()
+                .filter_map(|mime| mime.as_string())
+                .find(|mime| mime.starts_with("image/"));
+            if let Some(mime) = mime {
+                let blob = JsFuture::from(item.get_type(&mime)).await?;
+                return Ok(Some(blob.unchecked_into()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Write an image to the clipboard (browsers typically support only `image/png`)
+    pub async fn write_image(&self, image: &Blob) -> Result<()> {
+        if !self.is_supported() {
+            return Err(Error::String("Clipboard API is not available".into()));
+        }
+        let items = Object::new();
+        Reflect::set(&items, &image.type_().into(), image)?;
+        let item = ClipboardItem::new(&items)?;
+        write(Array::of1(&item).into()).await?;
+        Ok(())
+    }
+}
+
+fn write_text_with_exec_command(text: &str) -> Result<()> {
+    let body = body()?;
+    let textarea = document()
+        .create_element("textarea")?
+        .dyn_into::<HtmlTextAreaElement>()
+        .map_err(|_| "Unable to create textarea element")?;
+    textarea.set_value(text);
+    textarea.set_attribute("readonly", "")?;
+    textarea.set_attribute("style", "position:fixed;top:-1000px;opacity:0")?;
+    body.append_child(&textarea)?;
+    textarea.select();
+    let result = exec_command("copy");
+    textarea.remove();
+    match result {
+        Ok(true) => Ok(()),
+        _ => Err(Error::String("Unable to write clipboard text".into())),
+    }
 }
@@ -1,35 +1,61 @@
+//!
+//! File download helpers, allowing data produced in Rust
+//! to be saved by the user as a file.
+//!
+
 use crate::result::Result;
 use crate::utils::*;
 use js_sys::{Array, Uint8Array};
+use std::time::Duration;
 use web_sys::{Blob, MouseEvent, Url};
-
-pub fn data(filename: &str, content: &[u8], mime: &str) -> Result<()> {
+use workflow_core::task::{dispatch, sleep};
+
+/// Delay before the object URL is revoked, allowing
+/// the browser to start the download
+const REVOKE_DELAY: Duration = Duration::from_secs(1);
+
+/// Save `content` as a file named `filename` with the given `mime` type.
+/// Creates a [`Blob`] object URL, triggers the download and revokes
+/// the URL afterwards.
+///
+/// ```ignore
+/// let json = serde_json::to_vec(&store)?;
+/// workflow_dom::download::save_file("store.json", &json, "application/json")?;
+/// ```
+pub fn save_file(filename: &str, content: &[u8], mime: &str) -> Result<()> {
     let document = document();
     let body = body()?;
 
     let args = Array::new_with_length(1);
-    args.set(0, unsafe { Uint8Array::view(content).into() });
+    args.set(0, Uint8Array::from(content).into());
     let mut options = web_sys::BlobPropertyBag::new();
     options.type_(mime);
     let blob = Blob::new_with_u8_array_sequence_and_options(&args, &options)?;
     let url = Url::create_object_url_with_blob(&blob)?;
 
-    let el = document.create_element("a").unwrap();
-    el.set_attribute("href", filename).unwrap();
-    el.set_attribute("download", &url).unwrap();
-
-    body.append_child(&el).unwrap();
+    let el = document.create_element("a")?;
+    el.set_attribute("href", &url)?;
+    el.set_attribute("download", filename)?;
+    el.set_attribute("style", "display:none")?;
 
-    let event = MouseEvent::new("click").unwrap();
-    el.dispatch_event(&event).unwrap();
+    body.append_child(&el)?;
+    let event = MouseEvent::new("click")?;
+    el.dispatch_event(&event)?;
+    body.remove_child(&el)?;
 
-    body.remove_child(&el).unwrap();
-
-    Url::revoke_object_url(&url)?;
+    // revoking the URL immediately can cancel the download in some browsers
+    dispatch(async move {
+        sleep(REVOKE_DELAY).await;
+        Url::revoke_object_url(&url).ok();
+    });
 
     Ok(())
 }
 
+pub fn data(filename: &str, content: &[u8], mime: &str) -> Result<()> {
+    save_file(filename, content, mime)
+}
+
 pub fn text(filename: &str, content: &str) -> Result<()> {
-    data(filename, content.as_bytes(), "text/plain")
+    save_file(filename, content.as_bytes(), "text/plain")
 }
@@ -5,6 +5,7 @@ use thiserror::Error;
 use wasm_bindgen::JsValue;
 use workflow_core::channel::RecvError;
 // use workflow_core::sendable::Sendable;
+use workflow_wasm::callback::CallbackError;
 use workflow_wasm::jserror::*;
 
 /// Errors return by the [`workflow_dom`](super) module
@@ -20,6 +21,11 @@ pub enum Error {
     JsValue(JsErrorData),
     #[error("{0}")]
     RecvError(RecvError), //#[from] workflow_core::channel::RecvError),
+    #[error("{0}")]
+    Callback(CallbackError),
+    /// Failure loading a script or a stylesheet from the URL
+    #[error("Unable to load `{0}`")]
+    Load(String),
 }
 
 unsafe impl Send for Error {}
@@ -49,6 +55,12 @@ impl From<RecvError> for Error {
     }
 }
 
+impl From<CallbackError> for Error {
+    fn from(err: CallbackError) -> Self {
+        Self::Callback(err)
+    }
+}
+
 impl From<Error> for JsValue {
     fn from(err: Error) -> Self {
         JsValue::from_str(&err.to_string())
@@ -7,13 +7,29 @@
 //! JavaScript scripts, modules and CSS stylesheets directly within WASM
 //! binary.
 //!
+//! Scripts and stylesheets can also be loaded from URLs using [`load_script()`]
+//! and [`load_css()`].  These functions resolve once the resource is loaded
+//! (or fail with [`Error::Load`]); concurrent requests for the same URL
+//! are combined and resources that have already been loaded are not
+//! loaded again.
+//!
+//! ```ignore
+//! load_script("https://cdn.example.com/xterm.js", &LoadOptions::default()
+//!     .with_integrity("sha384-...")
+//!     .with_crossorigin("anonymous")).await?;
+//! ```
+//!
 
+use crate::error::Error;
 use crate::result::*;
 use crate::utils::*;
 use js_sys::{Array, Function, Uint8Array};
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
 use web_sys::Element;
 use web_sys::{Blob, Url};
 use workflow_core::channel::oneshot;
+use workflow_core::lookup::{LookupHandler, RequestType};
 use workflow_wasm::callback::*;
 
 pub type CustomEventCallback = Callback<CallbackClosureWithoutResult<web_sys::CustomEvent>>;
@@ -158,15 +174,7 @@ pub fn inject_blob_with_callback<C>(content: Content, callback: Option<&C>) -> R
 where
     C: AsRef<Function>,
 {
-    let doc = document();
-    let root = {
-        let collection = doc.get_elements_by_tag_name("head");
-        if collection.length() > 0 {
-            collection.item(0).unwrap()
-        } else {
-            doc.get_elements_by_tag_name("body").item(0).unwrap()
-        }
-    };
+    let root = root();
 
     match content {
         Content::Script(id, content) => {
@@ -182,3 +190,154 @@ where
 
     Ok(())
 }
+
+/// Document `head` element (or `body` if `head` is not present)
+fn root() -> Element {
+    let doc = document();
+    let collection = doc.get_elements_by_tag_name("head");
+    if collection.length() > 0 {
+        collection.item(0).unwrap()
+    } else {
+        doc.get_elements_by_tag_name("body").item(0).unwrap()
+    }
+}
+
+/// Options for [`load_script()`] and [`load_css()`]
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Load the script as an ES module
+    pub module: bool,
+    /// Subresource integrity hash (`integrity` attribute)
+    pub integrity: Option<String>,
+    /// CORS mode (`crossorigin` attribute), e.g. `"anonymous"`
+    pub crossorigin: Option<String>,
+    /// Id of the created element
+    pub id: Option<String>,
+}
+
+impl LoadOptions {
+    pub fn with_module(mut self, module: bool) -> Self {
+        self.module = module;
+        self
+    }
+
+    pub fn with_integrity(mut self, integrity: &str) -> Self {
+        self.integrity = Some(integrity.to_string());
+        self
+    }
+
+    pub fn with_crossorigin(mut self, crossorigin: &str) -> Self {
+        self.crossorigin = Some(crossorigin.to_string());
+        self
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    fn apply(&self, element: &Element) -> Result<()> {
+        if let Some(integrity) = self.integrity.as_ref() {
+            element.set_attribute("integrity", integrity)?;
+        }
+        if let Some(crossorigin) = self.crossorigin.as_ref() {
+            element.set_attribute("crossorigin", crossorigin)?;
+        }
+        if let Some(id) = self.id.as_ref() {
+            element.set_attribute("id", id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct UrlLoader {
+    lookup: LookupHandler<String, (), Error>,
+    loaded: Mutex<HashSet<String>>,
+}
+
+fn url_loader() -> &'static UrlLoader {
+    static LOADER: OnceLock<UrlLoader> = OnceLock::new();
+    LOADER.get_or_init(UrlLoader::default)
+}
+
+/// Load a script from `url` as a `<script>` element, resolving
+/// when the script is loaded. See the [module](self) documentation.
+pub async fn load_script(url: &str, options: &LoadOptions) -> Result<()> {
+    load_url(url, || {
+        let script = document().create_element("script")?;
+        let content_type = if options.module {
+            "module"
+        } else {
+            "text/javascript"
+        };
+        script.set_attribute("type", content_type)?;
+        options.apply(&script)?;
+        script.set_attribute("src", url)?;
+        Ok(script)
+    })
+    .await
+}
+
+/// Load a stylesheet from `url` as a `<link>` element, resolving
+/// when the stylesheet is loaded. See the [module](self) documentation.
+pub async fn load_css(url: &str, options: &LoadOptions) -> Result<()> {
+    load_url(url, || {
+        let link = document().create_element("link")?;
+        link.set_attribute("rel", "stylesheet")?;
+        link.set_attribute("type", "text/css")?;
+        options.apply(&link)?;
+        link.set_attribute("href", url)?;
+        Ok(link)
+    })
+    .await
+}
+
+async fn load_url<F>(url: &str, create: F) -> Result<()>
+where
+    F: FnOnce() -> Result<Element>,
+{
+    let loader = url_loader();
+    let key = url.to_string();
+    if loader.loaded.lock().unwrap().contains(&key) {
+        return Ok(());
+    }
+
+    match loader.lookup.queue(&key).await {
+        RequestType::New(receiver) => {
+            let result = match create() {
+                Ok(element) => load_element(url, element).await,
+                Err(err) => Err(err),
+            };
+            if result.is_ok() {
+                loader.loaded.lock().unwrap().insert(key.clone());
+            }
+            loader.lookup.complete(&key, result).await;
+            receiver.recv().await?
+        }
+        RequestType::Pending(receiver) => receiver.recv().await?,
+    }
+}
+
+/// Append the element to the document and wait for the `load` or `error` event
+async fn load_element(url: &str, element: Element) -> Result<()> {
+    let (sender, receiver) = oneshot();
+    let callback = callback!(move |event: web_sys::Event| {
+        sender.try_send(event.type_() == "load").ok();
+    });
+    element.add_event_listener_with_callback("load", callback.as_ref())?;
+    element.add_event_listener_with_callback("error", callback.as_ref())?;
+    root().append_child(&element)?;
+
+    let loaded = receiver.recv().await?;
+    element.remove_event_listener_with_callback("load", callback.as_ref())?;
+    element.remove_event_listener_with_callback("error", callback.as_ref())?;
+
+    if loaded {
+        Ok(())
+    } else {
+        // remove the element so that the load can be retried
+        element.remove();
+        Err(Error::Load(url.to_string()))
+    }
+}
@@ -23,11 +23,17 @@
 //! inject_blob(Content::Script(None, data)).await?;
 //! ```
 
+pub mod builder;
 pub mod clipboard;
 pub mod download;
 pub mod error;
 pub mod inject;
 pub mod link;
 pub mod loader;
+pub mod observer;
+pub mod render;
 pub mod result;
+pub mod upload;
 pub mod utils;
+
+pub use download::save_file;
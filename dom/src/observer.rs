@@ -0,0 +1,369 @@
+//!
+//! DOM observers exposed as async streams
+//!
+//! - [`MutationObserver`] wraps the browser
+//!   [`MutationObserver`](https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver)
+//!   API, yielding [`MutationRecord`] values.
+//! - [`ResizeObserver`] wraps the
+//!   [`ResizeObserver`](https://developer.mozilla.org/en-US/docs/Web/API/ResizeObserver)
+//!   API, yielding [`ResizeObserverEntry`] values when observed elements change size.
+//! - [`IntersectionObserver`] wraps the
+//!   [`IntersectionObserver`](https://developer.mozilla.org/en-US/docs/Web/API/IntersectionObserver)
+//!   API, yielding [`IntersectionObserverEntry`] values when observed elements
+//!   enter or leave the viewport (or the root element).
+//!
+//! Observers implement [`Stream`]; resize and intersection observers can
+//! alternatively be created with a callback closure.  Observers are
+//! disconnected when dropped.
+//!
+//! ```ignore
+//! use workflow_dom::observer::{MutationFilter, MutationObserver, ResizeObserver};
+//!
+//! let filter = MutationFilter::default().with_child_list(true).with_subtree(true);
+//! let mut observer = MutationObserver::try_new(&element, &filter)?;
+//! while let Some(record) = observer.next().await {
+//!     log_info!("mutation: {}", record.type_());
+//! }
+//!
+//! let _observer = ResizeObserver::try_new_with_callback(&canvas, move |entry| {
+//!     let rect = entry.content_rect();
+//!     resize_canvas(rect.width(), rect.height());
+//! })?;
+//! ```
+//!
+
+use crate::result::Result;
+use futures::Stream;
+use js_sys::Array;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Element, IntersectionObserverEntry, IntersectionObserverInit, MutationObserverInit,
+    MutationRecord, Node, ResizeObserverEntry,
+};
+use workflow_core::channel::{unbounded, Receiver};
+use workflow_wasm::callback::*;
+
+/// Configuration of mutations reported by the [`MutationObserver`]
+#[derive(Debug, Clone, Default)]
+pub struct MutationFilter {
+    pub child_list: bool,
+    pub attributes: bool,
+    pub character_data: bool,
+    pub subtree: bool,
+    pub attribute_old_value: bool,
+    pub character_data_old_value: bool,
+    /// Report changes only of the listed attributes
+    pub attribute_filter: Option<Vec<String>>,
+}
+
+impl MutationFilter {
+    pub fn with_child_list(mut self, child_list: bool) -> Self {
+        self.child_list = child_list;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: bool) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn with_character_data(mut self, character_data: bool) -> Self {
+        self.character_data = character_data;
+        self
+    }
+
+    pub fn with_subtree(mut self, subtree: bool) -> Self {
+        self.subtree = subtree;
+        self
+    }
+
+    pub fn with_attribute_old_value(mut self, attribute_old_value: bool) -> Self {
+        self.attribute_old_value = attribute_old_value;
+        self
+    }
+
+    pub fn with_character_data_old_value(mut self, character_data_old_value: bool) -> Self {
+        self.character_data_old_value = character_data_old_value;
+        self
+    }
+
+    pub fn with_attribute_filter(mut self, attributes: &[&str]) -> Self {
+        self.attribute_filter = Some(attributes.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    fn init(&self) -> MutationObserverInit {
+        let mut init = MutationObserverInit::new();
+        init.child_list(self.child_list)
+            .attributes(self.attributes)
+            .character_data(self.character_data)
+            .subtree(self.subtree);
+        // old values can be requested only if the corresponding mutations are observed
+        if self.attribute_old_value {
+            init.attribute_old_value(true);
+        }
+        if self.character_data_old_value {
+            init.character_data_old_value(true);
+        }
+        if let Some(filter) = self.attribute_filter.as_ref() {
+            let filter = filter.iter().map(JsValue::from).collect::<Array>();
+            init.attribute_filter(&filter);
+        }
+        init
+    }
+}
+
+/// Observer of DOM mutations, yielding [`MutationRecord`] values
+/// as a [`Stream`]. Disconnects the observer when dropped.
+pub struct MutationObserver {
+    observer: web_sys::MutationObserver,
+    receiver: Pin<Box<Receiver<MutationRecord>>>,
+    _callback: Callback<CallbackClosureWithoutResult<Array>>,
+}
+
+impl MutationObserver {
+    /// Observe mutations of the `target` node matching the `filter`
+    pub fn try_new(target: &Node, filter: &MutationFilter) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let callback = Callback::create(move |records: Array| {
+            for record in records.iter() {
+                sender.try_send(record.unchecked_into()).ok();
+            }
+        });
+        let observer = web_sys::MutationObserver::new(callback.as_ref())?;
+        observer.observe_with_options(target, &filter.init())?;
+
+        Ok(MutationObserver {
+            observer,
+            receiver: Box::pin(receiver),
+            _callback: callback,
+        })
+    }
+
+    /// Receiver of mutation records (an alternative to polling the observer as a [`Stream`])
+    pub fn receiver(&self) -> &Receiver<MutationRecord> {
+        &self.receiver
+    }
+
+    /// Retrieve and clear mutation records that have not been delivered yet
+    pub fn take_records(&self) -> Vec<MutationRecord> {
+        self.observer
+            .take_records()
+            .iter()
+            .map(|record| record.unchecked_into())
+            .collect()
+    }
+
+    pub fn disconnect(&self) {
+        self.observer.disconnect();
+    }
+}
+
+impl Stream for MutationObserver {
+    type Item = MutationRecord;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.as_mut().poll_next(cx)
+    }
+}
+
+impl Drop for MutationObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Observer of element size changes, yielding [`ResizeObserverEntry`] values
+/// as a [`Stream`] (unless created with a callback). Disconnects the observer
+/// when dropped.
+pub struct ResizeObserver {
+    observer: web_sys::ResizeObserver,
+    receiver: Option<Pin<Box<Receiver<ResizeObserverEntry>>>>,
+    _callback: Callback<CallbackClosureWithoutResult<Array>>,
+}
+
+impl ResizeObserver {
+    /// Observe size changes of the `target` element
+    pub fn try_new(target: &Element) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let mut observer = Self::try_new_with_callback(target, move |entry| {
+            sender.try_send(entry).ok();
+        })?;
+        observer.receiver = Some(Box::pin(receiver));
+        Ok(observer)
+    }
+
+    /// Observe size changes of the `target` element, invoking `callback` for each change
+    pub fn try_new_with_callback<F>(target: &Element, mut callback: F) -> Result<Self>
+    where
+        F: FnMut(ResizeObserverEntry) + 'static,
+    {
+        let callback = Callback::create(move |entries: Array| {
+            for entry in entries.iter() {
+                callback(entry.unchecked_into());
+            }
+        });
+        let observer = web_sys::ResizeObserver::new(callback.as_ref())?;
+        observer.observe(target);
+
+        Ok(ResizeObserver {
+            observer,
+            receiver: None,
+            _callback: callback,
+        })
+    }
+
+    /// Observe an additional element
+    pub fn observe(&self, target: &Element) {
+        self.observer.observe(target);
+    }
+
+    pub fn unobserve(&self, target: &Element) {
+        self.observer.unobserve(target);
+    }
+
+    pub fn disconnect(&self) {
+        self.observer.disconnect();
+    }
+}
+
+impl Stream for ResizeObserver {
+    type Item = ResizeObserverEntry;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.receiver.as_mut() {
+            Some(receiver) => receiver.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for ResizeObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Configuration of the [`IntersectionObserver`]
+#[derive(Debug, Clone, Default)]
+pub struct IntersectionOptions {
+    /// Element used as the viewport (the browser viewport if `None`)
+    pub root: Option<Element>,
+    /// Margin around the root (CSS margin syntax, e.g. `"0px 0px 200px 0px"`)
+    pub root_margin: Option<String>,
+    /// Intersection ratios at which entries are reported
+    pub thresholds: Vec<f64>,
+}
+
+impl IntersectionOptions {
+    pub fn with_root(mut self, root: Element) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn with_root_margin(mut self, root_margin: &str) -> Self {
+        self.root_margin = Some(root_margin.to_string());
+        self
+    }
+
+    pub fn with_thresholds(mut self, thresholds: &[f64]) -> Self {
+        self.thresholds = thresholds.to_vec();
+        self
+    }
+
+    fn init(&self) -> IntersectionObserverInit {
+        let mut init = IntersectionObserverInit::new();
+        init.root(self.root.as_ref());
+        if let Some(root_margin) = self.root_margin.as_ref() {
+            init.root_margin(root_margin);
+        }
+        if !self.thresholds.is_empty() {
+            let thresholds = self
+                .thresholds
+                .iter()
+                .map(|threshold| JsValue::from_f64(*threshold))
+                .collect::<Array>();
+            init.threshold(&thresholds);
+        }
+        init
+    }
+}
+
+/// Observer of element visibility, yielding [`IntersectionObserverEntry`]
+/// values as a [`Stream`] (unless created with a callback). Disconnects the
+/// observer when dropped.
+pub struct IntersectionObserver {
+    observer: web_sys::IntersectionObserver,
+    receiver: Option<Pin<Box<Receiver<IntersectionObserverEntry>>>>,
+    _callback: Callback<CallbackClosureWithoutResult<Array>>,
+}
+
+impl IntersectionObserver {
+    /// Observe intersection of the `target` element with the viewport
+    pub fn try_new(target: &Element, options: &IntersectionOptions) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let mut observer = Self::try_new_with_callback(target, options, move |entry| {
+            sender.try_send(entry).ok();
+        })?;
+        observer.receiver = Some(Box::pin(receiver));
+        Ok(observer)
+    }
+
+    /// Observe intersection of the `target` element with the viewport,
+    /// invoking `callback` for each change
+    pub fn try_new_with_callback<F>(
+        target: &Element,
+        options: &IntersectionOptions,
+        mut callback: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(IntersectionObserverEntry) + 'static,
+    {
+        let callback = Callback::create(move |entries: Array| {
+            for entry in entries.iter() {
+                callback(entry.unchecked_into());
+            }
+        });
+        let observer =
+            web_sys::IntersectionObserver::new_with_options(callback.as_ref(), &options.init())?;
+        observer.observe(target);
+
+        Ok(IntersectionObserver {
+            observer,
+            receiver: None,
+            _callback: callback,
+        })
+    }
+
+    /// Observe an additional element
+    pub fn observe(&self, target: &Element) {
+        self.observer.observe(target);
+    }
+
+    pub fn unobserve(&self, target: &Element) {
+        self.observer.unobserve(target);
+    }
+
+    pub fn disconnect(&self) {
+        self.observer.disconnect();
+    }
+}
+
+impl Stream for IntersectionObserver {
+    type Item = IntersectionObserverEntry;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.receiver.as_mut() {
+            Some(receiver) => receiver.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for IntersectionObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
@@ -0,0 +1,178 @@
+//!
+//! Render loop driven by `requestAnimationFrame()`
+//!
+//! [`RenderLoop`] invokes a callback on each animation frame, passing the
+//! time elapsed since the previously rendered frame.  The frame rate can be
+//! limited using [`RenderLoop::with_target_fps()`].  The loop is paused
+//! while the document is hidden (e.g. when the browser tab is in background)
+//! and resumed when the document becomes visible, in which case the first
+//! frame receives a zero delta.
+//!
+//! The loop stops when [`RenderLoop::stop()`] is called or when all
+//! [`RenderLoop`] handles are dropped.
+//!
+//! ```ignore
+//! let render_loop = RenderLoop::new(move |delta| {
+//!     scene.update(delta);
+//!     scene.render();
+//! })
+//! .with_target_fps(30.0);
+//! render_loop.start()?;
+//! ```
+//!
+
+use crate::result::Result;
+use crate::utils::*;
+use futures::StreamExt;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use workflow_log::log_error;
+use workflow_wasm::callback::*;
+use workflow_wasm::frame::animation_frames;
+
+type VisibilityCallback = Callback<CallbackClosureWithoutResult<web_sys::Event>>;
+
+struct Inner {
+    callback: RefCell<Box<dyn FnMut(Duration)>>,
+    target_fps: Cell<Option<f64>>,
+    running: Cell<bool>,
+    // incremented to terminate the active frame loop
+    generation: Cell<u64>,
+    visibility: RefCell<Option<VisibilityCallback>>,
+}
+
+impl Inner {
+    fn remove_visibility_listener(&self) {
+        if let Some(callback) = self.visibility.borrow_mut().take() {
+            document()
+                .remove_event_listener_with_callback("visibilitychange", callback.as_ref())
+                .ok();
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.remove_visibility_listener();
+    }
+}
+
+/// Render loop (see the [module](self) documentation)
+#[derive(Clone)]
+pub struct RenderLoop {
+    inner: Rc<Inner>,
+}
+
+impl RenderLoop {
+    /// Create a render loop invoking `callback` with the time
+    /// elapsed since the previous frame
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: FnMut(Duration) + 'static,
+    {
+        RenderLoop {
+            inner: Rc::new(Inner {
+                callback: RefCell::new(Box::new(callback)),
+                target_fps: Cell::new(None),
+                running: Cell::new(false),
+                generation: Cell::new(0),
+                visibility: RefCell::new(None),
+            }),
+        }
+    }
+
+    /// Limit the frame rate (by default, the callback is
+    /// invoked at the display refresh rate)
+    pub fn with_target_fps(self, fps: f64) -> Self {
+        self.set_target_fps(Some(fps));
+        self
+    }
+
+    pub fn set_target_fps(&self, fps: Option<f64>) {
+        self.inner.target_fps.set(fps.filter(|fps| *fps > 0.0));
+    }
+
+    pub fn target_fps(&self) -> Option<f64> {
+        self.inner.target_fps.get()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.running.get()
+    }
+
+    /// Start the render loop
+    pub fn start(&self) -> Result<()> {
+        if self.inner.running.replace(true) {
+            return Ok(());
+        }
+
+        let inner = Rc::downgrade(&self.inner);
+        let callback = Callback::create(move |_event: web_sys::Event| {
+            if let Some(inner) = inner.upgrade() {
+                if document().hidden() {
+                    pause(&inner);
+                } else if inner.running.get() {
+                    resume(&inner);
+                }
+            }
+        });
+        document().add_event_listener_with_callback("visibilitychange", callback.as_ref())?;
+        *self.inner.visibility.borrow_mut() = Some(callback);
+
+        if !document().hidden() {
+            resume(&self.inner);
+        }
+        Ok(())
+    }
+
+    /// Stop the render loop
+    pub fn stop(&self) {
+        if self.inner.running.replace(false) {
+            pause(&self.inner);
+            self.inner.remove_visibility_listener();
+        }
+    }
+}
+
+fn pause(inner: &Rc<Inner>) {
+    inner.generation.set(inner.generation.get() + 1);
+}
+
+fn resume(inner: &Rc<Inner>) {
+    let generation = inner.generation.get() + 1;
+    inner.generation.set(generation);
+    wasm_bindgen_futures::spawn_local(run(Rc::downgrade(inner), generation));
+}
+
+async fn run(inner: Weak<Inner>, generation: u64) {
+    let mut frames = animation_frames();
+    let mut last: Option<f64> = None;
+    while let Some(frame) = frames.next().await {
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+        if inner.generation.get() != generation {
+            break;
+        }
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) => {
+                log_error!("RenderLoop: {err}");
+                break;
+            }
+        };
+
+        let elapsed = last.map(|last| frame.timestamp - last).unwrap_or_default();
+        if let (Some(fps), Some(_)) = (inner.target_fps.get(), last) {
+            // frames are not delivered exactly at the refresh interval,
+            // allow a small tolerance to avoid skipping frames at the target rate
+            if elapsed < 1000.0 / fps - 1.0 {
+                continue;
+            }
+        }
+        last = Some(frame.timestamp);
+
+        (inner.callback.borrow_mut())(Duration::from_secs_f64(elapsed.max(0.0) / 1000.0));
+    }
+}
@@ -0,0 +1,248 @@
+//!
+//! File open and drag-and-drop helpers, allowing application code
+//! to read the contents of files selected by the user.
+//!
+//! - [`open_files()`] displays the browser file picker and resolves with the selected files.
+//! - [`DropZone`] attaches drag-and-drop handling to an element, yielding
+//!   dropped files as a [`Stream`].  Listeners are removed when dropped.
+//!
+//! File contents are read using [`FileData::bytes()`], [`FileData::text()`],
+//! [`FileData::bytes_with_progress()`] or as a stream of chunks using
+//! [`FileData::chunks()`].
+//!
+//! ```ignore
+//! use workflow_dom::upload::open_files;
+//!
+//! for file in open_files(Some(".json"), false).await? {
+//!     let data = file.bytes_with_progress(|loaded, total| {
+//!         log_info!("{} - {loaded}/{total}", file.name());
+//!     }).await?;
+//! }
+//! ```
+//!
+
+use crate::result::Result;
+use crate::utils::*;
+use futures::{stream, Stream};
+use js_sys::Uint8Array;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DragEvent, Element, File, FileList, HtmlInputElement};
+use workflow_core::channel::{oneshot, unbounded, Receiver};
+use workflow_wasm::callback::*;
+
+/// Default size of chunks used when reading files with progress
+pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// File selected by the user
+#[derive(Clone)]
+pub struct FileData {
+    file: File,
+}
+
+impl FileData {
+    pub fn name(&self) -> String {
+        self.file.name()
+    }
+
+    /// MIME type of the file (empty if unknown)
+    pub fn mime(&self) -> String {
+        self.file.type_()
+    }
+
+    pub fn size(&self) -> usize {
+        self.file.size() as usize
+    }
+
+    /// Access to the underlying [`web_sys::File`]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Read the entire file contents
+    pub async fn bytes(&self) -> Result<Vec<u8>> {
+        let buffer = JsFuture::from(self.file.array_buffer()).await?;
+        Ok(Uint8Array::new(&buffer).to_vec())
+    }
+
+    /// Read the file contents as UTF-8 text
+    pub async fn text(&self) -> Result<String> {
+        let text = JsFuture::from(self.file.text()).await?;
+        text.as_string()
+            .ok_or_else(|| "Unable to read file as text".into())
+    }
+
+    /// Read the file contents in chunks of `chunk_size` bytes
+    pub fn chunks(&self, chunk_size: usize) -> impl Stream<Item = Result<Vec<u8>>> {
+        let file = self.file.clone();
+        let size = self.size();
+        let chunk_size = chunk_size.max(1);
+        stream::unfold(0, move |offset| {
+            let file = file.clone();
+            async move {
+                if offset >= size {
+                    return None;
+                }
+                let end = (offset + chunk_size).min(size);
+                Some((read_range(&file, offset, end).await, end))
+            }
+        })
+    }
+
+    /// Read the entire file contents, invoking `progress` with the number
+    /// of bytes read and the file size after each chunk
+    pub async fn bytes_with_progress<F>(&self, mut progress: F) -> Result<Vec<u8>>
+    where
+        F: FnMut(usize, usize),
+    {
+        let size = self.size();
+        let mut data = Vec::with_capacity(size);
+        let mut chunks = Box::pin(self.chunks(DEFAULT_CHUNK_SIZE));
+        while let Some(chunk) = futures::StreamExt::next(&mut chunks).await {
+            data.extend_from_slice(&chunk?);
+            progress(data.len(), size);
+        }
+        Ok(data)
+    }
+}
+
+impl From<File> for FileData {
+    fn from(file: File) -> Self {
+        FileData { file }
+    }
+}
+
+async fn read_range(file: &File, start: usize, end: usize) -> Result<Vec<u8>> {
+    let blob = file.slice_with_f64_and_f64(start as f64, end as f64)?;
+    let buffer = JsFuture::from(blob.array_buffer()).await?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+fn file_list(files: Option<FileList>) -> Vec<FileData> {
+    files
+        .map(|files| {
+            (0..files.length())
+                .filter_map(|idx| files.item(idx))
+                .map(FileData::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Display the file picker and return the selected files. `accept` specifies
+/// accepted file types (e.g. `".json,.txt"` or `"image/*"`). Returns an empty
+/// list if the user cancels the selection.
+pub async fn open_files(accept: Option<&str>, multiple: bool) -> Result<Vec<FileData>> {
+    let input = document()
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()
+        .map_err(|_| "Unable to create input element")?;
+    input.set_type("file");
+    input.set_multiple(multiple);
+    if let Some(accept) = accept {
+        input.set_accept(accept);
+    }
+
+    let (sender, receiver) = oneshot();
+    let callback: Callback<CallbackClosureWithoutResult<web_sys::Event>> =
+        Callback::create(move |_event: web_sys::Event| {
+            sender.try_send(()).ok();
+        });
+    // `cancel` is dispatched by browsers supporting it when the picker is dismissed
+    for event in ["change", "cancel"] {
+        input.add_event_listener_with_callback(event, callback.as_ref())?;
+    }
+
+    input.click();
+    receiver.recv().await?;
+
+    for event in ["change", "cancel"] {
+        input.remove_event_listener_with_callback(event, callback.as_ref())?;
+    }
+
+    Ok(file_list(input.files()))
+}
+
+type DragEventCallback = Callback<CallbackClosureWithoutResult<DragEvent>>;
+
+/// Drag-and-drop target yielding dropped files as a [`Stream`].
+/// Event listeners are removed when the drop zone is dropped.
+pub struct DropZone {
+    element: Element,
+    receiver: Pin<Box<Receiver<Vec<FileData>>>>,
+    callbacks: Vec<(&'static str, DragEventCallback)>,
+}
+
+impl DropZone {
+    /// Accept files dropped onto the `element`. If supplied, `hover_class`
+    /// is added to the element while files are dragged over it.
+    pub fn try_new(element: &Element, hover_class: Option<&str>) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+
+        let hover = |enable: bool| {
+            let element = element.clone();
+            let hover_class = hover_class.map(String::from);
+            move |event: DragEvent| {
+                // prevent the browser from opening the file
+                event.prevent_default();
+                if let Some(hover_class) = hover_class.as_ref() {
+                    if enable {
+                        element.class_list().add_1(hover_class).ok();
+                    } else {
+                        element.class_list().remove_1(hover_class).ok();
+                    }
+                }
+            }
+        };
+
+        let on_leave = hover(false);
+        let on_drop = move |event: DragEvent| {
+            on_leave(event.clone());
+            let files = file_list(event.data_transfer().and_then(|dt| dt.files()));
+            if !files.is_empty() {
+                sender.try_send(files).ok();
+            }
+        };
+
+        let callbacks = vec![
+            ("dragenter", Callback::create(hover(true))),
+            ("dragover", Callback::create(hover(true))),
+            ("dragleave", Callback::create(hover(false))),
+            ("drop", Callback::create(on_drop)),
+        ];
+        for (event, callback) in callbacks.iter() {
+            element.add_event_listener_with_callback(event, callback.as_ref())?;
+        }
+
+        Ok(DropZone {
+            element: element.clone(),
+            receiver: Box::pin(receiver),
+            callbacks,
+        })
+    }
+
+    /// Receiver of dropped files (an alternative to polling the drop zone as a [`Stream`])
+    pub fn receiver(&self) -> &Receiver<Vec<FileData>> {
+        &self.receiver
+    }
+}
+
+impl Stream for DropZone {
+    type Item = Vec<FileData>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.as_mut().poll_next(cx)
+    }
+}
+
+impl Drop for DropZone {
+    fn drop(&mut self) {
+        for (event, callback) in self.callbacks.iter() {
+            self.element
+                .remove_event_listener_with_callback(event, callback.as_ref())
+                .ok();
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//!
+//! XChaCha20-Poly1305 authenticated encryption.
+//!
+//! Encrypted data is prefixed with a header authenticated together with
+//! the ciphertext (all integers are little-endian):
+//!
+//! ```text
+//! magic       4 bytes     "WFEC"
+//! version     1 byte      1
+//! kdf         1 byte      0 (key) or 1 (Argon2id password)
+//! memory      4 bytes     Argon2id memory size (KiB)   -- kdf 1 only
+//! iterations  4 bytes     Argon2id iterations          -- kdf 1 only
+//! parallelism 4 bytes     Argon2id parallelism         -- kdf 1 only
+//! salt        16 bytes    Argon2id salt                -- kdf 1 only
+//! nonce       24 bytes    XChaCha20 nonce
+//! ```
+//!
+//! followed by the ciphertext and the 16 byte Poly1305 tag.
+//!
+
+use crate::error::Error;
+use crate::kdf::{KdfLimits, KdfParams, KEY_LENGTH, SALT_LENGTH};
+use crate::result::Result;
+use crate::secret::Secret;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+const MAGIC: &[u8; 4] = b"WFEC";
+const VERSION: u8 = 1;
+const NONCE_LENGTH: usize = 24;
+const KDF_KEY: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+
+/// Returns `true` if `data` starts with the encrypted data header magic
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypt `data` using a [`KEY_LENGTH`] byte `key`
+pub fn encrypt(data: &[u8], key: &Secret) -> Result<Vec<u8>> {
+    let header = Header::new(None)?;
+    seal(&header, data, key)
+}
+
+/// Decrypt `data` encrypted using [`encrypt()`]
+pub fn decrypt(data: &[u8], key: &Secret) -> Result<Secret> {
+    let (header, ciphertext) = Header::parse(data)?;
+    if header.kdf.is_some() {
+        return Err(Error::PasswordRequired);
+    }
+    open(
+        &header,
+        &data[..data.len() - ciphertext.len()],
+        ciphertext,
+        key,
+    )
+}
+
+/// Encrypt `data` using a key derived from `password` with a random salt
+pub fn encrypt_with_password(
+    data: &[u8],
+    password: &Secret,
+    params: &KdfParams,
+) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LENGTH];
+    getrandom::getrandom(&mut salt)?;
+    let header = Header::new(Some((*params, salt)))?;
+    let key = params.derive_key(password.as_bytes(), &salt)?;
+    seal(&header, data, &key)
+}
+
+/// Decrypt `data` encrypted using [`encrypt_with_password()`]; the key
+/// derivation parameters are read from the header and must not exceed
+/// the default [`KdfLimits`].
+pub fn decrypt_with_password(data: &[u8], password: &Secret) -> Result<Secret> {
+    decrypt_with_password_and_limits(data, password, &KdfLimits::default())
+}
+
+/// Decrypt `data` encrypted using [`encrypt_with_password()`], failing with
+/// [`Error::InvalidHeader`] (before deriving the key) if the key derivation
+/// parameters read from the header exceed `limits`.
+pub fn decrypt_with_password_and_limits(
+    data: &[u8],
+    password: &Secret,
+    limits: &KdfLimits,
+) -> Result<Secret> {
+    let (header, ciphertext) = Header::parse(data)?;
+    let Some((params, salt)) = header.kdf.as_ref() else {
+        return Err(Error::KeyRequired);
+    };
+    params.validate(limits)?;
+    let key = params.derive_key(password.as_bytes(), salt)?;
+    open(
+        &header,
+        &data[..data.len() - ciphertext.len()],
+        ciphertext,
+        &key,
+    )
+}
+
+fn cipher(key: &Secret) -> Result<XChaCha20Poly1305> {
+    XChaCha20Poly1305::new_from_slice(key.as_bytes())
+        .map_err(|_| Error::KeyLength(key.len(), KEY_LENGTH))
+}
+
+fn seal(header: &Header, data: &[u8], key: &Secret) -> Result<Vec<u8>> {
+    let mut out = header.to_bytes();
+    let payload = Payload {
+        msg: data,
+        aad: &out,
+    };
+    let ciphertext = cipher(key)?
+        .encrypt(XNonce::from_slice(&header.nonce), payload)
+        .map_err(|_| Error::Encrypt)?;
+    out.extend(ciphertext);
+    Ok(out)
+}
+
+fn open(header: &Header, aad: &[u8], ciphertext: &[u8], key: &Secret) -> Result<Secret> {
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    cipher(key)?
+        .decrypt(XNonce::from_slice(&header.nonce), payload)
+        .map(Secret::new)
+        .map_err(|_| Error::Decrypt)
+}
+
+struct Header {
+    kdf: Option<(KdfParams, [u8; SALT_LENGTH])>,
+    nonce: [u8; NONCE_LENGTH],
+}
+
+impl Header {
+    fn new(kdf: Option<(KdfParams, [u8; SALT_LENGTH])>) -> Result<Self> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        getrandom::getrandom(&mut nonce)?;
+        Ok(Header { kdf, nonce })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 2 + 12 + SALT_LENGTH + NONCE_LENGTH);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        match self.kdf.as_ref() {
+            Some((params, salt)) => {
+                bytes.push(KDF_ARGON2ID);
+                bytes.extend_from_slice(&params.memory.to_le_bytes());
+                bytes.extend_from_slice(&params.iterations.to_le_bytes());
+                bytes.extend_from_slice(&params.parallelism.to_le_bytes());
+                bytes.extend_from_slice(salt);
+            }
+            None => bytes.push(KDF_KEY),
+        }
+        bytes.extend_from_slice(&self.nonce);
+        bytes
+    }
+
+    /// Parse the header, returns the header and the remaining data
+    fn parse(data: &[u8]) -> Result<(Self, &[u8])> {
+        let mut reader = Reader(data);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::InvalidHeader);
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let kdf = match reader.take(1)?[0] {
+            KDF_KEY => None,
+            KDF_ARGON2ID => {
+                let params = KdfParams::new(reader.u32()?, reader.u32()?, reader.u32()?);
+                let salt = reader.take(SALT_LENGTH)?.try_into().unwrap();
+                Some((params, salt))
+            }
+            _ => return Err(Error::InvalidHeader),
+        };
+        let nonce = reader.take(NONCE_LENGTH)?.try_into().unwrap();
+        Ok((Header { kdf, nonce }, reader.0))
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::InvalidHeader);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> KdfParams {
+        KdfParams::default().with_memory(256).with_iterations(1)
+    }
+
+    #[test]
+    fn password() {
+        let password = Secret::from("password");
+        let encrypted = encrypt_with_password(b"secret data", &password, &params()).unwrap();
+        assert!(is_encrypted(&encrypted));
+        let decrypted = decrypt_with_password(&encrypted, &password).unwrap();
+        assert_eq!(decrypted.as_bytes(), b"secret data");
+
+        let wrong = decrypt_with_password(&encrypted, &Secret::from("wrong"));
+        assert!(matches!(wrong, Err(Error::Decrypt)));
+
+        // header is authenticated
+        let mut tampered = encrypted.clone();
+        tampered[6] ^= 1;
+        assert!(decrypt_with_password(&tampered, &password).is_err());
+        assert!(matches!(
+            decrypt(&encrypted, &Secret::new(vec![0; KEY_LENGTH])),
+            Err(Error::PasswordRequired)
+        ));
+    }
+
+    #[test]
+    fn key() {
+        let key = params()
+            .derive_key(b"password", b"0123456789abcdef")
+            .unwrap();
+        assert_eq!(key.len(), KEY_LENGTH);
+        let encrypted = encrypt(b"", &key).unwrap();
+        assert_eq!(decrypt(&encrypted, &key).unwrap().as_bytes(), b"");
+        assert_ne!(
+            encrypt(b"data", &key).unwrap(),
+            encrypt(b"data", &key).unwrap()
+        );
+
+        assert!(matches!(
+            encrypt(b"data", &Secret::from("short")),
+            Err(Error::KeyLength(5, KEY_LENGTH))
+        ));
+        assert!(matches!(
+            decrypt(&encrypted[..10], &key),
+            Err(Error::InvalidHeader)
+        ));
+        let mut version = encrypted.clone();
+        version[4] = 2;
+        assert!(matches!(
+            decrypt(&version, &key),
+            Err(Error::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn kdf_limits() {
+        let password = Secret::from("password");
+        let encrypted = encrypt_with_password(b"secret data", &password, &params()).unwrap();
+
+        // tampered parameters are rejected before deriving the key
+        let mut tampered = encrypted.clone();
+        tampered[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            decrypt_with_password(&tampered, &password),
+            Err(Error::InvalidHeader)
+        ));
+        let mut tampered = encrypted.clone();
+        tampered[10..14].copy_from_slice(&0u32.to_le_bytes());
+        assert!(matches!(
+            decrypt_with_password(&tampered, &password),
+            Err(Error::InvalidHeader)
+        ));
+
+        let limits = KdfLimits::default().with_max_memory(128);
+        assert!(matches!(
+            decrypt_with_password_and_limits(&encrypted, &password, &limits),
+            Err(Error::InvalidHeader)
+        ));
+        let limits = KdfLimits::new(256, 1, 1);
+        let decrypted = decrypt_with_password_and_limits(&encrypted, &password, &limits).unwrap();
+        assert_eq!(decrypted.as_bytes(), b"secret data");
+    }
+}
@@ -0,0 +1,56 @@
+//!
+//!  Errors produced by this crate.
+//!
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Custom(String),
+
+    #[error("Key derivation error: {0}")]
+    Kdf(String),
+
+    #[error("Random number generator error: {0}")]
+    Random(String),
+
+    #[error("Invalid key length {0} (expected {1} bytes)")]
+    KeyLength(usize, usize),
+
+    #[error("Encryption failure")]
+    Encrypt,
+
+    #[error("Decryption failure (invalid password, key or corrupted data)")]
+    Decrypt,
+
+    #[error("Invalid encrypted data header")]
+    InvalidHeader,
+
+    #[error("Unsupported encrypted data version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Data is encrypted using a password")]
+    PasswordRequired,
+
+    #[error("Data is encrypted using a key")]
+    KeyRequired,
+}
+
+impl From<argon2::Error> for Error {
+    fn from(err: argon2::Error) -> Self {
+        Error::Kdf(err.to_string())
+    }
+}
+
+impl From<getrandom::Error> for Error {
+    fn from(err: getrandom::Error) -> Self {
+        Error::Random(err.to_string())
+    }
+}
+
+impl From<String> for Error {
+    fn from(error: String) -> Error {
+        Error::Custom(error)
+    }
+}
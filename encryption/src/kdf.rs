@@ -0,0 +1,146 @@
+//!
+//! Argon2id password-based key derivation.
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::secret::Secret;
+use argon2::{Algorithm, Argon2, Params, Version};
+
+/// Length of the derived key in bytes
+pub const KEY_LENGTH: usize = 32;
+/// Length of the salt generated for password-based encryption
+pub const SALT_LENGTH: usize = 16;
+
+/// Argon2id parameters.  The default parameters (19 MiB of memory,
+/// 2 iterations, 1 lane) follow the OWASP recommendations; parameters
+/// can be increased for data that is rarely decrypted (e.g. wallets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory size in KiB
+    pub memory: u32,
+    /// Number of iterations
+    pub iterations: u32,
+    /// Degree of parallelism (lanes)
+    pub parallelism: u32,
+}
+
+/// Maximum [`KdfParams`] accepted when decrypting data, preventing
+/// the parameters stored in the (untrusted) header of the encrypted
+/// data from exhausting the memory or the CPU.  The defaults allow
+/// 1 GiB of memory, 64 iterations and 16 lanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfLimits {
+    /// Maximum memory size in KiB
+    pub max_memory: u32,
+    /// Maximum number of iterations
+    pub max_iterations: u32,
+    /// Maximum degree of parallelism (lanes)
+    pub max_parallelism: u32,
+}
+
+impl Default for KdfLimits {
+    fn default() -> Self {
+        KdfLimits {
+            max_memory: 1024 * 1024,
+            max_iterations: 64,
+            max_parallelism: 16,
+        }
+    }
+}
+
+impl KdfLimits {
+    pub fn new(max_memory: u32, max_iterations: u32, max_parallelism: u32) -> Self {
+        KdfLimits {
+            max_memory,
+            max_iterations,
+            max_parallelism,
+        }
+    }
+
+    /// Maximum memory size in KiB
+    pub fn with_max_memory(mut self, max_memory: u32) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn with_max_parallelism(mut self, max_parallelism: u32) -> Self {
+        self.max_parallelism = max_parallelism;
+        self
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            memory: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    pub fn new(memory: u32, iterations: u32, parallelism: u32) -> Self {
+        KdfParams {
+            memory,
+            iterations,
+            parallelism,
+        }
+    }
+
+    /// Memory size in KiB
+    pub fn with_memory(mut self, memory: u32) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_parallelism(mut self, parallelism: u32) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Check the parameters against `limits`, fails with
+    /// [`Error::InvalidHeader`] if any of the parameters is
+    /// zero or exceeds the limits.
+    pub fn validate(&self, limits: &KdfLimits) -> Result<()> {
+        if self.memory == 0
+            || self.iterations == 0
+            || self.parallelism == 0
+            || self.memory > limits.max_memory
+            || self.iterations > limits.max_iterations
+            || self.parallelism > limits.max_parallelism
+        {
+            Err(Error::InvalidHeader)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Derive a [`KEY_LENGTH`] key from `password` and `salt`
+    pub fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<Secret> {
+        let params = Params::new(
+            self.memory,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )?;
+        let mut key = Secret::new(vec![0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params).hash_password_into(
+            password,
+            salt,
+            key.as_mut(),
+        )?;
+        Ok(key)
+    }
+}
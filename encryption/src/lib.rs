@@ -0,0 +1,41 @@
+//!
+//! [<img alt="github" src="https://img.shields.io/badge/github-workflow--rs-8da0cb?style=for-the-badge&labelColor=555555&color=8da0cb&logo=github" height="20">](https://github.com/workflow-rs/workflow-rs)
+//! [<img alt="crates.io" src="https://img.shields.io/crates/v/workflow-encryption.svg?maxAge=2592000&style=for-the-badge&color=fc8d62&logo=rust" height="20">](https://crates.io/crates/workflow-encryption)
+//! [<img alt="docs.rs" src="https://img.shields.io/badge/docs.rs-workflow--encryption-56c2a5?maxAge=2592000&style=for-the-badge&logo=docs.rs" height="20">](https://docs.rs/workflow-encryption)
+//! <img alt="license" src="https://img.shields.io/crates/l/workflow-encryption.svg?maxAge=2592000&color=6ac&style=for-the-badge&logoColor=fff" height="20">
+//! <img src="https://img.shields.io/badge/platform- native -informational?style=for-the-badge&color=50a0f0" height="20">
+//! <img src="https://img.shields.io/badge/platform- wasm32/browser -informational?style=for-the-badge&color=50a0f0" height="20">
+//! <img src="https://img.shields.io/badge/platform- wasm32/node.js -informational?style=for-the-badge&color=50a0f0" height="20">
+//!
+//! This crate provides password-based key derivation using Argon2id
+//! ([`KdfParams`]) and authenticated encryption of binary data using
+//! XChaCha20-Poly1305 ([`encrypt()`], [`encrypt_with_password()`]).
+//! Encrypted data is prefixed with a versioned header containing the
+//! key derivation parameters, the salt and the nonce, allowing data to be
+//! decrypted using only the password or the key.  Passwords, keys and
+//! decrypted data are held in a [`Secret`] buffer that is zeroized on drop.
+//!
+//! ```ignore
+//! use workflow_encryption::*;
+//!
+//! let password = Secret::from("correct horse battery staple");
+//! let encrypted = encrypt_with_password(b"mnemonic", &password, &KdfParams::default())?;
+//! let decrypted = decrypt_with_password(&encrypted, &password)?;
+//! assert_eq!(decrypted.as_bytes(), b"mnemonic");
+//! ```
+//!
+
+pub mod cipher;
+pub mod error;
+pub mod kdf;
+pub mod result;
+pub mod secret;
+
+pub use cipher::{
+    decrypt, decrypt_with_password, decrypt_with_password_and_limits, encrypt,
+    encrypt_with_password, is_encrypted,
+};
+pub use error::Error;
+pub use kdf::{KdfLimits, KdfParams};
+pub use result::Result;
+pub use secret::Secret;
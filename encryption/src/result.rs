@@ -0,0 +1,2 @@
+//! [`Result`] type alias
+pub type Result<T> = std::result::Result<T, crate::error::Error>;
@@ -0,0 +1,105 @@
+//!
+//! [`Secret`] buffer zeroized on drop.
+//!
+
+use std::fmt;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// Buffer holding sensitive data (passwords, keys or decrypted data).
+/// The buffer is zeroized when dropped and is not revealed by [`Debug`].
+/// Buffers are compared in constant time (for buffers of equal length).
+#[derive(Clone, Default)]
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    pub fn new(data: Vec<u8>) -> Self {
+        Secret(data)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Interpret the buffer as a UTF-8 string
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for Secret {}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(*** {} bytes)", self.0.len())
+    }
+}
+
+impl AsRef<[u8]> for Secret {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for Secret {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(data: Vec<u8>) -> Self {
+        Secret(data)
+    }
+}
+
+impl From<&[u8]> for Secret {
+    fn from(data: &[u8]) -> Self {
+        Secret(data.to_vec())
+    }
+}
+
+impl From<String> for Secret {
+    fn from(text: String) -> Self {
+        Secret(text.into_bytes())
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(text: &str) -> Self {
+        Secret(text.as_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eq() {
+        assert_eq!(Secret::from("secret"), Secret::from("secret"));
+        assert_ne!(Secret::from("secret"), Secret::from("secreT"));
+        assert_ne!(Secret::from("secret"), Secret::from("secret!"));
+        assert_eq!(Secret::default(), Secret::new(vec![]));
+    }
+}
@@ -8,6 +8,11 @@ pub mod dom {
     pub use workflow_dom::*;
 }
 
+#[cfg(feature = "encryption")]
+pub mod encryption {
+    pub use workflow_encryption::*;
+}
+
 #[cfg(feature = "html")]
 pub mod html {
     pub use workflow_html::*;
@@ -1,10 +1,12 @@
-use proc_macro2::{Ident, Literal, Span, TokenStream};
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
+use proc_macro_error::abort;
 use quote::{quote, ToTokens};
 use rand::Rng;
 use syn::{
     ext::IdentExt,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
+    spanned::Spanned,
     Block, Result, Token,
 };
 //use std::sync::Arc;
@@ -50,6 +52,15 @@ impl<'a> Attributes<'a> {
         //let mut used = vec![];
         let mut append: bool;
         for attr in &self.list {
+            if attr.get_event_handler().is_some() {
+                abort!(
+                    attr.name.span(),
+                    format!(
+                        "Event handler '{}' is not supported by Rust-based elements",
+                        attr.get_name()
+                    )
+                );
+            }
             append = true;
             let name_str = &attr.name.to_string();
             let name = &attr.name.to_property_name();
@@ -118,17 +129,20 @@ impl<'a> Attributes<'a> {
         let mut ref_field = quote!(reff: None);
         for attr in &self.list {
             let name = attr.get_name();
+            if let Some((event, handler)) = attr.get_event_handler() {
+                events.push(quote!(
+                    .on_event(#event, #handler)
+                ));
+                continue;
+            }
             let value = attr.get_value();
             let mut append = true;
             let value = match attr.attr_type {
                 AttributeType::Bool => {
                     quote! {workflow_html::AttributeValue::Bool(#value)}
                 }
-                AttributeType::Str => {
-                    quote! {workflow_html::AttributeValue::Str(String::from(#value))}
-                }
-                AttributeType::String => {
-                    quote! {workflow_html::AttributeValue::Str(String::from(#value))}
+                AttributeType::Str | AttributeType::String => {
+                    quote! {workflow_html::IntoAttributeValue::into_attribute_value(#value)}
                 }
                 AttributeType::Ref => {
                     ref_field = quote! {reff: Some((String::from(#name), String::from(#value)))};
@@ -169,6 +183,7 @@ impl<'a> Attributes<'a> {
 
 pub enum AttributeValue<'a> {
     Block(Block),
+    Closure(syn::ExprClosure),
     Literal(Literal),
     Path(syn::punctuated::Punctuated<syn::PathSegment, Token!(::)>),
     _Str(&'a str),
@@ -204,6 +219,28 @@ impl<'a> Attribute<'a> {
         items.fold(first, |a, b| format!("{a}-{b}"))
     }
 
+    /// Returns the event name and the handler closure if the attribute
+    /// is an `on<event>` attribute with a closure value (`onclick=|e| {...}`
+    /// or `onclick={|e| ...}`)
+    pub fn get_event_handler(&self) -> Option<(String, TokenStream)> {
+        let closure = match &self.value {
+            Some(AttributeValue::Closure(closure)) => closure.to_token_stream(),
+            Some(AttributeValue::Block(block)) => match block.stmts.as_slice() {
+                [syn::Stmt::Expr(expr @ syn::Expr::Closure(_))] => expr.to_token_stream(),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let name = self.get_name();
+        match name.strip_prefix("on") {
+            Some(event) if !event.is_empty() => Some((event.to_string(), closure)),
+            _ => abort!(
+                self.name.span(),
+                format!("Closure can only be bound to 'on<event>' attributes, found '{name}'")
+            ),
+        }
+    }
+
     pub fn get_value(&self) -> TokenStream {
         match &self.value {
             Some(value) => match value {
@@ -214,6 +251,7 @@ impl<'a> Attribute<'a> {
                         (&v.stmts[0]).into_token_stream()
                     }
                 }
+                AttributeValue::Closure(v) => v.to_token_stream(),
                 AttributeValue::Literal(v) => quote!(#v).into_token_stream(),
                 AttributeValue::_Str(v) => quote!(#v).into_token_stream(),
                 AttributeValue::Path(v) => quote!(#v).into_token_stream(),
@@ -252,7 +290,9 @@ impl<'a> Parse for Attribute<'a> {
             input.parse::<Token![=]>()?;
             let value;
             let parser = Punctuated::<syn::PathSegment, Token![::]>::parse_separated_nonempty;
-            if input.peek(syn::token::Brace) {
+            if input.peek(Token![|]) || input.peek(Token![||]) || input.peek(Token![move]) {
+                value = AttributeValue::Closure(parse_closure(input)?);
+            } else if input.peek(syn::token::Brace) {
                 value = AttributeValue::Block(input.parse::<Block>()?);
             } else if input.peek(syn::Lit) {
                 //value = AttributeValue::Str("");
@@ -267,6 +307,29 @@ impl<'a> Parse for Attribute<'a> {
     }
 }
 
+/// Parse an inline closure attribute value. The closure body must be
+/// enclosed in braces, as an unbraced expression would consume the
+/// closing `>` of the tag (e.g. `onclick=|e| { ... }`).
+fn parse_closure(input: ParseStream) -> Result<syn::ExprClosure> {
+    let span = input.span();
+    let tokens = input.step(|cursor| {
+        let mut tokens = TokenStream::new();
+        let mut rest = *cursor;
+        while let Some((tt, next)) = rest.token_tree() {
+            let is_body =
+                matches!(&tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+            tokens.extend([tt]);
+            rest = next;
+            if is_body {
+                return Ok((tokens, rest));
+            }
+        }
+        Err(cursor.error("Closure body must be enclosed in braces"))
+    })?;
+    syn::parse2::<syn::ExprClosure>(tokens)
+        .map_err(|_| syn::Error::new(span, "Invalid event handler closure"))
+}
+
 pub fn parse_attributes<'a>(input: ParseStream) -> Result<Attributes<'a>> {
     let mut list = vec![];
     //print!("parse_attributes: {:?}", input);
@@ -125,6 +125,7 @@ impl<'a> ToTokens for Element<'a> {
                     is_fragment:#is_fragment,
                     tag:String::from(#tag),
                     onclick:std::sync::Arc::new(std::sync::Mutex::new(None)),
+                    listeners:std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
                     #attributes,
                     #children,
                 }#(#events)*
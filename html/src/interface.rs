@@ -62,6 +62,13 @@ impl Render for Html {
         Ok(())
     }
 
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        for root in self.roots.iter() {
+            w.push(root.outer_html());
+        }
+        Ok(())
+    }
+
     fn remove_event_listeners(&self) -> ElementResult<()> {
         for root in &self.renderables {
             root.remove_event_listeners()?;
@@ -18,6 +18,29 @@
 //! In addition, HTML elements marked with `@name` attributes are collected into
 //! a separate `HashMap` allowing client to side-access them for external bindings.
 //!
+//! Dynamic values can be bound to text nodes and attributes using
+//! [`State`] - updating the state patches the bound DOM nodes
+//! (see the [`state`] module documentation).
+//!
+//! Templates created using the [`tree!`] macro can also be rendered to a string
+//! using [`Render::render_to_string()`], producing the same markup the DOM
+//! renderer injects without accessing the DOM. This allows server-side rendering,
+//! static generation and snapshot tests on native targets.
+//!
+//! DOM events can be bound to Rust closures using `on<event>` attributes.
+//! Handlers receive the [`web_sys::Event`] and are retained by the resulting
+//! [`Html`] structure; listeners are removed from the DOM elements once the
+//! [`Html`] structure (and all of its clones) is dropped.
+//!
+//! ```ignore
+//! let html = html! {
+//!     <button class="primary" onclick=move |_event| { counter.increment(); }>
+//!         "Increment"
+//!     </button>
+//!     <input oninput={move |event| log_info!("input: {:?}", event.target())} />
+//! }?;
+//! ```
+//!
 //! This crate works in conjunction with [`workflow-ux`](https://crates.io/crates/workflow-ux)
 //! allowing Rust HTML Form binding to HTML.
 //!
@@ -26,11 +49,13 @@
 pub mod escape;
 pub mod interface;
 pub mod render;
+pub mod state;
 pub mod utils;
 pub use interface::{Hooks, Html};
 
 pub use escape::{escape_attr, escape_html};
 pub use render::{Render, Renderables, Result, Write};
+pub use state::{Binding, Reactive, State};
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 pub use utils::{document, Element as WebElement, ElementResult};
@@ -42,9 +67,88 @@ pub use workflow_html_macros::{html, html_str, renderable, tree};
 pub enum AttributeValue {
     Bool(bool),
     Str(String),
+    State(Arc<dyn Reactive>),
+}
+
+/// Conversion of template attribute values into [`AttributeValue`]
+pub trait IntoAttributeValue {
+    fn into_attribute_value(self) -> AttributeValue;
+}
+
+impl<S: Into<String>> IntoAttributeValue for S {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::Str(self.into())
+    }
+}
+
+impl<T: std::fmt::Display + 'static> IntoAttributeValue for State<T> {
+    fn into_attribute_value(self) -> AttributeValue {
+        AttributeValue::State(Arc::new(self))
+    }
 }
 
 pub type OnClickClosure = Closure<dyn FnMut(web_sys::MouseEvent)>;
+pub type EventClosure = Closure<dyn FnMut(web_sys::Event)>;
+pub type EventListeners = Arc<Mutex<Vec<EventListener>>>;
+
+/// Event handler bound to an element using the `on<event>` template attribute.
+/// The JavaScript closure is created when the element is rendered into the DOM
+/// and the listener is removed from the element when [`EventListener`] is dropped.
+pub struct EventListener {
+    event: String,
+    handler: Option<Box<dyn FnMut(web_sys::Event)>>,
+    closure: Option<EventClosure>,
+    target: Option<WebElement>,
+}
+
+impl EventListener {
+    pub fn new<F>(event: &str, handler: F) -> Self
+    where
+        F: FnMut(web_sys::Event) + 'static,
+    {
+        EventListener {
+            event: event.to_string(),
+            handler: Some(Box::new(handler)),
+            closure: None,
+            target: None,
+        }
+    }
+
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    fn bind(&mut self, target: &WebElement) -> ElementResult<()> {
+        if let Some(handler) = self.handler.take() {
+            self.closure = Some(Closure::new(handler));
+        }
+        if let Some(closure) = self.closure.as_ref() {
+            target
+                .add_event_listener_with_callback(&self.event, closure.as_ref().unchecked_ref())?;
+            self.target = Some(target.clone());
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for EventListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventListener")
+            .field("event", &self.event)
+            .field("bound", &self.target.is_some())
+            .finish()
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        if let (Some(target), Some(closure)) = (self.target.take(), self.closure.as_ref()) {
+            target
+                .remove_event_listener_with_callback(&self.event, closure.as_ref().unchecked_ref())
+                .ok();
+        }
+    }
+}
 
 #[derive(Debug, Default, Clone)]
 pub struct Element<T: Render> {
@@ -54,6 +158,7 @@ pub struct Element<T: Render> {
     pub children: Option<T>,
     pub reff: Option<(String, String)>,
     pub onclick: Arc<Mutex<Option<OnClickClosure>>>,
+    pub listeners: EventListeners,
 }
 
 impl<T: Render + Clone + 'static> Element<T> {
@@ -69,6 +174,18 @@ impl<T: Render + Clone + 'static> Element<T> {
         }
         self
     }
+
+    /// Bind `handler` to the DOM `event` (used by the `on<event>` template attributes)
+    pub fn on_event<F>(self, event: &str, handler: F) -> Self
+    where
+        F: FnMut(web_sys::Event) + 'static,
+    {
+        self.listeners
+            .lock()
+            .unwrap()
+            .push(EventListener::new(event, handler));
+        self
+    }
     //self_.home_item.element.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())?;
 }
 
@@ -92,12 +209,21 @@ impl<T: Render + Clone + 'static> Render for Element<T> {
         renderables: &mut Renderables,
     ) -> ElementResult<()> {
         renderables.push(Arc::new(self.clone()));
+        if self.is_fragment {
+            if let Some(children) = self.children {
+                children.render_node(parent, map, renderables)?;
+            }
+            return Ok(());
+        }
         let mut el = document().create_element(&self.tag)?;
 
         let onclick = self.onclick.lock().unwrap();
         if let Some(onclick) = onclick.as_ref() {
             el.add_event_listener_with_callback("click", onclick.as_ref().unchecked_ref())?;
         }
+        for listener in self.listeners.lock().unwrap().iter_mut() {
+            listener.bind(&el)?;
+        }
 
         for (key, value) in &self.attributes {
             match value {
@@ -107,7 +233,11 @@ impl<T: Render + Clone + 'static> Render for Element<T> {
                     }
                 }
                 AttributeValue::Str(v) => {
-                    el.set_attribute(key, &escape_attr(v))?;
+                    el.set_attribute(key, v)?;
+                }
+                AttributeValue::State(state) => {
+                    el.set_attribute(key, &state.to_value_string())?;
+                    renderables.push(state.bind(Binding::Attribute(el.clone(), key.clone())));
                 }
             }
         }
@@ -139,6 +269,10 @@ impl<T: Render + Clone + 'static> Render for Element<T> {
                     AttributeValue::Str(v) => {
                         w.push(format!(" {}=\"{}\"", key, escape_attr(v)));
                     }
+                    AttributeValue::State(state) => {
+                        let value = state.to_value_string();
+                        w.push(format!(" {}=\"{}\"", key, escape_attr(value)));
+                    }
                 }
             }
             w.push(">".to_string());
@@ -151,8 +285,38 @@ impl<T: Render + Clone + 'static> Render for Element<T> {
         Ok(())
     }
 
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        if !self.is_fragment {
+            w.push(format!("<{}", self.tag));
+            for (key, value) in &self.attributes {
+                let value = match value {
+                    AttributeValue::Bool(true) => "true".to_string(),
+                    AttributeValue::Bool(false) => continue,
+                    AttributeValue::Str(v) => v.clone(),
+                    AttributeValue::State(state) => state.to_value_string(),
+                };
+                w.push(format!(" {}=\"{}\"", key, escape_attr(value)));
+            }
+            if let Some((_, value)) = &self.reff {
+                w.push(format!(" data-ref=\"{}\"", escape_attr(value.as_str())));
+            }
+            w.push(">".to_string());
+            if is_void_element(&self.tag) {
+                return Ok(());
+            }
+        }
+        if let Some(children) = &self.children {
+            children.render_markup(w)?;
+        }
+        if !self.is_fragment {
+            w.push(format!("</{}>", self.tag));
+        }
+        Ok(())
+    }
+
     fn remove_event_listeners(&self) -> ElementResult<()> {
         *self.onclick.lock().unwrap() = None;
+        self.listeners.lock().unwrap().clear();
         if let Some(children) = &self.children {
             children.remove_event_listeners()?;
         }
@@ -160,6 +324,16 @@ impl<T: Render + Clone + 'static> Render for Element<T> {
     }
 }
 
+/// Elements serialized without the closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+fn is_void_element(tag: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag.to_lowercase().as_str())
+}
+
 #[cfg(test)]
 mod test {
     //cargo test -- --nocapture --test-threads=1
@@ -207,6 +381,60 @@ mod test {
         assert_eq!(result, "<div class=\"xyz\"></div><div class=\"abc\"></div>");
     }
     #[test]
+    pub fn event_handlers() {
+        self::print_hr("event_handlers");
+        let label = "increment";
+        let tree = tree! {
+            <div class="counter">
+                <button class="primary" onclick=move |_event| { println!("{label}"); }>{label}</button>
+                <input onchange={|_event| ()} oninput=|_| {} value="1" />
+            </div>
+        };
+        let button = &tree.children.as_ref().unwrap().0;
+        assert_eq!(button.listeners.lock().unwrap()[0].event(), "click");
+        let result = tree.html();
+        println!("html: {}", result);
+        assert_eq!(
+            result,
+            "<div class=\"counter\"><button class=\"primary\">increment</button><input value=\"1\"></input></div>"
+        );
+    }
+    #[test]
+    pub fn state_bindings() {
+        self::print_hr("state_bindings");
+        let count = State::new(1);
+        let status = State::new("idle".to_string());
+        let tree = tree! {
+            <div class={status.clone()}>"Count: " {count.clone()}</div>
+        };
+        assert_eq!(tree.html(), "<div class=\"idle\">Count: 1</div>");
+        count.update(|count| *count += 1);
+        status.set("<active>".to_string());
+        assert_eq!(tree.html(), "<div class=\"&lt;active&gt;\">Count: 2</div>");
+        assert_eq!(count.bindings(), 0);
+    }
+    #[test]
+    pub fn markup() {
+        self::print_hr("markup");
+        let status = State::new("a&b".to_string());
+        let tree = tree! {
+            <>
+                <div class={status.clone()} ?active={true} ?hidden={false} @panel="main">
+                    "<text> & " {String::from("<b>")} {1}
+                    <input value="\"quoted\"" onchange=|_| {} />
+                    <br />
+                </div>
+                <p>{status}</p>
+            </>
+        };
+        let result = tree.render_to_string().unwrap();
+        println!("markup: {}", result);
+        assert_eq!(
+            result,
+            "<div active=\"true\" class=\"a&amp;b\" data-ref=\"main\">&lt;text&gt; &amp; &lt;b&gt;1<input value=\"&quot;quoted&quot;\"><br></div><p>a&amp;b</p>"
+        );
+    }
+    #[test]
     pub fn complex_html() {
         self::print_hr("complex_html");
         let world = "world";
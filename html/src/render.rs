@@ -1,3 +1,4 @@
+use crate::escape::escape_html;
 use crate::interface::Hooks;
 use crate::utils::{document, Element, ElementResult};
 use crate::Html;
@@ -67,6 +68,21 @@ pub trait Render {
 
     fn render(&self, _w: &mut Vec<String>) -> ElementResult<()>;
 
+    /// Render the markup that [`Render::render_node()`] would inject into the DOM
+    /// (escaped text, `data-ref` attributes, void elements without closing tags).
+    /// Does not access the DOM and can be used on native targets.
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        self.render(w)
+    }
+
+    /// Render the markup to a string (see [`Render::render_markup()`]),
+    /// for server-side rendering, static generation or snapshot tests
+    fn render_to_string(&self) -> ElementResult<String> {
+        let mut buf = vec![];
+        self.render_markup(&mut buf)?;
+        Ok(buf.join(""))
+    }
+
     fn remove_event_listeners(&self) -> ElementResult<()> {
         Ok(())
     }
@@ -85,6 +101,10 @@ impl Render for &str {
         w.push(self.to_string());
         Ok(())
     }
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        w.push(escape_html(*self).into_owned());
+        Ok(())
+    }
     fn render_node(
         self,
         parent: &mut Element,
@@ -105,6 +125,13 @@ impl<T: Render + Clone> Render for Vec<T> {
         Ok(())
     }
 
+    fn render_markup(&self, list: &mut Vec<std::string::String>) -> ElementResult<()> {
+        for item in self {
+            item.render_markup(list)?;
+        }
+        Ok(())
+    }
+
     fn render_node(
         self,
         parent: &mut Element,
@@ -159,6 +186,13 @@ impl<T: Render + Clone> Render for Option<T> {
         }
         Ok(())
     }
+
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        if let Some(h) = self {
+            h.render_markup(w)?;
+        }
+        Ok(())
+    }
 }
 
 macro_rules! impl_tuple {
@@ -171,6 +205,13 @@ macro_rules! impl_tuple {
                 $($ident.render(w)?;)+
                 Ok(())
             }
+            #[inline]
+            #[allow(non_snake_case)]
+            fn render_markup(&self, w: &mut Vec<String>)->ElementResult<()>{
+                let ($($ident,)+) = self;
+                $($ident.render_markup(w)?;)+
+                Ok(())
+            }
             #[allow(non_snake_case)]
             fn render_node(
                 self,
@@ -194,6 +235,10 @@ macro_rules! impl_types {
                     w.push(format!("{}", self));
                     Ok(())
                 }
+                fn render_markup(&self, w: &mut Vec<String>)->ElementResult<()>{
+                    w.push(escape_html(format!("{}", self)).into_owned());
+                    Ok(())
+                }
                 fn render_node(
                     self,
                     parent:&mut Element,
@@ -0,0 +1,222 @@
+//!
+//! Reactive values for rendered templates.
+//!
+//! A [`State`] value can be used as a text node or as an attribute value
+//! in `html!{}` templates.  Rendering the template binds the created DOM
+//! nodes to the state and [`State::set()`] patches only the bound text
+//! nodes and attributes, without re-rendering the template.
+//!
+//! Bindings are retained by the resulting [`Html`](crate::Html) structure
+//! and released when it is dropped.  [`State`] is a shared handle, so it
+//! should be cloned into the template to keep updating it afterwards.
+//!
+//! ```ignore
+//! let count = State::new(0);
+//! let status = State::new("idle".to_string());
+//!
+//! let html = html! {
+//!     <div class={status.clone()}>
+//!         "Count: " {count.clone()}
+//!     </div>
+//! }?;
+//!
+//! count.update(|count| *count += 1);
+//! status.set("active".to_string());
+//! ```
+//!
+
+use crate::escape::escape_html;
+use crate::interface::Hooks;
+use crate::render::{Render, Renderables};
+use crate::utils::{document, Element, ElementResult};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex, Weak};
+
+/// DOM node bound to a [`State`]
+#[derive(Debug, Clone)]
+pub enum Binding {
+    Text(web_sys::Text),
+    Attribute(Element, String),
+}
+
+impl Binding {
+    fn update(&self, value: &str) {
+        match self {
+            Binding::Text(text) => text.set_data(value),
+            Binding::Attribute(element, name) => {
+                element.set_attribute(name, value).ok();
+            }
+        }
+    }
+}
+
+struct Inner<T> {
+    value: T,
+    bindings: BTreeMap<u64, Binding>,
+    next_id: u64,
+}
+
+/// Reactive value (see the [module](self) documentation)
+pub struct State<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+impl<T> Clone for State<T> {
+    fn clone(&self) -> Self {
+        State {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for State<T> {
+    fn default() -> Self {
+        State::new(T::default())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for State<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        f.debug_struct("State")
+            .field("value", &inner.value)
+            .field("bindings", &inner.bindings.len())
+            .finish()
+    }
+}
+
+impl<T> State<T> {
+    pub fn new(value: T) -> Self {
+        State {
+            inner: Arc::new(Mutex::new(Inner {
+                value,
+                bindings: BTreeMap::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    /// Returns a clone of the current value
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.inner.lock().unwrap().value.clone()
+    }
+
+    /// Invoke `f` with a reference to the current value
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.lock().unwrap().value)
+    }
+
+    /// Number of DOM nodes currently bound to the state
+    pub fn bindings(&self) -> usize {
+        self.inner.lock().unwrap().bindings.len()
+    }
+}
+
+impl<T: Display> State<T> {
+    /// Set the value and patch all bound DOM nodes
+    pub fn set(&self, value: T) {
+        self.update(|v| *v = value);
+    }
+
+    /// Modify the value in place and patch all bound DOM nodes
+    /// (the state is locked while `f` is executed, `f` must not access it)
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut inner = self.inner.lock().unwrap();
+        f(&mut inner.value);
+        let value = inner.value.to_string();
+        for binding in inner.bindings.values() {
+            binding.update(&value);
+        }
+    }
+
+    /// Current value as rendered into the DOM
+    pub fn to_value_string(&self) -> String {
+        self.inner.lock().unwrap().value.to_string()
+    }
+}
+
+impl<T: Display + 'static> State<T> {
+    /// Bind a DOM node to the state.  The node is updated until
+    /// the returned [`BindingGuard`] is dropped.
+    pub fn bind(&self, binding: Binding) -> BindingGuard<T> {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.bindings.insert(id, binding);
+        BindingGuard {
+            state: Arc::downgrade(&self.inner),
+            id,
+        }
+    }
+}
+
+/// Guard created by [`State::bind()`], retained by the rendered
+/// [`Html`](crate::Html) structure. Dropping the guard unbinds the DOM node.
+pub struct BindingGuard<T> {
+    state: Weak<Mutex<Inner<T>>>,
+    id: u64,
+}
+
+impl<T> Drop for BindingGuard<T> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            state.lock().unwrap().bindings.remove(&self.id);
+        }
+    }
+}
+
+impl<T> Render for BindingGuard<T> {
+    fn render(&self, _w: &mut Vec<String>) -> ElementResult<()> {
+        Ok(())
+    }
+}
+
+impl<T: Display + 'static> Render for State<T> {
+    fn render(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        w.push(self.to_value_string());
+        Ok(())
+    }
+
+    fn render_markup(&self, w: &mut Vec<String>) -> ElementResult<()> {
+        w.push(escape_html(self.to_value_string()).into_owned());
+        Ok(())
+    }
+
+    fn render_node(
+        self,
+        parent: &mut Element,
+        _map: &mut Hooks,
+        renderables: &mut Renderables,
+    ) -> ElementResult<()> {
+        let text = document().create_text_node(&self.to_value_string());
+        parent.append_child(&text)?;
+        renderables.push(Arc::new(self.bind(Binding::Text(text))));
+        Ok(())
+    }
+}
+
+/// Type-erased [`State`] used as an element attribute value
+pub trait Reactive {
+    fn to_value_string(&self) -> String;
+    fn bind(&self, binding: Binding) -> Arc<dyn Render>;
+}
+
+impl<T: Display + 'static> Reactive for State<T> {
+    fn to_value_string(&self) -> String {
+        State::to_value_string(self)
+    }
+
+    fn bind(&self, binding: Binding) -> Arc<dyn Render> {
+        Arc::new(State::bind(self, binding))
+    }
+}
+
+impl std::fmt::Debug for dyn Reactive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Reactive({:?})", self.to_value_string())
+    }
+}
@@ -0,0 +1,159 @@
+//!
+//! Streaming downloads with progress reporting.
+//!
+//! [`Download`] is a [`Stream`] of response body chunks, received from the
+//! response body stream on native platforms and from the `fetch()` response
+//! `ReadableStream` on wasm32, allowing large files to be processed without
+//! buffering the entire response in memory.  Progress is reported as the
+//! number of bytes received and the total size from the `Content-Length`
+//! header (if supplied by the server).
+//!
+//! ```ignore
+//! let mut download = get_stream("https://example.com/archive.bin")
+//!     .await?
+//!     .with_progress(|progress| {
+//!         if let Some(percent) = progress.percent() {
+//!             log_info!("downloaded {percent:.1}%");
+//!         }
+//!     });
+//!
+//! while let Some(chunk) = download.next().await {
+//!     file.write_all(&chunk?).await?;
+//! }
+//! ```
+//!
+
+use crate::error::Error;
+use crate::request::{HeaderMap, Request, StatusCode};
+use crate::result::Result;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "wasm32")] {
+        type ChunkStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>>>>>;
+        type ProgressFn = Box<dyn FnMut(Progress)>;
+    } else {
+        type ChunkStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send>>;
+        type ProgressFn = Box<dyn FnMut(Progress) + Send>;
+    }
+}
+
+/// Send a GET request and return the response body stream,
+/// failing with [`Error::Status`] if the response status is not a success status
+pub async fn get_stream(url: impl Into<String>) -> Result<Download> {
+    Request::new(url).get_stream().await
+}
+
+/// Download progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of bytes received
+    pub received: u64,
+    /// Total number of bytes (from the `Content-Length` header)
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// Percentage of bytes received (`None` if the total size is not known)
+    pub fn percent(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| self.received as f64 * 100.0 / total as f64)
+    }
+}
+
+/// Response body stream (see the [module](self) documentation)
+pub struct Download {
+    status: StatusCode,
+    headers: HeaderMap,
+    total: Option<u64>,
+    received: u64,
+    stream: ChunkStream,
+    progress: Option<ProgressFn>,
+}
+
+impl Download {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let total = response.content_length();
+        let stream = response
+            .bytes_stream()
+            .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(Error::from));
+        Download {
+            status,
+            headers,
+            total,
+            received: 0,
+            stream: Box::pin(stream),
+            progress: None,
+        }
+    }
+
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            /// Invoke `callback` after each received chunk
+            pub fn with_progress(mut self, callback: impl FnMut(Progress) + 'static) -> Self {
+                self.progress = Some(Box::new(callback));
+                self
+            }
+        } else {
+            /// Invoke `callback` after each received chunk
+            pub fn with_progress(mut self, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+                self.progress = Some(Box::new(callback));
+                self
+            }
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            received: self.received,
+            total: self.total,
+        }
+    }
+
+    /// Return [`Error::Status`] if the response status is not a success status
+    pub fn error_for_status(self) -> Result<Self> {
+        if self.status.is_success() {
+            Ok(self)
+        } else {
+            Err(Error::Status(self.status, String::new()))
+        }
+    }
+
+    /// Receive the entire remaining body
+    pub async fn collect(mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(self.total.unwrap_or_default() as usize);
+        while let Some(chunk) = self.next().await {
+            data.extend(chunk?);
+        }
+        Ok(data)
+    }
+}
+
+impl Stream for Download {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.stream.as_mut().poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &poll {
+            self.received += chunk.len() as u64;
+            let progress = self.progress();
+            if let Some(callback) = self.progress.as_mut() {
+                callback(progress);
+            }
+        }
+        poll
+    }
+}
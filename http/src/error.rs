@@ -11,6 +11,12 @@ pub enum Error {
     #[error("JSON: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("{0}: {1}")]
+    Status(reqwest::StatusCode, String),
+
+    #[error("Request timed out")]
+    Timeout,
+
     #[error("Not implemented")]
     NotImplemented,
 }
@@ -0,0 +1,447 @@
+//!
+//! HTTP request builder and response.
+//!
+//! [`Request`] is backed by [`reqwest`], which uses the browser `fetch()`
+//! API on wasm32 targets, providing the same API and error types on all
+//! platforms.  Requests can be configured with a method, headers, query
+//! parameters, a body, a timeout and a [`RetryPolicy`].
+//!
+//! ```ignore
+//! let response = Request::new("https://api.example.com/v1/items")
+//!     .with_method(Method::POST)
+//!     .with_header("Authorization", format!("Bearer {token}"))
+//!     .with_query("page", 2)
+//!     .with_json(&item)?
+//!     .with_timeout(Duration::from_secs(10))
+//!     .with_retry(RetryPolicy::new(3).with_delay(Duration::from_millis(500)))
+//!     .send()
+//!     .await?
+//!     .error_for_status()?;
+//!
+//! let etag = response.header("etag");
+//! let items: Vec<Item> = response.json()?;
+//! ```
+//!
+
+use crate::download::Download;
+use crate::error::Error;
+use crate::result::Result;
+use futures::{select, FutureExt};
+pub use reqwest::header::HeaderMap;
+pub use reqwest::{Method, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use workflow_core::task::sleep;
+use workflow_core::time::backoff_delay;
+
+pub async fn get(url: impl Into<String>) -> Result<String> {
+    Request::new(url).get().await
+}
+
+pub async fn get_json<T: DeserializeOwned + 'static>(url: impl Into<String>) -> Result<T> {
+    Request::new(url).get_json().await
+}
+
+/// Policy for retrying requests that fail due to a transport error,
+/// a timeout or a `5xx` / `429 Too Many Requests` response status.
+/// The delay between attempts is multiplied by the backoff factor
+/// after each attempt, up to the maximum delay.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub delay: Duration,
+    pub backoff: f64,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            delay: Duration::from_millis(250),
+            backoff: 2.0,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Delay multiplier applied after each attempt (`1.0` for a constant delay)
+    pub fn with_backoff(mut self, backoff: f64) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Delay before the retry attempt `attempt` (starting from `1`)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        backoff_delay(self.delay, self.backoff, self.max_delay, attempt)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+/// Returns `true` if the request should be retried: on a transport error,
+/// a timeout or a `5xx` / `429 Too Many Requests` response status
+fn is_retryable<T>(result: &Result<T>, status: impl Fn(&T) -> StatusCode) -> bool {
+    match result {
+        Ok(response) => {
+            let status = status(response);
+            status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+        }
+        Err(Error::Timeout) => true,
+        Err(Error::Reqwest(err)) => !err.is_builder() && !err.is_decode(),
+        Err(_) => false,
+    }
+}
+
+/// HTTP request builder (see the [module](self) documentation)
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub url: String,
+    pub user_agent: Option<String>,
+    pub method: Method,
+    pub headers: Vec<(String, String)>,
+    pub query: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    pub timeout: Option<Duration>,
+    pub retry: Option<RetryPolicy>,
+}
+
+impl Request {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            user_agent: None,
+            method: Method::GET,
+            headers: Vec::new(),
+            query: Vec::new(),
+            body: None,
+            timeout: None,
+            retry: None,
+        }
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Append a query parameter to the URL
+    pub fn with_query(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.query.push((name.into(), value.to_string()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Set a JSON-serialized body and the `Content-Type: application/json` header
+    pub fn with_json<T: Serialize>(self, value: &T) -> Result<Self> {
+        let body = serde_json::to_vec(value)?;
+        Ok(self
+            .with_header("Content-Type", "application/json")
+            .with_body(body))
+    }
+
+    /// Fail the request with [`Error::Timeout`] if it does not
+    /// complete within `timeout` (applies to each attempt)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Send the request, retrying according to the [`RetryPolicy`].
+    /// A response with an error status is not an error, use
+    /// [`Response::error_for_status()`] to treat it as one.
+    pub async fn send(self) -> Result<Response> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                workflow_core::task::call_async_no_send!(self.send_impl().await)
+            } else {
+                self.send_impl().await
+            }
+        }
+    }
+
+    /// Send the request and return the response without receiving the body,
+    /// retrying according to the [`RetryPolicy`].  The timeout applies to
+    /// receiving the response headers only.  See [`Download`].
+    pub async fn send_stream(self) -> Result<Download> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                workflow_core::task::call_async_no_send!(self.send_stream_impl().await)
+            } else {
+                self.send_stream_impl().await
+            }
+        }
+    }
+
+    async fn send_impl(self) -> Result<Response> {
+        self.retry(|| self.attempt(), |response| response.status)
+            .await
+    }
+
+    async fn send_stream_impl(self) -> Result<Download> {
+        self.retry(|| self.attempt_stream(), |response| response.status())
+            .await
+            .map(Download::new)
+    }
+
+    async fn retry<T, F>(
+        &self,
+        attempt: impl Fn() -> F,
+        status: impl Fn(&T) -> StatusCode,
+    ) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let max_retries = self
+            .retry
+            .as_ref()
+            .map(|retry| retry.max_retries)
+            .unwrap_or(0);
+        let mut count = 0;
+        loop {
+            let result = attempt().await;
+            if !is_retryable(&result, &status) || count >= max_retries {
+                return result;
+            }
+            count += 1;
+            if let Some(retry) = self.retry.as_ref() {
+                sleep(retry.delay_for(count)).await;
+            }
+        }
+    }
+
+    fn build(&self) -> reqwest::RequestBuilder {
+        let mut request = reqwest::Client::new().request(self.method.clone(), &self.url);
+        if let Some(user_agent) = self.user_agent.as_ref() {
+            request = request.header("User-Agent", user_agent);
+        }
+        for (name, value) in self.headers.iter() {
+            request = request.header(name, value);
+        }
+        if !self.query.is_empty() {
+            request = request.query(&self.query);
+        }
+        if let Some(body) = self.body.as_ref() {
+            request = request.body(body.clone());
+        }
+        request
+    }
+
+    async fn with_timeout_of<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        match self.timeout {
+            Some(timeout) => select! {
+                result = future.fuse() => result,
+                _ = sleep(timeout).fuse() => Err(Error::Timeout),
+            },
+            None => future.await,
+        }
+    }
+
+    async fn attempt(&self) -> Result<Response> {
+        let request = self.build();
+        self.with_timeout_of(async move {
+            let response = request.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(Response {
+                status,
+                headers,
+                body,
+            })
+        })
+        .await
+    }
+
+    async fn attempt_stream(&self) -> Result<reqwest::Response> {
+        let request = self.build();
+        self.with_timeout_of(async move { Ok(request.send().await?) })
+            .await
+    }
+
+    /// Send a GET request and return the response text
+    pub async fn get(self) -> Result<String> {
+        self.with_method(Method::GET)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+    }
+
+    /// Send a GET request and deserialize the JSON response
+    pub async fn get_json<T: DeserializeOwned + 'static>(self) -> Result<T> {
+        self.with_method(Method::GET)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+
+    /// Send a GET request and return the response body stream
+    pub async fn get_stream(self) -> Result<Download> {
+        self.with_method(Method::GET)
+            .send_stream()
+            .await?
+            .error_for_status()
+    }
+}
+
+/// HTTP response returned by [`Request::send()`]
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Value of the header `name` (case-insensitive), if present and valid UTF-8
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.body
+    }
+
+    pub fn text(&self) -> Result<String> {
+        String::from_utf8(self.body.clone()).map_err(|err| Error::Custom(err.to_string()))
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// Return [`Error::Status`] if the response status is not a success status
+    pub fn error_for_status(self) -> Result<Self> {
+        if self.status.is_success() {
+            Ok(self)
+        } else {
+            let body = String::from_utf8_lossy(&self.body).to_string();
+            Err(Error::Status(self.status, body))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    pub fn test_retry_policy_delay() {
+        let retry = RetryPolicy::new(3);
+        assert_eq!(retry.delay_for(1), Duration::from_millis(250));
+        assert_eq!(retry.delay_for(3), Duration::from_secs(1));
+        assert_eq!(retry.delay_for(100), Duration::from_secs(30));
+        assert_eq!(retry.delay_for(u32::MAX), Duration::from_secs(30));
+
+        let retry = RetryPolicy::new(3)
+            .with_backoff(1.0)
+            .with_delay(Duration::from_millis(100));
+        assert_eq!(retry.delay_for(10), Duration::from_millis(100));
+    }
+
+    #[test]
+    pub fn test_retry_classification() {
+        let status = |status: &StatusCode| *status;
+        assert!(is_retryable(&Ok(StatusCode::INTERNAL_SERVER_ERROR), status));
+        assert!(is_retryable(&Ok(StatusCode::SERVICE_UNAVAILABLE), status));
+        assert!(is_retryable(&Ok(StatusCode::TOO_MANY_REQUESTS), status));
+        assert!(!is_retryable(&Ok(StatusCode::OK), status));
+        assert!(!is_retryable(&Ok(StatusCode::NOT_FOUND), status));
+        assert!(!is_retryable(&Ok(StatusCode::BAD_REQUEST), status));
+
+        assert!(is_retryable::<StatusCode>(&Err(Error::Timeout), status));
+        assert!(!is_retryable::<StatusCode>(
+            &Err(Error::Custom("error".to_string())),
+            status
+        ));
+        let builder = reqwest::Client::new().get("not a url").build().unwrap_err();
+        assert!(!is_retryable::<StatusCode>(
+            &Err(Error::Reqwest(builder)),
+            status
+        ));
+    }
+
+    #[tokio::test]
+    pub async fn test_retry_attempts() {
+        let attempts = AtomicU32::new(0);
+        let request = Request::new("http://localhost")
+            .with_retry(RetryPolicy::new(2).with_delay(Duration::from_millis(1)));
+        let result = request
+            .retry(
+                || async {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Ok(StatusCode::BAD_GATEWAY)
+                },
+                |status| *status,
+            )
+            .await;
+        assert_eq!(result.unwrap(), StatusCode::BAD_GATEWAY);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        attempts.store(0, Ordering::SeqCst);
+        let result = request
+            .retry(
+                || async {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err::<StatusCode, _>(Error::Custom("error".to_string()))
+                },
+                |status| *status,
+            )
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -25,6 +25,14 @@ pub enum Error {
 
     #[error("i18n: io failure: {0}")]
     Io(#[from] std::io::Error),
+
+    #[cfg(feature = "http")]
+    #[error("i18n: http failure: {0}")]
+    Http(#[from] workflow_http::error::Error),
+
+    #[cfg(feature = "store")]
+    #[error("i18n: store failure: {0}")]
+    Store(#[from] workflow_store::error::Error),
 }
 
 impl Error {
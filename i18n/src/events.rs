@@ -0,0 +1,89 @@
+//!
+//! Change notifications.
+//!
+//! Listeners registered using [`on_change()`] are invoked when the active
+//! language changes or when the dictionary is reloaded (e.g. after merging
+//! remotely loaded translations), allowing UI layers to re-render translated
+//! strings.
+//!
+//! ```ignore
+//! let id = workflow_i18n::events::on_change(move |change| {
+//!     title.set(t!("Settings"));
+//! });
+//! workflow_i18n::i18n::activate_language("ja")?;
+//! workflow_i18n::events::remove_listener(id);
+//! ```
+//!
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Change event delivered to listeners
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Language with the given code has been activated
+    Language(String),
+    /// Dictionary has been reloaded or merged with new data
+    Dictionary,
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "wasm32")] {
+        pub type ChangeFn = dyn Fn(&Change) + 'static;
+    } else {
+        pub type ChangeFn = dyn Fn(&Change) + Send + Sync + 'static;
+    }
+}
+
+struct Listener {
+    id: u64,
+    callback: Arc<ChangeFn>,
+}
+
+// wasm32 is single-threaded, listeners can capture non-Send (DOM) values
+#[cfg(target_arch = "wasm32")]
+unsafe impl Send for Listener {}
+
+static LISTENERS: Mutex<Vec<Listener>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "wasm32")] {
+        /// Register a change listener, returns an id for [`remove_listener()`]
+        pub fn on_change(callback: impl Fn(&Change) + 'static) -> u64 {
+            register(Arc::new(callback))
+        }
+    } else {
+        /// Register a change listener, returns an id for [`remove_listener()`]
+        pub fn on_change(callback: impl Fn(&Change) + Send + Sync + 'static) -> u64 {
+            register(Arc::new(callback))
+        }
+    }
+}
+
+fn register(callback: Arc<ChangeFn>) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    LISTENERS.lock().unwrap().push(Listener { id, callback });
+    id
+}
+
+/// Remove a listener registered using [`on_change()`]
+pub fn remove_listener(id: u64) -> bool {
+    let mut listeners = LISTENERS.lock().unwrap();
+    let len = listeners.len();
+    listeners.retain(|listener| listener.id != id);
+    listeners.len() != len
+}
+
+/// Invoke all listeners (listeners may register or remove listeners)
+pub(crate) fn notify(change: &Change) {
+    let callbacks = LISTENERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|listener| listener.callback.clone())
+        .collect::<Vec<_>>();
+    for callback in callbacks {
+        callback(change);
+    }
+}
@@ -0,0 +1,179 @@
+//!
+//! Argument interpolation and pluralization of translated strings.
+//!
+//! Translated strings can contain named placeholders (`{name}`) and
+//! plural selectors following the ICU message syntax:
+//!
+//! ```text
+//! "{count, plural, =0 {No files} one {# file in {folder}} other {# files in {folder}}}"
+//! ```
+//!
+//! Plural cases are selected by an exact value (`=0`) or by the CLDR
+//! plural category (`zero`, `one`, `two`, `few`, `many`, `other`) of the
+//! argument in the language of the translation; `#` within the selected
+//! case is replaced by the argument value.  Placeholders without a
+//! matching argument are retained as-is.
+//!
+
+use crate::plural::{plural_category, PluralCategory};
+use std::fmt::Display;
+
+/// Named argument supplied to [`format()`]
+pub type Arg<'a> = (&'a str, &'a dyn Display);
+
+/// Interpolate `args` into `text` using the plural rules of the language `code`
+pub fn format(code: &str, text: &str, args: &[Arg]) -> String {
+    let args = args
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect::<Vec<_>>();
+    let mut out = String::with_capacity(text.len());
+    format_message(&mut out, code, text, &args, None);
+    out
+}
+
+fn format_message(
+    out: &mut String,
+    code: &str,
+    text: &str,
+    args: &[(&str, String)],
+    number: Option<&str>,
+) {
+    let mut rest = text;
+    while let Some(pos) = rest.find(['{', '#']) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with('#') {
+            out.push_str(number.unwrap_or("#"));
+            rest = &rest[1..];
+            continue;
+        }
+        let Some(end) = closing_brace(rest) else {
+            break;
+        };
+        let placeholder = &rest[1..end];
+        if !format_placeholder(out, code, placeholder, args) {
+            out.push_str(&rest[..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+}
+
+/// Format `{placeholder}` contents, returns `false` if the placeholder can not be resolved
+fn format_placeholder(
+    out: &mut String,
+    code: &str,
+    placeholder: &str,
+    args: &[(&str, String)],
+) -> bool {
+    let mut parts = placeholder.splitn(3, ',').map(str::trim);
+    let name = parts.next().unwrap_or_default();
+    let Some((_, value)) = args.iter().find(|(arg, _)| *arg == name) else {
+        return false;
+    };
+    match (parts.next(), parts.next()) {
+        (None, _) => {
+            out.push_str(value);
+            true
+        }
+        (Some("plural"), Some(cases)) => match select_plural(code, value, cases) {
+            Some(message) => {
+                format_message(out, code, message, args, Some(value));
+                true
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Select the plural case message matching `value`
+fn select_plural<'a>(code: &str, value: &str, cases: &'a str) -> Option<&'a str> {
+    let category = plural_category(code, value);
+    let mut exact = None;
+    let mut by_category = None;
+    let mut other = None;
+
+    let mut rest = cases.trim_start();
+    while !rest.is_empty() {
+        let start = rest.find('{')?;
+        let end = closing_brace(&rest[start..])? + start;
+        let selector = rest[..start].trim();
+        let message = &rest[start + 1..end];
+        if let Some(selector) = selector.strip_prefix('=') {
+            if selector.parse::<f64>().ok() == value.trim().parse::<f64>().ok() {
+                exact.get_or_insert(message);
+            }
+        } else if selector == "other" {
+            other.get_or_insert(message);
+        } else if selector.parse::<PluralCategory>() == Ok(category) {
+            by_category.get_or_insert(message);
+        }
+        rest = rest[end + 1..].trim_start();
+    }
+
+    exact.or(by_category).or(other)
+}
+
+/// Position of the brace closing the brace at the start of `text`
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (pos, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+    use crate::plural::{plural_category, PluralCategory};
+
+    const FILES: &str =
+        "{count, plural, =0 {No files} one {# file in {folder}} other {# files in {folder}}}";
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            format("en", "Hello {name}, {missing}!", &[("name", &"World")]),
+            "Hello World, {missing}!"
+        );
+        assert_eq!(format("en", "# {count}", &[("count", &1)]), "# 1");
+    }
+
+    #[test]
+    fn plurals() {
+        let files =
+            |code, count: f64| format(code, FILES, &[("count", &count), ("folder", &"/tmp")]);
+        assert_eq!(files("en", 0.0), "No files");
+        assert_eq!(files("en", 1.0), "1 file in /tmp");
+        assert_eq!(files("en", 2.0), "2 files in /tmp");
+        assert_eq!(files("en", 1.5), "1.5 files in /tmp");
+        assert_eq!(files("ja", 1.0), "1 files in /tmp");
+    }
+
+    #[test]
+    fn categories() {
+        use PluralCategory::*;
+        let ru = ["1", "2", "5", "11", "21", "22", "1.5"].map(|n| plural_category("ru", n));
+        assert_eq!(ru, [One, Few, Many, Many, One, Few, Other]);
+        let ar = ["0", "1", "2", "3", "11", "100"].map(|n| plural_category("ar", n));
+        assert_eq!(ar, [Zero, One, Two, Few, Many, Other]);
+        let pl = ["1", "3", "5", "22", "1.5"].map(|n| plural_category("pl", n));
+        assert_eq!(pl, [One, Few, Many, Few, Other]);
+        assert_eq!(plural_category("fr", "0"), One);
+        assert_eq!(plural_category("pt-BR", "1"), One);
+        assert_eq!(plural_category("en", "1.0"), Other);
+        assert_eq!(plural_category("en", "n/a"), Other);
+    }
+}
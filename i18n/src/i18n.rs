@@ -1,4 +1,6 @@
 use crate::error::Error;
+use crate::events::{notify, Change};
+use crate::format::{format, Arg};
 use crate::result::Result;
 use arc_swap::*;
 use ritehash::FxHasher64;
@@ -9,10 +11,16 @@ use std::sync::{Arc, Mutex, MutexGuard};
 pub type FxBuildHasher = BuildHasherDefault<FxHasher64>;
 pub type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
 
-static mut JSON_DATA: Option<String> = None;
-static mut JSON_DATA_GUARD: Option<Mutex<()>> = None;
+static JSON_DATA_GUARD: Mutex<()> = Mutex::new(());
 static DICTIONARY: ArcSwapOption<Dictionary> = ArcSwapOption::const_empty();
 
+/// Retain JSON data for the lifetime of the application. Dictionary entries
+/// reference the JSON data and translated strings are returned as `&'static str`,
+/// so data of replaced dictionaries must remain valid.
+fn retain(json_data: String) -> &'static str {
+    Box::leak(json_data.into_boxed_str())
+}
+
 pub type StoreFn = dyn Send + Sync + Fn(&str) -> Result<()> + 'static;
 
 pub struct Builder {
@@ -54,10 +62,7 @@ impl Builder {
 
     pub fn try_init(self) -> Result<()> {
         let json_data = if let Some(json_data) = self.string_json_data {
-            unsafe {
-                JSON_DATA = Some(json_data);
-                JSON_DATA.as_deref()
-            }
+            Some(retain(json_data))
         } else {
             self.static_json_data
         };
@@ -199,7 +204,7 @@ pub fn dictionary() -> Arc<Dictionary> {
 }
 
 pub fn guard() -> MutexGuard<'static, ()> {
-    unsafe { JSON_DATA_GUARD.as_ref().unwrap().lock().unwrap() }
+    JSON_DATA_GUARD.lock().unwrap()
 }
 
 pub fn load(json_data_file: impl AsRef<Path>) -> Result<()> {
@@ -219,19 +224,62 @@ pub fn from_string(json_data: impl Into<String>) -> Result<()> {
         )
     };
 
-    unsafe {
-        JSON_DATA = Some(json_data.into());
-        DICTIONARY.swap(Some(Arc::new(Dictionary::try_new(
-            current_code,
-            default_code,
-            Some(JSON_DATA.as_ref().unwrap().as_str()),
-            store_fn,
-        )?)));
-    }
+    DICTIONARY.swap(Some(Arc::new(Dictionary::try_new(
+        current_code,
+        default_code,
+        Some(retain(json_data.into())),
+        store_fn,
+    )?)));
+    drop(_guard);
 
+    notify(&Change::Dictionary);
     Ok(())
 }
 
+/// Merge JSON data over the current dictionary and reload it. The data uses
+/// the `i18n.json` structure where all fields are optional, e.g.
+/// `{ "translations": { "ja": { "Hello": "こんにちは" } } }`: translations,
+/// languages and aliases are merged entry by entry and enabled language codes
+/// are added to the currently enabled ones.
+pub fn merge(json_data: &str) -> Result<()> {
+    let overlay = serde_json::from_str::<serde_json::Value>(json_data)?;
+    if !overlay.is_object() {
+        return Err(Error::custom("merged data must be a JSON object"));
+    }
+    let mut data = serde_json::to_value(Storable::from(dictionary().as_ref()))?;
+    merge_values(&mut data, overlay);
+    from_string(serde_json::to_string(&data)?)
+}
+
+fn merge_values(target: &mut serde_json::Value, source: serde_json::Value) {
+    use serde_json::Value;
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(source)) => {
+            for value in source {
+                if !target.contains(&value) {
+                    target.push(value);
+                }
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
+
+/// Activate a language (or a language alias) and notify change listeners.
+pub fn activate_language(language_code: impl Into<String>) -> Result<()> {
+    dictionary().activate_language_code(language_code)
+}
+
 /// Translate a string to the currently user-selected language.
 pub fn i18n(text: &str) -> &str {
     #[cfg(feature = "thread-safe")]
@@ -272,6 +320,19 @@ pub fn i18n(text: &str) -> &str {
     }
 }
 
+/// Translate a string to the currently user-selected language, interpolating
+/// named `args` and resolving plural forms (see [`crate::format`]).  Plural rules
+/// of the default language are used if the translation is missing.
+pub fn i18n_format(text: &str, args: &[Arg]) -> String {
+    let translated = i18n(text);
+    let dictionary = dictionary();
+    if dictionary.translate(text).is_some() {
+        format(dictionary.current_code().as_str(), translated, args)
+    } else {
+        format(dictionary.default_code(), translated, args)
+    }
+}
+
 /// Dictionary structure containing all translations and related data.
 pub struct Dictionary {
     /// list of languages {"en": "English", "ja": "日本語", ..."}
@@ -430,16 +491,17 @@ impl Dictionary {
         let language_code: String = language_code.into();
         let current_code = self.resolve_aliases(language_code.as_str())?;
         let current_title = self.language_title(current_code.as_str())?.to_string();
-        let current_translations = dictionary()
+        let current_translations = self
             .translations
-            .get(language_code.as_str())
+            .get(current_code.as_str())
             .ok_or(Error::UnknownLanguageCode(language_code))?
             .clone();
 
-        self.current_code.store(Arc::new(current_code));
+        self.current_code.store(Arc::new(current_code.clone()));
         self.current_title.store(Arc::new(current_title));
         self.current_translations.store(current_translations);
 
+        notify(&Change::Language(current_code));
         Ok(())
     }
 
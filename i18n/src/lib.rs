@@ -8,12 +8,43 @@
 //!
 //! i18n is a performance-oriented library for internationalization and translation embedding into Rust applications.
 //!
+//! The [`t!`] macro translates strings with named arguments and plural forms:
+//!
+//! ```ignore
+//! // "files-count": "{count, plural, =0 {No files} one {# file} other {# files}}"
+//! let text = t!("files-count", count = files.len());
+//! ```
+//!
+//! Translations can be merged at runtime from JSON data ([`i18n::merge()`]),
+//! a URL or the storage (see [`remote`]) and the active language can be
+//! switched using [`i18n::activate_language()`]; both emit change events
+//! (see [`events`]) allowing UI layers to re-render.
+//!
 pub mod error;
+pub mod events;
+pub mod format;
 pub mod i18n;
 pub mod json;
+pub mod plural;
+pub mod remote;
 pub mod result;
 
-pub use i18n::i18n;
+pub use i18n::{i18n, i18n_format};
+
+/// Translate a string to the currently user-selected language, interpolating
+/// named arguments and resolving plural forms (see [`format`]). Returns a `String`.
+#[macro_export]
+macro_rules! t {
+    ($text:expr) => {
+        $crate::i18n::i18n_format($text, &[])
+    };
+    ($text:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::i18n_format(
+            $text,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
 
 // #[macro_use]
 // extern crate serde_json;
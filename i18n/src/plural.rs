@@ -0,0 +1,293 @@
+//!
+//! CLDR plural rules (cardinal) for the languages included in the default
+//! i18n data set.  Languages without plural forms (e.g. Japanese or Chinese)
+//! always resolve to [`PluralCategory::Other`].
+//!
+
+use std::str::FromStr;
+
+/// CLDR plural category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl FromStr for PluralCategory {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(PluralCategory::Zero),
+            "one" => Ok(PluralCategory::One),
+            "two" => Ok(PluralCategory::Two),
+            "few" => Ok(PluralCategory::Few),
+            "many" => Ok(PluralCategory::Many),
+            "other" => Ok(PluralCategory::Other),
+            _ => Err(()),
+        }
+    }
+}
+
+/// CLDR plural operands of a decimal number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Operands {
+    /// absolute value
+    pub n: f64,
+    /// integer digits
+    pub i: u64,
+    /// number of visible fraction digits (with trailing zeros)
+    pub v: usize,
+    /// visible fraction digits (with trailing zeros)
+    pub f: u64,
+    /// visible fraction digits (without trailing zeros)
+    pub t: u64,
+}
+
+impl FromStr for Operands {
+    type Err = ();
+
+    /// Parse operands from a formatted decimal number (e.g. `"-1.50"`)
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().trim_start_matches(['-', '+']);
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if integer.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(());
+        }
+        let i = integer.parse::<u64>().map_err(|_| ())?;
+        let f = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u64>().map_err(|_| ())?
+        };
+        let trimmed = fraction.trim_end_matches('0');
+        let t = if trimmed.is_empty() {
+            0
+        } else {
+            trimmed.parse::<u64>().map_err(|_| ())?
+        };
+        let n = s.parse::<f64>().map_err(|_| ())?;
+        Ok(Operands {
+            n,
+            i,
+            v: fraction.len(),
+            f,
+            t,
+        })
+    }
+}
+
+/// Resolve the plural category of `number` (a formatted decimal number)
+/// for the language `code` (e.g. `"en"`, `"ru"` or `"pt-BR"`).
+/// Returns [`PluralCategory::Other`] if the number can not be parsed.
+pub fn plural_category(code: &str, number: &str) -> PluralCategory {
+    match number.parse::<Operands>() {
+        Ok(operands) => category(code, &operands),
+        Err(_) => PluralCategory::Other,
+    }
+}
+
+fn category(code: &str, ops: &Operands) -> PluralCategory {
+    use PluralCategory::*;
+
+    let language = code.split(['-', '_']).next().unwrap_or(code);
+    let Operands { n, i, v, f, t } = *ops;
+    let integer = v == 0;
+    let (i10, i100) = (i % 10, i % 100);
+    let (f10, f100) = (f % 10, f % 100);
+    let n_int = n.fract() == 0.0;
+    let (n10, n100) = (n % 10.0, n % 100.0);
+
+    match language {
+        "ja" | "zh" | "ko" | "th" | "vi" => Other,
+
+        "en" | "de" | "nl" | "sv" | "et" | "fi" | "it" | "ur" => {
+            if i == 1 && integer {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "af" | "bg" | "el" | "es" | "hu" | "mn" | "nb" | "no" | "ta" | "te" | "tr" => {
+            if n == 1.0 {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "da" => {
+            if n == 1.0 || (t != 0 && (i == 0 || i == 1)) {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "is" => {
+            if t != 0 || (i10 == 1 && i100 != 11) {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "fr" | "pt" => {
+            if i == 0 || i == 1 {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "bn" | "fa" | "hi" => {
+            if i == 0 || n == 1.0 {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "pa" => {
+            if n == 0.0 || n == 1.0 {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "fil" => {
+            if (integer && ((1..=3).contains(&i) || !matches!(i10, 4 | 6 | 9)))
+                || (!integer && !matches!(f10, 4 | 6 | 9))
+            {
+                One
+            } else {
+                Other
+            }
+        }
+
+        "he" => {
+            if (i == 1 && integer) || (i == 0 && !integer) {
+                One
+            } else if i == 2 && integer {
+                Two
+            } else {
+                Other
+            }
+        }
+
+        "ru" | "uk" => {
+            if integer && i10 == 1 && i100 != 11 {
+                One
+            } else if integer && (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+                Few
+            } else if integer && (i10 == 0 || (5..=9).contains(&i10) || (11..=14).contains(&i100)) {
+                Many
+            } else {
+                Other
+            }
+        }
+
+        "hr" | "sr" => {
+            if (integer && i10 == 1 && i100 != 11) || (f10 == 1 && f100 != 11) {
+                One
+            } else if (integer && (2..=4).contains(&i10) && !(12..=14).contains(&i100))
+                || ((2..=4).contains(&f10) && !(12..=14).contains(&f100))
+            {
+                Few
+            } else {
+                Other
+            }
+        }
+
+        "pl" => {
+            if i == 1 && integer {
+                One
+            } else if integer && (2..=4).contains(&i10) && !(12..=14).contains(&i100) {
+                Few
+            } else if integer
+                && ((i != 1 && i10 <= 1) || (5..=9).contains(&i10) || (12..=14).contains(&i100))
+            {
+                Many
+            } else {
+                Other
+            }
+        }
+
+        "cs" | "sk" => {
+            if i == 1 && integer {
+                One
+            } else if (2..=4).contains(&i) && integer {
+                Few
+            } else if !integer {
+                Many
+            } else {
+                Other
+            }
+        }
+
+        "lt" => {
+            if n_int && n10 == 1.0 && !(11.0..=19.0).contains(&n100) {
+                One
+            } else if n_int && n10 >= 2.0 && !(11.0..=19.0).contains(&n100) {
+                Few
+            } else if f != 0 {
+                Many
+            } else {
+                Other
+            }
+        }
+
+        "sl" => {
+            if integer && i100 == 1 {
+                One
+            } else if integer && i100 == 2 {
+                Two
+            } else if !integer || i100 == 3 || i100 == 4 {
+                Few
+            } else {
+                Other
+            }
+        }
+
+        "ro" => {
+            if i == 1 && integer {
+                One
+            } else if !integer || n == 0.0 || (n_int && (2.0..=19.0).contains(&n100)) {
+                Few
+            } else {
+                Other
+            }
+        }
+
+        "ar" => {
+            if n == 0.0 {
+                Zero
+            } else if n == 1.0 {
+                One
+            } else if n == 2.0 {
+                Two
+            } else if n_int && (3.0..=10.0).contains(&n100) {
+                Few
+            } else if n_int && (11.0..=99.0).contains(&n100) {
+                Many
+            } else {
+                Other
+            }
+        }
+
+        // languages without known rules use the English rule
+        _ => {
+            if i == 1 && integer {
+                One
+            } else {
+                Other
+            }
+        }
+    }
+}
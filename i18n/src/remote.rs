@@ -0,0 +1,30 @@
+//!
+//! Runtime loading of translation data from a URL (`http` feature)
+//! or from the storage (`store` feature).  Loaded data is merged over
+//! the current dictionary using [`merge()`] and change listeners are
+//! notified (see [`crate::events`]).
+//!
+//! ```ignore
+//! workflow_i18n::remote::load_url("https://example.com/i18n/ja.json").await?;
+//! workflow_i18n::remote::load_store("~/.my-app/i18n.json").await?;
+//! ```
+//!
+
+#[cfg(any(feature = "http", feature = "store"))]
+use crate::{i18n::merge, result::Result};
+
+/// Fetch JSON data from `url` and merge it over the current dictionary
+#[cfg(feature = "http")]
+pub async fn load_url(url: &str) -> Result<()> {
+    let json_data = workflow_http::get(url).await?;
+    merge(&json_data)
+}
+
+/// Read JSON data from the storage (`~` is resolved to the home folder)
+/// and merge it over the current dictionary
+#[cfg(feature = "store")]
+pub async fn load_store(filename: &str) -> Result<()> {
+    let filename = workflow_store::fs::resolve_path(filename)?;
+    let json_data = workflow_store::fs::read_to_string(&filename).await?;
+    merge(&json_data)
+}
@@ -27,6 +27,23 @@
 //! - `log_warn()`
 //! - `log_error!()`
 //!
+//! Rate-limited variants of these macros (`log_warn_throttled!(interval, ...)`
+//! etc.) emit at most one message per interval from each call site, while
+//! sampled variants (`log_warn_sampled!(n, ...)` etc.) emit one out of every
+//! `n` messages.  The number of suppressed messages is reported alongside
+//! the next emitted message.
+//!
+//! Contextual fields can be attached to all log messages emitted within
+//! an async block using `log_scope!(conn_id = 42, async { ... })`, allowing
+//! log lines to be correlated without threading identifiers manually.
+//!
+//! Log output can be grouped using `log_group!()`, `log_group_collapsed!()`
+//! and `log_group_end!()`.  In the browser these map to `console.group()`
+//! and `console.groupEnd()`, while log levels are mapped to the matching
+//! `console.error()`, `console.warn()`, `console.info()` and `console.debug()`
+//! functions and styled using `%c` CSS directives (configurable via
+//! `set_console_style()` and `set_console_styles_enabled()`).
+//!
 //! # Redirecting log output
 //!
 //! This crate allows you to configure a log sink that will receive
@@ -77,10 +94,24 @@ pub use self::console::*;
 
 pub mod levels;
 
+#[cfg(not(target_os = "solana"))]
+mod panic;
+
+#[cfg(not(target_os = "solana"))]
+pub mod scope;
+#[cfg(not(target_os = "solana"))]
+pub use scope::{current_fields, with_fields, Fields};
+
+#[cfg(not(target_os = "solana"))]
+pub mod throttle;
+#[cfg(not(target_os = "solana"))]
+pub use throttle::*;
+
 pub mod prelude {
     pub use super::console::*;
     pub use super::log::{
-        log_debug, log_error, log_info, log_trace, log_warn, set_log_level, Level, LevelFilter,
+        log_debug, log_error, log_group, log_group_collapsed, log_group_end, log_info, log_trace,
+        log_warn, set_log_level, Level, LevelFilter,
     };
 }
 
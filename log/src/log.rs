@@ -10,6 +10,7 @@ cfg_if! {
         use downcast::{ downcast_sync, AnySync };
         pub use hexplay::{self, HexViewBuilder};
         pub use termcolor::Buffer;
+        pub use crate::panic::PanicRecord;
         //use core::ops::Range;
 
         pub struct ColorHexView<'a>{
@@ -70,6 +71,13 @@ cfg_if! {
         /// function and will receive all log messages.
         pub trait Sink : AnySync {
             fn write(&self, target: Option<&str>, level : Level, args : &fmt::Arguments<'_>) -> bool;
+
+            /// Receives a [`PanicRecord`] captured by the panic hook. The default
+            /// implementation forwards the formatted record to [`Sink::write`]
+            /// with [`Level::Error`].
+            fn panic(&self, record : &PanicRecord) -> bool {
+                self.write(Some("panic"), Level::Error, &format_args!("{record}"))
+            }
         }
 
         struct SinkHandler {
@@ -139,6 +147,13 @@ cfg_if! {
                         None => { *SINK.lock().unwrap() = None; }
                     }
                 }
+                fn to_sink_panic(record : &PanicRecord) -> bool {
+                    // the lock can be held if the panic originates in the sink itself
+                    match SINK.try_lock() {
+                        Ok(handler) => handler.as_ref().map(|handler| handler.sink.panic(record)).unwrap_or(false),
+                        Err(_) => { false }
+                    }
+                }
                 #[inline(always)]
                 fn to_sink(target: Option<&str>, level : Level, args : &fmt::Arguments<'_>) -> bool {
                     match SINK.lock().unwrap().as_ref() {
@@ -183,6 +198,13 @@ cfg_if! {
                     }
 
                 }
+                fn to_sink_panic(record : &PanicRecord) -> bool {
+                    // the lock can be held if the panic originates in the sink itself
+                    match SINK.try_lock() {
+                        Ok(handler) => handler.as_ref().map(|handler| handler.sink.panic(record)).unwrap_or(false),
+                        Err(_) => { false }
+                    }
+                }
                 #[inline(always)]
                 fn to_sink(target : Option<&str>, level : Level, args : &fmt::Arguments<'_>) -> bool {
                     match SINK.lock().unwrap().as_ref() {
@@ -239,6 +261,9 @@ cfg_if! {
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm_log {
+    use super::Level;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
     use wasm_bindgen::prelude::*;
 
     #[wasm_bindgen]
@@ -249,31 +274,161 @@ pub mod wasm_log {
         pub fn warn(s: &str);
         #[wasm_bindgen(js_namespace = console)]
         pub fn error(s: &str);
+        #[wasm_bindgen(js_namespace = console)]
+        pub fn info(s: &str);
+        #[wasm_bindgen(js_namespace = console)]
+        pub fn debug(s: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = log)]
+        fn log_styled(s: &str, css: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = warn)]
+        fn warn_styled(s: &str, css: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = error)]
+        fn error_styled(s: &str, css: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = info)]
+        fn info_styled(s: &str, css: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = debug)]
+        fn debug_styled(s: &str, css: &str);
+        #[wasm_bindgen(js_namespace = console)]
+        pub fn group(s: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = groupCollapsed)]
+        pub fn group_collapsed(s: &str);
+        #[wasm_bindgen(js_namespace = console, js_name = groupEnd)]
+        pub fn group_end();
+    }
+
+    static STYLES_ENABLED: AtomicBool = AtomicBool::new(true);
+    static STYLES: Mutex<Option<[String; 5]>> = Mutex::new(None);
+    static GROUP_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+    /// Open a console group, tracking the number of open groups
+    pub fn open_group(label: &str, collapsed: bool) {
+        if collapsed {
+            group_collapsed(label);
+        } else {
+            group(label);
+        }
+        GROUP_DEPTH.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Close the innermost console group, if any group has been opened
+    /// (groups are not opened if disabled by the log level or consumed by the sink)
+    pub fn close_group() {
+        if GROUP_DEPTH
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| {
+                depth.checked_sub(1)
+            })
+            .is_ok()
+        {
+            group_end();
+        }
+    }
+
+    fn default_style(level: Level) -> &'static str {
+        match level {
+            Level::Error => "color: #e0454b; font-weight: bold",
+            Level::Warn => "color: #c98a00",
+            Level::Info => "",
+            Level::Debug => "color: #3a7bd5",
+            Level::Trace => "color: #8a8a8a",
+        }
+    }
+
+    /// Enable or disable `%c` CSS styling of the browser console output.
+    /// Styling is enabled by default.
+    pub fn set_console_styles_enabled(enabled: bool) {
+        STYLES_ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Override the CSS style applied to console messages of the given [`Level`].
+    /// Supplying an empty string outputs messages of this level unstyled.
+    pub fn set_console_style(level: Level, css: &str) {
+        let mut styles = STYLES.lock().unwrap();
+        let styles = styles.get_or_insert_with(|| {
+            [
+                Level::Error,
+                Level::Warn,
+                Level::Info,
+                Level::Debug,
+                Level::Trace,
+            ]
+            .map(|level| default_style(level).to_string())
+        });
+        styles[level as usize - 1] = css.to_string();
+    }
+
+    fn style(level: Level) -> Option<String> {
+        if !STYLES_ENABLED.load(Ordering::Relaxed) {
+            return None;
+        }
+        let css = match STYLES.lock().unwrap().as_ref() {
+            Some(styles) => styles[level as usize - 1].clone(),
+            None => default_style(level).to_string(),
+        };
+        (!css.is_empty()).then_some(css)
+    }
+
+    /// Outputs the text to the browser console using the console function
+    /// matching the log level (`console.error`, `console.warn`, `console.info`
+    /// or `console.debug`), applying the level style if styling is enabled.
+    /// [`Level::Trace`] is mapped to `console.debug` so that trace output
+    /// is filtered under the devtools "Verbose" level.
+    pub fn write(level: Level, text: &str) {
+        match style(level) {
+            Some(css) => {
+                // `%` in the text would otherwise be treated as a format directive
+                let text = format!("%c{}", text.replace('%', "%%"));
+                match level {
+                    Level::Error => error_styled(&text, &css),
+                    Level::Warn => warn_styled(&text, &css),
+                    Level::Info => info_styled(&text, &css),
+                    Level::Debug | Level::Trace => debug_styled(&text, &css),
+                }
+            }
+            None => match level {
+                Level::Error => error(text),
+                Level::Warn => warn(text),
+                Level::Info => info(text),
+                Level::Debug | Level::Trace => debug(text),
+            },
+        }
     }
 }
 
+#[cfg(target_arch = "wasm32")]
+pub use wasm_log::{set_console_style, set_console_styles_enabled};
+
 pub mod impls {
     use super::*;
 
+    #[cfg(target_os = "solana")]
+    #[inline(always)]
+    fn with_scope_fields(args: &fmt::Arguments<'_>, f: impl FnOnce(&fmt::Arguments<'_>)) {
+        f(args)
+    }
+    #[cfg(not(target_os = "solana"))]
+    use crate::scope::with_scope_fields;
+
     #[inline(always)]
     #[allow(unused_variables)]
     pub fn error_impl(target: Option<&str>, args: &fmt::Arguments<'_>) {
         if log_level_enabled(Level::Error) {
-            #[cfg(all(not(target_os = "solana"), feature = "sink"))]
-            {
-                if to_sink(target, Level::Error, args) {
-                    return;
+            with_scope_fields(args, |args| {
+                #[cfg(all(not(target_os = "solana"), feature = "sink"))]
+                {
+                    if to_sink(target, Level::Error, args) {
+                        return;
+                    }
                 }
-            }
-            cfg_if! {
-                if #[cfg(target_arch = "wasm32")] {
-                    workflow_log::wasm_log::error(&args.to_string());
-                } else if #[cfg(target_os = "solana")] {
-                    solana_program::log::sol_log(&args.to_string());
-                } else {
-                    println!("{args}");
+                cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        workflow_log::wasm_log::write(Level::Error, &args.to_string());
+                    } else if #[cfg(target_os = "solana")] {
+                        solana_program::log::sol_log(&args.to_string());
+                    } else {
+                        println!("{args}");
+                    }
                 }
-            }
+            });
         }
     }
 
@@ -281,21 +436,23 @@ pub mod impls {
     #[allow(unused_variables)]
     pub fn warn_impl(target: Option<&str>, args: &fmt::Arguments<'_>) {
         if log_level_enabled(Level::Warn) {
-            #[cfg(all(not(target_os = "solana"), feature = "sink"))]
-            {
-                if to_sink(target, Level::Warn, args) {
-                    return;
+            with_scope_fields(args, |args| {
+                #[cfg(all(not(target_os = "solana"), feature = "sink"))]
+                {
+                    if to_sink(target, Level::Warn, args) {
+                        return;
+                    }
                 }
-            }
-            cfg_if! {
-                if #[cfg(target_arch = "wasm32")] {
-                    workflow_log::wasm_log::warn(&args.to_string());
-                } else if #[cfg(target_os = "solana")] {
-                    solana_program::log::sol_log(&args.to_string());
-                } else {
-                    println!("{args}");
+                cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        workflow_log::wasm_log::write(Level::Warn, &args.to_string());
+                    } else if #[cfg(target_os = "solana")] {
+                        solana_program::log::sol_log(&args.to_string());
+                    } else {
+                        println!("{args}");
+                    }
                 }
-            }
+            });
         }
     }
 
@@ -303,21 +460,23 @@ pub mod impls {
     #[allow(unused_variables)]
     pub fn info_impl(target: Option<&str>, args: &fmt::Arguments<'_>) {
         if log_level_enabled(Level::Info) {
-            #[cfg(all(not(target_os = "solana"), feature = "sink"))]
-            {
-                if to_sink(target, Level::Info, args) {
-                    return;
+            with_scope_fields(args, |args| {
+                #[cfg(all(not(target_os = "solana"), feature = "sink"))]
+                {
+                    if to_sink(target, Level::Info, args) {
+                        return;
+                    }
                 }
-            }
-            cfg_if! {
-                if #[cfg(target_arch = "wasm32")] {
-                    workflow_log::wasm_log::log(&args.to_string());
-                } else if #[cfg(target_os = "solana")] {
-                    solana_program::log::sol_log(&args.to_string());
-                } else {
-                    println!("{args}");
+                cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        workflow_log::wasm_log::write(Level::Info, &args.to_string());
+                    } else if #[cfg(target_os = "solana")] {
+                        solana_program::log::sol_log(&args.to_string());
+                    } else {
+                        println!("{args}");
+                    }
                 }
-            }
+            });
         }
     }
 
@@ -325,21 +484,23 @@ pub mod impls {
     #[allow(unused_variables)]
     pub fn debug_impl(target: Option<&str>, args: &fmt::Arguments<'_>) {
         if log_level_enabled(Level::Debug) {
-            #[cfg(all(not(target_os = "solana"), feature = "sink"))]
-            {
-                if to_sink(target, Level::Debug, args) {
-                    return;
+            with_scope_fields(args, |args| {
+                #[cfg(all(not(target_os = "solana"), feature = "sink"))]
+                {
+                    if to_sink(target, Level::Debug, args) {
+                        return;
+                    }
                 }
-            }
-            cfg_if! {
-                if #[cfg(target_arch = "wasm32")] {
-                    workflow_log::wasm_log::log(&args.to_string());
-                } else if #[cfg(target_os = "solana")] {
-                    solana_program::log::sol_log(&args.to_string());
-                } else {
-                    println!("{args}");
+                cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        workflow_log::wasm_log::write(Level::Debug, &args.to_string());
+                    } else if #[cfg(target_os = "solana")] {
+                        solana_program::log::sol_log(&args.to_string());
+                    } else {
+                        println!("{args}");
+                    }
                 }
-            }
+            });
         }
     }
 
@@ -347,15 +508,42 @@ pub mod impls {
     #[allow(unused_variables)]
     pub fn trace_impl(target: Option<&str>, args: &fmt::Arguments<'_>) {
         if log_level_enabled(Level::Trace) {
+            with_scope_fields(args, |args| {
+                #[cfg(all(not(target_os = "solana"), feature = "sink"))]
+                {
+                    if to_sink(target, Level::Trace, args) {
+                        return;
+                    }
+                }
+                cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        workflow_log::wasm_log::write(Level::Trace, &args.to_string());
+                    } else if #[cfg(target_os = "solana")] {
+                        solana_program::log::sol_log(&args.to_string());
+                    } else {
+                        println!("{args}");
+                    }
+                }
+            });
+        }
+    }
+
+    /// Opens a console group labelled with the supplied message.
+    /// In the browser this maps to `console.group()` or `console.groupCollapsed()`,
+    /// in other environments the label is output as a regular info message.
+    #[inline(always)]
+    #[allow(unused_variables)]
+    pub fn group_impl(collapsed: bool, args: &fmt::Arguments<'_>) {
+        if log_level_enabled(Level::Info) {
             #[cfg(all(not(target_os = "solana"), feature = "sink"))]
             {
-                if to_sink(target, Level::Trace, args) {
+                if to_sink(None, Level::Info, args) {
                     return;
                 }
             }
             cfg_if! {
                 if #[cfg(target_arch = "wasm32")] {
-                    workflow_log::wasm_log::log(&args.to_string());
+                    workflow_log::wasm_log::open_group(&args.to_string(), collapsed);
                 } else if #[cfg(target_os = "solana")] {
                     solana_program::log::sol_log(&args.to_string());
                 } else {
@@ -364,6 +552,28 @@ pub mod impls {
             }
         }
     }
+
+    /// Closes the console group previously opened with [`group_impl`].
+    #[inline(always)]
+    pub fn group_end_impl() {
+        #[cfg(target_arch = "wasm32")]
+        workflow_log::wasm_log::close_group();
+    }
+
+    /// Delivers a [`PanicRecord`] to the installed log sink, regardless of
+    /// the current log level.  Returns `true` if the sink has consumed the
+    /// record, in which case the caller should not output it further.
+    #[cfg(not(target_os = "solana"))]
+    #[allow(unused_variables)]
+    pub fn panic_impl(record: &PanicRecord) -> bool {
+        cfg_if! {
+            if #[cfg(feature = "sink")] {
+                to_sink_panic(record)
+            } else {
+                false
+            }
+        }
+    }
 }
 
 /// Format and log message with [`Level::Error`]
@@ -426,8 +636,39 @@ macro_rules! log_trace {
     )
 }
 
+/// Format a message and open a log group labelled with it.
+/// In the browser this invokes `console.group()`; all subsequent
+/// messages are nested under this group until [`log_group_end`]
+/// is invoked.
+#[macro_export]
+macro_rules! log_group {
+    ($($t:tt)*) => (
+        workflow_log::impls::group_impl(false,&format_args!($($t)*))
+    )
+}
+
+/// Format a message and open a log group that is collapsed
+/// by default (`console.groupCollapsed()` in the browser).
+#[macro_export]
+macro_rules! log_group_collapsed {
+    ($($t:tt)*) => (
+        workflow_log::impls::group_impl(true,&format_args!($($t)*))
+    )
+}
+
+/// Close the log group opened by [`log_group`] or [`log_group_collapsed`]
+#[macro_export]
+macro_rules! log_group_end {
+    () => {
+        workflow_log::impls::group_end_impl()
+    };
+}
+
 pub use log_debug;
 pub use log_error;
+pub use log_group;
+pub use log_group_collapsed;
+pub use log_group_end;
 pub use log_info;
 pub use log_trace;
 pub use log_warn;
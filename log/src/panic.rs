@@ -0,0 +1,94 @@
+//!
+//! Structured panic records delivered to the installed log [`Sink`](crate::Sink).
+//!
+//! Panic hooks (such as the one provided by `workflow-panic-hook`) capture
+//! the panic message, source location and a best-effort stack trace into
+//! a [`PanicRecord`] and pass it to [`impls::panic_impl`](crate::impls::panic_impl)
+//! so that the panic can be persisted or relayed before the process
+//! (or browser tab) terminates.
+//!
+
+use std::any::Any;
+use std::fmt;
+
+/// Panic information captured by the panic hook.
+#[derive(Debug, Clone)]
+pub struct PanicRecord {
+    /// Panic message (payload)
+    pub message: String,
+    /// Source location where the panic occurred, if available
+    pub location: Option<String>,
+    /// Best-effort stack trace at the time of the panic
+    pub stack: Option<String>,
+}
+
+impl PanicRecord {
+    pub fn new(message: String, location: Option<String>, stack: Option<String>) -> Self {
+        PanicRecord {
+            message,
+            location,
+            stack,
+        }
+    }
+
+    /// Extracts the panic message from the panic payload,
+    /// which is typically a `&str` or a `String`.
+    pub fn message_from_payload(payload: &(dyn Any + Send)) -> String {
+        if let Some(msg) = payload.downcast_ref::<&str>() {
+            msg.to_string()
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            msg.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        }
+    }
+}
+
+impl fmt::Display for PanicRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked")?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        write!(f, ":\n{}", self.message)?;
+        if let Some(stack) = &self.stack {
+            write!(f, "\n\nStack:\n\n{stack}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn panic_record_display_test() {
+        let record = PanicRecord::new("boom".to_string(), None, None);
+        assert_eq!(record.to_string(), "panicked:\nboom");
+
+        let record = PanicRecord::new(
+            "boom".to_string(),
+            Some("src/lib.rs:10:5".to_string()),
+            Some("0: main".to_string()),
+        );
+        assert_eq!(
+            record.to_string(),
+            "panicked at src/lib.rs:10:5:\nboom\n\nStack:\n\n0: main"
+        );
+    }
+
+    #[test]
+    fn panic_record_payload_test() {
+        let payload = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(
+            PanicRecord::message_from_payload(&*payload),
+            "static message"
+        );
+        let value = 42;
+        let payload = std::panic::catch_unwind(|| panic!("formatted {value}")).unwrap_err();
+        assert_eq!(PanicRecord::message_from_payload(&*payload), "formatted 42");
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(42u32)).unwrap_err();
+        assert_eq!(PanicRecord::message_from_payload(&*payload), "Box<dyn Any>");
+    }
+}
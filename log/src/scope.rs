@@ -0,0 +1,174 @@
+//!
+//! Scoped contextual fields attached to log records.
+//!
+//! Fields declared using the [`log_scope!()`](crate::log_scope) macro
+//! (or the [`scope()`] and [`with_fields()`] functions) are attached to every
+//! log message emitted while the scope is active.  For async scopes the fields
+//! are re-established each time the wrapped future is polled, which makes them
+//! follow the task across executor threads (and work with the single-threaded
+//! WASM executor) without requiring runtime-specific task-local storage.
+//!
+//! ```ignore
+//! log_scope!(conn_id = 42, method = "ping", async move {
+//!     // logs as: [conn_id=42 method=ping] processing request
+//!     log_info!("processing request");
+//! }).await;
+//! ```
+//!
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A list of contextual log fields (key and formatted value)
+pub type Fields = Vec<(&'static str, String)>;
+
+thread_local! {
+    static FIELDS: RefCell<Fields> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pushes fields onto the thread-local field stack,
+/// removing them when dropped.
+struct ScopeGuard {
+    len: usize,
+}
+
+impl ScopeGuard {
+    fn enter(fields: &Fields) -> Self {
+        FIELDS.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let len = stack.len();
+            stack.extend(fields.iter().cloned());
+            ScopeGuard { len }
+        })
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        FIELDS.with(|stack| stack.borrow_mut().truncate(self.len));
+    }
+}
+
+/// Future returned by [`scope()`] that attaches log fields
+/// for the duration of each poll of the inner future.
+pub struct LogScope<F: Future> {
+    fields: Fields,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for LogScope<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let _guard = ScopeGuard::enter(&this.fields);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// Wraps the future so that the supplied fields are attached
+/// to all log records emitted while the future is executing.
+pub fn scope<F: Future>(fields: Fields, future: F) -> LogScope<F> {
+    LogScope {
+        fields,
+        future: Box::pin(future),
+    }
+}
+
+/// Executes the closure with the supplied fields attached
+/// to all log records emitted during its execution.
+pub fn with_fields<R>(fields: Fields, f: impl FnOnce() -> R) -> R {
+    let _guard = ScopeGuard::enter(&fields);
+    f()
+}
+
+/// Returns fields of all currently active log scopes (outermost first).
+pub fn current_fields() -> Fields {
+    FIELDS.with(|stack| stack.borrow().clone())
+}
+
+struct DisplayFields<'a>(&'a Fields);
+
+impl Display for DisplayFields<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (key, value)) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Invokes the closure with the log message prefixed by the
+/// currently active scope fields (if any).
+#[inline(always)]
+pub(crate) fn with_scope_fields(args: &fmt::Arguments<'_>, f: impl FnOnce(&fmt::Arguments<'_>)) {
+    let fields = FIELDS.with(|stack| {
+        let stack = stack.borrow();
+        (!stack.is_empty()).then(|| DisplayFields(&stack).to_string())
+    });
+    match fields {
+        Some(fields) => f(&format_args!("[{fields}] {args}")),
+        None => f(args),
+    }
+}
+
+/// Attaches contextual fields to all log records emitted within the
+/// supplied future (or an expression returning a future).
+///
+/// `log_scope!(conn_id = 42, call = "ping", async { ... })`
+#[macro_export]
+macro_rules! log_scope {
+    (@fields [$($fields:tt)*] $key:ident = $value:expr, $($rest:tt)+) => {
+        workflow_log::log_scope!(@fields [$($fields)* (stringify!($key), ($value).to_string()),] $($rest)+)
+    };
+    (@fields [$($fields:tt)*] $future:expr) => {
+        workflow_log::scope::scope(vec![$($fields)*], $future)
+    };
+    ($($t:tt)+) => {
+        workflow_log::log_scope!(@fields [] $($t)+)
+    };
+}
+
+pub use log_scope;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn log_scope_test() {
+        let future = log_scope!(conn_id = 42, async {
+            with_fields(vec![("method", "ping".to_string())], current_fields)
+        });
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(fields) => assert_eq!(
+                fields,
+                vec![
+                    ("conn_id", "42".to_string()),
+                    ("method", "ping".to_string())
+                ]
+            ),
+            Poll::Pending => panic!("scoped future should be ready"),
+        }
+        assert!(current_fields().is_empty());
+    }
+}
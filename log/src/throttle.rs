@@ -0,0 +1,259 @@
+//!
+//! Per call-site state used by the rate-limited (`log_*_throttled!()`)
+//! and sampled (`log_*_sampled!()`) log macros.
+//!
+//! Each macro invocation declares its own `static` [`Throttle`] or [`Sampler`]
+//! instance, so suppression is tracked independently for every log statement.
+//! Suppressed messages are counted and the count is reported alongside the
+//! next message that passes through as "suppressed N similar messages".
+//!
+
+use instant::{Duration, Instant};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Time-based log throttle: allows at most one message per interval.
+pub struct Throttle {
+    state: Mutex<Option<(Instant, usize)>>,
+}
+
+impl Throttle {
+    pub const fn new() -> Self {
+        Throttle {
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Returns `Some(suppressed)` if the message should be emitted, where
+    /// `suppressed` is the number of messages dropped since the last emitted
+    /// one, or `None` if the message should be suppressed.
+    pub fn check(&self, interval: Duration) -> Option<usize> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        match state.as_mut() {
+            Some((last, suppressed)) if now.duration_since(*last) < interval => {
+                *suppressed += 1;
+                None
+            }
+            _ => {
+                let suppressed = state.map(|(_, suppressed)| suppressed).unwrap_or(0);
+                *state = Some((now, 0));
+                Some(suppressed)
+            }
+        }
+    }
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Count-based log sampler: allows one out of every `n` messages.
+pub struct Sampler {
+    counter: AtomicUsize,
+}
+
+impl Sampler {
+    pub const fn new() -> Self {
+        Sampler {
+            counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns `Some(suppressed)` for the first message and every `n`-th
+    /// message after it, where `suppressed` is the number of messages
+    /// dropped since the last emitted one, or `None` otherwise.
+    pub fn check(&self, n: usize) -> Option<usize> {
+        let n = n.max(1);
+        let count = self.counter.fetch_add(1, Ordering::Relaxed);
+        match (count, count % n) {
+            (0, _) => Some(0),
+            (_, 0) => Some(n - 1),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_limited {
+    ($impl:ident, $level:ident, $check:expr, $($t:tt)*) => {{
+        if workflow_log::log_level_enabled(workflow_log::Level::$level) {
+            if let Some(suppressed) = $check {
+                if suppressed > 0 {
+                    workflow_log::impls::$impl(
+                        None,
+                        &format_args!(
+                            "{} (suppressed {} similar messages)",
+                            format_args!($($t)*),
+                            suppressed
+                        ),
+                    )
+                } else {
+                    workflow_log::impls::$impl(None, &format_args!($($t)*))
+                }
+            }
+        }
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_throttled {
+    ($impl:ident, $level:ident, $interval:expr, $($t:tt)*) => {{
+        static THROTTLE: workflow_log::throttle::Throttle = workflow_log::throttle::Throttle::new();
+        workflow_log::__log_limited!($impl, $level, THROTTLE.check($interval), $($t)*)
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_sampled {
+    ($impl:ident, $level:ident, $n:expr, $($t:tt)*) => {{
+        static SAMPLER: workflow_log::throttle::Sampler = workflow_log::throttle::Sampler::new();
+        workflow_log::__log_limited!($impl, $level, SAMPLER.check($n), $($t)*)
+    }};
+}
+
+/// Format and log message with [`Level::Error`](crate::Level::Error),
+/// emitting at most one message per supplied `Duration` interval.
+#[macro_export]
+macro_rules! log_error_throttled {
+    ($interval:expr, $($t:tt)*) => (
+        workflow_log::__log_throttled!(error_impl, Error, $interval, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Warn`](crate::Level::Warn),
+/// emitting at most one message per supplied `Duration` interval.
+#[macro_export]
+macro_rules! log_warn_throttled {
+    ($interval:expr, $($t:tt)*) => (
+        workflow_log::__log_throttled!(warn_impl, Warn, $interval, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Info`](crate::Level::Info),
+/// emitting at most one message per supplied `Duration` interval.
+#[macro_export]
+macro_rules! log_info_throttled {
+    ($interval:expr, $($t:tt)*) => (
+        workflow_log::__log_throttled!(info_impl, Info, $interval, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Debug`](crate::Level::Debug),
+/// emitting at most one message per supplied `Duration` interval.
+#[macro_export]
+macro_rules! log_debug_throttled {
+    ($interval:expr, $($t:tt)*) => (
+        workflow_log::__log_throttled!(debug_impl, Debug, $interval, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Trace`](crate::Level::Trace),
+/// emitting at most one message per supplied `Duration` interval.
+#[macro_export]
+macro_rules! log_trace_throttled {
+    ($interval:expr, $($t:tt)*) => (
+        workflow_log::__log_throttled!(trace_impl, Trace, $interval, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Error`](crate::Level::Error),
+/// emitting only one out of every `n` messages.
+#[macro_export]
+macro_rules! log_error_sampled {
+    ($n:expr, $($t:tt)*) => (
+        workflow_log::__log_sampled!(error_impl, Error, $n, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Warn`](crate::Level::Warn),
+/// emitting only one out of every `n` messages.
+#[macro_export]
+macro_rules! log_warn_sampled {
+    ($n:expr, $($t:tt)*) => (
+        workflow_log::__log_sampled!(warn_impl, Warn, $n, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Info`](crate::Level::Info),
+/// emitting only one out of every `n` messages.
+#[macro_export]
+macro_rules! log_info_sampled {
+    ($n:expr, $($t:tt)*) => (
+        workflow_log::__log_sampled!(info_impl, Info, $n, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Debug`](crate::Level::Debug),
+/// emitting only one out of every `n` messages.
+#[macro_export]
+macro_rules! log_debug_sampled {
+    ($n:expr, $($t:tt)*) => (
+        workflow_log::__log_sampled!(debug_impl, Debug, $n, $($t)*)
+    )
+}
+
+/// Format and log message with [`Level::Trace`](crate::Level::Trace),
+/// emitting only one out of every `n` messages.
+#[macro_export]
+macro_rules! log_trace_sampled {
+    ($n:expr, $($t:tt)*) => (
+        workflow_log::__log_sampled!(trace_impl, Trace, $n, $($t)*)
+    )
+}
+
+pub use log_debug_sampled;
+pub use log_debug_throttled;
+pub use log_error_sampled;
+pub use log_error_throttled;
+pub use log_info_sampled;
+pub use log_info_throttled;
+pub use log_trace_sampled;
+pub use log_trace_throttled;
+pub use log_warn_sampled;
+pub use log_warn_throttled;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn throttle_test() {
+        let throttle = Throttle::new();
+        let interval = Duration::from_secs(60);
+        assert_eq!(throttle.check(interval), Some(0));
+        assert_eq!(throttle.check(interval), None);
+        assert_eq!(throttle.check(interval), None);
+        assert_eq!(throttle.check(Duration::ZERO), Some(2));
+        assert_eq!(throttle.check(Duration::ZERO), Some(0));
+    }
+
+    #[test]
+    fn sampler_test() {
+        let sampler = Sampler::new();
+        let emitted = (0..7).map(|_| sampler.check(3)).collect::<Vec<_>>();
+        assert_eq!(
+            emitted,
+            vec![Some(0), None, None, Some(2), None, None, Some(2)]
+        );
+    }
+
+    #[test]
+    fn throttled_macros_test() {
+        for i in 0..3 {
+            log_warn_throttled!(Duration::from_secs(60), "throttled {i}");
+            log_info_sampled!(2, "sampled {i}");
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//!
+//! Async child process API for Node.js and NWJS, modelled after `tokio::process`.
+//!
+//! A [`Command`] configures the program arguments, environment and working
+//! directory.  [`Command::spawn()`] starts the process and returns a [`Child`]
+//! providing process output as channel [`Receiver`]s, stdin writing, signal
+//! delivery and an async [`Child::wait()`] for the [`ExitStatus`].
+//!
+//! ```ignore
+//! use workflow_node::command::Command;
+//!
+//! let mut child = Command::new("git")
+//!     .args(["log", "--oneline"])
+//!     .env("GIT_PAGER", "")
+//!     .current_dir("/home/user/project")
+//!     .spawn()?;
+//!
+//! let stdout = child.take_stdout().unwrap();
+//! while let Ok(data) = stdout.recv().await {
+//!     log_info!("{}", String::from_utf8_lossy(&data));
+//! }
+//!
+//! let status = child.wait_with_timeout(Duration::from_secs(5)).await?;
+//! ```
+//!
+
+use crate::child_process::{spawn_with_args_and_options, ChildProcess, KillSignal, SpawnOptions};
+use crate::error::Error;
+use crate::result::Result;
+use futures::{select, FutureExt};
+use js_sys::{Object, Reflect, Uint8Array};
+use node_sys::EventEmitter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use workflow_core::channel::{unbounded, Receiver, Sender};
+use workflow_core::task::sleep;
+use workflow_wasm::callback::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Object)]
+    type Writable;
+
+    #[wasm_bindgen(method, getter, js_name = stdin)]
+    fn stdin_writable(this: &ChildProcess) -> Option<Writable>;
+
+    #[wasm_bindgen(method)]
+    fn write(this: &Writable, data: &Uint8Array) -> bool;
+
+    #[wasm_bindgen(method)]
+    fn end(this: &Writable);
+}
+
+/// Process exit status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitStatus {
+    code: Option<i32>,
+    signal: Option<String>,
+}
+
+impl ExitStatus {
+    /// Returns `true` if the process exited with code `0`
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// Exit code (`None` if the process was terminated by a signal)
+    pub fn code(&self) -> Option<i32> {
+        self.code
+    }
+
+    /// Signal that terminated the process (e.g. `"SIGTERM"`)
+    pub fn signal(&self) -> Option<&str> {
+        self.signal.as_deref()
+    }
+}
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.code, self.signal.as_ref()) {
+            (Some(code), _) => write!(f, "exit code: {code}"),
+            (None, Some(signal)) => write!(f, "signal: {signal}"),
+            (None, None) => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Child process builder (see the [module](self) documentation)
+#[derive(Debug, Clone)]
+pub struct Command {
+    program: String,
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    env_clear: bool,
+    cwd: Option<PathBuf>,
+}
+
+impl Command {
+    pub fn new(program: &str) -> Command {
+        Command {
+            program: program.to_string(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            env_clear: false,
+            cwd: None,
+        }
+    }
+
+    pub fn arg<S: AsRef<str>>(&mut self, arg: S) -> &mut Command {
+        self.args.push(arg.as_ref().to_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_string()));
+        self
+    }
+
+    /// Set an environment variable (the environment of the
+    /// current process is inherited unless [`Command::env_clear()`] is used)
+    pub fn env<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) -> &mut Command {
+        self.envs
+            .push((key.as_ref().to_string(), value.as_ref().to_string()));
+        self
+    }
+
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Command
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        for (key, value) in vars {
+            self.env(key, value);
+        }
+        self
+    }
+
+    /// Do not inherit the environment of the current process
+    pub fn env_clear(&mut self) -> &mut Command {
+        self.env_clear = true;
+        self.envs.clear();
+        self
+    }
+
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Command {
+        self.cwd = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    fn options(&self) -> Result<SpawnOptions> {
+        let options = SpawnOptions::new();
+        if let Some(cwd) = self.cwd.as_ref() {
+            let cwd = cwd
+                .to_str()
+                .ok_or_else(|| Error::Custom(format!("invalid path: {}", cwd.display())))?;
+            options.cwd(cwd);
+        }
+
+        if self.env_clear || !self.envs.is_empty() {
+            let env = Object::new();
+            if !self.env_clear {
+                Object::assign(&env, &node_sys::process.env());
+            }
+            for (key, value) in self.envs.iter() {
+                Reflect::set(&env, &key.into(), &value.into())?;
+            }
+            options.set("env", env.into());
+        }
+        Ok(options)
+    }
+
+    /// Spawn the process
+    pub fn spawn(&mut self) -> Result<Child> {
+        let options = self.options()?;
+        let args = self.args.as_slice().into();
+        let proc = spawn_with_args_and_options(&self.program, &args, &options);
+        Child::try_new(proc)
+    }
+
+    /// Spawn the process and collect its output once it exits
+    pub async fn output(&mut self) -> Result<Output> {
+        let mut child = self.spawn()?;
+        child.close_stdin();
+        let stdout = child.take_stdout();
+        let stderr = child.take_stderr();
+        let status = child.wait().await?;
+        Ok(Output {
+            status,
+            stdout: stdout.map(collect).unwrap_or_default(),
+            stderr: stderr.map(collect).unwrap_or_default(),
+        })
+    }
+}
+
+/// Output of a process executed using [`Command::output()`]
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+fn collect(receiver: Receiver<Vec<u8>>) -> Vec<u8> {
+    let mut data = Vec::new();
+    while let Ok(chunk) = receiver.try_recv() {
+        data.extend(chunk);
+    }
+    data
+}
+
+type Listener = (
+    EventEmitter,
+    &'static str,
+    Callback<dyn FnMut(JsValue, JsValue)>,
+);
+type Termination = Arc<Mutex<Option<std::result::Result<ExitStatus, String>>>>;
+
+/// Running child process created by [`Command::spawn()`]
+pub struct Child {
+    proc: ChildProcess,
+    stdout: Option<Receiver<Vec<u8>>>,
+    stderr: Option<Receiver<Vec<u8>>>,
+    termination: Termination,
+    // closed when the process terminates
+    exit: Receiver<()>,
+    listeners: Vec<Listener>,
+}
+
+unsafe impl Send for Child {}
+unsafe impl Sync for Child {}
+
+impl Child {
+    fn try_new(proc: ChildProcess) -> Result<Child> {
+        let termination: Termination = Arc::new(Mutex::new(None));
+        let (exit_sender, exit) = unbounded::<()>();
+
+        let terminate = |termination: &Termination,
+                         exit: &Sender<()>,
+                         result: std::result::Result<ExitStatus, String>| {
+            termination.lock().unwrap().get_or_insert(result);
+            exit.close();
+        };
+
+        let mut listeners: Vec<Listener> = Vec::new();
+
+        // `close` is emitted after the stdio streams have been closed
+        let close = {
+            let termination = termination.clone();
+            let exit = exit_sender.clone();
+            Callback::create(move |code: JsValue, signal: JsValue| {
+                let status = ExitStatus {
+                    code: code.as_f64().map(|code| code as i32),
+                    signal: signal.as_string(),
+                };
+                terminate(&termination, &exit, Ok(status));
+            })
+        };
+        proc.on("close", close.get_fn());
+        listeners.push((proc.clone().unchecked_into(), "close", close));
+
+        // `error` is emitted if the process can not be spawned or killed
+        let error = {
+            let termination = termination.clone();
+            let exit = exit_sender.clone();
+            Callback::create(move |err: JsValue, _: JsValue| {
+                let err = workflow_wasm::jserror::JsErrorData::from(err).to_string();
+                terminate(&termination, &exit, Err(err));
+            })
+        };
+        proc.on("error", error.get_fn());
+        listeners.push((proc.clone().unchecked_into(), "error", error));
+
+        let mut output = |stream: JsValue| -> Option<Receiver<Vec<u8>>> {
+            if stream.is_null() || stream.is_undefined() {
+                return None;
+            }
+            let stream = stream.unchecked_into::<EventEmitter>();
+            let (sender, receiver) = unbounded();
+            let data = Callback::create(move |data: JsValue, _: JsValue| {
+                sender.try_send(Uint8Array::new(&data).to_vec()).ok();
+            });
+            stream.on("data", data.get_fn());
+            listeners.push((stream, "data", data));
+            Some(receiver)
+        };
+        let stdout = output(Reflect::get(&proc, &"stdout".into())?);
+        let stderr = output(Reflect::get(&proc, &"stderr".into())?);
+
+        Ok(Child {
+            proc,
+            stdout,
+            stderr,
+            termination,
+            exit,
+            listeners,
+        })
+    }
+
+    /// Process id (`None` if the process failed to spawn)
+    pub fn id(&self) -> Option<u32> {
+        Reflect::get(&self.proc, &"pid".into())
+            .ok()
+            .and_then(|pid| pid.as_f64())
+            .map(|pid| pid as u32)
+    }
+
+    /// Take the receiver of the process stdout output
+    pub fn take_stdout(&mut self) -> Option<Receiver<Vec<u8>>> {
+        self.stdout.take()
+    }
+
+    /// Take the receiver of the process stderr output
+    pub fn take_stderr(&mut self) -> Option<Receiver<Vec<u8>>> {
+        self.stderr.take()
+    }
+
+    /// Write data to the process stdin
+    pub fn write_stdin(&self, data: &[u8]) -> Result<()> {
+        let stdin = self.proc.stdin_writable().ok_or(Error::StdinClosed)?;
+        stdin.write(&Uint8Array::from(data));
+        Ok(())
+    }
+
+    /// Close the process stdin, signalling the end of input
+    pub fn close_stdin(&self) {
+        if let Some(stdin) = self.proc.stdin_writable() {
+            stdin.end();
+        }
+    }
+
+    /// Send a signal to the process
+    pub fn kill(&self, signal: KillSignal) -> Result<()> {
+        if self.proc.kill_with_signal(signal) {
+            Ok(())
+        } else {
+            Err(Error::NotRunning)
+        }
+    }
+
+    /// Returns the exit status if the process has exited
+    pub fn try_wait(&self) -> Result<Option<ExitStatus>> {
+        match self.termination.lock().unwrap().as_ref() {
+            Some(Ok(status)) => Ok(Some(status.clone())),
+            Some(Err(err)) => Err(Error::Custom(err.clone())),
+            None => Ok(None),
+        }
+    }
+
+    /// Wait for the process to exit
+    pub async fn wait(&self) -> Result<ExitStatus> {
+        // the channel is closed when the process terminates
+        self.exit.recv().await.ok();
+        self.try_wait()?.ok_or(Error::NotRunning)
+    }
+
+    /// Wait for the process to exit, returning [`Error::Timeout`]
+    /// if it does not exit within `timeout`
+    pub async fn wait_with_timeout(&self, timeout: Duration) -> Result<ExitStatus> {
+        select! {
+            status = self.wait().fuse() => status,
+            _ = sleep(timeout).fuse() => Err(Error::Timeout),
+        }
+    }
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        for (emitter, event, callback) in self.listeners.iter() {
+            emitter.off(event, callback.get_fn());
+        }
+    }
+}
@@ -12,6 +12,12 @@ pub enum Error {
     NotRunning,
     #[error("Child process reference is absent")]
     ProcIsAbsent,
+    #[error("Child process stdin is closed")]
+    StdinClosed,
+    #[error("Timeout")]
+    Timeout,
+    #[error("{0}")]
+    Custom(String),
     #[error("{0:?}")]
     Send(String),
     #[error("{0:?}")]
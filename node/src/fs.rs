@@ -1,7 +1,27 @@
+//!
+//! Node.js `fs` and `fs/promises` bindings.
+//!
+//! In addition to the raw bindings, this module provides async file and directory
+//! operations ([`read()`], [`write()`], [`rename()`], [`create_dir_all()`], [`read_dir()`],
+//! [`metadata()`], [`watch()`] etc.) following the `std::fs` naming.  These functions
+//! accept [`Path`] arguments (converting path separators on Windows in the same
+//! way as `workflow-store`) and return [`std::io::Result`], mapping Node.js error
+//! codes such as `ENOENT` to [`std::io::ErrorKind`], which allows the same error
+//! handling to be used on native and Node.js targets.
+//!
+
 use crate::require;
-use js_sys::Object;
+use js_sys::{Function, Object, Reflect, Uint8Array};
 use lazy_static::lazy_static;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use wasm_bindgen::prelude::*;
+use workflow_core::channel::{unbounded, Receiver};
+use workflow_core::runtime;
+use workflow_wasm::callback::*;
+use workflow_wasm::jserror::JsErrorData;
 
 lazy_static! {
     static ref FS: Fs = require("fs").unchecked_into();
@@ -25,10 +45,69 @@ extern "C" {
         options: Object,
     ) -> std::result::Result<JsValue, JsValue>;
 
+    #[wasm_bindgen(catch, js_name = readFile, method)]
+    async fn fs_read_file(this: &FsPromises, path: &str) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = readFile, method)]
+    async fn fs_read_file_with_encoding(
+        this: &FsPromises,
+        path: &str,
+        encoding: &str,
+    ) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = writeFile, method)]
+    async fn fs_write_file(
+        this: &FsPromises,
+        path: &str,
+        data: JsValue,
+    ) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = rename, method)]
+    async fn fs_rename(
+        this: &FsPromises,
+        from: &str,
+        to: &str,
+    ) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = mkdir, method)]
+    async fn fs_mkdir(
+        this: &FsPromises,
+        path: &str,
+        options: Object,
+    ) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = stat, method)]
+    async fn fs_stat(this: &FsPromises, path: &str) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = unlink, method)]
+    async fn fs_unlink(this: &FsPromises, path: &str) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = rm, method)]
+    async fn fs_rm(
+        this: &FsPromises,
+        path: &str,
+        options: Object,
+    ) -> std::result::Result<JsValue, JsValue>;
+
     #[wasm_bindgen(extends = Object)]
     #[derive(Clone)]
     pub type Fs;
 
+    #[wasm_bindgen(catch, js_name = watch, method)]
+    fn fs_watch(
+        this: &Fs,
+        path: &str,
+        options: Object,
+        listener: &Function,
+    ) -> std::result::Result<FsWatcher, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone)]
+    pub type FsWatcher;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &FsWatcher);
+
     #[wasm_bindgen(js_name = readdirSync, method)]
     pub fn fs_readdir_sync(this: &Fs, path: &str, callback: js_sys::Function);
 
@@ -124,3 +203,309 @@ pub fn rename_sync(from: &str, to: &str) -> std::result::Result<(), JsValue> {
 pub fn stat_sync(path: &str) -> std::result::Result<JsValue, JsValue> {
     FS.fs_stat_sync(path)
 }
+
+/// Convert a Node.js error to [`std::io::Error`], mapping
+/// the error `code` (e.g. `ENOENT`) to [`std::io::ErrorKind`]
+pub fn to_io_error(err: JsValue) -> io::Error {
+    let err = JsErrorData::from(err);
+    let kind = match err.code().as_deref() {
+        Some("ENOENT") => ErrorKind::NotFound,
+        Some("EEXIST") => ErrorKind::AlreadyExists,
+        Some("EACCES") | Some("EPERM") => ErrorKind::PermissionDenied,
+        Some("EINVAL") => ErrorKind::InvalidInput,
+        Some("EINTR") => ErrorKind::Interrupted,
+        Some("EAGAIN") => ErrorKind::WouldBlock,
+        Some("EPIPE") => ErrorKind::BrokenPipe,
+        _ => ErrorKind::Other,
+    };
+    io::Error::new(kind, err.to_string())
+}
+
+/// Path conversion to the current platform (matching `workflow_store::fs::ToPlatform`)
+fn to_platform_string(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if runtime::is_windows() {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    }
+}
+
+fn options(entries: &[(&str, JsValue)]) -> Object {
+    let options = Object::new();
+    for (key, value) in entries {
+        Reflect::set(&options, &(*key).into(), value).ok();
+    }
+    options
+}
+
+/// Read the entire contents of a file
+pub async fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    let path = to_platform_string(path.as_ref());
+    let data = FSP.fs_read_file(&path).await.map_err(to_io_error)?;
+    Ok(Uint8Array::new(&data).to_vec())
+}
+
+/// Read the entire contents of a file as UTF-8 text
+pub async fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = to_platform_string(path.as_ref());
+    let text = FSP
+        .fs_read_file_with_encoding(&path, "utf-8")
+        .await
+        .map_err(to_io_error)?;
+    text.as_string()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "file data is not a string"))
+}
+
+/// Write data to a file, replacing the file if it exists
+pub async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = to_platform_string(path.as_ref());
+    let data = Uint8Array::from(contents.as_ref());
+    FSP.fs_write_file(&path, data.into())
+        .await
+        .map_err(to_io_error)?;
+    Ok(())
+}
+
+/// Rename a file or directory, replacing the destination file if it exists
+pub async fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let from = to_platform_string(from.as_ref());
+    let to = to_platform_string(to.as_ref());
+    FSP.fs_rename(&from, &to).await.map_err(to_io_error)?;
+    Ok(())
+}
+
+/// Create a directory
+pub async fn create_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = to_platform_string(path.as_ref());
+    FSP.fs_mkdir(&path, Object::new())
+        .await
+        .map_err(to_io_error)?;
+    Ok(())
+}
+
+/// Create a directory and all of its missing parent directories
+pub async fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = to_platform_string(path.as_ref());
+    FSP.fs_mkdir(&path, options(&[("recursive", true.into())]))
+        .await
+        .map_err(to_io_error)?;
+    Ok(())
+}
+
+/// Remove a file
+pub async fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = to_platform_string(path.as_ref());
+    FSP.fs_unlink(&path).await.map_err(to_io_error)?;
+    Ok(())
+}
+
+/// Remove a directory and all of its contents
+pub async fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = to_platform_string(path.as_ref());
+    FSP.fs_rm(&path, options(&[("recursive", true.into())]))
+        .await
+        .map_err(to_io_error)?;
+    Ok(())
+}
+
+/// File metadata returned by [`metadata()`]. Timestamps are
+/// in seconds since the Unix epoch (as in `workflow_store::fs::Metadata`).
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    len: u64,
+    is_dir: bool,
+    is_file: bool,
+    created: u64,
+    modified: u64,
+    accessed: u64,
+}
+
+impl Metadata {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.is_file
+    }
+
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+
+    pub fn modified(&self) -> u64 {
+        self.modified
+    }
+
+    pub fn accessed(&self) -> u64 {
+        self.accessed
+    }
+}
+
+fn call_bool(object: &JsValue, method: &str) -> bool {
+    Reflect::get(object, &method.into())
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok())
+        .and_then(|f| f.call0(object).ok())
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn get_f64(object: &JsValue, property: &str) -> f64 {
+    Reflect::get(object, &property.into())
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or_default()
+}
+
+impl From<JsValue> for Metadata {
+    fn from(stats: JsValue) -> Self {
+        Metadata {
+            len: get_f64(&stats, "size") as u64,
+            is_dir: call_bool(&stats, "isDirectory"),
+            is_file: call_bool(&stats, "isFile"),
+            created: (get_f64(&stats, "birthtimeMs") / 1000.0) as u64,
+            modified: (get_f64(&stats, "mtimeMs") / 1000.0) as u64,
+            accessed: (get_f64(&stats, "atimeMs") / 1000.0) as u64,
+        }
+    }
+}
+
+/// Obtain file or directory metadata
+pub async fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    let path = to_platform_string(path.as_ref());
+    let stats = FSP.fs_stat(&path).await.map_err(to_io_error)?;
+    Ok(stats.into())
+}
+
+/// Directory entry returned by [`read_dir()`]
+#[derive(Clone, Debug)]
+pub struct DirEntry {
+    path: PathBuf,
+    file_name: String,
+    is_dir: bool,
+}
+
+impl DirEntry {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+/// Read directory entries
+pub async fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<Vec<DirEntry>> {
+    let path = path.as_ref();
+    let entries = FSP
+        .fs_readdir_with_options(
+            &to_platform_string(path),
+            options(&[("withFileTypes", true.into())]),
+        )
+        .await
+        .map_err(to_io_error)?;
+    let entries = js_sys::Array::from(&entries)
+        .iter()
+        .filter_map(|dirent| {
+            let file_name = Reflect::get(&dirent, &"name".into()).ok()?.as_string()?;
+            Some(DirEntry {
+                path: path.join(&file_name),
+                is_dir: call_bool(&dirent, "isDirectory"),
+                file_name,
+            })
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Kind of the change reported by [`Watcher`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchEventKind {
+    /// File contents changed
+    Change,
+    /// File was created, removed or renamed
+    Rename,
+}
+
+/// File system change reported by [`Watcher`]
+#[derive(Clone, Debug)]
+pub struct WatchEvent {
+    pub kind: WatchEventKind,
+    /// Name of the file that changed (relative to the watched directory)
+    pub file_name: Option<String>,
+}
+
+/// File system watcher created by [`watch()`], yielding [`WatchEvent`]
+/// values as a [`futures::Stream`]. The watcher is closed when dropped.
+pub struct Watcher {
+    watcher: FsWatcher,
+    receiver: Pin<Box<Receiver<WatchEvent>>>,
+    _callback: Callback<dyn FnMut(JsValue, JsValue)>,
+}
+
+unsafe impl Send for Watcher {}
+unsafe impl Sync for Watcher {}
+
+impl Watcher {
+    /// Receiver of watch events (an alternative to polling the watcher as a stream)
+    pub fn receiver(&self) -> &Receiver<WatchEvent> {
+        &self.receiver
+    }
+}
+
+impl futures::Stream for Watcher {
+    type Item = WatchEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.as_mut().poll_next(cx)
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.watcher.close();
+    }
+}
+
+/// Watch a file or directory for changes (`recursive` watches
+/// subdirectories; not supported on all platforms)
+pub fn watch<P: AsRef<Path>>(path: P, recursive: bool) -> io::Result<Watcher> {
+    let path = to_platform_string(path.as_ref());
+    let (sender, receiver) = unbounded();
+    let callback = Callback::create(move |kind: JsValue, file_name: JsValue| {
+        let kind = match kind.as_string().as_deref() {
+            Some("rename") => WatchEventKind::Rename,
+            _ => WatchEventKind::Change,
+        };
+        let file_name = file_name.as_string();
+        sender.try_send(WatchEvent { kind, file_name }).ok();
+    });
+    let watcher = FS
+        .fs_watch(
+            &path,
+            options(&[("recursive", recursive.into())]),
+            callback.get_fn(),
+        )
+        .map_err(to_io_error)?;
+
+    Ok(Watcher {
+        watcher,
+        receiver: Box::pin(receiver),
+        _callback: callback,
+    })
+}
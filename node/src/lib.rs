@@ -10,6 +10,7 @@
 //!
 
 pub mod child_process;
+pub mod command;
 pub mod error;
 pub mod fs;
 pub mod process;
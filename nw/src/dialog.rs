@@ -0,0 +1,147 @@
+//!
+//! Async file dialogs.
+//!
+//! NW.js displays native open and save dialogs using `<input type="file">`
+//! elements extended with the `nwsaveas`, `nwdirectory` and `nwworkingdir`
+//! attributes.  [`FileDialog`] creates and manages such an element,
+//! returning the paths selected by the user.
+//!
+//! # Synopsis
+//! ```ignore
+//! use workflow_nw::dialog::FileDialog;
+//!
+//! let files = FileDialog::new()
+//!     .with_filter(".json")
+//!     .with_filter(".txt")
+//!     .with_multiple(true)
+//!     .with_default_directory("~/Documents")
+//!     .open()
+//!     .await?;
+//!
+//! if let Some(path) = FileDialog::new().save("wallet.json").await? {
+//!     workflow_store::fs::write_string(&path, &data).await?;
+//! }
+//!
+//! let folder = FileDialog::new().open_directory().await?;
+//! ```
+//!
+
+use crate::result::Result;
+use js_sys::Reflect;
+use std::path::PathBuf;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlInputElement;
+use workflow_core::channel::oneshot;
+use workflow_dom::utils::document;
+use workflow_wasm::prelude::*;
+
+/// Builder and launcher for native file dialogs
+/// (see the [module](self) documentation)
+#[derive(Debug, Clone, Default)]
+pub struct FileDialog {
+    filters: Vec<String>,
+    multiple: bool,
+    default_directory: Option<String>,
+}
+
+impl FileDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an accepted file type, as a file extension (e.g. `".json"`)
+    /// or a MIME type (e.g. `"image/*"`)
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filters.push(filter.to_string());
+        self
+    }
+
+    /// Allow selection of multiple files (applies to [`FileDialog::open()`])
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Directory displayed when the dialog is opened (`~` is resolved to the home folder)
+    pub fn with_default_directory(mut self, directory: &str) -> Self {
+        self.default_directory = Some(directory.to_string());
+        self
+    }
+
+    /// Display the open file dialog and return the selected files.
+    /// Returns an empty list if the dialog is cancelled.
+    pub async fn open(&self) -> Result<Vec<PathBuf>> {
+        let input = self.create_input()?;
+        input.set_multiple(self.multiple);
+        self.show(&input).await?;
+        selected_files(&input)
+    }
+
+    /// Display the directory selection dialog.
+    /// Returns `None` if the dialog is cancelled.
+    pub async fn open_directory(&self) -> Result<Option<PathBuf>> {
+        let input = self.create_input()?;
+        input.set_attribute("nwdirectory", "")?;
+        self.show(&input).await?;
+        Ok(selected_files(&input)?.into_iter().next())
+    }
+
+    /// Display the save file dialog with `filename` as the suggested file name.
+    /// Returns `None` if the dialog is cancelled.
+    pub async fn save(&self, filename: &str) -> Result<Option<PathBuf>> {
+        let input = self.create_input()?;
+        input.set_attribute("nwsaveas", filename)?;
+        self.show(&input).await?;
+        let path = input.value();
+        Ok((!path.is_empty()).then(|| PathBuf::from(path)))
+    }
+
+    fn create_input(&self) -> Result<HtmlInputElement> {
+        let input = document()
+            .create_element("input")?
+            .dyn_into::<HtmlInputElement>()
+            .map_err(|_| "Unable to create input element")?;
+        input.set_type("file");
+        if !self.filters.is_empty() {
+            input.set_accept(&self.filters.join(","));
+        }
+        if let Some(directory) = self.default_directory.as_ref() {
+            let directory = workflow_store::fs::resolve_path(directory)?;
+            input.set_attribute("nwworkingdir", &directory.to_string_lossy())?;
+        }
+        Ok(input)
+    }
+
+    async fn show(&self, input: &HtmlInputElement) -> Result<()> {
+        let (sender, receiver) = oneshot();
+        let callback = callback!(move |_event: web_sys::Event| {
+            sender.try_send(()).ok();
+        });
+        // `cancel` is dispatched when the dialog is dismissed
+        for event in ["change", "cancel"] {
+            input.add_event_listener_with_callback(event, callback.get_fn())?;
+        }
+
+        input.click();
+        receiver.recv().await?;
+
+        for event in ["change", "cancel"] {
+            input.remove_event_listener_with_callback(event, callback.get_fn())?;
+        }
+        Ok(())
+    }
+}
+
+/// Paths of the selected files (NW.js extends `File` objects with the `path` property)
+fn selected_files(input: &HtmlInputElement) -> Result<Vec<PathBuf>> {
+    let Some(files) = input.files() else {
+        return Ok(vec![]);
+    };
+    let mut paths = Vec::with_capacity(files.length() as usize);
+    for file in (0..files.length()).filter_map(|idx| files.item(idx)) {
+        if let Some(path) = Reflect::get(&file, &"path".into())?.as_string() {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    Ok(paths)
+}
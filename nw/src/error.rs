@@ -3,6 +3,7 @@ use thiserror::Error;
 use wasm_bindgen::JsValue;
 use workflow_core::channel::{RecvError, TrySendError};
 use workflow_core::id::Id;
+use workflow_wasm::jserror::JsErrorData;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -19,7 +20,7 @@ pub enum Error {
     NW(#[from] nw_sys::error::Error),
 
     #[error("Error: {0}")]
-    JsValue(String),
+    JsValue(JsErrorData),
 
     #[error("Poison Error: {0}")]
     PoisonError(String),
@@ -51,9 +52,18 @@ pub enum Error {
     #[error("Broadcast data is not an object")]
     BroadcastDataNotObject,
 
+    #[error("Invalid shortcut key: `{0}`")]
+    InvalidShortcut(String),
+
+    #[error("Shortcut `{0}` is already registered")]
+    ShortcutConflict(String),
+
     #[error(transparent)]
     Wasm(#[from] workflow_wasm::error::Error),
 
+    #[error(transparent)]
+    Store(#[from] workflow_store::error::Error),
+
     #[error(transparent)]
     Ipc(#[from] crate::ipc::error::Error),
     // #[error(transparent)]
@@ -74,7 +84,7 @@ impl From<&str> for Error {
 
 impl From<JsValue> for Error {
     fn from(v: JsValue) -> Self {
-        Self::JsValue(format!("{v:?}"))
+        Self::JsValue(v.into())
     }
 }
 
@@ -89,8 +99,10 @@ where
 
 impl From<Error> for JsValue {
     fn from(err: Error) -> JsValue {
-        let s: String = err.to_string();
-        JsValue::from_str(&s)
+        match err {
+            Error::JsValue(error) => error.into(),
+            err => JsValue::from_str(&err.to_string()),
+        }
     }
 }
 
@@ -0,0 +1,243 @@
+//!
+//! Typed message channels between NW.js windows.
+//!
+//! [`IpcChannel`] binds a named channel in the current window and exchanges
+//! serde-serialized (JSON-encoded) messages with the channel of the same name
+//! bound in another window.  Received messages and peer lifecycle events are
+//! delivered as [`ChannelEvent`] values via [`IpcChannel::recv()`] or by polling
+//! the channel as a [`Stream`].
+//!
+//! The main window typically connects to a child window using
+//! [`IpcChannel::try_connect()`], while the child window creates the channel
+//! using [`IpcChannel::try_new()`]; the child then replies to the window that
+//! sent the first message.  When the peer window is closed (or reloaded),
+//! [`ChannelEvent::Closed`] is delivered and subsequent sends fail with
+//! [`Error::PeerClosed`].
+//!
+//! ```ignore
+//! // main window
+//! let channel = IpcChannel::<Message>::try_connect("status", &child)?;
+//! channel.send(&Message::Ping)?;
+//!
+//! // child window
+//! let channel = IpcChannel::<Message>::try_new("status")?;
+//! while let Ok(event) = channel.recv().await {
+//!     match event {
+//!         ChannelEvent::Message(Message::Ping) => channel.send(&Message::Pong)?,
+//!         ChannelEvent::Closed => break,
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+
+use crate::ipc::imports::*;
+use std::cell::RefCell;
+use std::task::{Context, Poll};
+use workflow_dom::utils::window;
+
+/// Property of the DOM window object holding handlers of bound channels
+const CHANNELS_PROPERTY: &str = "__workflow_ipc_channels";
+
+/// Event delivered by the [`IpcChannel`]
+#[derive(Debug, Clone)]
+pub enum ChannelEvent<T> {
+    /// Message received from the peer window
+    Message(T),
+    /// The peer window has been closed or unloaded
+    Closed,
+}
+
+type HandlerCallback = Callback<dyn FnMut(JsValue, JsValue)>;
+type UnloadCallback = Callback<CallbackClosureWithoutResult<JsValue>>;
+
+struct Peer {
+    window: web_sys::Window,
+    unload: UnloadCallback,
+}
+
+struct Inner<T> {
+    name: String,
+    local: web_sys::Window,
+    peer: RefCell<Option<Peer>>,
+    sender: Sender<ChannelEvent<T>>,
+    handler: RefCell<Option<HandlerCallback>>,
+}
+
+impl<T> Inner<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    fn connect(self: &Rc<Self>, peer: web_sys::Window) -> Result<()> {
+        self.disconnect();
+
+        let this = Rc::downgrade(self);
+        let unload: UnloadCallback = Callback::create(move |_event: JsValue| {
+            if let Some(this) = this.upgrade() {
+                this.disconnect();
+                this.sender.try_send(ChannelEvent::Closed).ok();
+            }
+        });
+        peer.add_event_listener_with_callback("unload", unload.get_fn())?;
+        self.peer.replace(Some(Peer {
+            window: peer,
+            unload,
+        }));
+        Ok(())
+    }
+
+    fn disconnect(&self) {
+        if let Some(Peer { window, unload }) = self.peer.borrow_mut().take() {
+            window
+                .remove_event_listener_with_callback("unload", unload.get_fn())
+                .ok();
+        }
+    }
+
+    fn handle(self: &Rc<Self>, message: JsValue, source: JsValue) {
+        if self.peer.borrow().is_none() {
+            if let Err(err) = self.connect(source.unchecked_into()) {
+                log_error!("IPC channel `{}`: unable to connect: {err}", self.name);
+            }
+        }
+
+        let Some(json) = message.as_string() else {
+            log_error!("IPC channel `{}`: received a non-string message", self.name);
+            return;
+        };
+        match serde_json::from_str::<T>(&json) {
+            Ok(message) => {
+                self.sender.try_send(ChannelEvent::Message(message)).ok();
+            }
+            Err(err) => {
+                log_error!("IPC channel `{}`: unable to deserialize: {err}", self.name);
+            }
+        }
+    }
+}
+
+/// Typed message channel between windows (see the [module](self) documentation)
+pub struct IpcChannel<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    inner: Rc<Inner<T>>,
+    receiver: Pin<Box<Receiver<ChannelEvent<T>>>>,
+}
+
+impl<T> IpcChannel<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    /// Bind the channel `name` in the current window. The channel
+    /// connects to the window that sends the first message.
+    pub fn try_new(name: &str) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let inner = Rc::new(Inner {
+            name: name.to_string(),
+            local: window(),
+            peer: RefCell::new(None),
+            sender,
+            handler: RefCell::new(None),
+        });
+
+        let this = Rc::downgrade(&inner);
+        let handler: HandlerCallback =
+            Callback::create(move |message: JsValue, source: JsValue| {
+                if let Some(this) = this.upgrade() {
+                    this.handle(message, source);
+                }
+            });
+        let channels = match Reflect::get(&inner.local, &CHANNELS_PROPERTY.into())? {
+            channels if channels.is_object() => channels,
+            _ => {
+                let channels = Object::new();
+                Reflect::set(&inner.local, &CHANNELS_PROPERTY.into(), &channels)?;
+                channels.into()
+            }
+        };
+        if Reflect::has(&channels, &name.into())? {
+            return Err(Error::Custom(format!(
+                "IPC channel `{name}` is already bound in this window"
+            )));
+        }
+        Reflect::set(&channels, &name.into(), handler.get_fn())?;
+        inner.handler.replace(Some(handler));
+
+        Ok(IpcChannel {
+            inner,
+            receiver: Box::pin(receiver),
+        })
+    }
+
+    /// Bind the channel `name` in the current window and
+    /// connect it to the channel of the same name in `window`
+    pub fn try_connect(name: &str, window: &nw_sys::Window) -> Result<Self> {
+        let channel = Self::try_new(name)?;
+        channel.inner.connect(window.window())?;
+        Ok(channel)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Returns `true` if the channel is connected to a peer window
+    pub fn is_connected(&self) -> bool {
+        self.inner.peer.borrow().is_some()
+    }
+
+    /// Send a message to the peer window
+    pub fn send(&self, message: &T) -> Result<()> {
+        let peer = self.inner.peer.borrow();
+        let Some(Peer { window, .. }) = peer.as_ref() else {
+            return Err(Error::PeerClosed);
+        };
+        let handler = Reflect::get(window, &CHANNELS_PROPERTY.into())
+            .and_then(|channels| Reflect::get(&channels, &self.inner.name.as_str().into()))
+            .ok()
+            // `instanceof` checks fail for objects created in another window
+            .filter(|handler| handler.is_function())
+            .map(|handler| handler.unchecked_into::<Function>())
+            .ok_or_else(|| Error::ChannelNotFound(self.inner.name.clone()))?;
+
+        let json = serde_json::to_string(message)?;
+        handler.call2(&JsValue::UNDEFINED, &json.into(), &self.inner.local)?;
+        Ok(())
+    }
+
+    /// Receive the next channel event
+    pub async fn recv(&self) -> Result<ChannelEvent<T>> {
+        Ok(self.receiver.recv().await?)
+    }
+
+    /// Receiver of channel events (an alternative to polling the channel as a [`Stream`])
+    pub fn receiver(&self) -> &Receiver<ChannelEvent<T>> {
+        &self.receiver
+    }
+}
+
+impl<T> Stream for IpcChannel<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    type Item = ChannelEvent<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Drop for IpcChannel<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    fn drop(&mut self) {
+        self.inner.disconnect();
+        if self.inner.handler.borrow_mut().take().is_some() {
+            if let Ok(channels) = Reflect::get(&self.inner.local, &CHANNELS_PROPERTY.into()) {
+                Reflect::delete_property(&channels.into(), &self.inner.name.as_str().into()).ok();
+            }
+        }
+    }
+}
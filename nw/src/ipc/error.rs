@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use wasm_bindgen::prelude::JsValue;
 use workflow_core::channel::*;
+use workflow_wasm::jserror::JsErrorData;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -13,7 +14,7 @@ pub enum Error {
     IO(#[from] std::io::Error),
 
     #[error("Error: {0}")]
-    JsValue(String),
+    JsValue(JsErrorData),
 
     #[error("IPC channel send error")]
     ChannelSendError,
@@ -35,6 +36,15 @@ pub enum Error {
 
     #[error("{0}")]
     ChannelError(String),
+
+    #[error("IPC channel `{0}` is not bound in the target window")]
+    ChannelNotFound(String),
+
+    #[error("IPC channel peer window is closed")]
+    PeerClosed,
+
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
 }
 
 impl From<String> for Error {
@@ -51,14 +61,16 @@ impl From<&str> for Error {
 
 impl From<JsValue> for Error {
     fn from(v: JsValue) -> Self {
-        Self::JsValue(format!("{v:?}"))
+        Self::JsValue(v.into())
     }
 }
 
 impl From<Error> for JsValue {
     fn from(err: Error) -> JsValue {
-        let s: String = err.to_string();
-        JsValue::from_str(&s)
+        match err {
+            Error::JsValue(error) => error.into(),
+            err => JsValue::from_str(&err.to_string()),
+        }
     }
 }
 
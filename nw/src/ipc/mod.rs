@@ -1,4 +1,5 @@
 // pub mod broadcast;
+pub mod channel;
 pub mod error;
 pub mod id;
 pub mod imports;
@@ -11,6 +12,7 @@ pub mod result;
 pub mod target;
 pub mod types;
 
+pub use channel::{ChannelEvent, IpcChannel};
 pub use error::ResponseError;
 pub use ipc::{get_ipc_target, Ipc, IpcDispatch};
 pub use method::Method;
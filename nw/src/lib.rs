@@ -105,13 +105,16 @@
 //!
 //! ```
 pub mod application;
+pub mod dialog;
 pub mod error;
 pub mod global;
 pub mod ipc;
 pub mod media;
 pub mod menu;
+pub mod notification;
 pub mod prelude;
 pub mod result;
 pub mod shortcut;
 pub mod tray;
 pub mod window;
+pub mod window_state;
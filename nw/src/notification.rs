@@ -0,0 +1,339 @@
+//!
+//! Desktop notifications.
+//!
+//! [`NotificationBuilder`] displays a system notification using the
+//! `chrome.notifications` API available in NW.js (and Chrome extensions),
+//! supporting action buttons and click callbacks.  If the API is not available,
+//! the [Web Notifications API](https://developer.mozilla.org/en-US/docs/Web/API/Notifications_API)
+//! is used instead, requesting the user permission if needed (action buttons
+//! are not supported by the Web Notifications API and are ignored).
+//!
+//! # Synopsis
+//! ```ignore
+//! use workflow_nw::notification::NotificationBuilder;
+//!
+//! NotificationBuilder::new("Connection lost")
+//!     .with_message("Unable to reach the RPC server, retrying...")
+//!     .with_icon("/resources/icons/icon.png")
+//!     .with_action("Retry now")
+//!     .with_action("Settings")
+//!     .with_click(move || window.focus())
+//!     .with_action_callback(move |index| match index {
+//!         0 => rpc.reconnect(),
+//!         _ => settings.show(),
+//!     })
+//!     .show()
+//!     .await?;
+//! ```
+//!
+
+use crate::result::Result;
+use js_sys::{Function, Reflect};
+use nw_sys::notifications;
+use nw_sys::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{NotificationOptions, NotificationPermission};
+use workflow_core::id::Id;
+use workflow_core::task::dispatch;
+use workflow_wasm::prelude::*;
+
+type Handler<T> = RefCell<Option<Box<T>>>;
+
+#[derive(Default)]
+struct Handlers {
+    click: Handler<dyn FnMut()>,
+    action: Handler<dyn FnMut(usize)>,
+    close: Handler<dyn FnMut()>,
+    // Web Notification event callbacks
+    callbacks: RefCell<Option<CallbackMap>>,
+}
+
+impl Handlers {
+    fn click(&self) {
+        if let Some(click) = self.click.borrow_mut().as_mut() {
+            click();
+        }
+    }
+
+    fn action(&self, index: usize) {
+        if let Some(action) = self.action.borrow_mut().as_mut() {
+            action(index);
+        }
+    }
+
+    fn close(&self) {
+        if let Some(close) = self.close.borrow_mut().as_mut() {
+            close();
+        }
+    }
+}
+
+thread_local! {
+    static HANDLERS: RefCell<HashMap<String, Rc<Handlers>>> = RefCell::new(HashMap::new());
+    static LISTENERS: RefCell<Option<CallbackMap>> = const { RefCell::new(None) };
+}
+
+fn handlers(id: &str) -> Option<Rc<Handlers>> {
+    HANDLERS.with(|handlers| handlers.borrow().get(id).cloned())
+}
+
+fn remove_handlers(id: &str) -> Option<Rc<Handlers>> {
+    HANDLERS.with(|handlers| handlers.borrow_mut().remove(id))
+}
+
+/// Invoke the close handler and release the notification handlers
+fn closed(id: &str) {
+    if let Some(handlers) = remove_handlers(id) {
+        handlers.close();
+        // the handlers may own the callback being executed,
+        // release them once the callback returns
+        dispatch(async move { drop(handlers) });
+    }
+}
+
+/// Returns `true` if the `chrome.notifications` API is available
+pub fn is_chrome_notifications_supported() -> bool {
+    Reflect::get(&js_sys::global(), &"chrome".into())
+        .and_then(|chrome| Reflect::get(&chrome, &"notifications".into()))
+        .map(|notifications| !notifications.is_undefined())
+        .unwrap_or(false)
+}
+
+/// Register `chrome.notifications` event listeners dispatching
+/// events to the handlers of the corresponding notification
+fn register_chrome_listeners() -> Result<()> {
+    LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        if listeners.is_some() {
+            return Ok(());
+        }
+        let callbacks = CallbackMap::new();
+
+        let clicked = callback!(move |id: String| {
+            if let Some(handlers) = handlers(&id) {
+                handlers.click();
+            }
+        });
+        notifications::on_clicked(clicked.get_fn());
+        callbacks.retain(clicked)?;
+
+        let button_clicked = callback!(move |id: String, index: u32| {
+            if let Some(handlers) = handlers(&id) {
+                handlers.action(index as usize);
+            }
+        });
+        notifications::on_button_clicked(button_clicked.get_fn());
+        callbacks.retain(button_clicked)?;
+
+        let on_closed = callback!(move |id: String, _by_user: JsValue| {
+            closed(&id);
+        });
+        notifications::on_closed(on_closed.get_fn());
+        callbacks.retain(on_closed)?;
+
+        listeners.replace(callbacks);
+        Ok(())
+    })
+}
+
+/// Notification builder (see the [module](self) documentation)
+pub struct NotificationBuilder {
+    title: String,
+    message: String,
+    icon: Option<String>,
+    actions: Vec<String>,
+    require_interaction: bool,
+    silent: bool,
+    handlers: Handlers,
+}
+
+impl NotificationBuilder {
+    pub fn new(title: &str) -> Self {
+        NotificationBuilder {
+            title: title.to_string(),
+            message: String::new(),
+            icon: None,
+            actions: Vec::new(),
+            require_interaction: false,
+            silent: false,
+            handlers: Handlers::default(),
+        }
+    }
+
+    pub fn with_message(mut self, message: &str) -> Self {
+        self.message = message.to_string();
+        self
+    }
+
+    /// Notification icon URL (required by `chrome.notifications`;
+    /// an empty transparent image is used if not supplied)
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Add an action button (at most two buttons are displayed)
+    pub fn with_action(mut self, title: &str) -> Self {
+        self.actions.push(title.to_string());
+        self
+    }
+
+    /// Keep the notification visible until the user dismisses it
+    pub fn with_require_interaction(mut self, require_interaction: bool) -> Self {
+        self.require_interaction = require_interaction;
+        self
+    }
+
+    pub fn with_silent(mut self, silent: bool) -> Self {
+        self.silent = silent;
+        self
+    }
+
+    /// Invoke `callback` when the notification is clicked
+    pub fn with_click<F>(self, callback: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.handlers.click.replace(Some(Box::new(callback)));
+        self
+    }
+
+    /// Invoke `callback` with the index of the action button clicked
+    pub fn with_action_callback<F>(self, callback: F) -> Self
+    where
+        F: FnMut(usize) + 'static,
+    {
+        self.handlers.action.replace(Some(Box::new(callback)));
+        self
+    }
+
+    /// Invoke `callback` when the notification is closed
+    pub fn with_close<F>(self, callback: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.handlers.close.replace(Some(Box::new(callback)));
+        self
+    }
+
+    /// Display the notification
+    pub async fn show(self) -> Result<Notification> {
+        if is_chrome_notifications_supported() {
+            self.show_chrome()
+        } else {
+            self.show_web().await
+        }
+    }
+
+    fn show_chrome(self) -> Result<Notification> {
+        register_chrome_listeners()?;
+
+        let id = Id::new().to_string();
+        let buttons = self
+            .actions
+            .iter()
+            .map(|title| notifications::Button::new().title(title))
+            .collect();
+        let options = notifications::Options::new()
+            .set_type(notifications::TemplateType::Basic)
+            .title(&self.title)
+            .message(&self.message)
+            .icon_url(self.icon.as_deref().unwrap_or(EMPTY_ICON))
+            .buttons(buttons)
+            .require_interaction(self.require_interaction)
+            .silent(self.silent);
+
+        HANDLERS.with(|handlers| {
+            handlers
+                .borrow_mut()
+                .insert(id.clone(), Rc::new(self.handlers))
+        });
+        notifications::create(Some(id.clone()), &options, None);
+
+        Ok(Notification { id, web: None })
+    }
+
+    async fn show_web(self) -> Result<Notification> {
+        let mut permission = web_sys::Notification::permission();
+        if permission == NotificationPermission::Default {
+            let result = JsFuture::from(web_sys::Notification::request_permission()?).await?;
+            permission = NotificationPermission::from_js_value(&result)
+                .unwrap_or(NotificationPermission::Denied);
+        }
+        if permission != NotificationPermission::Granted {
+            return Err("Notification permission denied".into());
+        }
+
+        let id = Id::new().to_string();
+        let mut options = NotificationOptions::new();
+        options
+            .body(&self.message)
+            .require_interaction(self.require_interaction)
+            .silent(Some(self.silent))
+            .tag(&id);
+        if let Some(icon) = self.icon.as_ref() {
+            options.icon(icon);
+        }
+        let notification = web_sys::Notification::new_with_options(&self.title, &options)?;
+
+        let callbacks = CallbackMap::new();
+        let id_ = id.clone();
+        let click = callback!(move |_event: JsValue| {
+            if let Some(handlers) = handlers(&id_) {
+                handlers.click();
+            }
+        });
+        notification.set_onclick(Some(click.get_fn()));
+        callbacks.retain(click)?;
+
+        let id_ = id.clone();
+        let close = callback!(move |_event: JsValue| {
+            closed(&id_);
+        });
+        notification.set_onclose(Some(close.get_fn()));
+        callbacks.retain(close)?;
+
+        self.handlers.callbacks.replace(Some(callbacks));
+        HANDLERS.with(|handlers| {
+            handlers
+                .borrow_mut()
+                .insert(id.clone(), Rc::new(self.handlers))
+        });
+
+        Ok(Notification {
+            id,
+            web: Some(notification),
+        })
+    }
+}
+
+/// Transparent 1x1 PNG used if a notification icon is not supplied
+const EMPTY_ICON: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+/// Displayed notification created by [`NotificationBuilder::show()`].
+/// Dropping the handle does not close the notification.
+pub struct Notification {
+    id: String,
+    web: Option<web_sys::Notification>,
+}
+
+impl Notification {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Close the notification
+    pub fn close(&self) {
+        match self.web.as_ref() {
+            Some(notification) => notification.close(),
+            None => {
+                notifications::clear(&self.id, None::<&Function>);
+                closed(&self.id);
+            }
+        }
+    }
+}
@@ -4,6 +4,9 @@
 pub use crate::application::Application;
 pub use crate::media::VideoConstraints;
 pub use crate::menu::{menu_separator, MenuItemBuilder, MenubarBuilder};
-pub use crate::shortcut::ShortcutBuilder;
-pub use crate::tray::TrayMenuBuilder;
+pub use crate::shortcut::{
+    is_global_shortcut_registered, register_global_shortcut, unregister_all_global_shortcuts,
+    unregister_global_shortcut, ShortcutBuilder,
+};
+pub use crate::tray::{TrayIcon, TrayMenuBuilder, TrayMenuItem};
 pub use crate::window;
@@ -25,10 +25,29 @@
 //! # }
 //! ```
 //!
+//! Global hotkeys can also be managed using [`register_global_shortcut()`]
+//! and [`unregister_global_shortcut()`].  Registered shortcuts are tracked by the
+//! crate, so an attempt to register a key combination that is already in use
+//! (regardless of modifier order or letter case) results in
+//! [`Error::ShortcutConflict`](crate::error::Error::ShortcutConflict).
+//! Failures reported by the operating system (e.g. the key being taken by
+//! another application) are delivered to the optional `failed` callback.
+//!
+//! ```ignore
+//! register_global_shortcut("Ctrl+Shift+H", move || {
+//!     window.toggle_visibility();
+//! }, None::<fn(String)>)?;
+//!
+//! unregister_global_shortcut("Shift+Ctrl+H")?;
+//! ```
+//!
 
 use crate::application::app;
+use crate::error::Error;
 use crate::result::Result;
 use nw_sys::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 use workflow_wasm::prelude::*;
 
@@ -178,3 +197,118 @@ impl ShortcutBuilder {
         })
     }
 }
+
+/// Global shortcut registered using [`register_global_shortcut()`]
+struct GlobalShortcut {
+    shortcut: nw_sys::Shortcut,
+    _callbacks: CallbackMap,
+}
+
+thread_local! {
+    static GLOBAL_SHORTCUTS: RefCell<HashMap<String, GlobalShortcut>> = RefCell::new(HashMap::new());
+}
+
+const MODIFIERS: [&str; 4] = ["ctrl", "alt", "shift", "command"];
+
+/// Normalize a shortcut key such as `"shift+Ctrl+a"` to a canonical
+/// form (`"ctrl+shift+a"`), used to detect conflicting registrations.
+fn normalize_key(key: &str) -> Result<String> {
+    let mut modifiers = [false; 4];
+    let mut code = None;
+    for part in key.split('+').map(str::trim) {
+        // a trailing `+` denotes the plus key, e.g. `Ctrl++`
+        let part = if part.is_empty() { "+" } else { part };
+        let part = part.to_lowercase();
+        if let Some(index) = MODIFIERS.iter().position(|m| *m == part) {
+            modifiers[index] = true;
+        } else if code.replace(part).is_some() {
+            return Err(Error::InvalidShortcut(key.to_string()));
+        }
+    }
+    let code = code.ok_or_else(|| Error::InvalidShortcut(key.to_string()))?;
+
+    let mut parts = MODIFIERS
+        .iter()
+        .zip(modifiers)
+        .filter_map(|(modifier, active)| active.then_some(*modifier))
+        .collect::<Vec<_>>();
+    parts.push(&code);
+    Ok(parts.join("+"))
+}
+
+/// Register a global hotkey (active even when the application is not focused).
+/// `callback` is invoked when the shortcut is pressed; `failed` is invoked
+/// with the error message if NW.js fails to register the key. Returns
+/// [`Error::ShortcutConflict`] if the key combination is already registered.
+pub fn register_global_shortcut<F, E>(key: &str, mut callback: F, failed: Option<E>) -> Result<()>
+where
+    F: FnMut() + 'static,
+    E: FnMut(String) + 'static,
+{
+    let id = normalize_key(key)?;
+    if is_global_shortcut_registered(key) {
+        return Err(Error::ShortcutConflict(key.to_string()));
+    }
+
+    let callbacks = CallbackMap::new();
+    let mut builder = ShortcutBuilder::new().key(key).active(move |_| {
+        callback();
+        Ok(())
+    });
+    if let Some(mut failed) = failed {
+        builder = builder.failed(move |err: JsValue| {
+            failed(err.as_string().unwrap_or_else(|| format!("{err:?}")));
+            Ok(())
+        });
+    }
+
+    let info = builder.finalize()?;
+    if let Some(callback) = info.active_callback {
+        callbacks.retain(callback)?;
+    }
+    if let Some(callback) = info.failed_callback {
+        callbacks.retain(callback)?;
+    }
+    nw_sys::app::register_global_hot_key(&info.shortcut);
+
+    GLOBAL_SHORTCUTS.with(|shortcuts| {
+        shortcuts.borrow_mut().insert(
+            id,
+            GlobalShortcut {
+                shortcut: info.shortcut,
+                _callbacks: callbacks,
+            },
+        );
+    });
+
+    Ok(())
+}
+
+/// Unregister a global hotkey previously registered using [`register_global_shortcut()`].
+/// Returns `false` if the key combination was not registered.
+pub fn unregister_global_shortcut(key: &str) -> Result<bool> {
+    let id = normalize_key(key)?;
+    let shortcut = GLOBAL_SHORTCUTS.with(|shortcuts| shortcuts.borrow_mut().remove(&id));
+    match shortcut {
+        Some(GlobalShortcut { shortcut, .. }) => {
+            nw_sys::app::unregister_global_hot_key(&shortcut);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Unregister all global hotkeys registered using [`register_global_shortcut()`]
+pub fn unregister_all_global_shortcuts() {
+    let shortcuts = GLOBAL_SHORTCUTS.with(|shortcuts| std::mem::take(&mut *shortcuts.borrow_mut()));
+    for (_, GlobalShortcut { shortcut, .. }) in shortcuts {
+        nw_sys::app::unregister_global_hot_key(&shortcut);
+    }
+}
+
+/// Returns `true` if the key combination is registered using [`register_global_shortcut()`]
+pub fn is_global_shortcut_registered(key: &str) -> bool {
+    normalize_key(key)
+        .map(|id| GLOBAL_SHORTCUTS.with(|shortcuts| shortcuts.borrow().contains_key(&id)))
+        .unwrap_or(false)
+}
@@ -48,6 +48,27 @@
 //!
 //! ```
 //!
+//! Tray menus can also be declared using [`TrayMenuItem`] entries, in which case
+//! [`TrayMenuBuilder::build_tray_icon()`] returns a [`TrayIcon`] that owns the menu
+//! callbacks and removes the tray when dropped:
+//!
+//! ```ignore
+//! let tray = TrayMenuBuilder::new()
+//!     .icon("resources/icons/tray-icon@2x.png")
+//!     .tooltip("My App")
+//!     .item(TrayMenuItem::item("Show", move || window.show()))
+//!     .item(TrayMenuItem::checkbox("Start on login", false, move |checked| {
+//!         settings.set_autostart(checked);
+//!     }))
+//!     .item(TrayMenuItem::submenu("Network", vec![
+//!         TrayMenuItem::item("Mainnet", move || select("mainnet")),
+//!         TrayMenuItem::item("Testnet", move || select("testnet")),
+//!     ]))
+//!     .item(TrayMenuItem::separator())
+//!     .item(TrayMenuItem::item("Exit", nw_sys::app::close_all_windows))
+//!     .build_tray_icon()?;
+//! ```
+//!
 
 use crate::application::app;
 use crate::result::Result;
@@ -57,6 +78,145 @@ use wasm_bindgen::prelude::*;
 use web_sys::MouseEvent;
 use workflow_wasm::prelude::*;
 
+/// Tray menu entry declared using [`TrayMenuBuilder::item()`]
+pub enum TrayMenuItem {
+    Item {
+        label: String,
+        callback: Box<dyn FnMut()>,
+    },
+    Checkbox {
+        label: String,
+        checked: bool,
+        callback: Box<dyn FnMut(bool)>,
+    },
+    Separator,
+    Submenu {
+        label: String,
+        items: Vec<TrayMenuItem>,
+    },
+}
+
+impl TrayMenuItem {
+    /// Menu item invoking `callback` when clicked
+    pub fn item<F>(label: &str, callback: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        TrayMenuItem::Item {
+            label: label.to_string(),
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Checkable menu item invoking `callback` with the new checked state when clicked
+    pub fn checkbox<F>(label: &str, checked: bool, callback: F) -> Self
+    where
+        F: FnMut(bool) + 'static,
+    {
+        TrayMenuItem::Checkbox {
+            label: label.to_string(),
+            checked,
+            callback: Box::new(callback),
+        }
+    }
+
+    pub fn separator() -> Self {
+        TrayMenuItem::Separator
+    }
+
+    /// Nested menu
+    pub fn submenu(label: &str, items: Vec<TrayMenuItem>) -> Self {
+        TrayMenuItem::Submenu {
+            label: label.to_string(),
+            items,
+        }
+    }
+
+    fn build(self, callbacks: &CallbackMap) -> Result<MenuItem> {
+        let item = match self {
+            TrayMenuItem::Item {
+                label,
+                mut callback,
+            } => {
+                let click = Callback::new(move |_: JsValue| -> std::result::Result<(), JsValue> {
+                    callback();
+                    Ok(())
+                });
+                let options = nw_sys::menu_item::Options::new()
+                    .label(&label)
+                    .click(click.as_ref());
+                callbacks.retain(click)?;
+                MenuItem::new(&options)
+            }
+            TrayMenuItem::Checkbox {
+                label,
+                checked,
+                mut callback,
+            } => {
+                let options = nw_sys::menu_item::Options::new()
+                    .set_type(MenuItemType::Checkbox)
+                    .label(&label)
+                    .checked(checked);
+                let item = MenuItem::new(&options);
+                let item_ = item.clone();
+                // NW.js toggles the checked state before invoking the click handler
+                let click = Callback::new(move |_: JsValue| -> std::result::Result<(), JsValue> {
+                    callback(item_.checked());
+                    Ok(())
+                });
+                item.set_click(click.as_ref());
+                callbacks.retain(click)?;
+                item
+            }
+            TrayMenuItem::Separator => MenuItem::new(&MenuItemType::Separator.into()),
+            TrayMenuItem::Submenu { label, items } => {
+                let submenu = build_menu(items, callbacks)?;
+                let options = nw_sys::menu_item::Options::new()
+                    .label(&label)
+                    .submenu(&submenu);
+                MenuItem::new(&options)
+            }
+        };
+        Ok(item)
+    }
+}
+
+fn build_menu(items: Vec<TrayMenuItem>, callbacks: &CallbackMap) -> Result<Menu> {
+    let menu = Menu::new();
+    for item in items {
+        menu.append(&item.build(callbacks)?);
+    }
+    Ok(menu)
+}
+
+/// System tray icon created by [`TrayMenuBuilder::build_tray_icon()`].
+/// Retains the tray callbacks and removes the tray when dropped.
+pub struct TrayIcon {
+    tray: Tray,
+    #[allow(dead_code)]
+    callbacks: CallbackMap,
+}
+
+impl TrayIcon {
+    pub fn tray(&self) -> &Tray {
+        &self.tray
+    }
+
+    pub fn set_tooltip(&self, tooltip: &str) {
+        self.tray.set_tooltip(tooltip);
+    }
+
+    pub fn set_icon(&self, icon: &str) {
+        self.tray.set_icon(icon);
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        self.tray.remove();
+    }
+}
+
 /// Provides a builder pattern for constructing a system tray menu
 /// for the application.
 ///
@@ -66,6 +226,7 @@ pub struct TrayMenuBuilder {
     pub menu: Option<Menu>,
     pub tooltip: Option<String>,
     pub callback: Option<Callback<CallbackClosure<MouseEvent>>>,
+    pub items: Vec<TrayMenuItem>,
 }
 
 impl Default for TrayMenuBuilder {
@@ -81,6 +242,7 @@ impl TrayMenuBuilder {
             menu: None,
             tooltip: None,
             callback: None,
+            items: Vec::new(),
         }
     }
 
@@ -183,6 +345,29 @@ impl TrayMenuBuilder {
         self.menu(submenu)
     }
 
+    /// Append a menu entry (used by [`TrayMenuBuilder::build_tray_icon()`])
+    pub fn item(mut self, item: TrayMenuItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Create the tray and the menu declared using [`TrayMenuBuilder::item()`].
+    /// The tray is removed when the returned [`TrayIcon`] is dropped.
+    pub fn build_tray_icon(mut self) -> Result<TrayIcon> {
+        let callbacks = CallbackMap::new();
+        let items = std::mem::take(&mut self.items);
+        if !items.is_empty() {
+            self.menu = Some(build_menu(items, &callbacks)?);
+        }
+
+        let (tray, callback) = self.build_impl()?;
+        if let Some(callback) = callback {
+            callbacks.retain(callback)?;
+        }
+
+        Ok(TrayIcon { tray, callbacks })
+    }
+
     pub fn build_impl(self) -> Result<(Tray, Option<Callback<CallbackClosure<MouseEvent>>>)> {
         let tray = Tray::new(&self.options);
 
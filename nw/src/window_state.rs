@@ -0,0 +1,301 @@
+//!
+//! Window state persistence (position, size and maximized state).
+//!
+//! [`WindowStateStore`] saves the window state to a JSON file (using
+//! [`workflow_store::fs`]) and restores it when the window is created.
+//! Saved state is validated against the currently connected screens, so a
+//! window that was last positioned on a disconnected monitor is moved back
+//! onto the primary screen instead of reopening off-screen.
+//!
+//! # Synopsis
+//! ```ignore
+//! let store = WindowStateStore::try_new("~/.my-app/window.json")?;
+//! let state = store.load().await;
+//!
+//! let options = nw_sys::window::Options::new().title("My App");
+//! let options = match state.as_ref() {
+//!     Some(state) => state.options(options),
+//!     None => options.width(1024).height(768),
+//! };
+//!
+//! app.create_window_with_callback("/root/index.html", &options, move |window| {
+//!     if let Some(state) = state.as_ref() {
+//!         state.apply(&window);
+//!     }
+//!     // the tracker must be retained for the lifetime of the window
+//!     tracker.replace(store.track(&window)?);
+//!     Ok(())
+//! })?;
+//! ```
+//!
+
+use crate::result::Result;
+use nw_sys::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use workflow_core::task::{dispatch, sleep};
+use workflow_log::log_error;
+use workflow_store::fs;
+use workflow_wasm::prelude::*;
+
+/// Delay after the last move or resize event before the state is saved
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Minimum width and height of the window area that must remain
+/// within the work area of a screen for the saved position to be used
+const MIN_VISIBLE_SIZE: i64 = 64;
+
+/// Window position, size and maximized state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// Rectangle of the screen area available to windows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkArea {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<&nw_sys::screen::ScreenInfo> for WorkArea {
+    fn from(screen: &nw_sys::screen::ScreenInfo) -> Self {
+        WorkArea {
+            x: screen.work_area.x,
+            y: screen.work_area.y,
+            width: screen.work_area.width,
+            height: screen.work_area.height,
+        }
+    }
+}
+
+impl WindowState {
+    /// Capture the current state of the `window`
+    pub fn capture(window: &Window, maximized: bool) -> Self {
+        WindowState {
+            x: window.x(),
+            y: window.y(),
+            width: window.width(),
+            height: window.height(),
+            maximized,
+        }
+    }
+
+    /// Returns `true` if the top edge of the window (where the title bar is
+    /// located) sufficiently overlaps the work area of one of the screens
+    pub fn is_visible(&self, areas: &[WorkArea]) -> bool {
+        let (x, y, width) = (self.x as i64, self.y as i64, self.width as i64);
+        areas.iter().any(|area| {
+            let (left, top) = (area.x as i64, area.y as i64);
+            let (right, bottom) = (left + area.width as i64, top + area.height as i64);
+            let overlap = (x + width).min(right) - x.max(left);
+            overlap >= MIN_VISIBLE_SIZE && y >= top && y + MIN_VISIBLE_SIZE <= bottom
+        })
+    }
+
+    /// Return the state adjusted to the given screen work areas. If the window
+    /// is not visible on any screen, it is centered on the first (primary) screen.
+    /// The window size is limited to the size of the screen it is placed on.
+    pub fn fit(&self, areas: &[WorkArea]) -> WindowState {
+        let Some(primary) = areas.first() else {
+            return self.clone();
+        };
+        if self.is_visible(areas) {
+            return self.clone();
+        }
+
+        let width = (self.width as f64).min(primary.width);
+        let height = (self.height as f64).min(primary.height);
+        WindowState {
+            x: (primary.x + (primary.width - width) / 2.0) as i32,
+            y: (primary.y + (primary.height - height) / 2.0) as i32,
+            width: width as u32,
+            height: height as u32,
+            maximized: self.maximized,
+        }
+    }
+
+    /// Apply the state to window creation options. NW.js window options
+    /// do not accept negative coordinates, in which case the position is
+    /// set by [`WindowState::apply()`] once the window is created.
+    pub fn options(&self, options: nw_sys::window::Options) -> nw_sys::window::Options {
+        let options = options.width(self.width).height(self.height);
+        if self.x >= 0 && self.y >= 0 {
+            options.left(self.x as u32).top(self.y as u32)
+        } else {
+            options
+        }
+    }
+
+    /// Apply the state to an existing window
+    pub fn apply(&self, window: &Window) {
+        window.set_x(self.x);
+        window.set_y(self.y);
+        window.resize_to(self.width, self.height);
+        if self.maximized {
+            window.maximize();
+        }
+    }
+}
+
+/// Work areas of the currently connected screens
+pub fn work_areas() -> Result<Vec<WorkArea>> {
+    nw_sys::screen::init_once();
+    Ok(nw_sys::screen::screens()?
+        .iter()
+        .map(WorkArea::from)
+        .collect())
+}
+
+/// Persistent storage of the [`WindowState`] (see the [module](self) documentation)
+#[derive(Debug, Clone)]
+pub struct WindowStateStore {
+    filename: PathBuf,
+}
+
+impl WindowStateStore {
+    /// Create a store saving window state to `filename` (`~` is resolved to the home folder)
+    pub fn try_new(filename: &str) -> Result<Self> {
+        Ok(WindowStateStore {
+            filename: fs::resolve_path(filename)?,
+        })
+    }
+
+    /// Load the saved window state, adjusted to the currently connected screens.
+    /// Returns `None` if the state has not been saved or can not be read.
+    pub async fn load(&self) -> Option<WindowState> {
+        if !fs::exists(&self.filename).await.unwrap_or(false) {
+            return None;
+        }
+        let state = match fs::read_json::<WindowState>(&self.filename).await {
+            Ok(state) => state,
+            Err(err) => {
+                log_error!("Unable to read window state: {err}");
+                return None;
+            }
+        };
+        match work_areas() {
+            Ok(areas) => Some(state.fit(&areas)),
+            Err(err) => {
+                log_error!("Unable to obtain screen information: {err}");
+                Some(state)
+            }
+        }
+    }
+
+    pub async fn save(&self, state: &WindowState) -> Result<()> {
+        if let Some(folder) = self.filename.parent() {
+            fs::create_dir_all(folder).await?;
+        }
+        fs::write_json(&self.filename, state).await?;
+        Ok(())
+    }
+
+    /// Track `window` move, resize and maximize events, saving the window
+    /// state after changes.  Events are tracked until the returned
+    /// [`WindowStateTracker`] is dropped.
+    pub fn track(&self, window: &Window) -> Result<WindowStateTracker> {
+        WindowStateTracker::try_new(self.clone(), window)
+    }
+}
+
+struct Inner {
+    store: WindowStateStore,
+    window: Window,
+    state: RefCell<WindowState>,
+    // incremented on each change to debounce saving
+    generation: Cell<u64>,
+}
+
+impl Inner {
+    fn update(self: &Rc<Self>, maximized: Option<bool>) {
+        {
+            let mut state = self.state.borrow_mut();
+            match maximized {
+                Some(maximized) => state.maximized = maximized,
+                // bounds of a maximized window are not retained,
+                // so that the window can be restored to its normal size
+                None if !state.maximized => {
+                    *state = WindowState::capture(&self.window, false);
+                }
+                None => return,
+            }
+        }
+
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        let inner = Rc::downgrade(self);
+        dispatch(async move {
+            sleep(SAVE_DELAY).await;
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+            if inner.generation.get() == generation {
+                let state = inner.state.borrow().clone();
+                if let Err(err) = inner.store.save(&state).await {
+                    log_error!("Unable to save window state: {err}");
+                }
+            }
+        });
+    }
+}
+
+/// Window event tracker created by [`WindowStateStore::track()`]
+pub struct WindowStateTracker {
+    inner: Rc<Inner>,
+    _callbacks: CallbackMap,
+}
+
+impl WindowStateTracker {
+    fn try_new(store: WindowStateStore, window: &Window) -> Result<Self> {
+        let inner = Rc::new(Inner {
+            store,
+            window: window.clone(),
+            state: RefCell::new(WindowState::capture(window, false)),
+            generation: Cell::new(0),
+        });
+
+        let callbacks = CallbackMap::new();
+        for (event, maximized) in [
+            ("move", None),
+            ("resize", None),
+            ("maximize", Some(true)),
+            ("restore", Some(false)),
+        ] {
+            let inner = Rc::downgrade(&inner);
+            let callback = callback!(move |_: JsValue| {
+                if let Some(inner) = inner.upgrade() {
+                    inner.update(maximized);
+                }
+            });
+            window.on(event, callback.get_fn());
+            callbacks.retain(callback)?;
+        }
+
+        Ok(WindowStateTracker {
+            inner,
+            _callbacks: callbacks,
+        })
+    }
+
+    /// Current window state
+    pub fn state(&self) -> WindowState {
+        self.inner.state.borrow().clone()
+    }
+
+    /// Save the current window state immediately
+    pub async fn save(&self) -> Result<()> {
+        let state = self.state();
+        self.inner.store.save(&state).await
+    }
+}
@@ -12,6 +12,11 @@
 //! a full-screen DIV element dumping the stack info in it.  This is useful when debugging on devices
 //! without access to console output.
 //!
+//! Panics are also captured as a structured [`workflow_log::PanicRecord`]
+//! (message, location and a best-effort stack trace) and delivered to the
+//! log sink installed via [`workflow_log::pipe`] before the default output
+//! takes place.  If the sink consumes the record, console output is skipped.
+//!
 //! ## Error.stackTraceLimit
 //!
 //! Many browsers only capture the top 10 frames of a stack trace. In rust programs this is less likely to be enough. To see more frames, you can set the non-standard value `Error.stackTraceLimit`. For more information see the [MDN Web Docs](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Microsoft_Extensions/Error.stackTraceLimit) or [v8 docs](https://v8.dev/docs/stack-trace-api).
@@ -21,6 +26,7 @@
 extern crate cfg_if;
 
 use std::panic;
+use workflow_log::PanicRecord;
 
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
@@ -42,7 +48,7 @@ cfg_if! {
             fn stack(error: &Error) -> String;
         }
 
-        fn process(info: &panic::PanicInfo) -> String{
+        fn process(info: &panic::PanicHookInfo) -> String{
             let mut msg = info.to_string();
 
             // Add the error stack to our message.
@@ -56,9 +62,7 @@ cfg_if! {
             // the message's contents, by including the stack in the message
             // contents we make sure it is available to the user.
             msg.push_str("\n\nStack:\n\n");
-            let e = Error::new();
-            let stack = e.stack();
-            msg.push_str(&stack);
+            msg.push_str(&stack());
 
             // Safari's devtools, on the other hand, _do_ mess with logged
             // messages' contents, so we attempt to break their heuristics for
@@ -70,11 +74,21 @@ cfg_if! {
         }
 
 
-        fn console_hook(info: &panic::PanicInfo){
+        fn stack() -> String {
+            Error::new().stack()
+        }
+
+        fn console_hook(info: &panic::PanicHookInfo){
+            // Deliver the panic to the workflow-log sink first,
+            // skipping console output if the sink consumes it
+            if to_log_sink(info, Some(stack())) {
+                return;
+            }
             // Finally, log the panic with `console.error`!
             console_error(process(info));
         }
-        fn popup_hook(info: &panic::PanicInfo){
+        fn popup_hook(info: &panic::PanicHookInfo){
+            to_log_sink(info, Some(stack()));
             // Finally, log the panic with `logger::error`!
             logger::error(process(info));
         }
@@ -99,7 +113,13 @@ cfg_if! {
     } else {
         use std::io::{self, Write};
 
-        fn hook(info: &panic::PanicInfo) {
+        fn hook(info: &panic::PanicHookInfo) {
+            let stack = std::backtrace::Backtrace::capture();
+            let stack = matches!(stack.status(), std::backtrace::BacktraceStatus::Captured)
+                .then(|| stack.to_string());
+            if to_log_sink(info, stack) {
+                return;
+            }
             let _ = writeln!(io::stderr(), "{info}");
         }
 
@@ -113,6 +133,18 @@ cfg_if! {
     }
 }
 
+/// Captures the panic as a [`PanicRecord`] and delivers it to the sink
+/// installed via [`workflow_log::pipe`].  Returns `true` if the sink
+/// has consumed the record.
+fn to_log_sink(info: &panic::PanicHookInfo, stack: Option<String>) -> bool {
+    let record = PanicRecord::new(
+        PanicRecord::message_from_payload(info.payload()),
+        info.location().map(|location| location.to_string()),
+        stack,
+    );
+    workflow_log::impls::panic_impl(&record)
+}
+
 pub enum Type {
     Console,
     Popup,
@@ -14,6 +14,26 @@ pub fn server_method(input: TokenStream) -> TokenStream {
     ts.into()
 }
 
+#[proc_macro]
+#[proc_macro_error]
+pub fn server_streaming_method(input: TokenStream) -> TokenStream {
+    let result = parse_macro_input!(input as method::Method);
+    let ts = quote! {
+        workflow_rpc::server::StreamingMethod::new(#result)
+    };
+    ts.into()
+}
+
+#[proc_macro]
+#[proc_macro_error]
+pub fn server_client_streaming_method(input: TokenStream) -> TokenStream {
+    let result = parse_macro_input!(input as method::Method);
+    let ts = quote! {
+        workflow_rpc::server::ClientStreamingMethod::new(#result)
+    };
+    ts.into()
+}
+
 #[proc_macro]
 #[proc_macro_error]
 pub fn server_notification(input: TokenStream) -> TokenStream {
@@ -24,6 +44,16 @@ pub fn server_notification(input: TokenStream) -> TokenStream {
     ts.into()
 }
 
+#[proc_macro]
+#[proc_macro_error]
+pub fn client_method(input: TokenStream) -> TokenStream {
+    let result = parse_macro_input!(input as method::Method);
+    let ts = quote! {
+        workflow_rpc::client::Method::new(#result)
+    };
+    ts.into()
+}
+
 #[proc_macro]
 #[proc_macro_error]
 pub fn client_notification(input: TokenStream) -> TokenStream {
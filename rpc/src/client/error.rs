@@ -31,6 +31,9 @@ pub enum Error {
     #[error("Missing method in notification message")]
     NotificationMethod,
 
+    #[error("Missing id or method in server request message")]
+    RequestMethod,
+
     #[error("invalid WebSocket message type for protocol")]
     WebSocketMessageType,
 
@@ -39,10 +42,15 @@ pub enum Error {
 
     /// Underlying WebSocket error
     #[error("WebSocket -> {0}")]
-    WebSocketError(#[from] WebSocketError),
+    WebSocketError(Box<WebSocketError>),
     /// RPC call timeout
     #[error("RPC request timeout")]
     Timeout,
+    /// RPC call cancelled using [`CallHandle::cancel()`](super::CallHandle::cancel)
+    #[error("RPC request cancelled")]
+    Cancelled,
+    #[error("RPC method is not a streaming method")]
+    NotStreamingMethod,
     /// Unable to send shutdown message to receiver
     #[error("Receiver ctl failure")]
     ReceiverCtl,
@@ -106,6 +114,12 @@ pub enum Error {
     // RegexError(#[from] regex::Error),
 }
 
+impl From<WebSocketError> for Error {
+    fn from(err: WebSocketError) -> Self {
+        Error::WebSocketError(Box::new(err))
+    }
+}
+
 impl From<ServerError> for Error {
     fn from(err: ServerError) -> Self {
         Error::ServerError(err)
@@ -0,0 +1,56 @@
+use crate::imports::*;
+
+#[async_trait]
+pub trait MethodTrait: Send + Sync + 'static {
+    /// Returns the serialized [`ServerResult`] of the handler
+    /// (transported as the response data)
+    async fn call_with_borsh(&self, data: &[u8]) -> ServerResult<Vec<u8>>;
+    async fn call_with_serde_json(&self, value: Value) -> ServerResult<Value>;
+}
+
+pub type MethodFn<Req, Resp> =
+    Arc<Box<dyn Send + Sync + Fn(Req) -> MethodFnReturn<Resp> + 'static>>;
+
+pub type MethodFnReturn<T> = Pin<Box<dyn Send + 'static + Future<Output = ServerResult<T>>>>;
+
+pub struct Method<Req, Resp>
+where
+    Req: MsgT,
+    Resp: MsgT,
+{
+    method: MethodFn<Req, Resp>,
+}
+
+impl<Req, Resp> Method<Req, Resp>
+where
+    Req: MsgT,
+    Resp: MsgT,
+{
+    pub fn new<FN>(method_fn: FN) -> Method<Req, Resp>
+    where
+        FN: Send + Sync + Fn(Req) -> MethodFnReturn<Resp> + 'static,
+    {
+        Method {
+            method: Arc::new(Box::new(method_fn)),
+        }
+    }
+}
+
+#[async_trait]
+impl<Req, Resp> MethodTrait for Method<Req, Resp>
+where
+    Req: MsgT,
+    Resp: MsgT,
+{
+    async fn call_with_borsh(&self, data: &[u8]) -> ServerResult<Vec<u8>> {
+        let req = Req::try_from_slice(data).map_err(|_| ServerError::ReqDeserialize)?;
+        let resp = (self.method)(req).await;
+        Ok(<ServerResult<Resp> as BorshSerialize>::try_to_vec(&resp)?)
+    }
+
+    async fn call_with_serde_json(&self, value: Value) -> ServerResult<Value> {
+        let req: Req = serde_json::from_value(value).map_err(|_| ServerError::ReqDeserialize)?;
+        let resp = (self.method)(req).await?;
+        serde_json::to_value(resp).map_err(|_| ServerError::RespSerialize)
+    }
+}
@@ -1,13 +1,17 @@
+pub mod method;
 pub mod notification;
 use crate::imports::*;
+pub use method::*;
 pub use notification::*;
 
-/// Collection of server-side notification handlers
+/// Collection of server-side notification handlers and
+/// the client-side methods called by the server
 pub struct Interface<Ops>
 where
     Ops: OpsT,
 {
     notifications: AHashMap<Ops, Box<dyn NotificationTrait>>,
+    methods: AHashMap<Ops, Box<dyn MethodTrait>>,
 }
 
 impl<Ops> Default for Interface<Ops>
@@ -26,6 +30,21 @@ where
     pub fn new() -> Interface<Ops> {
         Interface {
             notifications: AHashMap::new(),
+            methods: AHashMap::new(),
+        }
+    }
+
+    /// Register the method called by the server using
+    /// [`Messenger::call()`](crate::server::Messenger::call)
+    pub fn method<Req, Resp>(&mut self, op: Ops, method: Method<Req, Resp>)
+    where
+        Ops: OpsT,
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let method: Box<dyn MethodTrait> = Box::new(method);
+        if self.methods.insert(op.clone(), method).is_some() {
+            panic!("RPC method {op:?} is declared multiple times")
         }
     }
 
@@ -59,6 +78,26 @@ where
             Err(ServerError::NotFound)
         }
     }
+
+    pub async fn call_method_with_borsh(&self, op: &Ops, payload: &[u8]) -> ServerResult<Vec<u8>> {
+        if let Some(method) = self.methods.get(op) {
+            method.call_with_borsh(payload).await
+        } else {
+            Err(ServerError::NotFound)
+        }
+    }
+
+    pub async fn call_method_with_serde_json(
+        &self,
+        op: &Ops,
+        payload: Value,
+    ) -> ServerResult<Value> {
+        if let Some(method) = self.methods.get(op) {
+            method.call_with_serde_json(payload).await
+        } else {
+            Err(ServerError::NotFound)
+        }
+    }
 }
 
 impl<Ops> From<Interface<Ops>> for Option<Arc<Interface<Ops>>>
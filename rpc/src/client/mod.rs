@@ -11,16 +11,22 @@ pub use crate::client::error::Error;
 pub use crate::client::result::Result;
 
 use crate::imports::*;
-use futures_util::select_biased;
-pub use interface::{Interface, Notification};
+pub use crate::introspection::{Introspection, OpInfo};
+use futures::Stream;
+use futures_util::{select, select_biased};
+pub use interface::{Interface, Method, Notification};
+pub use protocol::CallStream;
 use protocol::ProtocolHandler;
 pub use protocol::{BorshProtocol, JsonProtocol};
 use std::fmt::Debug;
 use std::str::FromStr;
-use workflow_core::{channel::Multiplexer, task::yield_now};
+use workflow_core::{
+    channel::{Multiplexer, Receiver, Sender},
+    task::yield_now,
+};
 pub use workflow_websocket::client::{
-    ConnectOptions, ConnectResult, ConnectStrategy, Resolver, ResolverResult, WebSocketConfig,
-    WebSocketError,
+    ConnectOptions, ConnectResult, ConnectStrategy, MemoryTransport, Resolver, ResolverResult,
+    WebSocketConfig, WebSocketError,
 };
 
 #[cfg(feature = "wasm32-sdk")]
@@ -60,6 +66,22 @@ pub use workflow_websocket::client::options::IConnectOptions;
 ///
 pub use workflow_rpc_macros::client_notification as notification;
 
+///
+/// method!() macro for declaration of client-side RPC method handlers
+/// called by the server using [`Messenger::call()`](crate::server::Messenger::call)
+///
+/// Similarly to the [`notification!()`](notification) macro, this macro adds
+/// the Box and Pin syntax required by the async method closure:
+///
+/// ```ignore
+/// interface.method(MyOps::Status, method!(|req: StatusRequest| async move {
+///     // ...
+///     Ok(StatusResponse { })
+/// }))
+/// ```
+///
+pub use workflow_rpc_macros::client_method as method;
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Ctl {
     Connect,
@@ -238,6 +260,10 @@ where
                                             ctl_channel.try_broadcast(Ctl::Disconnect).expect("ctl_channel.try_broadcast(Ctl::Disconnect)");
                                         }
                                     }
+                                    WebSocketMessage::ReconnectExhausted => {
+                                        log_error!("wRPC client - WebSocket reconnect attempts exhausted");
+                                    }
+                                    WebSocketMessage::Ping(_) | WebSocketMessage::Pong(_) => { }
                                 }
                             },
                             Err(err) => {
@@ -301,6 +327,36 @@ where
     Json(Arc<JsonProtocol<Ops, Id>>),
 }
 
+impl<Ops, Id> Protocol<Ops, Id>
+where
+    Ops: OpsT,
+    Id: IdT,
+{
+    async fn request<Req, Resp>(
+        &self,
+        id: Id,
+        op: Ops,
+        req: Req,
+        timeout: Option<Duration>,
+    ) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        match self {
+            Protocol::Borsh(protocol) => protocol.request_with_id(id, op, req, timeout).await,
+            Protocol::Json(protocol) => protocol.request_with_id(id, op, req, timeout).await,
+        }
+    }
+
+    fn cancel(&self, id: &Id) -> bool {
+        match self {
+            Protocol::Borsh(protocol) => protocol.cancel(id),
+            Protocol::Json(protocol) => protocol.cancel(id),
+        }
+    }
+}
+
 impl<Ops, Id> From<Arc<dyn ProtocolHandler<Ops>>> for Protocol<Ops, Id>
 where
     Ops: OpsT,
@@ -317,6 +373,26 @@ where
     }
 }
 
+/// Handle to a call issued using [`RpcClient::call_with_timeout()`]
+pub struct CallHandle<Resp> {
+    cancel: Sender<()>,
+    result: Receiver<Result<Resp>>,
+}
+
+impl<Resp> CallHandle<Resp> {
+    /// Cancel the call, removing the pending request and resolving
+    /// [`CallHandle::wait()`] with [`Error::Cancelled`] (the response
+    /// is discarded if received later)
+    pub fn cancel(&self) {
+        self.cancel.try_send(()).ok();
+    }
+
+    /// Wait for the response, the timeout or the cancellation of the call
+    pub async fn wait(self) -> Result<Resp> {
+        self.result.recv().await?
+    }
+}
+
 #[derive(Clone)]
 pub struct RpcClient<Ops, Id = Id64>
 where
@@ -486,6 +562,131 @@ where
         }
     }
 
+    ///
+    /// Issue an async wRPC call to the streaming method, returns the stream
+    /// of the items (see [`Interface::streaming_method()`](crate::server::Interface::streaming_method)).
+    /// The stream ends once the server completes the response and yields
+    /// an error if the method fails or the connection is closed.  The server
+    /// sends the items as the client grants the credit (the items are consumed
+    /// from the stream), and dropping the stream cancels the response.
+    ///
+    /// Following are the trait requirements on the arguments:
+    /// - `Ops`: [`OpsT`]
+    /// - `Req`: [`MsgT`]
+    /// - `Resp`: [`MsgT`]
+    ///
+    pub async fn call_stream<Req, Resp>(&self, op: Ops, req: Req) -> Result<CallStream<Resp>>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        if !self.is_connected() {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+        match &self.protocol {
+            Protocol::Borsh(protocol) => protocol.request_stream(op, req).await,
+            Protocol::Json(protocol) => protocol.request_stream(op, req).await,
+        }
+    }
+
+    ///
+    /// Issue an async wRPC call to the client-streaming method sending the
+    /// `items` and wait for the response (see
+    /// [`Interface::client_streaming_method()`](crate::server::Interface::client_streaming_method)).
+    /// The items are pulled from the stream as the server grants the credit
+    /// (flow control), allowing large uploads without buffering the items in
+    /// memory.  The client timeout applies to the period of inactivity of the
+    /// server (the time elapsed since the last credit has been granted).
+    ///
+    /// Following are the trait requirements on the arguments:
+    /// - `Ops`: [`OpsT`]
+    /// - `Req`: [`MsgT`]
+    /// - `Resp`: [`MsgT`]
+    ///
+    pub async fn call_with_stream<Req, Resp>(
+        &self,
+        op: Ops,
+        items: impl Stream<Item = Req> + Send,
+    ) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        if !self.is_connected() {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+        match &self.protocol {
+            Protocol::Borsh(protocol) => protocol.request_with_stream(op, items).await,
+            Protocol::Json(protocol) => protocol.request_with_stream(op, items).await,
+        }
+    }
+
+    ///
+    /// Obtain the [`Introspection`] of the server (the registered methods,
+    /// notifications and the encoding) by calling the introspection method
+    /// bound to the supplied op on the server using
+    /// [`Interface::introspection()`](crate::server::Interface::introspection).
+    ///
+    pub async fn introspect(&self, op: Ops) -> Result<Introspection> {
+        self.call(op, ()).await
+    }
+
+    ///
+    /// Issue an async wRPC call returning a [`CallHandle`] that can be used to
+    /// wait for the response or to cancel the call.  If supplied, `timeout`
+    /// overrides the client timeout for this call, resolving the call with
+    /// [`Error::Timeout`] once elapsed.
+    ///
+    /// Following are the trait requirements on the arguments:
+    /// - `Ops`: [`OpsT`]
+    /// - `Req`: [`MsgT`]
+    /// - `Resp`: [`MsgT`]
+    ///
+    pub async fn call_with_timeout<Req, Resp>(
+        &self,
+        op: Ops,
+        req: Req,
+        timeout: Option<Duration>,
+    ) -> Result<CallHandle<Resp>>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        if !self.is_connected() {
+            return Err(WebSocketError::NotConnected.into());
+        }
+
+        let id = Id::generate();
+        let (cancel_sender, cancel_receiver) = oneshot();
+        let (result_sender, result_receiver) = oneshot();
+        let protocol = self.protocol.clone();
+        workflow_core::task::spawn(async move {
+            let elapsed = async {
+                match timeout {
+                    Some(timeout) => workflow_core::task::sleep(timeout).await,
+                    None => futures::future::pending().await,
+                }
+            };
+            let result = select! {
+                result = protocol.request(id.clone(), op, req, timeout).fuse() => result,
+                _ = elapsed.fuse() => Err(Error::Timeout),
+                _ = cancel_receiver.recv().fuse() => Err(Error::Cancelled),
+            };
+            if matches!(result, Err(Error::Timeout | Error::Cancelled)) {
+                protocol.cancel(&id);
+            }
+            result_sender.try_send(result).ok();
+        });
+        yield_now().await;
+
+        Ok(CallHandle {
+            cancel: cancel_sender,
+            result: result_receiver,
+        })
+    }
+
     /// Triggers a disconnection on the underlying WebSocket.
     /// This is intended for debug purposes only.
     /// Can be used to test application reconnection logic.
@@ -1,10 +1,14 @@
-use super::{Pending, PendingMap, ProtocolHandler};
+use super::{
+    spawn_post, upload, CallStream, Credits, Frame, Pending, PendingMap, ProtocolHandler,
+    StreamControl, StreamReceiver, Streams,
+};
 pub use crate::client::error::Error;
 pub use crate::client::result::Result;
 use crate::client::Interface;
 use crate::imports::*;
 use crate::messages::borsh::*;
 use core::marker::PhantomData;
+use futures::{Stream, StreamExt};
 
 pub type BorshResponseFn =
     Arc<Box<(dyn Fn(Result<&[u8]>, Option<&Duration>) -> Result<()> + Sync + Send)>>;
@@ -17,6 +21,8 @@ where
 {
     ws: Arc<WebSocket>,
     pending: PendingMap<Id, BorshResponseFn>,
+    streams: Arc<Streams<Id, Vec<u8>>>,
+    credits: Credits<Id>,
     interface: Option<Arc<Interface<Ops>>>,
     ops: PhantomData<Ops>,
     id: PhantomData<Id>,
@@ -31,6 +37,8 @@ where
         BorshProtocol {
             ws,
             pending: Arc::new(Mutex::new(AHashMap::new())),
+            streams: Arc::new(Streams::new()),
+            credits: Credits::new(),
             interface,
             ops: PhantomData,
             id: PhantomData,
@@ -38,7 +46,7 @@ where
     }
 }
 
-type MessageInfo<'l, Ops, Id> = (Option<Id>, Option<Ops>, Result<&'l [u8]>);
+type MessageInfo<'l, Ops, Id> = (Option<Id>, Option<Ops>, Frame, Result<&'l [u8]>);
 
 impl<Ops, Id> BorshProtocol<Ops, Id>
 where
@@ -51,17 +59,36 @@ where
                 let header = msg.header;
                 match header.kind {
                     ServerMessageKind::Success => {
-                        Ok((header.id, header.op, Ok(msg.payload)))
+                        Ok((header.id, header.op, Frame::Response, Ok(msg.payload)))
                         // Ok((Some(header.id), header.op.clone(), Ok(msg.data)))
                     }
                     ServerMessageKind::Error => {
                         if let Ok(err) = ServerError::try_from_slice(msg.payload) {
-                            Ok((header.id, None, Err(Error::RpcCall(err))))
+                            Ok((header.id, None, Frame::Response, Err(Error::RpcCall(err))))
                         } else {
-                            Ok((header.id, None, Err(Error::ErrorDeserializingResponseData)))
+                            Ok((
+                                header.id,
+                                None,
+                                Frame::Response,
+                                Err(Error::ErrorDeserializingResponseData),
+                            ))
                         }
                     }
-                    ServerMessageKind::Notification => Ok((None, header.op, Ok(msg.payload))),
+                    ServerMessageKind::Notification => {
+                        Ok((None, header.op, Frame::Response, Ok(msg.payload)))
+                    }
+                    ServerMessageKind::StreamItem => {
+                        Ok((header.id, header.op, Frame::StreamItem, Ok(msg.payload)))
+                    }
+                    ServerMessageKind::StreamEnd => {
+                        Ok((header.id, header.op, Frame::StreamEnd, Ok(msg.payload)))
+                    }
+                    ServerMessageKind::StreamCredit => {
+                        Ok((header.id, header.op, Frame::StreamCredit, Ok(msg.payload)))
+                    }
+                    ServerMessageKind::Request => {
+                        Ok((header.id, header.op, Frame::Request, Ok(msg.payload)))
+                    }
                 }
             }
             Err(err) => Err(ServerError::RespDeserialize(err.to_string())),
@@ -69,23 +96,40 @@ where
     }
 
     pub async fn request<Req, Resp>(&self, op: Ops, req: Req) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        self.request_with_id(Id::generate(), op, req, None).await
+    }
+
+    /// Issue the request using the supplied `id`, optionally overriding the client timeout
+    pub async fn request_with_id<Req, Resp>(
+        &self,
+        id: Id,
+        op: Ops,
+        req: Req,
+        timeout: Option<Duration>,
+    ) -> Result<Resp>
     where
         Req: MsgT,
         Resp: MsgT,
     {
         let payload = req.try_to_vec().map_err(|_| Error::BorshSerialize)?;
 
-        let id = Id::generate();
         let (sender, receiver) = oneshot();
 
         {
             let mut pending = self.pending.lock().unwrap();
             pending.insert(
                 id.clone(),
-                Pending::new(Arc::new(Box::new(move |result, _duration| {
-                    sender.try_send(result.map(|data| data.to_vec()))?;
-                    Ok(())
-                }))),
+                Pending::new(
+                    Arc::new(Box::new(move |result, _duration| {
+                        sender.try_send(result.map(|data| data.to_vec()))?;
+                        Ok(())
+                    })),
+                    timeout,
+                ),
             );
         }
 
@@ -101,6 +145,124 @@ where
         Ok(resp?)
     }
 
+    /// Issue the streaming method request, returns the stream of the items
+    pub async fn request_stream<Req, Resp>(&self, op: Ops, req: Req) -> Result<CallStream<Resp>>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let payload = req.try_to_vec().map_err(|_| Error::BorshSerialize)?;
+
+        let id = Id::generate();
+        let receiver = self.streams.insert(id.clone());
+        if let Err(err) = self
+            .ws
+            .post(to_ws_msg(
+                BorshReqHeader::new(Some(id.clone()), op.clone()),
+                &payload,
+            ))
+            .await
+        {
+            self.streams.remove(&id);
+            return Err(err.into());
+        }
+
+        let ws = self.ws.clone();
+        let streams = self.streams.clone();
+        let control = move |control| {
+            let (kind, payload) = match control {
+                StreamControl::Credit(credit) => {
+                    (ClientMessageKind::StreamCredit, credit.try_to_vec())
+                }
+                StreamControl::Cancel => {
+                    streams.remove(&id);
+                    (ClientMessageKind::StreamCancel, Ok(Vec::new()))
+                }
+            };
+            let msg = payload
+                .map(|payload| {
+                    let header = BorshReqHeader::new(Some(id.clone()), op.clone());
+                    to_ws_msg_with_kind(kind, header, &payload)
+                })
+                .map_err(|_| Error::BorshSerialize);
+            spawn_post(&ws, msg);
+        };
+
+        Ok(StreamReceiver::new(receiver, control)
+            .map(|item| {
+                Resp::try_from_slice(item?.as_ref())
+                    .map_err(|e| Error::BorshDeserialize(e.to_string()))
+            })
+            .boxed())
+    }
+
+    /// Issue the client-streaming method request sending the `items`
+    /// within the credit granted by the server, returns the response
+    pub async fn request_with_stream<Req, Resp>(
+        &self,
+        op: Ops,
+        items: impl Stream<Item = Req> + Send,
+    ) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let id = Id::generate();
+        let (sender, receiver) = oneshot();
+
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.insert(
+                id.clone(),
+                Pending::new(
+                    Arc::new(Box::new(move |result, _duration| {
+                        sender.try_send(result.map(|data| data.to_vec()))?;
+                        Ok(())
+                    })),
+                    None,
+                ),
+            );
+        }
+        let credits = self.credits.insert(id.clone());
+
+        let frame = |kind: StreamRequestKind, item: Option<Req>| {
+            let mut payload = kind.try_to_vec().map_err(|_| Error::BorshSerialize)?;
+            if let Some(item) = item {
+                BorshSerialize::serialize(&item, &mut payload)
+                    .map_err(|_| Error::BorshSerialize)?;
+            }
+            Ok(to_ws_msg(
+                BorshReqHeader::new(Some(id.clone()), op.clone()),
+                &payload,
+            ))
+        };
+
+        let result = async {
+            self.ws.post(frame(StreamRequestKind::Open, None)?).await?;
+            upload(&self.ws, items, credits, receiver, |item| match item {
+                Some(item) => frame(StreamRequestKind::Item, Some(item)),
+                None => frame(StreamRequestKind::End, None),
+            })
+            .await
+        }
+        .await;
+
+        self.pending.lock().unwrap().remove(&id);
+        self.credits.remove(&id);
+
+        let data = result?;
+        let resp = ServerResult::<Resp>::try_from_slice(data.as_ref())
+            .map_err(|e| Error::BorshDeserialize(e.to_string()))?;
+
+        Ok(resp?)
+    }
+
+    /// Remove the pending request, returns `false` if the
+    /// response has already been received
+    pub fn cancel(&self, id: &Id) -> bool {
+        self.pending.lock().unwrap().remove(id).is_some()
+    }
+
     pub async fn notify<Msg>(&self, op: Ops, payload: Msg) -> Result<()>
     where
         Msg: BorshSerialize + Send + Sync + 'static,
@@ -127,6 +289,38 @@ where
 
         Ok(())
     }
+
+    /// Call the client-side method requested by the server, posting
+    /// the response from a separate task (the method can call the server)
+    fn handle_request(&self, id: Option<Id>, op: Option<Ops>, payload: &[u8]) -> Result<()> {
+        let (Some(id), Some(op)) = (id, op) else {
+            return Err(Error::RequestMethod);
+        };
+
+        let ws = self.ws.clone();
+        let interface = self.interface.clone();
+        let payload = payload.to_vec();
+        workflow_core::task::spawn(async move {
+            let result = match &interface {
+                Some(interface) => interface.call_method_with_borsh(&op, &payload).await,
+                None => Err(ServerError::NotFound),
+            };
+            match result.or_else(|err| ServerResult::<()>::Err(err).try_to_vec()) {
+                Ok(data) => {
+                    let header = BorshReqHeader::<Ops, Id>::new(Some(id), op);
+                    let msg = to_ws_msg_with_kind(ClientMessageKind::Response, header, &data);
+                    if let Err(err) = ws.post(msg).await {
+                        log_trace!("unable to post the response to the server request: {err}");
+                    }
+                }
+                Err(err) => {
+                    log_trace!("error serializing the response to the server request: {err}")
+                }
+            }
+        });
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -144,7 +338,7 @@ where
 
     async fn handle_timeout(&self, timeout: Duration) {
         self.pending.lock().unwrap().retain(|_, pending| {
-            if pending.timestamp.elapsed() > timeout {
+            if pending.is_expired(timeout) {
                 (pending.callback)(Err(Error::Timeout), None).unwrap_or_else(|err| {
                     log_trace!("Error in RPC callback during timeout: `{err}`")
                 });
@@ -161,16 +355,36 @@ where
                 .unwrap_or_else(|err| log_trace!("Error in RPC callback during timeout: `{err}`"));
             false
         });
+        self.streams.disconnect();
 
         Ok(())
     }
 
     async fn handle_message(&self, message: WebSocketMessage) -> Result<()> {
         if let WebSocketMessage::Binary(server_message) = message {
-            let (id, op, result) = self.decode(server_message.as_slice())?;
-            if let Some(id) = id {
-                if let Some(pending) = self.pending.lock().unwrap().remove(&id) {
+            let (id, op, frame, result) = self.decode(&server_message)?;
+            if frame == Frame::Request {
+                self.handle_request(id, op, result?)
+            } else if let Some(id) = id {
+                let pending = match frame {
+                    Frame::Response => self.pending.lock().unwrap().remove(&id),
+                    _ => None,
+                };
+                if let Some(pending) = pending {
                     (pending.callback)(result, Some(&pending.timestamp.elapsed()))
+                } else if frame == Frame::StreamCredit {
+                    let credit = u32::try_from_slice(result?)
+                        .map_err(|e| Error::BorshDeserialize(e.to_string()))?;
+                    if let Some(pending) = self.pending.lock().unwrap().get_mut(&id) {
+                        pending.timestamp = Instant::now();
+                    }
+                    self.credits.grant(&id, credit);
+                    Ok(())
+                } else if self
+                    .streams
+                    .dispatch(&id, frame, result.map(|data| data.to_vec()))
+                {
+                    Ok(())
                 } else {
                     Err(Error::ResponseHandler(format!("{id:?}")))
                 }
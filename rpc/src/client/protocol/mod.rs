@@ -4,6 +4,11 @@ mod serde_json;
 pub use crate::client::error::Error;
 pub use crate::client::result::Result;
 use crate::imports::*;
+use crate::messages::{STREAM_REQUEST_WINDOW, STREAM_RESPONSE_WINDOW};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use futures::{future, select_biased};
+use std::task::{Context, Poll};
+use workflow_core::channel::{unbounded, Receiver, Sender};
 
 pub use self::borsh::BorshProtocol;
 pub use self::serde_json::JsonProtocol;
@@ -26,15 +31,327 @@ impl_downcast!(sync ProtocolHandler<Ops> where Ops: OpsT);
 
 struct Pending<F> {
     timestamp: Instant,
+    /// Per-call timeout overriding the client timeout
+    timeout: Option<Duration>,
     callback: F,
 }
 impl<F> Pending<F> {
-    fn new(callback: F) -> Self {
+    fn new(callback: F, timeout: Option<Duration>) -> Self {
         Self {
             timestamp: Instant::now(),
+            timeout,
             callback,
         }
     }
+
+    fn is_expired(&self, timeout: Duration) -> bool {
+        self.timestamp.elapsed() > self.timeout.unwrap_or(timeout)
+    }
 }
 
 type PendingMap<Id, F> = Arc<Mutex<AHashMap<Id, Pending<F>>>>;
+
+/// Stream of the items received from the streaming method
+pub type CallStream<Resp> = BoxStream<'static, Result<Resp>>;
+
+/// Kind of the server message correlated to the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    /// Method response (or the error terminating the stream)
+    Response,
+    /// Item of the streaming method response
+    StreamItem,
+    /// End of the streaming method response
+    StreamEnd,
+    /// Credit granted to the client-streaming call
+    StreamCredit,
+    /// Request of the server to the client-side method
+    Request,
+}
+
+/// Channels of the streaming method calls awaiting the stream items
+struct Streams<Id, T> {
+    streams: Mutex<AHashMap<Id, Sender<Result<T>>>>,
+}
+
+impl<Id, T> Streams<Id, T>
+where
+    Id: IdT,
+{
+    fn new() -> Self {
+        Streams {
+            streams: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    fn insert(&self, id: Id) -> Receiver<Result<T>> {
+        let (sender, receiver) = unbounded();
+        self.streams.lock().unwrap().insert(id, sender);
+        receiver
+    }
+
+    fn remove(&self, id: &Id) {
+        self.streams.lock().unwrap().remove(id);
+    }
+
+    /// Deliver the message to the stream, closing the stream once it has
+    /// ended (or failed).  Returns `false` if `id` does not belong to a stream.
+    fn dispatch(&self, id: &Id, frame: Frame, result: Result<T>) -> bool {
+        let mut streams = self.streams.lock().unwrap();
+        let closed = match streams.get(id) {
+            Some(sender) => match frame {
+                Frame::StreamItem => {
+                    let terminate = result.is_err();
+                    // the receiver is closed if the stream has been dropped
+                    sender.try_send(result).is_err() || terminate
+                }
+                Frame::StreamEnd => true,
+                Frame::StreamCredit | Frame::Request => false,
+                Frame::Response => {
+                    sender
+                        .try_send(result.and(Err(Error::NotStreamingMethod)))
+                        .ok();
+                    true
+                }
+            },
+            None => return false,
+        };
+        if closed {
+            streams.remove(id);
+        }
+        true
+    }
+
+    fn disconnect(&self) {
+        for (_, sender) in self.streams.lock().unwrap().drain() {
+            sender.try_send(Err(Error::Disconnect)).ok();
+        }
+    }
+}
+
+/// Control frame of the streaming method response sent by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamControl {
+    /// Grant the credit for the next items
+    Credit(u32),
+    /// Cancel the response (the stream has been dropped)
+    Cancel,
+}
+
+/// Items of the streaming method response, granting the credit to the
+/// server as the items are consumed and cancelling the response if the
+/// stream is dropped before it has ended
+struct StreamReceiver<T> {
+    items: BoxStream<'static, Result<T>>,
+    /// Posts the control frame to the server
+    control: Box<dyn Fn(StreamControl) + Send + Sync>,
+    consumed: u32,
+    ended: bool,
+}
+
+impl<T> StreamReceiver<T>
+where
+    T: Send + 'static,
+{
+    fn new(
+        items: Receiver<Result<T>>,
+        control: impl Fn(StreamControl) + Send + Sync + 'static,
+    ) -> Self {
+        StreamReceiver {
+            items: items.boxed(),
+            control: Box::new(control),
+            consumed: 0,
+            ended: false,
+        }
+    }
+}
+
+impl<T> Stream for StreamReceiver<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let item = futures::ready!(this.items.poll_next_unpin(cx));
+        match &item {
+            // the error terminates the stream
+            Some(Err(_)) | None => this.ended = true,
+            Some(Ok(_)) => {
+                this.consumed += 1;
+                if this.consumed == (STREAM_RESPONSE_WINDOW / 2).max(1) {
+                    (this.control)(StreamControl::Credit(this.consumed));
+                    this.consumed = 0;
+                }
+            }
+        }
+        Poll::Ready(item)
+    }
+}
+
+impl<T> Drop for StreamReceiver<T> {
+    fn drop(&mut self) {
+        if !self.ended {
+            (self.control)(StreamControl::Cancel);
+        }
+    }
+}
+
+/// Post the message from a separate task (used by the synchronous
+/// [`StreamReceiver`] control)
+fn spawn_post(ws: &Arc<WebSocket>, msg: Result<WebSocketMessage>) {
+    let ws = ws.clone();
+    workflow_core::task::spawn(async move {
+        let result = match msg {
+            Ok(msg) => ws.post(msg).await.map(|_| ()).map_err(Error::from),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            log_trace!("unable to post the stream control message: {err}");
+        }
+    });
+}
+
+/// Channels of the client-streaming calls awaiting the credit
+struct Credits<Id> {
+    credits: Mutex<AHashMap<Id, Sender<u32>>>,
+}
+
+impl<Id> Credits<Id>
+where
+    Id: IdT,
+{
+    fn new() -> Self {
+        Credits {
+            credits: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    fn insert(&self, id: Id) -> Receiver<u32> {
+        let (sender, receiver) = unbounded();
+        self.credits.lock().unwrap().insert(id, sender);
+        receiver
+    }
+
+    fn remove(&self, id: &Id) {
+        self.credits.lock().unwrap().remove(id);
+    }
+
+    fn grant(&self, id: &Id, credit: u32) {
+        if let Some(sender) = self.credits.lock().unwrap().get(id) {
+            sender.try_send(credit).ok();
+        }
+    }
+}
+
+///
+/// Send the items of the client-streaming call (created using `frame`, `None`
+/// ending the stream) within the credit granted by the server, until the
+/// response is received.  The call must be opened before invoking this function.
+///
+async fn upload<Req, R>(
+    ws: &WebSocket,
+    items: impl Stream<Item = Req>,
+    credits: Receiver<u32>,
+    response: Receiver<Result<R>>,
+    frame: impl Fn(Option<Req>) -> Result<WebSocketMessage>,
+) -> Result<R> {
+    let items = items.fuse();
+    futures::pin_mut!(items);
+    let mut credit = STREAM_REQUEST_WINDOW;
+    let mut ended = false;
+
+    loop {
+        let item = if credit > 0 && !ended {
+            items.next().left_future()
+        } else {
+            future::pending().right_future()
+        };
+
+        select_biased! {
+            response = response.recv().fuse() => return response?,
+            granted = credits.recv().fuse() => credit += granted?,
+            item = item.fuse() => {
+                match item {
+                    Some(item) => {
+                        ws.post(frame(Some(item))?).await?;
+                        credit -= 1;
+                    }
+                    None => {
+                        ws.post(frame(None)?).await?;
+                        ended = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::FutureExt;
+
+    fn stream_receiver(
+        items: Receiver<Result<u32>>,
+    ) -> (StreamReceiver<u32>, Arc<Mutex<Vec<StreamControl>>>) {
+        let controls = Arc::new(Mutex::new(Vec::new()));
+        let stream = {
+            let controls = controls.clone();
+            StreamReceiver::new(items, move |control| controls.lock().unwrap().push(control))
+        };
+        (stream, controls)
+    }
+
+    #[test]
+    fn test_stream_receiver_credit() {
+        let (sender, receiver) = unbounded();
+        let (mut stream, controls) = stream_receiver(receiver);
+
+        for item in 0..STREAM_RESPONSE_WINDOW {
+            sender.try_send(Ok(item)).unwrap();
+        }
+        let threshold = STREAM_RESPONSE_WINDOW / 2;
+        for item in 0..threshold {
+            assert_eq!(
+                stream.next().now_or_never().unwrap().unwrap().unwrap(),
+                item
+            );
+        }
+        assert_eq!(
+            *controls.lock().unwrap(),
+            vec![StreamControl::Credit(threshold)]
+        );
+
+        // the stream ended by the server is not cancelled
+        drop(sender);
+        assert_eq!(
+            futures::executor::block_on(stream.count()) as u32,
+            STREAM_RESPONSE_WINDOW - threshold
+        );
+        assert_eq!(
+            *controls.lock().unwrap(),
+            vec![
+                StreamControl::Credit(threshold),
+                StreamControl::Credit(threshold)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_receiver_cancel() {
+        let (sender, receiver) = unbounded();
+        let (mut stream, controls) = stream_receiver(receiver);
+
+        sender.try_send(Ok(1)).unwrap();
+        assert!(stream.next().now_or_never().is_some());
+        drop(stream);
+        assert_eq!(*controls.lock().unwrap(), vec![StreamControl::Cancel]);
+
+        // the stream terminated by the error is not cancelled
+        let (sender, receiver) = unbounded();
+        let (mut stream, controls) = stream_receiver(receiver);
+        sender.try_send(Err(Error::Disconnect)).unwrap();
+        assert!(stream.next().now_or_never().unwrap().unwrap().is_err());
+        drop(stream);
+        assert!(controls.lock().unwrap().is_empty());
+    }
+}
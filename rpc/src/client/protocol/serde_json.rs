@@ -1,11 +1,15 @@
 use core::marker::PhantomData;
 
-use super::{Pending, PendingMap, ProtocolHandler};
+use super::{
+    spawn_post, upload, CallStream, Credits, Frame, Pending, PendingMap, ProtocolHandler,
+    StreamControl, StreamReceiver, Streams,
+};
 pub use crate::client::error::Error;
 pub use crate::client::result::Result;
 use crate::client::Interface;
 use crate::imports::*;
 use crate::messages::serde_json::*;
+use futures::{Stream, StreamExt};
 
 pub type JsonResponseFn =
     Arc<Box<(dyn Fn(Result<Value>, Option<&Duration>) -> Result<()> + Sync + Send)>>;
@@ -18,6 +22,8 @@ where
 {
     ws: Arc<WebSocket>,
     pending: PendingMap<Id, JsonResponseFn>,
+    streams: Arc<Streams<Id, Value>>,
+    credits: Credits<Id>,
     interface: Option<Arc<Interface<Ops>>>,
     // ops: PhantomData<Ops>,
     id: PhantomData<Id>,
@@ -32,6 +38,8 @@ where
         JsonProtocol::<Ops, Id> {
             ws,
             pending: Arc::new(Mutex::new(AHashMap::new())),
+            streams: Arc::new(Streams::new()),
+            credits: Credits::new(),
             interface,
             // ops: PhantomData,
             id: PhantomData,
@@ -39,7 +47,7 @@ where
     }
 }
 
-type MessageInfo<Ops, Id> = (Option<Id>, Option<Ops>, Result<Value>);
+type MessageInfo<Ops, Id> = (Option<Id>, Option<Ops>, Frame, Result<Value>);
 
 impl<Ops, Id> JsonProtocol<Ops, Id>
 where
@@ -48,19 +56,35 @@ where
 {
     fn decode(&self, server_message: &str) -> Result<MessageInfo<Ops, Id>> {
         let msg: JSONServerMessage<Ops, Id> = serde_json::from_str(server_message)?;
+        let frame = match msg.stream {
+            None => Frame::Response,
+            Some(StreamFrame::Item) => Frame::StreamItem,
+            Some(StreamFrame::End) => Frame::StreamEnd,
+            Some(StreamFrame::Credit) => Frame::StreamCredit,
+            Some(StreamFrame::Cancel) => {
+                return Err(Error::SerdeDeserialize(
+                    "unexpected stream cancel frame".to_string(),
+                ));
+            }
+        };
+
+        if msg.request {
+            let params = msg.params.unwrap_or(Value::Null);
+            return Ok((msg.id, msg.method, Frame::Request, Ok(params)));
+        }
 
         if let Some(error) = msg.error {
-            Ok((msg.id, None, Err(error.into())))
+            Ok((msg.id, None, frame, Err(error.into())))
         } else if msg.id.is_some() {
             if let Some(result) = msg.params {
-                Ok((msg.id, None, Ok(result)))
+                Ok((msg.id, None, frame, Ok(result)))
             } else {
-                Ok((msg.id, None, Err(Error::NoDataInSuccessResponse)))
+                Ok((msg.id, None, frame, Err(Error::NoDataInSuccessResponse)))
             }
         } else if let Some(params) = msg.params {
-            Ok((None, msg.method, Ok(params)))
+            Ok((None, msg.method, frame, Ok(params)))
         } else {
-            Ok((None, None, Err(Error::NoDataInNotificationMessage)))
+            Ok((None, None, frame, Err(Error::NoDataInNotificationMessage)))
         }
     }
 
@@ -69,17 +93,34 @@ where
         Req: MsgT,
         Resp: MsgT,
     {
-        let id = Id::generate();
+        self.request_with_id(Id::generate(), op, req, None).await
+    }
+
+    /// Issue the request using the supplied `id`, optionally overriding the client timeout
+    pub async fn request_with_id<Req, Resp>(
+        &self,
+        id: Id,
+        op: Ops,
+        req: Req,
+        timeout: Option<Duration>,
+    ) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
         let (sender, receiver) = oneshot();
 
         {
             let mut pending = self.pending.lock().unwrap();
             pending.insert(
                 id.clone(),
-                Pending::new(Arc::new(Box::new(move |result, _duration| {
-                    sender.try_send(result)?;
-                    Ok(())
-                }))),
+                Pending::new(
+                    Arc::new(Box::new(move |result, _duration| {
+                        sender.try_send(result)?;
+                        Ok(())
+                    })),
+                    timeout,
+                ),
             );
         }
 
@@ -96,6 +137,113 @@ where
         Ok(resp)
     }
 
+    /// Issue the streaming method request, returns the stream of the items
+    pub async fn request_stream<Req, Resp>(&self, op: Ops, req: Req) -> Result<CallStream<Resp>>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let payload = serde_json::to_value(req)?;
+        let id = Id::generate();
+        let client_message = JsonClientMessage::new(Some(id.clone()), op.clone(), payload);
+        let json = serde_json::to_string(&client_message)?;
+
+        let receiver = self.streams.insert(id.clone());
+        if let Err(err) = self.ws.post(WebSocketMessage::Text(json)).await {
+            self.streams.remove(&id);
+            return Err(err.into());
+        }
+
+        let ws = self.ws.clone();
+        let streams = self.streams.clone();
+        let control = move |control| {
+            let (frame, params) = match control {
+                StreamControl::Credit(credit) => (StreamFrame::Credit, Value::from(credit)),
+                StreamControl::Cancel => {
+                    streams.remove(&id);
+                    (StreamFrame::Cancel, Value::Null)
+                }
+            };
+            let client_message =
+                JsonClientMessage::new(Some(id.clone()), op.clone(), params).with_stream(frame);
+            let msg = serde_json::to_string(&client_message)
+                .map(WebSocketMessage::Text)
+                .map_err(Error::from);
+            spawn_post(&ws, msg);
+        };
+
+        Ok(StreamReceiver::new(receiver, control)
+            .map(|item| {
+                <Resp as Deserialize>::deserialize(item?)
+                    .map_err(|e| Error::SerdeDeserialize(e.to_string()))
+            })
+            .boxed())
+    }
+
+    /// Issue the client-streaming method request sending the `items`
+    /// within the credit granted by the server, returns the response
+    pub async fn request_with_stream<Req, Resp>(
+        &self,
+        op: Ops,
+        items: impl Stream<Item = Req> + Send,
+    ) -> Result<Resp>
+    where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let id = Id::generate();
+        let (sender, receiver) = oneshot();
+
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.insert(
+                id.clone(),
+                Pending::new(
+                    Arc::new(Box::new(move |result, _duration| {
+                        sender.try_send(result)?;
+                        Ok(())
+                    })),
+                    None,
+                ),
+            );
+        }
+        let credits = self.credits.insert(id.clone());
+
+        let frame = |request: JsonStreamRequest| {
+            let client_message = JsonClientMessage::new(
+                Some(id.clone()),
+                op.clone(),
+                serde_json::to_value(request)?,
+            );
+            Ok(WebSocketMessage::Text(serde_json::to_string(
+                &client_message,
+            )?))
+        };
+
+        let result = async {
+            self.ws.post(frame(JsonStreamRequest::Open)?).await?;
+            upload(&self.ws, items, credits, receiver, |item| match item {
+                Some(item) => frame(JsonStreamRequest::Item(serde_json::to_value(item)?)),
+                None => frame(JsonStreamRequest::End),
+            })
+            .await
+        }
+        .await;
+
+        self.pending.lock().unwrap().remove(&id);
+        self.credits.remove(&id);
+
+        let resp = <Resp as Deserialize>::deserialize(result?)
+            .map_err(|e| Error::SerdeDeserialize(e.to_string()))?;
+        Ok(resp)
+    }
+
+    /// Remove the pending request, returns `false` if the
+    /// response has already been received
+    pub fn cancel(&self, id: &Id) -> bool {
+        self.pending.lock().unwrap().remove(id).is_some()
+    }
+
     pub async fn notify<Msg>(&self, op: Ops, data: Msg) -> Result<()>
     where
         Msg: Serialize + Send + Sync + 'static,
@@ -119,6 +267,36 @@ where
 
         Ok(())
     }
+
+    /// Call the client-side method requested by the server, posting
+    /// the response from a separate task (the method can call the server)
+    fn handle_request(&self, id: Option<Id>, op: Option<Ops>, payload: Value) -> Result<()> {
+        let (Some(id), Some(op)) = (id, op) else {
+            return Err(Error::RequestMethod);
+        };
+
+        let ws = self.ws.clone();
+        let interface = self.interface.clone();
+        workflow_core::task::spawn(async move {
+            let result = match &interface {
+                Some(interface) => interface.call_method_with_serde_json(&op, payload).await,
+                None => Err(ServerError::NotFound),
+            };
+            let response = JsonClientMessage::response(id, op, result.map_err(Into::into));
+            match serde_json::to_string(&response) {
+                Ok(json) => {
+                    if let Err(err) = ws.post(WebSocketMessage::Text(json)).await {
+                        log_trace!("unable to post the response to the server request: {err}");
+                    }
+                }
+                Err(err) => {
+                    log_trace!("error serializing the response to the server request: {err}")
+                }
+            }
+        });
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -136,7 +314,7 @@ where
 
     async fn handle_timeout(&self, timeout: Duration) {
         self.pending.lock().unwrap().retain(|_, pending| {
-            if pending.timestamp.elapsed() > timeout {
+            if pending.is_expired(timeout) {
                 (pending.callback)(Err(Error::Timeout), None).unwrap_or_else(|err| {
                     log_trace!("Error in RPC callback during timeout: `{err}`")
                 });
@@ -149,10 +327,26 @@ where
 
     async fn handle_message(&self, message: WebSocketMessage) -> Result<()> {
         if let WebSocketMessage::Text(server_message) = message {
-            let (id, method, result) = self.decode(server_message.as_str())?;
-            if let Some(id) = id {
-                if let Some(pending) = self.pending.lock().unwrap().remove(&id) {
+            let (id, method, frame, result) = self.decode(server_message.as_str())?;
+            if frame == Frame::Request {
+                self.handle_request(id, method, result?)
+            } else if let Some(id) = id {
+                let pending = match frame {
+                    Frame::Response => self.pending.lock().unwrap().remove(&id),
+                    _ => None,
+                };
+                if let Some(pending) = pending {
                     (pending.callback)(result, Some(&pending.timestamp.elapsed()))
+                } else if frame == Frame::StreamCredit {
+                    let credit = serde_json::from_value(result?)
+                        .map_err(|e| Error::SerdeDeserialize(e.to_string()))?;
+                    if let Some(pending) = self.pending.lock().unwrap().get_mut(&id) {
+                        pending.timestamp = Instant::now();
+                    }
+                    self.credits.grant(&id, credit);
+                    Ok(())
+                } else if self.streams.dispatch(&id, frame, result) {
+                    Ok(())
                 } else {
                     Err(Error::ResponseHandler(format!("{id:?}")))
                 }
@@ -175,6 +369,7 @@ where
                 .unwrap_or_else(|err| log_trace!("Error in RPC callback during timeout: `{err}`"));
             false
         });
+        self.streams.disconnect();
 
         Ok(())
     }
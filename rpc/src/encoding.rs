@@ -3,6 +3,7 @@
 //!
 
 use crate::error::Error;
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Debug, Display, Formatter},
@@ -13,7 +14,18 @@ use wasm_bindgen::prelude::*;
 
 /// wRPC protocol encoding: `Borsh` or `JSON`
 /// @category Transport
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Hash,
+    Eq,
+    PartialEq,
+)]
 #[wasm_bindgen]
 #[serde(rename_all = "kebab-case")]
 pub enum Encoding {
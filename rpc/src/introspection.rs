@@ -0,0 +1,52 @@
+//!
+//! Module containing the [`Introspection`] data structure describing
+//! the RPC methods and notifications registered on the server
+//! [`Interface`](crate::server::Interface).
+//!
+
+use crate::imports::*;
+
+/// Description of an RPC method or notification op
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct OpInfo {
+    /// Name of the op (the `Debug` representation of the op)
+    pub name: String,
+    /// Identifier of the op when using the `Borsh` encoding
+    /// (the Borsh-serialized op)
+    pub borsh: Vec<u8>,
+    /// Identifier of the op when using the `JSON` encoding
+    /// (the JSON-serialized op, supplied as the `method` field)
+    pub json: String,
+}
+
+impl OpInfo {
+    pub fn new<Ops>(op: &Ops) -> Self
+    where
+        Ops: OpsT,
+    {
+        OpInfo {
+            name: format!("{op:?}"),
+            borsh: op.try_to_vec().unwrap_or_default(),
+            json: serde_json::to_string(op).unwrap_or_default(),
+        }
+    }
+}
+
+///
+/// Response of the introspection method enabled using
+/// [`Interface::introspection()`](crate::server::Interface::introspection),
+/// obtained by the client using [`RpcClient::introspect()`](crate::client::RpcClient::introspect).
+///
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct Introspection {
+    /// Encoding of the server
+    pub encoding: Encoding,
+    /// RPC methods sorted by name (including the introspection method)
+    pub methods: Vec<OpInfo>,
+    /// RPC streaming methods sorted by name
+    pub streaming_methods: Vec<OpInfo>,
+    /// RPC client-streaming methods sorted by name
+    pub client_streaming_methods: Vec<OpInfo>,
+    /// RPC notifications sorted by name
+    pub notifications: Vec<OpInfo>,
+}
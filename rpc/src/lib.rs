@@ -14,6 +14,7 @@
 //! - Client to Server RPC method invocation
 //! - Client to Server notification messages
 //! - Server to Client notification messages
+//! - Server to Client RPC method invocation (see [`server::Messenger::call()`])
 //! - Server-side handshake scaffolding for custom connection negotiation
 //! - Easy to retain connection data structure for posting async client notifications
 //!
@@ -68,6 +69,7 @@ pub mod client;
 pub mod error;
 pub mod id;
 mod imports;
+pub mod introspection;
 pub mod messages;
 pub mod result;
 pub mod types;
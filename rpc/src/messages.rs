@@ -2,6 +2,14 @@
 //! RPC message serialization module (header serialization and deserialization for `Borsh` and `JSON` data structures)
 //!
 
+/// Number of the client stream items the client can send before receiving
+/// the credit from the server (the initial credit of the client-streaming call)
+pub const STREAM_REQUEST_WINDOW: u32 = 16;
+
+/// Number of the streaming method response items the server can send before
+/// receiving the credit from the client (the initial credit of the stream)
+pub const STREAM_RESPONSE_WINDOW: u32 = 16;
+
 pub mod serde_json {
     //! RPC message serialization for JSON encoding
     use serde::{Deserialize, Serialize};
@@ -13,6 +21,20 @@ pub mod serde_json {
         pub id: Option<Id>,
         pub method: Ops,
         pub params: Value,
+        /// Error of the client-side method responding to the request
+        /// of the server (see [`JSONServerMessage::request`])
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub error: Option<JsonServerError>,
+        /// Set if the message is the response of the client-side method to
+        /// the request of the server (otherwise the message is dispatched
+        /// as a request or a notification of the client)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pub response: bool,
+        /// Control frame of the streaming method response: the `credit`
+        /// frame carries the number of the items the server can send in
+        /// `params`, the `cancel` frame terminates the stream
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub stream: Option<StreamFrame>,
     }
 
     impl<Ops, Id> JsonClientMessage<Ops, Id> {
@@ -22,8 +44,33 @@ pub mod serde_json {
                 id,
                 method,
                 params: payload,
+                error: None,
+                response: false,
+                stream: None,
+            }
+        }
+
+        /// Create the response to the request of the server
+        pub fn response(id: Id, method: Ops, result: Result<Value, JsonServerError>) -> Self {
+            let (params, error) = match result {
+                Ok(params) => (params, None),
+                Err(error) => (Value::Null, Some(error)),
+            };
+            JsonClientMessage {
+                id: Some(id),
+                method,
+                params,
+                error,
+                response: true,
+                stream: None,
             }
         }
+
+        /// Mark the message as a control frame of the streaming method response
+        pub fn with_stream(mut self, stream: StreamFrame) -> Self {
+            self.stream = Some(stream);
+            self
+        }
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -39,6 +86,13 @@ pub mod serde_json {
         // pub result: Option<Value>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub error: Option<JsonServerError>,
+        /// Stream frame of the streaming method response
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub stream: Option<StreamFrame>,
+        /// Set if the message is a request of the server to the
+        /// client-side method (the client responds with the `id`)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pub request: bool,
     }
 
     impl<Ops, Id> JSONServerMessage<Ops, Id> {
@@ -56,8 +110,48 @@ pub mod serde_json {
                 // result,
                 error,
                 id,
+                stream: None,
+                request: false,
             }
         }
+
+        /// Mark the message as a request of the server to the client-side method
+        pub fn with_request(mut self) -> Self {
+            self.request = true;
+            self
+        }
+
+        /// Mark the message as a frame of the streaming method response
+        pub fn with_stream(mut self, stream: StreamFrame) -> Self {
+            self.stream = Some(stream);
+            self
+        }
+    }
+
+    /// Frame of the streaming method response: the `item` frame
+    /// carries a stream item in `params` (or the `error` terminating
+    /// the stream), the `end` frame terminates the stream.  The `credit`
+    /// frame carries the number of the stream items the peer can send
+    /// in `params` (sent by the server to the client-streaming call and
+    /// by the client to the streaming method response).  The `cancel`
+    /// frame is sent by the client dropping the streaming method response.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum StreamFrame {
+        Item,
+        End,
+        Credit,
+        Cancel,
+    }
+
+    /// Frame of the client-streaming method request supplied as `params`
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum JsonStreamRequest {
+        /// Open the call (sent once, before the items)
+        Open,
+        Item(Value),
+        End,
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +180,12 @@ pub mod serde_json {
             }
         }
     }
+
+    impl From<JsonServerError> for crate::error::ServerError {
+        fn from(err: JsonServerError) -> Self {
+            crate::error::ServerError::Text(err.message)
+        }
+    }
 }
 
 pub mod borsh {
@@ -116,6 +216,32 @@ pub mod borsh {
         buffer.into()
     }
 
+    /// Leading byte of the client messages other than the requests, followed
+    /// by the [`ClientMessageKind`] and the [`BorshReqHeader`].  The byte is
+    /// never a valid `Option` tag, so the request header layout is unchanged.
+    pub const CLIENT_MESSAGE_KIND_TAG: u8 = 0xff;
+
+    /// Create the client message of the given `kind` (see [`CLIENT_MESSAGE_KIND_TAG`])
+    pub fn to_ws_msg_with_kind<Ops, Id>(
+        kind: ClientMessageKind,
+        header: BorshReqHeader<Ops, Id>,
+        payload: &[u8],
+    ) -> WebSocketMessage
+    where
+        Id: BorshSerialize + BorshDeserialize,
+        Ops: BorshSerialize + BorshDeserialize,
+    {
+        if kind == ClientMessageKind::Request {
+            return to_ws_msg(header, payload);
+        }
+        let mut buffer = vec![CLIENT_MESSAGE_KIND_TAG];
+        kind.serialize(&mut buffer)
+            .and_then(|_| header.serialize(&mut buffer))
+            .expect("to_ws_msg header serialize error");
+        buffer.extend_from_slice(payload);
+        buffer.into()
+    }
+
     #[derive(Debug, BorshSerialize, BorshDeserialize)]
     pub struct BorshReqHeader<Ops, Id>
     where
@@ -136,6 +262,21 @@ pub mod borsh {
         }
     }
 
+    /// Kind of the client message, sent with the [`CLIENT_MESSAGE_KIND_TAG`]
+    /// prefix for the messages other than the requests
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+    pub enum ClientMessageKind {
+        /// Request of the server method (or the notification if the `id` is `None`)
+        Request,
+        /// Response of the client-side method to the [`ServerMessageKind::Request`],
+        /// carrying the serialized `ServerResult` payload
+        Response,
+        /// Credit granted to the streaming method response (`u32` payload)
+        StreamCredit,
+        /// Cancel the streaming method response (the stream has been dropped)
+        StreamCancel,
+    }
+
     #[derive(Debug, BorshSerialize, BorshDeserialize)]
     pub struct BorshServerMessageHeader<Ops, Id> {
         pub id: Option<Id>, //u64,
@@ -157,6 +298,25 @@ pub mod borsh {
         Success = 0,
         Error = 1,
         Notification = 0xff,
+        /// Stream item of the streaming method response (terminated
+        /// by the [`ServerMessageKind::StreamEnd`] or the `Error` message)
+        StreamItem = 2,
+        StreamEnd = 3,
+        /// Credit granted to the client-streaming call (`u32` payload)
+        StreamCredit = 4,
+        /// Request of the server to the client-side method, the client
+        /// responds with the `id` and the serialized `ServerResult` payload
+        Request = 5,
+    }
+
+    /// Frame of the client-streaming method request, prefixing the payload
+    /// (the [`StreamRequestKind::Item`] is followed by the serialized item)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+    pub enum StreamRequestKind {
+        /// Open the call (sent once, before the items)
+        Open,
+        Item,
+        End,
     }
 
     impl From<ServerMessageKind> for u32 {
@@ -181,6 +341,7 @@ pub mod borsh {
         Id: BorshSerialize + BorshDeserialize + 'data,
         Ops: BorshSerialize + BorshDeserialize + 'data,
     {
+        pub kind: ClientMessageKind,
         pub header: BorshReqHeader<Ops, Id>,
         pub payload: &'data [u8],
     }
@@ -207,8 +368,19 @@ pub mod borsh {
 
         fn try_from(src: &'data [u8]) -> Result<Self, Self::Error> {
             let mut payload = src;
+            let kind = match payload.split_first() {
+                Some((&CLIENT_MESSAGE_KIND_TAG, rest)) => {
+                    payload = rest;
+                    ClientMessageKind::deserialize(&mut payload)?
+                }
+                _ => ClientMessageKind::Request,
+            };
             let header = BorshReqHeader::<Ops, Id>::deserialize(&mut payload)?;
-            let message = BorshClientMessage { header, payload };
+            let message = BorshClientMessage {
+                kind,
+                header,
+                payload,
+            };
             Ok(message)
         }
     }
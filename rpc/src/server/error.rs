@@ -22,4 +22,12 @@ pub enum Error {
 
     #[error("SerdeJSON error: {0}")]
     SerdeJSON(#[from] serde_json::Error),
+
+    /// Error returned by the client-side method (or the failure
+    /// of the call such as a timeout) to [`Messenger::call()`](super::Messenger::call)
+    #[error("RPC call error: {0}")]
+    RpcCall(#[from] crate::error::ServerError),
+
+    #[error("RPC calls to the client are not supported by the messenger")]
+    CallNotSupported,
 }
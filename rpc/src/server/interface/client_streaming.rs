@@ -0,0 +1,117 @@
+//! Module containing RPC [`ClientStreamingMethod`] closure wrappers
+use super::method::MethodFnReturn;
+use crate::imports::*;
+use futures::stream::{BoxStream, StreamExt};
+
+/// Stream of the items sent by the client to the [`ClientStreamingMethod`].
+/// The stream ends once the client has sent all items or the connection
+/// has been closed, an error is yielded if the item can not be deserialized.
+pub type RequestStream<Req> = BoxStream<'static, ServerResult<Req>>;
+
+/// Base trait representing an RPC client-streaming method, used to retain
+/// client-streaming method structures in an [`Interface`](super::Interface)
+/// map without generics.
+#[async_trait]
+pub(crate) trait ClientStreamingMethodTrait<ServerContext, ConnectionContext>:
+    Send + Sync + 'static
+{
+    /// Returns the serialized [`ServerResult`] of the handler
+    /// (transported as the response data) and the handler error
+    async fn call_with_borsh(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Vec<u8>>,
+    ) -> ServerResult<(Vec<u8>, Option<ServerError>)>;
+    async fn call_with_serde_json(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Value>,
+    ) -> ServerResult<Value>;
+}
+
+/// RPC client-streaming method function type
+pub type ClientStreamingMethodFn<ServerContext, ConnectionContext, Req, Resp> = Arc<
+    Box<
+        dyn Send
+            + Sync
+            + Fn(ServerContext, ConnectionContext, RequestStream<Req>) -> MethodFnReturn<Resp>
+            + 'static,
+    >,
+>;
+
+///
+/// RPC client-streaming method wrapper. Contains the method closure function
+/// receiving the [`RequestStream`] of the items sent by the client and
+/// resolving to a single response.  The client sends the items as the
+/// handler consumes them (see [`STREAM_REQUEST_WINDOW`](crate::messages::STREAM_REQUEST_WINDOW)).
+///
+pub struct ClientStreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    method: ClientStreamingMethodFn<ServerContext, ConnectionContext, Req, Resp>,
+}
+
+impl<ServerContext, ConnectionContext, Req, Resp>
+    ClientStreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    pub fn new<FN>(
+        method_fn: FN,
+    ) -> ClientStreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+    where
+        FN: Send
+            + Sync
+            + Fn(ServerContext, ConnectionContext, RequestStream<Req>) -> MethodFnReturn<Resp>
+            + 'static,
+    {
+        ClientStreamingMethod {
+            method: Arc::new(Box::new(method_fn)),
+        }
+    }
+}
+
+#[async_trait]
+impl<ServerContext, ConnectionContext, Req, Resp>
+    ClientStreamingMethodTrait<ServerContext, ConnectionContext>
+    for ClientStreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    async fn call_with_borsh(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Vec<u8>>,
+    ) -> ServerResult<(Vec<u8>, Option<ServerError>)> {
+        let items = items
+            .map(|item| Req::try_from_slice(&item?).map_err(|_| ServerError::ReqDeserialize))
+            .boxed();
+        let resp = (self.method)(server_ctx, connection_ctx, items).await;
+        let vec = <ServerResult<Resp> as BorshSerialize>::try_to_vec(&resp)?;
+        Ok((vec, resp.err()))
+    }
+
+    async fn call_with_serde_json(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Value>,
+    ) -> ServerResult<Value> {
+        let items = items
+            .map(|item| serde_json::from_value(item?).map_err(|_| ServerError::ReqDeserialize))
+            .boxed();
+        let resp = (self.method)(server_ctx, connection_ctx, items).await?;
+        Ok(serde_json::to_value(resp).map_err(|_| ServerError::RespSerialize)?)
+    }
+}
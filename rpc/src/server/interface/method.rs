@@ -8,12 +8,14 @@ use crate::imports::*;
 pub(crate) trait MethodTrait<ServerContext, ConnectionContext>:
     Send + Sync + 'static
 {
+    /// Returns the serialized [`ServerResult`] of the handler
+    /// (transported as the response data) and the handler error
     async fn call_with_borsh(
         &self,
         server_ctx: ServerContext,
         connection_ctx: ConnectionContext,
         data: &[u8],
-    ) -> ServerResult<Vec<u8>>;
+    ) -> ServerResult<(Vec<u8>, Option<ServerError>)>;
     async fn call_with_serde_json(
         &self,
         server_ctx: ServerContext,
@@ -79,11 +81,11 @@ where
         server_ctx: ServerContext,
         connection_ctx: ConnectionContext,
         data: &[u8],
-    ) -> ServerResult<Vec<u8>> {
+    ) -> ServerResult<(Vec<u8>, Option<ServerError>)> {
         let req = Req::try_from_slice(data)?;
         let resp = (self.method)(server_ctx, connection_ctx, req).await;
         let vec = <ServerResult<Resp> as BorshSerialize>::try_to_vec(&resp)?;
-        Ok(vec)
+        Ok((vec, resp.err()))
     }
 
     async fn call_with_serde_json(
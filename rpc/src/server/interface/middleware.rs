@@ -0,0 +1,47 @@
+//! Module containing the RPC [`Middleware`] trait
+use crate::imports::*;
+
+/// Raw payload of the RPC method or notification supplied to the [`Middleware`]
+#[derive(Debug, Clone, Copy)]
+pub enum Payload<'data> {
+    Borsh(&'data [u8]),
+    SerdeJson(&'data Value),
+}
+
+///
+/// Middleware invoked around each RPC method and notification dispatch,
+/// registered using [`Interface::middleware()`](super::Interface::middleware).
+/// Middleware can be used to authenticate the requests (e.g. validating a
+/// token stored in the `ConnectionContext` during the handshake), audit
+/// logging or metrics without wrapping the [`Method`](super::Method) closures.
+///
+/// The `before` hooks are invoked in the order of registration prior to
+/// the dispatch; the `after` hooks are invoked in the same order once the
+/// handler has completed (including the calls rejected by a `before` hook).
+///
+#[async_trait]
+pub trait Middleware<ConnectionContext, Ops>: Send + Sync + 'static {
+    /// Invoked before the handler.  Returning an error rejects the call:
+    /// the method responds to the client with the error (or closes the
+    /// connection if the error is [`ServerError::Close`]), the notification
+    /// is discarded.
+    async fn before(
+        &self,
+        _connection_ctx: &ConnectionContext,
+        _op: &Ops,
+        _payload: Payload<'_>,
+    ) -> ServerResult<()> {
+        Ok(())
+    }
+
+    /// Invoked after the handler with the result of the call
+    /// and the time elapsed since the dispatch has started.
+    async fn after(
+        &self,
+        _connection_ctx: &ConnectionContext,
+        _op: &Ops,
+        _result: std::result::Result<(), &ServerError>,
+        _elapsed: Duration,
+    ) {
+    }
+}
@@ -3,12 +3,20 @@
 //! mappings of RPC method and notification handlers.
 //!
 
+pub mod client_streaming;
 pub mod method;
+pub mod middleware;
 pub mod notification;
+pub mod streaming;
 
 use crate::imports::*;
+use crate::introspection::{Introspection, OpInfo};
+pub use client_streaming::*;
+use futures::stream::{self, StreamExt};
 pub use method::*;
+pub use middleware::*;
 pub use notification::*;
+pub use streaming::*;
 
 /// [`Interface`] struct carries a mapping of RPC methods
 /// and notifications, used by protocols to dispatch calls
@@ -22,6 +30,12 @@ where
     server_ctx: ServerContext,
     methods: AHashMap<Ops, Box<dyn MethodTrait<ServerContext, ConnectionContext>>>,
     notifications: AHashMap<Ops, Box<dyn NotificationTrait<ServerContext, ConnectionContext>>>,
+    streaming_methods:
+        AHashMap<Ops, Box<dyn StreamingMethodTrait<ServerContext, ConnectionContext>>>,
+    client_streaming_methods:
+        AHashMap<Ops, Box<dyn ClientStreamingMethodTrait<ServerContext, ConnectionContext>>>,
+    middlewares: Vec<Arc<dyn Middleware<ConnectionContext, Ops>>>,
+    introspection: Option<Ops>,
 }
 
 impl<ServerContext, ConnectionContext, Ops> Interface<ServerContext, ConnectionContext, Ops>
@@ -39,6 +53,10 @@ where
             server_ctx,
             methods: AHashMap::new(),
             notifications: AHashMap::new(),
+            streaming_methods: AHashMap::new(),
+            client_streaming_methods: AHashMap::new(),
+            middlewares: Vec::new(),
+            introspection: None,
         }
     }
 
@@ -70,10 +88,102 @@ where
         Req: MsgT,
         Resp: MsgT,
     {
+        if self.contains_method(&op) {
+            panic!("RPC method {op:?} is declared multiple times")
+        }
         let method: Box<dyn MethodTrait<ServerContext, ConnectionContext>> = Box::new(method);
-        if self.methods.insert(op.clone(), method).is_some() {
+        self.methods.insert(op, method);
+    }
+
+    ///
+    /// Declare an RPC streaming method handler resolving to a [`ResponseStream`].
+    /// You can use a [`streaming_method!()`](macro@crate::server::streaming_method)
+    /// macro to declare the method as follows:
+    ///
+    /// ```ignore
+    /// interface.streaming_method(MyOps::List, streaming_method!(
+    ///   | connection_ctx: ConnectionCtx,
+    ///     server_ctx: ServerContext,
+    ///     req: MyReq |
+    /// async move {
+    ///     let items = server_ctx.items(req).await?;
+    ///     Ok(futures::stream::iter(items.into_iter().map(Ok)).boxed())
+    /// }))
+    /// ```
+    ///
+    /// The client receives the items using
+    /// [`RpcClient::call_stream()`](crate::client::RpcClient::call_stream).
+    ///
+    pub fn streaming_method<Req, Resp>(
+        &mut self,
+        op: Ops,
+        method: StreamingMethod<ServerContext, ConnectionContext, Req, Resp>,
+    ) where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        if self.contains_method(&op) {
             panic!("RPC method {op:?} is declared multiple times")
         }
+        let method: Box<dyn StreamingMethodTrait<ServerContext, ConnectionContext>> =
+            Box::new(method);
+        self.streaming_methods.insert(op, method);
+    }
+
+    ///
+    /// Declare an RPC client-streaming method handler receiving the
+    /// [`RequestStream`] of the items sent by the client. You can use a
+    /// [`client_streaming_method!()`](macro@crate::server::client_streaming_method)
+    /// macro to declare the method as follows:
+    ///
+    /// ```ignore
+    /// interface.client_streaming_method(MyOps::Upload, client_streaming_method!(
+    ///   | connection_ctx: ConnectionCtx,
+    ///     server_ctx: ServerContext,
+    ///     mut chunks: RequestStream<MyChunk> |
+    /// async move {
+    ///     let mut len = 0;
+    ///     while let Some(chunk) = chunks.next().await {
+    ///         len += server_ctx.store(chunk?).await?;
+    ///     }
+    ///     Ok(MyUploadResp { len })
+    /// }))
+    /// ```
+    ///
+    /// The client sends the items using
+    /// [`RpcClient::call_with_stream()`](crate::client::RpcClient::call_with_stream).
+    ///
+    pub fn client_streaming_method<Req, Resp>(
+        &mut self,
+        op: Ops,
+        method: ClientStreamingMethod<ServerContext, ConnectionContext, Req, Resp>,
+    ) where
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        if self.contains_method(&op) {
+            panic!("RPC method {op:?} is declared multiple times")
+        }
+        let method: Box<dyn ClientStreamingMethodTrait<ServerContext, ConnectionContext>> =
+            Box::new(method);
+        self.client_streaming_methods.insert(op, method);
+    }
+
+    fn contains_method(&self, op: &Ops) -> bool {
+        self.introspection.as_ref() == Some(op)
+            || self.methods.contains_key(op)
+            || self.streaming_methods.contains_key(op)
+            || self.client_streaming_methods.contains_key(op)
+    }
+
+    /// Returns `true` if the op is bound to a streaming method
+    pub(crate) fn is_streaming_method(&self, op: &Ops) -> bool {
+        self.streaming_methods.contains_key(op)
+    }
+
+    /// Returns `true` if the op is bound to a client-streaming method
+    pub(crate) fn is_client_streaming_method(&self, op: &Ops) -> bool {
+        self.client_streaming_methods.contains_key(op)
     }
 
     ///
@@ -107,19 +217,142 @@ where
         }
     }
 
+    ///
+    /// Enable the built-in introspection method bound to the supplied op.
+    /// The method responds with the [`Introspection`] listing the registered
+    /// methods and notifications and the encoding of the server, allowing
+    /// tooling and debugging consoles to discover the interface of the server
+    /// (see [`RpcClient::introspect()`](crate::client::RpcClient::introspect)).
+    /// The method accepts any request and is subject to the [`Middleware`].
+    ///
+    /// ```ignore
+    /// #[derive(Serialize, Deserialize, ...)]
+    /// enum MyOps {
+    ///     #[serde(rename = "__introspect")]
+    ///     Introspect,
+    ///     // ...
+    /// }
+    ///
+    /// interface.introspection(MyOps::Introspect);
+    /// ```
+    ///
+    pub fn introspection(&mut self, op: Ops) {
+        if self.introspection.is_some() || self.contains_method(&op) {
+            panic!("RPC method {op:?} is declared multiple times")
+        }
+        self.introspection = Some(op);
+    }
+
+    /// Create the [`Introspection`] of the interface
+    pub fn introspect(&self, encoding: Encoding) -> Introspection {
+        let mut methods = self
+            .methods
+            .keys()
+            .chain(self.introspection.iter())
+            .map(OpInfo::new)
+            .collect::<Vec<_>>();
+        let mut streaming_methods = self
+            .streaming_methods
+            .keys()
+            .map(OpInfo::new)
+            .collect::<Vec<_>>();
+        let mut client_streaming_methods = self
+            .client_streaming_methods
+            .keys()
+            .map(OpInfo::new)
+            .collect::<Vec<_>>();
+        let mut notifications = self
+            .notifications
+            .keys()
+            .map(OpInfo::new)
+            .collect::<Vec<_>>();
+        methods.sort_by(|a, b| a.name.cmp(&b.name));
+        streaming_methods.sort_by(|a, b| a.name.cmp(&b.name));
+        client_streaming_methods.sort_by(|a, b| a.name.cmp(&b.name));
+        notifications.sort_by(|a, b| a.name.cmp(&b.name));
+        Introspection {
+            encoding,
+            methods,
+            streaming_methods,
+            client_streaming_methods,
+            notifications,
+        }
+    }
+
+    ///
+    /// Register a [`Middleware`] invoked around each method and notification
+    /// dispatch (in the order of registration).
+    ///
+    /// ```ignore
+    /// interface.middleware(Arc::new(TokenAuth::new(secret)));
+    /// ```
+    ///
+    pub fn middleware(&mut self, middleware: Arc<dyn Middleware<ConnectionContext, Ops>>) {
+        self.middlewares.push(middleware);
+    }
+
+    async fn before(
+        &self,
+        op: &Ops,
+        connection_ctx: &ConnectionContext,
+        payload: Payload<'_>,
+    ) -> ServerResult<()> {
+        for middleware in self.middlewares.iter() {
+            middleware.before(connection_ctx, op, payload).await?;
+        }
+        Ok(())
+    }
+
+    async fn after(
+        &self,
+        op: &Ops,
+        connection_ctx: &ConnectionContext,
+        result: std::result::Result<(), &ServerError>,
+        started: Instant,
+    ) {
+        let elapsed = started.elapsed();
+        for middleware in self.middlewares.iter() {
+            middleware.after(connection_ctx, op, result, elapsed).await;
+        }
+    }
+
     pub(crate) async fn call_method_with_borsh(
         &self,
         op: &Ops,
         connection_ctx: ConnectionContext,
         payload: &[u8],
     ) -> ServerResult<Vec<u8>> {
-        if let Some(method) = self.methods.get(op) {
-            method
-                .call_with_borsh(self.server_ctx.clone(), connection_ctx, payload)
-                .await
-        } else {
-            Err(ServerError::NotFound)
-        }
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::Borsh(payload))
+            .await
+        {
+            Ok(()) if self.introspection.as_ref() == Some(op) => {
+                let resp = ServerResult::Ok(self.introspect(Encoding::Borsh));
+                Ok((resp.try_to_vec()?, None))
+            }
+            Ok(()) => match self.methods.get(op) {
+                Some(method) => {
+                    method
+                        .call_with_borsh(self.server_ctx.clone(), connection_ctx.clone(), payload)
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        let (result, error) = match result {
+            Ok((data, error)) => (Ok(data), error),
+            Err(err) => (Err(err.clone()), Some(err)),
+        };
+        self.after(
+            op,
+            &connection_ctx,
+            error.as_ref().map_or(Ok(()), Err),
+            started,
+        )
+        .await;
+        result
     }
 
     pub(crate) async fn call_method_with_serde_json(
@@ -128,13 +361,195 @@ where
         connection_ctx: ConnectionContext,
         payload: Value,
     ) -> ServerResult<Value> {
-        if let Some(method) = self.methods.get(op) {
-            method
-                .call_with_serde_json(self.server_ctx.clone(), connection_ctx, payload)
-                .await
-        } else {
-            Err(ServerError::NotFound)
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::SerdeJson(&payload))
+            .await
+        {
+            Ok(()) if self.introspection.as_ref() == Some(op) => {
+                serde_json::to_value(self.introspect(Encoding::SerdeJson))
+                    .map_err(|_| ServerError::RespSerialize)
+            }
+            Ok(()) => match self.methods.get(op) {
+                Some(method) => {
+                    method
+                        .call_with_serde_json(
+                            self.server_ctx.clone(),
+                            connection_ctx.clone(),
+                            payload,
+                        )
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        self.after(op, &connection_ctx, result.as_ref().map(|_| ()), started)
+            .await;
+        result
+    }
+
+    pub(crate) async fn call_streaming_method_with_borsh(
+        self: &Arc<Self>,
+        op: &Ops,
+        connection_ctx: ConnectionContext,
+        payload: &[u8],
+    ) -> ServerResult<ResponseStream<Vec<u8>>> {
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::Borsh(payload))
+            .await
+        {
+            Ok(()) => match self.streaming_methods.get(op) {
+                Some(method) => {
+                    method
+                        .call_with_borsh(self.server_ctx.clone(), connection_ctx.clone(), payload)
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        self.complete_stream(op, connection_ctx, result, started)
+            .await
+    }
+
+    pub(crate) async fn call_streaming_method_with_serde_json(
+        self: &Arc<Self>,
+        op: &Ops,
+        connection_ctx: ConnectionContext,
+        payload: Value,
+    ) -> ServerResult<ResponseStream<Value>> {
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::SerdeJson(&payload))
+            .await
+        {
+            Ok(()) => match self.streaming_methods.get(op) {
+                Some(method) => {
+                    method
+                        .call_with_serde_json(
+                            self.server_ctx.clone(),
+                            connection_ctx.clone(),
+                            payload,
+                        )
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        self.complete_stream(op, connection_ctx, result, started)
+            .await
+    }
+
+    /// Terminate the stream once an error item has been produced, invoking
+    /// the `after` middleware hooks once the stream has completed
+    async fn complete_stream<T>(
+        self: &Arc<Self>,
+        op: &Ops,
+        connection_ctx: ConnectionContext,
+        result: ServerResult<ResponseStream<T>>,
+        started: Instant,
+    ) -> ServerResult<ResponseStream<T>>
+    where
+        T: Send + 'static,
+    {
+        let stream = match result {
+            Ok(stream) => stream,
+            Err(err) => {
+                self.after(op, &connection_ctx, Err(&err), started).await;
+                return Err(err);
+            }
+        };
+
+        let state = (stream, self.clone(), op.clone(), connection_ctx);
+        Ok(stream::unfold(Some(state), move |state| async move {
+            let (mut stream, interface, op, connection_ctx) = state?;
+            match stream.next().await {
+                Some(Ok(item)) => Some((Ok(item), Some((stream, interface, op, connection_ctx)))),
+                Some(Err(err)) => {
+                    interface
+                        .after(&op, &connection_ctx, Err(&err), started)
+                        .await;
+                    Some((Err(err), None))
+                }
+                None => {
+                    interface.after(&op, &connection_ctx, Ok(()), started).await;
+                    None
+                }
+            }
+        })
+        .boxed())
+    }
+
+    /// Invoke the `before` middleware hooks once the client-streaming call
+    /// is opened, returns the time the call has started
+    pub(crate) async fn open_client_streaming_method(
+        &self,
+        op: &Ops,
+        connection_ctx: &ConnectionContext,
+        payload: Payload<'_>,
+    ) -> ServerResult<Instant> {
+        let started = Instant::now();
+        let result = match self.before(op, connection_ctx, payload).await {
+            Ok(()) if self.client_streaming_methods.contains_key(op) => Ok(started),
+            Ok(()) => Err(ServerError::NotFound),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = &result {
+            self.after(op, connection_ctx, Err(err), started).await;
         }
+        result
+    }
+
+    pub(crate) async fn call_client_streaming_method_with_borsh(
+        &self,
+        op: &Ops,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Vec<u8>>,
+        started: Instant,
+    ) -> ServerResult<Vec<u8>> {
+        let result = match self.client_streaming_methods.get(op) {
+            Some(method) => {
+                method
+                    .call_with_borsh(self.server_ctx.clone(), connection_ctx.clone(), items)
+                    .await
+            }
+            None => Err(ServerError::NotFound),
+        };
+        let (result, error) = match result {
+            Ok((data, error)) => (Ok(data), error),
+            Err(err) => (Err(err.clone()), Some(err)),
+        };
+        self.after(
+            op,
+            &connection_ctx,
+            error.as_ref().map_or(Ok(()), Err),
+            started,
+        )
+        .await;
+        result
+    }
+
+    pub(crate) async fn call_client_streaming_method_with_serde_json(
+        &self,
+        op: &Ops,
+        connection_ctx: ConnectionContext,
+        items: RequestStream<Value>,
+        started: Instant,
+    ) -> ServerResult<Value> {
+        let result = match self.client_streaming_methods.get(op) {
+            Some(method) => {
+                method
+                    .call_with_serde_json(self.server_ctx.clone(), connection_ctx.clone(), items)
+                    .await
+            }
+            None => Err(ServerError::NotFound),
+        };
+        self.after(op, &connection_ctx, result.as_ref().map(|_| ()), started)
+            .await;
+        result
     }
 
     pub(crate) async fn call_notification_with_borsh(
@@ -143,13 +558,24 @@ where
         connection_ctx: ConnectionContext,
         payload: &[u8],
     ) -> ServerResult<()> {
-        if let Some(notification) = self.notifications.get(op) {
-            notification
-                .call_with_borsh(self.server_ctx.clone(), connection_ctx, payload)
-                .await
-        } else {
-            Err(ServerError::NotFound)
-        }
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::Borsh(payload))
+            .await
+        {
+            Ok(()) => match self.notifications.get(op) {
+                Some(notification) => {
+                    notification
+                        .call_with_borsh(self.server_ctx.clone(), connection_ctx.clone(), payload)
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        self.after(op, &connection_ctx, result.as_ref().copied(), started)
+            .await;
+        result
     }
 
     pub(crate) async fn call_notification_with_serde_json(
@@ -158,12 +584,27 @@ where
         connection_ctx: ConnectionContext,
         payload: Value,
     ) -> ServerResult<()> {
-        if let Some(notification) = self.notifications.get(op) {
-            notification
-                .call_with_serde_json(self.server_ctx.clone(), connection_ctx, payload)
-                .await
-        } else {
-            Err(ServerError::NotFound)
-        }
+        let started = Instant::now();
+        let result = match self
+            .before(op, &connection_ctx, Payload::SerdeJson(&payload))
+            .await
+        {
+            Ok(()) => match self.notifications.get(op) {
+                Some(notification) => {
+                    notification
+                        .call_with_serde_json(
+                            self.server_ctx.clone(),
+                            connection_ctx.clone(),
+                            payload,
+                        )
+                        .await
+                }
+                None => Err(ServerError::NotFound),
+            },
+            Err(err) => Err(err),
+        };
+        self.after(op, &connection_ctx, result.as_ref().copied(), started)
+            .await;
+        result
     }
 }
@@ -0,0 +1,108 @@
+//! Module containing RPC [`StreamingMethod`] closure wrappers
+use super::method::MethodFnReturn;
+use crate::imports::*;
+use futures::stream::{BoxStream, StreamExt};
+
+/// Stream of the items returned by the [`StreamingMethod`] handler
+pub type ResponseStream<Resp> = BoxStream<'static, ServerResult<Resp>>;
+
+/// Base trait representing an RPC streaming method, used to retain
+/// streaming method structures in an [`Interface`](super::Interface)
+/// map without generics.
+#[async_trait]
+pub(crate) trait StreamingMethodTrait<ServerContext, ConnectionContext>:
+    Send + Sync + 'static
+{
+    async fn call_with_borsh(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        data: &[u8],
+    ) -> ServerResult<ResponseStream<Vec<u8>>>;
+    async fn call_with_serde_json(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        value: Value,
+    ) -> ServerResult<ResponseStream<Value>>;
+}
+
+/// RPC streaming method function type
+pub type StreamingMethodFn<ServerContext, ConnectionContext, Req, Resp> = Arc<
+    Box<
+        dyn Send
+            + Sync
+            + Fn(ServerContext, ConnectionContext, Req) -> MethodFnReturn<ResponseStream<Resp>>
+            + 'static,
+    >,
+>;
+
+///
+/// RPC streaming method wrapper. Contains the method closure function
+/// resolving to a [`ResponseStream`].  Each stream item is delivered
+/// to the client as a separate message correlated to the request;
+/// an error item terminates the stream.
+///
+pub struct StreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    method: StreamingMethodFn<ServerContext, ConnectionContext, Req, Resp>,
+}
+
+impl<ServerContext, ConnectionContext, Req, Resp>
+    StreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    pub fn new<FN>(method_fn: FN) -> StreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+    where
+        FN: Send
+            + Sync
+            + Fn(ServerContext, ConnectionContext, Req) -> MethodFnReturn<ResponseStream<Resp>>
+            + 'static,
+    {
+        StreamingMethod {
+            method: Arc::new(Box::new(method_fn)),
+        }
+    }
+}
+
+#[async_trait]
+impl<ServerContext, ConnectionContext, Req, Resp>
+    StreamingMethodTrait<ServerContext, ConnectionContext>
+    for StreamingMethod<ServerContext, ConnectionContext, Req, Resp>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Req: MsgT,
+    Resp: MsgT,
+{
+    async fn call_with_borsh(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        data: &[u8],
+    ) -> ServerResult<ResponseStream<Vec<u8>>> {
+        let req = Req::try_from_slice(data)?;
+        let stream = (self.method)(server_ctx, connection_ctx, req).await?;
+        Ok(stream.map(|item| Ok(item?.try_to_vec()?)).boxed())
+    }
+
+    async fn call_with_serde_json(
+        &self,
+        server_ctx: ServerContext,
+        connection_ctx: ConnectionContext,
+        value: Value,
+    ) -> ServerResult<ResponseStream<Value>> {
+        let req: Req = serde_json::from_value(value).map_err(|_| ServerError::ReqDeserialize)?;
+        let stream = (self.method)(server_ctx, connection_ctx, req).await?;
+        Ok(stream
+            .map(|item| serde_json::to_value(item?).map_err(|_| ServerError::RespSerialize))
+            .boxed())
+    }
+}
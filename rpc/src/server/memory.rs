@@ -0,0 +1,260 @@
+//!
+//! In-process RPC server connected to the [`RpcClient`](crate::client::RpcClient)
+//! using the WebSocket [`MemoryTransport`], allowing RPC [`Interface`]s to be
+//! tested without opening sockets.
+//!
+
+use super::{Messenger, ProtocolHandler};
+use crate::imports::*;
+use crate::server::Interface;
+use workflow_websocket::client::{
+    Error as ClientError, MemoryHandler, MemorySender, MemoryTransport, Message as ClientMessage,
+    Result as ClientResult,
+};
+use workflow_websocket::server::{Error as WebSocketError, Message, WebSocketSink};
+
+/// Creates the connection context of the in-process connection
+/// (taking the place of the [`RpcHandler::handshake()`](super::RpcHandler::handshake))
+pub type ConnectionFn<ConnectionContext> =
+    Arc<Box<dyn Send + Sync + Fn(Arc<Messenger>) -> ConnectionContext + 'static>>;
+
+///
+/// [`MemoryHandler`] dispatching the messages of the client connected
+/// using the [`MemoryTransport`] to the RPC [`Interface`] using the
+/// `Protocol` handler (see [`RpcMemoryHandler::transport()`]), as the
+/// [`RpcServer`](super::RpcServer) does for the WebSocket connections.
+///
+/// ```ignore
+/// let handler = RpcMemoryHandler::<_, _, BorshProtocol<_, _, MyOps, Id64>, _>::new(
+///     interface,
+///     |messenger| ConnectionContext::new(messenger),
+/// );
+/// let options = ConnectOptions::default().with_memory_transport(handler.transport());
+/// rpc_client.connect(options).await?;
+/// ```
+///
+pub struct RpcMemoryHandler<ServerContext, ConnectionContext, Protocol, Ops>
+where
+    Ops: OpsT,
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Protocol: ProtocolHandler<ServerContext, ConnectionContext, Ops> + Send + Sync + 'static,
+{
+    protocol: Arc<Protocol>,
+    connection_fn: ConnectionFn<ConnectionContext>,
+    /// Context and the sink of the current connection
+    connection: Mutex<Option<(ConnectionContext, WebSocketSink)>>,
+    _server_ctx: PhantomData<ServerContext>,
+    _ops: PhantomData<Ops>,
+}
+
+impl<ServerContext, ConnectionContext, Protocol, Ops>
+    RpcMemoryHandler<ServerContext, ConnectionContext, Protocol, Ops>
+where
+    Ops: OpsT,
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Protocol: ProtocolHandler<ServerContext, ConnectionContext, Ops> + Send + Sync + 'static,
+{
+    /// Create the handler serving the `interface`, `connection_fn` creates
+    /// the context of each connection receiving the connection [`Messenger`]
+    pub fn new<FN>(
+        interface: Arc<Interface<ServerContext, ConnectionContext, Ops>>,
+        connection_fn: FN,
+    ) -> Arc<Self>
+    where
+        FN: Send + Sync + Fn(Arc<Messenger>) -> ConnectionContext + 'static,
+    {
+        Arc::new(RpcMemoryHandler {
+            protocol: Arc::new(Protocol::new(interface)),
+            connection_fn: Arc::new(Box::new(connection_fn)),
+            connection: Mutex::new(None),
+            _server_ctx: PhantomData,
+            _ops: PhantomData,
+        })
+    }
+
+    /// Create the transport connecting the client to the handler
+    pub fn transport(self: &Arc<Self>) -> MemoryTransport {
+        MemoryTransport::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl<ServerContext, ConnectionContext, Protocol, Ops> MemoryHandler
+    for RpcMemoryHandler<ServerContext, ConnectionContext, Protocol, Ops>
+where
+    Ops: OpsT,
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Protocol: ProtocolHandler<ServerContext, ConnectionContext, Ops> + Send + Sync + 'static,
+{
+    async fn connect(&self, sender: &MemorySender) -> ClientResult<()> {
+        let (sink, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Message>();
+
+        // relay the messages posted to the sink to the client
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = receiver.recv().await {
+                let msg = match msg {
+                    Message::Text(text) => ClientMessage::Text(text),
+                    Message::Binary(data) => ClientMessage::Binary(data.into()),
+                    Message::Close(_) => ClientMessage::Close,
+                    Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+                };
+                let close = matches!(msg, ClientMessage::Close);
+                if sender.send(msg).await.is_err() || close {
+                    break;
+                }
+            }
+        });
+
+        let mut messenger = Messenger::new(self.protocol.encoding(), &sink);
+        if let Some(caller) = self.protocol.clone().caller() {
+            messenger = messenger.with_caller(caller);
+        }
+        let connection_ctx = (self.connection_fn)(Arc::new(messenger));
+        *self.connection.lock().unwrap() = Some((connection_ctx, sink));
+        Ok(())
+    }
+
+    async fn message(&self, message: ClientMessage, sender: &MemorySender) -> ClientResult<()> {
+        let msg = match message {
+            ClientMessage::Text(text) => Message::Text(text),
+            ClientMessage::Binary(data) => Message::Binary(data.into()),
+            _ => return Ok(()),
+        };
+
+        let (connection_ctx, sink) = self
+            .connection
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(ClientError::NotConnected)?;
+        match self
+            .protocol
+            .handle_message(connection_ctx, msg, &sink)
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(WebSocketError::ServerClose) => sender.close().await,
+            Err(err) => Err(ClientError::Custom(err.to_string())),
+        }
+    }
+
+    async fn disconnect(&self) {
+        self.connection.lock().unwrap().take();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::{self, RpcClient};
+    use crate::server::{BorshProtocol, JsonProtocol, Method, StreamingMethod};
+    use futures::StreamExt;
+    use workflow_websocket::client::ConnectOptions;
+
+    #[derive(
+        Debug, Clone, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+    )]
+    enum TestOps {
+        Add,
+        Count,
+        Notify,
+        Double,
+    }
+
+    type Messengers = Arc<Mutex<Vec<Arc<Messenger>>>>;
+
+    fn server_interface() -> Arc<Interface<(), Arc<Messenger>, TestOps>> {
+        let mut interface = Interface::new(());
+        interface.method(
+            TestOps::Add,
+            Method::new(|_: (), _: Arc<Messenger>, req: u32| Box::pin(async move { Ok(req + 1) })),
+        );
+        interface.streaming_method(
+            TestOps::Count,
+            StreamingMethod::new(|_: (), _: Arc<Messenger>, to: u32| {
+                Box::pin(async move { Ok(futures::stream::iter(0..to).map(Ok).boxed()) })
+            }),
+        );
+        Arc::new(interface)
+    }
+
+    async fn test_interface<Protocol>(encoding: Encoding)
+    where
+        Protocol: ProtocolHandler<(), Arc<Messenger>, TestOps> + Send + Sync + 'static,
+    {
+        let messengers = Messengers::default();
+        let handler = RpcMemoryHandler::<_, _, Protocol, _>::new(server_interface(), {
+            let messengers = messengers.clone();
+            move |messenger: Arc<Messenger>| {
+                messengers.lock().unwrap().push(messenger.clone());
+                messenger
+            }
+        });
+
+        let (notification_sender, notifications) = workflow_core::channel::unbounded();
+        let mut client_interface = client::Interface::new();
+        client_interface.method(
+            TestOps::Double,
+            client::Method::new(|req: u32| Box::pin(async move { Ok(req * 2) })),
+        );
+        client_interface.notification(
+            TestOps::Notify,
+            client::Notification::new(move |msg: String| {
+                let notification_sender = notification_sender.clone();
+                Box::pin(async move {
+                    notification_sender.try_send(msg).ok();
+                    Ok(())
+                })
+            }),
+        );
+
+        let rpc = RpcClient::<TestOps, Id64>::new_with_encoding(
+            encoding,
+            Some(Arc::new(client_interface)),
+            client::Options::new(),
+            None,
+        )
+        .unwrap();
+        rpc.connect(ConnectOptions::default().with_memory_transport(handler.transport()))
+            .await
+            .unwrap();
+
+        // client to server calls
+        assert_eq!(rpc.call::<u32, u32>(TestOps::Add, 1).await.unwrap(), 2);
+        let items = rpc
+            .call_stream::<u32, u32>(TestOps::Count, 3)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(items, vec![0, 1, 2]);
+
+        // server to client notifications and calls
+        let messenger = messengers.lock().unwrap()[0].clone();
+        messenger
+            .notify(TestOps::Notify, "hello".to_string())
+            .await
+            .unwrap();
+        assert_eq!(notifications.recv().await.unwrap(), "hello");
+        let doubled: u32 = messenger.call(TestOps::Double, 4u32).await.unwrap();
+        assert_eq!(doubled, 8);
+
+        rpc.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_borsh() {
+        test_interface::<BorshProtocol<(), Arc<Messenger>, TestOps, Id64>>(Encoding::Borsh).await;
+    }
+
+    #[tokio::test]
+    async fn test_memory_serde_json() {
+        test_interface::<JsonProtocol<(), Arc<Messenger>, TestOps, Id64>>(Encoding::SerdeJson)
+            .await;
+    }
+}
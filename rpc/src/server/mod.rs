@@ -7,6 +7,7 @@
 
 pub mod error;
 mod interface;
+pub mod memory;
 pub mod prelude;
 pub mod protocol;
 pub mod result;
@@ -14,8 +15,15 @@ pub mod result;
 pub use super::error::*;
 pub use crate::encoding::Encoding;
 use crate::imports::*;
-pub use interface::{Interface, Method, Notification};
+pub use crate::introspection::{Introspection, OpInfo};
+pub use interface::{
+    ClientStreamingMethod, Interface, Method, Middleware, Notification, Payload, RequestStream,
+    ResponseStream, StreamingMethod,
+};
+pub use memory::RpcMemoryHandler;
 pub use protocol::{BorshProtocol, JsonProtocol, ProtocolHandler};
+use protocol::{CallData, Caller};
+use std::any::Any;
 pub use std::net::SocketAddr;
 pub use tokio::sync::mpsc::UnboundedSender as TokioUnboundedSender;
 pub use workflow_websocket::server::{
@@ -67,6 +75,46 @@ use crate::server::result::Result;
 ///
 pub use workflow_rpc_macros::server_method as method;
 
+///
+/// streaming_method!() macro for declaration of RPC streaming method handlers
+///
+/// This macro adds the Box and Pin syntax to the async streaming method
+/// closure (see [`method!()`](macro@method)).  The closure resolves to a
+/// [`ResponseStream`]:
+///
+/// ```ignore
+/// interface.streaming_method(MyOps::List, streaming_method!(
+///   | connection_ctx: ConnectionCtx,
+///     server_ctx: ServerContext,
+///     req: MyReq |
+/// async move {
+///     Ok(futures::stream::iter(vec![Ok(MyItem { }), Ok(MyItem { })]).boxed())
+/// }))
+/// ```
+///
+pub use workflow_rpc_macros::server_streaming_method as streaming_method;
+
+///
+/// client_streaming_method!() macro for declaration of RPC client-streaming
+/// method handlers
+///
+/// This macro adds the Box and Pin syntax to the async client-streaming
+/// method closure (see [`method!()`](macro@method)).  The closure receives
+/// the [`RequestStream`] of the items sent by the client:
+///
+/// ```ignore
+/// interface.client_streaming_method(MyOps::Upload, client_streaming_method!(
+///   | connection_ctx: ConnectionCtx,
+///     server_ctx: ServerContext,
+///     chunks: RequestStream<MyChunk> |
+/// async move {
+///     // ...
+///     Ok(MyUploadResp { })
+/// }))
+/// ```
+///
+pub use workflow_rpc_macros::server_client_streaming_method as client_streaming_method;
+
 ///
 /// notification!() macro for declaration of RPC notification handlers
 ///
@@ -153,18 +201,24 @@ pub trait RpcHandler: Send + Sync + 'static {
     async fn disconnect(self: Arc<Self>, _ctx: Self::Context, _result: WebSocketResult<()>) {}
 }
 
+/// Default timeout of the [`Messenger::call()`]
+pub const DEFAULT_CALL_TIMEOUT_MILLIS: u64 = 60_000;
+
 ///
 /// The [`Messenger`] struct is supplied to the [`RpcHandler::handshake()`] call at
 /// the connection negotiation time. This structure comes in as [`Arc<Messenger>`]
 /// and can be retained for later processing. It provides two methods: [`Messenger::notify`]
 /// that can be used asynchronously to dispatch RPC notifications to the client
 /// and [`Messenger::close`] that can be used to terminate the RPC connection with
-/// the client.
+/// the client.  [`Messenger::call`] can be used to call the methods registered
+/// on the client-side [`Interface`](crate::client::Interface).
 ///
 #[derive(Debug)]
 pub struct Messenger {
     encoding: Encoding,
     sink: WebSocketSink,
+    /// `Arc<dyn Caller<Ops>>` of the protocol handler
+    caller: Option<Arc<dyn Any + Send + Sync>>,
 }
 
 impl Messenger {
@@ -172,9 +226,19 @@ impl Messenger {
         Self {
             encoding,
             sink: sink.clone(),
+            caller: None,
         }
     }
 
+    /// Enable [`Messenger::call()`] using the [`Caller`] of the protocol handler
+    pub fn with_caller<Ops>(mut self, caller: Arc<dyn Caller<Ops>>) -> Self
+    where
+        Ops: OpsT,
+    {
+        self.caller = Some(Arc::new(caller));
+        self
+    }
+
     /// Close the WebSocket connection. The server checks for the connection channel
     /// for the dispatch of this message and relays it to the client as well as
     /// proactively terminates the connection.
@@ -205,6 +269,65 @@ impl Messenger {
         Ok(())
     }
 
+    /// Call the method registered on the client-side [`Interface`](crate::client::Interface)
+    /// and wait for the response (see [`Messenger::call_with_timeout()`]).
+    pub async fn call<Ops, Req, Resp>(&self, op: Ops, req: Req) -> Result<Resp>
+    where
+        Ops: OpsT,
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let timeout = Duration::from_millis(DEFAULT_CALL_TIMEOUT_MILLIS);
+        self.call_with_timeout(op, req, timeout).await
+    }
+
+    ///
+    /// Call the method registered on the client-side [`Interface`](crate::client::Interface)
+    /// and wait for the response.  The error returned by the method is reported as
+    /// [`error::Error::RpcCall`]; the call fails with [`ServerError::Timeout`] if the response
+    /// has not been received within `timeout` or with [`ServerError::Close`] if the
+    /// connection is closed.
+    ///
+    /// Following are the trait requirements on the arguments:
+    /// - `Ops`: [`OpsT`]
+    /// - `Req`: [`MsgT`]
+    /// - `Resp`: [`MsgT`]
+    ///
+    pub async fn call_with_timeout<Ops, Req, Resp>(
+        &self,
+        op: Ops,
+        req: Req,
+        timeout: Duration,
+    ) -> Result<Resp>
+    where
+        Ops: OpsT,
+        Req: MsgT,
+        Resp: MsgT,
+    {
+        let caller = self
+            .caller
+            .as_ref()
+            .and_then(|caller| caller.downcast_ref::<Arc<dyn Caller<Ops>>>())
+            .ok_or(error::Error::CallNotSupported)?;
+
+        match self.encoding {
+            Encoding::Borsh => {
+                let data = CallData::Borsh(req.try_to_vec()?);
+                match caller.call(&self.sink, op, data, timeout).await? {
+                    CallData::Borsh(data) => Ok(ServerResult::<Resp>::try_from_slice(&data)??),
+                    CallData::SerdeJson(_) => Err(ServerError::NonBorshRequest.into()),
+                }
+            }
+            Encoding::SerdeJson => {
+                let data = CallData::SerdeJson(serde_json::to_value(req)?);
+                match caller.call(&self.sink, op, data, timeout).await? {
+                    CallData::SerdeJson(value) => Ok(serde_json::from_value(value)?),
+                    CallData::Borsh(_) => Err(ServerError::NonSerdeRequest.into()),
+                }
+            }
+        }
+    }
+
     /// Serialize message into a [`tungstenite::Message`] for direct websocket delivery.
     /// Once serialized it can be relayed using [`Messenger::send_raw_message()`].
     pub fn serialize_notification_message<Ops, Msg>(
@@ -311,7 +434,11 @@ where
         receiver: &mut WebSocketReceiver,
         sink: &WebSocketSink,
     ) -> WebSocketResult<Self::Context> {
-        let messenger = Arc::new(Messenger::new(self.protocol.encoding(), sink));
+        let mut messenger = Messenger::new(self.protocol.encoding(), sink);
+        if let Some(caller) = self.protocol.clone().caller() {
+            messenger = messenger.with_caller(caller);
+        }
+        let messenger = Arc::new(messenger);
 
         self.rpc_handler
             .clone()
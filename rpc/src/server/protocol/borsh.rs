@@ -4,12 +4,19 @@
 //! protocol.
 //!
 
-use super::Encoding;
+use super::download::{forward, Downloads};
+use super::request::Requests;
+use super::upload::Uploads;
+use super::{CallData, Caller, Encoding};
 use crate::imports::*;
 use crate::messages::borsh::*;
 pub use crate::server::result::Result;
 use crate::server::Interface;
+use crate::server::Payload;
 use crate::server::ProtocolHandler;
+use crate::server::ResponseStream;
+use futures::select_biased;
+use workflow_core::channel::Receiver;
 use workflow_websocket::server::{
     Error as WebSocketError, Message, Result as WebSocketResult, WebSocketSink,
 };
@@ -25,6 +32,123 @@ where
     id: PhantomData<Id>,
     ops: PhantomData<Ops>,
     interface: Arc<Interface<ServerContext, ConnectionContext, Ops>>,
+    uploads: Arc<Uploads<Id, Vec<u8>>>,
+    downloads: Arc<Downloads<Id>>,
+    requests: Requests<Id, Vec<u8>>,
+}
+
+impl<ServerContext, ConnectionContext, Ops, Id>
+    BorshProtocol<ServerContext, ConnectionContext, Ops, Id>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Ops: OpsT,
+    Id: IdT,
+{
+    /// Handle the message of the client-streaming method call
+    async fn handle_client_stream(
+        &self,
+        connection_ctx: ConnectionContext,
+        id: Id,
+        op: Ops,
+        mut payload: &[u8],
+        sink: &WebSocketSink,
+    ) -> WebSocketResult<()> {
+        let kind = StreamRequestKind::deserialize(&mut payload)
+            .map_err(|_| WebSocketError::MalformedMessage)?;
+
+        match kind {
+            StreamRequestKind::Open => {
+                let grant_sink = sink.clone();
+                let grant_header = (id.clone(), op.clone());
+                let grant = move |credit: u32| {
+                    if let Ok(data) = credit.try_to_vec() {
+                        let header = BorshServerMessageHeader::new(
+                            Some(grant_header.0.clone()),
+                            ServerMessageKind::StreamCredit,
+                            Some(grant_header.1.clone()),
+                        );
+                        post(&grant_sink, header, &data);
+                    }
+                };
+                let items = match self.uploads.open(id.clone(), sink, grant) {
+                    Ok(items) => items,
+                    Err(err) => {
+                        post_error::<Ops, Id>(sink, Some(id), &err);
+                        return Ok(());
+                    }
+                };
+
+                let started = match self
+                    .interface
+                    .open_client_streaming_method(&op, &connection_ctx, Payload::Borsh(payload))
+                    .await
+                {
+                    Ok(started) => started,
+                    Err(err) => {
+                        drop(items);
+                        self.uploads.remove(&id, sink);
+                        if err == ServerError::Close {
+                            return Err(WebSocketError::ServerClose);
+                        }
+                        post_error::<Ops, Id>(sink, Some(id), &err);
+                        return Ok(());
+                    }
+                };
+
+                let interface = self.interface.clone();
+                let uploads = self.uploads.clone();
+                let sink = sink.clone();
+                tokio::spawn(async move {
+                    let result = {
+                        let call = interface
+                            .call_client_streaming_method_with_borsh(
+                                &op,
+                                connection_ctx,
+                                items,
+                                started,
+                            )
+                            .fuse();
+                        let closed = sink.closed().fuse();
+                        futures::pin_mut!(call, closed);
+                        select_biased! {
+                            result = call => Some(result),
+                            _ = closed => None,
+                        }
+                    };
+
+                    // the stream of the items has been dropped with the call
+                    uploads.remove(&id, &sink);
+                    match result {
+                        Some(Ok(data)) => {
+                            let header = BorshServerMessageHeader::new(
+                                Some(id),
+                                ServerMessageKind::Success,
+                                Some(op.clone()),
+                            );
+                            post(&sink, header, &data);
+                        }
+                        Some(Err(err)) => {
+                            post_error::<Ops, Id>(&sink, Some(id), &err);
+                        }
+                        None => {}
+                    }
+                });
+            }
+            StreamRequestKind::Item => {
+                if !self.uploads.push(&id, sink, Some(Ok(payload.to_vec()))) {
+                    log_trace!("RPC client stream {:?} is not open", id);
+                }
+            }
+            StreamRequestKind::End => {
+                if !self.uploads.push(&id, sink, None) {
+                    log_trace!("RPC client stream {:?} is not open", id);
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -45,6 +169,9 @@ where
             id: PhantomData,
             ops: PhantomData,
             interface,
+            uploads: Arc::new(Uploads::new()),
+            downloads: Arc::new(Downloads::new()),
+            requests: Requests::new(),
         }
     }
 
@@ -63,7 +190,77 @@ where
             .try_into()
             .map_err(|_| WebSocketError::MalformedMessage)?;
 
-        if req.header.id.is_some() {
+        if req.kind == ClientMessageKind::Response {
+            // response of the client-side method to the request of the server
+            match req
+                .header
+                .id
+                .as_ref()
+                .and_then(|id| self.requests.take(id, sink))
+            {
+                Some(sender) => {
+                    sender.try_send(Ok(req.payload.to_vec())).ok();
+                }
+                None => log_trace!("RPC response to unknown request: {:?}", req.header.id),
+            }
+        } else if req.kind == ClientMessageKind::StreamCredit {
+            let id = req.header.id.ok_or(WebSocketError::MalformedMessage)?;
+            let credit =
+                u32::try_from_slice(req.payload).map_err(|_| WebSocketError::MalformedMessage)?;
+            if !self.downloads.grant(&id, sink, credit) {
+                log_trace!("RPC stream {:?} is not open", id);
+            }
+        } else if req.kind == ClientMessageKind::StreamCancel {
+            let id = req.header.id.ok_or(WebSocketError::MalformedMessage)?;
+            if !self.downloads.cancel(&id, sink) {
+                log_trace!("RPC stream {:?} is not open", id);
+            }
+        } else if let (Some(id), true) = (
+            &req.header.id,
+            self.interface.is_client_streaming_method(&req.header.op),
+        ) {
+            self.handle_client_stream(connection_ctx, id.clone(), req.header.op, req.payload, sink)
+                .await?;
+        } else if let (Some(id), true) = (
+            &req.header.id,
+            self.interface.is_streaming_method(&req.header.op),
+        ) {
+            let credit = match self.downloads.open(id.clone(), sink) {
+                Ok(credit) => credit,
+                Err(err) => {
+                    post_error::<Ops, Id>(sink, Some(id.clone()), &err);
+                    return Ok(());
+                }
+            };
+
+            let result = self
+                .interface
+                .call_streaming_method_with_borsh(&req.header.op, connection_ctx, req.payload)
+                .await;
+
+            match result {
+                Ok(stream) => {
+                    let id = id.clone();
+                    let op = req.header.op;
+                    let downloads = self.downloads.clone();
+                    let sink = sink.clone();
+                    tokio::spawn(async move {
+                        forward_stream::<Ops, Id>(id.clone(), op, stream, credit, &sink).await;
+                        downloads.remove(&id, &sink);
+                    });
+                }
+                Err(err) => {
+                    drop(credit);
+                    self.downloads.remove(id, sink);
+                    log_trace!("RPC server error: {:?} req: {:#?}", err, req);
+                    if err == ServerError::Close {
+                        return Err(WebSocketError::ServerClose);
+                    } else {
+                        post_error::<Ops, Id>(sink, req.header.id, &err);
+                    }
+                }
+            }
+        } else if req.header.id.is_some() {
             let result = self
                 .interface
                 .call_method_with_borsh(&req.header.op, connection_ctx, req.payload)
@@ -90,21 +287,8 @@ where
                     log_trace!("RPC server error: {:?} req: {:#?}", err, req);
                     if err == ServerError::Close {
                         return Err(WebSocketError::ServerClose);
-                    } else if let Ok(err_vec) = err.try_to_vec() {
-                        if let Ok(msg) = BorshServerMessage::new(
-                            BorshServerMessageHeader::<Ops, Id>::new(
-                                req.header.id,
-                                ServerMessageKind::Error,
-                                None,
-                            ),
-                            &err_vec,
-                        )
-                        .try_to_vec()
-                        {
-                            if let Err(e) = sink.send(msg.into()) {
-                                log_trace!("Sink error: {:?}", e);
-                            }
-                        }
+                    } else {
+                        post_error::<Ops, Id>(sink, req.header.id, &err);
                     }
                 }
             }
@@ -126,6 +310,115 @@ where
     {
         create_serialized_notification_message(op, msg)
     }
+
+    fn caller(self: Arc<Self>) -> Option<Arc<dyn Caller<Ops>>> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl<ServerContext, ConnectionContext, Ops, Id> Caller<Ops>
+    for BorshProtocol<ServerContext, ConnectionContext, Ops, Id>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Ops: OpsT,
+    Id: IdT,
+{
+    async fn call(
+        &self,
+        sink: &WebSocketSink,
+        op: Ops,
+        data: CallData,
+        timeout: Duration,
+    ) -> ServerResult<CallData> {
+        let CallData::Borsh(payload) = data else {
+            return Err(ServerError::NonBorshRequest);
+        };
+
+        let data = self
+            .requests
+            .request(sink, timeout, |id| {
+                let header = BorshServerMessageHeader::<Ops, Id>::new(
+                    Some(id),
+                    ServerMessageKind::Request,
+                    Some(op),
+                );
+                post(sink, header, &payload)
+            })
+            .await?;
+        Ok(CallData::Borsh(data))
+    }
+}
+
+/// Post the error response, returns `false` if the connection is closed
+fn post_error<Ops, Id>(sink: &WebSocketSink, id: Option<Id>, err: &ServerError) -> bool
+where
+    Ops: OpsT,
+    Id: IdT,
+{
+    match err.try_to_vec() {
+        Ok(err_vec) => post(
+            sink,
+            BorshServerMessageHeader::<Ops, Id>::new(id, ServerMessageKind::Error, None),
+            &err_vec,
+        ),
+        Err(_) => true,
+    }
+}
+
+/// Post the message, returns `false` if the connection is closed
+fn post<Ops, Id>(
+    sink: &WebSocketSink,
+    header: BorshServerMessageHeader<Ops, Id>,
+    data: &[u8],
+) -> bool
+where
+    Ops: OpsT,
+    Id: IdT,
+{
+    if let Ok(msg) = BorshServerMessage::new(header, data).try_to_vec() {
+        if let Err(e) = sink.send(msg.into()) {
+            log_trace!("Sink error: {:?}", e);
+            return false;
+        }
+    }
+    true
+}
+
+/// Post the items of the streaming method response as [`ServerMessageKind::StreamItem`]
+/// messages terminated by the [`ServerMessageKind::StreamEnd`] (or the error) message,
+/// within the credit granted by the client (until the client cancels the response)
+async fn forward_stream<Ops, Id>(
+    id: Id,
+    op: Ops,
+    stream: ResponseStream<Vec<u8>>,
+    credit: Receiver<u32>,
+    sink: &WebSocketSink,
+) where
+    Ops: OpsT,
+    Id: IdT,
+{
+    let ended = forward(stream, credit, sink, |item| match item {
+        Ok(data) => {
+            let header = BorshServerMessageHeader::new(
+                Some(id.clone()),
+                ServerMessageKind::StreamItem,
+                Some(op.clone()),
+            );
+            post(sink, header, &data)
+        }
+        Err(err) => post_error::<Ops, Id>(sink, Some(id.clone()), &err),
+    })
+    .await;
+
+    if ended {
+        post(
+            sink,
+            BorshServerMessageHeader::new(Some(id), ServerMessageKind::StreamEnd, Some(op)),
+            &[],
+        );
+    }
 }
 
 pub fn create_serialized_notification_message<Ops, Msg>(op: Ops, msg: Msg) -> Result<Message>
@@ -141,3 +434,191 @@ where
     .try_to_vec()?;
     Ok(Message::Binary(data))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::messages::STREAM_RESPONSE_WINDOW;
+    use crate::server::{Method, StreamingMethod};
+    use futures::StreamExt;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    #[derive(
+        Debug, Clone, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+    )]
+    enum TestOps {
+        Add,
+        Count,
+    }
+
+    fn client_message(
+        kind: ClientMessageKind,
+        header: BorshReqHeader<TestOps, Id64>,
+        payload: &[u8],
+    ) -> Message {
+        let msg = to_ws_msg_with_kind(kind, header, payload);
+        Message::Binary(Vec::try_from(msg).unwrap())
+    }
+
+    #[test]
+    fn test_request_header_layout() {
+        // the requests are encoded as the plain `BorshReqHeader` followed by the payload
+        let id = Id64::generate();
+        let header = BorshReqHeader::new(Some(id.clone()), TestOps::Count);
+        let mut expected = header.try_to_vec().unwrap();
+        expected.extend_from_slice(&[1, 2, 3]);
+        let msg = client_message(ClientMessageKind::Request, header, &[1, 2, 3]).into_data();
+        assert_eq!(msg, expected);
+        let req = BorshClientMessage::<TestOps, Id64>::try_from(&msg).unwrap();
+        assert_eq!(req.kind, ClientMessageKind::Request);
+        assert_eq!(req.payload, &[1, 2, 3]);
+
+        // the other kinds are prefixed with the tag and the kind
+        let header = BorshReqHeader::new(Some(id.clone()), TestOps::Count);
+        let msg = client_message(ClientMessageKind::StreamCancel, header, &[]).into_data();
+        assert_eq!(msg[0], CLIENT_MESSAGE_KIND_TAG);
+        let req = BorshClientMessage::<TestOps, Id64>::try_from(&msg).unwrap();
+        assert_eq!(req.kind, ClientMessageKind::StreamCancel);
+        assert_eq!(req.header.id, Some(id));
+        assert!(req.payload.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_collision() {
+        let mut interface = Interface::<(), (), TestOps>::new(());
+        interface.method(
+            TestOps::Add,
+            Method::new(|_: (), _: (), req: u32| Box::pin(async move { Ok(req + 1) })),
+        );
+        let protocol = Arc::new(BorshProtocol::<(), (), TestOps, Id64>::new(Arc::new(
+            interface,
+        )));
+        let (sink, mut receiver) = unbounded_channel();
+
+        let call = {
+            let caller = protocol.clone().caller().unwrap();
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                let data = CallData::Borsh(5u32.try_to_vec().unwrap());
+                caller
+                    .call(&sink, TestOps::Add, data, Duration::from_secs(5))
+                    .await
+            })
+        };
+
+        let msg = receiver.recv().await.unwrap().into_data();
+        let request = BorshServerMessage::<TestOps, Id64>::try_from(msg.as_ref()).unwrap();
+        assert!(matches!(request.header.kind, ServerMessageKind::Request));
+        let id = request.header.id.unwrap();
+
+        // the request of the client using the id of the pending request
+        // is dispatched to the server method
+        let msg = client_message(
+            ClientMessageKind::Request,
+            BorshReqHeader::new(Some(id.clone()), TestOps::Add),
+            &1u32.try_to_vec().unwrap(),
+        );
+        protocol.handle_message((), msg, &sink).await.unwrap();
+        let msg = receiver.recv().await.unwrap().into_data();
+        let response = BorshServerMessage::<TestOps, Id64>::try_from(msg.as_ref()).unwrap();
+        assert!(matches!(response.header.kind, ServerMessageKind::Success));
+        assert_eq!(response.header.id, Some(id.clone()));
+        assert_eq!(
+            ServerResult::<u32>::try_from_slice(response.payload).unwrap(),
+            Ok(2)
+        );
+        assert!(!call.is_finished());
+
+        // the response of the client completes the request of the server
+        let result = ServerResult::<u32>::Ok(6).try_to_vec().unwrap();
+        let header = BorshReqHeader::new(Some(id), TestOps::Add);
+        let msg = client_message(ClientMessageKind::Response, header, &result);
+        protocol.handle_message((), msg, &sink).await.unwrap();
+        let CallData::Borsh(data) = call.await.unwrap().unwrap() else {
+            panic!("unexpected call data");
+        };
+        assert_eq!(ServerResult::<u32>::try_from_slice(&data).unwrap(), Ok(6));
+    }
+
+    /// Sets the flag once the stream of the streaming method is dropped
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the kind and the id of the messages received within 10ms
+    async fn received(
+        receiver: &mut UnboundedReceiver<Message>,
+    ) -> Vec<(ServerMessageKind, Option<Id64>)> {
+        workflow_core::task::sleep(Duration::from_millis(10)).await;
+        let mut received = Vec::new();
+        while let Ok(msg) = receiver.try_recv() {
+            let msg = msg.into_data();
+            let msg = BorshServerMessage::<TestOps, Id64>::try_from(msg.as_ref()).unwrap();
+            received.push((msg.header.kind, msg.header.id));
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_stream_credit_and_cancel() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let mut interface = Interface::<(), (), TestOps>::new(());
+        interface.streaming_method(TestOps::Count, {
+            let dropped = dropped.clone();
+            StreamingMethod::new(move |_: (), _: (), from: u32| {
+                let flag = DropFlag(dropped.clone());
+                Box::pin(async move {
+                    Ok(futures::stream::iter(from..)
+                        .map(move |item| {
+                            let _ = &flag;
+                            Ok(item)
+                        })
+                        .boxed())
+                })
+            })
+        });
+        let protocol = BorshProtocol::<(), (), TestOps, Id64>::new(Arc::new(interface));
+        let (sink, mut receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        let msg = client_message(
+            ClientMessageKind::Request,
+            BorshReqHeader::new(Some(id.clone()), TestOps::Count),
+            &0u32.try_to_vec().unwrap(),
+        );
+        protocol.handle_message((), msg, &sink).await.unwrap();
+
+        // the server sends the items within the initial window
+        let items = received(&mut receiver).await;
+        assert_eq!(items.len() as u32, STREAM_RESPONSE_WINDOW);
+        assert!(items.iter().all(|(kind, item_id)| {
+            matches!(kind, ServerMessageKind::StreamItem) && item_id.as_ref() == Some(&id)
+        }));
+
+        let header = BorshReqHeader::new(Some(id.clone()), TestOps::Count);
+        let msg = client_message(
+            ClientMessageKind::StreamCredit,
+            header,
+            &4u32.try_to_vec().unwrap(),
+        );
+        protocol.handle_message((), msg, &sink).await.unwrap();
+        assert_eq!(received(&mut receiver).await.len(), 4);
+
+        // the cancelled stream is dropped without the end of the stream
+        let header = BorshReqHeader::new(Some(id.clone()), TestOps::Count);
+        protocol
+            .handle_message(
+                (),
+                client_message(ClientMessageKind::StreamCancel, header, &[]),
+                &sink,
+            )
+            .await
+            .unwrap();
+        assert!(received(&mut receiver).await.is_empty());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
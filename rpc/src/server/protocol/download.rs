@@ -0,0 +1,273 @@
+//!
+//! Streaming method responses in progress, relaying the credit granted
+//! by the client to the task forwarding the stream items and cancelling
+//! the response once the client has dropped the stream.  The responses
+//! are registered per connection (the clients choose the call ids).
+//!
+
+use crate::imports::*;
+use crate::messages::STREAM_RESPONSE_WINDOW;
+use crate::server::ResponseStream;
+use futures::{future, select_biased, StreamExt};
+use workflow_core::channel::{unbounded, Receiver, Sender};
+use workflow_websocket::server::WebSocketSink;
+
+struct Download {
+    /// Sink of the connection that has issued the call
+    sink: WebSocketSink,
+    /// Credit granted by the client, the response is
+    /// cancelled once the channel is closed
+    credit: Sender<u32>,
+}
+
+impl Download {
+    /// The response is discarded once the connection is
+    /// closed or the forwarding task has completed
+    fn is_retained(&self) -> bool {
+        !self.sink.is_closed() && !self.credit.is_closed()
+    }
+}
+
+pub(crate) struct Downloads<Id> {
+    downloads: Mutex<AHashMap<Id, Vec<Download>>>,
+}
+
+impl<Id> Downloads<Id>
+where
+    Id: IdT,
+{
+    pub fn new() -> Self {
+        Downloads {
+            downloads: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    /// Register the response of the call issued by the connection, returns
+    /// the channel receiving the credit (closed once the call is cancelled)
+    pub fn open(&self, id: Id, sink: &WebSocketSink) -> ServerResult<Receiver<u32>> {
+        let mut downloads = self.downloads.lock().unwrap();
+        downloads.retain(|_, calls| {
+            calls.retain(Download::is_retained);
+            !calls.is_empty()
+        });
+
+        let calls = downloads.entry(id.clone()).or_default();
+        if calls
+            .iter()
+            .any(|download| download.sink.same_channel(sink))
+        {
+            return Err(ServerError::Text(format!(
+                "RPC stream {id:?} is already open"
+            )));
+        }
+
+        let (sender, receiver) = unbounded();
+        calls.push(Download {
+            sink: sink.clone(),
+            credit: sender,
+        });
+        Ok(receiver)
+    }
+
+    /// Grant the credit to the response of the connection,
+    /// returns `false` if the response does not exist
+    pub fn grant(&self, id: &Id, sink: &WebSocketSink, credit: u32) -> bool {
+        let downloads = self.downloads.lock().unwrap();
+        downloads
+            .get(id)
+            .and_then(|calls| {
+                calls
+                    .iter()
+                    .find(|download| download.sink.same_channel(sink))
+            })
+            .map(|download| download.credit.try_send(credit).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// Cancel the response of the connection (closing the credit
+    /// channel), returns `false` if the response does not exist
+    pub fn cancel(&self, id: &Id, sink: &WebSocketSink) -> bool {
+        let mut downloads = self.downloads.lock().unwrap();
+        let Some(calls) = downloads.get_mut(id) else {
+            return false;
+        };
+        let len = calls.len();
+        calls.retain(|download| !download.sink.same_channel(sink));
+        let cancelled = calls.len() != len;
+        if calls.is_empty() {
+            downloads.remove(id);
+        }
+        cancelled
+    }
+
+    /// Remove the response of the connection once the forwarding task has
+    /// completed (the responses of the calls issued since using the same
+    /// id are retained)
+    pub fn remove(&self, id: &Id, sink: &WebSocketSink) {
+        let mut downloads = self.downloads.lock().unwrap();
+        if let Some(calls) = downloads.get_mut(id) {
+            calls.retain(|download| !download.sink.same_channel(sink) || download.is_retained());
+            if calls.is_empty() {
+                downloads.remove(id);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.downloads.lock().unwrap().values().map(Vec::len).sum()
+    }
+}
+
+///
+/// Relay the items of the streaming method response to `post` (returns `false`
+/// if the connection is closed) within the credit granted by the client.
+/// Returns `true` once the stream has ended, or `false` if the stream has
+/// failed (the error is the last item posted), the response has been
+/// cancelled or the connection has been closed.
+///
+pub(crate) async fn forward<T>(
+    mut stream: ResponseStream<T>,
+    credit: Receiver<u32>,
+    sink: &WebSocketSink,
+    mut post: impl FnMut(ServerResult<T>) -> bool,
+) -> bool {
+    let mut available = STREAM_RESPONSE_WINDOW;
+    loop {
+        let item = if available > 0 {
+            stream.next().left_future()
+        } else {
+            future::pending().right_future()
+        };
+
+        select_biased! {
+            granted = credit.recv().fuse() => match granted {
+                Ok(granted) => available = available.saturating_add(granted),
+                Err(_) => return false,
+            },
+            _ = sink.closed().fuse() => return false,
+            item = item.fuse() => match item {
+                Some(item) => {
+                    let failed = item.is_err();
+                    if !post(item) || failed {
+                        return false;
+                    }
+                    available -= 1;
+                }
+                None => return true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn test_download_credit() {
+        let downloads = Downloads::<Id64>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let (other, _other_receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        let credit = downloads.open(id.clone(), &sink).unwrap();
+        assert!(downloads.open(id.clone(), &sink).is_err());
+        assert!(downloads.grant(&id, &sink, 8));
+        assert!(!downloads.grant(&id, &other, 8));
+        assert_eq!(credit.recv().await, Ok(8));
+        assert!(credit.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_cancel() {
+        let downloads = Downloads::<Id64>::new();
+        let (first, _first_receiver) = unbounded_channel();
+        let (second, _second_receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        // the connections use the same id
+        let first_credit = downloads.open(id.clone(), &first).unwrap();
+        let second_credit = downloads.open(id.clone(), &second).unwrap();
+        assert!(downloads.cancel(&id, &first));
+        assert!(!downloads.cancel(&id, &first));
+        assert!(first_credit.recv().await.is_err());
+        assert!(downloads.grant(&id, &second, 1));
+        assert_eq!(second_credit.recv().await, Ok(1));
+        assert_eq!(downloads.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_remove() {
+        let downloads = Downloads::<Id64>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        let credit = downloads.open(id.clone(), &sink).unwrap();
+        // the response is retained while the forwarding task is running
+        downloads.remove(&id, &sink);
+        assert_eq!(downloads.len(), 1);
+        drop(credit);
+        downloads.remove(&id, &sink);
+        assert_eq!(downloads.len(), 0);
+        assert!(!downloads.grant(&id, &sink, 1));
+    }
+
+    #[tokio::test]
+    async fn test_forward_credit() {
+        let (sink, _receiver) = unbounded_channel();
+        let (grant, credit) = unbounded();
+        let (sender, items) = unbounded();
+        let stream = futures::stream::iter(0..).map(Ok).boxed();
+        let task = tokio::spawn(async move {
+            forward(stream, credit, &sink, |item| sender.try_send(item).is_ok()).await
+        });
+
+        // the server stops once the window is exhausted
+        for expected in 0..STREAM_RESPONSE_WINDOW {
+            assert_eq!(items.recv().await, Ok(Ok(expected)));
+        }
+        workflow_core::task::sleep(Duration::from_millis(10)).await;
+        assert!(items.try_recv().is_err());
+
+        grant.try_send(2).unwrap();
+        for expected in STREAM_RESPONSE_WINDOW..STREAM_RESPONSE_WINDOW + 2 {
+            assert_eq!(items.recv().await, Ok(Ok(expected)));
+        }
+        workflow_core::task::sleep(Duration::from_millis(10)).await;
+        assert!(items.try_recv().is_err());
+
+        // closing the credit channel cancels the response
+        drop(grant);
+        assert!(!task.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_forward_end() {
+        let (sink, _receiver) = unbounded_channel();
+        let (_grant, credit) = unbounded();
+        let mut items = Vec::new();
+        let stream = futures::stream::iter(0..3).map(Ok).boxed();
+        assert!(
+            forward(stream, credit, &sink, |item| {
+                items.push(item);
+                true
+            })
+            .await
+        );
+        assert_eq!(items, vec![Ok(0), Ok(1), Ok(2)]);
+
+        let (_grant, credit) = unbounded();
+        let mut items = Vec::new();
+        let stream = futures::stream::iter(vec![Ok(0), Err(ServerError::NotFound), Ok(1)]).boxed();
+        assert!(
+            !forward(stream, credit, &sink, |item| {
+                items.push(item);
+                true
+            })
+            .await
+        );
+        assert_eq!(items, vec![Ok(0), Err(ServerError::NotFound)]);
+    }
+}
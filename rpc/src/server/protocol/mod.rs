@@ -5,7 +5,10 @@
 //!
 
 pub mod borsh;
+mod download;
+mod request;
 pub mod serde_json;
+mod upload;
 
 use crate::imports::*;
 pub use crate::server::result::Result;
@@ -44,4 +47,32 @@ where
     ) -> Result<tungstenite::Message>
     where
         Msg: BorshSerialize + Serialize + Send + Sync + 'static;
+
+    /// Returns the [`Caller`] issuing the requests of the server to the
+    /// client-side methods (`None` if the protocol does not support them)
+    fn caller(self: Arc<Self>) -> Option<Arc<dyn Caller<Ops>>> {
+        None
+    }
+}
+
+/// Serialized request or response of the call to the client-side method
+#[derive(Debug, Clone)]
+pub enum CallData {
+    /// `Borsh` request or the serialized `ServerResult` response
+    Borsh(Vec<u8>),
+    SerdeJson(Value),
+}
+
+/// Issues the requests of the server to the client-side methods
+/// (see [`Messenger::call()`](crate::server::Messenger::call))
+#[async_trait]
+pub trait Caller<Ops>: Send + Sync + 'static {
+    /// Send the request to the connection and wait for the response
+    async fn call(
+        &self,
+        sink: &WebSocketSink,
+        op: Ops,
+        data: CallData,
+        timeout: Duration,
+    ) -> ServerResult<CallData>;
 }
@@ -0,0 +1,184 @@
+//!
+//! Requests issued by the server to the client-side methods using
+//! [`Messenger::call()`](crate::server::Messenger::call), awaiting
+//! the responses of the clients.
+//!
+
+use crate::imports::*;
+use futures::select_biased;
+use workflow_core::channel::Sender;
+use workflow_websocket::server::WebSocketSink;
+
+struct Request<T> {
+    /// Sink of the connection the request has been sent to
+    sink: WebSocketSink,
+    sender: Sender<ServerResult<T>>,
+}
+
+impl<T> Request<T> {
+    /// The request is discarded once the connection is closed or
+    /// the caller is no longer awaiting the response
+    fn is_retained(&self) -> bool {
+        !self.sink.is_closed() && !self.sender.is_closed()
+    }
+}
+
+pub(crate) struct Requests<Id, T> {
+    requests: Mutex<AHashMap<Id, Request<T>>>,
+}
+
+impl<Id, T> Requests<Id, T>
+where
+    Id: IdT,
+    T: Send + 'static,
+{
+    pub fn new() -> Self {
+        Requests {
+            requests: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    ///
+    /// Register the request for a new id and post it using `post` (returns
+    /// `false` if the connection is closed), then wait for the response.
+    /// Fails with [`ServerError::Timeout`] if the response has not been
+    /// received within `timeout` or with [`ServerError::Close`] if the
+    /// connection is closed.
+    ///
+    pub async fn request(
+        &self,
+        sink: &WebSocketSink,
+        timeout: Duration,
+        post: impl FnOnce(Id) -> bool,
+    ) -> ServerResult<T> {
+        let id = Id::generate();
+        let (sender, receiver) = oneshot();
+        {
+            let mut requests = self.requests.lock().unwrap();
+            requests.retain(|_, request| request.is_retained());
+            requests.insert(
+                id.clone(),
+                Request {
+                    sink: sink.clone(),
+                    sender,
+                },
+            );
+        }
+
+        let result = if post(id.clone()) {
+            select_biased! {
+                result = receiver.recv().fuse() => result.unwrap_or(Err(ServerError::Close)),
+                _ = sink.closed().fuse() => Err(ServerError::Close),
+                _ = workflow_core::task::sleep(timeout).fuse() => Err(ServerError::Timeout),
+            }
+        } else {
+            Err(ServerError::Close)
+        };
+
+        self.requests.lock().unwrap().remove(&id);
+        result
+    }
+
+    ///
+    /// Remove the request sent to the connection, returns the channel awaiting
+    /// the response or `None` if `id` does not belong to a pending request of
+    /// the connection (e.g. the request has timed out).
+    ///
+    pub fn take(&self, id: &Id, sink: &WebSocketSink) -> Option<Sender<ServerResult<T>>> {
+        let mut requests = self.requests.lock().unwrap();
+        match requests.get(id) {
+            Some(request) if request.sink.same_channel(sink) => {
+                requests.remove(id).map(|request| request.sender)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn test_request_response() {
+        let requests = Arc::new(Requests::<Id64, u32>::new());
+        let (sink, _receiver) = unbounded_channel();
+        let (other, _other_receiver) = unbounded_channel();
+        let (id_sender, id_receiver) = oneshot();
+
+        let task = {
+            let requests = requests.clone();
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                requests
+                    .request(&sink, Duration::from_secs(5), |id| {
+                        id_sender.try_send(id).is_ok()
+                    })
+                    .await
+            })
+        };
+
+        let id = id_receiver.recv().await.unwrap();
+        // the response of another connection is not delivered
+        assert!(requests.take(&id, &other).is_none());
+        let sender = requests.take(&id, &sink).expect("pending request");
+        sender.try_send(Ok(7)).unwrap();
+        assert_eq!(task.await.unwrap(), Ok(7));
+        assert!(requests.take(&id, &sink).is_none());
+        assert_eq!(requests.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let requests = Requests::<Id64, u32>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let mut request_id = None;
+
+        let result = requests
+            .request(&sink, Duration::from_millis(10), |id| {
+                request_id = Some(id);
+                true
+            })
+            .await;
+        assert_eq!(result, Err(ServerError::Timeout));
+        // the late response is discarded
+        assert!(requests.take(&request_id.unwrap(), &sink).is_none());
+        assert_eq!(requests.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_request_close() {
+        let requests = Requests::<Id64, u32>::new();
+        let (sink, receiver) = unbounded_channel();
+        drop(receiver);
+
+        let result = requests.request(&sink, Duration::MAX, |_| false).await;
+        assert_eq!(result, Err(ServerError::Close));
+        assert_eq!(requests.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_request_cancel() {
+        let requests = Requests::<Id64, u32>::new();
+        let (sink, _receiver) = unbounded_channel();
+
+        // the caller drops the request before the response is received
+        let mut request = Box::pin(requests.request(&sink, Duration::MAX, |_| true));
+        assert!(futures::poll!(request.as_mut()).is_pending());
+        drop(request);
+        assert_eq!(requests.len(), 1);
+
+        // the abandoned request is discarded by the next request
+        let result = requests
+            .request(&sink, Duration::from_millis(10), |_| true)
+            .await;
+        assert_eq!(result, Err(ServerError::Timeout));
+        assert_eq!(requests.len(), 0);
+    }
+}
@@ -3,12 +3,19 @@
 //! dispatch of RPC methods and notifications when using `JSON`
 //! protocol.
 //!
-use super::Encoding;
+use super::download::{forward, Downloads};
+use super::request::Requests;
+use super::upload::Uploads;
+use super::{CallData, Caller, Encoding};
 use crate::imports::*;
 use crate::messages::serde_json::*;
 pub use crate::server::result::Result;
 use crate::server::Interface;
+use crate::server::Payload;
 use crate::server::ProtocolHandler;
+use crate::server::ResponseStream;
+use futures::select_biased;
+use workflow_core::channel::Receiver;
 use workflow_websocket::server::{
     Error as WebSocketError, Message, Result as WebSocketResult, WebSocketSink,
 };
@@ -24,6 +31,132 @@ where
     id: PhantomData<Id>,
     ops: PhantomData<Ops>,
     interface: Arc<Interface<ServerContext, ConnectionContext, Ops>>,
+    uploads: Arc<Uploads<Id, Value>>,
+    downloads: Arc<Downloads<Id>>,
+    requests: Requests<Id, Value>,
+}
+
+impl<ServerContext, ConnectionContext, Ops, Id>
+    JsonProtocol<ServerContext, ConnectionContext, Ops, Id>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Ops: OpsT,
+    Id: IdT,
+{
+    /// Handle the message of the client-streaming method call
+    async fn handle_client_stream(
+        &self,
+        connection_ctx: ConnectionContext,
+        id: Id,
+        op: Ops,
+        params: Value,
+        sink: &WebSocketSink,
+    ) -> WebSocketResult<()> {
+        let request: JsonStreamRequest =
+            serde_json::from_value(params).map_err(|_| WebSocketError::MalformedMessage)?;
+
+        match request {
+            JsonStreamRequest::Open => {
+                let grant_sink = sink.clone();
+                let grant_header = (id.clone(), op.clone());
+                let grant = move |credit: u32| {
+                    let msg = JSONServerMessage::new(
+                        Some(grant_header.0.clone()),
+                        Some(grant_header.1.clone()),
+                        Some(Value::from(credit)),
+                        None,
+                    )
+                    .with_stream(StreamFrame::Credit);
+                    post(&grant_sink, msg);
+                };
+                let items = match self.uploads.open(id.clone(), sink, grant) {
+                    Ok(items) => items,
+                    Err(err) => {
+                        post(
+                            sink,
+                            JSONServerMessage::new(Some(id), Some(op), None, Some(err.into())),
+                        );
+                        return Ok(());
+                    }
+                };
+
+                let started = match self
+                    .interface
+                    .open_client_streaming_method(
+                        &op,
+                        &connection_ctx,
+                        Payload::SerdeJson(&Value::Null),
+                    )
+                    .await
+                {
+                    Ok(started) => started,
+                    Err(err) => {
+                        drop(items);
+                        self.uploads.remove(&id, sink);
+                        if err == ServerError::Close {
+                            return Err(WebSocketError::ServerClose);
+                        }
+                        post(
+                            sink,
+                            JSONServerMessage::new(Some(id), Some(op), None, Some(err.into())),
+                        );
+                        return Ok(());
+                    }
+                };
+
+                let interface = self.interface.clone();
+                let uploads = self.uploads.clone();
+                let sink = sink.clone();
+                tokio::spawn(async move {
+                    let result = {
+                        let call = interface
+                            .call_client_streaming_method_with_serde_json(
+                                &op,
+                                connection_ctx,
+                                items,
+                                started,
+                            )
+                            .fuse();
+                        let closed = sink.closed().fuse();
+                        futures::pin_mut!(call, closed);
+                        select_biased! {
+                            result = call => Some(result),
+                            _ = closed => None,
+                        }
+                    };
+
+                    // the stream of the items has been dropped with the call
+                    uploads.remove(&id, &sink);
+                    let msg = match result {
+                        Some(Ok(payload)) => {
+                            JSONServerMessage::new(Some(id), Some(op.clone()), Some(payload), None)
+                        }
+                        Some(Err(err)) => JSONServerMessage::new(
+                            Some(id),
+                            Some(op.clone()),
+                            None,
+                            Some(err.into()),
+                        ),
+                        None => return,
+                    };
+                    post(&sink, msg);
+                });
+            }
+            JsonStreamRequest::Item(item) => {
+                if !self.uploads.push(&id, sink, Some(Ok(item))) {
+                    log_trace!("RPC client stream {:?} is not open", id);
+                }
+            }
+            JsonStreamRequest::End => {
+                if !self.uploads.push(&id, sink, None) {
+                    log_trace!("RPC client stream {:?} is not open", id);
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -44,6 +177,9 @@ where
             id: PhantomData,
             ops: PhantomData,
             interface,
+            uploads: Arc::new(Uploads::new()),
+            downloads: Arc::new(Downloads::new()),
+            requests: Requests::new(),
         }
     }
 
@@ -62,7 +198,89 @@ where
         let req: JsonClientMessage<Ops, Id> =
             serde_json::from_str(text).map_err(|_| WebSocketError::MalformedMessage)?;
 
-        if req.id.is_some() {
+        if req.response {
+            // response of the client-side method to the request of the server
+            match req.id.as_ref().and_then(|id| self.requests.take(id, sink)) {
+                Some(sender) => {
+                    let result = match req.error {
+                        Some(err) => Err(err.into()),
+                        None => Ok(req.params),
+                    };
+                    sender.try_send(result).ok();
+                }
+                None => log_trace!("RPC response to unknown request: {:?}", req.id),
+            }
+        } else if let Some(frame) = req.stream {
+            // control frame of the streaming method response
+            let id = req.id.ok_or(WebSocketError::MalformedMessage)?;
+            let open = match frame {
+                StreamFrame::Credit => {
+                    let credit: u32 = serde_json::from_value(req.params)
+                        .map_err(|_| WebSocketError::MalformedMessage)?;
+                    self.downloads.grant(&id, sink, credit)
+                }
+                StreamFrame::Cancel => self.downloads.cancel(&id, sink),
+                StreamFrame::Item | StreamFrame::End => {
+                    return Err(WebSocketError::MalformedMessage);
+                }
+            };
+            if !open {
+                log_trace!("RPC stream {:?} is not open", id);
+            }
+        } else if let (Some(id), true) = (
+            &req.id,
+            self.interface.is_client_streaming_method(&req.method),
+        ) {
+            self.handle_client_stream(connection_ctx, id.clone(), req.method, req.params, sink)
+                .await?;
+        } else if let (Some(id), true) = (&req.id, self.interface.is_streaming_method(&req.method))
+        {
+            let credit = match self.downloads.open(id.clone(), sink) {
+                Ok(credit) => credit,
+                Err(err) => {
+                    post(
+                        sink,
+                        JSONServerMessage::new(req.id, Some(req.method), None, Some(err.into())),
+                    );
+                    return Ok(());
+                }
+            };
+
+            let result = self
+                .interface
+                .call_streaming_method_with_serde_json(&req.method, connection_ctx, req.params)
+                .await;
+
+            match result {
+                Ok(stream) => {
+                    let id = id.clone();
+                    let op = req.method;
+                    let downloads = self.downloads.clone();
+                    let sink = sink.clone();
+                    tokio::spawn(async move {
+                        forward_stream::<Ops, Id>(id.clone(), op, stream, credit, &sink).await;
+                        downloads.remove(&id, &sink);
+                    });
+                }
+                Err(err) => {
+                    drop(credit);
+                    self.downloads.remove(id, sink);
+                    if err == ServerError::Close {
+                        return Err(WebSocketError::ServerClose);
+                    } else {
+                        post(
+                            sink,
+                            JSONServerMessage::new(
+                                req.id,
+                                Some(req.method),
+                                None,
+                                Some(err.into()),
+                            ),
+                        );
+                    }
+                }
+            }
+        } else if req.id.is_some() {
             let result = self
                 .interface
                 .call_method_with_serde_json(&req.method, connection_ctx, req.params)
@@ -116,6 +334,89 @@ where
     {
         create_serialized_notification_message(op, msg)
     }
+
+    fn caller(self: Arc<Self>) -> Option<Arc<dyn Caller<Ops>>> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl<ServerContext, ConnectionContext, Ops, Id> Caller<Ops>
+    for JsonProtocol<ServerContext, ConnectionContext, Ops, Id>
+where
+    ServerContext: Clone + Send + Sync + 'static,
+    ConnectionContext: Clone + Send + Sync + 'static,
+    Ops: OpsT,
+    Id: IdT,
+{
+    async fn call(
+        &self,
+        sink: &WebSocketSink,
+        op: Ops,
+        data: CallData,
+        timeout: Duration,
+    ) -> ServerResult<CallData> {
+        let CallData::SerdeJson(params) = data else {
+            return Err(ServerError::NonSerdeRequest);
+        };
+
+        let data = self
+            .requests
+            .request(sink, timeout, |id| {
+                let msg = JSONServerMessage::<Ops, Id>::new(Some(id), Some(op), Some(params), None)
+                    .with_request();
+                post(sink, msg)
+            })
+            .await?;
+        Ok(CallData::SerdeJson(data))
+    }
+}
+
+/// Post the message, returns `false` if the connection is closed
+fn post<Ops, Id>(sink: &WebSocketSink, msg: JSONServerMessage<Ops, Id>) -> bool
+where
+    Ops: OpsT,
+    Id: IdT,
+{
+    if let Ok(msg) = serde_json::to_string(&msg) {
+        if let Err(e) = sink.send(msg.into()) {
+            log_trace!("Sink error: {:?}", e);
+            return false;
+        }
+    }
+    true
+}
+
+/// Post the items of the streaming method response as [`StreamFrame::Item`]
+/// messages terminated by the [`StreamFrame::End`] (or the error) message,
+/// within the credit granted by the client (until the client cancels the response)
+async fn forward_stream<Ops, Id>(
+    id: Id,
+    op: Ops,
+    stream: ResponseStream<Value>,
+    credit: Receiver<u32>,
+    sink: &WebSocketSink,
+) where
+    Ops: OpsT,
+    Id: IdT,
+{
+    let ended = forward(stream, credit, sink, |item| {
+        let (payload, error) = match item {
+            Ok(payload) => (Some(payload), None),
+            Err(err) => (None, Some(JsonServerError::from(err))),
+        };
+        let msg = JSONServerMessage::new(Some(id.clone()), Some(op.clone()), payload, error)
+            .with_stream(StreamFrame::Item);
+        post(sink, msg)
+    })
+    .await;
+
+    if ended {
+        post(
+            sink,
+            JSONServerMessage::new(Some(id), Some(op), None, None).with_stream(StreamFrame::End),
+        );
+    }
 }
 
 pub fn create_serialized_notification_message<Ops, Msg>(op: Ops, msg: Msg) -> Result<Message>
@@ -132,3 +433,76 @@ where
     ))?;
     Ok(Message::Text(json))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::Method;
+    use serde_json::json;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[derive(
+        Debug, Clone, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+    )]
+    enum TestOps {
+        Add,
+    }
+
+    fn client_message(msg: JsonClientMessage<TestOps, Id64>) -> Message {
+        Message::Text(serde_json::to_string(&msg).unwrap())
+    }
+
+    fn server_message(msg: Message) -> JSONServerMessage<TestOps, Id64> {
+        serde_json::from_str(msg.to_text().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_collision() {
+        let mut interface = Interface::<(), (), TestOps>::new(());
+        interface.method(
+            TestOps::Add,
+            Method::new(|_: (), _: (), req: u32| Box::pin(async move { Ok(req + 1) })),
+        );
+        let protocol = Arc::new(JsonProtocol::<(), (), TestOps, Id64>::new(Arc::new(
+            interface,
+        )));
+        let (sink, mut receiver) = unbounded_channel();
+
+        let call = {
+            let caller = protocol.clone().caller().unwrap();
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                let data = CallData::SerdeJson(json!(5));
+                caller
+                    .call(&sink, TestOps::Add, data, Duration::from_secs(5))
+                    .await
+            })
+        };
+
+        let request = server_message(receiver.recv().await.unwrap());
+        assert!(request.request);
+        let id = request.id.unwrap();
+
+        // the request of the client using the id of the pending request
+        // is dispatched to the server method
+        let msg = client_message(JsonClientMessage::new(
+            Some(id.clone()),
+            TestOps::Add,
+            json!(1),
+        ));
+        protocol.handle_message((), msg, &sink).await.unwrap();
+        let response = server_message(receiver.recv().await.unwrap());
+        assert!(!response.request);
+        assert_eq!(response.id, Some(id.clone()));
+        assert_eq!(response.params, Some(json!(2)));
+        assert!(!call.is_finished());
+
+        // the response of the client completes the request of the server
+        let msg = client_message(JsonClientMessage::response(id, TestOps::Add, Ok(json!(6))));
+        protocol.handle_message((), msg, &sink).await.unwrap();
+        let CallData::SerdeJson(data) = call.await.unwrap().unwrap() else {
+            panic!("unexpected call data");
+        };
+        assert_eq!(data, json!(6));
+    }
+}
@@ -0,0 +1,266 @@
+//!
+//! Client-streaming calls in progress, relaying the items sent by
+//! the client to the [`RequestStream`] and granting the credit as the
+//! items are consumed.  The calls are registered per connection: the
+//! clients choose the call ids, so the same id can be used by different
+//! connections at the same time.
+//!
+
+use crate::imports::*;
+use crate::messages::STREAM_REQUEST_WINDOW;
+use crate::server::RequestStream;
+use futures::StreamExt;
+use std::sync::atomic::AtomicU32;
+use workflow_core::channel::{unbounded, Sender};
+use workflow_websocket::server::WebSocketSink;
+
+struct Upload<T> {
+    /// Sink of the connection that has opened the call
+    sink: WebSocketSink,
+    sender: Sender<ServerResult<T>>,
+    /// Number of the items the client can send
+    credit: Arc<AtomicU32>,
+}
+
+impl<T> Upload<T> {
+    /// The call is discarded once the connection is closed or
+    /// the handler has dropped the stream of the items
+    fn is_retained(&self) -> bool {
+        !self.sink.is_closed() && !self.sender.is_closed()
+    }
+}
+
+pub(crate) struct Uploads<Id, T> {
+    uploads: Mutex<AHashMap<Id, Vec<Upload<T>>>>,
+}
+
+impl<Id, T> Uploads<Id, T>
+where
+    Id: IdT,
+    T: Send + 'static,
+{
+    pub fn new() -> Self {
+        Uploads {
+            uploads: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    /// Register the call opened by the connection, returns the stream of the
+    /// items invoking `grant` with the credit once the items are consumed
+    pub fn open(
+        &self,
+        id: Id,
+        sink: &WebSocketSink,
+        grant: impl Fn(u32) + Send + 'static,
+    ) -> ServerResult<RequestStream<T>> {
+        let mut uploads = self.uploads.lock().unwrap();
+        uploads.retain(|_, calls| {
+            calls.retain(Upload::is_retained);
+            !calls.is_empty()
+        });
+
+        let calls = uploads.entry(id.clone()).or_default();
+        if calls.iter().any(|upload| upload.sink.same_channel(sink)) {
+            return Err(ServerError::Text(format!(
+                "RPC client stream {id:?} is already open"
+            )));
+        }
+
+        let (sender, receiver) = unbounded();
+        let credit = Arc::new(AtomicU32::new(STREAM_REQUEST_WINDOW));
+        calls.push(Upload {
+            sink: sink.clone(),
+            sender,
+            credit: credit.clone(),
+        });
+
+        let threshold = (STREAM_REQUEST_WINDOW / 2).max(1);
+        let mut consumed = 0;
+        Ok(receiver
+            .inspect(move |_| {
+                consumed += 1;
+                if consumed == threshold {
+                    credit.fetch_add(consumed, Ordering::SeqCst);
+                    grant(consumed);
+                    consumed = 0;
+                }
+            })
+            .boxed())
+    }
+
+    /// Deliver the item to the call opened by the connection (`None` ends
+    /// the stream), returns `false` if the call does not exist.  The stream
+    /// fails if the client has sent more items than the credit allows.
+    pub fn push(&self, id: &Id, sink: &WebSocketSink, item: Option<ServerResult<T>>) -> bool {
+        let mut uploads = self.uploads.lock().unwrap();
+        let Some(calls) = uploads.get_mut(id) else {
+            return false;
+        };
+        let Some(index) = calls
+            .iter()
+            .position(|upload| upload.sink.same_channel(sink) && upload.is_retained())
+        else {
+            return false;
+        };
+
+        let close = match item {
+            Some(item) => {
+                let upload = &calls[index];
+                let credit =
+                    upload
+                        .credit
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |credit| {
+                            credit.checked_sub(1)
+                        });
+                if credit.is_ok() {
+                    // the receiver is closed if the handler has dropped the stream
+                    upload.sender.try_send(item).ok();
+                    false
+                } else {
+                    let err = ServerError::Text("RPC client stream credit exceeded".to_string());
+                    upload.sender.try_send(Err(err)).ok();
+                    true
+                }
+            }
+            None => true,
+        };
+
+        if close {
+            // dropping the sender terminates the stream
+            calls.swap_remove(index);
+            if calls.is_empty() {
+                uploads.remove(id);
+            }
+        }
+        true
+    }
+
+    /// Remove the call of the connection once the handler has completed
+    /// (the calls opened since using the same id are retained)
+    pub fn remove(&self, id: &Id, sink: &WebSocketSink) {
+        let mut uploads = self.uploads.lock().unwrap();
+        if let Some(calls) = uploads.get_mut(id) {
+            calls.retain(|upload| !upload.sink.same_channel(sink) || upload.is_retained());
+            if calls.is_empty() {
+                uploads.remove(id);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.uploads.lock().unwrap().values().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn items(count: u32) -> impl Iterator<Item = ServerResult<u32>> {
+        (0..count).map(Ok)
+    }
+
+    #[tokio::test]
+    async fn test_upload_credit_window() {
+        let uploads = Uploads::<Id64, u32>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let id = Id64::generate();
+        let granted = Arc::new(AtomicU32::new(0));
+
+        let grant = {
+            let granted = granted.clone();
+            move |credit| {
+                granted.fetch_add(credit, Ordering::SeqCst);
+            }
+        };
+        let mut stream = uploads.open(id.clone(), &sink, grant).unwrap();
+
+        for item in items(STREAM_REQUEST_WINDOW) {
+            assert!(uploads.push(&id, &sink, Some(item)));
+        }
+        // consuming half of the window grants the credit for the next items
+        let threshold = STREAM_REQUEST_WINDOW / 2;
+        for expected in items(threshold) {
+            assert_eq!(stream.next().await, Some(expected));
+        }
+        assert_eq!(granted.load(Ordering::SeqCst), threshold);
+
+        for item in items(threshold) {
+            assert!(uploads.push(&id, &sink, Some(item)));
+        }
+        assert!(uploads.push(&id, &sink, None));
+        let received = stream.collect::<Vec<_>>().await;
+        assert_eq!(received.len() as u32, STREAM_REQUEST_WINDOW);
+        assert!(received.iter().all(Result::is_ok));
+        assert_eq!(uploads.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_upload_credit_exhausted() {
+        let uploads = Uploads::<Id64, u32>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let id = Id64::generate();
+        let stream = uploads.open(id.clone(), &sink, |_| {}).unwrap();
+
+        // the client sends the items without waiting for the credit
+        for item in items(STREAM_REQUEST_WINDOW + 1) {
+            assert!(uploads.push(&id, &sink, Some(item)));
+        }
+        assert!(!uploads.push(&id, &sink, Some(Ok(0))));
+
+        let received = stream.collect::<Vec<_>>().await;
+        assert_eq!(received.len() as u32, STREAM_REQUEST_WINDOW + 1);
+        assert!(received[..STREAM_REQUEST_WINDOW as usize]
+            .iter()
+            .all(Result::is_ok));
+        assert!(matches!(received.last(), Some(Err(ServerError::Text(_)))));
+        assert_eq!(uploads.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_upload_per_connection() {
+        let uploads = Uploads::<Id64, u32>::new();
+        let (first, _first_receiver) = unbounded_channel();
+        let (second, _second_receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        // the connections use the same id
+        let first_stream = uploads.open(id.clone(), &first, |_| {}).unwrap();
+        let second_stream = uploads.open(id.clone(), &second, |_| {}).unwrap();
+        assert!(uploads.open(id.clone(), &first, |_| {}).is_err());
+
+        assert!(uploads.push(&id, &first, Some(Ok(1))));
+        assert!(uploads.push(&id, &second, Some(Ok(2))));
+        assert!(uploads.push(&id, &first, None));
+        assert_eq!(first_stream.collect::<Vec<_>>().await, vec![Ok(1)]);
+
+        // the completion of the first call does not remove the second one
+        uploads.remove(&id, &first);
+        uploads.remove(&id, &second);
+        assert!(uploads.push(&id, &second, Some(Ok(3))));
+        assert!(uploads.push(&id, &second, None));
+        assert_eq!(second_stream.collect::<Vec<_>>().await, vec![Ok(2), Ok(3)]);
+        assert_eq!(uploads.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_upload_remove() {
+        let uploads = Uploads::<Id64, u32>::new();
+        let (sink, _receiver) = unbounded_channel();
+        let id = Id64::generate();
+
+        // the handler completes without consuming the stream
+        let stream = uploads.open(id.clone(), &sink, |_| {}).unwrap();
+        drop(stream);
+        uploads.remove(&id, &sink);
+        assert_eq!(uploads.len(), 0);
+        assert!(!uploads.push(&id, &sink, Some(Ok(1))));
+
+        // the id can be reused once the call has completed
+        let stream = uploads.open(id.clone(), &sink, |_| {}).unwrap();
+        assert!(uploads.push(&id, &sink, None));
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![]);
+    }
+}
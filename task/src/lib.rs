@@ -10,6 +10,11 @@ use workflow_core::channel::{
 };
 pub use workflow_task_macros::{set_task, task};
 
+pub mod supervisor;
+pub use supervisor::{
+    Restart, RestartPolicy, SupervisedResult, SupervisedReturn, Supervisor, TaskMetrics, TaskStatus,
+};
+
 /// Errors produced by the [`Task`] implementation
 #[derive(Debug, Error)]
 pub enum TaskError {
@@ -25,6 +30,10 @@ pub enum TaskError {
     TrySendError(String),
     #[error("Task channel try receive {0:?}")]
     TryRecvError(#[from] TryRecvError),
+    #[error("Task `{0}` already exists")]
+    TaskExists(String),
+    #[error("Task `{0}` not found")]
+    TaskNotFound(String),
 }
 
 impl<T> From<SendError<T>> for TaskError {
@@ -0,0 +1,509 @@
+//!
+//! Supervised task groups.
+//!
+//! [`Supervisor`] manages a set of named tasks, restarting each task when
+//! it exits according to its [`RestartPolicy`] and tracking its status and
+//! [`TaskMetrics`].  A supervised task is an async closure receiving a
+//! termination signal (similar to [`Task`](crate::Task)) and returning a
+//! [`SupervisedResult`]; returning an error or panicking is considered a
+//! failure.
+//!
+//! ```ignore
+//! let supervisor = Supervisor::new();
+//! supervisor.insert(
+//!     "listener",
+//!     RestartPolicy::on_error()
+//!         .with_max_restarts(5)
+//!         .with_delay(Duration::from_secs(1)),
+//!     |stop: Receiver<()>| -> SupervisedReturn {
+//!         Box::pin(async move {
+//!             let listener = TcpListener::bind("0.0.0.0:8080").await?;
+//!             // ... accept connections until `stop` is signaled ...
+//!             Ok(())
+//!         })
+//!     },
+//! )?;
+//!
+//! supervisor.start_all()?;
+//! // ...
+//! println!("{:?}", supervisor.metrics("listener"));
+//! supervisor.stop_all().await;
+//! ```
+//!
+
+use crate::{TaskError, TaskResult};
+use futures::{select, Future, FutureExt};
+use std::collections::BTreeMap;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use workflow_core::channel::{oneshot, Receiver, Sender};
+use workflow_core::task::{sleep, spawn};
+use workflow_core::time::{backoff_delay, unixtime_as_millis_u64};
+
+/// Error returned by a supervised task
+pub type SupervisedError = Box<dyn std::error::Error + Send + Sync>;
+/// Result returned by a supervised task
+pub type SupervisedResult = std::result::Result<(), SupervisedError>;
+pub type SupervisedReturn = Pin<Box<dyn Send + 'static + Future<Output = SupervisedResult>>>;
+pub type SupervisedFn = Arc<Box<dyn Send + Sync + Fn(Receiver<()>) -> SupervisedReturn + 'static>>;
+
+/// Condition under which a task is restarted after exiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    /// Never restart the task
+    Never,
+    /// Restart the task whenever it exits
+    Always,
+    /// Restart the task if it returns an error or panics
+    OnError,
+}
+
+/// Task restart policy.  The delay before a restart is multiplied
+/// by the backoff factor after each restart, up to the maximum delay.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub restart: Restart,
+    /// Maximum number of restarts (`None` for unlimited restarts)
+    pub max_restarts: Option<u32>,
+    pub delay: Duration,
+    pub backoff: f64,
+    pub max_delay: Duration,
+}
+
+impl RestartPolicy {
+    pub fn new(restart: Restart) -> Self {
+        RestartPolicy {
+            restart,
+            max_restarts: None,
+            delay: Duration::from_millis(250),
+            backoff: 2.0,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    pub fn never() -> Self {
+        RestartPolicy::new(Restart::Never)
+    }
+
+    pub fn always() -> Self {
+        RestartPolicy::new(Restart::Always)
+    }
+
+    pub fn on_error() -> Self {
+        RestartPolicy::new(Restart::OnError)
+    }
+
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Delay multiplier applied after each restart (`1.0` for a constant delay)
+    pub fn with_backoff(mut self, backoff: f64) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Delay before the restart `restart` (starting from `1`)
+    pub fn delay_for(&self, restart: u32) -> Duration {
+        backoff_delay(self.delay, self.backoff, self.max_delay, restart)
+    }
+
+    fn should_restart(&self, failed: bool, restarts: u32) -> bool {
+        let restart = match self.restart {
+            Restart::Never => false,
+            Restart::Always => true,
+            Restart::OnError => failed,
+        };
+        restart
+            && self
+                .max_restarts
+                .map(|max_restarts| restarts < max_restarts)
+                .unwrap_or(true)
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::on_error()
+    }
+}
+
+/// Status of a supervised task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// The task has not been started
+    Idle,
+    Running,
+    /// The task has exited and is awaiting a restart
+    Restarting,
+    /// The task has been stopped by the supervisor
+    Stopped,
+    /// The task has completed successfully and will not be restarted
+    Completed,
+    /// The task has failed and will not be restarted
+    Failed(String),
+}
+
+/// Metrics of a supervised task
+#[derive(Debug, Clone)]
+pub struct TaskMetrics {
+    pub status: TaskStatus,
+    /// Number of times the task has been started (including restarts)
+    pub starts: u64,
+    /// Number of restarts since the task was last started by the supervisor
+    pub restarts: u32,
+    /// Number of times the task has failed
+    pub failures: u64,
+    pub last_error: Option<String>,
+    /// Time of the last (re)start in milliseconds since the UNIX epoch
+    pub last_start: Option<u64>,
+}
+
+impl Default for TaskMetrics {
+    fn default() -> Self {
+        TaskMetrics {
+            status: TaskStatus::Idle,
+            starts: 0,
+            restarts: 0,
+            failures: 0,
+            last_error: None,
+            last_start: None,
+        }
+    }
+}
+
+struct Run {
+    stop: Sender<()>,
+    completion: Receiver<()>,
+}
+
+struct Supervised {
+    name: String,
+    policy: RestartPolicy,
+    task_fn: SupervisedFn,
+    metrics: Mutex<TaskMetrics>,
+    run: Mutex<Option<Run>>,
+}
+
+impl Supervised {
+    fn update(&self, f: impl FnOnce(&mut TaskMetrics)) {
+        f(&mut self.metrics.lock().unwrap())
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(
+            self.metrics.lock().unwrap().status,
+            TaskStatus::Running | TaskStatus::Restarting
+        )
+    }
+
+    fn start(self: &Arc<Self>) -> TaskResult<()> {
+        let mut run = self.run.lock().unwrap();
+        if run.is_some() && self.is_active() {
+            return Err(TaskError::AlreadyRunning);
+        }
+
+        let (stop_sender, stop_receiver) = oneshot();
+        let (completion_sender, completion_receiver) = oneshot();
+        *run = Some(Run {
+            stop: stop_sender,
+            completion: completion_receiver,
+        });
+        self.update(|metrics| {
+            metrics.status = TaskStatus::Running;
+            metrics.restarts = 0;
+        });
+
+        let this = self.clone();
+        spawn(async move {
+            this.supervise(stop_receiver).await;
+            completion_sender.send(()).await.ok();
+        });
+
+        Ok(())
+    }
+
+    async fn supervise(&self, stop: Receiver<()>) {
+        let mut restarts = 0;
+        loop {
+            self.update(|metrics| {
+                metrics.status = TaskStatus::Running;
+                metrics.starts += 1;
+                metrics.last_start = Some(unixtime_as_millis_u64());
+            });
+
+            let result = AssertUnwindSafe((self.task_fn)(stop.clone()))
+                .catch_unwind()
+                .await;
+            let error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(err.to_string()),
+                Err(_) => Some(format!("task `{}` panicked", self.name)),
+            };
+
+            // stop signal consumed by the task
+            if self.is_stopping() {
+                break;
+            }
+
+            let failed = error.is_some();
+            if let Some(error) = error.as_ref() {
+                self.update(|metrics| {
+                    metrics.failures += 1;
+                    metrics.last_error = Some(error.clone());
+                });
+            }
+
+            if !self.policy.should_restart(failed, restarts) {
+                self.update(|metrics| {
+                    metrics.status = match error {
+                        Some(error) => TaskStatus::Failed(error),
+                        None => TaskStatus::Completed,
+                    }
+                });
+                return;
+            }
+
+            restarts += 1;
+            self.update(|metrics| {
+                metrics.status = TaskStatus::Restarting;
+                metrics.restarts = restarts;
+            });
+            select! {
+                _ = sleep(self.policy.delay_for(restarts)).fuse() => {},
+                _ = stop.recv().fuse() => break,
+            }
+        }
+
+        self.update(|metrics| metrics.status = TaskStatus::Stopped);
+    }
+
+    fn is_stopping(&self) -> bool {
+        self.run.lock().unwrap().is_none()
+    }
+
+    async fn stop(&self) {
+        let run = self.run.lock().unwrap().take();
+        if let Some(run) = run {
+            run.stop.try_send(()).ok();
+            run.completion.recv().await.ok();
+        }
+    }
+}
+
+/// Supervisor of a named set of tasks (see the [module](self) documentation)
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    tasks: Arc<Mutex<BTreeMap<String, Arc<Supervised>>>>,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Supervisor::default()
+    }
+
+    /// Register a task under `name`; the task is started by [`Supervisor::start()`]
+    /// or [`Supervisor::start_all()`].
+    pub fn insert<FN>(
+        &self,
+        name: impl Into<String>,
+        policy: RestartPolicy,
+        task_fn: FN,
+    ) -> TaskResult<()>
+    where
+        FN: Send + Sync + Fn(Receiver<()>) -> SupervisedReturn + 'static,
+    {
+        let name = name.into();
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.contains_key(&name) {
+            return Err(TaskError::TaskExists(name));
+        }
+        let supervised = Arc::new(Supervised {
+            name: name.clone(),
+            policy,
+            task_fn: Arc::new(Box::new(task_fn)),
+            metrics: Mutex::new(TaskMetrics::default()),
+            run: Mutex::new(None),
+        });
+        tasks.insert(name, supervised);
+        Ok(())
+    }
+
+    /// Stop and unregister the task `name`
+    pub async fn remove(&self, name: &str) -> TaskResult<()> {
+        let supervised = self.tasks.lock().unwrap().remove(name);
+        match supervised {
+            Some(supervised) => {
+                supervised.stop().await;
+                Ok(())
+            }
+            None => Err(TaskError::TaskNotFound(name.to_string())),
+        }
+    }
+
+    fn get(&self, name: &str) -> TaskResult<Arc<Supervised>> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| TaskError::TaskNotFound(name.to_string()))
+    }
+
+    fn all(&self) -> Vec<Arc<Supervised>> {
+        self.tasks.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn start(&self, name: &str) -> TaskResult<()> {
+        self.get(name)?.start()
+    }
+
+    /// Start all tasks that are not running
+    pub fn start_all(&self) -> TaskResult<()> {
+        for supervised in self.all() {
+            if !supervised.is_active() {
+                supervised.start()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Signal termination to the task `name` and wait for it to exit
+    pub async fn stop(&self, name: &str) -> TaskResult<()> {
+        self.get(name)?.stop().await;
+        Ok(())
+    }
+
+    /// Signal termination to all tasks and wait for them to exit
+    pub async fn stop_all(&self) {
+        futures::future::join_all(self.all().iter().map(|supervised| supervised.stop())).await;
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.tasks.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.metrics(name).map(|metrics| metrics.status)
+    }
+
+    pub fn metrics(&self, name: &str) -> Option<TaskMetrics> {
+        self.get(name)
+            .ok()
+            .map(|supervised| supervised.metrics.lock().unwrap().clone())
+    }
+
+    /// Metrics of all tasks, ordered by name
+    pub fn all_metrics(&self) -> Vec<(String, TaskMetrics)> {
+        self.all()
+            .into_iter()
+            .map(|supervised| {
+                let metrics = supervised.metrics.lock().unwrap().clone();
+                (supervised.name.clone(), metrics)
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn test_supervisor() {
+        let supervisor = Supervisor::new();
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        supervisor
+            .insert(
+                "failing",
+                RestartPolicy::on_error()
+                    .with_max_restarts(3)
+                    .with_delay(Duration::from_millis(10)),
+                move |_stop| -> SupervisedReturn {
+                    let attempt = counter.fetch_add(1, Ordering::SeqCst);
+                    Box::pin(async move { Err(format!("attempt {attempt}").into()) })
+                },
+            )
+            .unwrap();
+
+        supervisor
+            .insert(
+                "service",
+                RestartPolicy::always(),
+                |stop: Receiver<()>| -> SupervisedReturn {
+                    Box::pin(async move {
+                        stop.recv().await.ok();
+                        Ok(())
+                    })
+                },
+            )
+            .unwrap();
+
+        assert!(matches!(
+            supervisor.insert("service", RestartPolicy::never(), |_| Box::pin(async {
+                Ok(())
+            })),
+            Err(TaskError::TaskExists(_))
+        ));
+
+        supervisor.start_all().unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        let failing = supervisor.metrics("failing").unwrap();
+        assert_eq!(failing.status, TaskStatus::Failed("attempt 3".to_string()));
+        assert_eq!(
+            (failing.starts, failing.restarts, failing.failures),
+            (4, 3, 4)
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        assert_eq!(supervisor.status("service"), Some(TaskStatus::Running));
+
+        supervisor.stop_all().await;
+        assert_eq!(supervisor.status("service"), Some(TaskStatus::Stopped));
+        assert_eq!(supervisor.metrics("service").unwrap().starts, 1);
+
+        supervisor.start("service").unwrap();
+        supervisor.remove("service").await.unwrap();
+        assert_eq!(supervisor.names(), vec!["failing".to_string()]);
+    }
+
+    #[test]
+    pub fn test_restart_policy_delay() {
+        let policy = RestartPolicy::on_error();
+        assert_eq!(policy.delay_for(1), Duration::from_millis(250));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(500));
+        assert_eq!(policy.delay_for(4), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(67), Duration::from_secs(30));
+        assert_eq!(policy.delay_for(u32::MAX), Duration::from_secs(30));
+
+        let policy = RestartPolicy::always()
+            .with_backoff(0.5)
+            .with_max_delay(Duration::MAX);
+        assert_eq!(policy.delay_for(1000), Duration::from_millis(250));
+
+        let policy = RestartPolicy::always()
+            .with_delay(Duration::ZERO)
+            .with_max_delay(Duration::MAX);
+        assert_eq!(policy.delay_for(u32::MAX), Duration::ZERO);
+
+        let policy = RestartPolicy::always().with_max_delay(Duration::MAX);
+        assert_eq!(policy.delay_for(u32::MAX), Duration::MAX);
+    }
+}
@@ -0,0 +1,514 @@
+//!
+//! Declarative argument parsing for [`Handler`](crate::cli::Handler) commands
+//!
+//! A handler can declare its arguments by returning an [`ArgSpec`] from
+//! [`Handler::args()`](crate::cli::Handler::args).  [`HandlerCli`](crate::cli::HandlerCli)
+//! then validates the command line before invoking the handler (reporting
+//! parsing errors along with the command usage), responds to `<cmd> --help`
+//! and `help <cmd>`, and [`HandlerCompleter`](crate::completion::HandlerCompleter)
+//! completes option names and [`ArgType::Choice`] values.
+//!
+//! ```ignore
+//! fn args(&self, _ctx: &Arc<dyn Context>) -> Option<ArgSpec> {
+//!     Some(ArgSpec::new()
+//!         .with_arg(Arg::positional("address").required().with_help("target address"))
+//!         .with_arg(Arg::option("count").with_short('c').with_type(ArgType::Integer).with_default("10"))
+//!         .with_arg(Arg::flag("verbose").with_short('v').with_help("verbose output")))
+//! }
+//!
+//! async fn handle(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+//!     let args = self.args(ctx).unwrap().parse(&argv)?;
+//!     let address = args.get::<String>("address")?.unwrap();
+//!     let count = args.get::<u64>("count")?.unwrap();
+//!     let verbose = args.flag("verbose");
+//!     ...
+//! }
+//! ```
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Argument kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// Boolean switch (`--name` or `-n`)
+    Flag,
+    /// Named option receiving a value (`--name value`, `--name=value` or `-n value`)
+    Option,
+    /// Positional argument
+    Positional,
+}
+
+/// Argument value type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgType {
+    String,
+    Integer,
+    Float,
+    Bool,
+    /// One of the listed values
+    Choice(Vec<String>),
+}
+
+impl ArgType {
+    fn validate(&self, name: &str, value: &str) -> Result<()> {
+        let valid = match self {
+            ArgType::String => true,
+            ArgType::Integer => value.parse::<i64>().is_ok(),
+            ArgType::Float => value.parse::<f64>().is_ok(),
+            ArgType::Bool => value.parse::<bool>().is_ok(),
+            ArgType::Choice(choices) => choices.iter().any(|choice| choice == value),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::Args(format!(
+                "invalid value '{value}' for '{name}': expected {}",
+                self.hint()
+            )))
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            ArgType::String => "string".to_string(),
+            ArgType::Integer => "integer".to_string(),
+            ArgType::Float => "number".to_string(),
+            ArgType::Bool => "true|false".to_string(),
+            ArgType::Choice(choices) => choices.join("|"),
+        }
+    }
+}
+
+/// Argument declaration
+#[derive(Debug, Clone)]
+pub struct Arg {
+    name: String,
+    short: Option<char>,
+    kind: ArgKind,
+    ty: ArgType,
+    default: Option<String>,
+    required: bool,
+    help: String,
+}
+
+impl Arg {
+    fn new(name: &str, kind: ArgKind) -> Self {
+        Arg {
+            name: name.to_string(),
+            short: None,
+            kind,
+            ty: ArgType::String,
+            default: None,
+            required: false,
+            help: String::new(),
+        }
+    }
+
+    /// Declare a boolean flag
+    pub fn flag(name: &str) -> Self {
+        Arg::new(name, ArgKind::Flag)
+    }
+
+    /// Declare a named option
+    pub fn option(name: &str) -> Self {
+        Arg::new(name, ArgKind::Option)
+    }
+
+    /// Declare a positional argument (positional arguments
+    /// are matched in the order of declaration)
+    pub fn positional(name: &str) -> Self {
+        Arg::new(name, ArgKind::Positional)
+    }
+
+    /// Set the short (single character) name of a flag or an option
+    pub fn with_short(mut self, short: char) -> Self {
+        self.short = Some(short);
+        self
+    }
+
+    /// Set the value type (defaults to [`ArgType::String`])
+    pub fn with_type(mut self, ty: ArgType) -> Self {
+        self.ty = ty;
+        self
+    }
+
+    /// Set the default value
+    pub fn with_default(mut self, default: &str) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+
+    /// Mark the argument as required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the help text
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = help.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> ArgKind {
+        self.kind
+    }
+
+    fn usage(&self) -> String {
+        let usage = match self.kind {
+            ArgKind::Flag => format!("--{}", self.name),
+            ArgKind::Option => format!("--{} <{}>", self.name, self.ty.hint()),
+            ArgKind::Positional => format!("<{}>", self.name),
+        };
+        if self.required {
+            usage
+        } else {
+            format!("[{usage}]")
+        }
+    }
+
+    fn label(&self) -> String {
+        match (self.kind, self.short) {
+            (ArgKind::Positional, _) => format!("<{}>", self.name),
+            (ArgKind::Flag, Some(short)) => format!("-{short}, --{}", self.name),
+            (ArgKind::Flag, None) => format!("    --{}", self.name),
+            (ArgKind::Option, Some(short)) => {
+                format!("-{short}, --{} <{}>", self.name, self.ty.hint())
+            }
+            (ArgKind::Option, None) => format!("    --{} <{}>", self.name, self.ty.hint()),
+        }
+    }
+}
+
+/// Declaration of command arguments
+#[derive(Debug, Clone, Default)]
+pub struct ArgSpec {
+    args: Vec<Arg>,
+}
+
+impl ArgSpec {
+    pub fn new() -> Self {
+        ArgSpec::default()
+    }
+
+    /// Add an argument declaration
+    pub fn with_arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn args(&self) -> &[Arg] {
+        &self.args
+    }
+
+    fn named(&self, name: &str) -> Result<&Arg> {
+        self.args
+            .iter()
+            .find(|arg| arg.kind != ArgKind::Positional && arg.name == name)
+            .ok_or_else(|| Error::Args(format!("unknown option '--{name}'")))
+    }
+
+    fn short(&self, short: char) -> Result<&Arg> {
+        self.args
+            .iter()
+            .find(|arg| arg.kind != ArgKind::Positional && arg.short == Some(short))
+            .ok_or_else(|| Error::Args(format!("unknown option '-{short}'")))
+    }
+
+    /// Returns `true` if the arguments request help (`--help` or `-h`,
+    /// unless declared by the command)
+    pub fn is_help(&self, argv: &[String]) -> bool {
+        argv.iter().any(|arg| {
+            (arg == "--help" && self.named("help").is_err())
+                || (arg == "-h" && self.short('h').is_err())
+        })
+    }
+
+    /// Parse command arguments (excluding the command verb)
+    pub fn parse(&self, argv: &[String]) -> Result<Args> {
+        let mut values = HashMap::new();
+        let mut flags = HashSet::new();
+        let mut positionals = self
+            .args
+            .iter()
+            .filter(|arg| arg.kind == ArgKind::Positional);
+        let mut options_done = false;
+
+        let mut iter = argv.iter().filter(|arg| !arg.is_empty());
+        while let Some(token) = iter.next() {
+            let named = if options_done || token == "-" || !token.starts_with('-') {
+                None
+            } else if token == "--" {
+                options_done = true;
+                continue;
+            } else if let Some(long) = token.strip_prefix("--") {
+                match long.split_once('=') {
+                    Some((name, value)) => Some((self.named(name)?, Some(value.to_string()))),
+                    None => Some((self.named(long)?, None)),
+                }
+            } else {
+                let mut chars = token.chars().skip(1);
+                match (chars.next(), chars.next()) {
+                    (Some(short), None) => Some((self.short(short)?, None)),
+                    // negative numbers are treated as positional values
+                    _ if token.parse::<f64>().is_ok() => None,
+                    _ => return Err(Error::Args(format!("unknown option '{token}'"))),
+                }
+            };
+
+            match named {
+                Some((arg, value)) if arg.kind == ArgKind::Flag => {
+                    if value.is_some() {
+                        return Err(Error::Args(format!(
+                            "flag '--{}' does not take a value",
+                            arg.name
+                        )));
+                    }
+                    flags.insert(arg.name.clone());
+                }
+                Some((arg, value)) => {
+                    let value = match value {
+                        Some(value) => value,
+                        None => iter.next().cloned().ok_or_else(|| {
+                            Error::Args(format!("option '--{}' requires a value", arg.name))
+                        })?,
+                    };
+                    arg.ty.validate(&arg.name, &value)?;
+                    values.insert(arg.name.clone(), value);
+                }
+                None => {
+                    let arg = positionals
+                        .next()
+                        .ok_or_else(|| Error::Args(format!("unexpected argument '{token}'")))?;
+                    arg.ty.validate(&arg.name, token)?;
+                    values.insert(arg.name.clone(), token.clone());
+                }
+            }
+        }
+
+        for arg in self.args.iter().filter(|arg| arg.kind != ArgKind::Flag) {
+            if values.contains_key(&arg.name) {
+                continue;
+            }
+            if let Some(default) = &arg.default {
+                values.insert(arg.name.clone(), default.clone());
+            } else if arg.required {
+                return Err(Error::Args(match arg.kind {
+                    ArgKind::Positional => format!("missing argument <{}>", arg.name),
+                    _ => format!("missing option '--{}'", arg.name),
+                }));
+            }
+        }
+
+        Ok(Args { values, flags })
+    }
+
+    /// Single-line command usage
+    pub fn usage(&self, verb: &str) -> String {
+        let mut usage = vec![verb.to_string()];
+        let order = [ArgKind::Flag, ArgKind::Option, ArgKind::Positional];
+        for kind in order {
+            usage.extend(
+                self.args
+                    .iter()
+                    .filter(|arg| arg.kind == kind)
+                    .map(|arg| arg.usage()),
+            );
+        }
+        format!("usage: {}", usage.join(" "))
+    }
+
+    /// Command help listing the usage and all declared arguments
+    pub fn help(&self, verb: &str, about: &str) -> Vec<String> {
+        let mut lines = vec![];
+        if !about.is_empty() {
+            lines.push(about.to_string());
+            lines.push(String::new());
+        }
+        lines.push(self.usage(verb));
+        if self.args.is_empty() {
+            return lines;
+        }
+        lines.push(String::new());
+        let labels = self.args.iter().map(|arg| arg.label()).collect::<Vec<_>>();
+        let width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        for (arg, label) in self.args.iter().zip(labels) {
+            let mut line = format!("  {label:<width$}  {}", arg.help);
+            if let Some(default) = &arg.default {
+                line.push_str(&format!(" (default: {default})"));
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Completion candidates for the word being typed (the last word
+    /// of `line`): option names or values of [`ArgType::Choice`] arguments.
+    pub fn complete(&self, line: &str) -> Vec<String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (previous, word) = if line.ends_with(char::is_whitespace) {
+            (words.last().copied(), "")
+        } else {
+            let len = words.len();
+            (
+                len.checked_sub(2).map(|idx| words[idx]),
+                words.last().copied().unwrap_or_default(),
+            )
+        };
+
+        // value of the preceding option
+        if let Some(option) = previous.and_then(|previous| self.option_for(previous)) {
+            return match &option.ty {
+                ArgType::Choice(choices) => choices.clone(),
+                _ => vec![],
+            };
+        }
+
+        if word.starts_with('-') {
+            return self
+                .args
+                .iter()
+                .filter(|arg| arg.kind != ArgKind::Positional)
+                .map(|arg| format!("--{}", arg.name))
+                .collect();
+        }
+
+        // choices of the next positional argument
+        let end = words.len() - usize::from(!word.is_empty());
+        let consumed = self.count_positionals(words.get(1..end).unwrap_or_default());
+        self.args
+            .iter()
+            .filter(|arg| arg.kind == ArgKind::Positional)
+            .nth(consumed)
+            .map(|arg| match &arg.ty {
+                ArgType::Choice(choices) => choices.clone(),
+                _ => vec![],
+            })
+            .unwrap_or_default()
+    }
+
+    fn option_for(&self, token: &str) -> Option<&Arg> {
+        let arg = if let Some(long) = token.strip_prefix("--") {
+            self.named(long).ok()
+        } else {
+            let mut chars = token.strip_prefix('-')?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(short), None) => self.short(short).ok(),
+                _ => None,
+            }
+        };
+        arg.filter(|arg| arg.kind == ArgKind::Option)
+    }
+
+    fn count_positionals(&self, words: &[&str]) -> usize {
+        let mut count = 0;
+        let mut iter = words.iter();
+        while let Some(word) = iter.next() {
+            if self.option_for(word).is_some() {
+                iter.next();
+            } else if !word.starts_with('-') {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Parsed command arguments
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    values: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl Args {
+    /// Returns `true` if the flag is present
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    /// Raw value of an option or a positional argument
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|value| value.as_str())
+    }
+
+    /// Value of an option or a positional argument converted to `T`
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+    {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse::<T>()
+                    .map_err(|_| Error::Args(format!("invalid value '{value}' for '{name}'")))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ArgSpec {
+        ArgSpec::new()
+            .with_arg(Arg::positional("address").required())
+            .with_arg(
+                Arg::option("count")
+                    .with_short('c')
+                    .with_type(ArgType::Integer)
+                    .with_default("10"),
+            )
+            .with_arg(
+                Arg::option("network")
+                    .with_type(ArgType::Choice(vec!["mainnet".into(), "testnet".into()])),
+            )
+            .with_arg(Arg::flag("verbose").with_short('v'))
+    }
+
+    fn argv(cmd: &str) -> Vec<String> {
+        cmd.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_args_parse() {
+        let spec = spec();
+        let args = spec.parse(&argv("abc -c 5 --verbose")).unwrap();
+        assert_eq!(args.value("address"), Some("abc"));
+        assert_eq!(args.get::<u64>("count").unwrap(), Some(5));
+        assert!(args.flag("verbose"));
+
+        let args = spec.parse(&argv("--network=testnet abc")).unwrap();
+        assert_eq!(args.get::<u64>("count").unwrap(), Some(10));
+        assert_eq!(args.value("network"), Some("testnet"));
+        assert!(!args.flag("verbose"));
+
+        assert!(spec.parse(&argv("")).is_err());
+        assert!(spec.parse(&argv("abc --count x")).is_err());
+        assert!(spec.parse(&argv("abc --network devnet")).is_err());
+        assert!(spec.parse(&argv("abc def")).is_err());
+        assert!(spec.parse(&argv("abc --unknown")).is_err());
+    }
+
+    #[test]
+    fn test_args_complete() {
+        let spec = spec();
+        assert_eq!(
+            spec.complete("send abc --network "),
+            vec!["mainnet", "testnet"]
+        );
+        assert!(spec
+            .complete("send abc --")
+            .contains(&"--count".to_string()));
+        assert!(spec.complete("send ").is_empty());
+    }
+}
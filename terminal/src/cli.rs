@@ -2,6 +2,7 @@
 //! Cli trait for implementing a user-side command-line processor.
 //!
 
+use crate::args::ArgSpec;
 use crate::error::Error;
 use crate::parse;
 pub use crate::result::Result;
@@ -54,6 +55,12 @@ pub trait Handler: Sync + Send + AnySync {
     fn dyn_help(&self, _ctx: &Arc<dyn Context>) -> String {
         "".to_owned()
     }
+    /// Declare command arguments. If declared, arguments are validated
+    /// before [`Handler::handle()`] is invoked and the command help
+    /// (`<cmd> --help` or `help <cmd>`) is generated from the declaration.
+    fn args(&self, _ctx: &Arc<dyn Context>) -> Option<ArgSpec> {
+        None
+    }
     async fn complete(&self, _ctx: &Arc<dyn Context>, _cmd: &str) -> Result<Option<Vec<String>>> {
         Ok(None)
     }
@@ -185,18 +192,41 @@ impl HandlerCli {
         let argv = parse(cmd);
         let action = argv[0].to_lowercase();
 
+        // `help <cmd>` unless the application provides its own `help` command
+        if action == "help" && argv.len() == 2 && self.get("help").is_none() {
+            if let Some(help) = self.help(&ctx, &argv[1].to_lowercase()) {
+                help.into_iter().for_each(|line| ctx.term().writeln(line));
+                return Ok(());
+            }
+        }
+
         let handler = self.get(action.as_str());
         if let Some(handler) = handler {
-            handler
-                .clone()
-                .handle(&ctx, argv[1..].to_vec(), cmd)
-                .await?;
+            let argv = argv[1..].to_vec();
+            if let Some(spec) = handler.args(&ctx) {
+                if spec.is_help(&argv) {
+                    let help = spec.help(&action, &get_handler_help(handler.clone(), &ctx));
+                    help.into_iter().for_each(|line| ctx.term().writeln(line));
+                    return Ok(());
+                }
+                if let Err(err) = spec.parse(&argv) {
+                    return Err(Error::Args(format!("{err}\n\r{}", spec.usage(&action))));
+                }
+            }
+            handler.clone().handle(&ctx, argv, cmd).await?;
             Ok(())
         } else {
             Err(Error::CommandNotFound(action))
         }
     }
 
+    /// Help for a command declaring its arguments using [`Handler::args()`]
+    pub fn help(&self, ctx: &Arc<dyn Context>, verb: &str) -> Option<Vec<String>> {
+        let handler = self.get(verb)?;
+        let spec = handler.args(ctx)?;
+        Some(spec.help(verb, &get_handler_help(handler, ctx)))
+    }
+
     pub async fn complete<T>(&self, ctx: &Arc<T>, cmd: &str) -> Result<Option<Vec<String>>>
     where
         T: Context + Sized,
@@ -208,7 +238,10 @@ impl HandlerCli {
 
         let handler = self.get(action.as_str());
         if let Some(handler) = handler {
-            Ok(handler.clone().complete(&ctx, cmd).await?)
+            match handler.clone().complete(&ctx, cmd).await? {
+                Some(candidates) => Ok(Some(candidates)),
+                None => Ok(handler.args(&ctx).map(|spec| spec.complete(cmd))),
+            }
         } else {
             Err(Error::CommandNotFound(action))
         }
@@ -0,0 +1,83 @@
+//!
+//! Clipboard helpers
+//!
+//! [`Terminal::copy()`](crate::Terminal::copy) copies text to the system
+//! clipboard using the OSC 52 escape sequence when running natively (supported
+//! by most modern terminal emulators, including over SSH) and the async
+//! Clipboard API (or the NWJS clipboard) in the browser.
+//!
+//! Pasted text (received via bracketed paste natively or via the clipboard
+//! in the browser) is passed through a paste filter before it is inserted
+//! into the line buffer.  The default filter, [`sanitize()`], strips escape
+//! sequences and control characters, so pasted text can not inject terminal
+//! control sequences.  Pasted line breaks continue the input on a new line
+//! (as multi-line input) without submitting it.  A custom filter can be installed
+//! using [`Terminal::register_paste_filter()`](crate::Terminal::register_paste_filter).
+//!
+
+use std::sync::Arc;
+
+/// Paste filter receiving the pasted text and returning the text that
+/// should be inserted into the line buffer (or `None` to reject the paste).
+pub type PasteFilterFn = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Default paste filter: removes ANSI escape sequences and control characters
+/// except line breaks (normalized to `\n`), replacing tabs with spaces.
+/// Trailing line breaks are removed.
+pub fn sanitize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.trim_end_matches(['\r', '\n']).chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1B' => {
+                // skip CSI (`ESC [ ... final`) and OSC (`ESC ] ... BEL | ESC \`) sequences
+                match chars.next() {
+                    Some('[') => {
+                        for ch in chars.by_ref() {
+                            if ('\x40'..='\x7E').contains(&ch) {
+                                break;
+                            }
+                        }
+                    }
+                    Some(']') => {
+                        while let Some(ch) = chars.next() {
+                            if ch == '\x07' || (ch == '\x1B' && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                result.push('\n');
+            }
+            '\n' => result.push('\n'),
+            '\t' => result.push(' '),
+            ch if ch.is_control() => {}
+            ch => result.push(ch),
+        }
+    }
+    result
+}
+
+/// OSC 52 escape sequence setting the system clipboard content
+pub fn osc52(text: &str) -> String {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    format!("\x1B]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("ls -la\tsrc"), "ls -la src");
+        assert_eq!(sanitize("first\r\nsecond\rthird\n"), "first\nsecond\nthird");
+        assert_eq!(sanitize("echo \x1B[31mred\x1B[0m"), "echo red");
+        assert_eq!(sanitize("\x1B]0;title\x07text\x1B]8;;url\x1B\\"), "text");
+        assert_eq!(sanitize("a\x00b\x08c\x7F"), "abc");
+    }
+}
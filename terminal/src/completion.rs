@@ -0,0 +1,141 @@
+//!
+//! Pluggable tab-completion for the terminal command line
+//!
+//! An application can register a [`Completer`] with the terminal using
+//! [`Terminal::register_completer()`](crate::Terminal::register_completer).
+//! On `Tab`, the completer receives the current line buffer and cursor
+//! position and produces a [`Completion`].  A single candidate is inserted
+//! inline, multiple candidates are extended to their common prefix and,
+//! if the prefix can not be extended, listed below the prompt.
+//!
+//! [`HandlerCompleter`] provides completion of command names registered
+//! with [`HandlerCli`], delegating subcommand and argument completion to
+//! [`Handler::complete()`](crate::cli::Handler::complete) or, if not
+//! implemented, to the argument declaration of the handler
+//! ([`Handler::args()`](crate::cli::Handler::args)).
+//!
+
+use crate::cli::{Context, HandlerCli};
+use crate::result::Result;
+use crate::terminal::Terminal;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Completion candidates replacing the text between
+/// `start` and the cursor position (character indexes).
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+impl Completion {
+    /// Create a completion for the word preceding the cursor, retaining
+    /// only candidates that start with the word being completed.
+    pub fn for_word<S: ToString>(buffer: &str, cursor: usize, candidates: &[S]) -> Self {
+        let (start, word) = word_at(buffer, cursor);
+        let candidates = candidates
+            .iter()
+            .map(|candidate| candidate.to_string())
+            .filter(|candidate| candidate.starts_with(&word))
+            .collect();
+        Completion { start, candidates }
+    }
+
+    /// Longest prefix shared by all candidates
+    pub fn common_prefix(&self) -> String {
+        let mut iter = self.candidates.iter();
+        let Some(first) = iter.next() else {
+            return String::new();
+        };
+        let mut prefix = first.chars().collect::<Vec<_>>();
+        for candidate in iter {
+            let len = prefix
+                .iter()
+                .zip(candidate.chars())
+                .take_while(|(a, b)| *a == b)
+                .count();
+            prefix.truncate(len);
+        }
+        prefix.into_iter().collect()
+    }
+}
+
+/// Returns the character index and the text of the word preceding the cursor
+pub fn word_at(buffer: &str, cursor: usize) -> (usize, String) {
+    let chars = buffer.chars().take(cursor).collect::<Vec<_>>();
+    let start = chars
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    (start, chars[start..].iter().collect())
+}
+
+/// Tab-completion provider
+#[async_trait]
+pub trait Completer: Send + Sync {
+    /// Produce completion candidates for the supplied line `buffer`
+    /// and `cursor` position (character index).
+    async fn complete(
+        &self,
+        term: &Arc<Terminal>,
+        buffer: &str,
+        cursor: usize,
+    ) -> Result<Option<Completion>>;
+}
+
+/// [`Completer`] completing command names registered with [`HandlerCli`]
+/// and delegating completion of the following arguments to the
+/// [`Handler::complete()`](crate::cli::Handler::complete) function of the
+/// command handler, which should return candidates for the word being typed.
+pub struct HandlerCompleter {
+    cli: Arc<HandlerCli>,
+    ctx: Arc<dyn Context>,
+}
+
+impl HandlerCompleter {
+    pub fn new(cli: &Arc<HandlerCli>, ctx: Arc<dyn Context>) -> Self {
+        HandlerCompleter {
+            cli: cli.clone(),
+            ctx,
+        }
+    }
+}
+
+#[async_trait]
+impl Completer for HandlerCompleter {
+    async fn complete(
+        &self,
+        _term: &Arc<Terminal>,
+        buffer: &str,
+        cursor: usize,
+    ) -> Result<Option<Completion>> {
+        let line = buffer.chars().take(cursor).collect::<String>();
+        let (start, _) = word_at(&line, cursor);
+        if line.chars().take(start).all(char::is_whitespace) {
+            let mut verbs = self
+                .cli
+                .collect()
+                .iter()
+                .filter_map(|handler| handler.verb(&self.ctx))
+                .map(|verb| verb.to_lowercase())
+                .collect::<Vec<_>>();
+            verbs.sort();
+            return Ok(Some(Completion::for_word(&line, cursor, &verbs)));
+        }
+
+        let verb = line.split_whitespace().next().unwrap_or_default();
+        match self.cli.get(&verb.to_lowercase()) {
+            Some(handler) => {
+                let candidates = match handler.complete(&self.ctx, &line).await? {
+                    Some(candidates) => Some(candidates),
+                    // fall back to completion hints from the argument declaration
+                    None => handler.args(&self.ctx).map(|spec| spec.complete(&line)),
+                };
+                Ok(candidates.map(|candidates| Completion::for_word(&line, cursor, &candidates)))
+            }
+            None => Ok(None),
+        }
+    }
+}
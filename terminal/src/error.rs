@@ -7,13 +7,14 @@ use std::sync::PoisonError;
 use thiserror::Error;
 use wasm_bindgen::JsValue;
 use workflow_core::channel::{ChannelError, RecvError, SendError, TrySendError};
+use workflow_wasm::jserror::JsErrorData;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("{0}")]
     Custom(String),
     #[error("{0}")]
-    JsValue(String),
+    JsValue(JsErrorData),
     #[error("Poison Error: {0}")]
     PoisonError(String),
     #[error("Channel Receive Error")]
@@ -32,10 +33,14 @@ pub enum Error {
     DowncastError(String),
     #[error("command not found: {0}")]
     CommandNotFound(String),
+    #[error("{0}")]
+    Args(String),
     #[error("aborting...")]
     UserAbort,
     #[error(transparent)]
     CallbackError(#[from] workflow_wasm::callback::CallbackError),
+    #[error(transparent)]
+    Store(#[from] workflow_store::error::Error),
 }
 
 impl From<String> for Error {
@@ -51,7 +56,7 @@ impl From<&str> for Error {
 
 impl From<JsValue> for Error {
     fn from(v: JsValue) -> Self {
-        Self::JsValue(format!("{v:?}"))
+        Self::JsValue(v.into())
     }
 }
 
@@ -0,0 +1,135 @@
+//!
+//! Persistent command history options
+//!
+//! Command history is stored using [`workflow_store`] - as a file
+//! when running natively or under Node.js / NWJS, and in the browser
+//! local storage (keyed by the file name) when running in the browser.
+//! Entries are separated by new lines; lines of multi-line entries are
+//! stored with a trailing `\` continuation character.
+//!
+
+use crate::result::Result;
+use crate::UnicodeString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use workflow_core::runtime;
+use workflow_store::fs;
+
+/// Default maximum number of history entries retained
+pub const DEFAULT_MAX_HISTORY_ENTRIES: usize = 1000;
+
+pub type HistoryFilterFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Persistent command history options
+#[derive(Clone)]
+pub struct HistoryOptions {
+    /// History file path (`~/` is resolved to the home directory)
+    pub path: String,
+    /// Maximum number of history entries retained
+    pub max_entries: usize,
+    /// Do not store a command identical to the previous command
+    pub dedup: bool,
+    /// Commands matching this filter are not stored in history
+    /// (use to exclude commands containing sensitive data)
+    pub exclude: Option<HistoryFilterFn>,
+}
+
+impl HistoryOptions {
+    /// Create history options storing history in the supplied file
+    pub fn new(path: &str) -> Self {
+        HistoryOptions {
+            path: path.to_string(),
+            max_entries: DEFAULT_MAX_HISTORY_ENTRIES,
+            dedup: true,
+            exclude: None,
+        }
+    }
+
+    /// Set the maximum number of history entries
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Enable or disable suppression of consecutive duplicate entries
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Set a filter excluding matching commands from history
+    pub fn with_exclude<F>(mut self, exclude: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.exclude = Some(Arc::new(exclude));
+        self
+    }
+
+    /// Returns `true` if the command should be stored in history
+    pub fn accepts(&self, cmd: &str, previous: Option<&UnicodeString>) -> bool {
+        if cmd.trim().is_empty() {
+            return false;
+        }
+        if let Some(exclude) = &self.exclude {
+            if exclude(cmd) {
+                return false;
+            }
+        }
+        !(self.dedup
+            && previous
+                .map(|prev| prev.to_string() == cmd)
+                .unwrap_or(false))
+    }
+
+    fn resolve_path(&self) -> Result<PathBuf> {
+        Ok(fs::resolve_path(&self.path)?)
+    }
+
+    /// Load history entries from storage
+    pub async fn load(&self) -> Result<Vec<UnicodeString>> {
+        let path = self.resolve_path()?;
+        if !fs::exists(&path).await? {
+            return Ok(vec![]);
+        }
+        let text = fs::read_to_string(&path).await?;
+        let mut history = vec![];
+        let mut entry = String::new();
+        for line in text.lines() {
+            if let Some(line) = line.strip_suffix('\\') {
+                entry.push_str(line);
+                entry.push('\n');
+            } else {
+                entry.push_str(line);
+                if !entry.trim().is_empty() {
+                    history.push(UnicodeString::from(entry.as_str()));
+                }
+                entry.clear();
+            }
+        }
+        if history.len() > self.max_entries {
+            history.drain(0..history.len() - self.max_entries);
+        }
+        Ok(history)
+    }
+
+    /// Store history entries
+    pub async fn store(&self, history: &[UnicodeString]) -> Result<()> {
+        let path = self.resolve_path()?;
+        if !runtime::is_web() {
+            if let Some(folder) = path.parent().filter(|folder| folder != &Path::new("")) {
+                fs::create_dir_all(folder).await?;
+            }
+        }
+        let skip = history.len().saturating_sub(self.max_entries);
+        let text = history
+            .iter()
+            .skip(skip)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.to_string().replace('\n', "\\\n"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write_string(&path, &text).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+//!
+//! Background job control
+//!
+//! Command handlers can launch long-running operations as background jobs
+//! using [`Terminal::spawn_job()`](crate::Terminal::spawn_job), returning to
+//! the prompt immediately.  Jobs are tracked by the terminal and can be
+//! managed using the `jobs`, `fg` and `kill` built-in commands (enabled
+//! using [`Jobs::set_builtins()`]):
+//!
+//! - `jobs` - list jobs
+//! - `fg [id]` - bring a job to the foreground (displaying buffered output
+//!   and waiting for the job to complete)
+//! - `kill <id>` - abort a job
+//!
+//! Job output written using [`Job::writeln()`] is either buffered until the job
+//! is brought to the foreground ([`JobOutput::Buffered`]) or written to the
+//! terminal immediately with a `[id]` prefix ([`JobOutput::Streamed`]).
+//!
+//! ```ignore
+//! term.spawn_job("sync", JobOutput::Streamed, |job| async move {
+//!     for block in 0..100 {
+//!         job.writeln(format!("processing block {block}"));
+//!         sleep(Duration::from_millis(100)).await;
+//!     }
+//!     Ok(())
+//! });
+//! ```
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use crate::terminal::Terminal;
+use futures::future::{AbortHandle, Abortable};
+use futures::Future;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use workflow_core::channel::Channel;
+use workflow_core::task::spawn;
+
+pub type JobId = u64;
+
+/// Handling of output produced by a job running in background
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobOutput {
+    /// Buffer output until the job is brought to the foreground
+    Buffered,
+    /// Write output immediately, prefixed with the job id
+    Streamed,
+}
+
+/// Job execution state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Done,
+    Failed(String),
+    Killed,
+}
+
+impl std::fmt::Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobState::Running => write!(f, "Running"),
+            JobState::Done => write!(f, "Done"),
+            JobState::Failed(err) => write!(f, "Failed: {err}"),
+            JobState::Killed => write!(f, "Killed"),
+        }
+    }
+}
+
+struct JobInner {
+    id: JobId,
+    name: String,
+    output: JobOutput,
+    state: Mutex<JobState>,
+    buffer: Mutex<Vec<String>>,
+    foreground: AtomicBool,
+    abort: AbortHandle,
+    done: Channel<()>,
+}
+
+/// Handle to a background job, supplied to the job closure
+/// and allowing the job to produce output.
+#[derive(Clone)]
+pub struct Job {
+    inner: Arc<JobInner>,
+    term: Weak<Terminal>,
+}
+
+impl Job {
+    pub fn id(&self) -> JobId {
+        self.inner.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn state(&self) -> JobState {
+        self.inner.state.lock().unwrap().clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state() == JobState::Running
+    }
+
+    pub fn is_foreground(&self) -> bool {
+        self.inner.foreground.load(Ordering::SeqCst)
+    }
+
+    /// Write a line of job output (discarded if the terminal has been dropped)
+    pub fn writeln<S: ToString>(&self, text: S) {
+        let Some(term) = self.term.upgrade() else {
+            return;
+        };
+        let text = text.to_string();
+        if self.is_foreground() {
+            term.writeln(text);
+        } else {
+            match self.inner.output {
+                JobOutput::Buffered => self.inner.buffer.lock().unwrap().push(text),
+                JobOutput::Streamed => term.writeln(format!("[{}] {text}", self.id())),
+            }
+        }
+    }
+
+    /// Abort the job
+    pub fn kill(&self) {
+        self.inner.abort.abort();
+    }
+
+    fn finish(&self, state: JobState) {
+        *self.inner.state.lock().unwrap() = state;
+        self.inner.done.try_send(()).ok();
+    }
+
+    fn take_output(&self) -> Vec<String> {
+        std::mem::take(&mut *self.inner.buffer.lock().unwrap())
+    }
+
+    fn summary(&self) -> String {
+        let buffered = self.inner.buffer.lock().unwrap().len();
+        if buffered > 0 {
+            format!(
+                "[{}] {:<10} {} ({buffered} lines of output buffered)",
+                self.id(),
+                self.state().to_string(),
+                self.name()
+            )
+        } else {
+            format!(
+                "[{}] {:<10} {}",
+                self.id(),
+                self.state().to_string(),
+                self.name()
+            )
+        }
+    }
+}
+
+/// Registry of jobs tracked by the terminal
+pub struct Jobs {
+    jobs: Mutex<BTreeMap<JobId, Job>>,
+    next_id: AtomicU64,
+    builtins: AtomicBool,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Jobs {
+            jobs: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            builtins: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Jobs {
+    /// Enable or disable the `jobs`, `fg` and `kill` built-in commands
+    /// (disabled by default, as these verbs may be used by the application)
+    pub fn set_builtins(&self, enabled: bool) {
+        self.builtins.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn get(&self, id: JobId) -> Option<Job> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+
+    /// List of tracked jobs
+    pub fn list(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().values().cloned().collect()
+    }
+
+    fn remove(&self, id: JobId) {
+        self.jobs.lock().unwrap().remove(&id);
+    }
+
+    /// Most recently started job
+    fn last(&self) -> Option<Job> {
+        self.jobs.lock().unwrap().values().next_back().cloned()
+    }
+
+    pub(crate) fn spawn<F, Fut>(
+        &self,
+        term: &Arc<Terminal>,
+        name: &str,
+        output: JobOutput,
+        f: F,
+    ) -> JobId
+    where
+        F: FnOnce(Job) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (abort, registration) = AbortHandle::new_pair();
+        let job = Job {
+            inner: Arc::new(JobInner {
+                id,
+                name: name.to_string(),
+                output,
+                state: Mutex::new(JobState::Running),
+                buffer: Mutex::new(vec![]),
+                foreground: AtomicBool::new(false),
+                abort,
+                done: Channel::oneshot(),
+            }),
+            term: Arc::downgrade(term),
+        };
+        self.jobs.lock().unwrap().insert(id, job.clone());
+
+        let future = Abortable::new(f(job.clone()), registration);
+        spawn(async move {
+            let state = match future.await {
+                Ok(Ok(())) => JobState::Done,
+                Ok(Err(err)) => JobState::Failed(err.to_string()),
+                Err(_) => JobState::Killed,
+            };
+            job.finish(state);
+            if let Some(term) = job.term.upgrade() {
+                if !job.is_foreground() {
+                    term.writeln(job.summary());
+                    if job.inner.buffer.lock().unwrap().is_empty() {
+                        term.jobs().remove(id);
+                    }
+                }
+            }
+        });
+
+        id
+    }
+
+    /// Handle a built-in job control command, returns `false`
+    /// if the command is not a job control command.
+    pub(crate) async fn builtin(&self, term: &Arc<Terminal>, cmd: &str) -> Result<bool> {
+        if !self.builtins.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        let argv = cmd.split_whitespace().collect::<Vec<_>>();
+        match argv.as_slice() {
+            ["jobs"] => {
+                let jobs = self.list();
+                if jobs.is_empty() {
+                    term.writeln("no jobs");
+                }
+                for job in jobs {
+                    term.writeln(job.summary());
+                    if !job.is_running() && job.inner.buffer.lock().unwrap().is_empty() {
+                        self.remove(job.id());
+                    }
+                }
+            }
+            ["fg"] | ["fg", _] => {
+                let job = match argv.get(1) {
+                    Some(id) => self.get(parse_id(id)?),
+                    None => self.last(),
+                };
+                let Some(job) = job else {
+                    return Err(Error::Custom("no such job".to_string()));
+                };
+                job.inner.foreground.store(true, Ordering::SeqCst);
+                for line in job.take_output() {
+                    term.writeln(line);
+                }
+                if job.is_running() {
+                    job.inner.done.recv().await?;
+                }
+                if job.state() != JobState::Done {
+                    term.writeln(job.summary());
+                }
+                self.remove(job.id());
+            }
+            ["kill", id] => {
+                let Some(job) = self.get(parse_id(id)?) else {
+                    return Err(Error::Custom("no such job".to_string()));
+                };
+                job.kill();
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn parse_id(id: &str) -> Result<JobId> {
+    id.trim_start_matches('%')
+        .parse::<JobId>()
+        .map_err(|_| Error::Custom(format!("invalid job id: {id}")))
+}
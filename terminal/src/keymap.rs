@@ -0,0 +1,274 @@
+//!
+//! Customizable key bindings
+//!
+//! A [`KeyMap`] maps keys to line editing [`Action`]s or user callbacks.
+//! The terminal uses [`KeyMap::default()`] unless a different key map is
+//! supplied using [`Terminal::set_keymap()`](crate::Terminal::set_keymap)
+//! or [`Options::with_keymap()`](crate::Options::with_keymap).  Emacs and Vi
+//! editing presets are available via [`KeyMap::emacs()`] and [`KeyMap::vi()`].
+//!
+//! Printable characters that are not bound are inserted into the line
+//! buffer (unless the terminal is in the Vi normal mode).
+//!
+//! ```ignore
+//! let mut keymap = KeyMap::emacs();
+//! keymap.unbind(Key::Ctrl('d'));
+//! keymap.bind_callback(Key::Ctrl('l'), |term| {
+//!     term.write(ClearScreen);
+//!     term.prompt();
+//!     Ok(())
+//! });
+//! term.set_keymap(keymap);
+//! ```
+//!
+
+use crate::keys::Key;
+use crate::result::Result;
+use crate::terminal::Terminal;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub type KeyCallbackFn = Arc<dyn Fn(&Arc<Terminal>) -> Result<()> + Send + Sync>;
+
+/// Line editing actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Delete the character preceding the cursor
+    DeleteBackward,
+    /// Delete the character under the cursor
+    DeleteForward,
+    /// Move the cursor one character to the left
+    MoveLeft,
+    /// Move the cursor one character to the right
+    MoveRight,
+    /// Move the cursor to the beginning of the line
+    MoveHome,
+    /// Move the cursor to the end of the line
+    MoveEnd,
+    /// Move the cursor to the beginning of the previous word
+    MoveWordLeft,
+    /// Move the cursor past the end of the next word
+    MoveWordRight,
+    /// Recall the previous history entry
+    HistoryPrevious,
+    /// Recall the next history entry
+    HistoryNext,
+    /// Submit the line buffer for execution
+    Submit,
+    /// Invoke tab-completion
+    Complete,
+    /// Delete text from the cursor to the end of the line
+    KillToEnd,
+    /// Delete text from the beginning of the line to the cursor
+    KillToStart,
+    /// Delete the word preceding the cursor
+    KillWordBackward,
+    /// Interrupt (exits the terminal when running natively)
+    Interrupt,
+    /// Incremental reverse search of the command history
+    ReverseSearch,
+    /// Search the terminal output (xterm.js only)
+    SearchOutput,
+    /// Switch to the Vi insert mode
+    ViInsert,
+    /// Switch to the Vi insert mode after the cursor
+    ViAppend,
+    /// Switch to the Vi insert mode at the end of the line
+    ViAppendEnd,
+    /// Switch to the Vi insert mode at the beginning of the line
+    ViInsertStart,
+    /// Switch to the Vi normal (command) mode
+    ViNormal,
+}
+
+/// Action or user callback bound to a key
+#[derive(Clone)]
+pub enum Binding {
+    Action(Action),
+    Callback(KeyCallbackFn),
+}
+
+impl From<Action> for Binding {
+    fn from(action: Action) -> Self {
+        Binding::Action(action)
+    }
+}
+
+/// Line editing mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EditMode {
+    /// Characters are inserted into the line buffer
+    #[default]
+    Insert,
+    /// Vi normal (command) mode
+    Normal,
+}
+
+/// Key binding map
+#[derive(Clone)]
+pub struct KeyMap {
+    insert: HashMap<Key, Binding>,
+    normal: HashMap<Key, Binding>,
+}
+
+impl Default for KeyMap {
+    /// Key map providing basic line editing
+    /// (arrows, home/end, backspace/delete, enter, tab, search)
+    fn default() -> Self {
+        use Action::*;
+        let mut keymap = KeyMap::empty();
+        keymap.bind_actions(
+            EditMode::Insert,
+            &[
+                (Key::Backspace, DeleteBackward),
+                (Key::Delete, DeleteForward),
+                (Key::ArrowLeft, MoveLeft),
+                (Key::ArrowRight, MoveRight),
+                (Key::Home, MoveHome),
+                (Key::End, MoveEnd),
+                (Key::ArrowUp, HistoryPrevious),
+                (Key::ArrowDown, HistoryNext),
+                (Key::Enter, Submit),
+                (Key::Tab, Complete),
+                (Key::Ctrl('c'), Interrupt),
+                (Key::Ctrl('r'), ReverseSearch),
+                (Key::Ctrl('s'), SearchOutput),
+            ],
+        );
+        keymap
+    }
+}
+
+impl KeyMap {
+    /// Create a key map without any bindings
+    pub fn empty() -> Self {
+        KeyMap {
+            insert: HashMap::new(),
+            normal: HashMap::new(),
+        }
+    }
+
+    /// Emacs editing preset
+    pub fn emacs() -> Self {
+        use Action::*;
+        let mut keymap = KeyMap::default();
+        keymap.bind_actions(
+            EditMode::Insert,
+            &[
+                (Key::Ctrl('a'), MoveHome),
+                (Key::Ctrl('e'), MoveEnd),
+                (Key::Ctrl('b'), MoveLeft),
+                (Key::Ctrl('f'), MoveRight),
+                (Key::Alt('b'), MoveWordLeft),
+                (Key::Alt('f'), MoveWordRight),
+                (Key::Ctrl('p'), HistoryPrevious),
+                (Key::Ctrl('n'), HistoryNext),
+                (Key::Ctrl('h'), DeleteBackward),
+                (Key::Ctrl('d'), DeleteForward),
+                (Key::Ctrl('k'), KillToEnd),
+                (Key::Ctrl('u'), KillToStart),
+                (Key::Ctrl('w'), KillWordBackward),
+            ],
+        );
+        keymap
+    }
+
+    /// Vi editing preset (the terminal starts in the insert mode, `Esc`
+    /// switches to the normal mode)
+    pub fn vi() -> Self {
+        use Action::*;
+        let mut keymap = KeyMap::default();
+        keymap.bind_actions(EditMode::Insert, &[(Key::Esc, ViNormal)]);
+        keymap.bind_actions(
+            EditMode::Normal,
+            &[
+                (Key::Char('h'), MoveLeft),
+                (Key::Char('l'), MoveRight),
+                (Key::ArrowLeft, MoveLeft),
+                (Key::ArrowRight, MoveRight),
+                (Key::Backspace, MoveLeft),
+                (Key::Char(' '), MoveRight),
+                (Key::Char('0'), MoveHome),
+                (Key::Char('^'), MoveHome),
+                (Key::Char('$'), MoveEnd),
+                (Key::Home, MoveHome),
+                (Key::End, MoveEnd),
+                (Key::Char('b'), MoveWordLeft),
+                (Key::Char('w'), MoveWordRight),
+                (Key::Char('k'), HistoryPrevious),
+                (Key::Char('j'), HistoryNext),
+                (Key::ArrowUp, HistoryPrevious),
+                (Key::ArrowDown, HistoryNext),
+                (Key::Char('x'), DeleteForward),
+                (Key::Delete, DeleteForward),
+                (Key::Char('X'), DeleteBackward),
+                (Key::Char('D'), KillToEnd),
+                (Key::Char('i'), ViInsert),
+                (Key::Char('a'), ViAppend),
+                (Key::Char('A'), ViAppendEnd),
+                (Key::Char('I'), ViInsertStart),
+                (Key::Char('/'), ReverseSearch),
+                (Key::Ctrl('r'), ReverseSearch),
+                (Key::Enter, Submit),
+                (Key::Ctrl('c'), Interrupt),
+            ],
+        );
+        keymap
+    }
+
+    fn map(&self, mode: EditMode) -> &HashMap<Key, Binding> {
+        match mode {
+            EditMode::Insert => &self.insert,
+            EditMode::Normal => &self.normal,
+        }
+    }
+
+    fn map_mut(&mut self, mode: EditMode) -> &mut HashMap<Key, Binding> {
+        match mode {
+            EditMode::Insert => &mut self.insert,
+            EditMode::Normal => &mut self.normal,
+        }
+    }
+
+    fn bind_actions(&mut self, mode: EditMode, actions: &[(Key, Action)]) {
+        let map = self.map_mut(mode);
+        for (key, action) in actions {
+            map.insert(*key, Binding::Action(*action));
+        }
+    }
+
+    /// Bind a key to an action (in the insert mode)
+    pub fn bind(&mut self, key: Key, action: Action) -> &mut Self {
+        self.bind_in(EditMode::Insert, key, action)
+    }
+
+    /// Bind a key to an action in the specified edit mode
+    pub fn bind_in<B: Into<Binding>>(&mut self, mode: EditMode, key: Key, binding: B) -> &mut Self {
+        self.map_mut(mode).insert(key, binding.into());
+        self
+    }
+
+    /// Bind a key to a user callback (in the insert mode)
+    pub fn bind_callback<F>(&mut self, key: Key, callback: F) -> &mut Self
+    where
+        F: Fn(&Arc<Terminal>) -> Result<()> + Send + Sync + 'static,
+    {
+        self.bind_in(EditMode::Insert, key, Binding::Callback(Arc::new(callback)))
+    }
+
+    /// Remove a key binding (in the insert mode)
+    pub fn unbind(&mut self, key: Key) -> &mut Self {
+        self.unbind_in(EditMode::Insert, key)
+    }
+
+    /// Remove a key binding in the specified edit mode
+    pub fn unbind_in(&mut self, mode: EditMode, key: Key) -> &mut Self {
+        self.map_mut(mode).remove(&key);
+        self
+    }
+
+    /// Get the binding of the key in the specified edit mode
+    pub fn get(&self, mode: EditMode, key: &Key) -> Option<&Binding> {
+        self.map(mode).get(key)
+    }
+}
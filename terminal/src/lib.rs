@@ -95,28 +95,48 @@
 
 extern crate self as workflow_terminal;
 
+pub mod args;
 pub mod clear;
 pub mod cli;
+pub mod clipboard;
+pub mod completion;
 pub mod crlf;
 pub mod cursor;
 pub mod error;
+pub mod history;
+pub mod jobs;
+pub mod keymap;
 pub mod keys;
 pub mod macros;
+pub mod pager;
 pub mod prelude;
+pub mod progress;
 pub mod result;
+pub mod search;
+pub mod table;
 pub mod terminal;
+pub mod theme;
 pub mod unicode;
 
+pub use args::{Arg, ArgSpec, ArgType, Args};
 pub use cli::{Cli, Context, Handler, HandlerCli};
+pub use completion::{Completer, Completion, HandlerCompleter};
 pub use crlf::CrLf;
+pub use history::HistoryOptions;
+pub use jobs::{Job, JobOutput, JobState};
+pub use keymap::{Action, EditMode, KeyMap};
 pub use macros::*;
+pub use progress::{Progress, ProgressKind};
 pub use result::Result;
+pub use table::{Align, Column, Table};
 pub use terminal::parse;
+pub use terminal::Dimensions;
 pub use terminal::Event;
 pub use terminal::Modifiers;
 pub use terminal::Options;
 pub use terminal::TargetElement;
 pub use terminal::Terminal;
+pub use terminal::{MouseButton, MouseEvent};
 pub use terminal::{Theme, ThemeOption};
 pub use textwrap;
 pub use unicode::UnicodeString;
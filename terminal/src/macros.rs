@@ -1,6 +1,5 @@
 use crate::terminal::Terminal;
 use std::sync::Arc;
-use workflow_log::style;
 
 #[inline(always)]
 pub fn tprint_impl<T>(term: T, args: &str)
@@ -23,8 +22,8 @@ pub fn terrorln_impl<T>(term: T, args: &str)
 where
     T: Into<Arc<Terminal>>,
 {
-    term.into()
-        .writeln(style(args.to_string()).red().to_string());
+    let term = term.into();
+    term.writeln(term.theme().style_error(args));
 }
 
 #[inline(always)]
@@ -32,8 +31,8 @@ pub fn twarnln_impl<T>(term: T, args: &str)
 where
     T: Into<Arc<Terminal>>,
 {
-    term.into()
-        .writeln(style(args.to_string()).yellow().to_string());
+    let term = term.into();
+    term.writeln(term.theme().style_warning(args));
 }
 
 #[inline(always)]
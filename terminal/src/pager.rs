@@ -0,0 +1,198 @@
+//!
+//! Built-in pager for long output
+//!
+//! [`Terminal::page()`](crate::Terminal::page) displays text in a less-like
+//! pager using the alternate screen buffer, so long output does not overflow
+//! the terminal scrollback.  Supported keys:
+//!
+//! - `q`, `Esc` - quit
+//! - `ArrowDown`, `j`, `Enter` - scroll down one line
+//! - `ArrowUp`, `k` - scroll up one line
+//! - `Space`, `PageDown`, `f` - scroll down one page
+//! - `PageUp`, `b` - scroll up one page
+//! - `Home`, `g` - go to the beginning
+//! - `End`, `G` - go to the end
+//! - `/` - search (enter the search text followed by `Enter`)
+//! - `n`, `N` - go to the next / previous search match
+//!
+//! In the browser, the pager can also be scrolled using the mouse wheel.
+//!
+
+use crate::clear::ClearLine;
+use crate::cursor::Goto;
+use crate::keys::Key;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use textwrap::core::display_width;
+use workflow_core::channel::Sender;
+
+/// Enter the alternate screen buffer and hide the cursor
+pub const ENTER_ALTERNATE_SCREEN: &str = "\x1B[?1049h\x1B[?25l";
+/// Show the cursor and leave the alternate screen buffer
+pub const LEAVE_ALTERNATE_SCREEN: &str = "\x1B[?25h\x1B[?1049l";
+
+pub(crate) struct Pager {
+    lines: Vec<String>,
+    top: usize,
+    height: usize,
+    search: Option<String>,
+    search_input: Option<String>,
+    status: Option<String>,
+}
+
+impl Pager {
+    /// Create a pager wrapping `text` to `width` columns
+    /// and displaying `rows - 1` lines per page.
+    pub fn new(text: &str, width: usize, rows: usize) -> Self {
+        let lines = text
+            .lines()
+            .flat_map(|line| {
+                if display_width(line) <= width {
+                    vec![line.to_string()]
+                } else {
+                    textwrap::wrap(line, width)
+                        .into_iter()
+                        .map(|line| line.to_string())
+                        .collect()
+                }
+            })
+            .collect();
+        Pager {
+            lines,
+            top: 0,
+            height: rows.saturating_sub(1).max(1),
+            search: None,
+            search_input: None,
+            status: None,
+        }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Returns `true` if the text fits in a single page
+    pub fn fits(&self) -> bool {
+        self.lines.len() <= self.height
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.height)
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        self.top = (self.top + n).min(self.max_top());
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        self.top = self.top.saturating_sub(n);
+    }
+
+    /// Scroll by the number of lines (negative values scroll up)
+    pub fn scroll(&mut self, lines: isize) {
+        if lines < 0 {
+            self.scroll_up(lines.unsigned_abs());
+        } else {
+            self.scroll_down(lines as usize);
+        }
+    }
+
+    fn find(&mut self, forward: bool) {
+        let Some(search) = self.search.clone() else {
+            return;
+        };
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(|idx| self.lines[*idx].contains(&search))
+        } else {
+            (0..self.top)
+                .rev()
+                .find(|idx| self.lines[*idx].contains(&search))
+        };
+        match found {
+            Some(idx) => self.top = idx.min(self.max_top()),
+            None => self.status = Some(format!("pattern not found: {search}")),
+        }
+    }
+
+    /// Process a key, returns `false` if the pager should be closed
+    pub fn ingest(&mut self, key: Key) -> bool {
+        self.status = None;
+
+        if let Some(input) = self.search_input.as_mut() {
+            match key {
+                Key::Enter => {
+                    let search = self.search_input.take().unwrap_or_default();
+                    if !search.is_empty() {
+                        self.search = Some(search);
+                        // include the current top line in the initial search
+                        self.top = self.top.saturating_sub(1);
+                        self.find(true);
+                    }
+                }
+                Key::Esc => {
+                    self.search_input = None;
+                }
+                Key::Backspace => {
+                    input.pop();
+                }
+                Key::Char(ch) => input.push(ch),
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::ArrowDown | Key::Enter | Key::Char('j') => self.scroll_down(1),
+            Key::ArrowUp | Key::Char('k') => self.scroll_up(1),
+            Key::PageDown | Key::Char(' ') | Key::Char('f') => self.scroll_down(self.height),
+            Key::PageUp | Key::Char('b') => self.scroll_up(self.height),
+            Key::Home | Key::Char('g') => self.top = 0,
+            Key::End | Key::Char('G') => self.top = self.max_top(),
+            Key::Char('/') => self.search_input = Some(String::new()),
+            Key::Char('n') => self.find(true),
+            Key::Char('N') => self.find(false),
+            _ => {}
+        }
+        true
+    }
+
+    /// Render the current page including the status line
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for row in 0..self.height {
+            out.push_str(&format!("{}{}", Goto(1, row as u16 + 1), ClearLine));
+            if let Some(line) = self.lines.get(self.top + row) {
+                out.push_str(line);
+            }
+        }
+        let status = if let Some(input) = &self.search_input {
+            format!("/{input}")
+        } else if let Some(status) = &self.status {
+            status.clone()
+        } else {
+            let last = (self.top + self.height).min(self.lines.len());
+            format!(
+                "\x1B[7m lines {}-{} of {} (q: quit, /: search) \x1B[0m",
+                self.top + 1,
+                last,
+                self.lines.len()
+            )
+        };
+        out.push_str(&format!(
+            "{}{}{}",
+            Goto(1, self.height as u16 + 1),
+            ClearLine,
+            status
+        ));
+        out
+    }
+}
+
+/// Pager receiving keys while [`Terminal::page()`](crate::Terminal::page) is pending
+pub(crate) struct ActivePager {
+    pub pager: Pager,
+    /// terminates the key intake spawned for the pager
+    pub terminate: Arc<AtomicBool>,
+    pub closed: Sender<()>,
+}
@@ -0,0 +1,222 @@
+//!
+//! Progress bar and spinner widgets
+//!
+//! A [`Progress`] widget occupies the current terminal line while a command
+//! is executing and is redrawn using ANSI sequences (supported natively and
+//! by xterm.js).  Output produced using [`Terminal::writeln()`] while the
+//! widget is active is written above it, after which the widget is redrawn,
+//! so concurrent log output does not corrupt the widget.
+//!
+//! ```ignore
+//! let progress = term.progress();
+//! progress.set_message("downloading");
+//! for chunk in 0..100 {
+//!     progress.set_fraction(chunk as f64 / 100.0);
+//! }
+//! progress.finish(Some("download complete"));
+//! ```
+//!
+
+use crate::clear::ClearLine;
+use crate::terminal::Terminal;
+use futures::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use workflow_core::task::{interval, spawn};
+
+const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_BAR_WIDTH: usize = 30;
+
+/// Progress widget type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressKind {
+    /// Progress bar displaying completion fraction
+    Bar,
+    /// Animated spinner for operations of unknown length
+    Spinner,
+}
+
+#[derive(Debug)]
+pub(crate) struct ProgressState {
+    kind: ProgressKind,
+    message: String,
+    fraction: f64,
+    frame: usize,
+}
+
+impl ProgressState {
+    /// Render the widget line, the bar is narrowed to fit
+    /// within `cols` terminal columns (if available)
+    pub(crate) fn render(&self, cols: Option<usize>) -> String {
+        match self.kind {
+            ProgressKind::Bar => {
+                // message, ` [`, `] ` and the percentage (`100%`)
+                let width = cols
+                    .map(|cols| cols.saturating_sub(self.message.chars().count() + 8))
+                    .unwrap_or(DEFAULT_BAR_WIDTH)
+                    .min(DEFAULT_BAR_WIDTH);
+                let filled = (self.fraction * width as f64).round() as usize;
+                format!(
+                    "{} [{}{}] {:>3}%",
+                    self.message,
+                    "#".repeat(filled),
+                    "-".repeat(width - filled),
+                    (self.fraction * 100.0).round() as usize
+                )
+            }
+            ProgressKind::Spinner => {
+                format!(
+                    "{} {}",
+                    SPINNER_FRAMES[self.frame % SPINNER_FRAMES.len()],
+                    self.message
+                )
+            }
+        }
+    }
+}
+
+pub(crate) type SharedProgressState = Arc<Mutex<ProgressState>>;
+
+struct Inner {
+    term: Arc<Terminal>,
+    state: SharedProgressState,
+    finished: AtomicBool,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        finish(self, None);
+    }
+}
+
+/// Progress bar or spinner bound to a [`Terminal`] instance.
+/// The widget remains active until [`Progress::finish()`] is called
+/// or until all clones of the widget are dropped.
+#[derive(Clone)]
+pub struct Progress {
+    inner: Arc<Inner>,
+}
+
+impl Progress {
+    pub(crate) fn new(term: &Arc<Terminal>, kind: ProgressKind) -> Progress {
+        let state = Arc::new(Mutex::new(ProgressState {
+            kind,
+            message: String::new(),
+            fraction: 0.0,
+            frame: 0,
+        }));
+        let progress = Progress {
+            inner: Arc::new(Inner {
+                term: term.clone(),
+                state: state.clone(),
+                finished: AtomicBool::new(false),
+            }),
+        };
+        term.set_progress(Some(state));
+        progress.redraw();
+
+        if kind == ProgressKind::Spinner {
+            // the spinner task does not keep the widget alive
+            let inner = Arc::downgrade(&progress.inner);
+            spawn(async move {
+                let mut interval = interval(SPINNER_INTERVAL);
+                while interval.next().await.is_some() {
+                    match Weak::upgrade(&inner).map(|inner| Progress { inner }) {
+                        Some(progress) if !progress.is_finished() => progress.tick(),
+                        _ => break,
+                    }
+                }
+            });
+        }
+
+        progress
+    }
+
+    /// Set the message displayed alongside the widget
+    pub fn set_message<S: ToString>(&self, message: S) {
+        self.inner.state.lock().unwrap().message = message.to_string();
+        self.redraw();
+    }
+
+    /// Set the completion fraction (`0.0..=1.0`) of the progress bar
+    pub fn set_fraction(&self, fraction: f64) {
+        self.inner.state.lock().unwrap().fraction = fraction.clamp(0.0, 1.0);
+        self.redraw();
+    }
+
+    /// Advance the spinner animation
+    pub fn tick(&self) {
+        self.inner.state.lock().unwrap().frame += 1;
+        self.redraw();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.finished.load(Ordering::SeqCst)
+    }
+
+    /// Remove the widget, optionally replacing it with a final message
+    pub fn finish(&self, message: Option<&str>) {
+        finish(&self.inner, message);
+    }
+
+    fn redraw(&self) {
+        if !self.is_finished() {
+            let line = self
+                .inner
+                .state
+                .lock()
+                .unwrap()
+                .render(self.inner.term.cols());
+            self.inner.term.write(format!("{ClearLine}{line}"));
+        }
+    }
+}
+
+fn finish(inner: &Inner, message: Option<&str>) {
+    if inner.finished.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    inner.term.set_progress(None);
+    match message {
+        Some(message) => inner.term.write(format!("{ClearLine}{message}\n\r")),
+        None => inner.term.write(ClearLine),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bar(message: &str, fraction: f64) -> ProgressState {
+        ProgressState {
+            kind: ProgressKind::Bar,
+            message: message.to_string(),
+            fraction,
+            frame: 0,
+        }
+    }
+
+    #[test]
+    fn test_progress_bar_render() {
+        let state = bar("copy", 0.5);
+        let line = state.render(None);
+        assert_eq!(
+            line,
+            format!("copy [{}{}]  50%", "#".repeat(15), "-".repeat(15))
+        );
+        assert_eq!(state.render(Some(200)), line);
+
+        // narrowed to fit the terminal width
+        let line = state.render(Some(32));
+        assert_eq!(
+            line,
+            format!("copy [{}{}]  50%", "#".repeat(10), "-".repeat(10))
+        );
+        assert_eq!(line.chars().count(), 32);
+
+        // no room for the bar
+        assert_eq!(bar("copy", 1.0).render(Some(8)), "copy [] 100%");
+    }
+}
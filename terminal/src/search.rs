@@ -0,0 +1,69 @@
+//!
+//! Interactive search over command history and terminal output
+//!
+//! - History search (`Ctrl-R` by default, bound to [`Action::ReverseSearch`](crate::keymap::Action::ReverseSearch))
+//!   incrementally searches command history from the most recent entry.
+//!   Pressing `Ctrl-R` again moves to the next older match, `Enter` executes
+//!   the matched command, `Esc` (or `Ctrl-G`) cancels the search and any other
+//!   key places the matched command into the line buffer for editing.
+//! - Output search (`Ctrl-S` by default, bound to [`Action::SearchOutput`](crate::keymap::Action::SearchOutput))
+//!   searches the xterm.js scrollback buffer, scrolling to and selecting the
+//!   matching text.  `Enter` or `Ctrl-S` moves to the next older match and `Esc`
+//!   ends the search.  Output search is available only in the browser.
+//!
+
+use crate::clear::ClearLine;
+use crate::UnicodeString;
+
+/// Search target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    History,
+    Output,
+}
+
+pub(crate) struct Search {
+    pub kind: SearchKind,
+    pub query: String,
+    /// line buffer before the search was started
+    pub original: UnicodeString,
+    /// history index or output line of the current match
+    pub position: Option<usize>,
+    pub failed: bool,
+}
+
+impl Search {
+    pub fn new(kind: SearchKind, original: UnicodeString) -> Self {
+        Search {
+            kind,
+            query: String::new(),
+            original,
+            position: None,
+            failed: false,
+        }
+    }
+
+    /// Render the search status line displaying the current `matched` text
+    pub fn render(&self, matched: Option<&UnicodeString>) -> String {
+        let label = match self.kind {
+            SearchKind::History => "reverse-i-search",
+            SearchKind::Output => "find-in-output",
+        };
+        let failed = if self.failed { "failing " } else { "" };
+        let matched = matched
+            .map(|matched| matched.to_string().replace('\n', " "))
+            .unwrap_or_default();
+        format!("{ClearLine}({failed}{label})`{}': {matched}", self.query)
+    }
+}
+
+/// Index of the most recent history entry preceding `before`
+/// and containing `query`
+pub(crate) fn find_history(history: &[UnicodeString], query: &str, before: usize) -> Option<usize> {
+    if query.is_empty() {
+        return None;
+    }
+    history[..before.min(history.len())]
+        .iter()
+        .rposition(|entry| entry.to_string().contains(query))
+}
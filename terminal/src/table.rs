@@ -0,0 +1,378 @@
+//!
+//! Table rendering helper
+//!
+//! [`Table`] renders rows of data in aligned columns, fitting the table into
+//! the current terminal width by shrinking the widest columns and wrapping
+//! (or truncating) their content.  Styling is applied per column after the
+//! layout is computed, so cell content should be supplied as plain text.
+//!
+//! ```ignore
+//! let mut table = Table::new()
+//!     .with_column(Column::new("Address"))
+//!     .with_column(Column::new("Balance").with_align(Align::Right))
+//!     .with_column(Column::new("Note").with_max_width(40));
+//! table.add_row(["kaspa:qr...", "1,000.00", "savings"]);
+//! term.table(&table);
+//! ```
+//!
+
+use std::sync::Arc;
+use textwrap::core::display_width;
+use workflow_log::style;
+
+pub type StyleFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Column content alignment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// Table column definition
+#[derive(Clone)]
+pub struct Column {
+    header: String,
+    align: Align,
+    min_width: usize,
+    max_width: Option<usize>,
+    wrap: bool,
+    style: Option<StyleFn>,
+}
+
+impl Column {
+    pub fn new(header: &str) -> Self {
+        Column {
+            header: header.to_string(),
+            align: Align::Left,
+            min_width: 3,
+            max_width: None,
+            wrap: true,
+            style: None,
+        }
+    }
+
+    /// Set content alignment
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the minimum width the column can be shrunk to
+    pub fn with_min_width(mut self, min_width: usize) -> Self {
+        self.min_width = min_width.max(1);
+        self
+    }
+
+    /// Set the maximum column width
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width.max(1));
+        self
+    }
+
+    /// Wrap content exceeding the column width (default);
+    /// if disabled, the content is truncated.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Apply styling (e.g. ANSI colors) to the column content
+    pub fn with_style<F>(mut self, style: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.style = Some(Arc::new(style));
+        self
+    }
+
+    fn layout(&self, text: &str, width: usize) -> Vec<String> {
+        if self.wrap {
+            textwrap::wrap(text, width)
+                .into_iter()
+                .map(|line| line.to_string())
+                .collect()
+        } else {
+            vec![truncate(text, width)]
+        }
+    }
+}
+
+/// Table builder rendering rows of data in aligned columns
+#[derive(Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    separator: String,
+    header: bool,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Table {
+            columns: vec![],
+            rows: vec![],
+            separator: "  ".to_string(),
+            header: true,
+        }
+    }
+
+    /// Add a column
+    pub fn with_column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Add columns with the default settings
+    pub fn with_columns(mut self, headers: &[&str]) -> Self {
+        self.columns
+            .extend(headers.iter().map(|header| Column::new(header)));
+        self
+    }
+
+    /// Set the column separator (default is two spaces)
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Enable or disable rendering of the header row
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Add a row of cells. Missing cells are rendered as empty,
+    /// cells exceeding the number of columns are ignored.
+    pub fn add_row<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows
+            .push(row.into_iter().map(|cell| cell.to_string()).collect());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn cell(row: &[String], idx: usize) -> &str {
+        row.get(idx).map(|cell| cell.as_str()).unwrap_or_default()
+    }
+
+    /// Compute column widths fitting the table into `width`
+    fn widths(&self, width: usize) -> Vec<usize> {
+        let mut widths = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, column)| {
+                let header = if self.header {
+                    display_width(&column.header)
+                } else {
+                    0
+                };
+                let content = self
+                    .rows
+                    .iter()
+                    .flat_map(|row| Self::cell(row, idx).lines())
+                    .map(display_width)
+                    .max()
+                    .unwrap_or_default();
+                let natural = header.max(content).max(column.min_width);
+                column
+                    .max_width
+                    .map(|max| natural.min(max.max(column.min_width)))
+                    .unwrap_or(natural)
+            })
+            .collect::<Vec<_>>();
+
+        let separators = display_width(&self.separator) * self.columns.len().saturating_sub(1);
+        let available = width.saturating_sub(separators);
+        let mut total = widths.iter().sum::<usize>();
+        while total > available {
+            // shrink the widest column that can still be shrunk
+            let widest = widths
+                .iter()
+                .enumerate()
+                .filter(|(idx, w)| **w > self.columns[*idx].min_width)
+                .max_by_key(|(_, w)| **w)
+                .map(|(idx, _)| idx);
+            match widest {
+                Some(idx) => {
+                    widths[idx] -= 1;
+                    total -= 1;
+                }
+                None => break,
+            }
+        }
+        widths
+    }
+
+    fn render_row(&self, cells: &[&str], widths: &[usize], header: bool) -> Vec<String> {
+        let columns = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(idx, (column, width))| column.layout(cells.get(idx).unwrap_or(&""), *width))
+            .collect::<Vec<_>>();
+        let height = columns.iter().map(|lines| lines.len()).max().unwrap_or(1);
+
+        (0..height)
+            .map(|line| {
+                self.columns
+                    .iter()
+                    .zip(widths)
+                    .zip(columns.iter())
+                    .map(|((column, width), lines)| {
+                        let text = lines.get(line).map(|s| s.as_str()).unwrap_or_default();
+                        let text = align(text, *width, column.align);
+                        if header {
+                            style(text).bold().to_string()
+                        } else if let Some(style) = &column.style {
+                            style(&text)
+                        } else {
+                            text
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&self.separator)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// Render the table into lines fitting the supplied width
+    pub fn render(&self, width: usize) -> Vec<String> {
+        let widths = self.widths(width);
+        let mut lines = vec![];
+        if self.header {
+            let headers = self
+                .columns
+                .iter()
+                .map(|column| column.header.as_str())
+                .collect::<Vec<_>>();
+            lines.extend(self.render_row(&headers, &widths, true));
+            let rule = widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>()
+                .join(&self.separator);
+            lines.push(rule);
+        }
+        for row in self.rows.iter() {
+            let cells = (0..self.columns.len())
+                .map(|idx| Self::cell(row, idx))
+                .collect::<Vec<_>>();
+            lines.extend(self.render_row(&cells, &widths, false));
+        }
+        lines
+    }
+}
+
+fn align(text: &str, width: usize, align: Align) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    match align {
+        Align::Left => format!("{text}{}", " ".repeat(padding)),
+        Align::Right => format!("{}{text}", " ".repeat(padding)),
+        Align::Center => {
+            let left = padding / 2;
+            format!("{}{text}{}", " ".repeat(left), " ".repeat(padding - left))
+        }
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    let text = text.lines().next().unwrap_or_default();
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut len = 0;
+    for ch in text.chars() {
+        let w = display_width(ch.encode_utf8(&mut [0; 4]));
+        if len + w + 1 > width {
+            break;
+        }
+        result.push(ch);
+        len += w;
+    }
+    result.push('…');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alignment() {
+        let mut table = Table::new()
+            .with_header(false)
+            .with_column(Column::new("a"))
+            .with_column(Column::new("b").with_align(Align::Right))
+            .with_column(Column::new("c").with_align(Align::Center));
+        table.add_row(["x", "1", "m"]);
+        table.add_row(["long", "1000", "mid"]);
+        table.add_row(["y"]);
+        assert_eq!(
+            table.render(80),
+            vec!["x        1   m", "long  1000  mid", "y"]
+        );
+    }
+
+    #[test]
+    fn test_header_rule() {
+        let mut table = Table::new().with_columns(&["name", "value"]);
+        table.add_row(["alpha", "1"]);
+        let lines = table.render(80);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "-----  -----");
+        assert_eq!(lines[2], "alpha  1");
+    }
+
+    #[test]
+    fn test_column_sizing() {
+        let mut table = Table::new()
+            .with_header(false)
+            .with_column(Column::new("a"))
+            .with_column(Column::new("b"));
+        table.add_row(["aaaaaaaaaa", "bbbbbbbbbb"]);
+        assert_eq!(table.widths(80), vec![10, 10]);
+        assert_eq!(table.widths(16), vec![7, 7]);
+        // columns are not shrunk below the minimum width
+        assert_eq!(table.widths(4), vec![3, 3]);
+
+        let mut table = Table::new()
+            .with_header(false)
+            .with_column(Column::new("a").with_min_width(9))
+            .with_column(Column::new("b").with_max_width(6));
+        table.add_row(["aaaaaaaaaa", "bbbbbbbbbb"]);
+        assert_eq!(table.widths(80), vec![10, 6]);
+        assert_eq!(table.widths(14), vec![9, 3]);
+    }
+
+    #[test]
+    fn test_wrap_and_truncate() {
+        let mut table = Table::new()
+            .with_header(false)
+            .with_column(Column::new("a").with_max_width(5))
+            .with_column(Column::new("b").with_max_width(5).with_wrap(false));
+        table.add_row(["hello world", "truncated"]);
+        assert_eq!(table.render(80), vec!["hello  trun…", "world"]);
+
+        assert_eq!(truncate("short", 5), "short");
+        assert_eq!(truncate("first\nsecond", 10), "first");
+    }
+}
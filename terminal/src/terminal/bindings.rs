@@ -93,6 +93,12 @@ extern "C" {
     #[wasm_bindgen(method, js_name = "onKey")]
     pub fn on_key(this: &XtermImpl, f: &js_sys::Function);
 
+    #[wasm_bindgen(method, js_name = "onSelectionChange")]
+    pub fn on_selection_change(this: &XtermImpl, f: &js_sys::Function);
+
+    #[wasm_bindgen(method, js_name = "onResize")]
+    pub fn on_resize(this: &XtermImpl, f: &js_sys::Function);
+
     #[wasm_bindgen(method, js_name = "write")]
     fn _write(this: &XtermImpl, text: String);
 
@@ -108,6 +114,15 @@ extern "C" {
     #[wasm_bindgen(method, js_name = "getSelection")]
     pub fn get_selection(this: &XtermImpl) -> String;
 
+    #[wasm_bindgen(method, js_name = "select")]
+    pub fn select(this: &XtermImpl, column: u32, row: u32, length: u32);
+
+    #[wasm_bindgen(method, js_name = "scrollToLine")]
+    pub fn scroll_to_line(this: &XtermImpl, line: u32);
+
+    #[wasm_bindgen(method, getter, js_name = "buffer")]
+    pub fn buffer(this: &XtermImpl) -> XtermBufferNamespace;
+
     #[wasm_bindgen(method, js_name = "registerLinkMatcher")]
     pub fn register_link_matcher(
         this: &XtermImpl,
@@ -125,6 +140,36 @@ extern "C" {
 
 }
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = js_sys::Object)]
+    pub type XtermBufferNamespace;
+
+    #[wasm_bindgen(method, getter, js_name = "active")]
+    pub fn active(this: &XtermBufferNamespace) -> XtermBuffer;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    pub type XtermBuffer;
+
+    #[wasm_bindgen(method, getter, js_name = "length")]
+    pub fn length(this: &XtermBuffer) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = "baseY")]
+    pub fn base_y(this: &XtermBuffer) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = "cursorY")]
+    pub fn cursor_y(this: &XtermBuffer) -> u32;
+
+    #[wasm_bindgen(method, js_name = "getLine")]
+    pub fn get_line(this: &XtermBuffer, y: u32) -> Option<XtermBufferLine>;
+
+    #[wasm_bindgen(extends = js_sys::Object)]
+    pub type XtermBufferLine;
+
+    #[wasm_bindgen(method, js_name = "translateToString")]
+    pub fn translate_to_string(this: &XtermBufferLine, trim_right: bool) -> String;
+}
+
 impl Debug for XtermImpl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         write!(f, "Workflow Xterm")?;
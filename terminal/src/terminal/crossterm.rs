@@ -1,13 +1,15 @@
+use crate::clipboard::osc52;
 use crate::keys::Key;
 use crate::terminal::Options;
 use crate::terminal::Terminal;
+use crate::theme::Theme;
 use crate::Result;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 pub use crossterm::terminal::disable_raw_mode;
 use crossterm::{
-    event::{self, Event, KeyCode},
-    terminal,
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode},
+    execute, terminal,
 };
 use std::io::{stdout, Stdout, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -53,9 +55,11 @@ impl Crossterm {
 
     pub async fn run(&self) -> Result<()> {
         terminal::enable_raw_mode()?;
+        execute!(stdout(), EnableBracketedPaste).ok();
         self.flush();
         self.intake(&self.terminate).await?;
         self.flush();
+        execute!(stdout(), DisableBracketedPaste).ok();
         terminal::disable_raw_mode()?;
 
         Ok(())
@@ -85,6 +89,11 @@ impl Crossterm {
                         KeyCode::Up => Key::ArrowUp,
                         KeyCode::Down => Key::ArrowDown,
                         KeyCode::Backspace => Key::Backspace,
+                        KeyCode::Tab => Key::Tab,
+                        KeyCode::Home => Key::Home,
+                        KeyCode::End => Key::End,
+                        KeyCode::PageUp => Key::PageUp,
+                        KeyCode::PageDown => Key::PageDown,
                         _ => {
                             continue;
                         }
@@ -97,6 +106,11 @@ impl Crossterm {
                         break;
                     }
                 }
+            } else if let Event::Paste(text) = event {
+                self.terminal().paste(text)?;
+                self.flush();
+            } else if let Event::Resize(_, _) = event {
+                self.terminal().notify_resize();
             }
         }
 
@@ -111,6 +125,18 @@ impl Crossterm {
         self.flush();
     }
 
+    /// Copy text to the clipboard using the OSC 52 sequence
+    pub fn copy(&self, text: &str) -> Result<()> {
+        self.write(osc52(text));
+        Ok(())
+    }
+
+    /// Apply theme colors using OSC sequences (if supported by the host terminal)
+    pub fn set_theme(&self, theme: &Theme) -> Result<()> {
+        self.write(theme.osc_sequences());
+        Ok(())
+    }
+
     pub fn flush(&self) {
         // stdout
         if let Some(stdout) = self.stdout.lock().unwrap().as_mut() {
@@ -149,11 +175,11 @@ impl Crossterm {
     }
 
     pub fn cols(&self) -> Option<usize> {
-        None
+        terminal::size().ok().map(|(cols, _)| cols as usize)
     }
 
     pub fn rows(&self) -> Option<usize> {
-        None
+        terminal::size().ok().map(|(_, rows)| rows as usize)
     }
 
     pub fn increase_font_size(&self) -> Result<Option<f64>> {
@@ -4,10 +4,19 @@
 
 use crate::clear::*;
 use crate::cli::Cli;
+use crate::clipboard::{sanitize, PasteFilterFn};
+use crate::completion::{Completer, Completion};
 use crate::cursor::*;
 use crate::error::Error;
+use crate::history::HistoryOptions;
+use crate::jobs::{Job, JobId, JobOutput, Jobs};
+use crate::keymap::{Action, Binding, EditMode, KeyMap};
 use crate::keys::Key;
+use crate::pager::{ActivePager, Pager, ENTER_ALTERNATE_SCREEN, LEAVE_ALTERNATE_SCREEN};
+use crate::progress::{Progress, ProgressKind, SharedProgressState};
 use crate::result::Result;
+use crate::search::{find_history, Search, SearchKind};
+use crate::table::Table;
 use crate::CrLf;
 use crate::UnicodeString;
 use cfg_if::cfg_if;
@@ -22,6 +31,7 @@ use workflow_log::log_error;
 
 const DEFAULT_PARA_WIDTH: usize = 80;
 
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Modifiers {
     pub alt: bool,
     pub shift: bool,
@@ -37,13 +47,79 @@ pub enum Event {
 }
 pub type EventHandlerFn = Arc<Box<(dyn Fn(Event))>>;
 
+/// Mouse button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    Other(i16),
+}
+
+impl From<i16> for MouseButton {
+    fn from(button: i16) -> Self {
+        match button {
+            0 => MouseButton::Left,
+            1 => MouseButton::Middle,
+            2 => MouseButton::Right,
+            _ => MouseButton::Other(button),
+        }
+    }
+}
+
+/// Mouse events (xterm.js only); `col` and `row` are
+/// zero-based coordinates of the terminal cell.
+#[derive(Debug, Clone)]
+pub enum MouseEvent {
+    Down {
+        button: MouseButton,
+        col: usize,
+        row: usize,
+        modifiers: Modifiers,
+    },
+    Up {
+        button: MouseButton,
+        col: usize,
+        row: usize,
+        modifiers: Modifiers,
+    },
+    Click {
+        button: MouseButton,
+        col: usize,
+        row: usize,
+        modifiers: Modifiers,
+    },
+    /// Wheel scroll by the number of lines (negative values scroll up)
+    Wheel { lines: isize, modifiers: Modifiers },
+    /// Text selection has changed
+    Selection(String),
+}
+pub type MouseHandlerFn = Arc<Box<dyn Fn(MouseEvent)>>;
+
+/// Terminal dimensions in character cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensions {
+    pub cols: usize,
+    pub rows: usize,
+}
+
+/// Function receiving the multi-line input entered so far
+/// and returning `true` if the input is complete.
+pub type InputCompleteFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Async function producing the prompt, evaluated before each line of input
+/// and on [`Terminal::refresh_prompt()`].
+pub type PromptProviderFn = Arc<dyn Fn() -> future::BoxFuture<'static, String> + Send + Sync>;
+
+const DEFAULT_CONTINUATION_PROMPT: &str = "> ";
+
 mod options;
 pub use options::Options;
 pub use options::TargetElement;
 
 pub mod bindings;
 pub mod xterm;
-pub use xterm::{Theme, ThemeOption};
+pub use crate::theme::{Theme, ThemeOption};
 
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
@@ -64,6 +140,8 @@ cfg_if! {
 #[derive(Debug)]
 pub struct Inner {
     pub buffer: UnicodeString,
+    /// Previously entered lines of a multi-line input block
+    pub lines: Vec<UnicodeString>,
     history: Vec<UnicodeString>,
     pub cursor: usize,
     history_index: usize,
@@ -79,6 +157,7 @@ impl Inner {
     pub fn new() -> Self {
         Inner {
             buffer: UnicodeString::default(),
+            lines: vec![],
             history: vec![],
             cursor: 0,
             history_index: 0,
@@ -87,8 +166,59 @@ impl Inner {
 
     pub fn reset_line_buffer(&mut self) {
         self.buffer.clear();
+        self.lines.clear();
         self.cursor = 0;
     }
+
+    /// Multi-line input block (previous lines and the line buffer)
+    fn block(&self) -> UnicodeString {
+        let mut block = UnicodeString::default();
+        for line in self.lines.iter() {
+            block.extend(line.clone());
+            block.push('\n');
+        }
+        block.extend(self.buffer.clone());
+        block
+    }
+
+    /// Split a multi-line input block into previous lines and the line buffer
+    fn set_block(&mut self, block: UnicodeString) {
+        let mut lines = block
+            .0
+            .split(|c| *c == '\n')
+            .map(|line| UnicodeString(line.to_vec()))
+            .collect::<Vec<_>>();
+        self.buffer = lines.pop().unwrap_or_default();
+        self.lines = lines;
+        self.cursor = self.buffer.len();
+    }
+
+    /// Append a command to history, returns `true` if the history has changed.
+    fn push_history(&mut self, cmd: UnicodeString, options: Option<&HistoryOptions>) -> bool {
+        // replace the trailing empty entry created by history navigation
+        if self
+            .history
+            .last()
+            .map(|last| last.is_empty())
+            .unwrap_or(false)
+        {
+            self.history.pop();
+        }
+        let accept = options
+            .map(|options| options.accepts(&cmd.to_string(), self.history.last()))
+            .unwrap_or(true);
+        if accept {
+            self.history.push(cmd);
+            if let Some(options) = options {
+                let len = self.history.len();
+                if len > options.max_entries {
+                    self.history.drain(0..len - options.max_entries);
+                }
+            }
+        }
+        self.history_index = self.history.len();
+        accept
+    }
 }
 
 #[derive(Clone)]
@@ -97,6 +227,7 @@ struct UserInput {
     buffer: Arc<Mutex<UnicodeString>>,
     enabled: Arc<AtomicBool>,
     secret: Arc<AtomicBool>,
+    mask: Arc<Mutex<Option<char>>>,
     kbhit: Arc<AtomicBool>,
     terminate: Arc<AtomicBool>,
     sender: Sender<String>,
@@ -111,6 +242,7 @@ impl UserInput {
             buffer: Arc::new(Mutex::new(UnicodeString::default())),
             enabled: Arc::new(AtomicBool::new(false)),
             secret: Arc::new(AtomicBool::new(false)),
+            mask: Arc::new(Mutex::new(None)),
             kbhit: Arc::new(AtomicBool::new(false)),
             terminate: Arc::new(AtomicBool::new(false)),
             sender,
@@ -126,10 +258,32 @@ impl UserInput {
         self.buffer.lock().unwrap().clone().to_string()
     }
 
-    pub fn open(&self, secret: bool, kbhit: bool, prompt: Option<String>) -> Result<()> {
+    /// Buffer contents as they should be displayed: empty
+    /// for secret input or masked if the mask is set.
+    pub fn get_display_buffer(&self) -> String {
+        if self.is_secret() {
+            match self.get_mask() {
+                Some(mask) => {
+                    std::iter::repeat_n(mask, self.buffer.lock().unwrap().len()).collect()
+                }
+                None => String::new(),
+            }
+        } else {
+            self.get_buffer()
+        }
+    }
+
+    pub fn open(
+        &self,
+        secret: bool,
+        mask: Option<char>,
+        kbhit: bool,
+        prompt: Option<String>,
+    ) -> Result<()> {
         *self.prompt.lock().unwrap() = prompt;
         self.enabled.store(true, Ordering::SeqCst);
         self.secret.store(secret, Ordering::SeqCst);
+        *self.mask.lock().unwrap() = mask;
         self.kbhit.store(kbhit, Ordering::SeqCst);
         self.terminate.store(false, Ordering::SeqCst);
         Ok(())
@@ -145,6 +299,8 @@ impl UserInput {
         };
 
         self.enabled.store(false, Ordering::SeqCst);
+        self.secret.store(false, Ordering::SeqCst);
+        self.mask.lock().unwrap().take();
         self.terminate.store(true, Ordering::SeqCst);
         self.sender.try_send(s.to_string()).unwrap();
         Ok(())
@@ -153,11 +309,12 @@ impl UserInput {
     pub async fn capture(
         &self,
         secret: bool,
+        mask: Option<char>,
         kbhit: bool,
         prompt: Option<String>,
         term: &Arc<Terminal>,
     ) -> Result<String> {
-        self.open(secret, kbhit, prompt)?;
+        self.open(secret, mask, kbhit, prompt)?;
 
         let term = term.clone();
         let terminate = self.terminate.clone();
@@ -190,6 +347,20 @@ impl UserInput {
         self.secret.load(Ordering::SeqCst)
     }
 
+    fn get_mask(&self) -> Option<char> {
+        *self.mask.lock().unwrap()
+    }
+
+    /// Echo supplied characters unless the input is secret
+    /// (in which case the mask character is echoed, if set).
+    fn echo(&self, ch: &char, term: &Terminal) {
+        if !self.is_secret() {
+            term.write(ch);
+        } else if let Some(mask) = self.get_mask() {
+            term.write(mask);
+        }
+    }
+
     fn is_kbhit(&self) -> bool {
         self.kbhit.load(Ordering::SeqCst)
     }
@@ -202,17 +373,15 @@ impl UserInput {
             }
             Key::Char(ch) => {
                 self.buffer.lock().unwrap().push(ch);
-                if !self.is_secret() {
-                    term.write(ch);
-                }
+                self.echo(&ch, term);
                 if self.is_kbhit() {
                     term.crlf();
                     self.close()?;
                 }
             }
             Key::Backspace => {
-                self.buffer.lock().unwrap().pop();
-                if !self.is_secret() {
+                let removed = self.buffer.lock().unwrap().pop().is_some();
+                if !self.is_secret() || (removed && self.get_mask().is_some()) {
                     term.write("\x08 \x08");
                 }
             }
@@ -234,11 +403,7 @@ impl UserInput {
 
     fn inject_impl(&self, text: UnicodeString, term: &Terminal) -> Result<()> {
         let mut buffer = self.buffer.lock().unwrap();
-        if !self.is_secret() {
-            text.iter().for_each(|ch| {
-                term.write(ch);
-            });
-        }
+        text.iter().for_each(|ch| self.echo(ch, term));
         buffer.extend(text);
         Ok(())
     }
@@ -258,6 +423,21 @@ pub struct Terminal {
     pub pipe_crlf: Channel<String>,
     pub pipe_ctl: DuplexChannel<()>,
     pub para_width: Arc<AtomicUsize>,
+    history_options: Arc<Mutex<Option<HistoryOptions>>>,
+    completer: Arc<Mutex<Option<Arc<dyn Completer>>>>,
+    progress: Arc<Mutex<Option<SharedProgressState>>>,
+    continuation_prompt: Arc<Mutex<String>>,
+    input_complete: Arc<Mutex<Option<InputCompleteFn>>>,
+    pager: Arc<Mutex<Option<ActivePager>>>,
+    keymap: Arc<Mutex<KeyMap>>,
+    edit_mode: Arc<Mutex<EditMode>>,
+    theme: Arc<Mutex<Option<Theme>>>,
+    jobs: Arc<Jobs>,
+    paste_filter: Arc<Mutex<Option<PasteFilterFn>>>,
+    search: Arc<Mutex<Option<Search>>>,
+    prompt_provider: Arc<Mutex<Option<PromptProviderFn>>>,
+    dynamic_prompt: Arc<Mutex<Option<String>>>,
+    resize_listeners: Arc<Mutex<Vec<Sender<Dimensions>>>>,
 }
 
 impl Terminal {
@@ -277,6 +457,21 @@ impl Terminal {
             pipe_crlf: Channel::unbounded(),
             pipe_ctl: DuplexChannel::oneshot(),
             para_width: Arc::new(AtomicUsize::new(DEFAULT_PARA_WIDTH)),
+            history_options: Arc::new(Mutex::new(None)),
+            completer: Arc::new(Mutex::new(None)),
+            progress: Arc::new(Mutex::new(None)),
+            continuation_prompt: Arc::new(Mutex::new(DEFAULT_CONTINUATION_PROMPT.to_string())),
+            input_complete: Arc::new(Mutex::new(None)),
+            pager: Arc::new(Mutex::new(None)),
+            keymap: Arc::new(Mutex::new(KeyMap::default())),
+            edit_mode: Arc::new(Mutex::new(EditMode::Insert)),
+            theme: Arc::new(Mutex::new(None)),
+            jobs: Arc::new(Jobs::default()),
+            paste_filter: Arc::new(Mutex::new(None)),
+            search: Arc::new(Mutex::new(None)),
+            prompt_provider: Arc::new(Mutex::new(None)),
+            dynamic_prompt: Arc::new(Mutex::new(None)),
+            resize_listeners: Arc::new(Mutex::new(Vec::new())),
         };
 
         Ok(terminal)
@@ -303,6 +498,21 @@ impl Terminal {
             pipe_crlf: Channel::unbounded(),
             pipe_ctl: DuplexChannel::oneshot(),
             para_width: Arc::new(AtomicUsize::new(DEFAULT_PARA_WIDTH)),
+            history_options: Arc::new(Mutex::new(options.history.clone())),
+            completer: Arc::new(Mutex::new(None)),
+            progress: Arc::new(Mutex::new(None)),
+            continuation_prompt: Arc::new(Mutex::new(options.continuation_prompt())),
+            input_complete: Arc::new(Mutex::new(None)),
+            pager: Arc::new(Mutex::new(None)),
+            keymap: Arc::new(Mutex::new(options.keymap.clone().unwrap_or_default())),
+            edit_mode: Arc::new(Mutex::new(EditMode::Insert)),
+            theme: Arc::new(Mutex::new(options.theme.clone())),
+            jobs: Arc::new(Jobs::default()),
+            paste_filter: Arc::new(Mutex::new(None)),
+            search: Arc::new(Mutex::new(None)),
+            prompt_provider: Arc::new(Mutex::new(None)),
+            dynamic_prompt: Arc::new(Mutex::new(None)),
+            resize_listeners: Arc::new(Mutex::new(Vec::new())),
         };
 
         Ok(terminal)
@@ -312,8 +522,17 @@ impl Terminal {
     pub async fn init(self: &Arc<Self>) -> Result<()> {
         self.term.init(self).await?;
 
+        let theme = self.theme.lock().unwrap().clone();
+        if let Some(theme) = theme {
+            self.term.set_theme(&theme)?;
+        }
+
+        self.load_history().await?;
+
         self.handler.clone().init(self)?;
 
+        self.update_prompt().await;
+
         Ok(())
     }
 
@@ -332,21 +551,138 @@ impl Terminal {
         self.inner().unwrap().reset_line_buffer();
     }
 
+    /// Enable persistent command history, loading existing history entries.
+    pub async fn set_history_options(&self, options: HistoryOptions) -> Result<()> {
+        self.history_options.lock().unwrap().replace(options);
+        self.load_history().await
+    }
+
+    fn history_options(&self) -> Option<HistoryOptions> {
+        self.history_options.lock().unwrap().clone()
+    }
+
+    async fn load_history(&self) -> Result<()> {
+        if let Some(options) = self.history_options() {
+            let history = options.load().await?;
+            let mut data = self.inner()?;
+            data.history_index = history.len();
+            data.history = history;
+        }
+        Ok(())
+    }
+
+    async fn store_history(&self) -> Result<()> {
+        if let Some(options) = self.history_options() {
+            let history = self.history();
+            options.store(&history).await?;
+        }
+        Ok(())
+    }
+
     /// Get the current terminal prompt string
     pub fn get_prompt(&self) -> String {
-        if let Some(prompt) = self.handler.prompt() {
+        if let Some(prompt) = self.dynamic_prompt.lock().unwrap().clone() {
+            prompt
+        } else if let Some(prompt) = self.handler.prompt() {
             prompt
         } else {
             self.prompt.lock().unwrap().clone()
         }
     }
 
+    /// Current prompt rendered using the [`Theme`] prompt color
+    fn styled_prompt(&self) -> String {
+        self.theme().style_prompt(&self.get_prompt())
+    }
+
+    /// Register an async function producing the prompt (for example, displaying
+    /// the connection status). The function is evaluated before each line of input;
+    /// call [`Terminal::refresh_prompt()`] to re-evaluate it while the terminal is idle.
+    ///
+    /// ```ignore
+    /// let rpc = rpc.clone();
+    /// term.register_prompt_provider(move || {
+    ///     let rpc = rpc.clone();
+    ///     Box::pin(async move {
+    ///         match rpc.get_block_height().await {
+    ///             Ok(height) => format!("[{height}] $ "),
+    ///             Err(_) => "[offline] $ ".to_string(),
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    pub fn register_prompt_provider<F>(&self, provider: F)
+    where
+        F: Fn() -> future::BoxFuture<'static, String> + Send + Sync + 'static,
+    {
+        self.prompt_provider
+            .lock()
+            .unwrap()
+            .replace(Arc::new(provider));
+    }
+
+    /// Remove the registered prompt provider
+    pub fn unregister_prompt_provider(&self) {
+        self.prompt_provider.lock().unwrap().take();
+        self.dynamic_prompt.lock().unwrap().take();
+    }
+
+    /// Evaluate the prompt provider (if registered), updating the prompt
+    pub async fn update_prompt(&self) {
+        let provider = self.prompt_provider.lock().unwrap().clone();
+        if let Some(provider) = provider {
+            let prompt = provider().await;
+            self.dynamic_prompt.lock().unwrap().replace(prompt);
+        }
+    }
+
+    /// Get the prompt displayed on continuation lines of multi-line input
+    pub fn get_continuation_prompt(&self) -> String {
+        self.continuation_prompt.lock().unwrap().clone()
+    }
+
+    /// Set the prompt displayed on continuation lines of multi-line input
+    pub fn set_continuation_prompt(&self, prompt: &str) {
+        *self.continuation_prompt.lock().unwrap() = prompt.to_string();
+    }
+
+    /// Register a function deciding if the input is complete. While the function
+    /// returns `false`, `Enter` starts a new continuation line instead of submitting
+    /// the input. A line ending with `\` is always continued.
+    pub fn register_input_complete_handler<F>(&self, handler: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.input_complete
+            .lock()
+            .unwrap()
+            .replace(Arc::new(handler));
+    }
+
+    /// Remove the registered input completeness handler
+    pub fn unregister_input_complete_handler(&self) {
+        self.input_complete.lock().unwrap().take();
+    }
+
+    fn is_input_complete(&self, input: &str) -> bool {
+        let handler = self.input_complete.lock().unwrap().clone();
+        handler.map(|handler| handler(input)).unwrap_or(true)
+    }
+
+    /// Prompt for the current line of (possibly multi-line) input
+    fn line_prompt(&self, data: &Inner) -> String {
+        if data.lines.is_empty() {
+            self.styled_prompt()
+        } else {
+            self.get_continuation_prompt()
+        }
+    }
+
     /// Render the current prompt in the terminal
     pub fn prompt(&self) {
         let mut data = self.inner().unwrap();
-        data.cursor = 0;
-        data.buffer.clear();
-        self.term().write(self.get_prompt());
+        data.reset_line_buffer();
+        self.term().write(self.styled_prompt());
     }
 
     /// Output CRLF sequence
@@ -372,17 +708,18 @@ impl Terminal {
                 if let Some(prompt) = self.user_input.get_prompt() {
                     self.write(format!("{}{}\n\r", ClearLine, s.to_string()));
                     self.write(prompt);
-                    if !self.user_input.secret.load(Ordering::SeqCst) {
-                        self.write(self.user_input.get_buffer());
-                    }
+                    self.write(self.user_input.get_display_buffer());
                 }
+            } else if let Some(progress) = self.progress_line() {
+                // write above the active progress widget and redraw it
+                self.write(format!("{}{}\n\r{}", ClearLine, s.to_string(), progress));
             } else {
                 self.write(format!("{}\n\r", s.to_string()));
             }
         } else {
             self.write(format!("{}{}\n\r", ClearLine, s.to_string()));
             let data = self.inner().unwrap();
-            let p = format!("{}{}", self.get_prompt(), data.buffer);
+            let p = format!("{}{}", self.line_prompt(&data), data.buffer);
             self.write(p);
             let l = data.buffer.len() - data.cursor;
             for _ in 0..l {
@@ -393,12 +730,36 @@ impl Terminal {
 
     /// Refreshes the prompt and the user input buffer. This function
     /// is useful when the prompt is handled externally and contains
-    /// data that should be updated.
+    /// data that should be updated. If a prompt provider is registered
+    /// (see [`Terminal::register_prompt_provider()`]), it is re-evaluated
+    /// before the prompt is redrawn.
     pub fn refresh_prompt(&self) {
+        if self.prompt_provider.lock().unwrap().is_none() {
+            self.redraw_prompt();
+            return;
+        }
+
+        let term = self.clone();
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                workflow_core::task::dispatch(async move {
+                    term.update_prompt().await;
+                    term.redraw_prompt();
+                });
+            } else {
+                workflow_core::task::spawn(async move {
+                    term.update_prompt().await;
+                    term.redraw_prompt();
+                });
+            }
+        }
+    }
+
+    fn redraw_prompt(&self) {
         if !self.is_running() {
             self.write(format!("{}", ClearLine));
             let data = self.inner().unwrap();
-            let p = format!("{}{}", self.get_prompt(), data.buffer);
+            let p = format!("{}{}", self.line_prompt(&data), data.buffer);
             self.write(p);
             let l = data.buffer.len() - data.cursor;
             for _ in 0..l {
@@ -476,6 +837,18 @@ impl Terminal {
         Ok(())
     }
 
+    /// Render a [`Table`] fitting the current terminal width
+    pub fn table(&self, table: &Table) {
+        let width = self
+            .term()
+            .cols()
+            .unwrap_or_else(|| self.para_width.load(Ordering::SeqCst));
+        table
+            .render(width)
+            .into_iter()
+            .for_each(|line| self.writeln(line));
+    }
+
     /// Get a clone of Arc of the underlying terminal instance
     pub fn term(&self) -> Arc<Interface> {
         Arc::clone(&self.term)
@@ -550,7 +923,23 @@ impl Terminal {
         self.reset_line_buffer();
         self.term().write(prompt.to_string());
         self.user_input
-            .capture(secret, false, Some(prompt.to_string()), self)
+            .capture(secret, None, false, Some(prompt.to_string()), self)
+            .await
+    }
+
+    /// Ask for a secret (such as a password or a passphrase) without
+    /// echoing the user input. Secret input is never stored in history.
+    pub async fn ask_secret(self: &Arc<Terminal>, prompt: &str) -> Result<String> {
+        self.ask(true, prompt).await
+    }
+
+    /// Ask for a secret echoing the `mask` character (such as `*`)
+    /// for each character entered. Secret input is never stored in history.
+    pub async fn ask_masked(self: &Arc<Terminal>, prompt: &str, mask: char) -> Result<String> {
+        self.reset_line_buffer();
+        self.term().write(prompt.to_string());
+        self.user_input
+            .capture(true, Some(mask), false, Some(prompt.to_string()), self)
             .await
     }
 
@@ -560,7 +949,7 @@ impl Terminal {
             self.term().write(prompt.to_string());
         }
         self.user_input
-            .capture(true, true, prompt.map(String::from), self)
+            .capture(true, None, true, prompt.map(String::from), self)
             .await
     }
 
@@ -602,13 +991,45 @@ impl Terminal {
     }
 
     async fn ingest(self: &Arc<Terminal>, key: Key, _term_key: String) -> Result<()> {
+        if let Some(active) = self.pager.lock().unwrap().as_mut() {
+            if active.pager.ingest(key) {
+                self.write(active.pager.render());
+            } else {
+                active.terminate.store(true, Ordering::SeqCst);
+                active.closed.try_send(())?;
+            }
+            return Ok(());
+        }
+
+        let searching = self.search.lock().unwrap().is_some();
+        if searching {
+            return self.ingest_search(key).await;
+        }
+
         if self.user_input.is_enabled() {
             self.user_input.ingest(key, self)?;
             return Ok(());
         }
 
-        match key {
-            Key::Backspace => {
+        let mode = self.edit_mode();
+        let binding = self.keymap.lock().unwrap().get(mode, &key).cloned();
+        match binding {
+            Some(Binding::Action(action)) => self.perform(action).await?,
+            Some(Binding::Callback(callback)) => callback(self)?,
+            None => {
+                if let (Key::Char(ch), EditMode::Insert) = (key, mode) {
+                    self.inject_char(ch)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Perform a line editing action
+    pub async fn perform(self: &Arc<Terminal>, action: Action) -> Result<()> {
+        match action {
+            Action::DeleteBackward => {
                 let mut data = self.inner()?;
                 if data.cursor == 0 {
                     return Ok(());
@@ -619,12 +1040,13 @@ impl Terminal {
                 data.buffer.remove(idx);
                 self.trail(data.cursor, &data.buffer, true, true, 0);
             }
-            Key::ArrowUp => {
+            Action::HistoryPrevious => {
                 let mut data = self.inner()?;
                 if data.history_index == 0 {
                     return Ok(());
                 }
-                let current_buffer = data.buffer.clone();
+                let rows = data.lines.len();
+                let current_buffer = data.block();
                 let index = data.history_index;
                 //log_trace!("ArrowUp: index {}, data.history.len(): {}", index, data.history.len());
                 if data.history.len() <= index {
@@ -634,29 +1056,30 @@ impl Terminal {
                 }
                 data.history_index -= 1;
 
-                data.buffer = data.history[data.history_index].clone();
-                self.write(format!("{}{}{}", ClearLine, self.get_prompt(), data.buffer));
-                data.cursor = data.buffer.len();
+                let entry = data.history[data.history_index].clone();
+                data.set_block(entry);
+                self.redraw_block(&data, rows);
             }
-            Key::ArrowDown => {
+            Action::HistoryNext => {
                 let mut data = self.inner()?;
                 let len = data.history.len();
                 if data.history_index >= len {
                     return Ok(());
                 }
+                let rows = data.lines.len();
                 let index = data.history_index;
-                data.history[index] = data.buffer.clone();
+                data.history[index] = data.block();
                 data.history_index += 1;
                 if data.history_index == len {
-                    data.buffer.clear();
+                    data.reset_line_buffer();
                 } else {
-                    data.buffer = data.history[data.history_index].clone();
+                    let entry = data.history[data.history_index].clone();
+                    data.set_block(entry);
                 }
 
-                self.write(format!("{}{}{}", ClearLine, self.get_prompt(), data.buffer));
-                data.cursor = data.buffer.len();
+                self.redraw_block(&data, rows);
             }
-            Key::ArrowLeft => {
+            Action::MoveLeft => {
                 let mut data = self.inner()?;
                 if data.cursor == 0 {
                     return Ok(());
@@ -664,44 +1087,50 @@ impl Terminal {
                 data.cursor -= 1;
                 self.write(Left(1));
             }
-            Key::ArrowRight => {
+            Action::MoveRight => {
                 let mut data = self.inner()?;
                 if data.cursor < data.buffer.len() {
                     data.cursor += 1;
                     self.write(Right(1));
                 }
             }
-            Key::Enter => {
-                let cmd = {
+            Action::Submit => {
+                self.set_edit_mode(EditMode::Insert);
+                let (cmd, store) = {
                     let mut data = self.inner()?;
-                    let buffer = data.buffer.clone();
-                    let length = data.history.len();
+                    let mut line = data.buffer.clone();
 
                     data.buffer.clear();
                     data.cursor = 0;
 
+                    // a line ending with `\` is continued on the next line
+                    let continued = line.0.last() == Some(&'\\');
+                    if continued {
+                        line.pop();
+                    }
+                    data.lines.push(line);
+                    let buffer = data.block();
+                    if continued || !self.is_input_complete(&buffer.to_string()) {
+                        self.write(format!("\n\r{}", self.get_continuation_prompt()));
+                        return Ok(());
+                    }
+                    data.lines.clear();
+
                     if !buffer.is_empty() {
-                        let cmd = buffer.clone();
-
-                        if length == 0 || !data.history[length - 1].is_empty() {
-                            data.history_index = length;
-                        } else {
-                            data.history_index = length - 1;
-                        }
-                        let index = data.history_index;
-                        if length <= index {
-                            data.history.push(buffer);
-                        } else {
-                            data.history[index] = buffer;
-                        }
-                        data.history_index += 1;
-
-                        Some(cmd)
+                        let options = self.history_options();
+                        let changed = data.push_history(buffer.clone(), options.as_ref());
+                        (Some(buffer), changed && options.is_some())
                     } else {
-                        None
+                        (None, false)
                     }
                 };
 
+                if store {
+                    self.store_history()
+                        .await
+                        .unwrap_or_else(|err| log_error!("Error storing terminal history: {err}"));
+                }
+
                 self.crlf();
 
                 if let Some(cmd) = cmd {
@@ -709,13 +1138,14 @@ impl Terminal {
                     self.exec(cmd).await.ok();
                     self.running.store(false, Ordering::SeqCst);
                 } else {
+                    self.update_prompt().await;
                     self.prompt();
                 }
             }
-            Key::Alt(_c) => {
-                return Ok(());
+            Action::Complete => {
+                self.complete().await?;
             }
-            Key::Ctrl('c') => {
+            Action::Interrupt => {
                 cfg_if! {
                     if #[cfg(not(target_arch = "wasm32"))] {
                         self.exit().await;
@@ -723,20 +1153,453 @@ impl Terminal {
                 }
                 return Ok(());
             }
-            Key::Ctrl(_c) => {
-                return Ok(());
+            Action::ReverseSearch => {
+                self.start_search(SearchKind::History)?;
             }
-            Key::Char(ch) => {
-                self.inject_char(ch)?;
+            Action::SearchOutput => {
+                // the terminal output is accessible only in xterm.js
+                if cfg!(target_arch = "wasm32") {
+                    self.start_search(SearchKind::Output)?;
+                }
+            }
+            Action::DeleteForward => {
+                let mut data = self.inner()?;
+                if data.cursor < data.buffer.len() {
+                    let idx = data.cursor;
+                    data.buffer.remove(idx);
+                    self.trail(data.cursor, &data.buffer, true, true, 0);
+                }
+            }
+            Action::MoveHome => {
+                let mut data = self.inner()?;
+                self.move_cursor(&mut data, 0);
+            }
+            Action::MoveEnd => {
+                let mut data = self.inner()?;
+                let len = data.buffer.len();
+                self.move_cursor(&mut data, len);
+            }
+            Action::MoveWordLeft => {
+                let mut data = self.inner()?;
+                let cursor = word_start(&data.buffer, data.cursor);
+                self.move_cursor(&mut data, cursor);
+            }
+            Action::MoveWordRight => {
+                let mut data = self.inner()?;
+                let cursor = word_end(&data.buffer, data.cursor);
+                self.move_cursor(&mut data, cursor);
+            }
+            Action::KillToEnd => {
+                let mut data = self.inner()?;
+                let cursor = data.cursor;
+                data.buffer.0.truncate(cursor);
+                self.redraw_line(&data);
+            }
+            Action::KillToStart => {
+                let mut data = self.inner()?;
+                let cursor = data.cursor;
+                data.buffer.0.drain(0..cursor);
+                data.cursor = 0;
+                self.redraw_line(&data);
+            }
+            Action::KillWordBackward => {
+                let mut data = self.inner()?;
+                let start = word_start(&data.buffer, data.cursor);
+                let cursor = data.cursor;
+                data.buffer.0.drain(start..cursor);
+                data.cursor = start;
+                self.redraw_line(&data);
+            }
+            Action::ViInsert => {
+                self.set_edit_mode(EditMode::Insert);
+            }
+            Action::ViAppend => {
+                self.set_edit_mode(EditMode::Insert);
+                let mut data = self.inner()?;
+                let cursor = (data.cursor + 1).min(data.buffer.len());
+                self.move_cursor(&mut data, cursor);
+            }
+            Action::ViAppendEnd => {
+                self.set_edit_mode(EditMode::Insert);
+                let mut data = self.inner()?;
+                let len = data.buffer.len();
+                self.move_cursor(&mut data, len);
+            }
+            Action::ViInsertStart => {
+                self.set_edit_mode(EditMode::Insert);
+                let mut data = self.inner()?;
+                self.move_cursor(&mut data, 0);
+            }
+            Action::ViNormal => {
+                self.set_edit_mode(EditMode::Normal);
+                let mut data = self.inner()?;
+                if data.cursor > 0 {
+                    let cursor = data.cursor - 1;
+                    self.move_cursor(&mut data, cursor);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn start_search(&self, kind: SearchKind) -> Result<()> {
+        let search = Search::new(kind, self.inner()?.block());
+        self.write(search.render(None));
+        self.search.lock().unwrap().replace(search);
+        Ok(())
+    }
+
+    async fn ingest_search(self: &Arc<Terminal>, key: Key) -> Result<()> {
+        let Some(mut search) = self.search.lock().unwrap().take() else {
+            return Ok(());
+        };
+        match (key, search.kind) {
+            (Key::Char(ch), _) => {
+                search.query.push(ch);
+                self.update_search(&mut search, false);
+            }
+            (Key::Backspace, _) => {
+                search.query.pop();
+                self.update_search(&mut search, false);
+            }
+            (Key::Ctrl('r'), SearchKind::History)
+            | (Key::Ctrl('s') | Key::Enter, SearchKind::Output) => {
+                self.update_search(&mut search, true);
+            }
+            (Key::Esc | Key::Ctrl('g') | Key::Ctrl('c'), _) => {
+                return self.end_search(search, false);
+            }
+            (Key::Enter, SearchKind::History) => {
+                self.end_search(search, true)?;
+                return self.perform(Action::Submit).await;
             }
             _ => {
-                return Ok(());
+                return self.end_search(search, true);
+            }
+        }
+        self.search.lock().unwrap().replace(search);
+        Ok(())
+    }
+
+    /// Search for the query, continuing from the current match if `next` is `true`
+    fn update_search(&self, search: &mut Search, next: bool) {
+        if search.query.is_empty() {
+            search.position = None;
+            search.failed = false;
+        }
+        match search.kind {
+            SearchKind::History => {
+                let history = self.history();
+                let before = if next {
+                    search.position.unwrap_or(history.len())
+                } else {
+                    history.len()
+                };
+                match find_history(&history, &search.query, before) {
+                    Some(idx) => {
+                        search.position = Some(idx);
+                        search.failed = false;
+                    }
+                    None => search.failed = !search.query.is_empty(),
+                }
+                let matched = search.position.and_then(|idx| history.get(idx));
+                self.write(search.render(matched));
+            }
+            SearchKind::Output => {
+                let before = if next { search.position } else { None };
+                let found = self.find_in_output(&search.query, before);
+                match found {
+                    Some((line, _)) => {
+                        search.position = Some(line);
+                        search.failed = false;
+                    }
+                    None => search.failed = !search.query.is_empty(),
+                }
+                // select after writing the status line as the output scrolls the terminal
+                self.write(search.render(None));
+                if let Some((line, column)) = found {
+                    self.select_in_output(line, column, search.query.chars().count());
+                }
             }
         }
+    }
 
+    /// End the search, placing the matched history entry into the line
+    /// buffer if `accept` is `true` or restoring the original line buffer.
+    fn end_search(&self, search: Search, accept: bool) -> Result<()> {
+        let mut data = self.inner()?;
+        let rows = data.lines.len();
+        let block = match (accept, search.kind, search.position) {
+            (true, SearchKind::History, Some(idx)) => {
+                data.history.get(idx).cloned().unwrap_or(search.original)
+            }
+            _ => search.original,
+        };
+        data.set_block(block);
+        self.redraw_block(&data, rows);
         Ok(())
     }
 
+    /// Find text in the terminal output (xterm.js only), returns
+    /// the buffer line and column of the match.
+    pub fn find_in_output(&self, _query: &str, _before: Option<usize>) -> Option<(usize, usize)> {
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                self.term.find_in_buffer(_query, _before)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Scroll to and select text in the terminal output (xterm.js only)
+    pub fn select_in_output(&self, _line: usize, _column: usize, _length: usize) {
+        #[cfg(target_arch = "wasm32")]
+        self.term.select_in_buffer(_line, _column, _length);
+    }
+
+    /// Replace the key binding map
+    pub fn set_keymap(&self, keymap: KeyMap) {
+        *self.keymap.lock().unwrap() = keymap;
+        self.set_edit_mode(EditMode::Insert);
+    }
+
+    /// Get a copy of the key binding map
+    pub fn keymap(&self) -> KeyMap {
+        self.keymap.lock().unwrap().clone()
+    }
+
+    /// Bind a key to a line editing action (in the insert mode)
+    pub fn bind(&self, key: Key, action: Action) {
+        self.keymap.lock().unwrap().bind(key, action);
+    }
+
+    /// Bind a key to a user callback (in the insert mode)
+    pub fn bind_callback<F>(&self, key: Key, callback: F)
+    where
+        F: Fn(&Arc<Terminal>) -> Result<()> + Send + Sync + 'static,
+    {
+        self.keymap.lock().unwrap().bind_callback(key, callback);
+    }
+
+    /// Remove a key binding (in the insert mode)
+    pub fn unbind(&self, key: Key) {
+        self.keymap.lock().unwrap().unbind(key);
+    }
+
+    /// Get the current line editing mode
+    pub fn edit_mode(&self) -> EditMode {
+        *self.edit_mode.lock().unwrap()
+    }
+
+    fn set_edit_mode(&self, mode: EditMode) {
+        *self.edit_mode.lock().unwrap() = mode;
+    }
+
+    fn move_cursor(&self, data: &mut Inner, cursor: usize) {
+        if cursor < data.cursor {
+            self.write(Left((data.cursor - cursor) as u16));
+        } else if cursor > data.cursor {
+            self.write(Right((cursor - data.cursor) as u16));
+        }
+        data.cursor = cursor;
+    }
+
+    /// Redraw the prompt and the line buffer, positioning the cursor
+    fn redraw_line(&self, data: &Inner) {
+        self.write(format!(
+            "{}{}{}",
+            ClearLine,
+            self.line_prompt(data),
+            data.buffer
+        ));
+        for _ in 0..data.buffer.len() - data.cursor {
+            self.write("\x08");
+        }
+    }
+
+    /// Redraw multi-line input replacing `rows` previously displayed lines
+    fn redraw_block(&self, data: &Inner, rows: usize) {
+        let mut text = String::new();
+        if rows > 0 {
+            text.push_str(&Up(rows as u16).to_string());
+        }
+        // clear from the start of the line to the end of the screen
+        text.push_str("\r\x1B[J");
+        text.push_str(&self.styled_prompt());
+        for line in data.lines.iter() {
+            text.push_str(&format!("{}\n\r{}", line, self.get_continuation_prompt()));
+        }
+        text.push_str(&data.buffer.to_string());
+        self.write(text);
+        for _ in 0..data.buffer.len() - data.cursor {
+            self.write("\x08");
+        }
+    }
+
+    /// Create a progress bar widget. The widget should be used while a command
+    /// is executing and must be removed using [`Progress::finish()`].
+    pub fn progress(self: &Arc<Terminal>) -> Progress {
+        Progress::new(self, ProgressKind::Bar)
+    }
+
+    /// Create an animated spinner widget. The widget should be used while a command
+    /// is executing and must be removed using [`Progress::finish()`].
+    pub fn spinner(self: &Arc<Terminal>) -> Progress {
+        Progress::new(self, ProgressKind::Spinner)
+    }
+
+    pub(crate) fn set_progress(&self, progress: Option<SharedProgressState>) {
+        *self.progress.lock().unwrap() = progress;
+    }
+
+    fn progress_line(&self) -> Option<String> {
+        self.progress
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|state| state.lock().unwrap().render(self.cols()))
+    }
+
+    /// Display text in a pager (scrolling using arrow keys, `PageUp`/`PageDown`,
+    /// `Space`, `j`/`k`, searching using `/`, `n`/`N` and closed using `q`).
+    /// Text that fits into the terminal is written out directly.
+    pub async fn page<S: ToString>(self: &Arc<Terminal>, text: S) -> Result<()> {
+        let text = text.to_string();
+        let (Some(cols), Some(rows)) = (self.cols(), self.rows()) else {
+            text.lines().for_each(|line| self.writeln(line));
+            return Ok(());
+        };
+
+        let pager = Pager::new(&text, cols, rows);
+        if pager.fits() {
+            pager.lines().iter().for_each(|line| self.writeln(line));
+            return Ok(());
+        }
+
+        let closed = Channel::<()>::oneshot();
+        let terminate = Arc::new(AtomicBool::new(false));
+        self.write(format!("{}{}", ENTER_ALTERNATE_SCREEN, pager.render()));
+        self.pager.lock().unwrap().replace(ActivePager {
+            pager,
+            terminate: terminate.clone(),
+            closed: closed.sender.clone(),
+        });
+
+        // the main intake loop is blocked while a command is executing,
+        // so the pager receives keys from its own intake (see UserInput::capture)
+        let term = self.clone();
+        cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                workflow_core::task::dispatch(async move {
+                    let _result = term.term().intake(&terminate).await;
+                });
+            } else {
+                workflow_core::task::spawn(async move {
+                    let _result = term.term().intake(&terminate).await;
+                });
+            }
+        }
+
+        let result = closed.receiver.recv().await;
+        self.pager.lock().unwrap().take();
+        self.write(LEAVE_ALTERNATE_SCREEN);
+        result?;
+        Ok(())
+    }
+
+    /// Launch a background job tracked by the terminal. The closure receives
+    /// a [`Job`] handle that should be used to produce job output.
+    /// Jobs can be managed using the `jobs`, `fg` and `kill` built-in commands
+    /// once enabled using [`Jobs::set_builtins()`].
+    pub fn spawn_job<F, Fut>(self: &Arc<Terminal>, name: &str, output: JobOutput, f: F) -> JobId
+    where
+        F: FnOnce(Job) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let id = self.jobs.spawn(self, name, output, f);
+        self.writeln(format!("[{id}] {name}"));
+        id
+    }
+
+    /// Background jobs tracked by the terminal
+    pub fn jobs(&self) -> &Arc<Jobs> {
+        &self.jobs
+    }
+
+    /// Register a tab-completion provider
+    pub fn register_completer(&self, completer: Arc<dyn Completer>) {
+        self.completer.lock().unwrap().replace(completer);
+    }
+
+    /// Remove the registered tab-completion provider
+    pub fn unregister_completer(&self) {
+        self.completer.lock().unwrap().take();
+    }
+
+    async fn complete(self: &Arc<Terminal>) -> Result<()> {
+        let Some(completer) = self.completer.lock().unwrap().clone() else {
+            return Ok(());
+        };
+
+        let (buffer, cursor) = {
+            let data = self.inner()?;
+            (data.buffer.to_string(), data.cursor)
+        };
+
+        let Some(completion) = completer.complete(self, &buffer, cursor).await? else {
+            return Ok(());
+        };
+        if completion.candidates.is_empty() || completion.start > cursor {
+            return Ok(());
+        }
+
+        let word_len = cursor - completion.start;
+        let replacement = if completion.candidates.len() == 1 {
+            format!("{} ", completion.candidates[0])
+        } else {
+            let prefix = completion.common_prefix();
+            if prefix.chars().count() > word_len {
+                prefix
+            } else {
+                self.list_completions(&completion);
+                return Ok(());
+            }
+        };
+
+        let mut data = self.inner()?;
+        // the buffer may have changed while the completer was running
+        if data.buffer.to_string() != buffer || data.cursor != cursor {
+            return Ok(());
+        }
+        data.buffer.0.drain(completion.start..cursor);
+        let replacement = UnicodeString::from(replacement);
+        let len = replacement.len();
+        data.buffer.insert(completion.start, replacement);
+        data.cursor = completion.start + len;
+        self.redraw_line(&data);
+        Ok(())
+    }
+
+    fn list_completions(&self, completion: &Completion) {
+        let width = completion
+            .candidates
+            .iter()
+            .map(|candidate| candidate.chars().count())
+            .max()
+            .unwrap_or_default()
+            + 2;
+        let columns = (self.cols().unwrap_or(DEFAULT_PARA_WIDTH) / width).max(1);
+        completion.candidates.chunks(columns).for_each(|row| {
+            let line = row
+                .iter()
+                .map(|candidate| candidate.pad_to_width(width))
+                .collect::<String>();
+            self.writeln(line.trim_end());
+        });
+    }
+
     fn trail(
         &self,
         cursor: usize,
@@ -772,34 +1635,99 @@ impl Terminal {
     }
 
     pub async fn exec<S: ToString>(self: &Arc<Terminal>, cmd: S) -> Result<()> {
-        if let Err(err) = self
-            .handler
-            .clone()
-            .digest(self.clone(), cmd.to_string())
-            .await
-        {
+        let cmd = cmd.to_string();
+        let result = match self.jobs.builtin(self, &cmd).await {
+            Ok(true) => Ok(()),
+            Ok(false) => self.handler.clone().digest(self.clone(), cmd).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
             self.writeln(err);
         }
         if self.terminate.load(Ordering::SeqCst) {
             self.term().exit();
         } else {
+            self.update_prompt().await;
             self.prompt();
         }
         Ok(())
     }
 
-    pub fn set_theme(&self, _theme: Theme) -> Result<()> {
-        #[cfg(target_arch = "wasm32")]
-        self.term.set_theme(_theme)?;
+    /// Apply a theme, replacing the current theme
+    pub fn set_theme(&self, theme: Theme) -> Result<()> {
+        self.term.set_theme(&theme)?;
+        self.theme.lock().unwrap().replace(theme);
         Ok(())
     }
 
+    /// Get the current theme
+    pub fn theme(&self) -> Theme {
+        self.theme.lock().unwrap().clone().unwrap_or_default()
+    }
+
     pub fn update_theme(&self) -> Result<()> {
         #[cfg(target_arch = "wasm32")]
         self.term.update_theme()?;
         Ok(())
     }
 
+    /// Copy text to the system clipboard (OSC 52 when running natively,
+    /// Clipboard API or NWJS clipboard in the browser)
+    pub fn copy<S: ToString>(&self, text: S) -> Result<()> {
+        self.term.copy(&text.to_string())
+    }
+
+    /// Insert pasted text into the line buffer, applying the paste filter
+    pub fn paste<S: ToString>(&self, text: S) -> Result<()> {
+        let text = text.to_string();
+        let filter = self.paste_filter.lock().unwrap().clone();
+        let text = match filter {
+            Some(filter) => filter(&text),
+            None => Some(sanitize(&text)),
+        };
+        match text {
+            Some(text) if self.user_input.is_enabled() => self.inject(text.replace('\n', " ")),
+            Some(text) if text.contains('\n') => self.inject_lines(&text),
+            Some(text) if !text.is_empty() => self.inject(text),
+            _ => Ok(()),
+        }
+    }
+
+    /// Insert multi-line text at the cursor position, continuing the
+    /// input on new lines (the lines are not submitted)
+    fn inject_lines(&self, text: &str) -> Result<()> {
+        let mut data = self.inner()?;
+        let rows = data.lines.len();
+        let cursor = data.cursor;
+        let tail = UnicodeString(data.buffer.0.split_off(cursor));
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            data.buffer.extend(first.into());
+        }
+        for line in lines {
+            let line = std::mem::replace(&mut data.buffer, line.into());
+            data.lines.push(line);
+        }
+        data.cursor = data.buffer.len();
+        data.buffer.extend(tail);
+        self.redraw_block(&data, rows);
+        Ok(())
+    }
+
+    /// Register a filter applied to pasted text, replacing
+    /// the default [`sanitize()`](crate::clipboard::sanitize) filter
+    pub fn register_paste_filter<F>(&self, filter: F)
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.paste_filter.lock().unwrap().replace(Arc::new(filter));
+    }
+
+    /// Restore the default paste filter
+    pub fn unregister_paste_filter(&self) {
+        self.paste_filter.lock().unwrap().take();
+    }
+
     pub fn clipboard_copy(&self) -> Result<()> {
         #[cfg(target_arch = "wasm32")]
         self.term.clipboard_copy()?;
@@ -832,6 +1760,46 @@ impl Terminal {
         self.term.cols()
     }
 
+    pub fn rows(&self) -> Option<usize> {
+        self.term.rows()
+    }
+
+    /// Current terminal dimensions (`None` if not available)
+    pub fn dimensions(&self) -> Option<Dimensions> {
+        Some(Dimensions {
+            cols: self.cols()?,
+            rows: self.rows()?,
+        })
+    }
+
+    /// Subscribe to terminal resize notifications. The returned receiver
+    /// implements [`Stream`] and yields the new terminal dimensions each
+    /// time the terminal window (or the xterm.js container) is resized.
+    /// Dropping the receiver unsubscribes.
+    ///
+    /// ```ignore
+    /// let mut resize = term.resize_events();
+    /// while let Some(Dimensions { cols, .. }) = resize.next().await {
+    ///     term.set_para_width(cols);
+    /// }
+    /// ```
+    pub fn resize_events(&self) -> Receiver<Dimensions> {
+        let (sender, receiver) = unbounded();
+        self.resize_listeners.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Dispatch a resize notification to subscribers (invoked by terminal backends)
+    #[allow(dead_code)]
+    pub(crate) fn notify_resize(&self) {
+        if let Some(dimensions) = self.dimensions() {
+            self.resize_listeners
+                .lock()
+                .unwrap()
+                .retain(|sender| sender.try_send(dimensions).is_ok());
+        }
+    }
+
     pub async fn select<T>(self: &Arc<Terminal>, prompt: &str, list: &[T]) -> Result<Option<T>>
     where
         T: std::fmt::Display + Clone, // + IdT + Clone + Send + Sync + 'static,
@@ -876,6 +1844,27 @@ impl Terminal {
         Ok(())
     }
 
+    /// Register a mouse event handler (xterm.js only)
+    pub fn register_mouse_handler(self: &Arc<Self>, _handler: MouseHandlerFn) -> Result<()> {
+        #[cfg(target_arch = "wasm32")]
+        self.term.register_mouse_handler(_handler)?;
+        Ok(())
+    }
+
+    /// Process a mouse event, returns `true` if the event has been
+    /// consumed by the terminal (wheel scrolling of an active pager).
+    #[allow(dead_code)]
+    pub(crate) fn ingest_mouse(&self, event: &MouseEvent) -> bool {
+        if let MouseEvent::Wheel { lines, .. } = event {
+            if let Some(active) = self.pager.lock().unwrap().as_mut() {
+                active.pager.scroll(*lines);
+                self.write(active.pager.render());
+                return true;
+            }
+        }
+        false
+    }
+
     pub fn register_link_matcher(
         &self,
         _regexp: &js_sys::RegExp,
@@ -890,6 +1879,36 @@ impl Terminal {
     }
 }
 
+/// Character index of the beginning of the word preceding the cursor
+fn word_start(buffer: &UnicodeString, cursor: usize) -> usize {
+    let chars = &buffer.0[..cursor];
+    let end = chars
+        .iter()
+        .rposition(|c| !c.is_whitespace())
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    chars[..end]
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map(|idx| idx + 1)
+        .unwrap_or(0)
+}
+
+/// Character index past the end of the word following the cursor
+fn word_end(buffer: &UnicodeString, cursor: usize) -> usize {
+    let chars = &buffer.0;
+    let start = chars[cursor..]
+        .iter()
+        .position(|c| !c.is_whitespace())
+        .map(|idx| cursor + idx)
+        .unwrap_or(chars.len());
+    chars[start..]
+        .iter()
+        .position(|c| c.is_whitespace())
+        .map(|idx| start + idx)
+        .unwrap_or(chars.len())
+}
+
 /// Utility function to strip multiple white spaces and return a `Vec<String>`
 pub fn parse(s: &str) -> Vec<String> {
     let regex = Regex::new(r"\s+").unwrap();
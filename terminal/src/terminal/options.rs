@@ -2,6 +2,10 @@
 //! Terminal creation options
 //!
 
+use super::DEFAULT_CONTINUATION_PROMPT;
+use crate::history::HistoryOptions;
+use crate::keymap::KeyMap;
+use crate::theme::Theme;
 use web_sys::Element;
 
 /// Indicates the target element to which the Terminal instance should be
@@ -32,6 +36,14 @@ pub struct Options {
     pub font_size: Option<f64>,
     /// Default scrollback limit (xterm.js only)
     pub scrollback: Option<u32>,
+    /// Prompt displayed on continuation lines of multi-line input
+    pub continuation_prompt: Option<String>,
+    /// Persistent command history
+    pub history: Option<HistoryOptions>,
+    /// Key binding map (defaults to [`KeyMap::default()`])
+    pub keymap: Option<KeyMap>,
+    /// Color theme
+    pub theme: Option<Theme>,
 }
 
 impl Default for Options {
@@ -43,6 +55,10 @@ impl Default for Options {
             font_family: None,
             font_size: None,
             scrollback: Some(2048),
+            continuation_prompt: None,
+            history: None,
+            keymap: None,
+            theme: None,
         }
     }
 }
@@ -59,6 +75,12 @@ impl Options {
         self
     }
 
+    /// Set the prompt displayed on continuation lines of multi-line input
+    pub fn with_continuation_prompt(mut self, prompt: &str) -> Self {
+        self.continuation_prompt = Some(prompt.into());
+        self
+    }
+
     /// Set scrollback limit
     pub fn with_scrollback(mut self, scrollback: u32) -> Self {
         self.scrollback = Some(scrollback);
@@ -71,8 +93,33 @@ impl Options {
         self
     }
 
+    /// Enable persistent command history
+    pub fn with_history(mut self, history: HistoryOptions) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Set the key binding map (e.g. [`KeyMap::emacs()`] or [`KeyMap::vi()`])
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Set the color theme
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
     /// Get prompt string
     pub fn prompt(&self) -> String {
         self.prompt.as_ref().unwrap_or(&"$ ".to_string()).clone()
     }
+
+    /// Get continuation prompt string
+    pub fn continuation_prompt(&self) -> String {
+        self.continuation_prompt
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CONTINUATION_PROMPT.to_string())
+    }
 }
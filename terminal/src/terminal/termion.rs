@@ -1,6 +1,8 @@
+use crate::clipboard::osc52;
 use crate::keys::Key;
 use crate::terminal::Options;
 use crate::terminal::Terminal;
+use crate::theme::Theme;
 use crate::Result;
 use std::io::{stdin, stdout, Stdout, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -69,6 +71,8 @@ impl Termion {
                 K::Char(c) => {
                     if c == '\n' || c == '\r' {
                         Key::Enter
+                    } else if c == '\t' {
+                        Key::Tab
                     } else {
                         Key::Char(c)
                     }
@@ -81,6 +85,10 @@ impl Termion {
                 K::Up => Key::ArrowUp,
                 K::Down => Key::ArrowDown,
                 K::Backspace => Key::Backspace,
+                K::Home => Key::Home,
+                K::End => Key::End,
+                K::PageUp => Key::PageUp,
+                K::PageDown => Key::PageDown,
                 _ => {
                     continue;
                 }
@@ -105,9 +113,29 @@ impl Termion {
         self.flush();
     }
 
+    /// Copy text to the clipboard using the OSC 52 sequence
+    pub fn copy(&self, text: &str) -> Result<()> {
+        self.write(osc52(text));
+        Ok(())
+    }
+
+    /// Apply theme colors using OSC sequences (if supported by the host terminal)
+    pub fn set_theme(&self, theme: &Theme) -> Result<()> {
+        self.write(theme.osc_sequences());
+        Ok(())
+    }
+
     pub fn flush(&self) {
         if let Some(stdout) = self.stdout.lock().unwrap().as_mut() {
             stdout.flush().unwrap();
         }
     }
+
+    pub fn cols(&self) -> Option<usize> {
+        termion::terminal_size().ok().map(|(cols, _)| cols as usize)
+    }
+
+    pub fn rows(&self) -> Option<usize> {
+        termion::terminal_size().ok().map(|(_, rows)| rows as usize)
+    }
 }
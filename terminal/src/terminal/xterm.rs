@@ -1,11 +1,12 @@
 use super::bindings::*;
-use super::{LinkMatcherHandlerFn, Modifiers};
+use super::{LinkMatcherHandlerFn, Modifiers, MouseEvent, MouseHandlerFn};
 use crate::keys::Key;
 use crate::terminal::Event;
 use crate::terminal::EventHandlerFn;
 use crate::terminal::Options;
 use crate::terminal::TargetElement;
 use crate::terminal::Terminal;
+use crate::theme::{Theme, ThemeOption};
 use crate::Result;
 use std::cell::{RefCell, RefMut};
 use std::fmt::Debug;
@@ -17,83 +18,14 @@ use wasm_bindgen::JsValue;
 use web_sys::Element;
 use workflow_core::channel::{unbounded, Receiver, Sender};
 use workflow_core::runtime::{self, platform, Platform};
-use workflow_dom::clipboard;
+use workflow_dom::clipboard::clipboard;
 use workflow_dom::inject::*;
 use workflow_dom::utils::body;
 use workflow_dom::utils::*;
 use workflow_log::*;
-use workflow_wasm::jserror::*;
 use workflow_wasm::prelude::*;
 use workflow_wasm::utils::*;
 
-#[derive(Default)]
-pub struct Theme {
-    pub background: Option<String>,
-    pub foreground: Option<String>,
-    pub selection: Option<String>,
-    pub cursor: Option<String>,
-}
-
-pub enum ThemeOption {
-    Background,
-    Foreground,
-    Selection,
-    Cursor,
-}
-impl ThemeOption {
-    pub fn list() -> Vec<Self> {
-        Vec::from([
-            Self::Background,
-            Self::Foreground,
-            Self::Selection,
-            Self::Cursor,
-        ])
-    }
-}
-
-impl std::fmt::Display for ThemeOption {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Background => write!(f, "Background"),
-            Self::Foreground => write!(f, "Foreground"),
-            Self::Selection => write!(f, "Selection"),
-            Self::Cursor => write!(f, "Cursor"),
-        }
-    }
-}
-
-impl Theme {
-    pub fn new() -> Self {
-        Self {
-            ..Default::default()
-        }
-    }
-    pub fn get(&self, key: &ThemeOption) -> Option<String> {
-        match key {
-            ThemeOption::Background => self.background.clone(),
-            ThemeOption::Foreground => self.foreground.clone(),
-            ThemeOption::Selection => self.selection.clone(),
-            ThemeOption::Cursor => self.cursor.clone(),
-        }
-    }
-    pub fn set(&mut self, key: ThemeOption, value: Option<String>) {
-        match key {
-            ThemeOption::Background => {
-                self.background = value;
-            }
-            ThemeOption::Foreground => {
-                self.foreground = value;
-            }
-            ThemeOption::Selection => {
-                self.selection = value;
-            }
-            ThemeOption::Cursor => {
-                self.cursor = value;
-            }
-        }
-    }
-}
-
 enum Ctl {
     SinkEvent(SinkEvent),
     Copy(Option<String>),
@@ -176,6 +108,7 @@ pub struct Xterm {
     callbacks: CallbackMap,
     defaults: XtermOptions,
     event_handler: Rc<RefCell<Option<EventHandlerFn>>>,
+    mouse_handler: Rc<RefCell<Option<MouseHandlerFn>>>,
 }
 
 unsafe impl Send for Xterm {}
@@ -224,6 +157,7 @@ impl Xterm {
             disable_clipboard_handling: options.disable_clipboard_handling,
             callbacks: CallbackMap::default(),
             event_handler: Rc::new(RefCell::new(None)),
+            mouse_handler: Rc::new(RefCell::new(None)),
             defaults,
         };
         Ok(terminal)
@@ -316,9 +250,11 @@ impl Xterm {
 
         Ok(())
     }
-    pub fn set_theme(&self, theme: Theme) -> Result<()> {
+    pub fn set_theme(&self, theme: &Theme) -> Result<()> {
         let theme_obj = js_sys::Object::new();
-        let properties = ThemeOption::list();
+        let properties = ThemeOption::list()
+            .into_iter()
+            .filter(|option| option.is_xterm());
 
         for key in properties {
             if let Some(v) = theme.get(&key) {
@@ -354,7 +290,9 @@ impl Xterm {
         xterm.focus();
 
         self.init_kbd_listener(&xterm)?;
+        self.init_mouse_listeners(&xterm)?;
         self.init_resize_observer()?;
+        self.init_resize_listener(&xterm)?;
         if runtime::is_macos() && !self.disable_clipboard_handling {
             self.init_clipboard_listener_for_macos(&xterm)?;
         }
@@ -394,6 +332,146 @@ impl Xterm {
         Ok(())
     }
 
+    #[allow(dead_code)]
+    pub(super) fn register_mouse_handler(self: &Arc<Self>, handler: MouseHandlerFn) -> Result<()> {
+        self.mouse_handler.borrow_mut().replace(handler);
+        Ok(())
+    }
+
+    fn mouse_event(&self, event: MouseEvent) {
+        if self.terminal().ingest_mouse(&event) {
+            return;
+        }
+        let handler = self.mouse_handler.borrow().clone();
+        if let Some(handler) = handler {
+            handler(event);
+        }
+    }
+
+    /// Zero-based terminal cell (column, row) at the mouse event position
+    fn cell_at(&self, e: &web_sys::MouseEvent) -> (usize, usize) {
+        let xterm = self.xterm();
+        let Some(xterm) = xterm.as_ref() else {
+            return (0, 0);
+        };
+        let el = xterm.get_element();
+        let rect = el.get_bounding_client_rect();
+        let (cols, rows) = (xterm.cols().max(1) as f64, xterm.rows().max(1) as f64);
+        let x = (e.client_x() as f64 - rect.left()).max(0.0);
+        let y = (e.client_y() as f64 - rect.top()).max(0.0);
+        let col = (x / (rect.width() / cols)).floor().min(cols - 1.0);
+        let row = (y / (rect.height() / rows)).floor().min(rows - 1.0);
+        (col as usize, row as usize)
+    }
+
+    fn init_mouse_listeners(self: &Arc<Self>, xterm: &XtermImpl) -> Result<()> {
+        let el = xterm.get_element();
+
+        for event in ["mousedown", "mouseup", "click"] {
+            let this = self.clone();
+            let callback = callback!(
+                move |e: web_sys::MouseEvent| -> std::result::Result<(), JsValue> {
+                    let (col, row) = this.cell_at(&e);
+                    let button = e.button().into();
+                    let modifiers = Modifiers {
+                        shift: e.shift_key(),
+                        ctrl: e.ctrl_key(),
+                        alt: e.alt_key(),
+                        meta: e.meta_key(),
+                    };
+                    let event = match e.type_().as_str() {
+                        "mousedown" => MouseEvent::Down {
+                            button,
+                            col,
+                            row,
+                            modifiers,
+                        },
+                        "mouseup" => MouseEvent::Up {
+                            button,
+                            col,
+                            row,
+                            modifiers,
+                        },
+                        _ => MouseEvent::Click {
+                            button,
+                            col,
+                            row,
+                            modifiers,
+                        },
+                    };
+                    this.mouse_event(event);
+                    Ok(())
+                }
+            );
+            el.add_event_listener_with_callback(event, callback.as_ref())?;
+            self.callbacks.retain(callback)?;
+        }
+
+        let this = self.clone();
+        let callback = callback!(
+            move |e: web_sys::WheelEvent| -> std::result::Result<(), JsValue> {
+                let rows = this.rows().unwrap_or(1).max(1) as f64;
+                let lines = match e.delta_mode() {
+                    web_sys::WheelEvent::DOM_DELTA_PIXEL => {
+                        let height = this.element.client_height().max(1) as f64;
+                        e.delta_y() / (height / rows)
+                    }
+                    web_sys::WheelEvent::DOM_DELTA_LINE => e.delta_y(),
+                    _ => e.delta_y() * rows,
+                };
+                let lines = if lines < 0.0 {
+                    lines.floor()
+                } else {
+                    lines.ceil()
+                };
+                let modifiers = Modifiers {
+                    shift: e.shift_key(),
+                    ctrl: e.ctrl_key(),
+                    alt: e.alt_key(),
+                    meta: e.meta_key(),
+                };
+                this.mouse_event(MouseEvent::Wheel {
+                    lines: lines as isize,
+                    modifiers,
+                });
+                Ok(())
+            }
+        );
+        el.add_event_listener_with_callback("wheel", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+
+        let this = self.clone();
+        let callback = callback!(move || -> std::result::Result<(), JsValue> {
+            let selection = this
+                .xterm()
+                .as_ref()
+                .map(|xterm| xterm.get_selection())
+                .unwrap_or_default();
+            this.mouse_event(MouseEvent::Selection(selection));
+            Ok(())
+        });
+        xterm.on_selection_change(callback.as_ref());
+        self.callbacks.retain(callback)?;
+
+        Ok(())
+    }
+
+    fn init_resize_listener(self: &Arc<Self>, xterm: &XtermImpl) -> Result<()> {
+        let this = self.clone();
+        let callback = callback!(move |_: JsValue| -> std::result::Result<(), JsValue> {
+            // xterm may be resized by the fit addon before the terminal is bound
+            let terminal = this.terminal.lock().unwrap().clone();
+            if let Some(terminal) = terminal {
+                terminal.notify_resize();
+            }
+            Ok(())
+        });
+        xterm.on_resize(callback.as_ref());
+        self.callbacks.retain(callback)?;
+
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub(super) fn register_link_matcher(
         self: &Arc<Self>,
@@ -547,8 +625,8 @@ impl Xterm {
                     if runtime::is_nw() {
                         let clipboard = nw_sys::clipboard::get();
                         clipboard.set(&text);
-                    } else if let Err(err) = clipboard::write_text(&text).await {
-                        log_error!("{}", JsErrorData::from(err));
+                    } else if let Err(err) = clipboard().write_text(&text).await {
+                        log_error!("{err}");
                     }
 
                     if let Some(handler) = self.event_handler() {
@@ -557,17 +635,17 @@ impl Xterm {
                 }
                 Ctl::Paste(text) => {
                     if let Some(text) = text {
-                        self.terminal().inject(text)?;
+                        self.terminal().paste(text)?;
                     } else if runtime::is_nw() {
                         let clipboard = nw_sys::clipboard::get();
                         let text = clipboard.get();
                         if !text.is_empty() {
-                            self.terminal().inject(text)?;
+                            self.terminal().paste(text)?;
                         }
                     } else {
-                        let data_js_value = clipboard::read_text().await;
-                        if let Some(text) = data_js_value.as_string() {
-                            self.terminal().inject(text)?;
+                        match clipboard().read_text().await {
+                            Ok(text) => self.terminal().paste(text)?,
+                            Err(err) => log_error!("{err}"),
                         }
                     }
 
@@ -601,10 +679,11 @@ impl Xterm {
             "ArrowRight" => Key::ArrowRight,
             "Escape" => Key::Esc,
             "Delete" => Key::Delete,
-            "Tab" => {
-                // TODO implement completion handler
-                return Ok(());
-            }
+            "Tab" => Key::Tab,
+            "Home" => Key::Home,
+            "End" => Key::End,
+            "PageUp" => Key::PageUp,
+            "PageDown" => Key::PageDown,
             "Enter" => Key::Enter,
             _ => {
                 let printable = !e.meta_key; // ! (e.ctrl_key || e.alt_key || e.meta_key);
@@ -730,6 +809,44 @@ impl Xterm {
         Ok(())
     }
 
+    /// Find the most recent line of the scrollback buffer preceding `before`
+    /// (or the cursor line) containing `query`. Returns the buffer line index
+    /// and the column of the match.
+    pub fn find_in_buffer(&self, query: &str, before: Option<usize>) -> Option<(usize, usize)> {
+        if query.is_empty() {
+            return None;
+        }
+        let xterm = self.xterm();
+        let buffer = xterm.as_ref()?.buffer().active();
+        let cursor = buffer.base_y() + buffer.cursor_y();
+        let before = before
+            .map(|before| before as u32)
+            .unwrap_or(cursor)
+            .min(cursor);
+        (0..before).rev().find_map(|line| {
+            let text = buffer.get_line(line)?.translate_to_string(true);
+            let idx = text.find(query)?;
+            Some((line as usize, text[..idx].chars().count()))
+        })
+    }
+
+    /// Scroll to the buffer line and select `length` cells starting at `column`
+    pub fn select_in_buffer(&self, line: usize, column: usize, length: usize) {
+        if let Some(xterm) = self.xterm().as_ref() {
+            xterm.scroll_to_line(line as u32);
+            xterm.select(column as u32, line as u32, length as u32);
+        }
+    }
+
+    /// Copy text to the clipboard
+    pub fn copy(&self, text: &str) -> Result<()> {
+        self.sink
+            .sender
+            .try_send(Ctl::Copy(Some(text.to_string())))
+            .map_err(|_| "Unable to send copy Ctl")?;
+        Ok(())
+    }
+
     pub fn clipboard_paste(&self) -> Result<()> {
         self.sink
             .sender
@@ -0,0 +1,201 @@
+//!
+//! Terminal color themes
+//!
+//! A [`Theme`] is applied uniformly by all terminal backends: prompt,
+//! error and warning colors are rendered using ANSI escape sequences,
+//! while background, foreground, selection and cursor colors are set
+//! via the xterm.js theme API in the browser and via OSC escape sequences
+//! (if supported by the host terminal) when running natively.
+//! Themes can be switched at runtime using
+//! [`Terminal::set_theme()`](crate::Terminal::set_theme).
+//!
+//! Colors are specified as CSS color strings; ANSI rendering and OSC
+//! sequences require hex colors (`#rgb` or `#rrggbb`).
+//!
+
+use workflow_log::style;
+
+/// Terminal color theme
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub selection: Option<String>,
+    pub cursor: Option<String>,
+    pub prompt: Option<String>,
+    pub error: Option<String>,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeOption {
+    Background,
+    Foreground,
+    Selection,
+    Cursor,
+    Prompt,
+    Error,
+    Warning,
+}
+
+impl ThemeOption {
+    pub fn list() -> Vec<Self> {
+        Vec::from([
+            Self::Background,
+            Self::Foreground,
+            Self::Selection,
+            Self::Cursor,
+            Self::Prompt,
+            Self::Error,
+            Self::Warning,
+        ])
+    }
+
+    /// Returns `true` if the option is a part of the xterm.js theme
+    pub fn is_xterm(&self) -> bool {
+        matches!(
+            self,
+            Self::Background | Self::Foreground | Self::Selection | Self::Cursor
+        )
+    }
+}
+
+impl std::fmt::Display for ThemeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Background => write!(f, "Background"),
+            Self::Foreground => write!(f, "Foreground"),
+            Self::Selection => write!(f, "Selection"),
+            Self::Cursor => write!(f, "Cursor"),
+            Self::Prompt => write!(f, "Prompt"),
+            Self::Error => write!(f, "Error"),
+            Self::Warning => write!(f, "Warning"),
+        }
+    }
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Light theme (matches the default xterm.js terminal colors)
+    pub fn light() -> Self {
+        Self {
+            background: Some("#ffffff".to_string()),
+            foreground: Some("#000000".to_string()),
+            selection: Some("rgba(0,0,0,0.25)".to_string()),
+            cursor: Some("#000000".to_string()),
+            prompt: Some("#0b5394".to_string()),
+            error: Some("#c00000".to_string()),
+            warning: Some("#b45f06".to_string()),
+        }
+    }
+
+    /// Dark theme
+    pub fn dark() -> Self {
+        Self {
+            background: Some("#000000".to_string()),
+            foreground: Some("#ffffff".to_string()),
+            selection: Some("rgba(255,255,255,0.3)".to_string()),
+            cursor: Some("#ffffff".to_string()),
+            prompt: Some("#6fa8dc".to_string()),
+            error: Some("#ff6060".to_string()),
+            warning: Some("#ffd966".to_string()),
+        }
+    }
+
+    pub fn get(&self, key: &ThemeOption) -> Option<String> {
+        match key {
+            ThemeOption::Background => self.background.clone(),
+            ThemeOption::Foreground => self.foreground.clone(),
+            ThemeOption::Selection => self.selection.clone(),
+            ThemeOption::Cursor => self.cursor.clone(),
+            ThemeOption::Prompt => self.prompt.clone(),
+            ThemeOption::Error => self.error.clone(),
+            ThemeOption::Warning => self.warning.clone(),
+        }
+    }
+
+    pub fn set(&mut self, key: ThemeOption, value: Option<String>) {
+        match key {
+            ThemeOption::Background => {
+                self.background = value;
+            }
+            ThemeOption::Foreground => {
+                self.foreground = value;
+            }
+            ThemeOption::Selection => {
+                self.selection = value;
+            }
+            ThemeOption::Cursor => {
+                self.cursor = value;
+            }
+            ThemeOption::Prompt => {
+                self.prompt = value;
+            }
+            ThemeOption::Error => {
+                self.error = value;
+            }
+            ThemeOption::Warning => {
+                self.warning = value;
+            }
+        }
+    }
+
+    /// Render the prompt using the prompt color
+    pub fn style_prompt(&self, text: &str) -> String {
+        paint(self.prompt.as_deref(), text).unwrap_or_else(|| text.to_string())
+    }
+
+    /// Render text using the error color (red if not set)
+    pub fn style_error(&self, text: &str) -> String {
+        paint(self.error.as_deref(), text).unwrap_or_else(|| style(text).red().to_string())
+    }
+
+    /// Render text using the warning color (yellow if not set)
+    pub fn style_warning(&self, text: &str) -> String {
+        paint(self.warning.as_deref(), text).unwrap_or_else(|| style(text).yellow().to_string())
+    }
+
+    /// OSC escape sequences setting the terminal background,
+    /// foreground and cursor colors (used by native backends)
+    pub fn osc_sequences(&self) -> String {
+        [
+            (10, &self.foreground),
+            (11, &self.background),
+            (12, &self.cursor),
+        ]
+        .into_iter()
+        .filter_map(|(code, color)| {
+            let (r, g, b) = parse_hex_color(color.as_deref()?)?;
+            Some(format!("\x1B]{code};#{r:02x}{g:02x}{b:02x}\x07"))
+        })
+        .collect()
+    }
+}
+
+fn paint(color: Option<&str>, text: &str) -> Option<String> {
+    let (r, g, b) = parse_hex_color(color?)?;
+    Some(format!("\x1B[38;2;{r};{g};{b}m{text}\x1B[0m"))
+}
+
+/// Parse `#rgb` or `#rrggbb` color
+fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let mut chars = hex.chars().map(|c| channel(&c.to_string()).map(|v| v * 17));
+            Some((chars.next()??, chars.next()??, chars.next()??))
+        }
+        6 => Some((
+            channel(hex.get(0..2)?)?,
+            channel(hex.get(2..4)?)?,
+            channel(hex.get(4..6)?)?,
+        )),
+        _ => None,
+    }
+}
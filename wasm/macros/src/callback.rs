@@ -2,9 +2,22 @@ use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    ExprClosure, Ident, Result,
+    Expr, ExprClosure, Ident, Pat, Result, Stmt,
 };
 
+/// Detects closures of the form `|..| async move {..}` or
+/// `|..| { ..; async move {..} }` returning an async block.
+fn returns_async_block(body: &Expr) -> bool {
+    match body {
+        Expr::Async(_) => true,
+        Expr::Block(block) => matches!(
+            block.block.stmts.last(),
+            Some(Stmt::Expr(expr)) if returns_async_block(expr)
+        ),
+        _ => false,
+    }
+}
+
 pub struct Callback {
     result: TokenStream,
 }
@@ -21,7 +34,50 @@ impl Parse for Callback {
                     Ident::new(&format!("new_with_args_{len}"), Span::call_site())
                 };
 
-                quote! {workflow_wasm::callback::Callback::#fn_name(#closure)}
+                if closure.asyncness.is_some() {
+                    // the callback is an `FnMut` invoked multiple times: the async
+                    // closure is converted to a closure returning an async block,
+                    // which is cloned (along with the captured state) for each call
+                    let capture = &closure.capture;
+                    let inputs = &closure.inputs;
+                    let body = &closure.body;
+                    let (args, params): (Vec<_>, Vec<_>) = inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(index, input)| {
+                            let arg = Ident::new(&format!("__arg{index}"), Span::call_site());
+                            let param = match input {
+                                Pat::Type(pat) => {
+                                    let ty = &pat.ty;
+                                    quote! { #arg: #ty }
+                                }
+                                _ => quote! { #arg },
+                            };
+                            (arg, param)
+                        })
+                        .unzip();
+                    quote! {
+                        {
+                            let __future_fn = #capture |#inputs| async move { #body };
+                            workflow_wasm::callback::Callback::#fn_name(move |#(#params),*| {
+                                workflow_wasm::callback::spawn_async_callback(
+                                    __future_fn.clone()(#(#args),*),
+                                )
+                            })
+                        }
+                    }
+                } else if returns_async_block(&closure.body) {
+                    let capture = &closure.capture;
+                    let inputs = &closure.inputs;
+                    let body = &closure.body;
+                    quote! {
+                        workflow_wasm::callback::Callback::#fn_name(#capture |#inputs| {
+                            workflow_wasm::callback::spawn_async_callback(#body)
+                        })
+                    }
+                } else {
+                    quote! {workflow_wasm::callback::Callback::#fn_name(#closure)}
+                }
             }
             Err(_) => {
                 let ts = input.cursor().token_stream();
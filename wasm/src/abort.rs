@@ -0,0 +1,200 @@
+//!
+//! [`AbortToken`] bridges JavaScript `AbortController` / `AbortSignal`
+//! with Rust-side cancellation.
+//!
+//! A token can be created in Rust and its [`AbortSignal`] passed to JavaScript
+//! APIs (such as `fetch()` or `addEventListener()`), or it can be created from
+//! an existing JavaScript [`AbortSignal`], in which case aborting the signal
+//! aborts the token.  In both cases, Rust code can await [`AbortToken::aborted()`]
+//! or wrap a future using [`AbortToken::run()`] to cancel it upon abort.
+//!
+//! ```ignore
+//! let token = AbortToken::new();
+//! let response = token.run(fetch_with_signal(url, &token.signal())).await?;
+//! // ... elsewhere
+//! token.abort();
+//! ```
+//!
+
+use crate::callback::Callback;
+use futures::future::{select, Either};
+use futures::pin_mut;
+use js_sys::Object;
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use workflow_core::abortable::{Abortable, Aborted};
+use workflow_core::trigger::SingleTrigger;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Binding to the JavaScript `AbortController` class.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type AbortController;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AbortController;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn signal(this: &AbortController) -> AbortSignal;
+
+    #[wasm_bindgen(method)]
+    pub fn abort(this: &AbortController);
+
+    #[wasm_bindgen(method, js_name = abort)]
+    pub fn abort_with_reason(this: &AbortController, reason: &JsValue);
+
+    /// Binding to the JavaScript `AbortSignal` class.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type AbortSignal;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn aborted(this: &AbortSignal) -> bool;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn reason(this: &AbortSignal) -> JsValue;
+
+    #[wasm_bindgen(method, js_name = addEventListener)]
+    fn add_event_listener(this: &AbortSignal, event: &str, callback: &JsValue);
+
+    #[wasm_bindgen(method, js_name = removeEventListener)]
+    fn remove_event_listener(this: &AbortSignal, event: &str, callback: &JsValue);
+}
+
+/// External signal and the `abort` event listener registered on it
+type SignalListener = (AbortSignal, Callback<dyn FnMut(JsValue)>);
+
+struct Inner {
+    abortable: Abortable,
+    trigger: SingleTrigger,
+    controller: AbortController,
+    listener: RefCell<Option<SignalListener>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some((signal, callback)) = self.listener.borrow_mut().take() {
+            signal.remove_event_listener("abort", callback.as_ref());
+        }
+    }
+}
+
+/// Cancellation token bound to a JavaScript `AbortController`.
+#[derive(Clone)]
+pub struct AbortToken {
+    inner: Rc<Inner>,
+}
+
+impl Default for AbortToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AbortToken {
+    /// Create a new token backed by a new `AbortController`.
+    pub fn new() -> Self {
+        AbortToken {
+            inner: Rc::new(Inner {
+                abortable: Abortable::new(),
+                trigger: SingleTrigger::new(),
+                controller: AbortController::new(),
+                listener: RefCell::new(None),
+            }),
+        }
+    }
+
+    /// Create a new token that is aborted when the supplied
+    /// JavaScript `AbortSignal` is aborted.
+    pub fn from_signal(signal: &AbortSignal) -> Self {
+        let token = Self::new();
+        if signal.aborted() {
+            token.abort_with_reason(&signal.reason());
+        } else {
+            let abortable = token.inner.abortable.clone();
+            let trigger = token.inner.trigger.trigger.clone();
+            let controller = token.inner.controller.clone();
+            let source = signal.clone();
+            let callback = Callback::new(move |_event: JsValue| {
+                abortable.abort();
+                controller.abort_with_reason(&source.reason());
+                trigger.trigger();
+            });
+            signal.add_event_listener("abort", callback.as_ref());
+            *token.inner.listener.borrow_mut() = Some((signal.clone(), callback));
+        }
+        token
+    }
+
+    /// Abort the token, signalling the underlying `AbortController`.
+    pub fn abort(&self) {
+        self.inner.abortable.abort();
+        self.inner.controller.abort();
+        self.inner.trigger.trigger.trigger();
+    }
+
+    /// Abort the token supplying a reason to the underlying `AbortController`.
+    pub fn abort_with_reason(&self, reason: &JsValue) {
+        self.inner.abortable.abort();
+        self.inner.controller.abort_with_reason(reason);
+        self.inner.trigger.trigger.trigger();
+    }
+
+    /// Returns `true` if the token has been aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.inner.abortable.is_aborted()
+    }
+
+    /// Returns `Err(Aborted)` if the token has been aborted.
+    pub fn check(&self) -> Result<(), Aborted> {
+        self.inner.abortable.check()
+    }
+
+    /// JavaScript `AbortSignal` that can be passed to JavaScript APIs.
+    pub fn signal(&self) -> AbortSignal {
+        self.inner.controller.signal()
+    }
+
+    /// The underlying JavaScript `AbortController`.
+    pub fn controller(&self) -> &AbortController {
+        &self.inner.controller
+    }
+
+    /// [`Abortable`] that is aborted together with this token,
+    /// for use with APIs that accept [`Abortable`].
+    pub fn abortable(&self) -> Abortable {
+        self.inner.abortable.clone()
+    }
+
+    /// Resolves when the token is aborted.
+    pub async fn aborted(&self) {
+        if !self.is_aborted() {
+            self.inner.trigger.listener.clone().await;
+        }
+    }
+
+    /// Executes the future, cancelling it (dropping it and returning
+    /// `Err(Aborted)`) if the token is aborted before the future completes.
+    pub async fn run<F>(&self, future: F) -> Result<F::Output, Aborted>
+    where
+        F: Future,
+    {
+        self.check()?;
+        let aborted = self.aborted();
+        pin_mut!(future);
+        pin_mut!(aborted);
+        match select(future, aborted).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(_) => Err(Aborted),
+        }
+    }
+}
+
+impl From<&AbortSignal> for AbortToken {
+    fn from(signal: &AbortSignal) -> Self {
+        AbortToken::from_signal(signal)
+    }
+}
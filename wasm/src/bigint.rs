@@ -0,0 +1,206 @@
+//!
+//! Lossless conversions between JavaScript `BigInt` values and
+//! 64/128-bit Rust integers.
+//!
+//! JavaScript numbers can only represent integers up to 2^53 without loss
+//! of precision.  [`TryFromBigInt`] converts `BigInt` values (as well as
+//! safe-integer numbers and decimal strings) into `u64`, `i64`, `u128` and
+//! `i128`, returning an error instead of silently truncating the value.
+//! [`to_bigint`] performs the reverse conversion.
+//!
+//! The [`as_string`] module can be used with `#[serde(with = "...")]` to
+//! serialize large integers as decimal strings, preventing truncation when
+//! the serialized data passes through JavaScript numbers (e.g. as JSON).
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use js_sys::BigInt;
+use std::fmt::Display;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// Largest integer that can be represented by a JavaScript number without loss of precision
+pub const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Conversion from a JavaScript `BigInt` (or a safe-integer number, or a
+/// decimal string) into a Rust integer, with overflow checking.
+pub trait TryFromBigInt: Sized {
+    fn try_from_bigint(value: &JsValue) -> Result<Self>;
+}
+
+fn parse_integer<T>(text: &str, type_name: &str) -> Result<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    text.parse::<T>().map_err(|err| {
+        Error::Bounds(format!(
+            "unable to convert value `{text}` to {type_name}: {err}"
+        ))
+    })
+}
+
+fn try_integer_from_value<T>(value: &JsValue, type_name: &str) -> Result<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    if value.is_bigint() {
+        let text = value
+            .unchecked_ref::<BigInt>()
+            .to_string(10)
+            .map_err(|err| Error::Convert(format!("{err:?}")))?;
+        parse_integer(&String::from(text), type_name)
+    } else if let Some(f) = value.as_f64() {
+        if f.fract() != 0.0 || f.abs() > MAX_SAFE_INTEGER {
+            Err(Error::Bounds(format!(
+                "number `{f}` is not a safe integer and can not be converted to {type_name} without loss of precision"
+            )))
+        } else {
+            parse_integer(&format!("{f:.0}"), type_name)
+        }
+    } else if let Some(text) = value.as_string() {
+        parse_integer(text.trim(), type_name)
+    } else {
+        Err(Error::WrongType(format!(
+            "value is not a BigInt, number or string: `{value:?}`"
+        )))
+    }
+}
+
+macro_rules! impl_try_from_bigint {
+    ($($ty:ident),*) => ($(
+        impl TryFromBigInt for $ty {
+            fn try_from_bigint(value: &JsValue) -> Result<Self> {
+                try_integer_from_value(value, stringify!($ty))
+            }
+        }
+    )*)
+}
+
+impl_try_from_bigint!(u64, i64, u128, i128);
+
+/// Convert a 64 or 128-bit integer into a JavaScript `BigInt`.
+pub fn to_bigint<T>(value: T) -> BigInt
+where
+    BigInt: From<T>,
+{
+    BigInt::from(value)
+}
+
+/// Extension trait for lossless integer extraction from a [`JsValue`].
+pub trait BigIntExtension {
+    /// Convert the value (`BigInt`, safe-integer number or a decimal
+    /// string) into a 64 or 128-bit integer, failing on overflow.
+    fn try_as_integer<T: TryFromBigInt>(&self) -> Result<T>;
+}
+
+impl BigIntExtension for JsValue {
+    fn try_as_integer<T: TryFromBigInt>(&self) -> Result<T> {
+        T::try_from_bigint(self)
+    }
+}
+
+/// Serde helper serializing integers as decimal strings and deserializing them
+/// from strings or integers.  Use as `#[serde(with = "workflow_wasm::bigint::as_string")]`.
+pub mod as_string {
+    use serde::{de, Deserializer, Serializer};
+    use std::fmt::{self, Display};
+    use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> std::result::Result<T, D::Error>
+    where
+        T: FromStr + TryFrom<u64> + TryFrom<i64> + TryFrom<u128> + TryFrom<i128>,
+        <T as FromStr>::Err: Display,
+        D: Deserializer<'de>,
+    {
+        struct IntegerVisitor<T>(PhantomData<T>);
+
+        impl<T> IntegerVisitor<T> {
+            fn convert<V, E>(value: V) -> std::result::Result<T, E>
+            where
+                T: TryFrom<V>,
+                V: Display + Copy,
+                E: de::Error,
+            {
+                T::try_from(value)
+                    .map_err(|_| E::custom(format!("integer `{value}` is out of range")))
+            }
+        }
+
+        impl<T> de::Visitor<'_> for IntegerVisitor<T>
+        where
+            T: FromStr + TryFrom<u64> + TryFrom<i64> + TryFrom<u128> + TryFrom<i128>,
+            <T as FromStr>::Err: Display,
+        {
+            type Value = T;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an integer or a decimal string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<T, E> {
+                value.trim().parse::<T>().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<T, E> {
+                Self::convert(value)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<T, E> {
+                Self::convert(value)
+            }
+
+            fn visit_u128<E: de::Error>(self, value: u128) -> std::result::Result<T, E> {
+                Self::convert(value)
+            }
+
+            fn visit_i128<E: de::Error>(self, value: i128) -> std::result::Result<T, E> {
+                Self::convert(value)
+            }
+        }
+
+        deserializer.deserialize_any(IntegerVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        #[serde(with = "super::as_string")]
+        amount: u128,
+        #[serde(with = "super::as_string")]
+        offset: i64,
+    }
+
+    #[test]
+    fn test_as_string_serde() {
+        let data = Data {
+            amount: u128::MAX,
+            offset: -(1 << 60),
+        };
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(
+            json,
+            r#"{"amount":"340282366920938463463374607431768211455","offset":"-1152921504606846976"}"#
+        );
+        assert_eq!(serde_json::from_str::<Data>(&json).unwrap(), data);
+        let data = serde_json::from_str::<Data>(r#"{"amount":42,"offset":-7}"#).unwrap();
+        assert_eq!(data.amount, 42);
+        assert_eq!(data.offset, -7);
+        assert!(serde_json::from_str::<Data>(r#"{"amount":-1,"offset":0}"#).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//!
+//! Zero-copy and bounds-checked `Uint8Array` access helpers.
+//!
+//! [`view()`] creates a `Uint8Array` that references WASM linear memory
+//! directly, avoiding a copy when passing Rust data to JavaScript APIs that
+//! consume the data synchronously (such as `WebSocket.send()`).  Such views
+//! become *detached* (zero-length) as soon as the WASM memory grows, which
+//! can happen on any Rust allocation, so they must never be retained.
+//!
+//! When data needs to be read from JavaScript into Rust, the `copy_*`
+//! functions and [`ReusableBuffer`] copy the data with explicit bounds
+//! checks (instead of panicking on length mismatch) and allow the
+//! destination allocation to be reused across messages.
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use js_sys::{ArrayBuffer, Uint8Array};
+use std::ops::Range;
+use wasm_bindgen::prelude::*;
+
+/// Create a `Uint8Array` view of the supplied slice without copying.
+///
+/// # Safety
+///
+/// The returned array references WASM linear memory directly. It is only valid
+/// while `bytes` is alive and unmodified, and becomes detached if WASM memory
+/// grows (i.e. on any allocation that triggers memory growth).  No allocation
+/// may take place between the creation of the view and its last use, and the
+/// view must not be retained by JavaScript.  Use [`with_view()`] or
+/// [`Uint8Array::from`] (copying) if these conditions can not be guaranteed.
+pub unsafe fn view(bytes: &[u8]) -> Uint8Array {
+    Uint8Array::view(bytes)
+}
+
+/// Invoke the closure with a zero-copy `Uint8Array` view of `bytes`.
+///
+/// # Safety
+///
+/// The closure must not allocate (in Rust or by calling into Rust from
+/// JavaScript) and must not retain the supplied array beyond its execution.
+/// See [`view()`] for details.
+pub unsafe fn with_view<R>(bytes: &[u8], f: impl FnOnce(&Uint8Array) -> R) -> R {
+    let array = Uint8Array::view(bytes);
+    f(&array)
+}
+
+/// Copy the contents of `array` into `dest`, failing if the lengths differ.
+pub fn copy_to_slice(array: &Uint8Array, dest: &mut [u8]) -> Result<()> {
+    let len = array.length() as usize;
+    if len != dest.len() {
+        return Err(Error::Bounds(format!(
+            "source array length {len} does not match destination length {}",
+            dest.len()
+        )));
+    }
+    array.copy_to(dest);
+    Ok(())
+}
+
+/// Copy `src` into `array`, failing if the lengths differ.
+pub fn copy_from_slice(array: &Uint8Array, src: &[u8]) -> Result<()> {
+    let len = array.length() as usize;
+    if len != src.len() {
+        return Err(Error::Bounds(format!(
+            "destination array length {len} does not match source length {}",
+            src.len()
+        )));
+    }
+    array.copy_from(src);
+    Ok(())
+}
+
+/// Copy the contents of `array` into `buffer`, replacing its contents.
+/// The existing allocation of `buffer` is reused if it has sufficient capacity.
+pub fn copy_into(array: &Uint8Array, buffer: &mut Vec<u8>) {
+    buffer.clear();
+    buffer.resize(array.length() as usize, 0);
+    array.copy_to(buffer);
+}
+
+/// Copy the contents of an `ArrayBuffer` into a new `Vec<u8>`.
+pub fn array_buffer_to_vec(buffer: &ArrayBuffer) -> Vec<u8> {
+    Uint8Array::new(buffer).to_vec()
+}
+
+/// Bounds-checked version of `Uint8Array::subarray()` (which silently clamps
+/// out-of-range indexes).  The returned array shares the underlying buffer.
+pub fn subarray(array: &Uint8Array, range: Range<u32>) -> Result<Uint8Array> {
+    let len = array.length();
+    if range.start > range.end || range.end > len {
+        return Err(Error::Bounds(format!(
+            "range {}..{} is out of bounds for array of length {len}",
+            range.start, range.end
+        )));
+    }
+    Ok(array.subarray(range.start, range.end))
+}
+
+/// Returns `true` if the array is backed by a detached `ArrayBuffer`
+/// (for example, a WASM memory view invalidated by memory growth,
+/// or a buffer that has been transferred to a worker).
+pub fn is_detached(array: &Uint8Array) -> bool {
+    array.byte_length() == 0 && array.buffer().byte_length() == 0
+}
+
+/// Reusable buffer for copying data out of `Uint8Array` instances,
+/// avoiding an allocation per copy when processing a sequence of messages.
+#[derive(Default, Debug, Clone)]
+pub struct ReusableBuffer {
+    buffer: Vec<u8>,
+}
+
+impl ReusableBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ReusableBuffer {
+            buffer: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Copy the contents of `array` into the buffer, returning the copied data.
+    /// The returned slice is valid until the next call to `copy_from()`.
+    pub fn copy_from(&mut self, array: &Uint8Array) -> &[u8] {
+        copy_into(array, &mut self.buffer);
+        &self.buffer
+    }
+
+    /// Copy the contents of a `JsValue` containing a `Uint8Array`
+    /// or an `ArrayBuffer` into the buffer.
+    pub fn copy_from_value(&mut self, value: &JsValue) -> Result<&[u8]> {
+        if value.is_instance_of::<Uint8Array>() {
+            Ok(self.copy_from(value.unchecked_ref()))
+        } else if value.is_instance_of::<ArrayBuffer>() {
+            Ok(self.copy_from(&Uint8Array::new(value)))
+        } else {
+            Err(Error::WrongType(format!(
+                "value is not a Uint8Array or an ArrayBuffer: `{value:?}`"
+            )))
+        }
+    }
+
+    /// Data copied by the last call to `copy_from()`.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Take the contained data, leaving the buffer empty (without capacity).
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Current capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+}
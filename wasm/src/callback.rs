@@ -6,6 +6,7 @@
 use js_sys::Function;
 use std::{
     collections::HashMap,
+    future::Future,
     sync::{Arc, Mutex, MutexGuard},
 };
 use thiserror::Error;
@@ -170,6 +171,56 @@ impl<T> Callback<T> {
     }
 }
 
+impl<T> Callback<T> {
+    /// Create a new [`Callback`] instance with the given async closure
+    /// (a closure returning a future). Each invocation of the callback
+    /// spawns the returned future on the local executor.
+    pub fn new_async<A, Fut>(mut callback: T) -> Callback<dyn FnMut(A)>
+    where
+        T: 'static + FnMut(A) -> Fut,
+        A: FromWasmAbi + 'static,
+        Fut: Future + 'static,
+        Fut::Output: AsyncCallbackOutput,
+    {
+        Callback::create(move |a: A| spawn_async_callback(callback(a)))
+    }
+}
+
+/// Output of the future produced by an async callback closure.
+/// Implemented for `()` and `Result<(), E>`; errors returned by
+/// the future are logged using [`workflow_log::log_error`].
+pub trait AsyncCallbackOutput {
+    fn handle(self);
+}
+
+impl AsyncCallbackOutput for () {
+    fn handle(self) {}
+}
+
+impl<E> AsyncCallbackOutput for std::result::Result<(), E>
+where
+    E: std::fmt::Debug,
+{
+    fn handle(self) {
+        if let Err(err) = self {
+            workflow_log::log_error!("async callback error: {err:?}");
+        }
+    }
+}
+
+/// Spawns the future produced by an async callback closure on the local
+/// executor. Used by the [`macro@callback`] macro when supplied with an
+/// async closure.
+pub fn spawn_async_callback<F>(future: F)
+where
+    F: Future + 'static,
+    F::Output: AsyncCallbackOutput,
+{
+    wasm_bindgen_futures::spawn_local(async move {
+        future.await.handle();
+    });
+}
+
 impl<T> Callback<T>
 where
     T: ?Sized + WasmClosure + 'static,
@@ -411,6 +462,35 @@ impl CallbackMap {
 ///        //
 ///     });
 ///     ```
+/// - ##### Async closures:
+///   The macro accepts async closures (`async move |..| {..}`) as well as
+///   closures returning an async block (`move |..| async move {..}` or
+///   `move |..| { ..; async move {..} }`).
+///   Each invocation of the callback spawns the resulting future on the
+///   local executor via [`spawn_async_callback`]. The future can resolve to
+///   `()` or `Result<(), E>`, in which case errors are logged.
+///   Because the callback can be invoked multiple times, the async closure
+///   is cloned for each call, so the state it captures must implement
+///   `Clone` (closures returning an async block should clone the captured
+///   state before moving it into the future).
+///   ```no_compile
+///   let callback = callback!(async move |event: web_sys::MessageEvent| {
+///       sender.send(event.data()).await?;
+///       Ok::<_, JsValue>(())
+///   });
+///   ```
+///   Output will be as follows:
+///   ```no_compile
+///   let callback = {
+///       let future_fn = move |event: web_sys::MessageEvent| async move {
+///           sender.send(event.data()).await?;
+///           Ok::<_, JsValue>(())
+///       };
+///       Callback::new(move |arg0: web_sys::MessageEvent| {
+///           spawn_async_callback(future_fn.clone()(arg0))
+///       })
+///   };
+///   ```
 ///
 pub use workflow_wasm_macros::callback;
 
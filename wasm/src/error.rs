@@ -21,6 +21,15 @@ pub enum Error {
     #[error("error accessing property `{0}`")]
     PropertyAccess(String),
 
+    #[error("missing property `{0}` in path `{1}`")]
+    MissingPathSegment(String, String),
+
+    #[error("unable to access property `{0}` in path `{1}`: parent value is not an object")]
+    NotAnObjectAtPath(String, String),
+
+    #[error("error converting property `{0}`: {1}")]
+    PathConversion(String, String),
+
     #[error("{0}")]
     Bounds(String),
 
@@ -36,6 +45,9 @@ pub enum Error {
     #[error(transparent)]
     JsValue(JsErrorData),
 
+    #[error("serde: {0}")]
+    Serde(String),
+
     #[error("WASM ABI: {0}")]
     Abi(String),
 
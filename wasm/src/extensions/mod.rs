@@ -1,5 +1,7 @@
 pub mod jsvalue;
 pub mod object;
+pub mod path;
 
 pub use jsvalue::*;
 pub use object::*;
+pub use path::*;
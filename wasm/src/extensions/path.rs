@@ -0,0 +1,264 @@
+//!
+//! Path-based (`"result.block.height"`) property access for [`Object`].
+//!
+//! Path segments are separated by `.`; numeric segments can be used
+//! to index arrays (`"items.0.name"`).  Errors name the segment that
+//! could not be resolved along with the full path.
+//!
+
+use crate::bigint::TryFromBigInt;
+use crate::error::Error;
+use crate::extensions::jsvalue::JsValueExtension;
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// Conversion of a property value into a Rust type,
+/// used by the [`ObjectPathExtension`] accessors.
+pub trait FromPropertyValue: Sized {
+    fn from_property_value(value: JsValue) -> Result<Self, Error>;
+}
+
+impl FromPropertyValue for JsValue {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        Ok(value)
+    }
+}
+
+impl FromPropertyValue for Object {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value
+            .dyn_into::<Object>()
+            .map_err(|value| Error::WrongType(format!("value is not an object: `{value:?}`")))
+    }
+}
+
+impl FromPropertyValue for String {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value
+            .as_string()
+            .ok_or_else(|| Error::WrongType(format!("value is not a string: `{value:?}`")))
+    }
+}
+
+impl FromPropertyValue for bool {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value
+            .as_bool()
+            .ok_or_else(|| Error::WrongType(format!("value is not a boolean: `{value:?}`")))
+    }
+}
+
+impl FromPropertyValue for f64 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value
+            .as_f64()
+            .ok_or_else(|| Error::WrongType(format!("value is not a number: `{value:?}`")))
+    }
+}
+
+impl FromPropertyValue for u8 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value.try_as_u8()
+    }
+}
+
+impl FromPropertyValue for u16 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value.try_as_u16()
+    }
+}
+
+impl FromPropertyValue for u32 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value.try_as_u32()
+    }
+}
+
+impl FromPropertyValue for u64 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value.try_as_u64()
+    }
+}
+
+impl FromPropertyValue for i32 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        let f = f64::from_property_value(value)?;
+        if f.fract() != 0.0 || !(i32::MIN as f64..=i32::MAX as f64).contains(&f) {
+            Err(Error::Bounds(format!(
+                "value `{f}` is not an integer in the range ({}..{})",
+                i32::MIN,
+                i32::MAX
+            )))
+        } else {
+            Ok(f as i32)
+        }
+    }
+}
+
+impl FromPropertyValue for i64 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        if value.is_bigint() {
+            value.clone().try_into().map_err(|err| {
+                Error::Convert(format!(
+                    "unable to convert BigInt value to i64: `{value:?}`: {err:?}"
+                ))
+            })
+        } else {
+            // a Number can only represent integers up to 2^53 exactly
+            const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+            let f = f64::from_property_value(value)?;
+            if f.fract() != 0.0 || !(-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&f) {
+                Err(Error::Bounds(format!(
+                    "value `{f}` is not an integer in the safe range (-{MAX_SAFE_INTEGER}..{MAX_SAFE_INTEGER})"
+                )))
+            } else {
+                Ok(f as i64)
+            }
+        }
+    }
+}
+
+impl FromPropertyValue for u128 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        u128::try_from_bigint(&value)
+    }
+}
+
+impl FromPropertyValue for i128 {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        i128::try_from_bigint(&value)
+    }
+}
+
+impl FromPropertyValue for Vec<u8> {
+    fn from_property_value(value: JsValue) -> Result<Self, Error> {
+        value.try_as_vec_u8()
+    }
+}
+
+/// Path-based property accessors for [`Object`].
+pub trait ObjectPathExtension {
+    /// Get the value located at the `.`-separated `path`.
+    /// Returns [`Error::MissingPathSegment`] naming the first
+    /// segment that is `undefined` or `null`.
+    fn get_path_value(&self, path: &str) -> Result<JsValue, Error>;
+
+    /// Get the value located at `path` converted to type `T`.
+    fn get_path<T>(&self, path: &str) -> Result<T, Error>
+    where
+        T: FromPropertyValue;
+
+    /// Get the value located at `path` converted to type `T`.
+    /// Returns `Ok(None)` if any of the path segments do not exist.
+    fn try_get_path<T>(&self, path: &str) -> Result<Option<T>, Error>
+    where
+        T: FromPropertyValue;
+
+    /// Get the array located at `path`, converting each element to type `T`.
+    fn get_path_vec<T>(&self, path: &str) -> Result<Vec<T>, Error>
+    where
+        T: FromPropertyValue;
+
+    /// Set the value at `path`, creating intermediate objects if they do not exist.
+    fn set_path(&self, path: &str, value: &JsValue) -> Result<(), Error>;
+}
+
+fn is_missing(value: &JsValue) -> bool {
+    value.is_undefined() || value.is_null()
+}
+
+fn is_container(value: &JsValue) -> bool {
+    value.is_object() || value.is_function()
+}
+
+impl ObjectPathExtension for Object {
+    fn get_path_value(&self, path: &str) -> Result<JsValue, Error> {
+        let mut value: JsValue = self.into();
+        for segment in path.split('.') {
+            if !is_container(&value) {
+                return Err(Error::NotAnObjectAtPath(
+                    segment.to_string(),
+                    path.to_string(),
+                ));
+            }
+            value = Reflect::get(&value, &JsValue::from(segment))
+                .map_err(|_| Error::PropertyAccess(path.to_string()))?;
+            if is_missing(&value) {
+                return Err(Error::MissingPathSegment(
+                    segment.to_string(),
+                    path.to_string(),
+                ));
+            }
+        }
+        Ok(value)
+    }
+
+    fn get_path<T>(&self, path: &str) -> Result<T, Error>
+    where
+        T: FromPropertyValue,
+    {
+        let value = self.get_path_value(path)?;
+        T::from_property_value(value)
+            .map_err(|err| Error::PathConversion(path.to_string(), err.to_string()))
+    }
+
+    fn try_get_path<T>(&self, path: &str) -> Result<Option<T>, Error>
+    where
+        T: FromPropertyValue,
+    {
+        match self.get_path(path) {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::MissingPathSegment(_, _)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn get_path_vec<T>(&self, path: &str) -> Result<Vec<T>, Error>
+    where
+        T: FromPropertyValue,
+    {
+        let value = self.get_path_value(path)?;
+        if !Array::is_array(&value) {
+            return Err(Error::PathConversion(
+                path.to_string(),
+                "value is not an array".to_string(),
+            ));
+        }
+        Array::from(&value)
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                T::from_property_value(item).map_err(|err| {
+                    Error::PathConversion(format!("{path}[{index}]"), err.to_string())
+                })
+            })
+            .collect()
+    }
+
+    fn set_path(&self, path: &str, value: &JsValue) -> Result<(), Error> {
+        let mut segments = path.split('.').peekable();
+        let mut target: JsValue = self.into();
+        while let Some(segment) = segments.next() {
+            let key = JsValue::from(segment);
+            if segments.peek().is_none() {
+                Reflect::set(&target, &key, value)
+                    .map_err(|_| Error::PropertyAccess(path.to_string()))?;
+                break;
+            }
+            let mut next =
+                Reflect::get(&target, &key).map_err(|_| Error::PropertyAccess(path.to_string()))?;
+            if is_missing(&next) {
+                next = Object::new().into();
+                Reflect::set(&target, &key, &next)
+                    .map_err(|_| Error::PropertyAccess(path.to_string()))?;
+            } else if !is_container(&next) {
+                return Err(Error::NotAnObjectAtPath(
+                    segment.to_string(),
+                    path.to_string(),
+                ));
+            }
+            target = next;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//!
+//! `requestAnimationFrame()` helpers: [`next_frame()`] future and
+//! [`animation_frames()`] stream yielding frame timing information.
+//!
+//! Pending frame requests are cancelled (via `cancelAnimationFrame()`)
+//! when the future or the stream is dropped.
+//!
+//! ```ignore
+//! let mut frames = animation_frames();
+//! while let Some(frame) = frames.next().await {
+//!     update(frame.delta);
+//!     render();
+//! }
+//! ```
+//!
+
+use crate::result::Result;
+use futures::Stream;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(catch, js_name = requestAnimationFrame)]
+    fn request_animation_frame(callback: &JsValue) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = cancelAnimationFrame)]
+    fn cancel_animation_frame(handle: &JsValue) -> std::result::Result<(), JsValue>;
+}
+
+/// Animation frame timing information.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    /// Frame timestamp in milliseconds (`DOMHighResTimeStamp`
+    /// supplied to the `requestAnimationFrame()` callback).
+    pub timestamp: f64,
+    /// Time elapsed since the previous frame (zero for the first frame).
+    pub delta: Duration,
+}
+
+#[derive(Default)]
+struct State {
+    handle: Option<JsValue>,
+    timestamp: Option<f64>,
+    waker: Option<Waker>,
+}
+
+/// Shared frame request state; the closure is retained for
+/// the lifetime of the future or the stream.
+struct FrameRequest {
+    state: Rc<RefCell<State>>,
+    closure: Closure<dyn FnMut(f64)>,
+}
+
+impl FrameRequest {
+    fn new() -> Self {
+        let state = Rc::new(RefCell::new(State::default()));
+        let state_ = state.clone();
+        let closure = Closure::<dyn FnMut(f64)>::new(move |timestamp: f64| {
+            let mut state = state_.borrow_mut();
+            state.handle = None;
+            state.timestamp = Some(timestamp);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        FrameRequest { state, closure }
+    }
+
+    fn poll_frame(&self, cx: &mut Context<'_>) -> Poll<Result<f64>> {
+        let mut state = self.state.borrow_mut();
+        if let Some(timestamp) = state.timestamp.take() {
+            return Poll::Ready(Ok(timestamp));
+        }
+        if state.handle.is_none() {
+            match request_animation_frame(self.closure.as_ref()) {
+                Ok(handle) => state.handle = Some(handle),
+                Err(err) => return Poll::Ready(Err(err.into())),
+            }
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for FrameRequest {
+    fn drop(&mut self) {
+        if let Some(handle) = self.state.borrow_mut().handle.take() {
+            cancel_animation_frame(&handle).ok();
+        }
+    }
+}
+
+/// Future returned by [`next_frame()`].
+pub struct NextFrame {
+    request: FrameRequest,
+}
+
+impl Future for NextFrame {
+    type Output = Result<f64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.request.poll_frame(cx)
+    }
+}
+
+/// Resolves on the next animation frame with the frame timestamp
+/// (in milliseconds).  Returns an error if `requestAnimationFrame()`
+/// is not available (e.g. in Node.js).
+pub fn next_frame() -> NextFrame {
+    NextFrame {
+        request: FrameRequest::new(),
+    }
+}
+
+/// Stream returned by [`animation_frames()`].
+pub struct AnimationFrames {
+    request: FrameRequest,
+    last: Option<f64>,
+    done: bool,
+}
+
+impl Stream for AnimationFrames {
+    type Item = Result<Frame>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match this.request.poll_frame(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(timestamp)) => {
+                let delta = this
+                    .last
+                    .map(|last| Duration::from_secs_f64((timestamp - last).max(0.0) / 1000.0))
+                    .unwrap_or_default();
+                this.last = Some(timestamp);
+                Poll::Ready(Some(Ok(Frame { timestamp, delta })))
+            }
+            Poll::Ready(Err(err)) => {
+                this.done = true;
+                Poll::Ready(Some(Err(err)))
+            }
+        }
+    }
+}
+
+/// Create a stream yielding a [`Frame`] on each animation frame.  A frame is
+/// requested only while the stream is being polled, so an idle stream does not
+/// consume resources.  The pending request is cancelled when the stream is dropped.
+pub fn animation_frames() -> AnimationFrames {
+    AnimationFrames {
+        request: FrameRequest::new(),
+        last: None,
+        done: false,
+    }
+}
@@ -1,6 +1,13 @@
 //! Structures for handling JavaScript errors. Specifically this module
 //! provides a `JsErrorData` struct which is used to extract information
 //! from a `JsValue` that represents a JavaScript error.
+//!
+//! `JsErrorData` captures the error `name`, `message`, `cause`, `stack`
+//! and `code` properties, as well as the numeric `DOMException` code,
+//! and classifies the error using [`JsErrorKind`], allowing error handling
+//! to branch on the actual JavaScript error type instead of matching
+//! against a `format!("{error:?}")` string.  Thrown values that are not
+//! error objects (such as strings) are captured as the error message.
 
 use std::sync::Arc;
 use wasm_bindgen::prelude::*;
@@ -33,12 +40,73 @@ impl JsValueErrorTrait for JsValue {
     }
 }
 
+/// Classification of a JavaScript error based on its `name` property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsErrorKind {
+    /// `Error` (generic error)
+    Error,
+    TypeError,
+    RangeError,
+    SyntaxError,
+    ReferenceError,
+    EvalError,
+    URIError,
+    AggregateError,
+    /// `AbortError` (`DOMException` produced by `AbortController`)
+    AbortError,
+    /// `TimeoutError` (`DOMException` produced by `AbortSignal.timeout()`)
+    TimeoutError,
+    /// `NetworkError` (`DOMException`)
+    NetworkError,
+    /// `NotFoundError` (`DOMException`)
+    NotFoundError,
+    /// `NotAllowedError` (`DOMException`)
+    NotAllowedError,
+    /// `SecurityError` (`DOMException`)
+    SecurityError,
+    /// `QuotaExceededError` (`DOMException`)
+    QuotaExceededError,
+    /// `InvalidStateError` (`DOMException`)
+    InvalidStateError,
+    /// Any other named error (including other `DOMException` names)
+    Other(String),
+    /// Thrown value that is not an error object (e.g. a string)
+    NotAnError,
+}
+
+impl From<&str> for JsErrorKind {
+    fn from(name: &str) -> Self {
+        match name {
+            "Error" => JsErrorKind::Error,
+            "TypeError" => JsErrorKind::TypeError,
+            "RangeError" => JsErrorKind::RangeError,
+            "SyntaxError" => JsErrorKind::SyntaxError,
+            "ReferenceError" => JsErrorKind::ReferenceError,
+            "EvalError" => JsErrorKind::EvalError,
+            "URIError" => JsErrorKind::URIError,
+            "AggregateError" => JsErrorKind::AggregateError,
+            "AbortError" => JsErrorKind::AbortError,
+            "TimeoutError" => JsErrorKind::TimeoutError,
+            "NetworkError" => JsErrorKind::NetworkError,
+            "NotFoundError" => JsErrorKind::NotFoundError,
+            "NotAllowedError" => JsErrorKind::NotAllowedError,
+            "SecurityError" => JsErrorKind::SecurityError,
+            "QuotaExceededError" => JsErrorKind::QuotaExceededError,
+            "InvalidStateError" => JsErrorKind::InvalidStateError,
+            name => JsErrorKind::Other(name.to_string()),
+        }
+    }
+}
+
 struct Inner {
     name: Option<String>,
     message: Option<String>,
     cause: Option<String>,
     stack: Option<String>,
     code: Option<String>,
+    // numeric `DOMException.code` (legacy error code)
+    dom_exception_code: Option<u16>,
+    kind: JsErrorKind,
     // origin
     origin: Sendable<JsValue>,
 }
@@ -70,6 +138,31 @@ impl JsErrorData {
     pub fn code(&self) -> &Option<String> {
         &self.inner.code
     }
+
+    /// Numeric `DOMException.code` if the error is a `DOMException`
+    pub fn dom_exception_code(&self) -> Option<u16> {
+        self.inner.dom_exception_code
+    }
+
+    /// Returns `true` if the error is a `DOMException`
+    pub fn is_dom_exception(&self) -> bool {
+        self.inner.dom_exception_code.is_some()
+    }
+
+    /// Error classification based on the error `name`
+    pub fn kind(&self) -> &JsErrorKind {
+        &self.inner.kind
+    }
+
+    /// Returns `true` if the error is an `AbortError`
+    pub fn is_abort(&self) -> bool {
+        self.inner.kind == JsErrorKind::AbortError
+    }
+
+    /// The original thrown value
+    pub fn origin(&self) -> &JsValue {
+        self.inner.origin.as_ref()
+    }
 }
 
 impl std::fmt::Debug for JsErrorData {
@@ -80,6 +173,8 @@ impl std::fmt::Debug for JsErrorData {
             .field("cause", &self.inner.cause)
             .field("stack", &self.inner.stack)
             .field("code", &self.inner.code)
+            .field("dom_exception_code", &self.inner.dom_exception_code)
+            .field("kind", &self.inner.kind)
             .finish()
     }
 }
@@ -97,23 +192,74 @@ impl std::fmt::Display for JsErrorData {
     }
 }
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = DOMException)]
+    type DomException;
+}
+
+fn get_property(object: &JsValue, prop: &str) -> Option<JsValue> {
+    if !object.is_object() {
+        return None;
+    }
+    js_sys::Reflect::get(object, &prop.into())
+        .ok()
+        .filter(|v| !v.is_undefined() && !v.is_null())
+}
+
+fn get_string(object: &JsValue, prop: &str) -> Option<String> {
+    get_property(object, prop).and_then(|v| v.as_string())
+}
+
+fn is_dom_exception(error: &JsValue) -> bool {
+    // `DOMException` may not be available in older Node.js versions
+    let available = js_sys::Reflect::get(&js_sys::global(), &"DOMException".into())
+        .map(|v| v.is_function())
+        .unwrap_or(false);
+    available && error.is_instance_of::<DomException>()
+}
+
 impl From<JsValue> for JsErrorData {
     fn from(error: JsValue) -> Self {
-        let name = js_sys::Reflect::get(&error, &"name".into())
-            .ok()
-            .and_then(|v| v.as_string());
-        let message = js_sys::Reflect::get(&error, &"message".into())
-            .ok()
-            .and_then(|v| v.as_string());
-        let cause = js_sys::Reflect::get(&error, &"cause".into())
-            .ok()
-            .and_then(|v| v.as_string());
-        let stack = js_sys::Reflect::get(&error, &"stack".into())
-            .ok()
-            .and_then(|v| v.as_string());
-        let code = js_sys::Reflect::get(&error, &"code".into())
-            .ok()
-            .and_then(|v| v.as_string());
+        if !error.is_object() {
+            // thrown value is not an error object (e.g. `throw "message"`)
+            let message = error.as_string().unwrap_or_else(|| format!("{error:?}"));
+            return Self {
+                inner: Arc::new(Inner {
+                    name: None,
+                    message: Some(message),
+                    cause: None,
+                    stack: None,
+                    code: None,
+                    dom_exception_code: None,
+                    kind: JsErrorKind::NotAnError,
+                    origin: Sendable::new(error),
+                }),
+            };
+        }
+
+        let name = get_string(&error, "name");
+        let message = get_string(&error, "message");
+        // `cause` can be any value; for error objects the cause message is used
+        let cause = get_property(&error, "cause").map(|cause| {
+            cause
+                .as_string()
+                .or_else(|| get_string(&cause, "message"))
+                .unwrap_or_else(|| format!("{cause:?}"))
+        });
+        let stack = get_string(&error, "stack");
+        // `code` is a string in Node.js errors and a number in `DOMException`
+        let code = get_property(&error, "code")
+            .and_then(|v| v.as_string().or_else(|| v.as_f64().map(|f| f.to_string())));
+        let dom_exception_code = is_dom_exception(&error).then(|| {
+            get_property(&error, "code")
+                .and_then(|v| v.as_f64())
+                .unwrap_or_default() as u16
+        });
+        let kind = name
+            .as_deref()
+            .map(JsErrorKind::from)
+            .unwrap_or(JsErrorKind::Error);
 
         Self {
             inner: Arc::new(Inner {
@@ -122,6 +268,8 @@ impl From<JsValue> for JsErrorData {
                 cause,
                 stack,
                 code,
+                dom_exception_code,
+                kind,
                 origin: Sendable::new(error),
             }),
         }
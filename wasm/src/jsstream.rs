@@ -0,0 +1,218 @@
+//!
+//! Adapters between JavaScript async iterators / `ReadableStream`
+//! and Rust [`Stream`]s.
+//!
+//! [`JsStream`] converts a JavaScript async iterable (any object implementing
+//! `[Symbol.asyncIterator]`), an async iterator (an object with a `next()`
+//! method returning a promise) or a `ReadableStream` into a Rust
+//! `Stream<Item = Result<JsValue>>`.
+//!
+//! ```ignore
+//! let mut stream = JsStream::try_new(&response.body())?;
+//! while let Some(chunk) = stream.next().await {
+//!     let chunk = Uint8Array::new(&chunk?);
+//!     // ...
+//! }
+//! ```
+//!
+//! [`to_readable_stream`] performs the reverse conversion, creating a
+//! JavaScript `ReadableStream` that pulls items from a Rust [`Stream`].
+//! Unlike [`AsyncStream`](crate::stream::AsyncStream), these adapters do
+//! not use `eval` and can be used in environments with CSP restrictions.
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use futures::{Stream, StreamExt};
+use js_sys::{Function, Object, Promise, Reflect, Symbol};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+#[wasm_bindgen]
+extern "C" {
+    /// Binding to the JavaScript `ReadableStream` class.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type ReadableStream;
+
+    #[wasm_bindgen(catch, constructor)]
+    fn new_with_underlying_source(source: &Object) -> std::result::Result<ReadableStream, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    type ReadableStreamDefaultController;
+
+    #[wasm_bindgen(catch, method)]
+    fn enqueue(
+        this: &ReadableStreamDefaultController,
+        chunk: &JsValue,
+    ) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(catch, method)]
+    fn close(this: &ReadableStreamDefaultController) -> std::result::Result<(), JsValue>;
+}
+
+/// Rust [`Stream`] reading items from a JavaScript async iterator
+/// or a `ReadableStream`.
+pub struct JsStream {
+    /// iterator or `ReadableStreamDefaultReader` object
+    source: Object,
+    /// `next()` or `read()` function of the source
+    next: Function,
+    pending: Option<JsFuture>,
+    done: bool,
+}
+
+impl JsStream {
+    /// Create a stream from a JavaScript async iterable, async iterator
+    /// or a `ReadableStream`.  Results in [`Error::WrongType`] if the
+    /// supplied value is none of these.
+    pub fn try_new(value: &JsValue) -> Result<Self> {
+        if !value.is_object() {
+            return Err(Error::NotAnObject);
+        }
+
+        // `ReadableStream` (detected by the presence of `getReader()`)
+        let get_reader = Reflect::get(value, &"getReader".into())?;
+        if let Some(get_reader) = get_reader.dyn_ref::<Function>() {
+            let reader = get_reader.call0(value)?;
+            return Self::with_method(reader.unchecked_into(), "read");
+        }
+
+        let async_iterator = Reflect::get(value, &Symbol::async_iterator())?;
+        if let Some(f) = async_iterator.dyn_ref::<Function>() {
+            let iterator = f.call0(value)?;
+            return Self::with_method(iterator.unchecked_into(), "next");
+        }
+
+        Self::with_method(value.clone().unchecked_into(), "next")
+    }
+
+    fn with_method(source: Object, method: &str) -> Result<Self> {
+        let next = Reflect::get(&source, &method.into())?
+            .dyn_into::<Function>()
+            .map_err(|_| {
+                Error::WrongType(format!(
+                    "object is not an async iterator or a ReadableStream (missing `{method}()`)"
+                ))
+            })?;
+        Ok(JsStream {
+            source,
+            next,
+            pending: None,
+            done: false,
+        })
+    }
+}
+
+impl TryFrom<&JsValue> for JsStream {
+    type Error = Error;
+    fn try_from(value: &JsValue) -> Result<Self> {
+        JsStream::try_new(value)
+    }
+}
+
+impl Stream for JsStream {
+    type Item = Result<JsValue>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        if this.pending.is_none() {
+            match this.next.call0(&this.source) {
+                Ok(promise) => {
+                    this.pending = Some(JsFuture::from(Promise::resolve(&promise)));
+                }
+                Err(err) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            }
+        }
+
+        let pending = this.pending.as_mut().unwrap();
+        match Pin::new(pending).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                this.pending = None;
+                let item = result.and_then(|result| {
+                    let done = Reflect::get(&result, &"done".into())?;
+                    if done.is_truthy() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(Reflect::get(&result, &"value".into())?))
+                    }
+                });
+                match item {
+                    Ok(Some(value)) => Poll::Ready(Some(Ok(value))),
+                    Ok(None) => {
+                        this.done = true;
+                        Poll::Ready(None)
+                    }
+                    Err(err) => {
+                        this.done = true;
+                        Poll::Ready(Some(Err(err.into())))
+                    }
+                }
+            }
+        }
+    }
+}
+
+type SharedStream = Rc<RefCell<Option<Pin<Box<dyn Stream<Item = JsValue>>>>>>;
+
+/// Create a JavaScript `ReadableStream` that pulls items from the supplied
+/// Rust [`Stream`].  The Rust stream is dropped when the JavaScript side
+/// cancels the `ReadableStream` or when the stream is exhausted.
+pub fn to_readable_stream<T>(source: impl Stream<Item = T> + 'static) -> Result<ReadableStream>
+where
+    T: Into<JsValue> + 'static,
+{
+    let stream: SharedStream =
+        Rc::new(RefCell::new(Some(Box::pin(source.map(|item| item.into())))));
+
+    let pull_stream = stream.clone();
+    let pull = Closure::<dyn FnMut(JsValue) -> Promise>::new(move |controller: JsValue| {
+        let stream = pull_stream.clone();
+        let controller = controller.unchecked_into::<ReadableStreamDefaultController>();
+        future_to_promise(async move {
+            // `pull()` is not re-invoked until the returned promise resolves,
+            // so the stream can be temporarily taken out of the shared slot
+            let taken = stream.borrow_mut().take();
+            if let Some(mut source) = taken {
+                match source.next().await {
+                    Some(item) => {
+                        controller.enqueue(&item)?;
+                        stream.borrow_mut().replace(source);
+                    }
+                    None => {
+                        controller.close()?;
+                    }
+                }
+            }
+            Ok(JsValue::UNDEFINED)
+        })
+    });
+
+    let cancel = Closure::<dyn FnMut(JsValue)>::new(move |_reason: JsValue| {
+        stream.borrow_mut().take();
+    });
+
+    let underlying_source = Object::new();
+    Reflect::set(&underlying_source, &"pull".into(), &pull.into_js_value())?;
+    Reflect::set(
+        &underlying_source,
+        &"cancel".into(),
+        &cancel.into_js_value(),
+    )?;
+    Ok(ReadableStream::new_with_underlying_source(
+        &underlying_source,
+    )?)
+}
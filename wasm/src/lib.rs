@@ -12,19 +12,27 @@
 
 extern crate self as workflow_wasm;
 
+pub mod abort;
+pub mod bigint;
+pub mod buffer;
 pub mod callback;
 pub mod convert;
 pub mod error;
 pub mod extensions;
+pub mod frame;
 pub mod init;
 pub mod jserror;
+pub mod jsstream;
 pub mod options;
 pub mod panic;
+pub mod performance;
 pub mod prelude;
 pub mod printable;
 pub mod result;
 pub mod serde;
+pub mod timers;
 pub mod utils;
+pub mod worker;
 
 #[cfg(feature = "defer")]
 pub mod defer;
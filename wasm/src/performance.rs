@@ -0,0 +1,169 @@
+//!
+//! Portable accessors for the JavaScript `performance` API and memory metrics.
+//!
+//! All functions handle the absence of the underlying APIs gracefully
+//! (for example, `performance.memory` is available only in Chromium-based
+//! environments and `measureUserAgentSpecificMemory()` requires cross-origin
+//! isolation), returning `None` instead of failing.
+//!
+//! ```ignore
+//! let stopwatch = Stopwatch::new();
+//! let response = rpc.call(request).await?;
+//! log_info!("RPC latency: {:?}", stopwatch.elapsed());
+//! ```
+//!
+
+use crate::result::Result;
+use js_sys::{Function, Object, Promise, Reflect};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+fn performance() -> Option<Object> {
+    Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .and_then(|performance| performance.dyn_into::<Object>().ok())
+}
+
+fn method(object: &Object, name: &str) -> Option<Function> {
+    Reflect::get(object, &name.into())
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok())
+}
+
+fn get_f64(object: &JsValue, name: &str) -> Option<f64> {
+    Reflect::get(object, &name.into())
+        .ok()
+        .and_then(|v| v.as_f64())
+}
+
+/// High resolution timestamp in milliseconds (`performance.now()`),
+/// falling back to `Date.now()` if the `performance` API is not available.
+pub fn now() -> f64 {
+    performance()
+        .and_then(|performance| {
+            method(&performance, "now")
+                .and_then(|now| now.call0(&performance).ok())
+                .and_then(|v| v.as_f64())
+        })
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Create a named performance mark (`performance.mark()`).
+/// Returns `false` if the API is not available.
+pub fn mark(name: &str) -> bool {
+    performance()
+        .and_then(|performance| {
+            method(&performance, "mark").map(|mark| mark.call1(&performance, &name.into()).is_ok())
+        })
+        .unwrap_or(false)
+}
+
+/// Create a named performance measure between two marks
+/// (`performance.measure()`), returning the measured duration.
+/// If `end_mark` is `None`, the measure ends at the current time.
+/// Returns `None` if the API is not available or the marks do not exist.
+pub fn measure(name: &str, start_mark: &str, end_mark: Option<&str>) -> Option<Duration> {
+    let performance = performance()?;
+    let measure = method(&performance, "measure")?;
+    let entry = match end_mark {
+        Some(end_mark) => measure.call3(
+            &performance,
+            &name.into(),
+            &start_mark.into(),
+            &end_mark.into(),
+        ),
+        None => measure.call2(&performance, &name.into(), &start_mark.into()),
+    }
+    .ok()?;
+    get_f64(&entry, "duration").map(|ms| Duration::from_secs_f64(ms.max(0.0) / 1000.0))
+}
+
+/// Remove marks (`performance.clearMarks()`) and measures
+/// (`performance.clearMeasures()`) with the given name.
+pub fn clear(name: &str) {
+    if let Some(performance) = performance() {
+        for f in ["clearMarks", "clearMeasures"] {
+            if let Some(f) = method(&performance, f) {
+                f.call1(&performance, &name.into()).ok();
+            }
+        }
+    }
+}
+
+/// JavaScript heap usage reported by the non-standard
+/// `performance.memory` API (Chromium-based environments only).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapMemory {
+    pub used_js_heap_size: u64,
+    pub total_js_heap_size: u64,
+    pub js_heap_size_limit: u64,
+}
+
+/// Obtain JavaScript heap usage from `performance.memory`.
+pub fn heap_memory() -> Option<HeapMemory> {
+    let performance = performance()?;
+    let memory = Reflect::get(&performance, &"memory".into()).ok()?;
+    if !memory.is_object() {
+        return None;
+    }
+    Some(HeapMemory {
+        used_js_heap_size: get_f64(&memory, "usedJSHeapSize")? as u64,
+        total_js_heap_size: get_f64(&memory, "totalJSHeapSize")? as u64,
+        js_heap_size_limit: get_f64(&memory, "jsHeapSizeLimit")? as u64,
+    })
+}
+
+/// Measure total memory used by the agent (in bytes) using
+/// `performance.measureUserAgentSpecificMemory()`.  Returns `Ok(None)` if
+/// the API is not available (it requires a cross-origin isolated context).
+/// Please note that the browser may delay the measurement significantly.
+pub async fn measure_user_agent_specific_memory() -> Result<Option<u64>> {
+    let Some(performance) = performance() else {
+        return Ok(None);
+    };
+    let Some(measure) = method(&performance, "measureUserAgentSpecificMemory") else {
+        return Ok(None);
+    };
+    let promise = Promise::resolve(&measure.call0(&performance)?);
+    let result = JsFuture::from(promise).await?;
+    Ok(get_f64(&result, "bytes").map(|bytes| bytes as u64))
+}
+
+/// Current size of the WASM linear memory in bytes.
+pub fn wasm_memory_size() -> u64 {
+    let buffer = Reflect::get(&wasm_bindgen::memory(), &"buffer".into()).ok();
+    buffer
+        .and_then(|buffer| get_f64(&buffer, "byteLength"))
+        .unwrap_or_default() as u64
+}
+
+/// Simple elapsed time measurement based on [`now()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    start: f64,
+}
+
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Stopwatch { start: now() }
+    }
+
+    /// Time elapsed since the creation of the stopwatch.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.start).max(0.0) / 1000.0)
+    }
+
+    /// Restart the stopwatch, returning the time elapsed so far.
+    pub fn restart(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        self.start = now();
+        elapsed
+    }
+}
@@ -1,10 +1,46 @@
 //! `to_value` utility that serializes data to a [`JsValue`] using
 //! `serde_wasm_bindgen` with `BigInt` serialization enabled.
+//!
+//! [`to_object`] and [`from_object`] helpers convert between serde-compatible
+//! Rust types and JavaScript [`Object`] instances, producing
+//! [`workflow_wasm::error::Error`](crate::error::Error) on failure.
+use js_sys::Object;
+use serde::{de::DeserializeOwned, Serialize};
 pub use serde_wasm_bindgen::*;
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{JsCast, JsValue};
 type Result<T> = std::result::Result<T, Error>;
 
 /// Converts a Rust value into a [`JsValue`].
 pub fn to_value<T: serde::ser::Serialize + ?Sized>(value: &T) -> Result<JsValue> {
     value.serialize(&Serializer::new().serialize_large_number_types_as_bigints(true))
 }
+
+/// Serializes a Rust value into a JavaScript [`Object`].  Maps are serialized
+/// as plain objects and 64-bit integers as `BigInt`.  Results in an error
+/// if the value does not serialize to an object (e.g. a primitive value).
+pub fn to_object<T>(value: &T) -> std::result::Result<Object, crate::error::Error>
+where
+    T: Serialize + ?Sized,
+{
+    let serializer = Serializer::new()
+        .serialize_maps_as_objects(true)
+        .serialize_large_number_types_as_bigints(true);
+    value
+        .serialize(&serializer)
+        .map_err(|err| crate::error::Error::Serde(err.to_string()))?
+        .dyn_into::<Object>()
+        .map_err(|value| {
+            crate::error::Error::WrongType(format!(
+                "serialized value is not an object: `{value:?}`"
+            ))
+        })
+}
+
+/// Deserializes a Rust value from a JavaScript [`Object`] (or any [`JsValue`]
+/// convertible into an object reference).
+pub fn from_object<T>(object: impl AsRef<JsValue>) -> std::result::Result<T, crate::error::Error>
+where
+    T: DeserializeOwned,
+{
+    from_value(object.as_ref().clone()).map_err(|err| crate::error::Error::Serde(err.to_string()))
+}
@@ -0,0 +1,176 @@
+//!
+//! [`Timeout`] and [`Interval`] RAII wrappers for the JavaScript
+//! `setTimeout()` / `setInterval()` APIs.
+//!
+//! Each timer retains its closure and clears the underlying JavaScript timer
+//! when cancelled or dropped, eliminating leaked timers when the owner
+//! (such as a UI component) is torn down.  Timer events can be received
+//! via an optional callback or by awaiting [`Timeout::next()`] /
+//! [`Interval::next()`].
+//!
+//! ```ignore
+//! let interval = Interval::new(Duration::from_secs(1))?;
+//! while interval.next().await.is_some() {
+//!     // ...
+//! }
+//! ```
+//!
+
+use crate::result::Result;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use workflow_core::channel::Channel;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(catch, js_name = setTimeout)]
+    fn set_timeout(closure: &JsValue, timeout: u32) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = clearTimeout)]
+    fn clear_timeout(handle: &JsValue) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(catch, js_name = setInterval)]
+    fn set_interval(closure: &JsValue, timeout: u32) -> std::result::Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = clearInterval)]
+    fn clear_interval(handle: &JsValue) -> std::result::Result<(), JsValue>;
+}
+
+struct TimerContext {
+    handle: JsValue,
+    // retained for the lifetime of the timer
+    #[allow(dead_code)]
+    closure: Closure<dyn FnMut()>,
+}
+
+struct Inner {
+    ctx: RefCell<Option<TimerContext>>,
+    channel: Channel<()>,
+    clear: fn(&JsValue) -> std::result::Result<(), JsValue>,
+}
+
+impl Inner {
+    fn new(clear: fn(&JsValue) -> std::result::Result<(), JsValue>) -> Rc<Self> {
+        Rc::new(Inner {
+            ctx: RefCell::new(None),
+            // timer events are coalesced if not consumed
+            channel: Channel::bounded(1),
+            clear,
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        self.ctx.borrow().is_some()
+    }
+
+    fn cancel(&self) {
+        if let Some(ctx) = self.ctx.borrow_mut().take() {
+            (self.clear)(&ctx.handle).ok();
+        }
+        self.channel.receiver.close();
+    }
+
+    async fn next(&self) -> Option<()> {
+        self.channel.recv().await.ok()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// One-shot timer backed by `setTimeout()`, cleared on drop.
+pub struct Timeout {
+    inner: Rc<Inner>,
+}
+
+impl Timeout {
+    /// Create a new timeout that fires once after `duration`.
+    pub fn new(duration: Duration) -> Result<Self> {
+        Self::with_callback(duration, || {})
+    }
+
+    /// Create a new timeout that invokes `callback` after `duration`.
+    pub fn with_callback<F>(duration: Duration, callback: F) -> Result<Self>
+    where
+        F: FnOnce() + 'static,
+    {
+        let inner = Inner::new(clear_timeout);
+        let mut callback = Some(callback);
+        let sender = inner.channel.sender.clone();
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            sender.try_send(()).ok();
+            sender.close();
+            if let Some(callback) = callback.take() {
+                callback();
+            }
+        });
+        let handle = set_timeout(closure.as_ref(), duration.as_millis() as u32)?;
+        *inner.ctx.borrow_mut() = Some(TimerContext { handle, closure });
+        Ok(Timeout { inner })
+    }
+
+    /// Returns `true` if the timeout has not been cancelled or dropped.
+    pub fn is_active(&self) -> bool {
+        self.inner.is_active()
+    }
+
+    /// Cancel the timeout.  Pending [`next()`](Self::next) calls resolve to `None`.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Resolves to `Some(())` when the timeout fires or to `None`
+    /// if the timeout has been cancelled or has already been consumed.
+    pub async fn next(&self) -> Option<()> {
+        self.inner.next().await
+    }
+}
+
+/// Repeating timer backed by `setInterval()`, cleared on drop.
+pub struct Interval {
+    inner: Rc<Inner>,
+}
+
+impl Interval {
+    /// Create a new interval firing every `period`.
+    pub fn new(period: Duration) -> Result<Self> {
+        Self::with_callback(period, || {})
+    }
+
+    /// Create a new interval invoking `callback` every `period`.
+    pub fn with_callback<F>(period: Duration, mut callback: F) -> Result<Self>
+    where
+        F: FnMut() + 'static,
+    {
+        let inner = Inner::new(clear_interval);
+        let sender = inner.channel.sender.clone();
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            sender.try_send(()).ok();
+            callback();
+        });
+        let handle = set_interval(closure.as_ref(), period.as_millis() as u32)?;
+        *inner.ctx.borrow_mut() = Some(TimerContext { handle, closure });
+        Ok(Interval { inner })
+    }
+
+    /// Returns `true` if the interval has not been cancelled or dropped.
+    pub fn is_active(&self) -> bool {
+        self.inner.is_active()
+    }
+
+    /// Cancel the interval.  Pending [`next()`](Self::next) calls resolve to `None`.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Resolves to `Some(())` on the next interval tick (ticks that were not
+    /// consumed are coalesced) or to `None` if the interval has been cancelled.
+    pub async fn next(&self) -> Option<()> {
+        self.inner.next().await
+    }
+}
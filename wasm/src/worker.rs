@@ -0,0 +1,281 @@
+//!
+//! Web Worker spawn helper with a typed (serde-encoded) message channel.
+//!
+//! [`WorkerChannel::spawn()`] starts a module Web Worker that loads the
+//! wasm-bindgen generated JavaScript module, initializes it and invokes the
+//! specified exported entry point.  Inside the worker, the entry point creates
+//! a [`WorkerScope`] to communicate with the parent.  Messages are encoded
+//! using `serde_wasm_bindgen`, so any `Serialize` / `Deserialize` type can
+//! be exchanged.  Messages posted before the worker creates its
+//! [`WorkerScope`] are buffered and delivered once the scope is created.
+//!
+//! ```ignore
+//! // parent
+//! let worker = WorkerChannel::<Request, Response>::spawn("./app.js", "worker_main")?;
+//! worker.send(&Request::Hash(data))?;
+//! let response = worker.recv().await?;
+//!
+//! // worker (exported from the same crate)
+//! #[wasm_bindgen]
+//! pub fn worker_main() {
+//!     let scope = WorkerScope::<Request, Response>::new().unwrap();
+//!     spawn_local(async move {
+//!         while let Ok(request) = scope.recv().await {
+//!             scope.send(&process(request)).unwrap();
+//!         }
+//!     });
+//! }
+//! ```
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use js_sys::{Array, Object, Reflect};
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use wasm_bindgen::prelude::*;
+use workflow_core::channel::Channel;
+
+/// Global property used by the worker bootstrap script to
+/// buffer messages received before [`WorkerScope`] is created.
+const PENDING_MESSAGES: &str = "__workflow_worker_pending";
+
+#[wasm_bindgen]
+extern "C" {
+    /// Binding to the JavaScript `Worker` class.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type Worker;
+
+    #[wasm_bindgen(catch, constructor)]
+    fn new_with_options(url: &str, options: &Object) -> std::result::Result<Worker, JsValue>;
+
+    #[wasm_bindgen(catch, method, js_name = postMessage)]
+    fn post_message(this: &Worker, message: &JsValue) -> std::result::Result<(), JsValue>;
+
+    #[wasm_bindgen(method)]
+    fn terminate(this: &Worker);
+
+    #[wasm_bindgen(method, setter)]
+    fn set_onmessage(this: &Worker, callback: &JsValue);
+
+    #[wasm_bindgen(method, setter)]
+    fn set_onerror(this: &Worker, callback: &JsValue);
+
+    #[wasm_bindgen(js_name = Blob)]
+    type Blob;
+
+    #[wasm_bindgen(catch, constructor, js_class = "Blob")]
+    fn new_blob(parts: &Array, options: &Object) -> std::result::Result<Blob, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = URL, js_name = createObjectURL)]
+    fn create_object_url(blob: &Blob) -> std::result::Result<String, JsValue>;
+
+    #[wasm_bindgen(js_namespace = URL, js_name = revokeObjectURL)]
+    fn revoke_object_url(url: &str);
+
+    #[wasm_bindgen(catch, js_name = postMessage)]
+    fn global_post_message(message: &JsValue) -> std::result::Result<(), JsValue>;
+}
+
+fn to_message<T: Serialize>(message: &T) -> Result<JsValue> {
+    serde_wasm_bindgen::to_value(message).map_err(|err| Error::Serde(err.to_string()))
+}
+
+fn from_event<T: DeserializeOwned>(event: &JsValue) -> Result<T> {
+    let data = Reflect::get(event, &"data".into())?;
+    serde_wasm_bindgen::from_value(data).map_err(|err| Error::Serde(err.to_string()))
+}
+
+fn bootstrap_script(module_url: &str, entry: &str) -> String {
+    format!(
+        r#"
+        self.{PENDING_MESSAGES} = [];
+        self.onmessage = (event) => self.{PENDING_MESSAGES}.push(event);
+        import({module_url:?}).then(async (module) => {{
+            await module.default();
+            module[{entry:?}]();
+        }}).catch((err) => {{
+            setTimeout(() => {{ throw err; }});
+        }});
+        "#
+    )
+}
+
+/// Typed channel to a Web Worker spawned from the parent context.
+/// The worker is terminated when the channel is dropped.
+pub struct WorkerChannel<Tx, Rx> {
+    worker: Worker,
+    channel: Channel<Result<Rx>>,
+    // closures retained for the lifetime of the worker
+    _onmessage: Closure<dyn FnMut(JsValue)>,
+    _onerror: Closure<dyn FnMut(JsValue)>,
+    _send: PhantomData<Tx>,
+}
+
+impl<Tx, Rx> WorkerChannel<Tx, Rx>
+where
+    Tx: Serialize,
+    Rx: DeserializeOwned + 'static,
+{
+    /// Spawn a module worker that imports the wasm-bindgen generated JavaScript
+    /// module located at `module_url` (must be an absolute URL or relative to
+    /// the page), initializes it and invokes the exported function `entry`.
+    pub fn spawn(module_url: &str, entry: &str) -> Result<Self> {
+        let module_url = resolve_url(module_url)?;
+        let parts = Array::new();
+        parts.push(&bootstrap_script(&module_url, entry).into());
+        let options = Object::new();
+        Reflect::set(&options, &"type".into(), &"application/javascript".into())?;
+        let blob = Blob::new_blob(&parts, &options)?;
+        let url = create_object_url(&blob)?;
+
+        let options = Object::new();
+        Reflect::set(&options, &"type".into(), &"module".into())?;
+        let worker = Worker::new_with_options(&url, &options);
+        revoke_object_url(&url);
+        let worker = worker?;
+
+        let channel = Channel::<Result<Rx>>::unbounded();
+
+        let sender = channel.sender.clone();
+        let onmessage = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            sender.try_send(from_event(&event)).ok();
+        });
+        worker.set_onmessage(onmessage.as_ref());
+
+        let sender = channel.sender.clone();
+        let onerror = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let message = Reflect::get(&event, &"message".into())
+                .ok()
+                .and_then(|message| message.as_string())
+                .unwrap_or_else(|| "unknown worker error".to_string());
+            sender.try_send(Err(Error::Custom(message))).ok();
+        });
+        worker.set_onerror(onerror.as_ref());
+
+        Ok(WorkerChannel {
+            worker,
+            channel,
+            _onmessage: onmessage,
+            _onerror: onerror,
+            _send: PhantomData,
+        })
+    }
+
+    /// Post a message to the worker.
+    pub fn send(&self, message: &Tx) -> Result<()> {
+        self.worker.post_message(&to_message(message)?)?;
+        Ok(())
+    }
+
+    /// Receive the next message from the worker.  Worker errors
+    /// (uncaught exceptions) are received as `Err`.
+    pub async fn recv(&self) -> Result<Rx> {
+        self.channel
+            .recv()
+            .await
+            .map_err(|err| Error::Custom(err.to_string()))?
+    }
+
+    /// The underlying JavaScript `Worker`.
+    pub fn worker(&self) -> &Worker {
+        &self.worker
+    }
+
+    /// Terminate the worker.
+    pub fn terminate(&self) {
+        self.worker.terminate();
+        self.channel.receiver.close();
+    }
+}
+
+impl<Tx, Rx> Drop for WorkerChannel<Tx, Rx> {
+    fn drop(&mut self) {
+        self.worker.set_onmessage(&JsValue::NULL);
+        self.worker.set_onerror(&JsValue::NULL);
+        self.worker.terminate();
+    }
+}
+
+/// Resolve `url` relative to the current location, as the
+/// worker bootstrap script is loaded from a `blob:` URL.
+fn resolve_url(url: &str) -> Result<String> {
+    let location = Reflect::get(&js_sys::global(), &"location".into())?;
+    let base = location
+        .is_object()
+        .then(|| Reflect::get(&location, &"href".into()).ok())
+        .flatten()
+        .and_then(|href| href.as_string());
+    match base {
+        Some(base) => {
+            let ctor = Reflect::get(&js_sys::global(), &"URL".into())?;
+            let args = Array::of2(&url.into(), &base.into());
+            let resolved = Reflect::construct(ctor.unchecked_ref(), &args)?;
+            Ok(Reflect::get(&resolved, &"href".into())?
+                .as_string()
+                .unwrap_or_else(|| url.to_string()))
+        }
+        None => Ok(url.to_string()),
+    }
+}
+
+/// Typed channel to the parent context, created within the worker.
+pub struct WorkerScope<Tx, Rx> {
+    channel: Channel<Result<Rx>>,
+    _onmessage: Closure<dyn FnMut(JsValue)>,
+    _send: PhantomData<Tx>,
+}
+
+impl<Tx, Rx> WorkerScope<Tx, Rx>
+where
+    Tx: Serialize,
+    Rx: DeserializeOwned + 'static,
+{
+    /// Create the worker scope, delivering any messages received
+    /// by the worker before the scope was created.
+    pub fn new() -> Result<Self> {
+        let global = js_sys::global();
+        let channel = Channel::<Result<Rx>>::unbounded();
+
+        let sender = channel.sender.clone();
+        let onmessage = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            sender.try_send(from_event(&event)).ok();
+        });
+
+        let pending = Reflect::get(&global, &PENDING_MESSAGES.into())?;
+        if Array::is_array(&pending) {
+            for event in Array::from(&pending).iter() {
+                channel.sender.try_send(from_event(&event)).ok();
+            }
+            Reflect::delete_property(global.unchecked_ref(), &PENDING_MESSAGES.into())?;
+        }
+        Reflect::set(&global, &"onmessage".into(), onmessage.as_ref())?;
+
+        Ok(WorkerScope {
+            channel,
+            _onmessage: onmessage,
+            _send: PhantomData,
+        })
+    }
+
+    /// Post a message to the parent context.
+    pub fn send(&self, message: &Tx) -> Result<()> {
+        global_post_message(&to_message(message)?)?;
+        Ok(())
+    }
+
+    /// Receive the next message from the parent context.
+    pub async fn recv(&self) -> Result<Rx> {
+        self.channel
+            .recv()
+            .await
+            .map_err(|err| Error::Custom(err.to_string()))?
+    }
+}
+
+impl<Tx, Rx> Drop for WorkerScope<Tx, Rx> {
+    fn drop(&mut self) {
+        Reflect::set(&js_sys::global(), &"onmessage".into(), &JsValue::NULL).ok();
+    }
+}
@@ -0,0 +1,7 @@
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn callback_macro() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/callback-async-block.rs");
+    t.pass("tests/ui/callback-async-closure.rs");
+}
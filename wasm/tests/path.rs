@@ -0,0 +1,105 @@
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Object, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use workflow_wasm::error::Error;
+use workflow_wasm::extensions::ObjectPathExtension;
+
+fn object() -> Object {
+    JSON::parse(
+        r#"{
+            "result": {
+                "block": { "height": 42, "hash": "00ff" },
+                "items": [{ "name": "first" }, { "name": "second" }],
+                "large": 3000000000,
+                "negative": -7,
+                "fraction": 1.5,
+                "empty": null
+            }
+        }"#,
+    )
+    .unwrap()
+    .unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn test_get_path() {
+    let object = object();
+    assert_eq!(object.get_path::<u64>("result.block.height").unwrap(), 42);
+    assert_eq!(
+        object.get_path::<String>("result.block.hash").unwrap(),
+        "00ff"
+    );
+    assert_eq!(
+        object.get_path::<String>("result.items.1.name").unwrap(),
+        "second"
+    );
+    assert_eq!(object.get_path::<i32>("result.negative").unwrap(), -7);
+    assert_eq!(
+        object.get_path::<i64>("result.large").unwrap(),
+        3_000_000_000
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_missing_path_segment() {
+    let object = object();
+    match object.get_path::<u64>("result.header.height") {
+        Err(Error::MissingPathSegment(segment, path)) => {
+            assert_eq!(segment, "header");
+            assert_eq!(path, "result.header.height");
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert!(matches!(
+        object.get_path::<u64>("result.empty"),
+        Err(Error::MissingPathSegment(segment, _)) if segment == "empty"
+    ));
+    assert!(matches!(
+        object.get_path::<u64>("result.block.height.value"),
+        Err(Error::NotAnObjectAtPath(segment, _)) if segment == "value"
+    ));
+    assert_eq!(
+        object.try_get_path::<u64>("result.header.height").unwrap(),
+        None
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_integer_bounds() {
+    let object = object();
+    assert!(matches!(
+        object.get_path::<i32>("result.large"),
+        Err(Error::Bounds(_))
+    ));
+    assert!(matches!(
+        object.get_path::<u32>("result.negative"),
+        Err(Error::Bounds(_))
+    ));
+
+    let object = Object::new();
+    object.set_path("value", &JsValue::from(f64::NAN)).unwrap();
+    assert!(matches!(
+        object.get_path::<i32>("value"),
+        Err(Error::Bounds(_))
+    ));
+    assert!(matches!(
+        object.get_path::<i64>("value"),
+        Err(Error::Bounds(_))
+    ));
+}
+
+#[wasm_bindgen_test]
+fn test_fractional_values() {
+    let object = object();
+    assert_eq!(object.get_path::<f64>("result.fraction").unwrap(), 1.5);
+    assert!(matches!(
+        object.get_path::<i32>("result.fraction"),
+        Err(Error::Bounds(_))
+    ));
+    assert!(matches!(
+        object.get_path::<i64>("result.fraction"),
+        Err(Error::Bounds(_))
+    ));
+}
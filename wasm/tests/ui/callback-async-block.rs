@@ -0,0 +1,21 @@
+use std::sync::Arc;
+use wasm_bindgen::JsValue;
+use workflow_wasm::callback::callback;
+
+#[allow(dead_code)]
+fn callbacks() {
+    let state = Arc::new(String::from("state"));
+
+    let _callback = callback!(move |value: JsValue| {
+        let state = state.clone();
+        async move {
+            let _ = (state, value);
+        }
+    });
+
+    let _callback = callback!(move |_value: JsValue| async move {
+        Ok::<_, JsValue>(())
+    });
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+use wasm_bindgen::JsValue;
+use workflow_wasm::callback::callback;
+
+#[allow(dead_code)]
+fn callbacks() {
+    let state = Arc::new(String::from("state"));
+
+    // the captured state is moved into the future of each call
+    let _callback = callback!(async move |value: JsValue| {
+        let _ = (state, value);
+    });
+
+    let state = Arc::new(String::from("state"));
+    let _callback = callback!(async move |first: JsValue, second: JsValue| {
+        let _ = (state, first, second);
+        Ok::<_, JsValue>(())
+    });
+}
+
+fn main() {}
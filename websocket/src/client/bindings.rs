@@ -254,6 +254,8 @@ impl WebSocket {
     pub fn new_with_config(
         url: &str,
         config: &WebSocketConfig,
+        headers: &[(String, String)],
+        protocols: &[String],
     ) -> super::result::Result<WebSocket> {
         if is_node() {
             let WebSocketNodeJsConfig {
@@ -262,7 +264,9 @@ impl WebSocket {
                 headers,
                 request_options,
                 client_config,
-            } = WebSocketNodeJsConfig::try_from(config)?;
+            } = WebSocketNodeJsConfig::try_from(config)?
+                .with_headers(headers)?
+                .with_protocols(protocols);
 
             Ok(Self::new_with_nodejs_config_impl(
                 url,
@@ -272,6 +276,12 @@ impl WebSocket {
                 request_options,
                 client_config,
             )?)
+        } else if !protocols.is_empty() {
+            let protocols = protocols
+                .iter()
+                .map(JsValue::from)
+                .collect::<js_sys::Array>();
+            Ok(Self::new_with_str_sequence(url, &protocols)?)
         } else {
             Ok(Self::new(url)?)
         }
@@ -284,4 +294,16 @@ impl WebSocket {
         }
         Ok(())
     }
+
+    /// Close the connection with the supplied close code and reason,
+    /// returns `true` if the connection was open (or connecting)
+    pub fn close_with_frame_if_open(&self, frame: &CloseFrame) -> super::result::Result<bool> {
+        let ready_state = self.ready_state();
+        if ready_state != Self::CLOSED && ready_state != Self::CLOSING {
+            self.close_with_code_and_reason(frame.code, &frame.reason)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 }
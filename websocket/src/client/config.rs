@@ -2,7 +2,10 @@
 //! WebSocket client configuration options
 //!
 
-use super::{error::Error, result::Result, Handshake, Resolver};
+use super::{
+    error::Error, result::Result, ConnectionInitializer, DeflateConfig, Handshake, Keepalive,
+    OfflineQueue, RateLimit, ReceiverOverflow, Resolver, TlsOptions,
+};
 use cfg_if::cfg_if;
 use js_sys::Object;
 use std::sync::Arc;
@@ -43,25 +46,64 @@ pub struct WebSocketConfig {
     /// be reasonably big for all normal use-cases but small enough to prevent memory eating
     /// by a malicious user.
     pub max_frame_size: Option<usize>,
+    /// Payload size of the frames produced by
+    /// [`WebSocket::send_stream()`](super::WebSocket::send_stream)
+    /// (native client only). The default value is 64 KiB.
+    pub stream_frame_size: usize,
+    /// When set to `true`, received [`Message::Ping`](super::Message::Ping) and
+    /// [`Message::Pong`](super::Message::Pong) control frames (including the
+    /// responses to the keepalive pings) are delivered to the receiver channel
+    /// (native client only). The default value is `false`.
+    pub receive_ping_pong: bool,
     /// When set to `true`, the server will accept and handle unmasked frames
     /// from the client. According to the RFC 6455, the server must close the
     /// connection to the client in such cases, however it seems like there are
     /// some popular libraries that are sending unmasked frames, ignoring the RFC.
     /// By default this option is set to `false`, i.e. according to RFC 6455.
     pub accept_unmasked_frames: bool,
+    /// Prefix of the application-level echo used by
+    /// [`WebSocket::ping()`](super::WebSocket::ping) in the WASM client
+    /// (browsers do not expose Ping frames).  The client sends the text message
+    /// `{prefix}{nonce}` which the server is expected to echo back verbatim;
+    /// the echo is not delivered to the receiver channel.  If not supplied,
+    /// `ping()` is not supported by the WASM client.  Ignored by the native client.
+    pub ping_echo: Option<String>,
     /// The capacity of the channel used to queue incoming messages from WebSocket.
+    /// If not supplied, the channel is unbounded.
     pub receiver_channel_cap: Option<usize>,
+    /// Policy applied when a message is received while the bounded receiver
+    /// channel is full. The default value is [`ReceiverOverflow::Block`].
+    pub receiver_overflow: ReceiverOverflow,
     /// The capacity of the channel used to queue outgoing messages to WebSocket.
     pub sender_channel_cap: Option<usize>,
+    /// The capacity of the channels of the broadcast subscriptions (see
+    /// [`WebSocket::subscribe()`](super::WebSocket::subscribe)).
+    /// The default value is 1024.
+    pub subscriber_channel_cap: usize,
     /// Handshake handler for WebSocket connections. If supplied, it will be called
     /// when the connection is established. The handshake handler can be used to
     /// perform additional validation or setup before the connection is used.
     pub handshake: Option<Arc<dyn Handshake>>,
+    /// Connection initializer invoked after every successful connection
+    /// and reconnection. See [`ConnectionInitializer`].
+    pub initializer: Option<Arc<dyn ConnectionInitializer>>,
     /// Resolver for WebSocket connections. If supplied, it will be called to resolve
     /// the URL before the connection is established. The resolver can be used as
     /// an alternative to supplying the URL and will be invoked each time the
     /// websocket needs to be connected or reconnected.
     pub resolver: Option<Arc<dyn Resolver>>,
+    /// TLS options for `wss://` connections (native client only). If not
+    /// supplied, the default configuration of the TLS backend is used.
+    pub tls: Option<TlsOptions>,
+    /// Keepalive options (disabled if not supplied). See [`Keepalive`].
+    pub keepalive: Option<Keepalive>,
+    /// Offline queue options (disabled if not supplied). See [`OfflineQueue`].
+    pub offline_queue: Option<OfflineQueue>,
+    /// Outbound rate limit (unlimited if not supplied). See [`RateLimit`].
+    pub rate_limit: Option<RateLimit>,
+    /// `permessage-deflate` compression offered to the server (native client
+    /// only, disabled if not supplied). See [`DeflateConfig`].
+    pub deflate: Option<DeflateConfig>,
 }
 
 impl Default for WebSocketConfig {
@@ -71,11 +113,22 @@ impl Default for WebSocketConfig {
             max_write_buffer_size: usize::MAX,
             max_message_size: Some(64 << 20),
             max_frame_size: Some(16 << 20),
+            stream_frame_size: 64 << 10,
+            receive_ping_pong: false,
             accept_unmasked_frames: false,
+            ping_echo: None,
             receiver_channel_cap: None,
+            receiver_overflow: ReceiverOverflow::default(),
             sender_channel_cap: None,
+            subscriber_channel_cap: 1024,
             handshake: None,
+            initializer: None,
             resolver: None,
+            tls: None,
+            keepalive: None,
+            offline_queue: None,
+            rate_limit: None,
+            deflate: None,
         }
     }
 }
@@ -96,6 +149,38 @@ cfg_if! {
             maxMessageSize: number,
             /** Maximum size of the WebSocket frame. */
             maxFrameSize: number,
+            /**
+             * Prefix of the application-level echo used by `ping()`. The client
+             * sends the text message `{prefix}{nonce}` which the server is
+             * expected to echo back verbatim.
+             */
+            pingEcho?: string,
+            /**
+             * Capacity of the channel queueing the received messages
+             * (unbounded if not supplied).
+             */
+            receiverChannelCap?: number,
+            /**
+             * Policy applied when a message is received while the receiver
+             * channel is full (defaults to "block").
+             */
+            receiverOverflow?: "block" | "drop-oldest" | "drop-newest" | "disconnect",
+            /**
+             * Keepalive options (intervals in milliseconds). Browsers do not
+             * expose WebSocket Ping frames, keepalive requires an application-level
+             * `message` the server responds to.
+             */
+            keepalive?: { interval?: number, timeout?: number, message?: string },
+            /**
+             * Offline queue options. Messages posted while the WebSocket is
+             * disconnected are queued and sent once the connection is re-established.
+             */
+            offlineQueue?: { capacity?: number, overflow?: "drop-oldest" | "drop-newest" | "reject" },
+            /**
+             * Outbound rate limit. Messages exceeding the rate are delayed
+             * until the rate allows them to be sent.
+             */
+            rateLimit?: { messagesPerSecond?: number, bytesPerSecond?: number },
         }
         "#;
 
@@ -116,6 +201,51 @@ cfg_if! {
                     if let Some(max_message_size) = args.get_value("maxMessageSize")?.as_f64() {
                         config.max_message_size = Some(max_message_size as usize);
                     }
+                    if let Some(ping_echo) = args.get_value("pingEcho")?.as_string() {
+                        config.ping_echo = Some(ping_echo);
+                    }
+                    if let Some(cap) = args.get_value("receiverChannelCap")?.as_f64() {
+                        config.receiver_channel_cap = Some(cap as usize);
+                    }
+                    if let Some(overflow) = args.get_value("receiverOverflow")?.as_string() {
+                        config.receiver_overflow = overflow.parse()?;
+                    }
+                    let keepalive = args.get_value("keepalive")?;
+                    if let Some(args) = keepalive.dyn_ref::<Object>() {
+                        let mut keepalive = Keepalive::default();
+                        if let Some(interval) = args.get_value("interval")?.as_f64() {
+                            keepalive.interval = std::time::Duration::from_millis(interval as u64);
+                        }
+                        if let Some(timeout) = args.get_value("timeout")?.as_f64() {
+                            keepalive.timeout = std::time::Duration::from_millis(timeout as u64);
+                        }
+                        if let Some(message) = args.get_value("message")?.as_string() {
+                            keepalive.message = Some(message.into());
+                        }
+                        config.keepalive = Some(keepalive);
+                    }
+                    let offline_queue = args.get_value("offlineQueue")?;
+                    if let Some(args) = offline_queue.dyn_ref::<Object>() {
+                        let mut offline_queue = OfflineQueue::default();
+                        if let Some(capacity) = args.get_value("capacity")?.as_f64() {
+                            offline_queue.capacity = capacity as usize;
+                        }
+                        if let Some(overflow) = args.get_value("overflow")?.as_string() {
+                            offline_queue.overflow = overflow.parse()?;
+                        }
+                        config.offline_queue = Some(offline_queue);
+                    }
+                    let rate_limit = args.get_value("rateLimit")?;
+                    if let Some(args) = rate_limit.dyn_ref::<Object>() {
+                        let mut rate_limit = RateLimit::default();
+                        if let Some(messages_per_second) = args.get_value("messagesPerSecond")?.as_f64() {
+                            rate_limit.messages_per_second = Some(messages_per_second as u32);
+                        }
+                        if let Some(bytes_per_second) = args.get_value("bytesPerSecond")?.as_f64() {
+                            rate_limit.bytes_per_second = Some(bytes_per_second as u64);
+                        }
+                        config.rate_limit = Some(rate_limit);
+                    }
                     config
                 } else {
                     Default::default()
@@ -146,6 +276,41 @@ impl Default for WebSocketNodeJsConfig {
     }
 }
 
+impl WebSocketNodeJsConfig {
+    /// Apply custom upgrade request headers; the `Origin` header
+    /// is supplied separately as required by the Node.js client.
+    pub fn with_headers(
+        mut self,
+        headers: &[(String, String)],
+    ) -> std::result::Result<Self, workflow_wasm::error::Error> {
+        if headers.is_empty() {
+            return Ok(self);
+        }
+        let object = Object::new();
+        for (name, value) in headers {
+            if name.eq_ignore_ascii_case("origin") {
+                self.origin = JsValue::from(value);
+            } else {
+                object.set(name, &JsValue::from(value))?;
+            }
+        }
+        self.headers = object.into();
+        Ok(self)
+    }
+
+    /// Apply subprotocols offered to the server
+    pub fn with_protocols(mut self, protocols: &[String]) -> Self {
+        if !protocols.is_empty() {
+            self.protocols = protocols
+                .iter()
+                .map(JsValue::from)
+                .collect::<js_sys::Array>()
+                .into();
+        }
+        self
+    }
+}
+
 impl TryFrom<&WebSocketConfig> for WebSocketNodeJsConfig {
     type Error = Error;
     fn try_from(config: &WebSocketConfig) -> Result<Self> {
@@ -0,0 +1,264 @@
+//!
+//! TCP connection establishment for native WebSocket connections
+//! using Happy Eyeballs (RFC 8305)
+//!
+
+use super::{proxy, DnsResolver, Socks5Proxy};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::io::{Error, ErrorKind, Result};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{lookup_host, TcpStream};
+
+/// Delay before starting the connection attempt to the next resolved
+/// address while the previous attempts are still in progress
+/// (the "Connection Attempt Delay" recommended by RFC 8305)
+pub const CONNECTION_ATTEMPT_DELAY_MILLIS: u64 = 250;
+
+/// Connect to the first reachable address of `host`.  All resolved
+/// addresses are attempted, alternating between IPv6 and IPv4 addresses,
+/// starting a new attempt every [`CONNECTION_ATTEMPT_DELAY_MILLIS`] (or
+/// as soon as the previous attempt fails) without cancelling the attempts
+/// in progress.  The first established connection is returned and the
+/// remaining attempts are dropped.  If the SOCKS5 `proxy` applies to the
+/// host, the connection is established via the proxy, which resolves the
+/// host; onion service hosts are never resolved locally.
+pub(crate) async fn connect(
+    host: &str,
+    port: u16,
+    dns_resolver: Option<&Arc<dyn DnsResolver>>,
+    proxy: Option<&Socks5Proxy>,
+) -> Result<TcpStream> {
+    match proxy {
+        Some(proxy) if proxy.is_proxied(host) => {
+            return proxy::connect(proxy, host, port).await;
+        }
+        _ if proxy::is_onion(host) => {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                format!("connecting to the onion service `{host}` requires a SOCKS5 proxy"),
+            ));
+        }
+        _ => {}
+    }
+
+    let addrs = interleave(resolve(host, port, dns_resolver).await?);
+    if addrs.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("no addresses resolved for `{host}`"),
+        ));
+    }
+
+    let delay = Duration::from_millis(CONNECTION_ATTEMPT_DELAY_MILLIS);
+    let mut pending = addrs.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+
+    loop {
+        match pending.next() {
+            Some(addr) => attempts.push(TcpStream::connect(addr)),
+            None if attempts.is_empty() => break,
+            None => {}
+        }
+
+        let next_attempt = tokio::time::sleep(delay);
+        tokio::pin!(next_attempt);
+        loop {
+            tokio::select! {
+                result = attempts.next(), if !attempts.is_empty() => match result {
+                    Some(Ok(stream)) => return Ok(stream),
+                    Some(Err(err)) => {
+                        last_error = Some(err);
+                        if attempts.is_empty() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = &mut next_attempt, if pending.len() > 0 => break,
+                else => break,
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::new(ErrorKind::NotConnected, "connection failed")))
+}
+
+/// Resolve the host using the custom resolver (if supplied) or the system resolver
+async fn resolve(
+    host: &str,
+    port: u16,
+    dns_resolver: Option<&Arc<dyn DnsResolver>>,
+) -> Result<Vec<SocketAddr>> {
+    // IPv6 literals are supplied in the URL form (`[::1]`)
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+
+    match dns_resolver {
+        Some(dns_resolver) => dns_resolver
+            .resolve(host, port)
+            .await
+            .map_err(|err| Error::other(err.to_string())),
+        None => Ok(lookup_host((host, port)).await?.collect()),
+    }
+}
+
+/// Order the resolved addresses alternating between address families,
+/// starting with the family of the first address returned by the resolver
+fn interleave(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let Some(first) = addrs.first() else {
+        return addrs;
+    };
+    let ipv6 = first.is_ipv6();
+    let len = addrs.len();
+    let (preferred, other): (Vec<_>, Vec<_>) =
+        addrs.into_iter().partition(|addr| addr.is_ipv6() == ipv6);
+
+    let mut other = other.into_iter();
+    let mut addrs = Vec::with_capacity(len);
+    for addr in preferred {
+        addrs.push(addr);
+        addrs.extend(other.next());
+    }
+    addrs.extend(other);
+    addrs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::result::Result as ClientResult;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Resolver returning the supplied addresses
+    struct StaticResolver {
+        addrs: Vec<SocketAddr>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DnsResolver for StaticResolver {
+        async fn resolve(&self, host: &str, _port: u16) -> ClientResult<Vec<SocketAddr>> {
+            assert_eq!(host, "example.test");
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.addrs.clone())
+        }
+    }
+
+    fn addrs(addrs: &[&str]) -> Vec<SocketAddr> {
+        addrs.iter().map(|addr| addr.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_interleave() {
+        assert!(interleave(Vec::new()).is_empty());
+
+        let ipv4 = addrs(&["127.0.0.1:80", "127.0.0.2:80"]);
+        assert_eq!(interleave(ipv4.clone()), ipv4);
+
+        // the family of the first address is preferred
+        let mixed = addrs(&["[::1]:80", "[::2]:80", "[::3]:80", "127.0.0.1:80"]);
+        assert_eq!(
+            interleave(mixed),
+            addrs(&["[::1]:80", "127.0.0.1:80", "[::2]:80", "[::3]:80"])
+        );
+        let mixed = addrs(&["127.0.0.1:80", "[::1]:80", "[::2]:80", "[::3]:80"]);
+        assert_eq!(
+            interleave(mixed),
+            addrs(&["127.0.0.1:80", "[::1]:80", "[::2]:80", "[::3]:80"])
+        );
+        let mixed = addrs(&["[::1]:80", "[::2]:80", "127.0.0.1:80", "127.0.0.2:80"]);
+        assert_eq!(
+            interleave(mixed),
+            addrs(&["[::1]:80", "127.0.0.1:80", "[::2]:80", "127.0.0.2:80"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_ip_literal() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stream = connect("127.0.0.1", port, None, None).await.unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_connect_dns_resolver() {
+        // the port of the dropped listener refuses the connection
+        let refused = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let resolver = Arc::new(StaticResolver {
+            addrs: vec![refused, listener.local_addr().unwrap()],
+            calls: AtomicUsize::new(0),
+        });
+        let dns_resolver: Arc<dyn DnsResolver> = resolver.clone();
+
+        // the next address is attempted once the first attempt fails
+        let stream = connect("example.test", 0, Some(&dns_resolver), None)
+            .await
+            .unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+        assert_eq!(resolver.calls.load(Ordering::SeqCst), 1);
+
+        // the resolver is not invoked for the IP address literals
+        let port = listener.local_addr().unwrap().port();
+        connect("127.0.0.1", port, Some(&dns_resolver), None)
+            .await
+            .unwrap();
+        assert_eq!(resolver.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connect_onion() {
+        // the onion services are never resolved locally
+        let err = connect("example.onion", 80, None, None).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        // the proxy receives the hostname of the onion service
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy =
+            Socks5Proxy::new(listener.local_addr().unwrap().to_string()).with_onion_only(true);
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [0x05, 0x01, 0x00]);
+            stream.write_all(&[0x05, 0x00]).await.unwrap();
+
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).await.unwrap();
+            assert_eq!(request, [0x05, 0x01, 0x00, 0x03, 13]);
+            let mut host = [0u8; 15];
+            stream.read_exact(&mut host).await.unwrap();
+            assert_eq!(&host[..13], b"example.onion");
+            assert_eq!(u16::from_be_bytes([host[13], host[14]]), 80);
+            stream
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+        });
+        connect("example.onion", 80, None, Some(&proxy))
+            .await
+            .unwrap();
+        server.await.unwrap();
+
+        // the other hosts are connected directly with the `onion_only` proxy
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let proxy = Socks5Proxy::new("127.0.0.1:1").with_onion_only(true);
+        connect("127.0.0.1", port, None, Some(&proxy))
+            .await
+            .unwrap();
+    }
+}
@@ -20,7 +20,7 @@ pub enum Error {
     #[error("Missing WebSocket URL (must be supplied in constructor or the connect() method)")]
     MissingUrl,
 
-    #[error("WebSocket URL must start with ws:// or wss:// - supplied argument is:`{0}`")]
+    #[error("WebSocket URL must start with ws://, wss:// or ws+unix:// (native client only) - supplied argument is:`{0}`")]
     AddressSchema(String),
 
     #[error("Invalid message type")]
@@ -47,9 +47,48 @@ pub enum Error {
     #[error("Unable to connect to {0}")]
     Connect(String),
 
+    #[error("Unable to connect to {0}: {1}")]
+    ConnectFailed(String, String),
+
+    #[error("Server selected a subprotocol that was not offered: `{0}`")]
+    Subprotocol(String),
+
+    #[error("WebSocket send timeout")]
+    SendTimeout,
+
+    #[error("WebSocket send cancelled")]
+    SendCancelled,
+
+    #[error("Subscription lagged behind, {0} items have been dropped")]
+    Lagged(u64),
+
+    #[error("WebSocket offline queue is full")]
+    QueueFull,
+
+    #[error("Invalid overflow policy: {0}")]
+    InvalidOverflowPolicy(String),
+
+    #[error("WebSocket receiver channel overflow")]
+    ReceiverOverflow,
+
+    #[error("WebSocket ping timeout")]
+    PingTimeout,
+
+    #[error("WebSocket ping requires the echo ping prefix (WebSocketConfig::ping_echo) in the WASM client")]
+    PingUnsupported,
+
+    #[error("WebSocket keepalive timeout")]
+    KeepaliveTimeout,
+
+    #[error("Unable to connect after {0} reconnect attempts")]
+    ReconnectExhausted(u32),
+
     #[error("Handshake negotiation failure (internal)")]
     NegotiationFailure,
 
+    #[error("Handshake negotiation timeout")]
+    HandshakeTimeout,
+
     #[error("Dispatch channel ack error")]
     DispatchChannelAck,
 
@@ -87,11 +126,26 @@ pub enum Error {
     #[error("Connection timeout")]
     ConnectionTimeout,
 
+    #[error("WebSocket connect cancelled")]
+    Cancelled,
+
     #[error("Invalid connect strategy argument: {0}")]
     InvalidConnectStrategyArg(String),
 
     #[error("Invalid connect strategy")]
     InvalidConnectStrategy,
+
+    #[error("Invalid failover argument: {0}")]
+    InvalidFailover(String),
+
+    #[error("Invalid HTTP header `{0}`")]
+    InvalidHeader(String),
+
+    #[error("TLS error: {0}")]
+    Tls(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 impl Error {
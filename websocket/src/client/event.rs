@@ -0,0 +1,49 @@
+//!
+//! WebSocket connection events
+//!
+
+/// Close code reported when the connection is closed normally by the client
+pub const CLOSE_CODE_NORMAL: u16 = 1000;
+/// Close code reported when the connection has been lost without receiving
+/// a close frame (as defined by RFC 6455, never sent over the wire)
+pub const CLOSE_CODE_ABNORMAL: u16 = 1006;
+
+/// Connection state change event delivered to the channels created
+/// using [`WebSocket::events()`](super::WebSocket::events).  Unlike the
+/// receiver channel [`Message`](super::Message), events carry the details
+/// of the state change, such as the close code and reason supplied by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// Connection has been opened (and the handshake, if any, has succeeded),
+    /// `protocol` is the subprotocol selected by the server (if any)
+    Open { protocol: Option<String> },
+    /// Connection has been closed
+    Close {
+        /// Close code (see RFC 6455 section 7.4)
+        code: u16,
+        /// Close reason supplied by the server
+        reason: String,
+        /// `true` if the connection has been closed using the closing handshake
+        was_clean: bool,
+    },
+    /// Connection error (including failure to connect)
+    Error(String),
+    /// Client is going to attempt to reconnect after the retry delay,
+    /// `attempt` is the number of the reconnect attempt (starting from `1`)
+    Reconnecting { attempt: u32 },
+}
+
+impl Event {
+    /// Close event for a connection lost without receiving a close frame
+    pub fn abnormal_close() -> Self {
+        Event::Close {
+            code: CLOSE_CODE_ABNORMAL,
+            reason: String::new(),
+            was_clean: false,
+        }
+    }
+
+    pub fn is_close(&self) -> bool {
+        matches!(self, Event::Close { .. })
+    }
+}
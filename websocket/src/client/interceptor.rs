@@ -0,0 +1,61 @@
+//!
+//! Message interceptors invoked by the WebSocket dispatcher
+//!
+
+use super::message::Message;
+use std::sync::Arc;
+
+///
+/// Interceptor registered using [`ConnectOptions::with_interceptor()`](super::ConnectOptions::with_interceptor),
+/// allowing the messages to be transformed (e.g. compressed, encrypted or
+/// adapted to a different protocol), traced or dropped (by returning `None`)
+/// without modifying the client.
+///
+/// Outbound messages are passed through the interceptors in the order of
+/// registration and inbound messages in the reverse order (the first
+/// registered interceptor is the closest to the application).  Interceptors
+/// apply to the text, binary, ping and pong messages exchanged by the
+/// dispatcher; the [`Handshake`](super::Handshake) negotiation, keepalive
+/// pings and [`WebSocket::send_stream()`](super::WebSocket::send_stream)
+/// bypass the interceptors.  An outbound message dropped by an interceptor
+/// is acknowledged as sent.
+///
+pub trait Interceptor: Send + Sync + 'static {
+    fn on_outbound(&self, message: Message) -> Option<Message> {
+        Some(message)
+    }
+
+    fn on_inbound(&self, message: Message) -> Option<Message> {
+        Some(message)
+    }
+}
+
+impl std::fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+/// Interceptor chain executed by the dispatcher
+pub(crate) struct Interceptors(Vec<Arc<dyn Interceptor>>);
+
+impl Interceptors {
+    pub fn new(interceptors: &[Arc<dyn Interceptor>]) -> Self {
+        Interceptors(interceptors.to_vec())
+    }
+
+    pub fn outbound(&self, message: Message) -> Option<Message> {
+        self.0.iter().try_fold(message, |message, interceptor| {
+            interceptor.on_outbound(message)
+        })
+    }
+
+    pub fn inbound(&self, message: Message) -> Option<Message> {
+        self.0
+            .iter()
+            .rev()
+            .try_fold(message, |message, interceptor| {
+                interceptor.on_inbound(message)
+            })
+    }
+}
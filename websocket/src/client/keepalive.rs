@@ -0,0 +1,149 @@
+//!
+//! Keepalive (dead connection detection) options
+//!
+
+use super::message::Message;
+use futures::StreamExt;
+use workflow_core::task::Interval;
+use workflow_core::time::{Duration, Instant};
+
+pub const DEFAULT_KEEPALIVE_INTERVAL_MILLIS: u64 = 30_000;
+pub const DEFAULT_KEEPALIVE_TIMEOUT_MILLIS: u64 = 10_000;
+
+///
+/// Keepalive options.  When enabled, the client sends a Ping every
+/// `interval` and, if no message (including the Pong) is received
+/// within `timeout` of the Ping, the connection is considered dead:
+/// it is dropped and the client reconnects according to the
+/// [`ConnectStrategy`](super::ConnectStrategy).
+///
+/// The native client sends protocol-level Ping frames.  Browsers and
+/// the Node.js client do not expose Ping frames, therefore the WASM
+/// client sends the application-level `message` instead (which the
+/// server is expected to respond to); if `message` is not supplied,
+/// keepalive is disabled in the WASM client.
+///
+#[derive(Clone, Debug)]
+pub struct Keepalive {
+    pub interval: Duration,
+    pub timeout: Duration,
+    /// Application-level ping message (WASM client only)
+    pub message: Option<Message>,
+}
+
+impl Default for Keepalive {
+    fn default() -> Self {
+        Keepalive {
+            interval: Duration::from_millis(DEFAULT_KEEPALIVE_INTERVAL_MILLIS),
+            timeout: Duration::from_millis(DEFAULT_KEEPALIVE_TIMEOUT_MILLIS),
+            message: None,
+        }
+    }
+}
+
+impl Keepalive {
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Keepalive {
+            interval,
+            timeout,
+            message: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<Message>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Action to be taken by the dispatcher on a keepalive tick
+pub(crate) enum KeepaliveAction {
+    None,
+    Ping,
+    Timeout,
+}
+
+/// Keepalive state tracked by the dispatcher for the duration of a connection
+pub(crate) struct KeepaliveState {
+    keepalive: Keepalive,
+    interval: Option<Interval>,
+    last_ping: Instant,
+    ping_sent: Option<Instant>,
+}
+
+impl KeepaliveState {
+    pub fn new(keepalive: Option<Keepalive>) -> Self {
+        // check often enough to detect the timeout with reasonable precision
+        let interval = keepalive.as_ref().map(|keepalive| {
+            workflow_core::task::interval(keepalive.interval.min(keepalive.timeout) / 2)
+        });
+        KeepaliveState {
+            keepalive: keepalive.unwrap_or_default(),
+            interval,
+            last_ping: Instant::now(),
+            ping_sent: None,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn message(&self) -> Option<&Message> {
+        self.keepalive.message.as_ref()
+    }
+
+    /// Resolves on each keepalive tick (never resolves if keepalive is disabled)
+    pub async fn tick(&mut self) {
+        match self.interval.as_mut() {
+            Some(interval) => {
+                interval.next().await;
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Register activity on the connection, returns the time elapsed
+    /// since the keepalive ping if a response to the ping was pending
+    pub fn received(&mut self) -> Option<Duration> {
+        self.ping_sent.take().map(|ping_sent| ping_sent.elapsed())
+    }
+
+    pub fn action(&mut self) -> KeepaliveAction {
+        match self.ping_sent {
+            Some(ping_sent) if ping_sent.elapsed() >= self.keepalive.timeout => {
+                KeepaliveAction::Timeout
+            }
+            None if self.last_ping.elapsed() >= self.keepalive.interval => {
+                let now = Instant::now();
+                self.last_ping = now;
+                self.ping_sent = Some(now);
+                KeepaliveAction::Ping
+            }
+            _ => KeepaliveAction::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keepalive_action() {
+        let keepalive = Keepalive::new(Duration::ZERO, Duration::from_secs(60));
+        let mut state = KeepaliveState::new(Some(keepalive));
+        assert!(matches!(state.action(), KeepaliveAction::Ping));
+        // no further pings until the pending ping is answered
+        assert!(matches!(state.action(), KeepaliveAction::None));
+        assert!(state.received().is_some());
+        assert!(state.received().is_none());
+        assert!(matches!(state.action(), KeepaliveAction::Ping));
+
+        let keepalive = Keepalive::new(Duration::ZERO, Duration::ZERO);
+        let mut state = KeepaliveState::new(Some(keepalive));
+        assert!(matches!(state.action(), KeepaliveAction::Ping));
+        assert!(matches!(state.action(), KeepaliveAction::Timeout));
+
+        let keepalive = Keepalive::new(Duration::from_secs(60), Duration::ZERO);
+        let mut state = KeepaliveState::new(Some(keepalive));
+        assert!(matches!(state.action(), KeepaliveAction::None));
+    }
+}
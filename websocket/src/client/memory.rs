@@ -0,0 +1,264 @@
+//!
+//! In-memory (loopback) transport connecting the WebSocket to an in-process peer
+//!
+
+use super::{
+    error::Error, event::Event, interceptor::Interceptors, message::CloseFrame, result::Result,
+    subscribe::ReceiverChannel, Ack, ConnectOptions, Handshake, Message,
+};
+use async_trait::async_trait;
+use futures::{select_biased, Future, FutureExt};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use workflow_core::channel::*;
+use workflow_log::log_trace;
+
+///
+/// In-process peer of the [`MemoryTransport`], taking the place of the
+/// WebSocket server.  The handler receives the messages sent by the client
+/// and responds using the supplied [`MemorySender`].  The `workflow-rpc`
+/// crate provides the `RpcMemoryHandler` serving an RPC interface.
+///
+#[async_trait]
+pub trait MemoryHandler: Send + Sync + 'static {
+    /// Invoked when the client connects (before the client [`Handshake`]),
+    /// an error fails the connection attempt
+    async fn connect(&self, _sender: &MemorySender) -> Result<()> {
+        Ok(())
+    }
+
+    /// Invoked for every message sent by the client (including the
+    /// messages of the handshake negotiation), an error is returned
+    /// to the client awaiting [`WebSocket::send()`](super::WebSocket::send)
+    async fn message(&self, message: Message, sender: &MemorySender) -> Result<()>;
+
+    /// Invoked once the connection has been closed
+    async fn disconnect(&self) {}
+}
+
+/// Sender delivering the messages of the [`MemoryHandler`] to the client
+#[derive(Clone)]
+pub struct MemorySender {
+    channel: Sender<Message>,
+}
+
+impl MemorySender {
+    /// Deliver the message to the receiver channel of the client
+    pub async fn send(&self, message: Message) -> Result<()> {
+        self.channel
+            .send(message)
+            .await
+            .map_err(|_| Error::NotConnected)
+    }
+
+    /// Close the connection (the client does not reconnect)
+    pub async fn close(&self) -> Result<()> {
+        self.send(Message::Close).await
+    }
+}
+
+///
+/// Loopback transport wiring the WebSocket directly to the in-process
+/// [`MemoryHandler`] instead of connecting to the server, allowing RPC
+/// interfaces and handshakes to be tested without opening sockets (native
+/// and WASM).  Enabled using [`ConnectOptions::with_memory_transport()`].
+///
+/// The transport relays the messages of the WebSocket sender and receiver
+/// channels, invoking the [`Handshake`], [`ConnectionInitializer`](super::ConnectionInitializer)
+/// and [`Interceptor`](super::Interceptor)s as the client does and flushing
+/// the offline queue once connected.  The URL, connect strategy, keepalive
+/// and rate limit are not used; the connection is not re-established once
+/// closed by the handler.
+///
+#[derive(Clone)]
+pub struct MemoryTransport {
+    handler: Arc<dyn MemoryHandler>,
+}
+
+impl std::fmt::Debug for MemoryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryTransport").finish_non_exhaustive()
+    }
+}
+
+impl MemoryTransport {
+    pub fn new(handler: Arc<dyn MemoryHandler>) -> Self {
+        MemoryTransport { handler }
+    }
+
+    /// Connect to the handler and perform the client handshake
+    pub(crate) async fn open<'a>(
+        &self,
+        sender_channel: &'a Channel<(Message, Ack)>,
+        receiver_channel: &'a ReceiverChannel,
+        handshake: Option<Arc<dyn Handshake>>,
+        options: &ConnectOptions,
+    ) -> Result<MemoryConnection<'a>> {
+        let inbound = Channel::unbounded();
+        let connection = MemoryConnection {
+            handler: self.handler.clone(),
+            sender: MemorySender {
+                channel: inbound.sender.clone(),
+            },
+            inbound: inbound.receiver,
+            sender_channel,
+            receiver_channel,
+            interceptors: Interceptors::new(&options.interceptors),
+        };
+
+        connection.handler.connect(&connection.sender).await?;
+        if let Some(handshake) = handshake {
+            let negotiation = connection.handshake(handshake);
+            match options.handshake_timeout {
+                Some(handshake_timeout) => {
+                    select_biased! {
+                        result = negotiation.fuse() => result?,
+                        _ = workflow_core::task::sleep(handshake_timeout).fuse() => {
+                            connection.handler.disconnect().await;
+                            return Err(Error::HandshakeTimeout);
+                        }
+                    }
+                }
+                None => negotiation.await?,
+            }
+        }
+
+        Ok(connection)
+    }
+}
+
+/// Connection to the [`MemoryHandler`] established by [`MemoryTransport::open()`]
+pub(crate) struct MemoryConnection<'a> {
+    handler: Arc<dyn MemoryHandler>,
+    sender: MemorySender,
+    inbound: Receiver<Message>,
+    sender_channel: &'a Channel<(Message, Ack)>,
+    receiver_channel: &'a ReceiverChannel,
+    interceptors: Interceptors,
+}
+
+impl MemoryConnection<'_> {
+    /// Relay the handshake negotiation between the handshake and the handler
+    async fn handshake(&self, handshake: Arc<dyn Handshake>) -> Result<()> {
+        let (sender_tx, sender_rx) = unbounded();
+        let (receiver_tx, receiver_rx) = unbounded();
+        let (accept_tx, accept_rx) = oneshot();
+
+        workflow_core::task::spawn(async move {
+            accept_tx
+                .send(
+                    handshake
+                        .handshake_with_response(&sender_tx, &receiver_rx, None)
+                        .await,
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    log_trace!("WebSocket handshake unable to send completion: `{}`", err)
+                });
+        });
+
+        let result = loop {
+            select_biased! {
+                result = accept_rx.recv().fuse() => {
+                    break match result {
+                        Ok(result) => result,
+                        Err(err) => Err(err.into()),
+                    };
+                },
+                msg = sender_rx.recv().fuse() => {
+                    if let Ok(msg) = msg {
+                        if let Err(err) = self.handler.message(msg, &self.sender).await {
+                            break Err(err);
+                        }
+                    }
+                },
+                msg = self.inbound.recv().fuse() => {
+                    match msg {
+                        Ok(Message::Close) | Err(_) => break Err(Error::NegotiationFailure),
+                        Ok(msg) => receiver_tx.send(msg).await?,
+                    }
+                }
+            }
+        };
+
+        if result.is_err() {
+            self.handler.disconnect().await;
+        }
+        result
+    }
+
+    /// Relay the messages until the connection is closed by the handler or
+    /// the `shutdown` future resolves, returns the [`Event::Close`] event
+    pub async fn dispatch(
+        self,
+        pending: VecDeque<Message>,
+        shutdown: impl Future<Output = CloseFrame>,
+    ) -> Result<Event> {
+        for msg in pending {
+            if let Some(msg) = self.interceptors.outbound(msg) {
+                if let Err(err) = self.handler.message(msg, &self.sender).await {
+                    log_trace!("WebSocket unable to send queued message: {}", err);
+                }
+            }
+        }
+
+        let shutdown = shutdown.fuse();
+        futures::pin_mut!(shutdown);
+        let event = loop {
+            select_biased! {
+                msg = self.inbound.recv().fuse() => {
+                    match msg {
+                        Ok(Message::Close) | Err(_) => {
+                            break Event::Close {
+                                code: CloseFrame::default().code,
+                                reason: String::new(),
+                                was_clean: true,
+                            };
+                        }
+                        Ok(msg) => {
+                            if let Some(msg) = self.interceptors.inbound(msg) {
+                                match self.receiver_channel.send(msg).await {
+                                    Err(Error::ReceiverOverflow) => {
+                                        break Event::Close {
+                                            code: CloseFrame::default().code,
+                                            reason: Error::ReceiverOverflow.to_string(),
+                                            was_clean: true,
+                                        };
+                                    }
+                                    result => result?,
+                                }
+                            }
+                        }
+                    }
+                },
+                msg = self.sender_channel.recv().fuse() => {
+                    let (msg, ack) = msg?;
+                    let result = match self.interceptors.outbound(msg) {
+                        Some(msg) => self.handler.message(msg, &self.sender).await,
+                        None => Ok(()),
+                    };
+                    match (result, ack) {
+                        (result, Some(ack_sender)) => {
+                            ack_sender.send(result.map(Arc::new).map_err(Arc::new)).await?;
+                        }
+                        (Err(err), None) => {
+                            log_trace!("WebSocket memory transport handler error: {}", err);
+                        }
+                        (Ok(_), None) => {}
+                    }
+                },
+                frame = shutdown => {
+                    break Event::Close {
+                        code: frame.code,
+                        reason: frame.reason,
+                        was_clean: true,
+                    };
+                }
+            }
+        };
+
+        self.handler.disconnect().await;
+        self.receiver_channel.send(Message::Close).await?;
+        Ok(event)
+    }
+}
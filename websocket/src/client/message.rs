@@ -1,4 +1,6 @@
 use super::error::Error;
+use super::event::CLOSE_CODE_NORMAL;
+use bytes::Bytes;
 use std::sync::Arc;
 use workflow_core::channel::*;
 
@@ -18,29 +20,82 @@ use workflow_core::channel::*;
 pub enum Message {
     /// Text message
     Text(String),
-    /// Binary message
-    Binary(Vec<u8>),
+    /// Binary message. The payload is a reference-counted [`Bytes`]
+    /// buffer: cloning the message (e.g. to retain it for a retry) does
+    /// not copy the payload.  Converting from `Vec<u8>` takes ownership of
+    /// the vector without copying; converting into `Vec<u8>` (as the native
+    /// client does for the underlying `tungstenite` message) copies the
+    /// payload only if the buffer is shared.
+    Binary(Bytes),
+    /// Ping control frame (native client only, browsers do not expose
+    /// WebSocket control frames).  Received pings are delivered to the
+    /// receiver channel only if enabled using
+    /// [`WebSocketConfig::receive_ping_pong`](super::WebSocketConfig::receive_ping_pong);
+    /// the client responds to pings automatically.
+    Ping(Vec<u8>),
+    /// Pong control frame (native client only, see [`Message::Ping`])
+    Pong(Vec<u8>),
     /// Connection has Opened
     Open,
     /// Connection has Closed
     Close,
+    /// Reconnect attempts have been exhausted (see
+    /// [`ConnectOptions::max_reconnect_attempts`](super::ConnectOptions::max_reconnect_attempts)),
+    /// the client has stopped reconnecting
+    ReconnectExhausted,
 }
 
-impl From<Message> for Vec<u8> {
-    fn from(msg: Message) -> Self {
+/// Maximum time to wait for the server to respond to the Close frame
+pub const CLOSE_HANDSHAKE_TIMEOUT_MILLIS: u64 = 5_000;
+
+/// Close frame sent to the server when closing the connection
+/// using [`WebSocket::disconnect_with()`](super::WebSocket::disconnect_with).
+/// Note that browsers accept only the code `1000` or codes in the range
+/// `3000..=4999`, and the reason must not exceed 123 bytes (UTF-8).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CloseFrame {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl CloseFrame {
+    pub fn new(code: u16, reason: impl Into<String>) -> Self {
+        CloseFrame {
+            code,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Default for CloseFrame {
+    fn default() -> Self {
+        CloseFrame::new(CLOSE_CODE_NORMAL, "")
+    }
+}
+
+/// Payload of a [`Message::Text`] or [`Message::Binary`] message,
+/// fails with [`Error::InvalidMessageType`] for other message types.
+impl TryFrom<Message> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(msg: Message) -> Result<Self, Self::Error> {
         match msg {
-            Message::Text(string) => string.into(),
-            Message::Binary(vec) => vec,
-            _ => {
-                panic!("WebSocket - From<Message> for Vec<u8>: unsupported message type: {msg:?}",);
-            }
+            Message::Text(string) => Ok(string.into()),
+            Message::Binary(bytes) => Ok(bytes.into()),
+            _ => Err(Error::InvalidMessageType),
         }
     }
 }
 
 impl From<Vec<u8>> for Message {
     fn from(vec: Vec<u8>) -> Self {
-        Message::Binary(vec)
+        Message::Binary(vec.into())
+    }
+}
+
+impl From<Bytes> for Message {
+    fn from(bytes: Bytes) -> Self {
+        Message::Binary(bytes)
     }
 }
 
@@ -56,14 +111,16 @@ impl From<&str> for Message {
     }
 }
 
-impl AsRef<[u8]> for Message {
-    fn as_ref(&self) -> &[u8] {
-        match self {
-            Message::Text(string) => string.as_ref(),
-            Message::Binary(vec) => vec.as_ref(),
-            _ => {
-                panic!("WebSocket - AsRef<[u8]> for Message: unsupported message type: {self:?}",);
-            }
+/// Payload of a [`Message::Text`] or [`Message::Binary`] message,
+/// fails with [`Error::InvalidMessageType`] for other message types.
+impl<'msg> TryFrom<&'msg Message> for &'msg [u8] {
+    type Error = Error;
+
+    fn try_from(msg: &'msg Message) -> Result<Self, Self::Error> {
+        match msg {
+            Message::Text(string) => Ok(string.as_bytes()),
+            Message::Binary(bytes) => Ok(bytes.as_ref()),
+            _ => Err(Error::InvalidMessageType),
         }
     }
 }
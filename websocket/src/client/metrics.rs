@@ -0,0 +1,90 @@
+//!
+//! WebSocket connection statistics
+//!
+
+use super::message::Message;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use workflow_core::time::{Duration, Instant};
+
+/// Connection statistics returned by [`WebSocket::metrics()`](super::WebSocket::metrics).
+/// Byte counters include only the payload of text and binary messages
+/// (excluding the WebSocket framing and control frames).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    /// Number of times the connection has been re-established
+    pub reconnects: u64,
+    /// Round-trip time measured by the last keepalive ping (see
+    /// [`Keepalive`](super::Keepalive)); in the WASM client this is the
+    /// time until the first message received after the keepalive message
+    pub rtt: Option<Duration>,
+    /// Time elapsed since the current connection has been opened
+    /// (`None` if not connected)
+    pub uptime: Option<Duration>,
+}
+
+/// Counters updated by the dispatcher
+#[derive(Default)]
+pub(crate) struct MetricsCounters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    messages_sent: AtomicU64,
+    messages_received: AtomicU64,
+    connections: AtomicU64,
+    rtt: Mutex<Option<Duration>>,
+    connected_at: Mutex<Option<Instant>>,
+}
+
+impl MetricsCounters {
+    pub fn sent(&self, len: usize) {
+        self.bytes_sent.fetch_add(len as u64, Ordering::Relaxed);
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn received(&self, len: usize) {
+        self.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn opened(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        *self.connected_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub fn closed(&self) {
+        *self.connected_at.lock().unwrap() = None;
+    }
+
+    pub fn rtt(&self, rtt: Duration) {
+        *self.rtt.lock().unwrap() = Some(rtt);
+    }
+
+    pub fn snapshot(&self) -> Metrics {
+        Metrics {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            reconnects: self.connections.load(Ordering::Relaxed).saturating_sub(1),
+            rtt: *self.rtt.lock().unwrap(),
+            uptime: self
+                .connected_at
+                .lock()
+                .unwrap()
+                .map(|connected_at| connected_at.elapsed()),
+        }
+    }
+}
+
+/// Payload length of a text or binary message
+pub(crate) fn payload_len(message: &Message) -> usize {
+    match message {
+        Message::Text(text) => text.len(),
+        Message::Binary(data) => data.len(),
+        _ => 0,
+    }
+}
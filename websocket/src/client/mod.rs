@@ -8,30 +8,68 @@ cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         mod wasm;
         use wasm::WebSocketInterface;
+
+        /// Unix domain sockets are not available in WASM
+        fn is_unix_url(_url: &str) -> bool {
+            false
+        }
     } else {
+        mod dial;
         mod native;
+        mod stream;
+        mod transport;
         use native::WebSocketInterface;
+        use transport::{is_unix_url, SingleStream};
+        pub use transport::{ClientStream, StreamConnector};
     }
 }
 
 pub mod bindings;
 pub mod config;
 pub mod error;
+pub mod event;
+pub mod interceptor;
+pub mod keepalive;
+pub mod memory;
 pub mod message;
+pub mod metrics;
 pub mod options;
+pub mod ping;
+pub mod proxy;
+pub mod queue;
+pub mod ratelimit;
 pub mod result;
+pub mod split;
+pub mod state;
+pub mod subscribe;
+pub mod tls;
 
+pub use crate::deflate::DeflateConfig;
+pub use bytes::Bytes;
 pub use config::WebSocketConfig;
 pub use error::Error;
-use futures::Future;
+pub use event::Event;
+use futures::{select, Future, FutureExt};
+pub use interceptor::Interceptor;
+pub use keepalive::Keepalive;
+pub use memory::{MemoryHandler, MemorySender, MemoryTransport};
 pub use message::*;
-pub use options::{ConnectOptions, ConnectStrategy};
+pub use metrics::Metrics;
+pub use options::{Backoff, ConnectOptions, ConnectStrategy, Failover};
+pub use proxy::Socks5Proxy;
+pub use queue::{OfflineQueue, OverflowPolicy};
+pub use ratelimit::RateLimit;
 pub use result::Result;
+pub use split::{WebSocketReceiver, WebSocketSender};
+pub use state::{State, StateChanges};
+pub use subscribe::{Broadcast, Lagged, ReceiverOverflow, Subscription};
+pub use tls::{ClientCertificate, TlsOptions};
 
 use async_trait::async_trait;
 use std::pin::Pin;
 use std::sync::Arc;
-use workflow_core::channel::{oneshot, Channel, Receiver, Sender};
+use workflow_core::channel::{oneshot, Channel, MultiplexerChannel, Receiver, Sender};
+use workflow_core::time::Duration;
 pub type ConnectResult<E> = std::result::Result<Option<Receiver<Result<()>>>, E>;
 
 pub type HandshakeFn = Arc<
@@ -43,6 +81,62 @@ pub type HandshakeFnReturn = Pin<Box<(dyn Send + Sync + 'static + Future<Output
 pub trait Handshake: Send + Sync + 'static {
     async fn handshake(&self, sender: &Sender<Message>, receiver: &Receiver<Message>)
         -> Result<()>;
+
+    /// Handshake negotiation receiving the HTTP response of the WebSocket
+    /// upgrade (e.g. to read session cookies or the server version headers).
+    /// The response is available in the native client only (`None` in WASM,
+    /// browsers do not expose the upgrade response).  Invoked by the client
+    /// in place of [`Handshake::handshake()`], which it calls by default.
+    async fn handshake_with_response(
+        &self,
+        sender: &Sender<Message>,
+        receiver: &Receiver<Message>,
+        _response: Option<&UpgradeResponse>,
+    ) -> Result<()> {
+        self.handshake(sender, receiver).await
+    }
+}
+
+/// HTTP response of the WebSocket upgrade request
+/// (see [`Handshake::handshake_with_response()`])
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpgradeResponse {
+    /// HTTP status code (`101 Switching Protocols`)
+    pub status: u16,
+    /// Response headers in the order received; a header may be
+    /// present multiple times (e.g. `Set-Cookie`)
+    pub headers: Vec<(String, String)>,
+}
+
+impl UpgradeResponse {
+    /// Value of the header `name` (case-insensitive); the first
+    /// value if the header is present multiple times
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// All values of the header `name` (case-insensitive)
+    pub fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Initializer invoked after every successful connection and reconnection
+/// (following the [`Handshake`]) with the sender handle of the WebSocket,
+/// allowing the application to re-authenticate and restore subscriptions
+/// after transparent reconnects.  The initializer runs in a separate task
+/// and may wait for its messages to be relayed; messages held in the
+/// offline queue are sent before the messages sent by the initializer.
+/// Errors returned by the initializer are reported as [`Event::Error`].
+#[async_trait]
+pub trait ConnectionInitializer: Send + Sync + 'static {
+    async fn initialize(&self, sender: &WebSocketSender) -> Result<()>;
 }
 
 #[async_trait]
@@ -50,6 +144,22 @@ pub trait Resolver: Send + Sync + 'static {
     async fn resolve_url(&self) -> ResolverResult;
 }
 pub type ResolverResult = Result<String>;
+
+/// Hostname resolver used by the native client to obtain the socket
+/// addresses of the WebSocket server (e.g. DNS-over-HTTPS, a static hosts
+/// map or resolution via a proxy), replacing the system resolver.  The
+/// resolver is not invoked for IP address literals and is ignored by
+/// the WASM client.
+#[async_trait]
+pub trait DnsResolver: Send + Sync + 'static {
+    async fn resolve(&self, host: &str, port: u16) -> Result<Vec<std::net::SocketAddr>>;
+}
+
+impl std::fmt::Debug for dyn DnsResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DnsResolver")
+    }
+}
 pub type WebSocketError = Error;
 
 struct Inner {
@@ -72,6 +182,31 @@ impl Inner {
     }
 }
 
+/// Handle to a message sent using [`WebSocket::post_with_timeout()`]
+pub struct SendHandle {
+    cancel: Sender<()>,
+    result: Receiver<std::result::Result<(), Arc<Error>>>,
+}
+
+impl SendHandle {
+    /// Stop waiting for the message to be relayed, resolving [`SendHandle::wait()`]
+    /// with [`Error::SendCancelled`].  Note that the message may still be sent
+    /// if it has already been queued for dispatch.
+    pub fn cancel(&self) {
+        self.cancel.try_send(()).ok();
+    }
+
+    /// Wait until the message has been relayed to the underlying
+    /// websocket implementation, the timeout has elapsed or the
+    /// send has been cancelled.
+    pub async fn wait(self) -> std::result::Result<(), Arc<Error>> {
+        self.result
+            .recv()
+            .await
+            .map_err(|_| Arc::new(Error::DispatchChannelAck))?
+    }
+}
+
 /// An async WebSocket implementation capable of operating
 /// uniformly under a browser-backed executor in WASM and under
 /// native tokio-runtime.
@@ -82,9 +217,13 @@ pub struct WebSocket {
 
 impl WebSocket {
     /// Create a new WebSocket instance connecting to the given URL.
+    /// On unix targets, the native client also accepts the
+    /// `ws+unix:///path/socket:/ws-path` (and `wss+unix://`) URLs,
+    /// connecting over the unix domain socket at `/path/socket` and
+    /// requesting the `/ws-path` resource (defaults to `/`).
     pub fn new(url: Option<&str>, config: Option<WebSocketConfig>) -> Result<WebSocket> {
         if let Some(url) = url {
-            if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            if !url.starts_with("ws://") && !url.starts_with("wss://") && !is_unix_url(url) {
                 return Err(Error::AddressSchema(url.to_string()));
             }
         }
@@ -103,25 +242,63 @@ impl WebSocket {
             Channel::<(Message, Ack)>::unbounded()
         };
 
-        let client = Arc::new(WebSocketInterface::new(
+        let mut client = WebSocketInterface::new(
             url,
             Some(config),
             sender_channel.clone(),
             receiver_channel.clone(),
-        )?);
+        )?;
 
         let websocket = WebSocket {
-            inner: Arc::new(Inner::new(client, sender_channel, receiver_channel)),
+            inner: Arc::new_cyclic(|inner| {
+                client.bind(inner.clone());
+                Inner::new(Arc::new(client), sender_channel, receiver_channel)
+            }),
         };
 
         Ok(websocket)
     }
 
+    /// Create a new WebSocket instance connected over the supplied stream
+    /// (native client only), e.g. a TLS session terminated by the application,
+    /// a connection bridged over QUIC or a test harness.  The WebSocket
+    /// handshake is performed over the stream using the upgrade request for
+    /// `url` (including the headers and subprotocols of the `options`).
+    /// The connection is not re-established once the stream is closed;
+    /// use [`ConnectOptions::with_stream_connector()`] to supply the stream
+    /// of every connection attempt.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn connect_with_stream<S>(
+        stream: S,
+        url: &str,
+        config: Option<WebSocketConfig>,
+        options: ConnectOptions,
+    ) -> Result<WebSocket>
+    where
+        S: ClientStream,
+    {
+        let websocket = WebSocket::new(Some(url), config)?;
+        let options = ConnectOptions {
+            block_async_connect: true,
+            strategy: ConnectStrategy::Fallback,
+            ..options
+        }
+        .with_stream_connector(Arc::new(SingleStream::new(stream)));
+        websocket.connect(options).await?;
+        Ok(websocket)
+    }
+
     /// Get current websocket connection URL
     pub fn url(&self) -> Option<String> {
         self.inner.client.current_url()
     }
 
+    /// Subprotocol selected by the server for the current connection
+    /// (see [`ConnectOptions::protocols`])
+    pub fn protocol(&self) -> Option<String> {
+        self.inner.client.protocol()
+    }
+
     /// Changes WebSocket connection URL.
     /// Following this call, you must invoke
     /// `WebSocket::reconnect().await` manually
@@ -147,11 +324,41 @@ impl WebSocket {
         &self.inner.receiver_channel.receiver
     }
 
+    /// Create a new channel receiving connection [`Event`]s
+    /// (the channel is unregistered when dropped)
+    pub fn events(&self) -> MultiplexerChannel<Event> {
+        self.inner.client.events().channel()
+    }
+
+    /// Create a new broadcast subscription receiving a copy of every inbound
+    /// message and connection event (see [`Subscription`]), allowing multiple
+    /// consumers to observe the inbound traffic independently of the
+    /// receiver channel (the subscription is unregistered when dropped)
+    pub fn subscribe(&self) -> Subscription {
+        self.inner.client.subscribe()
+    }
+
+    /// Connection statistics (counters are cumulative across reconnects)
+    pub fn metrics(&self) -> Metrics {
+        self.inner.client.metrics().snapshot()
+    }
+
     /// Returns true if websocket is connected, false otherwise
     pub fn is_connected(&self) -> bool {
         self.inner.client.is_connected()
     }
 
+    /// Current connection state
+    pub fn state(&self) -> State {
+        self.inner.client.state()
+    }
+
+    /// Create a watch-style receiver of the connection state changes
+    /// (see [`StateChanges`])
+    pub fn state_changes(&self) -> StateChanges {
+        self.inner.client.state_changes()
+    }
+
     /// Connects the websocket to the destination URL.
     /// Optionally accepts `block_until_connected` argument
     /// that will block the async execution until the websocket
@@ -173,6 +380,17 @@ impl WebSocket {
         self.inner.client.disconnect().await
     }
 
+    /// Disconnects the websocket from the destination server, sending the
+    /// Close frame with the supplied status code and reason.  Returns once
+    /// the server has responded to the Close frame (or after
+    /// [`CLOSE_HANDSHAKE_TIMEOUT_MILLIS`]).
+    pub async fn disconnect_with(&self, code: u16, reason: &str) -> Result<()> {
+        self.inner
+            .client
+            .disconnect_with(CloseFrame::new(code, reason))
+            .await
+    }
+
     /// Trigger WebSocket to reconnect.  This method
     /// closes the underlying WebSocket connection
     /// causing the WebSocket implementation to
@@ -187,7 +405,15 @@ impl WebSocket {
     /// This function enforces async yield in order to prevent
     /// potential blockage of the executor if it is being executed
     /// in tight loops.
+    ///
+    /// If the [`OfflineQueue`] is enabled in [`WebSocketConfig`], messages
+    /// posted while the WebSocket is not connected are queued and sent
+    /// once the connection is re-established.
     pub async fn post(&self, message: Message) -> Result<&Self> {
+        let Some(message) = self.inner.client.queue_if_offline(message)? else {
+            return Ok(self);
+        };
+
         if !self.inner.client.is_connected() {
             return Err(Error::NotConnected);
         }
@@ -224,6 +450,100 @@ impl WebSocket {
             .map(|_| self)
     }
 
+    /// Sends a binary message read from `reader` to the destination server
+    /// and waits until the message has been relayed to the underlying
+    /// websocket implementation.  The native client sends the message as
+    /// a fragmented message (frames of up to
+    /// [`WebSocketConfig::stream_frame_size`] bytes) without reading the
+    /// entire payload into memory; other messages are not sent until the
+    /// stream completes.  Browsers and Node.js do not support sending
+    /// fragmented messages, therefore the WASM client reads the entire
+    /// payload and sends it as a single binary message.
+    ///
+    /// Incoming fragmented messages are reassembled by the client up to
+    /// [`WebSocketConfig::max_message_size`].
+    pub async fn send_stream<R>(&self, reader: R) -> std::result::Result<&Self, Arc<Error>>
+    where
+        R: futures::io::AsyncRead + Send + 'static,
+    {
+        if !self.inner.client.is_connected() {
+            return Err(Arc::new(Error::NotConnected));
+        }
+
+        self.inner
+            .client
+            .send_stream(Box::pin(reader))
+            .await
+            .map(|_| self)
+    }
+
+    /// Number of messages in the offline queue
+    pub fn pending_len(&self) -> usize {
+        self.inner.client.pending().len()
+    }
+
+    /// Discard all messages in the offline queue
+    pub fn clear_pending(&self) {
+        self.inner.client.pending().clear()
+    }
+
+    /// Sends a message to the destination server, failing with
+    /// [`Error::SendTimeout`] if the message has not been relayed to the
+    /// underlying websocket implementation within `timeout`.  Returns
+    /// a [`SendHandle`] that can be used to wait for the result or to
+    /// cancel the pending send.
+    pub async fn post_with_timeout(
+        &self,
+        message: Message,
+        timeout: Duration,
+    ) -> Result<SendHandle> {
+        if !self.inner.client.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let (cancel_sender, cancel_receiver) = oneshot();
+        let (result_sender, result_receiver) = oneshot();
+        let this = self.clone();
+        workflow_core::task::spawn(async move {
+            let result = select! {
+                result = this.send(message).fuse() => result.map(|_| ()),
+                _ = workflow_core::task::sleep(timeout).fuse() => Err(Arc::new(Error::SendTimeout)),
+                _ = cancel_receiver.recv().fuse() => Err(Arc::new(Error::SendCancelled)),
+            };
+            result_sender.try_send(result).ok();
+        });
+        workflow_core::task::yield_now().await;
+
+        Ok(SendHandle {
+            cancel: cancel_sender,
+            result: result_receiver,
+        })
+    }
+
+    /// Measures the round-trip time to the server, failing with
+    /// [`Error::PingTimeout`] if the response has not been received within
+    /// [`DEFAULT_PING_TIMEOUT_MILLIS`](ping::DEFAULT_PING_TIMEOUT_MILLIS).
+    /// See [`WebSocket::ping_with_timeout()`].
+    pub async fn ping(&self) -> Result<Duration> {
+        self.ping_with_timeout(Duration::from_millis(ping::DEFAULT_PING_TIMEOUT_MILLIS))
+            .await
+    }
+
+    /// Measures the round-trip time to the server.  The native client sends
+    /// a Ping frame carrying a nonce and resolves once the matching Pong is
+    /// received.  Browsers do not expose Ping frames, therefore the WASM client
+    /// sends the application-level echo configured using
+    /// [`WebSocketConfig::ping_echo`] (failing with [`Error::PingUnsupported`]
+    /// if not configured).  Fails with [`Error::PingTimeout`] if the response
+    /// has not been received within `timeout`.
+    pub async fn ping_with_timeout(&self, timeout: Duration) -> Result<Duration> {
+        if !self.inner.client.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        self.inner.client.ping(timeout).await
+    }
+
     /// Receives message from the websocket. Blocks until a message is
     /// received from the underlying websocket connection.
     pub async fn recv(&self) -> Result<Message> {
@@ -1,49 +1,75 @@
 use super::{
-    error::Error, message::Message, result::Result, Ack, ConnectOptions, ConnectResult,
-    ConnectStrategy, Handshake, Resolver, WebSocketConfig,
+    error::Error,
+    event::{Event, CLOSE_CODE_NORMAL},
+    interceptor::Interceptors,
+    keepalive::{KeepaliveAction, KeepaliveState},
+    message::{CloseFrame, Message, CLOSE_HANDSHAKE_TIMEOUT_MILLIS},
+    metrics::{payload_len, MetricsCounters},
+    options::Endpoints,
+    ping::{self, PendingPings},
+    queue::{PendingQueue, QueueFull},
+    ratelimit::RateLimiter,
+    result::Result,
+    state::{State, StateCell, StateChanges},
+    stream::{OutboundStream, StreamReader},
+    subscribe::{ReceiverChannel, Subscription},
+    tls,
+    transport::{self, Transport},
+    Ack, ConnectOptions, ConnectResult, ConnectStrategy, Handshake, MemoryTransport, Resolver,
+    UpgradeResponse, WebSocket, WebSocketConfig, WebSocketSender,
 };
+use crate::deflate::{self, DeflateStream};
 use futures::{
     select_biased,
     stream::{SplitSink, SplitStream},
     FutureExt,
 };
 use futures_util::{SinkExt, StreamExt};
+use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+    Arc, Mutex, Weak,
 };
-use tokio::net::TcpStream;
 use tokio::time::timeout;
 use tokio_tungstenite::{
-    connect_async_with_config, tungstenite::protocol::Message as TsMessage, MaybeTlsStream,
-    WebSocketStream,
+    tungstenite::client::IntoClientRequest,
+    tungstenite::handshake::client::{Request, Response},
+    tungstenite::http::header::{HeaderName, HeaderValue},
+    tungstenite::protocol::frame::{coding::CloseCode, CloseFrame as TsCloseFrame, Frame},
+    tungstenite::protocol::Message as TsMessage,
+    MaybeTlsStream, WebSocketStream,
 };
 use tungstenite::protocol::WebSocketConfig as TsWebSocketConfig;
 pub use workflow_core as core;
 use workflow_core::channel::*;
+use workflow_core::time::Duration;
 pub use workflow_log::*;
 
-impl From<Message> for tungstenite::Message {
-    fn from(message: Message) -> Self {
+impl TryFrom<Message> for tungstenite::Message {
+    type Error = Error;
+
+    fn try_from(message: Message) -> Result<Self> {
         match message {
-            Message::Text(text) => text.into(),
-            Message::Binary(data) => data.into(),
-            _ => {
-                panic!("From<Message> for tungstenite::Message - invalid message type: {message:?}",)
-            }
+            Message::Text(text) => Ok(text.into()),
+            Message::Binary(data) => Ok(TsMessage::Binary(data.into())),
+            Message::Ping(data) => Ok(TsMessage::Ping(data)),
+            Message::Pong(data) => Ok(TsMessage::Pong(data)),
+            _ => Err(Error::InvalidMessageType),
         }
     }
 }
 
-impl From<tungstenite::Message> for Message {
-    fn from(message: tungstenite::Message) -> Self {
+impl TryFrom<tungstenite::Message> for Message {
+    type Error = Error;
+
+    fn try_from(message: tungstenite::Message) -> Result<Self> {
         match message {
-            TsMessage::Text(text) => Message::Text(text),
-            TsMessage::Binary(data) => Message::Binary(data),
-            TsMessage::Close(_) => Message::Close,
-            _ => panic!(
-                "TryFrom<tungstenite::Message> for Message - invalid message type: {message:?}",
-            ),
+            TsMessage::Text(text) => Ok(Message::Text(text)),
+            TsMessage::Binary(data) => Ok(Message::Binary(data.into())),
+            TsMessage::Ping(data) => Ok(Message::Ping(data)),
+            TsMessage::Pong(data) => Ok(Message::Pong(data)),
+            TsMessage::Close(_) => Ok(Message::Close),
+            _ => Err(Error::InvalidMessageType),
         }
     }
 }
@@ -61,20 +87,35 @@ impl From<WebSocketConfig> for TsWebSocketConfig {
     }
 }
 
+/// Outbound item selected by the dispatcher
+enum Outgoing {
+    Message((Message, Ack)),
+    Stream(OutboundStream),
+    Frame(std::io::Result<Frame>),
+    Closed,
+}
+
 #[derive(Default)]
 struct Settings {
     default_url: Option<String>,
     current_url: Option<String>,
+    protocol: Option<String>,
 }
 
 pub struct WebSocketInterface {
     settings: Mutex<Settings>,
     config: Mutex<WebSocketConfig>,
     reconnect: AtomicBool,
-    is_connected: AtomicBool,
-    receiver_channel: Channel<Message>,
+    state: StateCell,
+    receiver_channel: ReceiverChannel,
     sender_channel: Channel<(Message, Ack)>,
-    shutdown: DuplexChannel<()>,
+    stream_channel: Channel<OutboundStream>,
+    events: Multiplexer<Event>,
+    pending: PendingQueue,
+    metrics: MetricsCounters,
+    pings: PendingPings,
+    websocket: Weak<super::Inner>,
+    shutdown: DuplexChannel<CloseFrame>,
 }
 
 impl WebSocketInterface {
@@ -84,6 +125,8 @@ impl WebSocketInterface {
         sender_channel: Channel<(Message, Ack)>,
         receiver_channel: Channel<Message>,
     ) -> Result<WebSocketInterface> {
+        let config = config.unwrap_or_default();
+        let receiver_channel = ReceiverChannel::new(receiver_channel, config.receiver_overflow);
         let settings = Settings {
             default_url: url.map(String::from),
             ..Default::default()
@@ -91,17 +134,92 @@ impl WebSocketInterface {
 
         let iface = WebSocketInterface {
             settings: Mutex::new(settings),
-            config: Mutex::new(config.unwrap_or_default()),
+            config: Mutex::new(config),
             receiver_channel,
             sender_channel,
+            stream_channel: Channel::unbounded(),
             reconnect: AtomicBool::new(true),
-            is_connected: AtomicBool::new(false),
+            state: StateCell::new(),
+            events: Multiplexer::new(),
+            pending: PendingQueue::default(),
+            metrics: MetricsCounters::default(),
+            pings: PendingPings::default(),
+            websocket: Weak::new(),
             shutdown: DuplexChannel::unbounded(),
         };
 
         Ok(iface)
     }
 
+    /// Bind the interface to the owning [`WebSocket`](super::WebSocket)
+    pub(super) fn bind(&mut self, websocket: Weak<super::Inner>) {
+        self.websocket = websocket;
+    }
+
+    pub fn events(&self) -> &Multiplexer<Event> {
+        &self.events
+    }
+
+    pub fn pending(&self) -> &PendingQueue {
+        &self.pending
+    }
+
+    /// Create a new broadcast subscription to the inbound messages and events
+    pub fn subscribe(&self) -> Subscription {
+        let capacity = self.config.lock().unwrap().subscriber_channel_cap;
+        self.receiver_channel.subscribe(capacity)
+    }
+
+    pub fn metrics(&self) -> &MetricsCounters {
+        &self.metrics
+    }
+
+    /// Send the Ping frame carrying the nonce and wait for the matching Pong
+    pub async fn ping(self: &Arc<Self>, timeout: Duration) -> Result<Duration> {
+        self.pings
+            .ping(&self.sender_channel, timeout, |nonce| {
+                Message::Ping(ping::payload(nonce))
+            })
+            .await
+    }
+
+    /// Queue the message if the offline queue is enabled and the WebSocket
+    /// is not connected, returns the message if it has not been queued
+    pub fn queue_if_offline(
+        &self,
+        message: Message,
+    ) -> std::result::Result<Option<Message>, QueueFull> {
+        match self.config.lock().unwrap().offline_queue.as_ref() {
+            Some(options) => self.pending.push_if_offline(options, &self.state, message),
+            None => Ok(Some(message)),
+        }
+    }
+
+    /// Queue the stream for the dispatcher and wait until the
+    /// final frame has been relayed to the websocket
+    pub async fn send_stream(
+        self: &Arc<Self>,
+        reader: StreamReader,
+    ) -> std::result::Result<(), Arc<Error>> {
+        let chunk_size = self.config.lock().unwrap().stream_frame_size;
+        let (ack_sender, ack_receiver) = oneshot();
+        self.stream_channel
+            .send(OutboundStream::new(reader, chunk_size, Some(ack_sender)))
+            .await
+            .map_err(|err| Arc::new(err.into()))?;
+
+        ack_receiver
+            .recv()
+            .await
+            .map_err(|_| Arc::new(Error::DispatchChannelAck))?
+            .map(|_| ())
+    }
+
+    fn post_event(&self, event: Event) {
+        self.receiver_channel.publish_event(&event);
+        self.events.try_broadcast(event).ok();
+    }
+
     pub fn default_url(self: &Arc<Self>) -> Option<String> {
         self.settings.lock().unwrap().default_url.clone()
     }
@@ -110,6 +228,10 @@ impl WebSocketInterface {
         self.settings.lock().unwrap().current_url.clone()
     }
 
+    pub fn protocol(self: &Arc<Self>) -> Option<String> {
+        self.settings.lock().unwrap().protocol.clone()
+    }
+
     pub fn set_default_url(self: &Arc<Self>, url: &str) {
         self.settings
             .lock()
@@ -127,13 +249,42 @@ impl WebSocketInterface {
     }
 
     pub fn is_connected(self: &Arc<Self>) -> bool {
-        self.is_connected.load(Ordering::SeqCst)
+        self.state.get().is_connected()
+    }
+
+    pub fn state(&self) -> State {
+        self.state.get()
+    }
+
+    pub fn state_changes(&self) -> StateChanges {
+        self.state.watch()
     }
 
     fn resolver(&self) -> Option<Arc<dyn Resolver>> {
         self.config.lock().unwrap().resolver.clone()
     }
 
+    /// Run the connection initializer (if configured) in a separate task
+    /// (the initializer may wait for its messages to be relayed, requiring
+    /// the dispatcher to be running)
+    fn initialize(self: &Arc<Self>) {
+        let Some(initializer) = self.config.lock().unwrap().initializer.clone() else {
+            return;
+        };
+        let Some(websocket) = self.websocket.upgrade() else {
+            return;
+        };
+
+        let sender = WebSocketSender::new(WebSocket { inner: websocket });
+        let this = self.clone();
+        core::task::spawn(async move {
+            if let Err(err) = initializer.initialize(&sender).await {
+                log_trace!("WebSocket connection initializer error: {err}");
+                this.post_event(Event::Error(err.to_string()));
+            }
+        });
+    }
+
     fn handshake(&self) -> Option<Arc<dyn Handshake>> {
         self.config.lock().unwrap().handshake.clone()
     }
@@ -146,8 +297,17 @@ impl WebSocketInterface {
         self.config.lock().unwrap().clone()
     }
 
-    async fn resolve_url(self: &Arc<Self>, options: &ConnectOptions) -> Result<String> {
-        let url = if let Some(url) = options.url.as_ref().or(self.default_url().as_ref()) {
+    async fn resolve_url(
+        self: &Arc<Self>,
+        options: &ConnectOptions,
+        endpoint: usize,
+    ) -> Result<String> {
+        let url = if let Some(url) = options
+            .url
+            .as_ref()
+            .or(options.urls.get(endpoint))
+            .or(self.default_url().as_ref())
+        {
             url.clone()
         } else if let Some(resolver) = self.resolver() {
             resolver.resolve_url().await?
@@ -158,61 +318,171 @@ impl WebSocketInterface {
         Ok(url)
     }
 
+    /// Resolve the URL and create the WebSocket upgrade request including custom headers
+    /// (returns the path of the unix domain socket for the `ws+unix://` URLs)
+    async fn resolve_request(
+        self: &Arc<Self>,
+        options: &ConnectOptions,
+        endpoint: usize,
+    ) -> Result<(Request, Option<PathBuf>, String)> {
+        let url = self.resolve_url(options, endpoint).await?;
+        let (mut request, unix_socket) = if transport::is_unix_url(&url) {
+            let (unix_socket, url) = transport::unix_socket_url(&url)
+                .ok_or_else(|| Error::AddressSchema(url.clone()))?;
+            (url.into_client_request()?, Some(unix_socket))
+        } else {
+            (url.as_str().into_client_request()?, None)
+        };
+        for (name, value) in options.headers.iter() {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidHeader(name.clone()))?;
+            let header_value =
+                HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(name.clone()))?;
+            request.headers_mut().append(header_name, header_value);
+        }
+        if !options.protocols.is_empty() {
+            let protocols = options.protocols.join(", ");
+            let header_value = HeaderValue::from_str(&protocols)
+                .map_err(|_| Error::InvalidHeader("Sec-WebSocket-Protocol".to_string()))?;
+            request
+                .headers_mut()
+                .insert("Sec-WebSocket-Protocol", header_value);
+        }
+        if let Some(config) = self.config().deflate.as_ref() {
+            deflate::offer(config, &mut request);
+        }
+        Ok((request, unix_socket, url))
+    }
+
     pub async fn connect(self: &Arc<Self>, options: ConnectOptions) -> ConnectResult<Error> {
         let this = self.clone();
 
-        if self.is_connected.load(Ordering::SeqCst) {
+        if self.is_connected() {
             return Err(Error::AlreadyConnected);
         }
 
+        if let Some(transport) = options.memory_transport.clone() {
+            return self.connect_memory(transport, options).await;
+        }
+
         let (connect_trigger, connect_listener) = oneshot::<Result<()>>();
         let mut connect_trigger = Some(connect_trigger);
 
         this.reconnect.store(true, Ordering::SeqCst);
 
         let block_async_connect = options.block_async_connect;
-        let ts_websocket_config = Some(self.config().into());
+        let config = self.config();
+        let connector = config.tls.as_ref().map(tls::connector).transpose()?;
+        let deflate = config.deflate.clone();
+        let ts_websocket_config = Some(config.into());
 
         core::task::spawn(async move {
+            let mut attempt = 0;
+            let mut endpoints = Endpoints::new(&options);
             'outer: loop {
-                match this.resolve_url(&options).await {
-                    Ok(url) => {
-                        let connect_future =
-                            connect_async_with_config(&url, ts_websocket_config, false);
+                this.state.set(State::Connecting);
+                let request = select_biased! {
+                    request = this.resolve_request(&options, endpoints.current()).fuse() => request,
+                    _ = options.cancelled().fuse() => {
+                        this.connect_cancelled(connect_trigger.take());
+                        break 'outer;
+                    }
+                };
+                match request {
+                    Ok((request, unix_socket, url)) => {
+                        let connect_future = async {
+                            let stream = Transport::connect(
+                                &request,
+                                &url,
+                                unix_socket.as_deref(),
+                                &options,
+                            )
+                            .await?;
+                            let (stream, response) = tls::connect_async(
+                                request,
+                                stream,
+                                ts_websocket_config,
+                                connector.clone(),
+                                deflate.clone(),
+                            )
+                            .await?;
+                            // subprotocol selected by the server must have been offered
+                            let protocol =
+                                response
+                                    .headers()
+                                    .get("Sec-WebSocket-Protocol")
+                                    .map(|protocol| {
+                                        String::from_utf8_lossy(protocol.as_bytes())
+                                            .trim()
+                                            .to_string()
+                                    });
+                            match protocol {
+                                Some(protocol) if !options.protocols.contains(&protocol) => {
+                                    Err(Error::Subprotocol(protocol))
+                                }
+                                protocol => Ok((stream, protocol, upgrade_response(&response))),
+                            }
+                        };
                         let timeout_future = timeout(options.connect_timeout(), connect_future);
+                        let result = select_biased! {
+                            result = timeout_future.fuse() => result,
+                            _ = options.cancelled().fuse() => {
+                                this.connect_cancelled(connect_trigger.take());
+                                break 'outer;
+                            }
+                        };
 
-                        match timeout_future.await {
+                        match result {
                             // connect success
                             Ok(Ok(stream)) => {
                                 // log_trace!("connected...");
 
-                                this.is_connected.store(true, Ordering::SeqCst);
-                                let (mut ws_stream, _) = stream;
+                                this.state.set(State::Handshaking);
+                                attempt = 0;
+                                endpoints.connected();
+                                let (mut ws_stream, protocol, response) = stream;
+                                this.settings.lock().unwrap().protocol = protocol;
 
                                 if connect_trigger.is_some() {
                                     connect_trigger.take().unwrap().try_send(Ok(())).ok();
                                 }
 
-                                if let Err(err) = this.dispatcher(&mut ws_stream).await {
+                                if let Err(err) =
+                                    this.dispatcher(&mut ws_stream, &options, response).await
+                                {
                                     log_trace!("WebSocket dispatcher error: {}", err);
                                 }
+                                this.metrics.closed();
+                                this.pings.clear();
+                                endpoints.disconnected();
 
-                                this.is_connected.store(false, Ordering::SeqCst);
+                                this.state.set(State::Closed);
                             }
                             // connect error
                             Ok(Err(e)) => {
                                 log_trace!("WebSocket failed to connect to {}: {}", url, e);
+                                this.post_event(Event::Error(e.to_string()));
+                                // try the next endpoint before observing the retry delay
+                                if this.reconnect.load(Ordering::SeqCst) && endpoints.failed() {
+                                    continue 'outer;
+                                }
                                 if matches!(options.strategy, ConnectStrategy::Fallback) {
                                     if options.block_async_connect && connect_trigger.is_some() {
-                                        connect_trigger
-                                            .take()
-                                            .unwrap()
-                                            .try_send(Err(e.into()))
-                                            .ok();
+                                        connect_trigger.take().unwrap().try_send(Err(e)).ok();
                                     }
                                     break;
                                 }
-                                workflow_core::task::sleep(options.retry_interval()).await;
+                                attempt += 1;
+                                if options.is_reconnect_exhausted(attempt) {
+                                    this.reconnect_exhausted(attempt - 1, connect_trigger.take())
+                                        .await;
+                                    break 'outer;
+                                }
+                                this.retrying(attempt);
+                                if !retry_delay(&options, attempt).await {
+                                    this.connect_cancelled(connect_trigger.take());
+                                    break 'outer;
+                                }
                             }
                             // timeout error
                             Err(_) => {
@@ -220,6 +490,11 @@ impl WebSocketInterface {
                                     "WebSocket connection timeout while connecting to {}",
                                     url
                                 );
+                                this.post_event(Event::Error(Error::ConnectionTimeout.to_string()));
+                                // try the next endpoint before observing the retry delay
+                                if this.reconnect.load(Ordering::SeqCst) && endpoints.failed() {
+                                    continue 'outer;
+                                }
                                 if matches!(options.strategy, ConnectStrategy::Fallback) {
                                     if options.block_async_connect && connect_trigger.is_some() {
                                         connect_trigger
@@ -230,7 +505,17 @@ impl WebSocketInterface {
                                     }
                                     break;
                                 }
-                                workflow_core::task::sleep(options.retry_interval()).await;
+                                attempt += 1;
+                                if options.is_reconnect_exhausted(attempt) {
+                                    this.reconnect_exhausted(attempt - 1, connect_trigger.take())
+                                        .await;
+                                    break 'outer;
+                                }
+                                this.retrying(attempt);
+                                if !retry_delay(&options, attempt).await {
+                                    this.connect_cancelled(connect_trigger.take());
+                                    break 'outer;
+                                }
                             }
                         };
 
@@ -240,14 +525,26 @@ impl WebSocketInterface {
                     }
                     Err(err) => {
                         log_trace!("WebSocket failed to get session URL: {}", err);
+                        this.post_event(Event::Error(err.to_string()));
                         if !this.reconnect.load(Ordering::SeqCst) {
                             break 'outer;
                         } else {
-                            workflow_core::task::sleep(options.retry_interval()).await;
+                            attempt += 1;
+                            if options.is_reconnect_exhausted(attempt) {
+                                this.reconnect_exhausted(attempt - 1, connect_trigger.take())
+                                    .await;
+                                break 'outer;
+                            }
+                            this.retrying(attempt);
+                            if !retry_delay(&options, attempt).await {
+                                this.connect_cancelled(connect_trigger.take());
+                                break 'outer;
+                            }
                         }
                     }
                 }
             }
+            this.state.set(State::Closed);
         });
 
         match block_async_connect {
@@ -259,10 +556,135 @@ impl WebSocketInterface {
         }
     }
 
+    /// Connect to the in-process handler of the [`MemoryTransport`]
+    async fn connect_memory(
+        self: &Arc<Self>,
+        transport: MemoryTransport,
+        options: ConnectOptions,
+    ) -> ConnectResult<Error> {
+        let this = self.clone();
+        let (connect_trigger, connect_listener) = oneshot::<Result<()>>();
+        let block_async_connect = options.block_async_connect;
+
+        this.reconnect.store(false, Ordering::SeqCst);
+        this.state.set(State::Handshaking);
+
+        core::task::spawn(async move {
+            let connection = match transport
+                .open(
+                    &this.sender_channel,
+                    &this.receiver_channel,
+                    this.handshake(),
+                    &options,
+                )
+                .await
+            {
+                Ok(connection) => connection,
+                Err(err) => {
+                    this.state.set(State::Closed);
+                    this.post_event(Event::Error(err.to_string()));
+                    connect_trigger.try_send(Err(err)).ok();
+                    return;
+                }
+            };
+
+            this.state.set(State::Open);
+            this.metrics.opened();
+            this.receiver_channel
+                .send(Message::Open)
+                .await
+                .unwrap_or_else(|err| {
+                    log_trace!("WebSocket unable to post `open` to receiver channel: `{err}`")
+                });
+            this.post_event(Event::Open { protocol: None });
+            connect_trigger.try_send(Ok(())).ok();
+            this.initialize();
+
+            let shutdown_requested = AtomicBool::new(false);
+            let shutdown = async {
+                let frame = this.shutdown.request.receiver.recv().await;
+                shutdown_requested.store(true, Ordering::SeqCst);
+                frame.unwrap_or_default()
+            };
+            match connection.dispatch(this.pending.take(), shutdown).await {
+                Ok(event) => this.post_event(event),
+                Err(err) => log_trace!("WebSocket memory transport error: {}", err),
+            }
+            this.metrics.closed();
+            this.state.set(State::Closed);
+
+            if shutdown_requested.load(Ordering::SeqCst) {
+                this.shutdown
+                    .response
+                    .sender
+                    .send(())
+                    .await
+                    .unwrap_or_else(|err| {
+                        log_trace!("WebSocket unable to signal dispatcher shutdown: {}", err)
+                    });
+            }
+        });
+
+        match block_async_connect {
+            true => match connect_listener.recv().await? {
+                Ok(_) => Ok(None),
+                Err(e) => Err(e),
+            },
+            false => Ok(Some(connect_listener)),
+        }
+    }
+
+    /// Report the reconnect attempt `attempt` before observing the retry delay
+    fn retrying(&self, attempt: u32) {
+        self.state.set(State::Retrying { attempt });
+        self.post_event(Event::Reconnecting { attempt });
+    }
+
+    /// Disable reconnects after the connect operation has been cancelled
+    /// (see [`ConnectOptions::cancel`]), failing the pending `connect()` call
+    fn connect_cancelled(self: &Arc<Self>, connect_trigger: Option<Sender<Result<()>>>) {
+        log_trace!("WebSocket connect cancelled");
+        self.reconnect.store(false, Ordering::SeqCst);
+        if let Some(connect_trigger) = connect_trigger {
+            connect_trigger.try_send(Err(Error::Cancelled)).ok();
+        }
+    }
+
+    /// Disable reconnects, failing the pending `connect()` call and
+    /// posting [`Message::ReconnectExhausted`] to the receiver channel
+    async fn reconnect_exhausted(
+        self: &Arc<Self>,
+        attempts: u32,
+        connect_trigger: Option<Sender<Result<()>>>,
+    ) {
+        log_trace!("WebSocket reconnect attempts exhausted after {attempts} attempts");
+        self.reconnect.store(false, Ordering::SeqCst);
+        if let Some(connect_trigger) = connect_trigger {
+            connect_trigger
+                .try_send(Err(Error::ReconnectExhausted(attempts)))
+                .ok();
+        }
+        self.receiver_channel
+            .send(Message::ReconnectExhausted)
+            .await
+            .unwrap_or_else(|err| {
+                log_trace!(
+                    "WebSocket unable to post `reconnect exhausted` to receiver channel: `{err}`"
+                )
+            });
+    }
+
     async fn handshake_impl(
         self: &Arc<Self>,
-        ws_sender: &mut SplitSink<&mut WebSocketStream<MaybeTlsStream<TcpStream>>, TsMessage>,
-        ws_receiver: &mut SplitStream<&mut WebSocketStream<MaybeTlsStream<TcpStream>>>,
+        ws_sender: &mut SplitSink<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+            TsMessage,
+        >,
+        ws_receiver: &mut SplitStream<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+        >,
+        handshake_timeout: Option<Duration>,
+        response: UpgradeResponse,
     ) -> Result<()> {
         if let Some(handshake) = self.handshake() {
             let (sender_tx, sender_rx) = unbounded();
@@ -271,32 +693,53 @@ impl WebSocketInterface {
 
             core::task::spawn(async move {
                 accept_tx
-                    .send(handshake.handshake(&sender_tx, &receiver_rx).await)
+                    .send(
+                        handshake
+                            .handshake_with_response(&sender_tx, &receiver_rx, Some(&response))
+                            .await,
+                    )
                     .await
                     .unwrap_or_else(|err| {
                         log_trace!("WebSocket handshake unable to send completion: `{}`", err)
                     });
             });
 
-            loop {
-                select_biased! {
-                    result = accept_rx.recv().fuse() => {
-                        return result?;
-                    },
-                    msg = sender_rx.recv().fuse() => {
-                        if let Ok(msg) = msg {
-                            ws_sender.send(msg.into()).await?;
-                        }
-                    },
-                    msg = ws_receiver.next().fuse() => {
-                        if let Some(Ok(msg)) = msg {
-                            receiver_tx.send(msg.into()).await?;
-                        } else {
-                            return Err(Error::NegotiationFailure);
+            let negotiation = async {
+                loop {
+                    select_biased! {
+                        result = accept_rx.recv().fuse() => {
+                            return result?;
+                        },
+                        msg = sender_rx.recv().fuse() => {
+                            if let Ok(msg) = msg {
+                                ws_sender.send(msg.try_into()?).await?;
+                            }
+                        },
+                        msg = ws_receiver.next().fuse() => {
+                            if let Some(Ok(msg)) = msg {
+                                // control frames are handled by tungstenite
+                                if let Ok(msg) = Message::try_from(msg) {
+                                    receiver_tx.send(msg).await?;
+                                }
+                            } else {
+                                return Err(Error::NegotiationFailure);
+                            }
                         }
                     }
                 }
-            }
+            };
+
+            return match handshake_timeout {
+                Some(handshake_timeout) => match timeout(handshake_timeout, negotiation).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        log_trace!("WebSocket handshake negotiation timeout");
+                        self.post_event(Event::Error(Error::HandshakeTimeout.to_string()));
+                        Err(Error::HandshakeTimeout)
+                    }
+                },
+                None => negotiation.await,
+            };
         }
 
         Ok(())
@@ -304,60 +747,241 @@ impl WebSocketInterface {
 
     async fn dispatcher(
         self: &Arc<Self>,
-        ws_stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+        ws_stream: &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+        options: &ConnectOptions,
+        response: UpgradeResponse,
     ) -> Result<()> {
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
-        self.handshake_impl(&mut ws_sender, &mut ws_receiver)
-            .await?;
+        self.handshake_impl(
+            &mut ws_sender,
+            &mut ws_receiver,
+            options.handshake_timeout,
+            response,
+        )
+        .await?;
 
+        self.state.set(State::Open);
+        self.metrics.opened();
         self.receiver_channel.send(Message::Open).await?;
+        self.post_event(Event::Open {
+            protocol: self.protocol(),
+        });
+
+        let config = self.config();
+        let mut ratelimit = RateLimiter::new(config.rate_limit);
+        let interceptors = Interceptors::new(&options.interceptors);
+        for msg in self.pending.take() {
+            let Some(msg) = interceptors.outbound(msg) else {
+                continue;
+            };
+            let len = payload_len(&msg);
+            let msg = match TsMessage::try_from(msg) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    log_trace!("WebSocket unable to send queued message: {}", err);
+                    continue;
+                }
+            };
+            ratelimit.ready().await;
+            ratelimit.consume(len);
+            ws_sender.send(msg).await?;
+            self.metrics.sent(len);
+        }
+        self.initialize();
 
+        let mut keepalive = KeepaliveState::new(config.keepalive);
+        let mut outbound: Option<OutboundStream> = None;
+        let mut closed = false;
         loop {
             select_biased! {
-                dispatch = self.sender_channel.recv().fuse() => {
-                    if let Ok((msg,ack)) = dispatch {
-                        if let Some(ack_sender) = ack {
-                            let result = ws_sender.send(msg.into()).await
-                                .map(Arc::new)
-                                .map_err(|err|Arc::new(err.into()));
-                            ack_sender.send(result).await?;
-                        } else {
-                            ws_sender.send(msg.into()).await?;
+                outgoing = async {
+                    ratelimit.ready().await;
+                    match outbound.as_mut() {
+                        Some(stream) => Outgoing::Frame(stream.next_frame().await),
+                        None => select_biased! {
+                            dispatch = self.sender_channel.recv().fuse() => {
+                                dispatch.map(Outgoing::Message).unwrap_or(Outgoing::Closed)
+                            }
+                            stream = self.stream_channel.recv().fuse() => {
+                                stream.map(Outgoing::Stream).unwrap_or(Outgoing::Closed)
+                            }
+                        },
+                    }
+                }.fuse() => {
+                    match outgoing {
+                        Outgoing::Message((msg, ack)) => {
+                            let Some(msg) = interceptors.outbound(msg) else {
+                                if let Some(ack_sender) = ack {
+                                    ack_sender.send(Ok(Arc::new(()))).await?;
+                                }
+                                continue;
+                            };
+                            let len = payload_len(&msg);
+                            match (TsMessage::try_from(msg), ack) {
+                                (Ok(msg), Some(ack_sender)) => {
+                                    ratelimit.consume(len);
+                                    let result = ws_sender.send(msg).await
+                                        .map(Arc::new)
+                                        .map_err(|err|Arc::new(err.into()));
+                                    if result.is_ok() {
+                                        self.metrics.sent(len);
+                                    }
+                                    ack_sender.send(result).await?;
+                                }
+                                (Ok(msg), None) => {
+                                    ratelimit.consume(len);
+                                    ws_sender.send(msg).await?;
+                                    self.metrics.sent(len);
+                                }
+                                (Err(err), Some(ack_sender)) => {
+                                    ack_sender.send(Err(Arc::new(err))).await?;
+                                }
+                                (Err(err), None) => {
+                                    log_trace!("WebSocket unable to send message: {}", err);
+                                }
+                            }
+                        }
+                        Outgoing::Stream(stream) => {
+                            outbound = Some(stream);
+                        }
+                        Outgoing::Frame(Ok(frame)) => {
+                            let is_final = frame.header().is_final;
+                            ratelimit.consume(frame.payload().len());
+                            ws_sender.send(TsMessage::Frame(frame)).await?;
+                            if is_final {
+                                if let Some(mut stream) = outbound.take() {
+                                    self.metrics.sent(stream.len());
+                                    if let Some(ack_sender) = stream.take_ack() {
+                                        ack_sender.send(Ok(Arc::new(()))).await?;
+                                    }
+                                }
+                            }
                         }
+                        Outgoing::Frame(Err(err)) => {
+                            log_trace!("WebSocket unable to read the message stream: {}", err);
+                            if let Some(mut stream) = outbound.take() {
+                                if let Some(ack_sender) = stream.take_ack() {
+                                    let error = std::io::Error::new(err.kind(), err.to_string());
+                                    ack_sender.send(Err(Arc::new(error.into()))).await?;
+                                }
+                                // a partially sent fragmented message can not be
+                                // aborted, the connection has to be dropped
+                                if stream.is_started() {
+                                    self.receiver_channel.send(Message::Close).await?;
+                                    self.post_event(Event::abnormal_close());
+                                    return Err(err.into());
+                                }
+                            }
+                        }
+                        Outgoing::Closed => { }
                     }
                 }
                 msg = ws_receiver.next().fuse() => {
                     match msg {
                         Some(Ok(msg)) => {
+                            let rtt = keepalive.received();
                             match msg {
-                                TsMessage::Binary(_) | TsMessage::Text(_) | TsMessage::Close(_) => {
+                                TsMessage::Binary(_) | TsMessage::Text(_) => {
+                                    self.metrics.received(msg.len());
+                                    if let Some(msg) = interceptors.inbound(msg.try_into()?) {
+                                        if let Err(err) = self.receiver_channel.send(msg).await {
+                                            return self.receiver_error(err, &mut ws_sender, &mut ws_receiver).await;
+                                        }
+                                    }
+                                }
+                                TsMessage::Close(frame) => {
+                                    if !closed {
+                                        closed = true;
+                                        self.post_event(frame.map(|frame| Event::Close {
+                                            code: frame.code.into(),
+                                            reason: frame.reason.to_string(),
+                                            was_clean: true,
+                                        }).unwrap_or(Event::Close {
+                                            code: CloseCode::Status.into(),
+                                            reason: String::new(),
+                                            was_clean: true,
+                                        }));
+                                    }
                                     self
                                         .receiver_channel
-                                        .send(msg.into())
+                                        .send(Message::Close)
                                         .await?;
                                 }
                                 TsMessage::Ping(data) => {
+                                    if config.receive_ping_pong {
+                                        if let Some(msg) = interceptors.inbound(Message::Ping(data.clone())) {
+                                            if let Err(err) = self.receiver_channel.send(msg).await {
+                                                return self.receiver_error(err, &mut ws_sender, &mut ws_receiver).await;
+                                            }
+                                        }
+                                    }
                                     ws_sender.send(TsMessage::Pong(data)).await?;
                                 },
-                                TsMessage::Pong(_) => { },
+                                TsMessage::Pong(data) => {
+                                    if let Some(rtt) = rtt {
+                                        self.metrics.rtt(rtt);
+                                    }
+                                    if let Some(nonce) = ping::nonce(&data) {
+                                        self.pings.resolve(nonce);
+                                    }
+                                    if config.receive_ping_pong {
+                                        if let Some(msg) = interceptors.inbound(Message::Pong(data)) {
+                                            if let Err(err) = self.receiver_channel.send(msg).await {
+                                                return self.receiver_error(err, &mut ws_sender, &mut ws_receiver).await;
+                                            }
+                                        }
+                                    }
+                                },
                                 TsMessage::Frame(_frame) => { },
                             }
                         }
                         Some(Err(e)) => {
                             self.receiver_channel.send(Message::Close).await?;
                             log_trace!("WebSocket error: {}", e);
+                            self.post_event(Event::Error(e.to_string()));
+                            if !closed {
+                                self.post_event(Event::abnormal_close());
+                            }
                             break;
                         }
                         None => {
                             self.receiver_channel.send(Message::Close).await?;
                             log_trace!("WebSocket connection closed");
+                            if !closed {
+                                self.post_event(Event::abnormal_close());
+                            }
                             break;
                         }
                     }
                 }
-                _ = self.shutdown.request.receiver.recv().fuse() => {
+                _ = keepalive.tick().fuse() => {
+                    match keepalive.action() {
+                        KeepaliveAction::Ping => {
+                            ws_sender.send(TsMessage::Ping(Vec::new())).await?;
+                        }
+                        KeepaliveAction::Timeout => {
+                            log_trace!("WebSocket keepalive timeout");
+                            self.receiver_channel.send(Message::Close).await?;
+                            self.post_event(Event::Error(Error::KeepaliveTimeout.to_string()));
+                            self.post_event(Event::abnormal_close());
+                            return Err(Error::KeepaliveTimeout);
+                        }
+                        KeepaliveAction::None => { }
+                    }
+                }
+                frame = self.shutdown.request.receiver.recv().fuse() => {
+                    let frame = frame.unwrap_or_default();
+                    let was_clean = closed
+                        || Self::close_handshake(&mut ws_sender, &mut ws_receiver, &frame).await;
                     self.receiver_channel.send(Message::Close).await?;
+                    if !closed {
+                        self.post_event(Event::Close {
+                            code: frame.code,
+                            reason: frame.reason,
+                            was_clean,
+                        });
+                    }
                     self.shutdown.response.sender.send(()).await?;
                     break;
                 }
@@ -367,14 +991,90 @@ impl WebSocketInterface {
         Ok(())
     }
 
+    /// Handle the failure to deliver the message to the receiver channel;
+    /// closes the connection if the channel has overflowed (see
+    /// [`ReceiverOverflow::Disconnect`](super::ReceiverOverflow::Disconnect))
+    async fn receiver_error(
+        &self,
+        err: Error,
+        ws_sender: &mut SplitSink<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+            TsMessage,
+        >,
+        ws_receiver: &mut SplitStream<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+        >,
+    ) -> Result<()> {
+        if !matches!(err, Error::ReceiverOverflow) {
+            return Err(err);
+        }
+
+        log_trace!("WebSocket receiver channel overflow");
+        self.reconnect.store(false, Ordering::SeqCst);
+        let frame = CloseFrame::new(CLOSE_CODE_NORMAL, err.to_string());
+        let was_clean = Self::close_handshake(ws_sender, ws_receiver, &frame).await;
+        self.receiver_channel.send(Message::Close).await?;
+        self.post_event(Event::Error(err.to_string()));
+        self.post_event(Event::Close {
+            code: frame.code,
+            reason: frame.reason,
+            was_clean,
+        });
+        Err(err)
+    }
+
+    /// Send the Close frame and wait for the server to respond with
+    /// the Close frame, returns `true` if the close handshake has completed
+    async fn close_handshake(
+        ws_sender: &mut SplitSink<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+            TsMessage,
+        >,
+        ws_receiver: &mut SplitStream<
+            &mut WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+        >,
+        frame: &CloseFrame,
+    ) -> bool {
+        let frame = TsCloseFrame {
+            code: frame.code.into(),
+            reason: frame.reason.clone().into(),
+        };
+        if let Err(err) = ws_sender.send(TsMessage::Close(Some(frame))).await {
+            log_trace!("WebSocket unable to send close frame: {}", err);
+            return false;
+        }
+
+        let handshake = async {
+            while let Some(Ok(msg)) = ws_receiver.next().await {
+                if let TsMessage::Close(_) = msg {
+                    return true;
+                }
+            }
+            false
+        };
+
+        timeout(
+            Duration::from_millis(CLOSE_HANDSHAKE_TIMEOUT_MILLIS),
+            handshake,
+        )
+        .await
+        .unwrap_or(false)
+    }
+
     pub async fn close(self: &Arc<Self>) -> Result<()> {
+        self.close_with(CloseFrame::default()).await
+    }
+
+    /// Close the connection sending the supplied Close frame
+    pub async fn close_with(self: &Arc<Self>, frame: CloseFrame) -> Result<()> {
         // if self.inner.lock().unwrap().is_some() {
-        if self.is_connected.load(Ordering::SeqCst) {
+        if self.is_connected() {
             // } self.inner.lock().unwrap().is_some() {
+            self.state.set(State::Closing);
             self.shutdown
                 .request
                 .sender
-                .send(())
+                .send(frame)
                 .await
                 .unwrap_or_else(|err| {
                     log_error!("Unable to signal WebSocket dispatcher shutdown: {}", err)
@@ -393,15 +1093,45 @@ impl WebSocketInterface {
     }
 
     pub async fn disconnect(self: &Arc<Self>) -> Result<()> {
+        self.disconnect_with(CloseFrame::default()).await
+    }
+
+    pub async fn disconnect_with(self: &Arc<Self>, frame: CloseFrame) -> Result<()> {
         self.reconnect.store(false, Ordering::SeqCst);
-        self.close().await?;
+        self.close_with(frame).await?;
         Ok(())
     }
 
     pub fn trigger_abort(self: &Arc<Self>) -> Result<()> {
-        if self.is_connected.load(Ordering::SeqCst) {
+        if self.is_connected() {
             self.receiver_channel.try_send(Message::Close)?;
         }
         Ok(())
     }
 }
+
+/// Wait for the delay before the reconnect attempt `attempt`,
+/// returns `false` if the connect operation has been cancelled
+/// Status and headers of the WebSocket upgrade response
+fn upgrade_response(response: &Response) -> UpgradeResponse {
+    UpgradeResponse {
+        status: response.status().as_u16(),
+        headers: response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect(),
+    }
+}
+
+async fn retry_delay(options: &ConnectOptions, attempt: u32) -> bool {
+    select_biased! {
+        _ = workflow_core::task::sleep(options.retry_delay(attempt)).fuse() => true,
+        _ = options.cancelled().fuse() => false,
+    }
+}
@@ -1,10 +1,13 @@
 use super::error::Error;
 use super::result::Result;
+use super::{DnsResolver, Interceptor, MemoryTransport, Socks5Proxy};
 use cfg_if::cfg_if;
 use std::str::FromStr;
+use std::sync::Arc;
+use triggered::Listener;
 use wasm_bindgen::convert::TryFromJsValue;
 use wasm_bindgen::prelude::*;
-use workflow_core::time::Duration;
+use workflow_core::time::{backoff_delay, Duration};
 
 /// `ConnectionStrategy` specifies how the WebSocket `async fn connect()`
 /// function should behave during the first-time connectivity phase.
@@ -19,6 +22,10 @@ pub enum ConnectStrategy {
     /// Causes `connect()` to return immediately if the first-time connection
     /// has failed.
     Fallback,
+    /// Continuously attempt to connect to the server, increasing the delay
+    /// between attempts according to the [`Backoff`] policy supplied in
+    /// [`ConnectOptions::backoff`] (or the default policy).
+    RetryWithBackoff,
 }
 
 impl FromStr for ConnectStrategy {
@@ -27,6 +34,7 @@ impl FromStr for ConnectStrategy {
         match s {
             "retry" => Ok(ConnectStrategy::Retry),
             "fallback" => Ok(ConnectStrategy::Fallback),
+            "backoff" | "retry-with-backoff" => Ok(ConnectStrategy::RetryWithBackoff),
             _ => Err(Error::InvalidConnectStrategyArg(s.to_string())),
         }
     }
@@ -46,6 +54,91 @@ impl ConnectStrategy {
     }
 }
 
+/// Order in which the endpoints supplied in [`ConnectOptions::urls`]
+/// are attempted.  In both modes, the client moves on to the next endpoint
+/// immediately after a failed connection attempt and waits for the retry
+/// delay only once all endpoints have failed.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Failover {
+    /// Always start from the first endpoint (including after the
+    /// connection has been lost), the endpoints are listed in the
+    /// order of priority.
+    #[default]
+    Priority,
+    /// Continue from the endpoint following the last one attempted
+    /// (including after the connection has been lost).
+    RoundRobin,
+}
+
+impl FromStr for Failover {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "priority" => Ok(Failover::Priority),
+            "round-robin" => Ok(Failover::RoundRobin),
+            _ => Err(Error::InvalidFailover(s.to_string())),
+        }
+    }
+}
+
+/// Exponential reconnect backoff policy used by [`ConnectStrategy::RetryWithBackoff`].
+/// The delay before the reconnect attempt `n` is `initial_delay * multiplier^(n-1)`,
+/// capped at `max_delay` and randomized by `±jitter` (a fraction of the delay)
+/// to prevent clients from reconnecting simultaneously.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    pub initial_delay: Duration,
+    pub multiplier: f64,
+    pub max_delay: Duration,
+    /// Randomization factor in the range `0.0..=1.0`
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(DEFAULT_BACKOFF_INITIAL_DELAY_MILLIS),
+            multiplier: 2.0,
+            max_delay: Duration::from_millis(DEFAULT_BACKOFF_MAX_DELAY_MILLIS),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before the reconnect attempt `attempt` (starting from `1`)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = backoff_delay(self.initial_delay, self.multiplier, self.max_delay, attempt);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (rand::random::<f64>() * 2.0 - 1.0);
+        Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(delay)
+    }
+}
+
 impl TryFrom<JsValue> for ConnectStrategy {
     type Error = Error;
     fn try_from(value: JsValue) -> Result<Self> {
@@ -73,16 +166,66 @@ pub struct ConnectOptions {
     /// Optional `url` that will change the current URL of the WebSocket.
     /// Note that the URL overrides the use of resolver.
     pub url: Option<String>,
+    /// Endpoints the client fails over between (used if `url` is not
+    /// supplied, overriding the default URL and the resolver).  The
+    /// endpoint the client is connected to is available via
+    /// [`WebSocket::url()`](super::WebSocket::url).
+    pub urls: Vec<String>,
+    /// Order in which the endpoints in `urls` are attempted.
+    pub failover: Failover,
     /// Optional `timeout` that will change the timeout of the WebSocket connection process.
     /// `Timeout` is the period after which the async connection attempt is aborted. `Timeout`
     /// is followed by the retry delay if the [`ConnectionStrategy`] is set to `Retry`.
     pub connect_timeout: Option<Duration>,
+    /// Maximum duration of the custom [`Handshake`](super::Handshake)
+    /// negotiation (unlimited if `None`).  If the negotiation does not
+    /// complete in time, the connection is dropped with
+    /// [`Error::HandshakeTimeout`] and the [`ConnectStrategy`] is applied.
+    pub handshake_timeout: Option<Duration>,
     /// Retry interval denotes the time to wait before attempting to reconnect.
     pub retry_interval: Option<Duration>,
+    /// Reconnect backoff policy used by [`ConnectStrategy::RetryWithBackoff`].
+    pub backoff: Option<Backoff>,
+    /// Maximum number of consecutive reconnect attempts (`None` for unlimited).
+    /// Once exhausted, the client stops reconnecting and posts
+    /// [`Message::ReconnectExhausted`](super::Message::ReconnectExhausted)
+    /// to the receiver channel.
+    pub max_reconnect_attempts: Option<u32>,
+    /// Custom HTTP headers (e.g. `Authorization`, `Origin` or `Cookie`) supplied
+    /// with the WebSocket upgrade request. Headers are supported by the native
+    /// and Node.js clients only; browsers do not allow custom WebSocket headers.
+    pub headers: Vec<(String, String)>,
+    /// Subprotocols offered to the server (`Sec-WebSocket-Protocol` header)
+    /// in the order of preference.  The protocol selected by the server is
+    /// available via [`WebSocket::protocol()`](super::WebSocket::protocol).
+    pub protocols: Vec<String>,
+    /// Custom hostname resolver (native client only). If not supplied,
+    /// the host is resolved using the system resolver.
+    pub dns_resolver: Option<Arc<dyn DnsResolver>>,
+    /// SOCKS5 proxy (native client only), required to connect to Tor
+    /// onion services (`*.onion` hosts). See [`Socks5Proxy`].
+    pub proxy: Option<Socks5Proxy>,
+    /// Connect to the in-process handler of the [`MemoryTransport`]
+    /// instead of the server (see [`MemoryTransport`]).
+    pub memory_transport: Option<MemoryTransport>,
+    /// Connector supplying the connection streams (native client only).
+    /// See [`StreamConnector`](super::StreamConnector).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub stream_connector: Option<Arc<dyn super::StreamConnector>>,
+    /// Cancellation listener. Once triggered, the connection attempt in
+    /// progress (or the delay before the next attempt) is aborted, the
+    /// reconnect loop is stopped and the pending `connect()` call fails
+    /// with [`Error::Cancelled`]. An established connection is not closed
+    /// (use `disconnect()`), but will not be re-established once lost.
+    pub cancel: Option<Listener>,
+    /// Message interceptors executed by the dispatcher (see [`Interceptor`]).
+    pub interceptors: Vec<Arc<dyn Interceptor>>,
 }
 
 pub const DEFAULT_CONNECT_TIMEOUT_MILLIS: u64 = 5_000;
 pub const DEFAULT_CONNECT_RETRY_MILLIS: u64 = 5_000;
+pub const DEFAULT_BACKOFF_INITIAL_DELAY_MILLIS: u64 = 1_000;
+pub const DEFAULT_BACKOFF_MAX_DELAY_MILLIS: u64 = 60_000;
 
 impl Default for ConnectOptions {
     fn default() -> Self {
@@ -90,8 +233,22 @@ impl Default for ConnectOptions {
             block_async_connect: true,
             strategy: ConnectStrategy::Retry,
             url: None,
+            urls: Vec::new(),
+            failover: Failover::default(),
             connect_timeout: None,
+            handshake_timeout: None,
             retry_interval: None,
+            backoff: None,
+            max_reconnect_attempts: None,
+            headers: Vec::new(),
+            protocols: Vec::new(),
+            dns_resolver: None,
+            proxy: None,
+            memory_transport: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            stream_connector: None,
+            cancel: None,
+            interceptors: Vec::new(),
         }
     }
 }
@@ -102,8 +259,22 @@ impl ConnectOptions {
             block_async_connect: true,
             strategy: ConnectStrategy::Fallback,
             url: None,
+            urls: Vec::new(),
+            failover: Failover::default(),
             connect_timeout: None,
+            handshake_timeout: None,
             retry_interval: None,
+            backoff: None,
+            max_reconnect_attempts: None,
+            headers: Vec::new(),
+            protocols: Vec::new(),
+            dns_resolver: None,
+            proxy: None,
+            memory_transport: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            stream_connector: None,
+            cancel: None,
+            interceptors: Vec::new(),
         }
     }
     pub fn reconnect_defaults() -> Self {
@@ -111,8 +282,22 @@ impl ConnectOptions {
             block_async_connect: true,
             strategy: ConnectStrategy::Retry,
             url: None,
+            urls: Vec::new(),
+            failover: Failover::default(),
             connect_timeout: None,
+            handshake_timeout: None,
             retry_interval: None,
+            backoff: None,
+            max_reconnect_attempts: None,
+            headers: Vec::new(),
+            protocols: Vec::new(),
+            dns_resolver: None,
+            proxy: None,
+            memory_transport: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            stream_connector: None,
+            cancel: None,
+            interceptors: Vec::new(),
         }
     }
 
@@ -121,8 +306,124 @@ impl ConnectOptions {
             block_async_connect: false,
             strategy: ConnectStrategy::Retry,
             url: None,
+            urls: Vec::new(),
+            failover: Failover::default(),
             connect_timeout: None,
+            handshake_timeout: None,
             retry_interval: None,
+            backoff: None,
+            max_reconnect_attempts: None,
+            headers: Vec::new(),
+            protocols: Vec::new(),
+            dns_resolver: None,
+            proxy: None,
+            memory_transport: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            stream_connector: None,
+            cancel: None,
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Add an endpoint to the failover list (see [`ConnectOptions::urls`]).
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    pub fn with_failover(mut self, failover: Failover) -> Self {
+        self.failover = failover;
+        self
+    }
+
+    /// Add a custom HTTP header to the WebSocket upgrade request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Offer a subprotocol to the server during the WebSocket upgrade.
+    pub fn with_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocols.push(protocol.into());
+        self
+    }
+
+    /// Resolve the server hostname using the supplied resolver.
+    pub fn with_dns_resolver(mut self, dns_resolver: Arc<dyn DnsResolver>) -> Self {
+        self.dns_resolver = Some(dns_resolver);
+        self
+    }
+
+    /// Connect via the supplied SOCKS5 proxy.
+    pub fn with_proxy(mut self, proxy: Socks5Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Connect to the in-process handler of the supplied transport.
+    pub fn with_memory_transport(mut self, memory_transport: MemoryTransport) -> Self {
+        self.memory_transport = Some(memory_transport);
+        self
+    }
+
+    /// Connect over the streams supplied by the connector.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_stream_connector(
+        mut self,
+        stream_connector: Arc<dyn super::StreamConnector>,
+    ) -> Self {
+        self.stream_connector = Some(stream_connector);
+        self
+    }
+
+    /// Abort connecting once the supplied listener is triggered.
+    pub fn with_cancel(mut self, cancel: Listener) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Append an interceptor to the interceptor chain.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Use [`ConnectStrategy::RetryWithBackoff`] with the supplied backoff policy.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.strategy = ConnectStrategy::RetryWithBackoff;
+        self.backoff = Some(backoff);
+        self
+    }
+
+    pub fn with_handshake_timeout(mut self, handshake_timeout: Duration) -> Self {
+        self.handshake_timeout = Some(handshake_timeout);
+        self
+    }
+
+    pub fn with_max_reconnect_attempts(mut self, max_reconnect_attempts: u32) -> Self {
+        self.max_reconnect_attempts = Some(max_reconnect_attempts);
+        self
+    }
+
+    /// Returns `true` if the reconnect attempt `attempt` (starting from `1`)
+    /// exceeds [`ConnectOptions::max_reconnect_attempts`]
+    pub fn is_reconnect_exhausted(&self, attempt: u32) -> bool {
+        self.max_reconnect_attempts
+            .is_some_and(|max_reconnect_attempts| attempt > max_reconnect_attempts)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_triggered())
+    }
+
+    /// Resolves once the cancellation listener has been triggered
+    /// (never resolves if the listener has not been supplied)
+    pub(crate) async fn cancelled(&self) {
+        match self.cancel.clone() {
+            Some(cancel) => cancel.await,
+            None => futures::future::pending().await,
         }
     }
 
@@ -135,6 +436,73 @@ impl ConnectOptions {
         self.retry_interval
             .unwrap_or(Duration::from_millis(DEFAULT_CONNECT_RETRY_MILLIS))
     }
+
+    /// Delay before the reconnect attempt `attempt` (starting from `1`):
+    /// the backoff delay if the strategy is [`ConnectStrategy::RetryWithBackoff`],
+    /// otherwise the retry interval.
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        match self.strategy {
+            ConnectStrategy::RetryWithBackoff => {
+                self.backoff.clone().unwrap_or_default().delay(attempt)
+            }
+            _ => self.retry_interval(),
+        }
+    }
+}
+
+/// Failover state tracking the endpoint (index in [`ConnectOptions::urls`])
+/// to be attempted next
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    count: usize,
+    failover: Failover,
+    index: usize,
+    tried: usize,
+}
+
+impl Endpoints {
+    pub fn new(options: &ConnectOptions) -> Self {
+        Endpoints {
+            count: options.urls.len().max(1),
+            failover: options.failover,
+            index: 0,
+            tried: 0,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.index
+    }
+
+    fn advance(&mut self) {
+        self.index = (self.index + 1) % self.count;
+    }
+
+    /// Register a failed connection attempt, returns `true` if the next
+    /// endpoint should be attempted immediately or `false` if all endpoints
+    /// have failed (and the retry delay should be observed)
+    pub fn failed(&mut self) -> bool {
+        self.tried += 1;
+        if self.tried < self.count {
+            self.advance();
+            true
+        } else {
+            self.tried = 0;
+            self.disconnected();
+            false
+        }
+    }
+
+    pub fn connected(&mut self) {
+        self.tried = 0;
+    }
+
+    pub fn disconnected(&mut self) {
+        match self.failover {
+            Failover::Priority => self.index = 0,
+            Failover::RoundRobin => self.advance(),
+        }
+    }
 }
 
 cfg_if! {
@@ -169,14 +537,44 @@ cfg_if! {
              * If supplied, the URL will override the use of resolver.
              */
             url?: string,
+            /**
+             * Endpoints the WebSocket fails over between (used if `url` is not supplied).
+             */
+            urls?: string[],
+            /**
+             * Order in which the endpoints are attempted (default is `priority`).
+             */
+            failover?: "priority" | "round-robin",
             /**
              * A custom connection timeout in milliseconds.
              */
             timeoutDuration?: number,
+            /**
+             * Maximum duration of the custom handshake negotiation in milliseconds.
+             */
+            handshakeTimeout?: number,
             /** 
              * A custom retry interval in milliseconds.
              */
             retryInterval?: number,
+            /**
+             * Reconnect backoff policy used by {link ConnectStrategy.RetryWithBackoff}.
+             * Delays are specified in milliseconds, `jitter` is a fraction of the delay.
+             */
+            backoff?: { initialDelay?: number, multiplier?: number, maxDelay?: number, jitter?: number },
+            /**
+             * Maximum number of consecutive reconnect attempts (unlimited if not specified).
+             */
+            maxReconnectAttempts?: number,
+            /**
+             * Custom HTTP headers supplied with the WebSocket upgrade request
+             * (supported in Node.js only, browsers do not allow custom headers).
+             */
+            headers?: Record<string, string>,
+            /**
+             * Subprotocols offered to the server in the order of preference.
+             */
+            protocols?: string[],
         }
         "#;
 
@@ -198,6 +596,16 @@ cfg_if! {
             fn try_from(args: &IConnectOptions) -> Result<Self> {
                 let options = if let Some(args) = args.dyn_ref::<Object>() {
                     let url = args.get_value("url")?.as_string();
+                    let urls = args.get_value("urls")?;
+                    let urls = if let Some(urls) = urls.dyn_ref::<js_sys::Array>() {
+                        urls.iter().filter_map(|url| url.as_string()).collect()
+                    } else {
+                        Vec::new()
+                    };
+                    let failover = match args.get_value("failover")?.as_string() {
+                        Some(failover) => failover.parse()?,
+                        None => Failover::default(),
+                    };
                     let block_async_connect = args
                         .get_value("blockAsyncConnect")?
                         .as_bool()
@@ -207,27 +615,99 @@ cfg_if! {
                         .get_value("timeoutDuration")?
                         .as_f64()
                         .map(|f| Duration::from_millis(f as u64));
+                    let handshake_timeout = args
+                        .get_value("handshakeTimeout")?
+                        .as_f64()
+                        .map(|f| Duration::from_millis(f as u64));
                     let retry_interval = args
                         .get_value("retryInterval")?
                         .as_f64()
                         .map(|f| Duration::from_millis(f as u64));
+                    let max_reconnect_attempts = args
+                        .get_value("maxReconnectAttempts")?
+                        .as_f64()
+                        .map(|attempts| attempts as u32);
+                    let backoff = args.get_value("backoff")?;
+                    let backoff = if let Some(args) = backoff.dyn_ref::<Object>() {
+                        let mut backoff = Backoff::default();
+                        if let Some(delay) = args.get_value("initialDelay")?.as_f64() {
+                            backoff.initial_delay = Duration::from_millis(delay as u64);
+                        }
+                        if let Some(multiplier) = args.get_value("multiplier")?.as_f64() {
+                            backoff.multiplier = multiplier;
+                        }
+                        if let Some(delay) = args.get_value("maxDelay")?.as_f64() {
+                            backoff.max_delay = Duration::from_millis(delay as u64);
+                        }
+                        if let Some(jitter) = args.get_value("jitter")?.as_f64() {
+                            backoff.jitter = jitter;
+                        }
+                        Some(backoff)
+                    } else {
+                        None
+                    };
+                    let headers = args.get_value("headers")?;
+                    let headers = if let Some(headers) = headers.dyn_ref::<Object>() {
+                        Object::entries(headers)
+                            .iter()
+                            .filter_map(|entry| {
+                                let entry = entry.unchecked_into::<js_sys::Array>();
+                                Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+                            })
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+
+                    let protocols = args.get_value("protocols")?;
+                    let protocols = if let Some(protocols) = protocols.dyn_ref::<js_sys::Array>() {
+                        protocols.iter().filter_map(|protocol| protocol.as_string()).collect()
+                    } else {
+                        Vec::new()
+                    };
 
                     ConnectOptions {
                         block_async_connect,
                         strategy,
                         url,
+                        urls,
+                        failover,
                         connect_timeout: timeout,
+                        handshake_timeout,
                         retry_interval,
-                        ..Default::default()
+                        backoff,
+                        max_reconnect_attempts,
+                        headers,
+                        protocols,
+                        dns_resolver: None,
+                        proxy: None,
+                        memory_transport: None,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        stream_connector: None,
+                        cancel: None,
+                        interceptors: Vec::new(),
                     }
                 } else if let Some(retry) = args.as_bool() {
                     ConnectOptions {
                         block_async_connect: true,
                         strategy: ConnectStrategy::new(retry),
                         url: None,
+                        urls: Vec::new(),
+                        failover: Failover::default(),
                         connect_timeout: None,
+                        handshake_timeout: None,
                         retry_interval: None,
-                        ..Default::default()
+                        backoff: None,
+                        max_reconnect_attempts: None,
+                        headers: Vec::new(),
+                        protocols: Vec::new(),
+                        dns_resolver: None,
+                        proxy: None,
+                        memory_transport: None,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        stream_connector: None,
+                        cancel: None,
+                        interceptors: Vec::new(),
                     }
                 } else {
                     ConnectOptions::default()
@@ -238,3 +718,50 @@ cfg_if! {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn backoff(jitter: f64) -> Backoff {
+        Backoff::new()
+            .with_initial_delay(Duration::from_millis(100))
+            .with_multiplier(2.0)
+            .with_max_delay(Duration::from_secs(10))
+            .with_jitter(jitter)
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = backoff(0.0);
+        // attempts `0` and `1` use the initial delay
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(200));
+        assert_eq!(backoff.delay(4), Duration::from_millis(800));
+        // the delay is capped at `max_delay` without overflowing
+        assert_eq!(backoff.delay(8), Duration::from_secs(10));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(10));
+        // multipliers below `1.0` do not shrink the delay
+        let backoff = backoff.with_multiplier(0.5);
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_millis(100));
+        // a zero delay remains zero
+        let backoff = Backoff::new()
+            .with_initial_delay(Duration::ZERO)
+            .with_multiplier(f64::MAX);
+        assert_eq!(backoff.delay(u32::MAX), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_backoff_jitter() {
+        for attempt in [0, 1, 3, u32::MAX] {
+            let delay = backoff(0.0).delay(attempt).as_secs_f64();
+            for jitter in [0.5, 1.0, 4.0] {
+                let jittered = backoff(jitter).delay(attempt).as_secs_f64();
+                let jitter = jitter.min(1.0);
+                assert!(jittered >= delay * (1.0 - jitter) - 1e-9);
+                assert!(jittered <= delay * (1.0 + jitter) + 1e-9);
+            }
+        }
+    }
+}
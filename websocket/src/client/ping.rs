@@ -0,0 +1,95 @@
+//!
+//! Round-trip time measurement (see [`WebSocket::ping()`](super::WebSocket::ping))
+//!
+
+use super::{error::Error, message::Message, result::Result, Ack};
+use ahash::AHashMap;
+use futures::{select_biased, FutureExt};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use workflow_core::channel::*;
+use workflow_core::time::{Duration, Instant};
+
+pub const DEFAULT_PING_TIMEOUT_MILLIS: u64 = 10_000;
+
+/// Pings awaiting the matching response, resolved by the dispatcher
+#[derive(Default)]
+pub(crate) struct PendingPings {
+    nonce: AtomicU64,
+    pending: Mutex<AHashMap<u64, (Instant, Sender<Duration>)>>,
+}
+
+impl PendingPings {
+    ///
+    /// Send the ping `message` created for a new nonce and wait for the
+    /// response, returns the round-trip time.  Fails with
+    /// [`Error::PingTimeout`] if the response has not been received within
+    /// `timeout` or with [`Error::NotConnected`] if the connection is closed.
+    ///
+    pub async fn ping(
+        &self,
+        sender_channel: &Channel<(Message, Ack)>,
+        timeout: Duration,
+        message: impl FnOnce(u64) -> Message,
+    ) -> Result<Duration> {
+        let nonce = self.nonce.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(nonce, (Instant::now(), sender));
+
+        let response = async {
+            sender_channel.send((message(nonce), None)).await?;
+            receiver.recv().await.map_err(|_| Error::NotConnected)
+        };
+        let result = select_biased! {
+            rtt = response.fuse() => rtt,
+            _ = workflow_core::task::sleep(timeout).fuse() => Err(Error::PingTimeout),
+        };
+
+        self.pending.lock().unwrap().remove(&nonce);
+        result
+    }
+
+    /// Resolve the ping matching the nonce, returns `false` if
+    /// the nonce does not belong to a pending ping
+    pub fn resolve(&self, nonce: u64) -> bool {
+        match self.pending.lock().unwrap().remove(&nonce) {
+            Some((sent, sender)) => {
+                sender.try_send(sent.elapsed()).ok();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fail the pending pings once the connection is closed
+    pub fn clear(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// Payload of the Ping frame carrying the nonce
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn payload(nonce: u64) -> Vec<u8> {
+    nonce.to_be_bytes().to_vec()
+}
+
+/// Nonce carried by the Pong frame payload
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn nonce(payload: &[u8]) -> Option<u64> {
+    payload.try_into().ok().map(u64::from_be_bytes)
+}
+
+/// Text message of the application-level echo ping
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn echo(prefix: &str, nonce: u64) -> Message {
+    Message::Text(format!("{prefix}{nonce}"))
+}
+
+/// Nonce carried by the echoed text message
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn echo_nonce(prefix: &str, text: &str) -> Option<u64> {
+    text.strip_prefix(prefix)?.parse().ok()
+}
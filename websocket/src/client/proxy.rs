@@ -0,0 +1,211 @@
+//!
+//! SOCKS5 proxy support for native WebSocket connections
+//!
+
+/// Default address of the SOCKS5 proxy of the Tor daemon
+pub const TOR_SOCKS5_PROXY_ADDRESS: &str = "127.0.0.1:9050";
+
+///
+/// SOCKS5 proxy (RFC 1928) used by the native client (ignored by the WASM
+/// client).  Hostnames are passed to the proxy and resolved remotely; the
+/// local resolver (including the
+/// [`ConnectOptions::dns_resolver`](super::ConnectOptions::dns_resolver))
+/// is not consulted.  Tor onion service hosts (`*.onion`) are never resolved
+/// locally: connections to onion services fail unless a proxy is configured.
+///
+#[derive(Clone)]
+pub struct Socks5Proxy {
+    /// Address of the proxy (`host:port`)
+    pub address: String,
+    /// Username and password (RFC 1929) supplied to the proxy (Tor uses
+    /// distinct credentials to isolate the circuits of the connections)
+    pub credentials: Option<(String, String)>,
+    /// Route only the connections to onion services through the proxy,
+    /// connecting to the other hosts directly
+    pub onion_only: bool,
+}
+
+impl std::fmt::Debug for Socks5Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Socks5Proxy")
+            .field("address", &self.address)
+            .field(
+                "username",
+                &self.credentials.as_ref().map(|(username, _)| username),
+            )
+            .field("onion_only", &self.onion_only)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Socks5Proxy {
+    pub fn new(address: impl Into<String>) -> Self {
+        Socks5Proxy {
+            address: address.into(),
+            credentials: None,
+            onion_only: false,
+        }
+    }
+
+    /// Tor proxy at the default address ([`TOR_SOCKS5_PROXY_ADDRESS`])
+    /// used only for the connections to onion services
+    pub fn tor() -> Self {
+        Socks5Proxy::new(TOR_SOCKS5_PROXY_ADDRESS).with_onion_only(true)
+    }
+
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    pub fn with_onion_only(mut self, onion_only: bool) -> Self {
+        self.onion_only = onion_only;
+        self
+    }
+
+    /// Returns `true` if the connection to `host` should be made via the proxy
+    pub fn is_proxied(&self, host: &str) -> bool {
+        !self.onion_only || is_onion(host)
+    }
+}
+
+/// Returns `true` if `host` is a Tor onion service address
+pub fn is_onion(host: &str) -> bool {
+    host.trim_end_matches('.')
+        .rsplit_once('.')
+        .is_some_and(|(name, tld)| !name.is_empty() && tld.eq_ignore_ascii_case("onion"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use socks5::connect;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod socks5 {
+    use super::Socks5Proxy;
+    use std::io::{Error, ErrorKind, Result};
+    use std::net::IpAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    const VERSION: u8 = 0x05;
+    const METHOD_NO_AUTH: u8 = 0x00;
+    const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+    const CMD_CONNECT: u8 = 0x01;
+    const ATYP_IPV4: u8 = 0x01;
+    const ATYP_DOMAIN: u8 = 0x03;
+    const ATYP_IPV6: u8 = 0x04;
+
+    /// Connect to `host:port` via the SOCKS5 proxy; hostnames
+    /// are supplied to the proxy without resolving them locally
+    pub(crate) async fn connect(proxy: &Socks5Proxy, host: &str, port: u16) -> Result<TcpStream> {
+        let mut stream = TcpStream::connect(proxy.address.as_str()).await?;
+
+        // method negotiation
+        let method = if proxy.credentials.is_some() {
+            METHOD_USERNAME_PASSWORD
+        } else {
+            METHOD_NO_AUTH
+        };
+        stream.write_all(&[VERSION, 1, method]).await?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != VERSION {
+            return Err(protocol_error("invalid SOCKS version"));
+        }
+        if reply[1] != method {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "SOCKS5 proxy rejected the authentication method",
+            ));
+        }
+
+        // username/password authentication (RFC 1929)
+        if let Some((username, password)) = proxy.credentials.as_ref() {
+            let mut request = vec![0x01];
+            for field in [username, password] {
+                let len = u8::try_from(field.len()).map_err(|_| {
+                    Error::new(ErrorKind::InvalidInput, "SOCKS5 credentials are too long")
+                })?;
+                request.push(len);
+                request.extend_from_slice(field.as_bytes());
+            }
+            stream.write_all(&request).await?;
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0x00 {
+                return Err(Error::new(
+                    ErrorKind::PermissionDenied,
+                    "SOCKS5 proxy authentication failed",
+                ));
+            }
+        }
+
+        // connect request
+        let mut request = vec![VERSION, CMD_CONNECT, 0x00];
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => {
+                request.push(ATYP_IPV4);
+                request.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                request.push(ATYP_IPV6);
+                request.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                let len = u8::try_from(host.len())
+                    .map_err(|_| Error::new(ErrorKind::InvalidInput, "hostname is too long"))?;
+                request.push(ATYP_DOMAIN);
+                request.push(len);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != VERSION {
+            return Err(protocol_error("invalid SOCKS version"));
+        }
+        if reply[1] != 0x00 {
+            return Err(reply_error(reply[1]));
+        }
+
+        // skip the bound address
+        let len = match reply[3] {
+            ATYP_IPV4 => 4,
+            ATYP_IPV6 => 16,
+            ATYP_DOMAIN => stream.read_u8().await? as usize,
+            _ => return Err(protocol_error("invalid address type")),
+        };
+        let mut bound = vec![0u8; len + 2];
+        stream.read_exact(&mut bound).await?;
+
+        Ok(stream)
+    }
+
+    fn protocol_error(message: &str) -> Error {
+        Error::new(ErrorKind::InvalidData, format!("SOCKS5 proxy: {message}"))
+    }
+
+    fn reply_error(reply: u8) -> Error {
+        let (kind, message) = match reply {
+            0x02 => (
+                ErrorKind::PermissionDenied,
+                "connection not allowed by ruleset",
+            ),
+            0x03 => (ErrorKind::Other, "network unreachable"),
+            0x04 => (ErrorKind::NotFound, "host unreachable"),
+            0x05 => (ErrorKind::ConnectionRefused, "connection refused"),
+            0x06 => (ErrorKind::TimedOut, "TTL expired"),
+            0x07 => (ErrorKind::Unsupported, "command not supported"),
+            0x08 => (ErrorKind::Unsupported, "address type not supported"),
+            _ => (ErrorKind::Other, "general failure"),
+        };
+        Error::new(kind, format!("SOCKS5 proxy: {message}"))
+    }
+}
@@ -0,0 +1,220 @@
+//!
+//! Offline queue buffering messages posted while the WebSocket is disconnected
+//!
+
+use super::{error::Error, message::Message, result::Result, state::StateCell};
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+pub const DEFAULT_OFFLINE_QUEUE_CAPACITY: usize = 1024;
+
+/// Returned when a message is rejected by a full queue,
+/// converts into [`Error::QueueFull`]
+#[derive(Debug)]
+pub(crate) struct QueueFull;
+
+impl From<QueueFull> for Error {
+    fn from(_: QueueFull) -> Error {
+        Error::QueueFull
+    }
+}
+
+/// Policy applied when a message is added to a full queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued message
+    #[default]
+    DropOldest,
+    /// Discard the message being added
+    DropNewest,
+    /// Reject the message being added with [`Error::QueueFull`]
+    Reject,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "drop-oldest" => Ok(OverflowPolicy::DropOldest),
+            "drop-newest" => Ok(OverflowPolicy::DropNewest),
+            "reject" => Ok(OverflowPolicy::Reject),
+            _ => Err(Error::InvalidOverflowPolicy(s.to_string())),
+        }
+    }
+}
+
+///
+/// Offline queue options.  When enabled, messages posted using
+/// [`WebSocket::post()`](super::WebSocket::post) while the WebSocket
+/// is not connected are buffered (up to `capacity` messages) and sent
+/// once the connection is re-established (before any other messages).
+///
+#[derive(Clone, Debug)]
+pub struct OfflineQueue {
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for OfflineQueue {
+    fn default() -> Self {
+        OfflineQueue {
+            capacity: DEFAULT_OFFLINE_QUEUE_CAPACITY,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+impl OfflineQueue {
+    pub fn new(capacity: usize) -> Self {
+        OfflineQueue {
+            capacity,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// Messages pending delivery, shared by the client and the dispatcher
+#[derive(Default)]
+pub(crate) struct PendingQueue {
+    messages: Mutex<VecDeque<Message>>,
+}
+
+impl PendingQueue {
+    /// Queue the message if the WebSocket is not connected, returning the
+    /// message back if it should be sent immediately.  The connection state is
+    /// checked while holding the queue lock: the dispatcher updates the state
+    /// before calling [`PendingQueue::take()`], so a message is either queued
+    /// before the queue is flushed or returned to be sent after the flushed messages.
+    pub fn push_if_offline(
+        &self,
+        options: &OfflineQueue,
+        state: &StateCell,
+        message: Message,
+    ) -> std::result::Result<Option<Message>, QueueFull> {
+        let mut messages = self.messages.lock().unwrap();
+        if state.get().is_connected() {
+            return Ok(Some(message));
+        }
+
+        if messages.len() >= options.capacity {
+            match options.overflow {
+                OverflowPolicy::DropOldest => {
+                    messages.pop_front();
+                }
+                OverflowPolicy::DropNewest => return Ok(None),
+                OverflowPolicy::Reject => return Err(QueueFull),
+            }
+        }
+        if options.capacity > 0 {
+            messages.push_back(message);
+        }
+        Ok(None)
+    }
+
+    /// Take all queued messages
+    pub fn take(&self) -> VecDeque<Message> {
+        std::mem::take(&mut *self.messages.lock().unwrap())
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    pub fn clear(&self) {
+        self.messages.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::State;
+
+    fn texts(messages: VecDeque<Message>) -> Vec<String> {
+        messages
+            .into_iter()
+            .map(|message| match message {
+                Message::Text(text) => text,
+                _ => panic!("unexpected message"),
+            })
+            .collect()
+    }
+
+    fn push(queue: &PendingQueue, options: &OfflineQueue, state: &StateCell, text: &str) -> bool {
+        matches!(
+            queue.push_if_offline(options, state, Message::Text(text.to_string())),
+            Ok(None)
+        )
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let state = StateCell::new();
+        for (overflow, expected) in [
+            (OverflowPolicy::DropOldest, vec!["2", "3"]),
+            (OverflowPolicy::DropNewest, vec!["1", "2"]),
+            (OverflowPolicy::Reject, vec!["1", "2"]),
+        ] {
+            let queue = PendingQueue::default();
+            let options = OfflineQueue::new(2).with_overflow(overflow);
+            assert!(push(&queue, &options, &state, "1"));
+            assert!(push(&queue, &options, &state, "2"));
+            let result = queue.push_if_offline(&options, &state, Message::Text("3".into()));
+            match overflow {
+                OverflowPolicy::Reject => assert!(matches!(result, Err(QueueFull))),
+                _ => assert!(matches!(result, Ok(None))),
+            }
+            assert_eq!(queue.len(), 2);
+            assert_eq!(texts(queue.take()), expected);
+            assert_eq!(queue.len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        // the messages are discarded (or rejected) without being queued
+        let state = StateCell::new();
+        let queue = PendingQueue::default();
+        assert!(push(&queue, &OfflineQueue::new(0), &state, "1"));
+        assert_eq!(queue.len(), 0);
+        let options = OfflineQueue::new(0).with_overflow(OverflowPolicy::Reject);
+        assert!(!push(&queue, &options, &state, "1"));
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_connected() {
+        // the messages are returned to be sent while connected
+        let state = StateCell::new();
+        let queue = PendingQueue::default();
+        let options = OfflineQueue::default();
+        assert!(push(&queue, &options, &state, "1"));
+        state.set(State::Open);
+        let result = queue.push_if_offline(&options, &state, Message::Text("2".into()));
+        assert!(matches!(result, Ok(Some(Message::Text(text))) if text == "2"));
+        assert_eq!(texts(queue.take()), vec!["1"]);
+    }
+
+    #[test]
+    fn test_overflow_policy_from_str() {
+        assert_eq!(
+            "drop-oldest".parse::<OverflowPolicy>().unwrap(),
+            OverflowPolicy::DropOldest
+        );
+        assert_eq!(
+            "drop-newest".parse::<OverflowPolicy>().unwrap(),
+            OverflowPolicy::DropNewest
+        );
+        assert_eq!(
+            "reject".parse::<OverflowPolicy>().unwrap(),
+            OverflowPolicy::Reject
+        );
+        assert!("drop".parse::<OverflowPolicy>().is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//!
+//! Outbound message rate limiting
+//!
+
+use workflow_core::time::{Duration, Instant};
+
+///
+/// Outbound rate limit options.  When enabled, messages are sent at most
+/// at the supplied rates (a token bucket allowing bursts of up to one
+/// second worth of messages or bytes); messages exceeding the rate are
+/// held in the sender channel until the bucket refills.  A message larger
+/// than the remaining byte allowance is still sent, delaying the following
+/// messages until the allowance has been replenished.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of messages sent per second (unlimited if `None`)
+    pub messages_per_second: Option<u32>,
+    /// Maximum number of payload bytes sent per second (unlimited if `None`)
+    pub bytes_per_second: Option<u64>,
+}
+
+impl RateLimit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_messages_per_second(mut self, messages_per_second: u32) -> Self {
+        self.messages_per_second = Some(messages_per_second);
+        self
+    }
+
+    pub fn with_bytes_per_second(mut self, bytes_per_second: u64) -> Self {
+        self.bytes_per_second = Some(bytes_per_second);
+        self
+    }
+}
+
+/// Token bucket with the refill `rate` (tokens per second) and the capacity
+/// of one second worth of tokens. Tokens may go negative, in which case
+/// the debt has to be repaid before the bucket is ready again.
+struct Bucket {
+    rate: f64,
+    tokens: f64,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        Bucket { rate, tokens: rate }
+    }
+
+    fn refill(&mut self, elapsed: f64) {
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+    }
+
+    /// Time until the bucket holds at least `tokens` tokens
+    fn wait(&self, tokens: f64) -> f64 {
+        ((tokens - self.tokens) / self.rate).max(0.0)
+    }
+}
+
+/// Rate limiter state tracked by the dispatcher
+pub(crate) struct RateLimiter {
+    messages: Option<Bucket>,
+    bytes: Option<Bucket>,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate_limit: Option<RateLimit>) -> Self {
+        let rate_limit = rate_limit.unwrap_or_default();
+        RateLimiter {
+            messages: rate_limit
+                .messages_per_second
+                .filter(|rate| *rate > 0)
+                .map(|rate| Bucket::new(rate as f64)),
+            bytes: rate_limit
+                .bytes_per_second
+                .filter(|rate| *rate > 0)
+                .map(|rate| Bucket::new(rate as f64)),
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        for bucket in [self.messages.as_mut(), self.bytes.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            bucket.refill(elapsed);
+        }
+    }
+
+    /// Resolves once the next message can be sent
+    pub async fn ready(&mut self) {
+        loop {
+            self.refill();
+            let messages = self.messages.as_ref().map(|bucket| bucket.wait(1.0));
+            let bytes = self.bytes.as_ref().map(|bucket| bucket.wait(0.0));
+            let wait = messages.into_iter().chain(bytes).fold(0.0, f64::max);
+            if wait <= 0.0 {
+                break;
+            }
+            workflow_core::task::sleep(Duration::from_secs_f64(wait)).await;
+        }
+    }
+
+    /// Consume the tokens for a message with the payload of `len` bytes
+    pub fn consume(&mut self, len: usize) {
+        if let Some(bucket) = self.messages.as_mut() {
+            bucket.tokens -= 1.0;
+        }
+        if let Some(bucket) = self.bytes.as_mut() {
+            bucket.tokens -= len as f64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bucket() {
+        let mut bucket = Bucket::new(10.0);
+        assert_eq!(bucket.wait(1.0), 0.0);
+
+        // the debt has to be repaid before the bucket is ready
+        bucket.tokens -= 15.0;
+        assert_eq!(bucket.wait(0.0), 0.5);
+        bucket.refill(0.2);
+        assert_eq!(bucket.tokens, -3.0);
+
+        // the bucket holds at most one second worth of tokens
+        bucket.refill(10.0);
+        assert_eq!(bucket.tokens, 10.0);
+        assert_eq!(bucket.wait(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_rate_limiter_disabled() {
+        let limiter = RateLimiter::new(None);
+        assert!(limiter.messages.is_none() && limiter.bytes.is_none());
+        // zero rates are treated as unlimited
+        let rate_limit = RateLimit::new()
+            .with_messages_per_second(0)
+            .with_bytes_per_second(0);
+        let limiter = RateLimiter::new(Some(rate_limit));
+        assert!(limiter.messages.is_none() && limiter.bytes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_messages() {
+        let mut limiter = RateLimiter::new(Some(RateLimit::new().with_messages_per_second(20)));
+        let start = Instant::now();
+        // the burst of one second worth of messages is not delayed
+        for _ in 0..20 {
+            limiter.ready().await;
+            limiter.consume(0);
+        }
+        assert!(start.elapsed() < Duration::from_millis(40));
+        // the following message waits for the token (50ms)
+        limiter.ready().await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_bytes() {
+        let mut limiter = RateLimiter::new(Some(RateLimit::new().with_bytes_per_second(100_000)));
+        limiter.ready().await;
+        // the message larger than the allowance is sent, delaying
+        // the following message until the debt has been repaid (50ms)
+        limiter.consume(105_000);
+        let start = Instant::now();
+        limiter.ready().await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! Sender and receiver halves of the WebSocket client
+//!
+
+use super::{error::Error, message::Message, result::Result, SendHandle, WebSocket};
+use std::sync::Arc;
+use workflow_core::time::Duration;
+
+///
+/// Sending half of the [`WebSocket`] created using [`WebSocket::split()`].
+/// The sender can be cloned and moved to the tasks producing messages.
+///
+#[derive(Clone)]
+pub struct WebSocketSender {
+    websocket: WebSocket,
+}
+
+impl WebSocketSender {
+    pub(crate) fn new(websocket: WebSocket) -> Self {
+        WebSocketSender { websocket }
+    }
+
+    /// Returns true if websocket is connected, false otherwise
+    pub fn is_connected(&self) -> bool {
+        self.websocket.is_connected()
+    }
+
+    /// Sends a message to the destination server (see [`WebSocket::post()`]).
+    pub async fn post(&self, message: Message) -> Result<&Self> {
+        self.websocket.post(message).await?;
+        Ok(self)
+    }
+
+    /// Sends a message to the destination server and waits until the
+    /// message is relayed to the underlying websocket implementation
+    /// (see [`WebSocket::send()`]).
+    pub async fn send(&self, message: Message) -> std::result::Result<&Self, Arc<Error>> {
+        self.websocket.send(message).await?;
+        Ok(self)
+    }
+
+    /// Sends a message to the destination server with a timeout
+    /// (see [`WebSocket::post_with_timeout()`]).
+    pub async fn post_with_timeout(
+        &self,
+        message: Message,
+        timeout: Duration,
+    ) -> Result<SendHandle> {
+        self.websocket.post_with_timeout(message, timeout).await
+    }
+}
+
+///
+/// Receiving half of the [`WebSocket`] created using [`WebSocket::split()`].
+/// Messages received by the WebSocket are delivered to a single consumer:
+/// while the receiver is in use, messages should not be received via the
+/// [`WebSocket`] handle (messages would be distributed between the two).
+///
+pub struct WebSocketReceiver {
+    websocket: WebSocket,
+}
+
+impl WebSocketReceiver {
+    /// Receives message from the websocket. Blocks until a message is
+    /// received from the underlying websocket connection.
+    pub async fn recv(&mut self) -> Result<Message> {
+        self.websocket.recv().await
+    }
+}
+
+impl WebSocket {
+    /// Split the WebSocket into the [`WebSocketSender`] and [`WebSocketReceiver`]
+    /// halves, allowing producers and the consumer to run in separate tasks.
+    /// The WebSocket handle remains usable for managing the connection.
+    pub fn split(&self) -> (WebSocketSender, WebSocketReceiver) {
+        (
+            WebSocketSender::new(self.clone()),
+            WebSocketReceiver {
+                websocket: self.clone(),
+            },
+        )
+    }
+}
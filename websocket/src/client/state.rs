@@ -0,0 +1,170 @@
+//!
+//! Connection state of the WebSocket client
+//!
+
+use super::{error::Error, result::Result};
+use std::sync::{Arc, Mutex, Weak};
+use workflow_core::channel::*;
+
+/// Connection state returned by [`WebSocket::state()`](super::WebSocket::state)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum State {
+    /// Connection to the server is being established
+    Connecting,
+    /// Connection has been established and the [`Handshake`](super::Handshake)
+    /// negotiation is in progress
+    Handshaking,
+    /// Connection is open
+    Open,
+    /// Connection is being closed by the client
+    Closing,
+    /// Connection is closed (the client is not connected and does not reconnect)
+    Closed,
+    /// Client is waiting for the retry delay before the reconnect attempt
+    /// `attempt` (starting from `1`)
+    Retrying { attempt: u32 },
+}
+
+impl State {
+    /// Returns `true` if the connection is established (messages can be sent)
+    pub fn is_connected(&self) -> bool {
+        matches!(self, State::Handshaking | State::Open | State::Closing)
+    }
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::Connecting => write!(f, "connecting"),
+            State::Handshaking => write!(f, "handshaking"),
+            State::Open => write!(f, "open"),
+            State::Closing => write!(f, "closing"),
+            State::Closed => write!(f, "closed"),
+            State::Retrying { attempt } => write!(f, "retrying (attempt {attempt})"),
+        }
+    }
+}
+
+///
+/// Watch-style receiver of the state changes created using
+/// [`WebSocket::state_changes()`](super::WebSocket::state_changes).
+/// The first call to [`StateChanges::recv()`] returns the state at the time
+/// of the subscription; subsequent calls wait for the next state change.
+/// Only the latest state is retained, intermediate states are skipped if the
+/// receiver falls behind.  The receiver is unregistered when dropped.
+///
+pub struct StateChanges {
+    channel: Arc<Channel<State>>,
+}
+
+impl StateChanges {
+    /// Receive the latest state, waiting until the state changes
+    pub async fn recv(&self) -> Result<State> {
+        self.channel.recv().await.map_err(|_| Error::ReceiveChannel)
+    }
+
+    /// Receive the latest state if it has changed since the last call
+    pub fn try_recv(&self) -> Option<State> {
+        self.channel.try_recv().ok()
+    }
+}
+
+/// Current state of the connection, notifying the [`StateChanges`] receivers
+pub(crate) struct StateCell {
+    state: Mutex<State>,
+    watchers: Mutex<Vec<Weak<Channel<State>>>>,
+}
+
+impl StateCell {
+    pub fn new() -> Self {
+        StateCell {
+            state: Mutex::new(State::Closed),
+            watchers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn get(&self) -> State {
+        *self.state.lock().unwrap()
+    }
+
+    pub fn set(&self, state: State) {
+        let mut current = self.state.lock().unwrap();
+        if *current == state {
+            return;
+        }
+        *current = state;
+        self.watchers
+            .lock()
+            .unwrap()
+            .retain(|watcher| match watcher.upgrade() {
+                Some(channel) => {
+                    publish(&channel, state);
+                    true
+                }
+                None => false,
+            });
+    }
+
+    pub fn watch(&self) -> StateChanges {
+        let current = self.state.lock().unwrap();
+        let channel = Arc::new(Channel::bounded(1));
+        publish(&channel, *current);
+        self.watchers.lock().unwrap().push(Arc::downgrade(&channel));
+        StateChanges { channel }
+    }
+}
+
+impl Drop for StateCell {
+    fn drop(&mut self) {
+        for watcher in self.watchers.lock().unwrap().iter() {
+            if let Some(channel) = watcher.upgrade() {
+                channel.sender.close();
+            }
+        }
+    }
+}
+
+/// Replace the state retained by the channel with `state`
+fn publish(channel: &Channel<State>, mut state: State) {
+    while let Err(TrySendError::Full(rejected)) = channel.try_send(state) {
+        channel.try_recv().ok();
+        state = rejected;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_state_changes() {
+        let cell = StateCell::new();
+        let changes = cell.watch();
+        assert_eq!(changes.try_recv(), Some(State::Closed));
+        assert_eq!(changes.try_recv(), None);
+
+        // setting the current state is not a change
+        cell.set(State::Closed);
+        assert_eq!(changes.try_recv(), None);
+
+        // only the latest state is retained
+        cell.set(State::Connecting);
+        cell.set(State::Open);
+        assert_eq!(changes.try_recv(), Some(State::Open));
+        assert!(cell.get().is_connected());
+
+        // dropped receivers are unregistered
+        drop(changes);
+        cell.set(State::Closed);
+        assert!(cell.watchers.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_closed() {
+        let cell = StateCell::new();
+        let changes = cell.watch();
+        assert_eq!(changes.recv().await.unwrap(), State::Closed);
+        drop(cell);
+        assert!(matches!(changes.recv().await, Err(Error::ReceiveChannel)));
+    }
+}
@@ -0,0 +1,79 @@
+//!
+//! Binary messages streamed as a sequence of WebSocket frames
+//!
+
+use super::message::Ack;
+use futures::io::{AsyncRead, AsyncReadExt};
+use std::pin::Pin;
+use tokio_tungstenite::tungstenite::protocol::frame::{
+    coding::{Data, OpCode},
+    Frame,
+};
+
+pub(crate) type StreamReader = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Binary message read from `reader` and sent as a fragmented
+/// message (the initial frame followed by continuation frames)
+pub(crate) struct OutboundStream {
+    reader: StreamReader,
+    chunk_size: usize,
+    ack: Ack,
+    buffer: Vec<u8>,
+    eof: bool,
+    started: bool,
+    len: usize,
+}
+
+impl OutboundStream {
+    pub fn new(reader: StreamReader, chunk_size: usize, ack: Ack) -> Self {
+        OutboundStream {
+            reader,
+            chunk_size: chunk_size.max(1),
+            ack,
+            buffer: Vec::new(),
+            eof: false,
+            started: false,
+            len: 0,
+        }
+    }
+
+    /// `true` once the first frame has been produced
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Total payload length of the frames produced so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn take_ack(&mut self) -> Ack {
+        self.ack.take()
+    }
+
+    /// Read the next frame of the message.  The reader is read one byte
+    /// ahead of the frame in order to mark the last frame as final.  The
+    /// future is cancel-safe: data read before the future is dropped is
+    /// retained for the next call.
+    pub async fn next_frame(&mut self) -> std::io::Result<Frame> {
+        while !self.eof && self.buffer.len() <= self.chunk_size {
+            let mut data = vec![0; self.chunk_size + 1 - self.buffer.len()];
+            match self.reader.read(&mut data).await? {
+                0 => self.eof = true,
+                n => self.buffer.extend_from_slice(&data[..n]),
+            }
+        }
+
+        let len = self.buffer.len().min(self.chunk_size);
+        let payload = self.buffer.drain(..len).collect::<Vec<_>>();
+        let is_final = self.eof && self.buffer.is_empty();
+        let opcode = if self.started {
+            OpCode::Data(Data::Continue)
+        } else {
+            OpCode::Data(Data::Binary)
+        };
+        self.started = true;
+        self.len += len;
+        Ok(Frame::message(payload, opcode, is_final))
+    }
+}
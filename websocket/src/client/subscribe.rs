@@ -0,0 +1,272 @@
+//!
+//! Receiver channel overflow policy and broadcast subscriptions
+//! to inbound messages and connection events
+//!
+
+use super::{error::Error, event::Event, message::Message, result::Result};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use thiserror::Error;
+use workflow_core::channel::*;
+use workflow_log::log_trace;
+
+///
+/// Policy applied when a message is received while the bounded receiver
+/// channel is full (see [`WebSocketConfig::receiver_channel_cap`](super::WebSocketConfig::receiver_channel_cap)).
+/// The [`Message::Open`], [`Message::Close`] and [`Message::ReconnectExhausted`]
+/// notifications are never dropped: unless the policy is `Block`, the oldest
+/// queued message is discarded to make room for the notification.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReceiverOverflow {
+    /// Suspend the dispatcher until the application receives a message
+    /// (stops reading from the connection)
+    #[default]
+    Block,
+    /// Discard the oldest queued message
+    DropOldest,
+    /// Discard the received message
+    DropNewest,
+    /// Close the connection, posting the [`Error::ReceiverOverflow`] error
+    /// event (the client does not reconnect)
+    Disconnect,
+}
+
+impl FromStr for ReceiverOverflow {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "block" => Ok(ReceiverOverflow::Block),
+            "drop-oldest" => Ok(ReceiverOverflow::DropOldest),
+            "drop-newest" => Ok(ReceiverOverflow::DropNewest),
+            "disconnect" => Ok(ReceiverOverflow::Disconnect),
+            _ => Err(Error::InvalidOverflowPolicy(s.to_string())),
+        }
+    }
+}
+
+/// Item delivered to the [`Subscription`]s
+#[derive(Clone, Debug)]
+pub enum Broadcast {
+    /// Message received by the WebSocket (including the
+    /// [`Message::Open`] and [`Message::Close`] notifications)
+    Message(Message),
+    /// Connection event
+    Event(Event),
+}
+
+struct Subscriber {
+    channel: Channel<Broadcast>,
+    lagged: AtomicU64,
+}
+
+impl Subscriber {
+    /// Queue the item, dropping the oldest queued item if the channel is full
+    fn publish(&self, mut item: Broadcast) {
+        while let Err(TrySendError::Full(rejected)) = self.channel.try_send(item) {
+            if self.channel.try_recv().is_ok() {
+                self.lagged.fetch_add(1, Ordering::Relaxed);
+            }
+            item = rejected;
+        }
+    }
+}
+
+///
+/// Broadcast receiver created using [`WebSocket::subscribe()`](super::WebSocket::subscribe).
+/// Each subscription receives a copy of every inbound message and connection
+/// event independently of the other subscriptions and of the WebSocket
+/// receiver channel.  Items are queued up to the capacity configured using
+/// [`WebSocketConfig::subscriber_channel_cap`](super::WebSocketConfig::subscriber_channel_cap);
+/// if the subscription falls behind, the oldest items are dropped and the
+/// next call to [`Subscription::recv()`] returns [`Error::Lagged`] with the
+/// number of dropped items.  The subscription is unregistered when dropped.
+///
+pub struct Subscription {
+    subscriber: Arc<Subscriber>,
+}
+
+impl Subscription {
+    /// Receive the next item, waiting until an item is available
+    pub async fn recv(&self) -> Result<Broadcast> {
+        self.check_lagged()?;
+        Ok(self.subscriber.channel.recv().await?)
+    }
+
+    /// Receive the next item if available (`None` if no items are queued)
+    pub fn try_recv(&self) -> std::result::Result<Option<Broadcast>, Lagged> {
+        self.check_lagged()?;
+        Ok(self.subscriber.channel.try_recv().ok())
+    }
+
+    fn check_lagged(&self) -> std::result::Result<(), Lagged> {
+        match self.subscriber.lagged.swap(0, Ordering::Relaxed) {
+            0 => Ok(()),
+            lagged => Err(Lagged(lagged)),
+        }
+    }
+}
+
+/// Returned by [`Subscription::try_recv()`] when the subscription has fallen
+/// behind, carries the number of dropped items; converts into [`Error::Lagged`]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Subscription lagged behind, {0} items have been dropped")]
+pub struct Lagged(pub u64);
+
+impl From<Lagged> for Error {
+    fn from(lagged: Lagged) -> Error {
+        Error::Lagged(lagged.0)
+    }
+}
+
+/// Receiver channel applying the [`ReceiverOverflow`] policy and relaying
+/// the inbound messages and events to the subscriptions
+pub(crate) struct ReceiverChannel {
+    channel: Channel<Message>,
+    overflow: ReceiverOverflow,
+    subscribers: Mutex<Vec<Weak<Subscriber>>>,
+}
+
+impl ReceiverChannel {
+    pub fn new(channel: Channel<Message>, overflow: ReceiverOverflow) -> Self {
+        ReceiverChannel {
+            channel,
+            overflow,
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue the message, applying the overflow policy if the channel is full;
+    /// fails with [`Error::ReceiverOverflow`] if the connection should be closed
+    pub async fn send(&self, mut msg: Message) -> Result<()> {
+        self.publish(|| Broadcast::Message(msg.clone()));
+        if self.overflow == ReceiverOverflow::Block {
+            return Ok(self.channel.send(msg).await?);
+        }
+
+        let notification = matches!(
+            msg,
+            Message::Open | Message::Close | Message::ReconnectExhausted
+        );
+        loop {
+            msg = match self.channel.try_send(msg) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(rejected)) => match self.overflow {
+                    _ if notification => rejected,
+                    ReceiverOverflow::DropOldest => rejected,
+                    ReceiverOverflow::DropNewest => {
+                        log_trace!("WebSocket receiver channel is full, dropping message");
+                        return Ok(());
+                    }
+                    _ => return Err(Error::ReceiverOverflow),
+                },
+                Err(err) => return Err(err.into()),
+            };
+            // discard the oldest message to make room
+            if self.channel.try_recv().is_ok() {
+                log_trace!("WebSocket receiver channel is full, dropping the oldest message");
+            }
+        }
+    }
+
+    pub fn try_send(&self, msg: Message) -> std::result::Result<(), TrySendError<Message>> {
+        self.publish(|| Broadcast::Message(msg.clone()));
+        self.channel.try_send(msg)
+    }
+
+    pub fn publish_event(&self, event: &Event) {
+        self.publish(|| Broadcast::Event(event.clone()));
+    }
+
+    pub fn subscribe(&self, capacity: usize) -> Subscription {
+        let subscriber = Arc::new(Subscriber {
+            channel: Channel::bounded(capacity.max(1)),
+            lagged: AtomicU64::new(0),
+        });
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&subscriber));
+        Subscription { subscriber }
+    }
+
+    fn publish(&self, item: impl Fn() -> Broadcast) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| match subscriber.upgrade() {
+                Some(subscriber) => {
+                    subscriber.publish(item());
+                    true
+                }
+                None => false,
+            });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(text: &str) -> Message {
+        Message::Text(text.to_string())
+    }
+
+    fn texts(channel: &Channel<Message>) -> Vec<String> {
+        std::iter::from_fn(|| channel.try_recv().ok())
+            .map(|message| match message {
+                Message::Text(text) => text,
+                message => format!("{message:?}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_receiver_overflow() {
+        for (overflow, expected) in [
+            (ReceiverOverflow::DropOldest, vec!["2", "3"]),
+            (ReceiverOverflow::DropNewest, vec!["1", "2"]),
+        ] {
+            let channel = Channel::bounded(2);
+            let receiver = ReceiverChannel::new(channel.clone(), overflow);
+            for item in ["1", "2", "3"] {
+                receiver.send(text(item)).await.unwrap();
+            }
+            assert_eq!(texts(&channel), expected);
+        }
+
+        let channel = Channel::bounded(1);
+        let receiver = ReceiverChannel::new(channel.clone(), ReceiverOverflow::Disconnect);
+        receiver.send(text("1")).await.unwrap();
+        assert!(matches!(
+            receiver.send(text("2")).await,
+            Err(Error::ReceiverOverflow)
+        ));
+        // notifications replace the oldest message instead of failing
+        receiver.send(Message::Close).await.unwrap();
+        assert!(matches!(channel.try_recv(), Ok(Message::Close)));
+    }
+
+    #[test]
+    fn test_subscription_lagged() {
+        let receiver = ReceiverChannel::new(Channel::unbounded(), ReceiverOverflow::Block);
+        let subscription = receiver.subscribe(2);
+        for item in ["1", "2", "3", "4"] {
+            receiver.try_send(text(item)).unwrap();
+        }
+        assert!(matches!(subscription.try_recv(), Err(Lagged(2))));
+        for expected in ["3", "4"] {
+            assert!(matches!(
+                subscription.try_recv(),
+                Ok(Some(Broadcast::Message(Message::Text(text)))) if text == expected
+            ));
+        }
+        assert!(matches!(subscription.try_recv(), Ok(None)));
+
+        // dropped subscriptions are unregistered
+        drop(subscription);
+        receiver.try_send(text("5")).unwrap();
+        assert!(receiver.subscribers.lock().unwrap().is_empty());
+    }
+}
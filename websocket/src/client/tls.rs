@@ -0,0 +1,305 @@
+//!
+//! TLS configuration for native WebSocket connections
+//!
+
+/// Client certificate used for mutual TLS authentication
+#[derive(Clone)]
+pub struct ClientCertificate {
+    /// PEM-encoded certificate chain (leaf certificate first)
+    pub certificate_chain: Vec<u8>,
+    /// PEM-encoded PKCS#8 private key
+    pub private_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ClientCertificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientCertificate")
+            .field("certificate_chain", &self.certificate_chain.len())
+            .finish_non_exhaustive()
+    }
+}
+
+///
+/// TLS options applied to `wss://` connections of the native client
+/// (ignored by the WASM client which uses the TLS configuration of the
+/// browser or Node.js).  The TLS backend is selected using the `native-tls`
+/// (default) or `rustls-tls-*` crate features.
+///
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// PEM-encoded root CA certificates trusted in addition to the
+    /// system (native-tls, rustls-tls-native-roots) or bundled
+    /// (rustls-tls-webpki-roots) root certificates.
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Client certificate presented to the server
+    pub client_certificate: Option<ClientCertificate>,
+    /// Accept invalid server certificates (including expired, self-signed
+    /// or certificates issued for a different host).  This option disables
+    /// server authentication and should only be used for testing.
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl TlsOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust an additional PEM-encoded root CA certificate (or bundle)
+    pub fn with_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Present a PEM-encoded client certificate chain and PKCS#8 private key
+    pub fn with_client_certificate(
+        mut self,
+        certificate_chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_certificate = Some(ClientCertificate {
+            certificate_chain: certificate_chain.into(),
+            private_key: private_key.into(),
+        });
+        self
+    }
+
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use connector::{connect_async, connector};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod connector {
+    use super::TlsOptions;
+    use crate::client::{error::Error, result::Result, transport::Transport, DeflateConfig};
+    use crate::deflate::DeflateStream;
+    use tokio_tungstenite::tungstenite::handshake::client::{Request, Response};
+    use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+    use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+    /// TLS configuration error, converts into [`Error::Tls`]
+    #[derive(Debug)]
+    pub(crate) struct TlsError(String);
+
+    impl From<TlsError> for Error {
+        fn from(err: TlsError) -> Error {
+            Error::Tls(err.0)
+        }
+    }
+
+    /// Perform the WebSocket upgrade over the stream, establishing the TLS session
+    /// for `wss://` requests using the supplied connector (or the default connector
+    /// if `None`).  The `permessage-deflate` compression is applied above the TLS
+    /// session if offered in the request using the `deflate` configuration.
+    pub(crate) async fn connect_async(
+        request: Request,
+        stream: Transport,
+        config: Option<WebSocketConfig>,
+        connector: Option<Connector>,
+        deflate: Option<DeflateConfig>,
+    ) -> Result<(
+        WebSocketStream<DeflateStream<MaybeTlsStream<Transport>>>,
+        Response,
+    )> {
+        let stream = wrap_stream(&request, stream, connector).await?;
+        let stream = DeflateStream::client(stream, deflate).with_limits(config.as_ref());
+        Ok(tokio_tungstenite::client_async_with_config(request, stream, config).await?)
+    }
+
+    cfg_if::cfg_if! {
+        if #[cfg(any(
+            feature = "native-tls",
+            feature = "rustls-tls-native-roots",
+            feature = "rustls-tls-webpki-roots"
+        ))] {
+            use tokio_tungstenite::tungstenite::error::UrlError;
+
+            /// Establish the TLS session for `wss://` requests
+            async fn wrap_stream(
+                request: &Request,
+                stream: Transport,
+                connector: Option<Connector>,
+            ) -> Result<MaybeTlsStream<Transport>> {
+                if request.uri().scheme_str() != Some("wss") {
+                    return Ok(MaybeTlsStream::Plain(stream));
+                }
+                let domain = request
+                    .uri()
+                    .host()
+                    .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?;
+                let connector = match connector {
+                    Some(connector) => connector,
+                    None => self::connector(&TlsOptions::default())?,
+                };
+
+                match connector {
+                    #[cfg(feature = "native-tls")]
+                    Connector::NativeTls(connector) => {
+                        let stream = tokio_native_tls::TlsConnector::from(connector)
+                            .connect(domain, stream)
+                            .await
+                            .map_err(tls_error)?;
+                        Ok(MaybeTlsStream::NativeTls(stream))
+                    }
+                    #[cfg(any(feature = "rustls-tls-native-roots", feature = "rustls-tls-webpki-roots"))]
+                    Connector::Rustls(config) => {
+                        // rustls expects the IPv6 addresses without the brackets
+                        let domain = domain.trim_start_matches('[').trim_end_matches(']');
+                        let domain = rustls::pki_types::ServerName::try_from(domain)
+                            .map_err(tls_error)?
+                            .to_owned();
+                        let stream = tokio_rustls::TlsConnector::from(config)
+                            .connect(domain, stream)
+                            .await
+                            .map_err(tls_error)?;
+                        Ok(MaybeTlsStream::Rustls(stream))
+                    }
+                    // `Connector::Plain`
+                    _ => Ok(MaybeTlsStream::Plain(stream)),
+                }
+            }
+        } else {
+            use tokio_tungstenite::tungstenite::error::UrlError;
+
+            /// TLS is not available without a TLS backend feature
+            async fn wrap_stream(
+                request: &Request,
+                stream: Transport,
+                _connector: Option<Connector>,
+            ) -> Result<MaybeTlsStream<Transport>> {
+                if request.uri().scheme_str() == Some("wss") {
+                    return Err(tungstenite::Error::Url(UrlError::TlsFeatureNotEnabled).into());
+                }
+                Ok(MaybeTlsStream::Plain(stream))
+            }
+        }
+    }
+
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "native-tls")] {
+            use native_tls::{Certificate, Identity, TlsConnector};
+
+            /// Create the native-tls connector for the supplied options
+            pub(crate) fn connector(options: &TlsOptions) -> std::result::Result<Connector, TlsError> {
+                let mut builder = TlsConnector::builder();
+                for pem in options.root_certificates.iter() {
+                    let certificate = Certificate::from_pem(pem).map_err(tls_error)?;
+                    builder.add_root_certificate(certificate);
+                }
+                if let Some(client) = options.client_certificate.as_ref() {
+                    let identity =
+                        Identity::from_pkcs8(&client.certificate_chain, &client.private_key)
+                            .map_err(tls_error)?;
+                    builder.identity(identity);
+                }
+                builder.danger_accept_invalid_certs(options.danger_accept_invalid_certs);
+                Ok(Connector::NativeTls(builder.build().map_err(tls_error)?))
+            }
+
+        } else if #[cfg(any(feature = "rustls-tls-native-roots", feature = "rustls-tls-webpki-roots"))] {
+            use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+            use rustls::crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+            use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+            use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+            use std::sync::Arc;
+
+            /// Create the rustls connector for the supplied options
+            pub(crate) fn connector(options: &TlsOptions) -> std::result::Result<Connector, TlsError> {
+                let mut roots = RootCertStore::empty();
+                #[cfg(feature = "rustls-tls-native-roots")]
+                roots.add_parsable_certificates(
+                    rustls_native_certs::load_native_certs().map_err(tls_error)?,
+                );
+                #[cfg(feature = "rustls-tls-webpki-roots")]
+                roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+                for pem in options.root_certificates.iter() {
+                    for certificate in certificates(pem)? {
+                        roots.add(certificate).map_err(tls_error)?;
+                    }
+                }
+
+                let builder = ClientConfig::builder().with_root_certificates(roots);
+                let mut config = if let Some(client) = options.client_certificate.as_ref() {
+                    let key = rustls_pemfile::private_key(&mut client.private_key.as_slice())
+                        .map_err(tls_error)?
+                        .ok_or_else(|| TlsError("missing client private key".to_string()))?;
+                    builder
+                        .with_client_auth_cert(certificates(&client.certificate_chain)?, key)
+                        .map_err(tls_error)?
+                } else {
+                    builder.with_no_client_auth()
+                };
+
+                if options.danger_accept_invalid_certs {
+                    config
+                        .dangerous()
+                        .set_certificate_verifier(Arc::new(AcceptAnyCertificate(ring::default_provider())));
+                }
+
+                Ok(Connector::Rustls(Arc::new(config)))
+            }
+
+            fn certificates(pem: &[u8]) -> std::result::Result<Vec<CertificateDer<'static>>, TlsError> {
+                rustls_pemfile::certs(&mut &pem[..])
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(tls_error)
+            }
+
+            /// Server certificate verifier accepting any certificate
+            /// (used with `danger_accept_invalid_certs`), verifying
+            /// only the handshake signatures.
+            #[derive(Debug)]
+            struct AcceptAnyCertificate(CryptoProvider);
+
+            impl ServerCertVerifier for AcceptAnyCertificate {
+                fn verify_server_cert(
+                    &self,
+                    _end_entity: &CertificateDer<'_>,
+                    _intermediates: &[CertificateDer<'_>],
+                    _server_name: &ServerName<'_>,
+                    _ocsp_response: &[u8],
+                    _now: UnixTime,
+                ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+                    Ok(ServerCertVerified::assertion())
+                }
+
+                fn verify_tls12_signature(
+                    &self,
+                    message: &[u8],
+                    cert: &CertificateDer<'_>,
+                    dss: &DigitallySignedStruct,
+                ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+                    verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+                }
+
+                fn verify_tls13_signature(
+                    &self,
+                    message: &[u8],
+                    cert: &CertificateDer<'_>,
+                    dss: &DigitallySignedStruct,
+                ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+                    verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+                }
+
+                fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+                    self.0.signature_verification_algorithms.supported_schemes()
+                }
+            }
+
+        } else {
+            /// TLS is not available without a TLS backend feature
+            pub(crate) fn connector(_options: &TlsOptions) -> std::result::Result<Connector, TlsError> {
+                Err(TlsError("no TLS backend feature enabled".to_string()))
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    fn tls_error<E: std::fmt::Display>(err: E) -> TlsError {
+        TlsError(err.to_string())
+    }
+}
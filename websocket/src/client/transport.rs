@@ -0,0 +1,252 @@
+//!
+//! Transport streams of native WebSocket connections
+//!
+
+use super::{dial, ConnectOptions};
+use async_trait::async_trait;
+use std::io::IoSlice;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+
+/// URL scheme prefixes of the WebSocket connections over unix domain sockets
+const UNIX_SCHEMES: [(&str, &str); 2] = [("ws+unix://", "ws"), ("wss+unix://", "wss")];
+
+/// Returns `true` if the URL uses the `ws+unix://` or `wss+unix://` scheme
+pub(crate) fn is_unix_url(url: &str) -> bool {
+    UNIX_SCHEMES
+        .iter()
+        .any(|(prefix, _)| url.starts_with(prefix))
+}
+
+///
+/// Split the `ws+unix:///path/socket:/ws-path` URL into the path of the
+/// unix domain socket and the `ws://localhost/ws-path` URL of the upgrade
+/// request (the request path defaults to `/`).  Returns `None` if the URL
+/// does not use the `ws+unix://` or `wss+unix://` scheme or if the socket
+/// path is missing.
+///
+pub(crate) fn unix_socket_url(url: &str) -> Option<(PathBuf, String)> {
+    let (rest, scheme) = UNIX_SCHEMES
+        .iter()
+        .find_map(|(prefix, scheme)| url.strip_prefix(prefix).map(|rest| (rest, scheme)))?;
+    let (socket, path) = rest.split_once(':').unwrap_or((rest, "/"));
+    if socket.is_empty() {
+        return None;
+    }
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    };
+    Some((PathBuf::from(socket), format!("{scheme}://localhost{path}")))
+}
+
+/// Stream supplied by the application carrying the WebSocket connection
+pub trait ClientStream: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<S> ClientStream for S where S: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+///
+/// Connector supplying the streams of the native client connections in
+/// place of the TCP (or unix domain socket) connection to the host of the
+/// URL, e.g. a connection bridged over QUIC, a TLS session terminated by
+/// the application or an in-process test harness.  The connector is invoked
+/// with the URL for every connection attempt (including reconnects); the
+/// WebSocket handshake (over TLS for `wss://` URLs), the dispatcher and the
+/// connect strategy are applied to the returned stream.  The DNS resolver
+/// and the proxy are not used.
+///
+#[async_trait]
+pub trait StreamConnector: Send + Sync + 'static {
+    async fn connect(&self, url: &str) -> std::io::Result<Box<dyn ClientStream>>;
+}
+
+impl std::fmt::Debug for dyn StreamConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StreamConnector")
+    }
+}
+
+/// Connector supplying the single stream of
+/// [`WebSocket::connect_with_stream()`](super::WebSocket::connect_with_stream)
+pub(crate) struct SingleStream {
+    stream: Mutex<Option<Box<dyn ClientStream>>>,
+}
+
+impl SingleStream {
+    pub fn new(stream: impl ClientStream) -> Self {
+        SingleStream {
+            stream: Mutex::new(Some(Box::new(stream))),
+        }
+    }
+}
+
+#[async_trait]
+impl StreamConnector for SingleStream {
+    async fn connect(&self, _url: &str) -> std::io::Result<Box<dyn ClientStream>> {
+        self.stream.lock().unwrap().take().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "the supplied stream has already been used",
+            )
+        })
+    }
+}
+
+/// Stream carrying the WebSocket connection
+pub(crate) enum Transport {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+    Custom(Box<dyn ClientStream>),
+}
+
+impl Transport {
+    ///
+    /// Obtain the stream from the [`StreamConnector`] or connect to the unix
+    /// domain socket if supplied, otherwise establish the TCP connection to
+    /// the host of the request URL (attempting all resolved addresses or
+    /// connecting via the proxy, see [`dial::connect()`])
+    ///
+    pub(crate) async fn connect(
+        request: &Request,
+        url: &str,
+        unix_socket: Option<&Path>,
+        options: &ConnectOptions,
+    ) -> std::result::Result<Transport, tungstenite::Error> {
+        if let Some(connector) = options.stream_connector.as_ref() {
+            return Ok(Transport::Custom(connector.connect(url).await?));
+        }
+
+        if let Some(unix_socket) = unix_socket {
+            cfg_if::cfg_if! {
+                if #[cfg(unix)] {
+                    return Ok(Transport::Unix(UnixStream::connect(unix_socket).await?));
+                } else {
+                    let _ = unix_socket;
+                    return Err(tungstenite::Error::Url(UrlError::UnsupportedUrlScheme));
+                }
+            }
+        }
+
+        let uri = request.uri();
+        let host = uri
+            .host()
+            .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?;
+        let port = uri
+            .port_u16()
+            .or_else(|| match uri.scheme_str() {
+                Some("wss") => Some(443),
+                Some("ws") => Some(80),
+                _ => None,
+            })
+            .ok_or(tungstenite::Error::Url(UrlError::UnsupportedUrlScheme))?;
+        let stream = dial::connect(
+            host,
+            port,
+            options.dns_resolver.as_ref(),
+            options.proxy.as_ref(),
+        )
+        .await
+        .map_err(tungstenite::Error::Io)?;
+        Ok(Transport::Tcp(stream))
+    }
+}
+
+macro_rules! transport {
+    ($self:ident, $stream:ident => $expr:expr) => {
+        match $self.get_mut() {
+            Transport::Tcp($stream) => $expr,
+            #[cfg(unix)]
+            Transport::Unix($stream) => $expr,
+            Transport::Custom($stream) => $expr,
+        }
+    };
+}
+
+impl AsyncRead for Transport {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        transport!(self, stream => Pin::new(stream).poll_read(cx, buf))
+    }
+}
+
+impl AsyncWrite for Transport {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        transport!(self, stream => Pin::new(stream).poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        transport!(self, stream => Pin::new(stream).poll_write_vectored(cx, bufs))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Transport::Tcp(stream) => stream.is_write_vectored(),
+            #[cfg(unix)]
+            Transport::Unix(stream) => stream.is_write_vectored(),
+            Transport::Custom(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        transport!(self, stream => Pin::new(stream).poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        transport!(self, stream => Pin::new(stream).poll_shutdown(cx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unix_socket_url() {
+        assert_eq!(
+            unix_socket_url("ws+unix:///tmp/app.sock:/rpc"),
+            Some((
+                PathBuf::from("/tmp/app.sock"),
+                "ws://localhost/rpc".to_string()
+            ))
+        );
+        assert_eq!(
+            unix_socket_url("wss+unix:///tmp/app.sock"),
+            Some((
+                PathBuf::from("/tmp/app.sock"),
+                "wss://localhost/".to_string()
+            ))
+        );
+        assert_eq!(
+            unix_socket_url("ws+unix:///tmp/app.sock:rpc"),
+            Some((
+                PathBuf::from("/tmp/app.sock"),
+                "ws://localhost/rpc".to_string()
+            ))
+        );
+        assert_eq!(unix_socket_url("ws+unix://:/rpc"), None);
+        assert_eq!(unix_socket_url("ws://localhost/rpc"), None);
+        assert!(is_unix_url("ws+unix://:/rpc"));
+        assert!(!is_unix_url("ws://localhost/rpc"));
+    }
+}
@@ -1,37 +1,69 @@
 use super::{
     bindings::WebSocket as W3CWebSocket,
     error::Error,
-    message::{Ack, Message},
+    event::Event,
+    interceptor::Interceptors,
+    keepalive::{KeepaliveAction, KeepaliveState},
+    message::{Ack, CloseFrame, Message, CLOSE_HANDSHAKE_TIMEOUT_MILLIS},
+    metrics::{payload_len, MetricsCounters},
+    options::Endpoints,
+    ping::{self, PendingPings},
+    queue::{PendingQueue, QueueFull},
+    ratelimit::RateLimiter,
     result::Result,
-    ConnectOptions, ConnectResult, Handshake, Resolver, WebSocketConfig,
+    state::{State, StateCell, StateChanges},
+    subscribe::{ReceiverChannel, Subscription},
+    ConnectOptions, ConnectResult, Handshake, MemoryTransport, Resolver, WebSocketConfig,
+    WebSocketSender,
 };
+use bytes::BytesMut;
+use futures::io::{AsyncRead, AsyncReadExt};
 use futures::{select, select_biased, FutureExt};
 use js_sys::{ArrayBuffer, Uint8Array};
 use std::ops::Deref;
+use std::pin::Pin;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
-};
-use wasm_bindgen::JsCast;
-use web_sys::{
-    CloseEvent as WsCloseEvent, ErrorEvent as WsErrorEvent, MessageEvent as WsMessageEvent,
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    Arc, Mutex, Weak,
 };
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CloseEvent as WsCloseEvent, MessageEvent as WsMessageEvent};
 use workflow_core::runtime::*;
 use workflow_core::{
-    channel::{oneshot, unbounded, Channel, DuplexChannel, Sender},
+    channel::{oneshot, unbounded, Channel, DuplexChannel, Multiplexer, Sender},
     task::spawn,
+    time::{Duration, Instant},
 };
 use workflow_log::*;
+use workflow_wasm::buffer::copy_to_slice;
 use workflow_wasm::callback::*;
 
 impl TryFrom<WsMessageEvent> for Message {
     type Error = Error;
 
     fn try_from(event: WsMessageEvent) -> std::result::Result<Self, Self::Error> {
+        ReceiveBuffer::default().message(event)
+    }
+}
+
+/// Receive buffer of the `message` event handler: binary payloads are
+/// copied into a shared allocation and split off as [`Bytes`](bytes::Bytes),
+/// the allocation is reused once the messages received from it have been
+/// released (instead of allocating a new buffer for each message).
+#[derive(Default)]
+struct ReceiveBuffer {
+    buffer: BytesMut,
+}
+
+impl ReceiveBuffer {
+    fn message(&mut self, event: WsMessageEvent) -> Result<Message> {
         match event.data() {
             data if data.is_instance_of::<ArrayBuffer>() => {
-                let buffer = Uint8Array::new(data.unchecked_ref());
-                Ok(Message::Binary(buffer.to_vec()))
+                let array = Uint8Array::new(&data);
+                // `BytesMut` reclaims the allocation if no longer referenced
+                self.buffer.resize(array.length() as usize, 0);
+                copy_to_slice(&array, &mut self.buffer)?;
+                Ok(Message::Binary(self.buffer.split().freeze()))
             }
             data if data.is_string() => match data.as_string() {
                 Some(text) => Ok(Message::Text(text)),
@@ -68,8 +100,15 @@ impl WebSocket {
         Ok(WebSocket(W3CWebSocket::new(url)?))
     }
 
-    pub fn new_with_config(url: &str, config: &WebSocketConfig) -> Result<Self> {
-        Ok(WebSocket(W3CWebSocket::new_with_config(url, config)?))
+    pub fn new_with_config(
+        url: &str,
+        config: &WebSocketConfig,
+        headers: &[(String, String)],
+        protocols: &[String],
+    ) -> Result<Self> {
+        Ok(WebSocket(W3CWebSocket::new_with_config(
+            url, config, headers, protocols,
+        )?))
     }
 
     fn cleanup(&self) {
@@ -92,6 +131,11 @@ struct Settings {
     default_url: Option<String>,
     // URL WebSocket is currently connected to
     current_url: Option<String>,
+    // subprotocol selected by the server
+    protocol: Option<String>,
+    // details of the last error reported by the WebSocket
+    // during the current connection attempt
+    last_error: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -108,10 +152,17 @@ pub struct WebSocketInterface {
     settings: Arc<Mutex<Settings>>,
     config: Mutex<WebSocketConfig>,
     reconnect: AtomicBool,
-    is_connected: AtomicBool,
+    reconnect_attempts: AtomicU32,
+    state: StateCell,
     event_channel: Channel<Message>,
     sender_channel: Channel<(Message, Ack)>,
-    receiver_channel: Channel<Message>,
+    receiver_channel: Arc<ReceiverChannel>,
+    events: Multiplexer<Event>,
+    pending: PendingQueue,
+    metrics: MetricsCounters,
+    pings: PendingPings,
+    websocket: Weak<super::Inner>,
+    endpoints: Mutex<Endpoints>,
     dispatcher_shutdown: DuplexChannel,
 }
 
@@ -124,6 +175,8 @@ impl WebSocketInterface {
     ) -> Result<WebSocketInterface> {
         sanity_checks()?;
 
+        let config = config.unwrap_or_default();
+        let receiver_channel = ReceiverChannel::new(receiver_channel, config.receiver_overflow);
         let settings = Settings {
             default_url: url.map(String::from),
             ..Default::default()
@@ -132,18 +185,30 @@ impl WebSocketInterface {
         let iface = WebSocketInterface {
             inner: Arc::new(Mutex::new(None)),
             settings: Arc::new(Mutex::new(settings)),
-            config: Mutex::new(config.unwrap_or_default()),
+            config: Mutex::new(config),
             sender_channel,
-            receiver_channel,
+            receiver_channel: Arc::new(receiver_channel),
             event_channel: Channel::unbounded(),
             reconnect: AtomicBool::new(true),
-            is_connected: AtomicBool::new(false),
+            reconnect_attempts: AtomicU32::new(0),
+            state: StateCell::new(),
+            events: Multiplexer::new(),
+            pending: PendingQueue::default(),
+            metrics: MetricsCounters::default(),
+            pings: PendingPings::default(),
+            websocket: Weak::new(),
+            endpoints: Mutex::new(Endpoints::new(&ConnectOptions::default())),
             dispatcher_shutdown: DuplexChannel::unbounded(),
         };
 
         Ok(iface)
     }
 
+    /// Bind the interface to the owning [`WebSocket`](super::WebSocket)
+    pub(super) fn bind(&mut self, websocket: Weak<super::Inner>) {
+        self.websocket = websocket;
+    }
+
     pub fn default_url(self: &Arc<Self>) -> Option<String> {
         self.settings.lock().unwrap().default_url.clone()
     }
@@ -152,6 +217,10 @@ impl WebSocketInterface {
         self.settings.lock().unwrap().current_url.clone()
     }
 
+    pub fn protocol(self: &Arc<Self>) -> Option<String> {
+        self.settings.lock().unwrap().protocol.clone()
+    }
+
     pub fn set_default_url(self: &Arc<Self>, url: &str) {
         self.settings
             .lock()
@@ -168,14 +237,174 @@ impl WebSocketInterface {
             .replace(url.to_string());
     }
 
+    pub fn events(&self) -> &Multiplexer<Event> {
+        &self.events
+    }
+
+    pub fn pending(&self) -> &PendingQueue {
+        &self.pending
+    }
+
+    /// Create a new broadcast subscription to the inbound messages and events
+    pub fn subscribe(&self) -> Subscription {
+        let capacity = self.config.lock().unwrap().subscriber_channel_cap;
+        self.receiver_channel.subscribe(capacity)
+    }
+
+    pub fn metrics(&self) -> &MetricsCounters {
+        &self.metrics
+    }
+
+    /// Send the application-level echo carrying the nonce and
+    /// wait for the server to echo it back (see [`WebSocketConfig::ping_echo`])
+    pub async fn ping(self: &Arc<Self>, timeout: Duration) -> Result<Duration> {
+        let prefix = self
+            .config
+            .lock()
+            .unwrap()
+            .ping_echo
+            .clone()
+            .ok_or(Error::PingUnsupported)?;
+        self.pings
+            .ping(&self.sender_channel, timeout, |nonce| {
+                ping::echo(&prefix, nonce)
+            })
+            .await
+    }
+
+    /// Queue the message if the offline queue is enabled and the WebSocket
+    /// is not connected, returns the message if it has not been queued
+    pub fn queue_if_offline(
+        &self,
+        message: Message,
+    ) -> std::result::Result<Option<Message>, QueueFull> {
+        match self.config.lock().unwrap().offline_queue.as_ref() {
+            Some(options) => self.pending.push_if_offline(options, &self.state, message),
+            None => Ok(Some(message)),
+        }
+    }
+
+    /// Read the entire stream and send it as a single binary message
+    /// (fragmented messages are not supported by the W3C WebSocket API)
+    pub async fn send_stream(
+        self: &Arc<Self>,
+        mut reader: Pin<Box<dyn AsyncRead + Send>>,
+    ) -> std::result::Result<(), Arc<Error>> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .await
+            .map_err(|err| Arc::new(err.into()))?;
+
+        let (ack_sender, ack_receiver) = oneshot();
+        self.sender_channel
+            .send((Message::Binary(data.into()), Some(ack_sender)))
+            .await
+            .map_err(|err| Arc::new(err.into()))?;
+
+        ack_receiver
+            .recv()
+            .await
+            .map_err(|_| Arc::new(Error::DispatchChannelAck))?
+            .map(|_| ())
+    }
+
+    fn post_event(&self, event: Event) {
+        self.receiver_channel.publish_event(&event);
+        self.events.try_broadcast(event).ok();
+    }
+
     pub fn is_connected(self: &Arc<Self>) -> bool {
-        self.is_connected.load(Ordering::SeqCst)
+        self.state.get().is_connected()
+    }
+
+    pub fn state(&self) -> State {
+        self.state.get()
+    }
+
+    pub fn state_changes(&self) -> StateChanges {
+        self.state.watch()
+    }
+
+    /// Delay before the next reconnect attempt (see [`ConnectOptions::retry_delay()`]).
+    /// Returns `None` if the reconnect attempts have been exhausted,
+    /// in which case reconnects are disabled, the pending `connect()`
+    /// call fails and [`Message::ReconnectExhausted`] is posted to
+    /// the receiver channel.
+    async fn reconnect_delay(
+        self: &Arc<Self>,
+        options: &ConnectOptions,
+        connect_trigger: &Arc<Mutex<Option<Sender<Result<()>>>>>,
+    ) -> Option<Duration> {
+        let attempt = self.reconnect_attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        if options.is_reconnect_exhausted(attempt) {
+            log_trace!(
+                "WebSocket reconnect attempts exhausted after {} attempts",
+                attempt - 1
+            );
+            self.reconnect.store(false, Ordering::SeqCst);
+            self.state.set(State::Closed);
+            let connect_trigger = connect_trigger.lock().unwrap().take();
+            if let Some(connect_trigger) = connect_trigger {
+                connect_trigger
+                    .send(Err(Error::ReconnectExhausted(attempt - 1)))
+                    .await
+                    .ok();
+            }
+            self.receiver_channel
+                .send(Message::ReconnectExhausted)
+                .await
+                .unwrap_or_else(|err| {
+                    log_trace!("WebSocket unable to post `reconnect exhausted` to receiver channel: `{err}`")
+                });
+            return None;
+        }
+
+        self.state.set(State::Retrying { attempt });
+        self.post_event(Event::Reconnecting { attempt });
+        Some(options.retry_delay(attempt))
+    }
+
+    /// Disable reconnects after the connect operation has been cancelled
+    /// (see [`ConnectOptions::cancel`]), failing the pending `connect()` call
+    async fn connect_cancelled(
+        self: &Arc<Self>,
+        connect_trigger: &Arc<Mutex<Option<Sender<Result<()>>>>>,
+    ) {
+        log_trace!("WebSocket connect cancelled");
+        self.reconnect.store(false, Ordering::SeqCst);
+        self.state.set(State::Closed);
+        let connect_trigger = connect_trigger.lock().unwrap().take();
+        if let Some(connect_trigger) = connect_trigger {
+            connect_trigger.send(Err(Error::Cancelled)).await.ok();
+        }
     }
 
     fn resolver(&self) -> Option<Arc<dyn Resolver>> {
         self.config.lock().unwrap().resolver.clone()
     }
 
+    /// Run the connection initializer (if configured) in a separate task
+    /// (the initializer may wait for its messages to be relayed, requiring
+    /// the dispatcher to be running)
+    fn initialize(self: &Arc<Self>) {
+        let Some(initializer) = self.config.lock().unwrap().initializer.clone() else {
+            return;
+        };
+        let Some(websocket) = self.websocket.upgrade() else {
+            return;
+        };
+
+        let sender = WebSocketSender::new(super::WebSocket { inner: websocket });
+        let this = self.clone();
+        spawn(async move {
+            if let Err(err) = initializer.initialize(&sender).await {
+                log_trace!("WebSocket connection initializer error: {err}");
+                this.post_event(Event::Error(err.to_string()));
+            }
+        });
+    }
+
     fn handshake(&self) -> Option<Arc<dyn Handshake>> {
         self.config.lock().unwrap().handshake.clone()
     }
@@ -185,7 +414,13 @@ impl WebSocketInterface {
     }
 
     async fn resolve_url(self: &Arc<Self>, options: &ConnectOptions) -> Result<String> {
-        let url = if let Some(url) = options.url.as_ref().or(self.default_url().as_ref()) {
+        let endpoint = self.endpoints.lock().unwrap().current();
+        let url = if let Some(url) = options
+            .url
+            .as_ref()
+            .or(options.urls.get(endpoint))
+            .or(self.default_url().as_ref())
+        {
             url.clone()
         } else if let Some(resolver) = self.resolver() {
             resolver.resolve_url().await?
@@ -197,7 +432,12 @@ impl WebSocketInterface {
     }
 
     pub async fn connect(self: &Arc<Self>, options: ConnectOptions) -> ConnectResult<Error> {
+        if let Some(transport) = options.memory_transport.clone() {
+            return self.connect_memory(transport, options).await;
+        }
+
         let (connect_trigger, connect_listener) = oneshot::<Result<()>>();
+        *self.endpoints.lock().unwrap() = Endpoints::new(&options);
 
         let connect_trigger = Arc::new(Mutex::new(Some(connect_trigger)));
         self.connect_impl(options.clone(), connect_trigger).await?;
@@ -211,6 +451,80 @@ impl WebSocketInterface {
         }
     }
 
+    /// Connect to the in-process handler of the [`MemoryTransport`]
+    async fn connect_memory(
+        self: &Arc<Self>,
+        transport: MemoryTransport,
+        options: ConnectOptions,
+    ) -> ConnectResult<Error> {
+        if self.is_connected() {
+            return Err(Error::AlreadyConnected);
+        }
+
+        let this = self.clone();
+        let (connect_trigger, connect_listener) = oneshot::<Result<()>>();
+        let block_async_connect = options.block_async_connect;
+
+        this.reconnect.store(false, Ordering::SeqCst);
+        this.state.set(State::Handshaking);
+
+        spawn(async move {
+            let connection = match transport
+                .open(
+                    &this.sender_channel,
+                    &this.receiver_channel,
+                    this.handshake(),
+                    &options,
+                )
+                .await
+            {
+                Ok(connection) => connection,
+                Err(err) => {
+                    this.state.set(State::Closed);
+                    this.post_event(Event::Error(err.to_string()));
+                    connect_trigger.try_send(Err(err)).ok();
+                    return;
+                }
+            };
+
+            this.state.set(State::Open);
+            this.metrics.opened();
+            this.receiver_channel
+                .send(Message::Open)
+                .await
+                .unwrap_or_else(|err| {
+                    log_trace!("WebSocket unable to post `open` to receiver channel: `{err}`")
+                });
+            this.post_event(Event::Open { protocol: None });
+            connect_trigger.try_send(Ok(())).ok();
+            this.initialize();
+
+            // `close()` signals the dispatcher via the event channel
+            let shutdown = async {
+                while let Ok(msg) = this.event_channel.recv().await {
+                    if matches!(msg, Message::Close) {
+                        break;
+                    }
+                }
+                CloseFrame::default()
+            };
+            match connection.dispatch(this.pending.take(), shutdown).await {
+                Ok(event) => this.post_event(event),
+                Err(err) => log_trace!("WebSocket memory transport error: {}", err),
+            }
+            this.metrics.closed();
+            this.state.set(State::Closed);
+        });
+
+        match block_async_connect {
+            true => match connect_listener.recv().await? {
+                Ok(_) => Ok(None),
+                Err(e) => Err(e),
+            },
+            false => Ok(Some(connect_listener)),
+        }
+    }
+
     fn retry_connect_impl(
         self: Arc<Self>,
         options: ConnectOptions,
@@ -232,11 +546,15 @@ impl WebSocketInterface {
         }
 
         self.reconnect.store(true, Ordering::SeqCst);
+        self.state.set(State::Connecting);
+        self.settings.lock().unwrap().last_error = None;
 
         let url = match self.resolve_url(&options).await {
             Ok(url) => url,
             Err(err) => {
                 log_trace!("WebSocket unable to resolve URL: {err}");
+                self.state.set(State::Closed);
+                self.post_event(Event::Error(err.to_string()));
                 let self_ = self.clone();
 
                 if options.strategy.is_fallback() {
@@ -254,18 +572,22 @@ impl WebSocketInterface {
                 spawn(async move {
                     // if reconnect is true, we sleep for reconnect interval and try to reconnect
                     if self_.reconnect.load(Ordering::SeqCst) {
-                        workflow_core::task::sleep(
-                            options
-                                .retry_interval
-                                .unwrap_or(std::time::Duration::from_millis(1000)),
-                        )
-                        .await;
+                        let Some(delay) = self_.reconnect_delay(&options, &connect_trigger_).await
+                        else {
+                            return;
+                        };
+                        if !sleep_unless_cancelled(&options, delay).await {
+                            self_.connect_cancelled(&connect_trigger_).await;
+                            return;
+                        }
                         // check again if reconnect may have been disabled during sleep
                         if self_.reconnect.load(Ordering::SeqCst) {
                             self_
                                 .retry_connect_impl(options, connect_trigger_)
                                 .await
                                 .ok();
+                        } else {
+                            self_.state.set(State::Closed);
                         }
                     }
                 });
@@ -276,13 +598,25 @@ impl WebSocketInterface {
 
         let mut inner = self.inner.lock().unwrap();
 
-        let ws = WebSocket::new_with_config(&url, &self.config.lock().unwrap())?;
+        let ws = WebSocket::new_with_config(
+            &url,
+            &self.config.lock().unwrap(),
+            &options.headers,
+            &options.protocols,
+        )?;
         ws.set_binary_type(web_sys::BinaryType::Arraybuffer);
 
         // - Message
         let event_sender_ = self.event_channel.sender.clone();
+        let mut receive_buffer = ReceiveBuffer::default();
         let onmessage = callback!(move |event: WsMessageEvent| {
-            let msg: Message = event.try_into().expect("MessageEvent Error");
+            let msg = match receive_buffer.message(event) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    log_trace!("WebSocket unable to process `message` event: `{err}`");
+                    return;
+                }
+            };
             event_sender_.try_send(msg).unwrap_or_else(|err| {
                 log_trace!("WebSocket unable to try_send() `message` to event channel: `{err}`")
             });
@@ -290,8 +624,16 @@ impl WebSocketInterface {
         ws.set_onmessage(Some(onmessage.as_ref()));
 
         // - Error
-        let onerror = callback!(move |_event: WsErrorEvent| {
-            // log_trace!("WS - error event: {:?}", _event);
+        let events = self.events.clone();
+        let receiver_channel = self.receiver_channel.clone();
+        let settings = self.settings.clone();
+        let onerror = callback!(move |event: JsValue| {
+            // log_trace!("WS - error event: {:?}", event);
+            let error = error_details(&event);
+            settings.lock().unwrap().last_error = Some(error.clone());
+            let event = Event::Error(error);
+            receiver_channel.publish_event(&event);
+            events.try_broadcast(event).ok();
         });
         ws.set_onerror(Some(onerror.as_ref()));
 
@@ -306,8 +648,29 @@ impl WebSocketInterface {
 
         // - Close
         let event_sender_ = self.event_channel.sender.clone();
-        let onclose = callback!(move |_event: WsCloseEvent| {
-            // log_trace!("WS - close event: {:?}", _event);
+        let events = self.events.clone();
+        let receiver_channel = self.receiver_channel.clone();
+        let settings = self.settings.clone();
+        let onclose = callback!(move |event: WsCloseEvent| {
+            // log_trace!("WS - close event: {:?}", event);
+            if !event.was_clean() {
+                let mut settings = settings.lock().unwrap();
+                if settings.last_error.is_none() {
+                    let reason = event.reason();
+                    settings.last_error = Some(if reason.is_empty() {
+                        format!("connection closed with code {}", event.code())
+                    } else {
+                        format!("connection closed with code {}: {reason}", event.code())
+                    });
+                }
+            }
+            let event = Event::Close {
+                code: event.code(),
+                reason: event.reason(),
+                was_clean: event.was_clean(),
+            };
+            receiver_channel.publish_event(&event);
+            events.try_broadcast(event).ok();
             event_sender_
                 .try_send(Message::Close)
                 .unwrap_or_else(|err| {
@@ -329,21 +692,33 @@ impl WebSocketInterface {
 
         let self_ = self.clone();
         spawn(async move {
-            self_
+            let failover = self_
                 .dispatcher_task(&ws, options.clone(), connect_trigger.clone())
                 .await
-                .unwrap_or_else(|err| log_trace!("WebSocket error: {err}"));
+                .unwrap_or_else(|err| {
+                    log_trace!("WebSocket error: {err}");
+                    false
+                });
+            self_.state.set(State::Closed);
+            // try the next endpoint before observing the retry delay
+            if failover && self_.reconnect.load(Ordering::SeqCst) {
+                self_.reconnect(options, connect_trigger).await.ok();
+                return;
+            }
             // if reconnect is true, we sleep for reconnect interval and try to reconnect
             if self_.reconnect.load(Ordering::SeqCst) {
-                workflow_core::task::sleep(
-                    options
-                        .retry_interval
-                        .unwrap_or(std::time::Duration::from_millis(1000)),
-                )
-                .await;
+                let Some(delay) = self_.reconnect_delay(&options, &connect_trigger).await else {
+                    return;
+                };
+                if !sleep_unless_cancelled(&options, delay).await {
+                    self_.connect_cancelled(&connect_trigger).await;
+                    return;
+                }
                 // check again if reconnect may have been disabled during sleep
                 if self_.reconnect.load(Ordering::SeqCst) {
                     self_.reconnect(options, connect_trigger).await.ok();
+                } else {
+                    self_.state.set(State::Closed);
                 }
             }
         });
@@ -369,7 +744,11 @@ impl WebSocketInterface {
         }
     }
 
-    async fn handshake_impl(self: &Arc<Self>, ws: &WebSocket) -> Result<()> {
+    async fn handshake_impl(
+        self: &Arc<Self>,
+        ws: &WebSocket,
+        handshake_timeout: Option<Duration>,
+    ) -> Result<()> {
         if let Some(handshake) = self.handshake() {
             let (sender_tx, sender_rx) = unbounded();
             let (receiver_tx, receiver_rx) = unbounded();
@@ -377,18 +756,35 @@ impl WebSocketInterface {
 
             spawn(async move {
                 accept_tx
-                    .send(handshake.handshake(&sender_tx, &receiver_rx).await)
+                    .send(
+                        handshake
+                            .handshake_with_response(&sender_tx, &receiver_rx, None)
+                            .await,
+                    )
                     .await
                     .unwrap_or_else(|err| {
                         log_trace!("WebSocket handshake unable to send completion: `{}`", err)
                     });
             });
 
+            let started = Instant::now();
             loop {
                 select_biased! {
                     result = accept_rx.recv().fuse() => {
                         return result?;
                     },
+                    _ = async {
+                        match handshake_timeout {
+                            Some(timeout) => {
+                                workflow_core::task::sleep(timeout.saturating_sub(started.elapsed())).await
+                            }
+                            None => futures::future::pending().await,
+                        }
+                    }.fuse() => {
+                        log_trace!("WebSocket handshake negotiation timeout");
+                        self.post_event(Event::Error(Error::HandshakeTimeout.to_string()));
+                        return Err(Error::HandshakeTimeout);
+                    },
                     msg = sender_rx.recv().fuse() => {
                         if let Ok(msg) = msg {
                             ws.try_send(&msg)?;
@@ -411,24 +807,121 @@ impl WebSocketInterface {
         ws: &WebSocket,
         options: ConnectOptions,
         connect_trigger: Arc<Mutex<Option<Sender<Result<()>>>>>,
-    ) -> Result<()> {
+    ) -> Result<bool> {
+        let config = self.config.lock().unwrap().clone();
+        // keepalive requires an application-level ping message
+        let keepalive = config
+            .keepalive
+            .filter(|keepalive| keepalive.message.is_some());
+        let mut keepalive = KeepaliveState::new(keepalive);
+        let mut ratelimit = RateLimiter::new(config.rate_limit);
+        let interceptors = Interceptors::new(&options.interceptors);
+        let echo = config.ping_echo;
+        // set if the connection attempt has failed and the next endpoint should be attempted
+        let mut failover = false;
+        let started = Instant::now();
+
         'outer: loop {
+            let connecting = !self.is_connected();
             select! {
                 _ = self.dispatcher_shutdown.request.receiver.recv().fuse() => {
                     break 'outer;
                 },
+                _ = async {
+                    if connecting {
+                        options.cancelled().await
+                    } else {
+                        futures::future::pending().await
+                    }
+                }.fuse() => {
+                    self.abort_connecting();
+                    self.connect_cancelled(&connect_trigger).await;
+                    break 'outer;
+                },
+                _ = async {
+                    if connecting {
+                        let timeout = options.connect_timeout().saturating_sub(started.elapsed());
+                        workflow_core::task::sleep(timeout).await
+                    } else {
+                        futures::future::pending().await
+                    }
+                }.fuse() => {
+                    log_trace!(
+                        "WebSocket connection timeout while connecting to {}",
+                        self.current_url().unwrap_or_default()
+                    );
+                    self.abort_connecting();
+                    self.post_event(Event::Error(Error::ConnectionTimeout.to_string()));
+                    if self.endpoints.lock().unwrap().failed() {
+                        failover = true;
+                    } else if options.strategy.is_fallback() {
+                        self.reconnect.store(false, Ordering::SeqCst);
+                        let connect_trigger = connect_trigger.lock().unwrap().take();
+                        if let Some(connect_trigger) = connect_trigger {
+                            connect_trigger.send(Err(Error::ConnectionTimeout)).await.ok();
+                        }
+                    }
+                    break 'outer;
+                },
+                _ = keepalive.tick().fuse() => {
+                    if self.is_connected() {
+                        match keepalive.action() {
+                            KeepaliveAction::Ping => {
+                                if let Some(msg) = keepalive.message() {
+                                    ws.try_send(msg).unwrap_or_else(|err| {
+                                        log_trace!("WebSocket unable to send keepalive message: `{err}`")
+                                    });
+                                }
+                            }
+                            KeepaliveAction::Timeout => {
+                                log_trace!("WebSocket keepalive timeout");
+                                self.post_event(Event::Error(Error::KeepaliveTimeout.to_string()));
+                                // results in the `close` event and reconnect
+                                ws.close().unwrap_or_else(|err| {
+                                    log_trace!("WebSocket unable to close the connection: `{err:?}`")
+                                });
+                            }
+                            KeepaliveAction::None => { }
+                        }
+                    }
+                },
                 msg = self.event_channel.recv().fuse() => {
+                    if let Some(rtt) = keepalive.received() {
+                        self.metrics.rtt(rtt);
+                    }
                     match msg {
                         Ok(msg) => {
                             match msg {
                                 Message::Binary(_) | Message::Text(_) => {
-                                    self.receiver_channel.sender.send(msg).await.unwrap();
+                                    self.metrics.received(payload_len(&msg));
+                                    if let (Message::Text(text), Some(prefix)) = (&msg, echo.as_deref()) {
+                                        if ping::echo_nonce(prefix, text).is_some_and(|nonce| self.pings.resolve(nonce)) {
+                                            continue;
+                                        }
+                                    }
+                                    if let Some(msg) = interceptors.inbound(msg) {
+                                        match self.receiver_channel.send(msg).await {
+                                            Err(Error::ReceiverOverflow) => {
+                                                log_trace!("WebSocket receiver channel overflow");
+                                                self.post_event(Event::Error(Error::ReceiverOverflow.to_string()));
+                                                // results in the `close` event
+                                                self.reconnect.store(false, Ordering::SeqCst);
+                                                ws.close().unwrap_or_else(|err| {
+                                                    log_trace!("WebSocket unable to close the connection: `{err:?}`")
+                                                });
+                                            }
+                                            result => result.unwrap(),
+                                        }
+                                    }
                                 },
                                 Message::Open => {
                                     // log_info!("WebSocket Message::Open");
+                                    self.state.set(State::Handshaking);
                                     // handle handshake failure
-                                    if let Err(err) = self.handshake_impl(ws).await {
+                                    if let Err(err) = self.handshake_impl(ws, options.handshake_timeout).await {
                                         log_info!("WebSocket handshake negotiation error: {err}");
+                                        // drop the connection, allowing the reconnect to proceed
+                                        self.abort_connecting();
 
                                         if options.strategy.is_fallback() {
                                             self.reconnect.store(false, Ordering::SeqCst);
@@ -442,15 +935,40 @@ impl WebSocketInterface {
                                         return Err(Error::NegotiationFailure);
                                     }
 
-                                    self.is_connected.store(true, Ordering::SeqCst);
+                                    let protocol = ws.protocol();
+                                    let mut settings = self.settings.lock().unwrap();
+                                    settings.protocol = (!protocol.is_empty()).then_some(protocol);
+                                    settings.last_error = None;
+                                    drop(settings);
+                                    self.state.set(State::Open);
+                                    self.reconnect_attempts.store(0, Ordering::SeqCst);
+                                    self.endpoints.lock().unwrap().connected();
+                                    self.metrics.opened();
+
+                                    for msg in self.pending.take() {
+                                        let Some(msg) = interceptors.outbound(msg) else {
+                                            continue;
+                                        };
+                                        ratelimit.ready().await;
+                                        ratelimit.consume(payload_len(&msg));
+                                        match ws.try_send(&msg) {
+                                            Ok(()) => self.metrics.sent(payload_len(&msg)),
+                                            Err(err) => log_trace!("WebSocket unable to send queued message: `{err}`"),
+                                        }
+                                    }
+                                    self.initialize();
 
                                     let connect_trigger = connect_trigger.lock().unwrap().take();
                                     if let Some(connect_trigger) = connect_trigger {
                                         connect_trigger.send(Ok(())).await.ok();
                                     }
 
-                                    self.receiver_channel.sender.send(msg).await.unwrap();
+                                    self.receiver_channel.send(msg).await.unwrap();
+                                    self.post_event(Event::Open {
+                                        protocol: self.protocol(),
+                                    });
                                 },
+                                Message::Ping(_) | Message::Pong(_) | Message::ReconnectExhausted => {},
                                 Message::Close => {
                                     // log_info!("WebSocket Message::Close");
 
@@ -458,9 +976,14 @@ impl WebSocketInterface {
                                         inner.ws.cleanup();
                                     }
 
-                                    if self.is_connected.load(Ordering::SeqCst) {
-                                        self.is_connected.store(false, Ordering::SeqCst);
-                                        self.receiver_channel.sender.send(msg).await.unwrap();
+                                    if self.is_connected() {
+                                        self.state.set(State::Closed);
+                                        self.metrics.closed();
+                                        self.pings.clear();
+                                        self.endpoints.lock().unwrap().disconnected();
+                                        self.receiver_channel.send(msg).await.unwrap();
+                                    } else if self.endpoints.lock().unwrap().failed() {
+                                        failover = true;
                                     } else if options.strategy.is_fallback() && options.block_async_connect {
                                         // if we never connected and receiver Close while
                                         // the strategy is Fallback, we disable reconnect
@@ -468,7 +991,12 @@ impl WebSocketInterface {
 
                                         let connect_trigger = connect_trigger.lock().unwrap().take();
                                         if let Some(connect_trigger) = connect_trigger {
-                                            connect_trigger.send(Err(Error::Connect(self.current_url().unwrap()))).await.ok();
+                                            let url = self.current_url().unwrap();
+                                            let err = match self.settings.lock().unwrap().last_error.take() {
+                                                Some(reason) => Error::ConnectFailed(url, reason),
+                                                None => Error::Connect(url),
+                                            };
+                                            connect_trigger.send(Err(err)).await.ok();
                                         }
                                     }
 
@@ -481,7 +1009,10 @@ impl WebSocketInterface {
                         }
                     }
                 },
-                msg = self.sender_channel.receiver.recv().fuse() => {
+                msg = async {
+                    ratelimit.ready().await;
+                    self.sender_channel.receiver.recv().await
+                }.fuse() => {
 
                     if let Ok((msg, ack)) = msg {
 
@@ -489,25 +1020,49 @@ impl WebSocketInterface {
                         //     return Err(Error::NotConnected);
                         // }
 
+                        let Some(msg) = interceptors.outbound(msg) else {
+                            if let Some(ack) = ack {
+                                ack.send(Ok(Arc::new(()))).await.unwrap_or_else(|err| {
+                                    log_trace!("WebSocket error producing message ack {:?}", err)
+                                });
+                            }
+                            continue;
+                        };
+                        let len = payload_len(&msg);
+                        ratelimit.consume(len);
                         if let Some(ack) = ack {
                             let result = ws
                                 .try_send(&msg)
                                 .map(Arc::new)
                                 .map_err(Arc::new);
+                            if result.is_ok() {
+                                self.metrics.sent(len);
+                            }
                             ack.send(result).await.unwrap_or_else(|err| {
                                 log_trace!("WebSocket error producing message ack {:?}", err)
                             });
                         } else {
-                            ws.try_send(&msg).unwrap_or_else(|err| {
-                                log_trace!("WebSocket unable to send `raw ws` message: `{err}`")
-                            });
+                            match ws.try_send(&msg) {
+                                Ok(()) => self.metrics.sent(len),
+                                Err(err) => log_trace!("WebSocket unable to send `raw ws` message: `{err}`"),
+                            }
                         }
                     }
                 }
             }
         }
 
-        Ok(())
+        Ok(failover)
+    }
+
+    /// Abort the connection attempt in progress
+    fn abort_connecting(self: &Arc<Self>) {
+        if let Some(inner) = self.inner.lock().unwrap().take() {
+            inner.ws.cleanup();
+            inner.ws.close().unwrap_or_else(|err| {
+                log_trace!("WebSocket unable to close the connection: `{err:?}`")
+            });
+        }
     }
 
     async fn _shutdown(self: &Arc<Self>) -> Result<()> {
@@ -520,12 +1075,33 @@ impl WebSocketInterface {
     }
 
     pub async fn close(self: &Arc<Self>) -> Result<()> {
-        if let Some(inner) = self.inner.lock().unwrap().take() {
+        self.close_with(CloseFrame::default()).await
+    }
+
+    /// Close the connection sending the supplied Close frame and
+    /// wait for the close handshake to complete
+    pub async fn close_with(self: &Arc<Self>, frame: CloseFrame) -> Result<()> {
+        if self.is_connected() {
+            self.state.set(State::Closing);
+        }
+        let inner = self.inner.lock().unwrap().take();
+        if let Some(inner) = inner {
             inner.ws.cleanup();
-            inner.ws.close_if_open()?;
+            if inner.ws.close_with_frame_if_open(&frame)? {
+                let was_clean = wait_closed(
+                    &inner.ws,
+                    Duration::from_millis(CLOSE_HANDSHAKE_TIMEOUT_MILLIS),
+                )
+                .await;
+                self.post_event(Event::Close {
+                    code: frame.code,
+                    reason: frame.reason,
+                    was_clean,
+                });
+            }
         }
 
-        if self.is_connected.load(Ordering::SeqCst) {
+        if self.is_connected() {
             self.event_channel.try_send(Message::Close)?;
         }
 
@@ -547,13 +1123,17 @@ impl WebSocketInterface {
     }
 
     pub async fn disconnect(self: &Arc<Self>) -> Result<()> {
+        self.disconnect_with(CloseFrame::default()).await
+    }
+
+    pub async fn disconnect_with(self: &Arc<Self>, frame: CloseFrame) -> Result<()> {
         self.reconnect.store(false, Ordering::SeqCst);
-        self.close().await.ok();
+        self.close_with(frame).await.ok();
         Ok(())
     }
 
     pub fn trigger_abort(self: &Arc<Self>) -> Result<()> {
-        if self.is_connected.load(Ordering::SeqCst) {
+        if self.is_connected() {
             if let Some(ws) = self.ws() {
                 ws.close_if_open()?;
             }
@@ -575,11 +1155,55 @@ impl TrySendMessage for WebSocket {
         match message {
             Message::Binary(data) => self.send_with_u8_array(data).map_err(|e| e.into()),
             Message::Text(text) => self.send_with_str(text).map_err(|e| e.into()),
-            _ => {
-                panic!("WebSocket trying to convert unsupported message type: `{message:?}`");
-            }
+            _ => Err(Error::InvalidMessageType),
+        }
+    }
+}
+
+/// Wait for `delay`, returns `false` if the connect
+/// operation has been cancelled in the meantime
+/// Extract the error details from the `error` event supplied by the
+/// WebSocket implementation.  Browsers deliberately do not expose the
+/// cause of the failure (the event carries no details), while Node.js
+/// implementations supply the error `message` (e.g. `ECONNREFUSED`,
+/// `ENOTFOUND` or TLS certificate errors) either on the event or on
+/// the underlying `error` object.
+fn error_details(event: &JsValue) -> String {
+    let get = |target: &JsValue, key: &str| {
+        js_sys::Reflect::get(target, &JsValue::from_str(key))
+            .ok()
+            .and_then(|value| value.as_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    get(event, "message")
+        .or_else(|| {
+            js_sys::Reflect::get(event, &JsValue::from_str("error"))
+                .ok()
+                .and_then(|error| get(&error, "message").or_else(|| error.as_string()))
+        })
+        .or_else(|| event.as_string())
+        .unwrap_or_else(|| "WebSocket error".to_string())
+}
+
+async fn sleep_unless_cancelled(options: &ConnectOptions, delay: Duration) -> bool {
+    select! {
+        _ = workflow_core::task::sleep(delay).fuse() => true,
+        _ = options.cancelled().fuse() => false,
+    }
+}
+
+/// Wait for the connection to reach the `CLOSED` state,
+/// returns `false` if the state has not been reached within `timeout`
+async fn wait_closed(ws: &WebSocket, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while ws.ready_state() != WebSocket::CLOSED {
+        if start.elapsed() >= timeout {
+            return false;
         }
+        workflow_core::task::sleep(Duration::from_millis(10)).await;
     }
+    true
 }
 
 fn w3c_websocket_available() -> Result<bool> {
@@ -0,0 +1,380 @@
+//!
+//! `permessage-deflate` extension negotiation (RFC 7692 section 7.1)
+//!
+
+use super::{DeflateConfig, MAX_WINDOW_BITS, MIN_WINDOW_BITS};
+use std::fmt;
+use tungstenite::handshake::client::Request as ClientRequest;
+use tungstenite::handshake::server::{Callback, ErrorResponse, Request, Response};
+use tungstenite::http::header::{HeaderValue, SEC_WEBSOCKET_EXTENSIONS};
+use tungstenite::protocol::Role;
+
+const PERMESSAGE_DEFLATE: &str = "permessage-deflate";
+
+/// Extension parameters offered by the client or agreed by the server
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Params {
+    pub server_no_context_takeover: bool,
+    pub client_no_context_takeover: bool,
+    pub server_max_window_bits: Option<u8>,
+    /// The offer may contain the parameter without a value
+    /// (formatted without the value if set to 15)
+    pub client_max_window_bits: Option<u8>,
+}
+
+impl Params {
+    /// Parse the parameters of the `permessage-deflate` element
+    fn parse<'a>(params: impl Iterator<Item = &'a str>) -> Result<Self, String> {
+        let mut result = Params::default();
+        for param in params {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (param.trim(), None),
+            };
+            let duplicate = match (name.to_ascii_lowercase().as_str(), value) {
+                ("server_no_context_takeover", None) => {
+                    std::mem::replace(&mut result.server_no_context_takeover, true)
+                }
+                ("client_no_context_takeover", None) => {
+                    std::mem::replace(&mut result.client_no_context_takeover, true)
+                }
+                ("server_max_window_bits", Some(value)) => result
+                    .server_max_window_bits
+                    .replace(window_bits(value)?)
+                    .is_some(),
+                ("client_max_window_bits", value) => {
+                    let bits = value.map(window_bits).transpose()?;
+                    result
+                        .client_max_window_bits
+                        .replace(bits.unwrap_or(MAX_WINDOW_BITS))
+                        .is_some()
+                }
+                _ => {
+                    let param = param.trim();
+                    return Err(format!("invalid {PERMESSAGE_DEFLATE} parameter `{param}`"));
+                }
+            };
+            if duplicate {
+                return Err(format!("duplicate {PERMESSAGE_DEFLATE} parameter `{name}`"));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Parameters of the extension agreed by the server (the
+    /// `Sec-WebSocket-Extensions` header value of the response)
+    pub fn from_response(value: &str) -> Result<Self, String> {
+        let mut extensions = extensions(value);
+        let params = match (extensions.next(), extensions.next()) {
+            (Some((PERMESSAGE_DEFLATE, params)), None) => Params::parse(params)?,
+            _ => return Err(format!("unexpected WebSocket extensions `{value}`")),
+        };
+        Ok(params)
+    }
+}
+
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PERMESSAGE_DEFLATE}")?;
+        if self.server_no_context_takeover {
+            write!(f, "; server_no_context_takeover")?;
+        }
+        if self.client_no_context_takeover {
+            write!(f, "; client_no_context_takeover")?;
+        }
+        if let Some(bits) = self.server_max_window_bits {
+            write!(f, "; server_max_window_bits={bits}")?;
+        }
+        match self.client_max_window_bits {
+            Some(MAX_WINDOW_BITS) => write!(f, "; client_max_window_bits")?,
+            Some(bits) => write!(f, "; client_max_window_bits={bits}")?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// Extensions listed in the `Sec-WebSocket-Extensions` header value
+/// (the extension names are lowercase)
+fn extensions(value: &str) -> impl Iterator<Item = (&str, impl Iterator<Item = &str>)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .map(|element| {
+            let mut params = element.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let name = if name.eq_ignore_ascii_case(PERMESSAGE_DEFLATE) {
+                PERMESSAGE_DEFLATE
+            } else {
+                name
+            };
+            (name, params.filter(|param| !param.trim().is_empty()))
+        })
+}
+
+fn window_bits(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(bits) if (8..=MAX_WINDOW_BITS).contains(&bits) => Ok(bits),
+        _ => Err(format!(
+            "invalid {PERMESSAGE_DEFLATE} window bits `{value}`"
+        )),
+    }
+}
+
+/// Window bits of the configuration limited to the supported range
+pub(crate) fn clamp_window_bits(bits: u8) -> u8 {
+    bits.clamp(MIN_WINDOW_BITS, MAX_WINDOW_BITS)
+}
+
+/// Offer the extension in the upgrade request of the client
+/// (the client always accepts the `client_max_window_bits` restriction)
+pub(crate) fn offer(config: &DeflateConfig, request: &mut ClientRequest) {
+    let server_max_window_bits = clamp_window_bits(config.server_max_window_bits);
+    let params = Params {
+        server_no_context_takeover: config.server_no_context_takeover,
+        client_no_context_takeover: config.client_no_context_takeover,
+        server_max_window_bits: (server_max_window_bits < MAX_WINDOW_BITS)
+            .then_some(server_max_window_bits),
+        client_max_window_bits: Some(clamp_window_bits(config.client_max_window_bits)),
+    };
+    if let Ok(value) = HeaderValue::from_str(&params.to_string()) {
+        request
+            .headers_mut()
+            .insert(SEC_WEBSOCKET_EXTENSIONS, value);
+    }
+}
+
+/// Accept the first supported offer of the client, adding the agreed
+/// parameters to the response of the server (the offers are declined
+/// if none of them is supported)
+pub(crate) fn accept_offer(config: &DeflateConfig, request: &Request, response: &mut Response) {
+    let offers = request
+        .headers()
+        .get_all(SEC_WEBSOCKET_EXTENSIONS)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let server_window_bits = clamp_window_bits(config.server_max_window_bits);
+    let client_window_bits = clamp_window_bits(config.client_max_window_bits);
+    let agreed = extensions(&offers)
+        .filter(|(name, _)| *name == PERMESSAGE_DEFLATE)
+        .filter_map(|(_, params)| Params::parse(params).ok())
+        .find_map(|offer| {
+            let server_max_window_bits = match offer.server_max_window_bits {
+                // the 256 byte window is not supported by the compressor
+                Some(bits) if bits < MIN_WINDOW_BITS => return None,
+                Some(bits) => Some(bits.min(server_window_bits)),
+                None => (server_window_bits < MAX_WINDOW_BITS).then_some(server_window_bits),
+            };
+            // the client window can be restricted only if offered by the client
+            let client_max_window_bits = offer
+                .client_max_window_bits
+                .map(|bits| bits.min(client_window_bits))
+                .filter(|bits| *bits < MAX_WINDOW_BITS);
+            Some(Params {
+                server_no_context_takeover: offer.server_no_context_takeover
+                    || config.server_no_context_takeover,
+                client_no_context_takeover: offer.client_no_context_takeover
+                    || config.client_no_context_takeover,
+                server_max_window_bits,
+                client_max_window_bits,
+            })
+        });
+
+    if let Some(agreed) = agreed {
+        if let Ok(value) = HeaderValue::from_str(&agreed.to_string()) {
+            response
+                .headers_mut()
+                .insert(SEC_WEBSOCKET_EXTENSIONS, value);
+        }
+    }
+}
+
+/// Server handshake callback accepting the offer of the client
+/// (see [`accept_offer`]) if the extension is enabled
+pub(crate) struct AcceptOffer<'config>(pub Option<&'config DeflateConfig>);
+
+impl Callback for AcceptOffer<'_> {
+    fn on_request(
+        self,
+        request: &Request,
+        mut response: Response,
+    ) -> Result<Response, ErrorResponse> {
+        if let Some(config) = self.0 {
+            accept_offer(config, request, &mut response);
+        }
+        Ok(response)
+    }
+}
+
+/// Parameters of the extension agreed in the response of the server
+/// (received by the client or sent by the server), `None` if the
+/// extension has not been agreed.  Fails if the client can not
+/// comply with the response.
+pub(crate) fn agreed(
+    role: Role,
+    config: &DeflateConfig,
+    extensions: &str,
+) -> Result<Option<Params>, String> {
+    if extensions.trim().is_empty() {
+        return Ok(None);
+    }
+    let params = Params::from_response(extensions)?;
+    if role == Role::Client {
+        let server_window_bits = clamp_window_bits(config.server_max_window_bits);
+        match params.server_max_window_bits {
+            Some(bits) if bits > server_window_bits => {
+                return Err(format!("unexpected server_max_window_bits={bits}"))
+            }
+            None if server_window_bits < MAX_WINDOW_BITS => {
+                return Err("missing server_max_window_bits".to_string())
+            }
+            _ => {}
+        }
+        if let Some(bits) = params.client_max_window_bits {
+            if bits < MIN_WINDOW_BITS {
+                return Err(format!("unsupported client_max_window_bits={bits}"));
+            }
+        }
+    }
+    Ok(Some(params))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tungstenite::client::IntoClientRequest;
+    use tungstenite::http;
+
+    fn accept(config: &DeflateConfig, offers: &[&str]) -> Option<String> {
+        let mut request = http::Request::builder();
+        for offer in offers {
+            request = request.header(SEC_WEBSOCKET_EXTENSIONS, *offer);
+        }
+        let request = request.body(()).unwrap();
+        let mut response = Response::default();
+        accept_offer(config, &request, &mut response);
+        response
+            .headers()
+            .get(SEC_WEBSOCKET_EXTENSIONS)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    fn offered(config: &DeflateConfig) -> String {
+        let mut request = "ws://localhost/".into_client_request().unwrap();
+        offer(config, &mut request);
+        let value = request.headers().get(SEC_WEBSOCKET_EXTENSIONS).unwrap();
+        value.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_deflate_offer() {
+        assert_eq!(
+            offered(&DeflateConfig::default()),
+            "permessage-deflate; client_max_window_bits"
+        );
+        let config = DeflateConfig::default()
+            .with_client_no_context_takeover(true)
+            .with_server_max_window_bits(10)
+            .with_client_max_window_bits(4);
+        assert_eq!(
+            offered(&config),
+            "permessage-deflate; client_no_context_takeover; server_max_window_bits=10; client_max_window_bits=9"
+        );
+    }
+
+    #[test]
+    fn test_deflate_accept_offer() {
+        let config = DeflateConfig::default();
+        assert_eq!(accept(&config, &[]), None);
+        assert_eq!(accept(&config, &["x-webkit-deflate-frame"]), None);
+        assert_eq!(
+            accept(&config, &["permessage-deflate; client_max_window_bits"]).as_deref(),
+            Some("permessage-deflate")
+        );
+
+        // the first supported offer is accepted
+        assert_eq!(
+            accept(
+                &config,
+                &[
+                    "permessage-deflate; server_max_window_bits=8, permessage-deflate; unknown",
+                    "Permessage-Deflate; server_no_context_takeover; server_max_window_bits=\"11\"; client_max_window_bits=12",
+                ]
+            )
+            .as_deref(),
+            Some("permessage-deflate; server_no_context_takeover; server_max_window_bits=11; client_max_window_bits=12")
+        );
+
+        // the server restrictions apply to the offer
+        let config = DeflateConfig::default()
+            .with_server_no_context_takeover(true)
+            .with_server_max_window_bits(10)
+            .with_client_max_window_bits(11);
+        assert_eq!(
+            accept(&config, &["permessage-deflate"]).as_deref(),
+            Some("permessage-deflate; server_no_context_takeover; server_max_window_bits=10")
+        );
+        assert_eq!(
+            accept(&config, &["permessage-deflate; client_max_window_bits"]).as_deref(),
+            Some("permessage-deflate; server_no_context_takeover; server_max_window_bits=10; client_max_window_bits=11")
+        );
+        assert_eq!(
+            accept(
+                &config,
+                &["permessage-deflate; server_max_window_bits=10; server_max_window_bits=10"]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_deflate_agreed() {
+        let config = DeflateConfig::default();
+        assert_eq!(agreed(Role::Client, &config, ""), Ok(None));
+        assert_eq!(
+            agreed(
+                Role::Client,
+                &config,
+                "permessage-deflate; client_no_context_takeover; client_max_window_bits=10"
+            ),
+            Ok(Some(Params {
+                client_no_context_takeover: true,
+                client_max_window_bits: Some(10),
+                ..Default::default()
+            }))
+        );
+        assert!(agreed(Role::Client, &config, "x-unknown").is_err());
+        assert!(agreed(
+            Role::Client,
+            &config,
+            "permessage-deflate, permessage-deflate"
+        )
+        .is_err());
+        assert!(agreed(
+            Role::Client,
+            &config,
+            "permessage-deflate; client_max_window_bits=8"
+        )
+        .is_err());
+
+        // the server must comply with the window requested by the client
+        let config = DeflateConfig::default().with_server_max_window_bits(10);
+        assert!(agreed(Role::Client, &config, "permessage-deflate").is_err());
+        assert!(agreed(
+            Role::Client,
+            &config,
+            "permessage-deflate; server_max_window_bits=11"
+        )
+        .is_err());
+        assert!(agreed(
+            Role::Client,
+            &config,
+            "permessage-deflate; server_max_window_bits=9"
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+//!
+//! `permessage-deflate` WebSocket compression (RFC 7692) for the native
+//! client and server.  The compression is negotiated during the WebSocket
+//! upgrade and applied by the [`DeflateStream`] between the transport (TLS)
+//! stream and the WebSocket protocol.
+//!
+
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        mod extension;
+        mod stream;
+        pub(crate) use extension::{offer, AcceptOffer};
+        pub use stream::DeflateStream;
+    }
+}
+
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+pub const DEFAULT_MIN_SIZE: usize = 256;
+pub const MIN_WINDOW_BITS: u8 = 9;
+pub const MAX_WINDOW_BITS: u8 = 15;
+
+///
+/// `permessage-deflate` options (compression is disabled if not supplied).
+/// The client offers the extension using these parameters and the server
+/// accepts the offers of the clients, applying its own restrictions; the
+/// messages are compressed only if both peers support the extension.
+///
+/// The context takeover and the window size trade memory for the
+/// compression ratio: without the context takeover the compression state
+/// is reset after each message (messages can not refer to the data of the
+/// previous messages), while the window bits limit the size of the LZ77
+/// window (`2^bits` bytes) used by the compressor of each side.
+///
+/// The window bits are limited to the range `9..=15` (the 256 byte window
+/// is not supported by the zlib implementation).  Browsers negotiate the
+/// compression automatically, these options are ignored by the WASM client.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeflateConfig {
+    /// Compression level (`0..=9`), the default value is 6
+    pub compression_level: u32,
+    /// Messages smaller than `min_size` bytes are sent uncompressed.
+    /// The default value is 256 bytes.
+    pub min_size: usize,
+    /// The server resets its compression state after each message
+    pub server_no_context_takeover: bool,
+    /// The client resets its compression state after each message
+    pub client_no_context_takeover: bool,
+    /// Maximum window bits of the server compressor (default 15)
+    pub server_max_window_bits: u8,
+    /// Maximum window bits of the client compressor (default 15)
+    pub client_max_window_bits: u8,
+}
+
+impl Default for DeflateConfig {
+    fn default() -> Self {
+        DeflateConfig {
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            min_size: DEFAULT_MIN_SIZE,
+            server_no_context_takeover: false,
+            client_no_context_takeover: false,
+            server_max_window_bits: MAX_WINDOW_BITS,
+            client_max_window_bits: MAX_WINDOW_BITS,
+        }
+    }
+}
+
+impl DeflateConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_compression_level(mut self, compression_level: u32) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn with_server_no_context_takeover(mut self, no_context_takeover: bool) -> Self {
+        self.server_no_context_takeover = no_context_takeover;
+        self
+    }
+
+    pub fn with_client_no_context_takeover(mut self, no_context_takeover: bool) -> Self {
+        self.client_no_context_takeover = no_context_takeover;
+        self
+    }
+
+    pub fn with_server_max_window_bits(mut self, bits: u8) -> Self {
+        self.server_max_window_bits = bits;
+        self
+    }
+
+    pub fn with_client_max_window_bits(mut self, bits: u8) -> Self {
+        self.client_max_window_bits = bits;
+        self
+    }
+}
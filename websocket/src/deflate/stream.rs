@@ -0,0 +1,852 @@
+//!
+//! Stream adapter compressing the outgoing and decompressing the incoming
+//! WebSocket messages once `permessage-deflate` has been agreed.
+//!
+
+use super::extension::{self, clamp_window_bits, Params};
+use super::DeflateConfig;
+use bytes::{Buf, BytesMut};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use std::io::{self, Cursor};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tungstenite::http::header::SEC_WEBSOCKET_EXTENSIONS;
+use tungstenite::protocol::frame::coding::{Data, OpCode};
+use tungstenite::protocol::frame::FrameHeader;
+use tungstenite::protocol::{Role, WebSocketConfig};
+
+/// Trailer of the sync flush removed from the compressed messages
+const DEFLATE_TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+/// Maximum size of the HTTP header of the WebSocket upgrade response
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+/// Size of the processed frames pending to be written to the inner
+/// stream at which the writes are suspended until the data is written
+const MAX_PENDING_WRITE_SIZE: usize = 128 * 1024;
+
+enum State {
+    /// Awaiting the upgrade response (received by the client
+    /// or sent by the server) containing the agreed extensions
+    Handshake,
+    /// The extension has not been agreed, the data is relayed unchanged
+    Plain,
+    /// The extension has been agreed
+    Deflate(Box<Codec>),
+}
+
+/// Compression state of the connection
+struct Codec {
+    compress: Compress,
+    /// Reset the compressor after each message (no context takeover)
+    compress_reset: bool,
+    min_size: usize,
+    decompress: Decompress,
+    /// Reset the decompressor after each message (no context takeover)
+    decompress_reset: bool,
+    /// The message being sent is compressed
+    deflating: bool,
+    /// The message being received is compressed
+    inflating: bool,
+    /// Decompressed size of the message being received
+    inflated: usize,
+}
+
+impl Codec {
+    fn new(role: Role, config: &DeflateConfig, params: &Params) -> Self {
+        let (window_bits, compress_reset, decompress_reset) = match role {
+            Role::Client => (
+                params.client_max_window_bits,
+                params.client_no_context_takeover || config.client_no_context_takeover,
+                params.server_no_context_takeover,
+            ),
+            Role::Server => (
+                params.server_max_window_bits,
+                params.server_no_context_takeover,
+                params.client_no_context_takeover,
+            ),
+        };
+        let window_bits = clamp_window_bits(window_bits.unwrap_or(super::MAX_WINDOW_BITS));
+        let level = Compression::new(config.compression_level.min(9));
+        Codec {
+            compress: Compress::new_with_window_bits(level, false, window_bits),
+            compress_reset,
+            min_size: config.min_size,
+            decompress: Decompress::new(false),
+            decompress_reset,
+            deflating: false,
+            inflating: false,
+            inflated: 0,
+        }
+    }
+
+    /// Compress the payload of the frame, the final frame of the
+    /// message is completed removing the trailer of the sync flush
+    fn deflate(&mut self, input: &[u8], is_final: bool, output: &mut Vec<u8>) -> io::Result<()> {
+        let mut consumed = 0;
+        loop {
+            output.reserve(input.len() - consumed + 64);
+            let total_in = self.compress.total_in();
+            self.compress
+                .compress_vec(&input[consumed..], output, FlushCompress::Sync)
+                .map_err(invalid_data)?;
+            consumed += (self.compress.total_in() - total_in) as usize;
+            // the flush is complete if the output has not been filled
+            if consumed == input.len() && output.len() < output.capacity() {
+                break;
+            }
+        }
+
+        if is_final {
+            if output.ends_with(&DEFLATE_TAIL) {
+                output.truncate(output.len() - DEFLATE_TAIL.len());
+            } else if output.is_empty() {
+                // nothing has been flushed since the previous message,
+                // the empty message is sent as an empty stored block
+                output.push(0x00);
+            }
+            if self.compress_reset {
+                self.compress.reset();
+            }
+        }
+        Ok(())
+    }
+
+    /// Decompress the payload of the frame, failing if the decompressed
+    /// message exceeds `max_message_size`
+    fn inflate(
+        &mut self,
+        input: &[u8],
+        is_final: bool,
+        max_message_size: Option<usize>,
+        output: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        self.inflate_chunk(input, max_message_size, output)?;
+        if is_final {
+            self.inflate_chunk(&DEFLATE_TAIL, max_message_size, output)?;
+            if self.decompress_reset {
+                self.decompress.reset(false);
+            }
+        }
+        Ok(())
+    }
+
+    fn inflate_chunk(
+        &mut self,
+        input: &[u8],
+        max_message_size: Option<usize>,
+        output: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let mut consumed = 0;
+        loop {
+            output.reserve((input.len() - consumed).max(1024) * 2);
+            let (total_in, total_out) = (self.decompress.total_in(), self.decompress.total_out());
+            let status = self
+                .decompress
+                .decompress_vec(&input[consumed..], output, FlushDecompress::Sync)
+                .map_err(invalid_data)?;
+            let read = (self.decompress.total_in() - total_in) as usize;
+            let written = (self.decompress.total_out() - total_out) as usize;
+            consumed += read;
+            self.inflated += written;
+            if max_message_size.is_some_and(|max| self.inflated > max) {
+                return Err(invalid_data("decompressed message is too large"));
+            }
+            if status == Status::StreamEnd {
+                // the message has been completed with the final block,
+                // the following message starts a new stream
+                self.decompress.reset(false);
+                return Ok(());
+            }
+            if (consumed == input.len() && output.len() < output.capacity())
+                || (read == 0 && written == 0)
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+///
+/// Stream adapter applying `permessage-deflate` to the WebSocket frames
+/// relayed between the WebSocket protocol and the inner (transport or TLS)
+/// stream.  The adapter observes the upgrade response (received by the
+/// client or sent by the server) and compresses the messages only if the
+/// extension has been agreed, otherwise the data is relayed unchanged.
+///
+/// The client must offer the extension in the upgrade request and the
+/// server must accept the offer in the upgrade response; both are handled
+/// by the native [`WebSocket`](crate::client::WebSocket) client and the
+/// [`WebSocketServer`](crate::server::WebSocketServer).
+///
+pub struct DeflateStream<S> {
+    inner: S,
+    role: Role,
+    config: Option<DeflateConfig>,
+    state: State,
+    max_frame_size: Option<usize>,
+    max_message_size: Option<usize>,
+    /// The upgrade request has been received by the server, the following
+    /// data is processed once the upgrade response has been sent
+    request_received: bool,
+    /// Data received from the inner stream pending to be processed
+    read_buffer: BytesMut,
+    /// Processed data pending to be read
+    read_output: BytesMut,
+    /// Data written to the stream pending to be processed (incomplete frames)
+    write_buffer: BytesMut,
+    /// Processed data pending to be written to the inner stream
+    write_output: BytesMut,
+}
+
+impl<S> DeflateStream<S> {
+    /// Create the client-side stream, `config` must match the
+    /// extension offered in the upgrade request (if any)
+    pub fn client(inner: S, config: Option<DeflateConfig>) -> Self {
+        Self::new(inner, Role::Client, config)
+    }
+
+    /// Create the server-side stream, `config` must match the
+    /// configuration used to accept the offer of the client (if any)
+    pub fn server(inner: S, config: Option<DeflateConfig>) -> Self {
+        Self::new(inner, Role::Server, config)
+    }
+
+    fn new(inner: S, role: Role, config: Option<DeflateConfig>) -> Self {
+        let state = if config.is_some() {
+            State::Handshake
+        } else {
+            State::Plain
+        };
+        DeflateStream {
+            inner,
+            role,
+            config,
+            state,
+            max_frame_size: None,
+            max_message_size: None,
+            request_received: false,
+            read_buffer: BytesMut::new(),
+            read_output: BytesMut::new(),
+            write_buffer: BytesMut::new(),
+            write_output: BytesMut::new(),
+        }
+    }
+
+    /// Apply the frame and message size limits of the WebSocket configuration
+    /// to the received compressed frames and the decompressed messages
+    pub fn with_limits(mut self, config: Option<&WebSocketConfig>) -> Self {
+        let config = config.cloned().unwrap_or_default();
+        self.max_frame_size = config.max_frame_size;
+        self.max_message_size = config.max_message_size;
+        self
+    }
+
+    /// `true` if `permessage-deflate` has been agreed
+    pub fn is_deflate(&self) -> bool {
+        matches!(self.state, State::Deflate(_))
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Switch to the agreed mode once the header of the upgrade
+    /// response (`head`) has been received or sent
+    fn negotiate(&mut self, head: &[u8]) -> io::Result<()> {
+        self.state = match (self.config.as_ref(), response_extensions(head)) {
+            (Some(config), Some(extensions)) => {
+                match extension::agreed(self.role, config, &extensions).map_err(invalid_data)? {
+                    Some(params) => {
+                        State::Deflate(Box::new(Codec::new(self.role, config, &params)))
+                    }
+                    None => State::Plain,
+                }
+            }
+            _ => State::Plain,
+        };
+        Ok(())
+    }
+
+    /// Process the data received from the inner stream
+    fn process_read(&mut self) -> io::Result<()> {
+        if matches!(self.state, State::Handshake) {
+            if self.request_received {
+                return Ok(());
+            }
+            let Some(end) = header_end(&self.read_buffer) else {
+                if self.read_buffer.len() > MAX_HEADER_SIZE {
+                    return Err(invalid_data("WebSocket upgrade header is too large"));
+                }
+                return Ok(());
+            };
+            let head = self.read_buffer.split_to(end);
+            match self.role {
+                Role::Client => self.negotiate(&head)?,
+                // the frames received along with the request are
+                // retained until the upgrade response has been sent
+                Role::Server => self.request_received = true,
+            }
+            self.read_output.extend_from_slice(&head);
+        }
+
+        match &mut self.state {
+            State::Deflate(codec) => {
+                while let Some((header, offset, length)) =
+                    parse_frame(&self.read_buffer, self.max_frame_size)?
+                {
+                    let mut frame = self.read_buffer.split_to(offset + length);
+                    match header.opcode {
+                        OpCode::Data(Data::Text | Data::Binary) => {
+                            codec.inflating = header.rsv1;
+                            codec.inflated = 0;
+                        }
+                        // compressed continuation frames are rejected by the protocol
+                        OpCode::Data(Data::Continue) if !header.rsv1 => {}
+                        _ => {
+                            self.read_output.extend_from_slice(&frame);
+                            continue;
+                        }
+                    }
+                    if !codec.inflating {
+                        self.read_output.extend_from_slice(&frame);
+                        continue;
+                    }
+
+                    let payload = &mut frame[offset..];
+                    if let Some(mask) = header.mask {
+                        apply_mask(payload, mask);
+                    }
+                    let mut data = Vec::new();
+                    codec.inflate(payload, header.is_final, self.max_message_size, &mut data)?;
+                    write_frames(&mut self.read_output, &header, &data, self.max_frame_size)?;
+                }
+            }
+            State::Plain => {
+                let data = self.read_buffer.split();
+                self.read_output.unsplit(data);
+            }
+            State::Handshake => {}
+        }
+        Ok(())
+    }
+
+    /// Process the data written to the stream
+    fn process_write(&mut self) -> io::Result<()> {
+        if matches!(self.state, State::Handshake) {
+            let Some(end) = header_end(&self.write_buffer) else {
+                return Ok(());
+            };
+            let head = self.write_buffer.split_to(end);
+            self.negotiate(&head)?;
+            self.write_output.extend_from_slice(&head);
+        }
+
+        match &mut self.state {
+            State::Deflate(codec) => {
+                while let Some((header, offset, length)) = parse_frame(&self.write_buffer, None)? {
+                    let mut frame = self.write_buffer.split_to(offset + length);
+                    match header.opcode {
+                        OpCode::Data(Data::Text | Data::Binary) => {
+                            codec.deflating = !header.is_final || length >= codec.min_size;
+                        }
+                        OpCode::Data(Data::Continue) => {}
+                        _ => {
+                            self.write_output.extend_from_slice(&frame);
+                            continue;
+                        }
+                    }
+                    if !codec.deflating {
+                        self.write_output.extend_from_slice(&frame);
+                        continue;
+                    }
+
+                    let payload = &mut frame[offset..];
+                    if let Some(mask) = header.mask {
+                        apply_mask(payload, mask);
+                    }
+                    let mut data = Vec::with_capacity(payload.len() / 2 + 64);
+                    codec.deflate(payload, header.is_final, &mut data)?;
+                    if let Some(mask) = header.mask {
+                        apply_mask(&mut data, mask);
+                    }
+                    // the first frame of the message is marked as compressed
+                    let header = FrameHeader {
+                        rsv1: header.opcode != OpCode::Data(Data::Continue),
+                        ..header
+                    };
+                    write_header(&mut self.write_output, &header, data.len())?;
+                    self.write_output.extend_from_slice(&data);
+                }
+            }
+            State::Plain => {
+                let data = self.write_buffer.split();
+                self.write_output.unsplit(data);
+            }
+            State::Handshake => {}
+        }
+        Ok(())
+    }
+}
+
+impl<S> DeflateStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    /// Write the processed data to the inner stream
+    fn poll_write_output(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_output.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_output))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.write_output.advance(written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> AsyncRead for DeflateStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if !this.read_output.is_empty() {
+                let len = this.read_output.len().min(buf.remaining());
+                buf.put_slice(&this.read_output[..len]);
+                this.read_output.advance(len);
+                return Poll::Ready(Ok(()));
+            }
+
+            if matches!(this.state, State::Plain) && this.read_buffer.is_empty() {
+                return Pin::new(&mut this.inner).poll_read(cx, buf);
+            }
+
+            this.process_read()?;
+            if !this.read_output.is_empty() {
+                continue;
+            }
+
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let mut chunk = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+            if chunk.filled().is_empty() {
+                // the incomplete frame (if any) is discarded
+                // at the end of the stream
+                return Poll::Ready(Ok(()));
+            }
+            this.read_buffer.extend_from_slice(chunk.filled());
+        }
+    }
+}
+
+impl<S> AsyncWrite for DeflateStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.write_output.len() >= MAX_PENDING_WRITE_SIZE {
+            ready!(this.poll_write_output(cx))?;
+        }
+
+        let relay = this.write_output.is_empty()
+            && this.write_buffer.is_empty()
+            && match this.state {
+                // the request sent by the client is relayed unchanged
+                State::Handshake => this.role == Role::Client,
+                State::Plain => true,
+                State::Deflate(_) => false,
+            };
+        if relay {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+
+        this.write_buffer.extend_from_slice(buf);
+        this.process_write()?;
+        // the data has been accepted, the output is written
+        // by the following writes or flush
+        if let Poll::Ready(Err(err)) = this.poll_write_output(cx) {
+            return Poll::Ready(Err(err));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_output(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_output(cx))?;
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Length of the HTTP header terminated by an empty line
+fn header_end(data: &[u8]) -> Option<usize> {
+    data.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|position| position + 4)
+}
+
+/// `Sec-WebSocket-Extensions` header values of the HTTP response (empty if
+/// not present), `None` if the response is not `101 Switching Protocols`
+fn response_extensions(head: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(head);
+    let mut lines = head.split("\r\n");
+    let status = lines.next()?.split_whitespace().nth(1)?;
+    if status != "101" {
+        return None;
+    }
+    let extensions = lines
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| {
+            name.trim()
+                .eq_ignore_ascii_case(SEC_WEBSOCKET_EXTENSIONS.as_str())
+        })
+        .map(|(_, value)| value.trim())
+        .collect::<Vec<_>>();
+    Some(extensions.join(", "))
+}
+
+/// Parse the frame at the start of the buffer, returns the header, the offset
+/// and the length of the payload (`None` if the frame is incomplete)
+fn parse_frame(
+    data: &[u8],
+    max_frame_size: Option<usize>,
+) -> io::Result<Option<(FrameHeader, usize, usize)>> {
+    let mut cursor = Cursor::new(data);
+    let Some((header, length)) = FrameHeader::parse(&mut cursor).map_err(invalid_data)? else {
+        return Ok(None);
+    };
+    let length = usize::try_from(length).map_err(invalid_data)?;
+    if max_frame_size.is_some_and(|max| length > max) {
+        return Err(invalid_data("WebSocket frame is too large"));
+    }
+    let offset = cursor.position() as usize;
+    if data.len() - offset < length {
+        return Ok(None);
+    }
+    Ok(Some((header, offset, length)))
+}
+
+fn write_header(output: &mut BytesMut, header: &FrameHeader, length: usize) -> io::Result<()> {
+    let mut head = Vec::with_capacity(14);
+    header
+        .format(length as u64, &mut head)
+        .map_err(invalid_data)?;
+    output.extend_from_slice(&head);
+    Ok(())
+}
+
+/// Write the decompressed payload as uncompressed frames, splitting the
+/// payload into the frames of at most `max_frame_size` bytes.  The masked
+/// frames are masked using the zero key (the payload remains unchanged).
+fn write_frames(
+    output: &mut BytesMut,
+    header: &FrameHeader,
+    mut data: &[u8],
+    max_frame_size: Option<usize>,
+) -> io::Result<()> {
+    let max_frame_size = max_frame_size.unwrap_or(usize::MAX).max(1);
+    let mut opcode = header.opcode;
+    loop {
+        let (payload, rest) = data.split_at(data.len().min(max_frame_size));
+        let frame = FrameHeader {
+            is_final: header.is_final && rest.is_empty(),
+            rsv1: false,
+            opcode,
+            mask: header.mask.map(|_| [0; 4]),
+            ..header.clone()
+        };
+        write_header(output, &frame, payload.len())?;
+        output.extend_from_slice(payload);
+        if rest.is_empty() {
+            return Ok(());
+        }
+        data = rest;
+        opcode = OpCode::Data(Data::Continue);
+    }
+}
+
+fn apply_mask(data: &mut [u8], mask: [u8; 4]) {
+    for (index, byte) in data.iter_mut().enumerate() {
+        *byte ^= mask[index & 3];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream};
+    use tokio_tungstenite::{accept_hdr_async_with_config, client_async_with_config};
+    use tokio_tungstenite::{tungstenite::client::IntoClientRequest, WebSocketStream};
+    use tungstenite::handshake::derive_accept_key;
+    use tungstenite::protocol::frame::Frame;
+    use tungstenite::Message;
+
+    /// Compressed `Hello` text frame (RFC 7692 section 7.2.3.1)
+    const HELLO_FRAME: [u8; 9] = [0xc1, 0x07, 0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00];
+
+    type TestStream = WebSocketStream<DeflateStream<DuplexStream>>;
+
+    async fn connect(
+        client: Option<DeflateConfig>,
+        server: Option<DeflateConfig>,
+    ) -> (TestStream, TestStream) {
+        let (client_io, server_io) = duplex(1024 * 1024);
+        let server = tokio::spawn(async move {
+            let stream = DeflateStream::server(server_io, server.clone());
+            accept_hdr_async_with_config(stream, extension::AcceptOffer(server.as_ref()), None)
+                .await
+                .unwrap()
+        });
+
+        let mut request = "ws://localhost/".into_client_request().unwrap();
+        if let Some(config) = client.as_ref() {
+            extension::offer(config, &mut request);
+        }
+        let stream = DeflateStream::client(client_io, client);
+        let (client, _) = client_async_with_config(request, stream, None)
+            .await
+            .unwrap();
+        (client, server.await.unwrap())
+    }
+
+    #[test]
+    fn test_deflate_codec() {
+        let config = DeflateConfig::default();
+        let mut client = Codec::new(Role::Client, &config, &Params::default());
+        let mut server = Codec::new(Role::Server, &config, &Params::default());
+
+        let mut first = Vec::new();
+        client.deflate(b"Hello", true, &mut first).unwrap();
+        assert_eq!(first, HELLO_FRAME[2..]);
+        // the second message refers to the first one (context takeover)
+        let mut second = Vec::new();
+        client.deflate(b"Hello", true, &mut second).unwrap();
+        assert!(second.len() < first.len());
+
+        for compressed in [first, second] {
+            let mut data = Vec::new();
+            server.inflate(&compressed, true, None, &mut data).unwrap();
+            assert_eq!(data, b"Hello");
+            server.inflated = 0;
+        }
+
+        // the decompressed size is limited by the message size
+        let mut compressed = Vec::new();
+        client.deflate(&[0; 4096], true, &mut compressed).unwrap();
+        let mut data = Vec::new();
+        assert!(server
+            .inflate(&compressed, true, Some(1024), &mut data)
+            .is_err());
+    }
+
+    #[test]
+    fn test_deflate_codec_no_context_takeover() {
+        let config = DeflateConfig::default().with_client_no_context_takeover(true);
+        let params = Params {
+            server_no_context_takeover: true,
+            server_max_window_bits: Some(9),
+            ..Default::default()
+        };
+        let mut client = Codec::new(Role::Client, &config, &params);
+        let mut server = Codec::new(Role::Server, &config, &params);
+
+        let mut first = Vec::new();
+        client.deflate(b"Hello", true, &mut first).unwrap();
+        let mut second = Vec::new();
+        client.deflate(b"Hello", true, &mut second).unwrap();
+        assert_eq!(first, second);
+
+        let mut compressed = Vec::new();
+        server.deflate(b"Hello", true, &mut compressed).unwrap();
+        let mut data = Vec::new();
+        client.inflate(&compressed, true, None, &mut data).unwrap();
+        assert_eq!(data, b"Hello");
+    }
+
+    #[tokio::test]
+    async fn test_deflate_stream() {
+        let configs = [
+            DeflateConfig::default().with_min_size(0),
+            DeflateConfig::default()
+                .with_server_no_context_takeover(true)
+                .with_client_no_context_takeover(true)
+                .with_server_max_window_bits(10)
+                .with_client_max_window_bits(9),
+        ];
+        let text = "workflow-rs ".repeat(1000);
+        let binary = (0..100_000u32).map(|n| (n % 251) as u8).collect::<Vec<_>>();
+
+        for config in configs {
+            let (mut client, mut server) = connect(Some(config.clone()), Some(config)).await;
+            assert!(client.get_ref().is_deflate());
+            assert!(server.get_ref().is_deflate());
+
+            for _ in 0..2 {
+                client.send(Message::text(text.clone())).await.unwrap();
+                let received = server.next().await.unwrap().unwrap();
+                assert_eq!(received, Message::text(text.clone()));
+
+                server.send(Message::binary(binary.clone())).await.unwrap();
+                let received = client.next().await.unwrap().unwrap();
+                assert_eq!(received, Message::binary(binary.clone()));
+
+                client.send(Message::binary(Vec::new())).await.unwrap();
+                let received = server.next().await.unwrap().unwrap();
+                assert_eq!(received, Message::binary(Vec::new()));
+
+                // the control frames are not compressed
+                client.send(Message::Ping("ping".into())).await.unwrap();
+                let received = server.next().await.unwrap().unwrap();
+                assert_eq!(received, Message::Ping("ping".into()));
+                server.flush().await.unwrap();
+                let received = client.next().await.unwrap().unwrap();
+                assert_eq!(received, Message::Pong("ping".into()));
+            }
+
+            // fragmented message
+            let first = Frame::message(text.clone().into_bytes(), OpCode::Data(Data::Text), false);
+            let last = Frame::message(
+                text.clone().into_bytes(),
+                OpCode::Data(Data::Continue),
+                true,
+            );
+            client.send(Message::Frame(first)).await.unwrap();
+            client.send(Message::Frame(last)).await.unwrap();
+            let received = server.next().await.unwrap().unwrap();
+            assert_eq!(received, Message::text(text.repeat(2)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deflate_declined() {
+        let config = DeflateConfig::default().with_min_size(0);
+        for (client_config, server_config) in [(Some(config.clone()), None), (None, Some(config))] {
+            let (mut client, mut server) = connect(client_config, server_config).await;
+            assert!(!client.get_ref().is_deflate());
+            assert!(!server.get_ref().is_deflate());
+
+            client.send(Message::text("Hello")).await.unwrap();
+            let received = server.next().await.unwrap().unwrap();
+            assert_eq!(received, Message::text("Hello"));
+            server.send(Message::text("Hello")).await.unwrap();
+            let received = client.next().await.unwrap().unwrap();
+            assert_eq!(received, Message::text("Hello"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deflate_frames() {
+        let (client_io, mut server_io) = duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let mut request = Vec::new();
+            while header_end(&request).is_none() {
+                let mut chunk = [0; 1024];
+                let len = server_io.read(&mut chunk).await.unwrap();
+                request.extend_from_slice(&chunk[..len]);
+            }
+            let request = String::from_utf8(request).unwrap();
+            let key = request
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("sec-websocket-key"))
+                .map(|(_, key)| key.trim())
+                .unwrap();
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\n\
+                Connection: Upgrade\r\n\
+                Upgrade: websocket\r\n\
+                Sec-WebSocket-Accept: {}\r\n\
+                Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n",
+                derive_accept_key(key.as_bytes())
+            );
+            // the compressed frame follows the response
+            let mut data = response.into_bytes();
+            data.extend_from_slice(&HELLO_FRAME);
+            server_io.write_all(&data).await.unwrap();
+
+            let mut frame = [0; 13];
+            server_io.read_exact(&mut frame).await.unwrap();
+            frame
+        });
+
+        let config = DeflateConfig::default().with_min_size(0);
+        let mut request = "ws://localhost/".into_client_request().unwrap();
+        extension::offer(&config, &mut request);
+        let stream = DeflateStream::client(client_io, Some(config));
+        let (mut client, _) = client_async_with_config(request, stream, None)
+            .await
+            .unwrap();
+        let received = client.next().await.unwrap().unwrap();
+        assert_eq!(received, Message::text("Hello"));
+
+        client.send(Message::text("Hello")).await.unwrap();
+        let frame = server.await.unwrap();
+        // compressed masked text frame
+        assert_eq!(frame[..2], [0xc1, 0x87]);
+        let mut payload = frame[6..].to_vec();
+        apply_mask(&mut payload, frame[2..6].try_into().unwrap());
+        assert_eq!(payload, HELLO_FRAME[2..]);
+    }
+
+    #[tokio::test]
+    async fn test_deflate_request_frames() {
+        let (mut client_io, server_io) = duplex(64 * 1024);
+        let config = DeflateConfig::default();
+        let server = tokio::spawn(async move {
+            let stream = DeflateStream::server(server_io, Some(config.clone()));
+            let accept_offer = extension::AcceptOffer(Some(&config));
+            let mut server = accept_hdr_async_with_config(stream, accept_offer, None)
+                .await
+                .unwrap();
+            server.next().await.unwrap().unwrap()
+        });
+
+        // the compressed frame is sent along with the request
+        let mut data = b"GET / HTTP/1.1\r\n\
+            Host: localhost\r\n\
+            Connection: Upgrade\r\n\
+            Upgrade: websocket\r\n\
+            Sec-WebSocket-Version: 13\r\n\
+            Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+            Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n"
+            .to_vec();
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+        let mut payload = HELLO_FRAME[2..].to_vec();
+        apply_mask(&mut payload, mask);
+        data.extend_from_slice(&[0xc1, 0x87]);
+        data.extend_from_slice(&mask);
+        data.extend_from_slice(&payload);
+        client_io.write_all(&data).await.unwrap();
+
+        assert_eq!(server.await.unwrap(), Message::text("Hello"));
+    }
+}
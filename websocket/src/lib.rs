@@ -23,5 +23,6 @@
 //!
 
 pub mod client;
+pub mod deflate;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod server;
@@ -68,6 +68,11 @@ pub enum Error {
     #[error("Error waiting for listener shutdown: {0}")]
     Join(String),
 
+    /// Some of the connections have not terminated within
+    /// the shutdown timeout (carries the number of connections)
+    #[error("Shutdown timeout: {0} connections are still active")]
+    ShutdownTimeout(usize),
+
     #[error(transparent)]
     IoError(#[from] std::io::Error),
 }
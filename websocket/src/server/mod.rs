@@ -1,6 +1,8 @@
 //!
 //! async WebSocket server functionality (requires tokio executor)
 //!
+use crate::deflate::AcceptOffer;
+use ahash::AHashMap;
 use async_trait::async_trait;
 use cfg_if::cfg_if;
 use downcast_rs::*;
@@ -11,30 +13,33 @@ use futures_util::{
 };
 use std::net::SocketAddr;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc::{
     UnboundedReceiver as TokioUnboundedReceiver, UnboundedSender as TokioUnboundedSender,
 };
-use tokio_tungstenite::{accept_async_with_config, WebSocketStream};
+use tokio::sync::Notify;
+use tokio_tungstenite::{accept_hdr_async_with_config, WebSocketStream};
+use tungstenite::protocol::frame::{coding::CloseCode, CloseFrame};
 use tungstenite::Error as WebSocketError;
 use workflow_core::channel::DuplexChannel;
 use workflow_log::*;
 pub mod error;
 pub mod result;
 
+pub use crate::deflate::{DeflateConfig, DeflateStream};
 pub use error::Error;
 pub use result::Result;
 pub use tungstenite::protocol::WebSocketConfig;
 pub use tungstenite::Message;
 /// WebSocket stream sender for dispatching [`tungstenite::Message`].
 /// This stream object must have a mutable reference and can not be cloned.
-pub type WebSocketSender = SplitSink<WebSocketStream<TcpStream>, Message>;
+pub type WebSocketSender = SplitSink<WebSocketStream<DeflateStream<TcpStream>>, Message>;
 /// WebSocket stream receiver for receiving [`tungstenite::Message`].
 /// This stream object must have a mutable reference and can not be cloned.
-pub type WebSocketReceiver = SplitStream<WebSocketStream<TcpStream>>;
+pub type WebSocketReceiver = SplitStream<WebSocketStream<DeflateStream<TcpStream>>>;
 /// WebSocketSink [`tokio::sync::mpsc::UnboundedSender`] for dispatching
 /// messages from within the [`WebSocketHandler::message`]. This is an
 /// `MPSC` channel that can be cloned and retained externally for the
@@ -135,6 +140,10 @@ where
     pub counters: Arc<WebSocketCounters>,
     pub handler: Arc<T>,
     pub stop: DuplexChannel,
+    connections: Mutex<AHashMap<u64, WebSocketSink>>,
+    connections_closed: Notify,
+    next_connection_id: AtomicU64,
+    deflate: Option<DeflateConfig>,
 }
 
 impl<T> WebSocketServer<T>
@@ -142,20 +151,43 @@ where
     T: WebSocketHandler + Send + Sync + 'static,
 {
     pub fn new(handler: Arc<T>, counters: Option<Arc<WebSocketCounters>>) -> Arc<Self> {
+        Self::new_with_deflate(handler, counters, None)
+    }
+
+    /// Create the server accepting the `permessage-deflate` compression
+    /// offered by the clients using the `deflate` configuration (the
+    /// compression is disabled if `None`)
+    pub fn new_with_deflate(
+        handler: Arc<T>,
+        counters: Option<Arc<WebSocketCounters>>,
+        deflate: Option<DeflateConfig>,
+    ) -> Arc<Self> {
         Arc::new(WebSocketServer {
             counters: counters.unwrap_or_default(),
             handler,
             stop: DuplexChannel::oneshot(),
+            connections: Mutex::new(AHashMap::new()),
+            connections_closed: Notify::new(),
+            next_connection_id: AtomicU64::new(0),
+            deflate,
         })
     }
 
+    /// Number of active (registered) connections
+    pub fn connection_count(&self) -> usize {
+        self.connections.lock().unwrap().len()
+    }
+
     async fn handle_connection(
         self: &Arc<Self>,
         peer: SocketAddr,
         stream: TcpStream,
         config: Option<WebSocketConfig>,
     ) -> Result<()> {
-        let ws_stream = accept_async_with_config(stream, config).await?;
+        let stream =
+            DeflateStream::server(stream, self.deflate.clone()).with_limits(config.as_ref());
+        let accept_offer = AcceptOffer(self.deflate.as_ref());
+        let ws_stream = accept_hdr_async_with_config(stream, accept_offer, config).await?;
         self.handler.connect(&peer).await?;
         // log_trace!("WebSocket connected: {}", peer);
 
@@ -176,9 +208,21 @@ where
             }
         };
 
+        let id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(id, sink_sender.clone());
         let result = self
             .connection_task(&ctx, ws_sender, ws_receiver, sink_sender, sink_receiver)
             .await;
+        {
+            let mut connections = self.connections.lock().unwrap();
+            connections.remove(&id);
+            if connections.is_empty() {
+                self.connections_closed.notify_waiters();
+            }
+        }
         self.handler.disconnect(ctx, result).await;
         // log_trace!("WebSocket disconnected: {}", peer);
 
@@ -369,6 +413,49 @@ where
         self.stop()?;
         self.join().await
     }
+
+    /// Gracefully close all active connections by sending the Close
+    /// frame (`1001 Going Away`), waiting up to `timeout` for the
+    /// connections to terminate.  Returns [`Error::ShutdownTimeout`]
+    /// if some of the connections have not terminated in time.
+    pub async fn close_connections(&self, timeout: Duration) -> Result<()> {
+        let sinks = self
+            .connections
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for sink in sinks {
+            let frame = CloseFrame {
+                code: CloseCode::Away,
+                reason: "server shutdown".into(),
+            };
+            sink.send(Message::Close(Some(frame))).ok();
+        }
+
+        let closed = async {
+            loop {
+                // register before checking the count to not miss the notification
+                let notified = self.connections_closed.notified();
+                if self.connection_count() == 0 {
+                    break;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, closed)
+            .await
+            .map_err(|_| Error::ShutdownTimeout(self.connection_count()))
+    }
+
+    /// Stop accepting connections (see [`WebSocketServer::stop_and_join()`])
+    /// and gracefully close all active connections (see
+    /// [`WebSocketServer::close_connections()`]).
+    pub async fn shutdown(&self, timeout: Duration) -> Result<()> {
+        self.stop_and_join().await?;
+        self.close_connections(timeout).await
+    }
 }
 
 /// Base WebSocketServer trait allows the [`WebSocketServer<T>`] struct
@@ -413,6 +500,14 @@ pub trait WebSocketServerTrait: DowncastSync {
     fn stop(&self) -> Result<()>;
     async fn join(&self) -> Result<()>;
     async fn stop_and_join(&self) -> Result<()>;
+    /// Stop the server and gracefully close all active connections,
+    /// waiting up to `timeout` for the connections to terminate.
+    /// The default implementation only stops the server (the trait
+    /// does not track the connections) and ignores the `timeout`;
+    /// servers tracking connections should override it.
+    async fn shutdown(&self, _timeout: Duration) -> Result<()> {
+        self.stop_and_join().await
+    }
 }
 impl_downcast!(sync WebSocketServerTrait);
 
@@ -436,6 +531,10 @@ where
     async fn stop_and_join(&self) -> Result<()> {
         self.stop_and_join().await
     }
+
+    async fn shutdown(&self, timeout: Duration) -> Result<()> {
+        self.shutdown(timeout).await
+    }
 }
 
 pub mod handshake {